@@ -0,0 +1,97 @@
+use std::hint::black_box;
+use std::marker::PhantomData;
+
+use criterion::{criterion_group, criterion_main, Criterion};
+use feanor_serde::identifier::{bucket_count, IdentifierTable};
+use feanor_serde::impl_deserialize_seed_for_dependent_struct;
+use serde::de::DeserializeSeed;
+use serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize)]
+struct Wide {
+    alpha: i64, bravo: i64, charlie: i64, delta: i64, echo: i64,
+    foxtrot: i64, golf: i64, hotel: i64, india: i64, juliett: i64,
+    kilo: i64, lima: i64, mike: i64, november: i64, oscar: i64,
+    papa: i64, quebec: i64, romeo: i64, sierra: i64, tango: i64
+}
+
+struct DeserializeSeedWide;
+
+impl_deserialize_seed_for_dependent_struct!{
+    pub struct DeserializedWide<'de> using DeserializeSeedWide {
+        alpha: i64: |_| PhantomData::<i64>,
+        bravo: i64: |_| PhantomData::<i64>,
+        charlie: i64: |_| PhantomData::<i64>,
+        delta: i64: |_| PhantomData::<i64>,
+        echo: i64: |_| PhantomData::<i64>,
+        foxtrot: i64: |_| PhantomData::<i64>,
+        golf: i64: |_| PhantomData::<i64>,
+        hotel: i64: |_| PhantomData::<i64>,
+        india: i64: |_| PhantomData::<i64>,
+        juliett: i64: |_| PhantomData::<i64>,
+        kilo: i64: |_| PhantomData::<i64>,
+        lima: i64: |_| PhantomData::<i64>,
+        mike: i64: |_| PhantomData::<i64>,
+        november: i64: |_| PhantomData::<i64>,
+        oscar: i64: |_| PhantomData::<i64>,
+        papa: i64: |_| PhantomData::<i64>,
+        quebec: i64: |_| PhantomData::<i64>,
+        romeo: i64: |_| PhantomData::<i64>,
+        sierra: i64: |_| PhantomData::<i64>,
+        tango: i64: |_| PhantomData::<i64>
+    }
+}
+
+fn wide_json() -> String {
+    let value = Wide {
+        alpha: 1, bravo: 2, charlie: 3, delta: 4, echo: 5,
+        foxtrot: 6, golf: 7, hotel: 8, india: 9, juliett: 10,
+        kilo: 11, lima: 12, mike: 13, november: 14, oscar: 15,
+        papa: 16, quebec: 17, romeo: 18, sierra: 19, tango: 20
+    };
+    serde_json::to_string(&value).unwrap()
+}
+
+fn bench_wide_struct(c: &mut Criterion) {
+    let json = wide_json();
+    let mut group = c.benchmark_group("wide_struct_json");
+    group.bench_function("derive", |b| b.iter(|| {
+        let result: Wide = serde_json::from_str(black_box(&json)).unwrap();
+        black_box(result.tango)
+    }));
+    group.bench_function("macro", |b| b.iter(|| {
+        let result = DeserializeSeedWide.deserialize(&mut serde_json::Deserializer::from_str(black_box(&json))).unwrap();
+        black_box(result.tango)
+    }));
+    group.finish();
+}
+
+const NAMES: &[&str] = &[
+    "alpha", "bravo", "charlie", "delta", "echo", "foxtrot", "golf", "hotel", "india", "juliett",
+    "kilo", "lima", "mike", "november", "oscar", "papa", "quebec", "romeo", "sierra", "tango"
+];
+
+fn match_str(value: &str) -> Option<usize> {
+    match value {
+        "alpha" => Some(0), "bravo" => Some(1), "charlie" => Some(2), "delta" => Some(3), "echo" => Some(4),
+        "foxtrot" => Some(5), "golf" => Some(6), "hotel" => Some(7), "india" => Some(8), "juliett" => Some(9),
+        "kilo" => Some(10), "lima" => Some(11), "mike" => Some(12), "november" => Some(13), "oscar" => Some(14),
+        "papa" => Some(15), "quebec" => Some(16), "romeo" => Some(17), "sierra" => Some(18), "tango" => Some(19),
+        _ => None
+    }
+}
+
+fn bench_identifier_lookup(c: &mut Criterion) {
+    static TABLE: IdentifierTable<20, { bucket_count(NAMES) }> = IdentifierTable::new(NAMES);
+    let mut group = c.benchmark_group("identifier_lookup");
+    group.bench_function("match", |b| b.iter(|| {
+        NAMES.iter().map(|name| match_str(black_box(name)).unwrap()).sum::<usize>()
+    }));
+    group.bench_function("table", |b| b.iter(|| {
+        NAMES.iter().map(|name| TABLE.find(black_box(name.as_bytes())).unwrap()).sum::<usize>()
+    }));
+    group.finish();
+}
+
+criterion_group!(benches, bench_wide_struct, bench_identifier_lookup);
+criterion_main!(benches);
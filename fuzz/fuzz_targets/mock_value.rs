@@ -0,0 +1,32 @@
+#![no_main]
+
+use std::marker::PhantomData;
+
+use feanor_serde::mock::{MockDeserializer, MockValue};
+use feanor_serde::{impl_deserialize_seed_for_dependent_enum, impl_deserialize_seed_for_dependent_struct};
+use libfuzzer_sys::fuzz_target;
+use serde::de::DeserializeSeed;
+
+struct DeserializeSeedRecord;
+
+impl_deserialize_seed_for_dependent_struct!{
+    pub struct Record<'de> using DeserializeSeedRecord {
+        id: u64: |_| PhantomData::<u64>,
+        name: Option<String>: |_| PhantomData::<Option<String>>,
+        values: Vec<i32>: |_| PhantomData::<Vec<i32>>
+    }
+}
+
+struct DeserializeSeedShape;
+
+impl_deserialize_seed_for_dependent_enum!{
+    pub enum Shape<'de> using DeserializeSeedShape {
+        Circle(f64): |_| PhantomData::<f64>,
+        Rect((u32, u32)): |_| PhantomData::<(u32, u32)>
+    }
+}
+
+fuzz_target!(|value: MockValue| {
+    let _ = DeserializeSeedRecord.deserialize(MockDeserializer::new(value.clone()));
+    let _ = DeserializeSeedShape.deserialize(MockDeserializer::new(value));
+});
@@ -0,0 +1,30 @@
+#![no_main]
+
+use std::collections::BTreeMap;
+use std::iter::repeat;
+use std::marker::PhantomData;
+
+use feanor_serde::map::{DeserializeSeedMap, SerializableMap};
+use feanor_serde::seq::{DeserializeSeedSeq, SerializableSeq};
+use libfuzzer_sys::fuzz_target;
+use serde::de::DeserializeSeed;
+
+fuzz_target!(|data: (Vec<i64>, BTreeMap<String, Option<u32>>)| {
+    let (elements, entries) = data;
+
+    let seq = SerializableSeq::new_with_len(elements.iter(), elements.len());
+    let seed = DeserializeSeedSeq::new(repeat(PhantomData::<i64>), Vec::new(), |mut current, next| { current.push(next); current });
+    let serialized = postcard::to_allocvec(&seq).unwrap();
+    assert_eq!(elements, seed.deserialize(&mut postcard::Deserializer::from_bytes(&serialized)).unwrap());
+    let seed = DeserializeSeedSeq::new(repeat(PhantomData::<i64>), Vec::new(), |mut current, next| { current.push(next); current });
+    let serialized = serde_json::to_string(&seq).unwrap();
+    assert_eq!(elements, seed.deserialize(&mut serde_json::Deserializer::from_str(&serialized)).unwrap());
+
+    let map = SerializableMap::new_with_len(entries.iter(), entries.len());
+    let seed = DeserializeSeedMap::new(repeat((PhantomData::<String>, PhantomData::<Option<u32>>)), BTreeMap::new(), |mut current, key, value| { current.insert(key, value); current });
+    let serialized = postcard::to_allocvec(&map).unwrap();
+    assert_eq!(entries, seed.deserialize(&mut postcard::Deserializer::from_bytes(&serialized)).unwrap());
+    let seed = DeserializeSeedMap::new(repeat((PhantomData::<String>, PhantomData::<Option<u32>>)), BTreeMap::new(), |mut current, key, value| { current.insert(key, value); current });
+    let serialized = serde_json::to_string(&map).unwrap();
+    assert_eq!(entries, seed.deserialize(&mut serde_json::Deserializer::from_str(&serialized)).unwrap());
+});
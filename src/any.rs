@@ -0,0 +1,201 @@
+use std::marker::PhantomData;
+
+use serde::de::{DeserializeSeed, Visitor};
+use serde::Deserializer;
+
+///
+/// A [`Visitor`] that is constructed from a context, e.g. the parameters required to
+/// deserialize nested values using [`DeserializeSeed`]s.
+///
+/// Every [`Visitor`] is a [`SeededVisitor`] whose context is the visitor itself, so plain
+/// visitors can be used wherever a [`SeededVisitor`] is expected.
+///
+pub trait SeededVisitor<'de, C>: Visitor<'de> {
+
+    fn new(context: C) -> Self;
+}
+
+impl<'de, V> SeededVisitor<'de, V> for V
+    where V: Visitor<'de>
+{
+    fn new(context: V) -> Self {
+        context
+    }
+}
+
+///
+/// A [`DeserializeSeed`] that deserializes a value of a self-describing format using
+/// [`Deserializer::deserialize_any()`], and the [`SeededVisitor`] constructed from the given
+/// context. This allows handling values whose type is only known at runtime, e.g. either a
+/// number or a sequence.
+///
+/// # Example
+/// ```
+/// # use feanor_serde::any::*;
+/// # use feanor_serde::seq::*;
+/// # use feanor_serde::default_seed::seed_of;
+/// # use serde::de::{DeserializeSeed, Visitor, SeqAccess};
+/// # use serde::de::value::SeqAccessDeserializer;
+/// #[derive(Debug, PartialEq)]
+/// enum Residues {
+///     Single(i64),
+///     Many(Vec<i64>)
+/// }
+/// struct ResiduesVisitor {
+///     modulus: i64
+/// }
+/// impl<'de> Visitor<'de> for ResiduesVisitor {
+///     type Value = Residues;
+///
+///     fn expecting(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+///         write!(f, "an integer or a sequence of integers")
+///     }
+///
+///     fn visit_i64<E>(self, value: i64) -> Result<Residues, E> {
+///         Ok(Residues::Single(value.rem_euclid(self.modulus)))
+///     }
+///
+///     fn visit_u64<E>(self, value: u64) -> Result<Residues, E> {
+///         Ok(Residues::Single((value % self.modulus as u64) as i64))
+///     }
+///
+///     fn visit_seq<A>(self, seq: A) -> Result<Residues, A::Error>
+///         where A: SeqAccess<'de>
+///     {
+///         let values = SeqSeedBuilder::new(seed_of::<i64>).collect_vec().deserialize(SeqAccessDeserializer::new(seq))?;
+///         return Ok(Residues::Many(values.into_iter().map(|x| x.rem_euclid(self.modulus)).collect()));
+///     }
+/// }
+/// impl<'de> SeededVisitor<'de, i64> for ResiduesVisitor {
+///     fn new(modulus: i64) -> Self {
+///         Self { modulus }
+///     }
+/// }
+///
+/// let seed = DeserializeSeedAny::<ResiduesVisitor, _>::new(5);
+/// assert_eq!(Residues::Single(2), seed.deserialize(&mut serde_json::Deserializer::from_str("7")).unwrap());
+/// let seed = DeserializeSeedAny::<ResiduesVisitor, _>::new(5);
+/// assert_eq!(Residues::Many(vec![1, 4]), seed.deserialize(&mut serde_json::Deserializer::from_str("[6, -1]")).unwrap());
+/// ```
+///
+pub struct DeserializeSeedAny<V, C> {
+    context: C,
+    visitor: PhantomData<V>
+}
+
+impl<V, C> DeserializeSeedAny<V, C> {
+
+    pub const fn new(context: C) -> Self {
+        Self { context: context, visitor: PhantomData }
+    }
+
+    ///
+    /// Constructs the [`SeededVisitor`], e.g. to pass it to a specific method of a [`Deserializer`]
+    /// instead of [`Deserializer::deserialize_any()`].
+    ///
+    pub fn into_visitor<'de>(self) -> V
+        where V: SeededVisitor<'de, C>
+    {
+        V::new(self.context)
+    }
+}
+
+impl<V> DeserializeSeedAny<V, V> {
+
+    ///
+    /// Creates a [`DeserializeSeedAny`] that uses the given plain [`Visitor`].
+    ///
+    pub const fn from_visitor(visitor: V) -> Self {
+        Self::new(visitor)
+    }
+}
+
+impl<V, C> Clone for DeserializeSeedAny<V, C>
+    where C: Clone
+{
+    fn clone(&self) -> Self {
+        Self::new(self.context.clone())
+    }
+}
+
+impl<V, C> Copy for DeserializeSeedAny<V, C>
+    where C: Copy
+{}
+
+impl<'de, V, C> DeserializeSeed<'de> for DeserializeSeedAny<V, C>
+    where V: SeededVisitor<'de, C>
+{
+    type Value = V::Value;
+
+    fn deserialize<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+        where D: Deserializer<'de>
+    {
+        deserializer.deserialize_any(self.into_visitor())
+    }
+}
+
+#[cfg(test)]
+use crate::default_seed::seed_of;
+#[cfg(test)]
+use crate::map::DeserializeSeedMap;
+#[cfg(test)]
+use std::collections::BTreeMap;
+#[cfg(test)]
+use std::iter::repeat;
+
+#[cfg(test)]
+#[derive(Debug, PartialEq)]
+enum Setting {
+    Flag(bool),
+    Text(String),
+    Nested(BTreeMap<String, i64>)
+}
+
+#[cfg(test)]
+struct SettingVisitor {
+    scale: i64
+}
+
+#[cfg(test)]
+impl<'de> Visitor<'de> for SettingVisitor {
+    type Value = Setting;
+
+    fn expecting(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "a bool, a string or a map")
+    }
+
+    fn visit_bool<E>(self, value: bool) -> Result<Setting, E> {
+        Ok(Setting::Flag(value))
+    }
+
+    fn visit_str<E>(self, value: &str) -> Result<Setting, E> {
+        Ok(Setting::Text(value.to_owned()))
+    }
+
+    fn visit_map<A>(self, map: A) -> Result<Setting, A::Error>
+        where A: serde::de::MapAccess<'de>
+    {
+        let scale = self.scale;
+        let seed = DeserializeSeedMap::new(repeat((seed_of::<String>(), seed_of::<i64>())), BTreeMap::new(), move |mut current, key, value| { current.insert(key, value * scale); current });
+        return seed.deserialize(serde::de::value::MapAccessDeserializer::new(map)).map(Setting::Nested);
+    }
+}
+
+#[cfg(test)]
+impl<'de> SeededVisitor<'de, i64> for SettingVisitor {
+    fn new(scale: i64) -> Self {
+        Self { scale }
+    }
+}
+
+#[test]
+fn test_serde_json() {
+    let seed = DeserializeSeedAny::<SettingVisitor, _>::new(10);
+    assert_eq!(Setting::Flag(true), seed.deserialize(&mut serde_json::Deserializer::from_str("true")).unwrap());
+    assert_eq!(Setting::Text("x".to_owned()), seed.deserialize(&mut serde_json::Deserializer::from_str("\"x\"")).unwrap());
+    assert_eq!(Setting::Nested(BTreeMap::from([("a".to_owned(), 10), ("b".to_owned(), -20)])), seed.deserialize(&mut serde_json::Deserializer::from_str(r#"{"a": 1, "b": -2}"#)).unwrap());
+    assert!(seed.deserialize(&mut serde_json::Deserializer::from_str("1")).is_err());
+
+    let plain = DeserializeSeedAny::from_visitor(SettingVisitor { scale: 1 });
+    assert_eq!(Setting::Flag(false), plain.deserialize(&mut serde_json::Deserializer::from_str("false")).unwrap());
+}
@@ -0,0 +1,205 @@
+use ::arbitrary::{Arbitrary, Result, Unstructured};
+
+use crate::mock::{MockValue, MockVariant};
+
+///
+/// The maximal nesting depth of [`MockValue`]s generated by their [`Arbitrary`] implementation.
+///
+pub const MAX_DEPTH: usize = 4;
+
+///
+/// The maximal number of elements of sequences, maps and strings in [`MockValue`]s generated
+/// by their [`Arbitrary`] implementation.
+///
+pub const MAX_LEN: usize = 8;
+
+///
+/// Generates an arbitrary [`MockValue`] of nesting depth at most `depth`. The [`Arbitrary`]
+/// implementation of [`MockValue`] uses this with [`MAX_DEPTH`].
+///
+pub fn arbitrary_mock_value(u: &mut Unstructured<'_>, depth: usize) -> Result<MockValue> {
+    const LEAF_KINDS: usize = 9;
+    const KINDS: usize = LEAF_KINDS + 5;
+    return Ok(match u.choose_index(if depth == 0 { LEAF_KINDS } else { KINDS })? {
+        0 => MockValue::Bool(u.arbitrary()?),
+        1 => MockValue::I64(u.arbitrary()?),
+        2 => MockValue::U64(u.arbitrary()?),
+        3 => MockValue::F64(u.arbitrary()?),
+        4 => MockValue::Char(u.arbitrary()?),
+        5 => MockValue::Str(arbitrary_identifier_str(u)?),
+        6 => MockValue::Bytes((0..u.int_in_range(0..=MAX_LEN)?).map(|_| u.arbitrary()).collect::<Result<_>>()?),
+        7 => MockValue::Unit,
+        8 => MockValue::None,
+        9 => MockValue::Some(Box::new(arbitrary_mock_value(u, depth - 1)?)),
+        10 => MockValue::NewtypeStruct(Box::new(arbitrary_mock_value(u, depth - 1)?)),
+        11 => MockValue::Seq(arbitrary_elements(u, depth - 1)?),
+        12 => MockValue::Map(arbitrary_entries(u, depth - 1)?),
+        13 => MockValue::Enum(Box::new(arbitrary_identifier(u)?), arbitrary_mock_variant(u, depth - 1)?),
+        _ => unreachable!()
+    });
+}
+
+///
+/// Generates an arbitrary [`MockVariant`] whose content has nesting depth at most `depth`.
+///
+pub fn arbitrary_mock_variant(u: &mut Unstructured<'_>, depth: usize) -> Result<MockVariant> {
+    return Ok(match u.choose_index(4)? {
+        0 => MockVariant::Unit,
+        1 => MockVariant::Newtype(Box::new(arbitrary_mock_value(u, depth)?)),
+        2 => MockVariant::Tuple(arbitrary_elements(u, depth)?),
+        3 => MockVariant::Struct(arbitrary_entries(u, depth)?),
+        _ => unreachable!()
+    });
+}
+
+///
+/// Generates a [`MockValue`] that is "near-valid" w.r.t. the given value, by applying up to
+/// `max_mutations` of the modifications of [`MockValue::mutations()`] to it.
+///
+/// This is meant to drive a [`serde::de::DeserializeSeed`] over inputs close to the ones it
+/// accepts, as completely arbitrary values are usually rejected right away.
+///
+/// # Example
+/// ```
+/// # use feanor_serde::impl_deserialize_seed_for_dependent_struct;
+/// # use feanor_serde::arbitrary::*;
+/// # use feanor_serde::mock::*;
+/// # use feanor_serde::default_seed::seed_of;
+/// # use serde::de::DeserializeSeed;
+/// # use arbitrary::Unstructured;
+/// struct PointDeserializeSeed;
+/// impl_deserialize_seed_for_dependent_struct!{
+///     pub struct Point<'de> using PointDeserializeSeed {
+///         x: i64: |_| seed_of::<i64>(),
+///         y: i64: |_| seed_of::<i64>()
+///     }
+/// }
+/// let input = MockValue::map([("x", 1i64), ("y", 2i64)]);
+/// let mut u = Unstructured::new(&[7, 1, 200, 3, 42]);
+/// let mutated = arbitrary_near_valid(&input, &mut u, 3).unwrap();
+/// if let Ok(point) = PointDeserializeSeed.deserialize(MockDeserializer::new(mutated)) {
+///     assert_eq!((1, 2), (point.x, point.y));
+/// }
+/// ```
+///
+pub fn arbitrary_near_valid(value: &MockValue, u: &mut Unstructured<'_>, max_mutations: usize) -> Result<MockValue> {
+    let mut current = value.clone();
+    for _ in 0..u.int_in_range(0..=max_mutations)? {
+        let mut mutations = current.mutations();
+        if mutations.is_empty() {
+            break;
+        }
+        let index = u.choose_index(mutations.len())?;
+        current = mutations.swap_remove(index);
+    }
+    return Ok(current);
+}
+
+fn arbitrary_identifier_str(u: &mut Unstructured<'_>) -> Result<String> {
+    (0..u.int_in_range(0..=MAX_LEN)?).map(|_| Ok(char::from(u.int_in_range(b'a'..=b'e')?))).collect()
+}
+
+///
+/// Generates a value suitable as a map key or enum variant, i.e. a short string or a small
+/// index, so that it matches some field or variant reasonably often.
+///
+fn arbitrary_identifier(u: &mut Unstructured<'_>) -> Result<MockValue> {
+    return Ok(match u.choose_index(3)? {
+        0 => MockValue::Str(arbitrary_identifier_str(u)?),
+        1 => MockValue::Bytes(arbitrary_identifier_str(u)?.into_bytes()),
+        2 => MockValue::U64(u.int_in_range(0..=MAX_LEN as u64)?),
+        _ => unreachable!()
+    });
+}
+
+fn arbitrary_elements(u: &mut Unstructured<'_>, depth: usize) -> Result<Vec<MockValue>> {
+    (0..u.int_in_range(0..=MAX_LEN)?).map(|_| arbitrary_mock_value(u, depth)).collect()
+}
+
+fn arbitrary_entries(u: &mut Unstructured<'_>, depth: usize) -> Result<Vec<(MockValue, MockValue)>> {
+    (0..u.int_in_range(0..=MAX_LEN)?).map(|_| Ok((arbitrary_identifier(u)?, arbitrary_mock_value(u, depth)?))).collect()
+}
+
+impl<'a> Arbitrary<'a> for MockValue {
+
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        arbitrary_mock_value(u, MAX_DEPTH)
+    }
+}
+
+impl<'a> Arbitrary<'a> for MockVariant {
+
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        arbitrary_mock_variant(u, MAX_DEPTH)
+    }
+}
+
+#[cfg(test)]
+use std::marker::PhantomData;
+#[cfg(test)]
+use serde::de::DeserializeSeed;
+#[cfg(test)]
+use crate::mock::MockDeserializer;
+#[cfg(test)]
+use crate::impl_deserialize_seed_for_dependent_struct;
+
+#[cfg(test)]
+fn pseudorandom_bytes(seed: u64, len: usize) -> Vec<u8> {
+    let mut state = seed.wrapping_mul(0x9E3779B97F4A7C15) | 1;
+    (0..len).map(|_| {
+        state ^= state << 13;
+        state ^= state >> 7;
+        state ^= state << 17;
+        (state >> 56) as u8
+    }).collect()
+}
+
+#[test]
+fn test_arbitrary_depth() {
+    fn depth(value: &MockValue) -> usize {
+        match value {
+            MockValue::Some(value) | MockValue::NewtypeStruct(value) => 1 + depth(value),
+            MockValue::Seq(elements) => 1 + elements.iter().map(depth).max().unwrap_or(0),
+            MockValue::Map(entries) => 1 + entries.iter().map(|(_, value)| depth(value)).max().unwrap_or(0),
+            MockValue::Enum(_, MockVariant::Unit) => 1,
+            MockValue::Enum(_, MockVariant::Newtype(value)) => 1 + depth(value),
+            MockValue::Enum(_, MockVariant::Tuple(elements)) => 1 + elements.iter().map(depth).max().unwrap_or(0),
+            MockValue::Enum(_, MockVariant::Struct(entries)) => 1 + entries.iter().map(|(_, value)| depth(value)).max().unwrap_or(0),
+            _ => 0
+        }
+    }
+    for i in 0..200 {
+        let data = pseudorandom_bytes(i, 256);
+        let value = MockValue::arbitrary(&mut Unstructured::new(&data)).unwrap();
+        assert!(depth(&value) <= MAX_DEPTH);
+    }
+}
+
+#[test]
+fn test_macro_seed_on_arbitrary() {
+    struct DeserializeSeedRecord;
+    impl_deserialize_seed_for_dependent_struct!{
+        pub struct Record<'de> using DeserializeSeedRecord {
+            a: i64: |_| PhantomData::<i64>,
+            b: Option<String>: |_| PhantomData::<Option<String>>
+        }
+    }
+    let valid = MockValue::map([(MockValue::from("a"), MockValue::from(1i64)), (MockValue::from("b"), MockValue::some("x"))]);
+    let mut accepted = 0;
+    for i in 0..200 {
+        let data = pseudorandom_bytes(i, 64);
+        let mut u = Unstructured::new(&data);
+        let mutated = arbitrary_near_valid(&valid, &mut u, 2).unwrap();
+        let is_valid = mutated == valid;
+        let result = DeserializeSeedRecord.deserialize(MockDeserializer::new(mutated));
+        if is_valid {
+            let result = result.unwrap();
+            assert_eq!(1, result.a);
+            assert_eq!(Some("x"), result.b.as_deref());
+            accepted += 1;
+        }
+        let arbitrary = MockValue::arbitrary(&mut u).unwrap();
+        let _ = DeserializeSeedRecord.deserialize(MockDeserializer::new(arbitrary));
+    }
+    assert!(accepted > 0);
+}
@@ -0,0 +1,433 @@
+use serde::de::{DeserializeSeed, Error, SeqAccess, Unexpected, Visitor};
+use serde::ser::{Serialize, Serializer};
+use serde::Deserializer;
+
+use crate::limits::Limits;
+
+const STANDARD_ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+const URL_SAFE_ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789-_";
+
+/// Marks the bytes that are not part of the alphabet in a [`decode_table()`]
+const INVALID_CHAR: u8 = 0xFF;
+
+static STANDARD_DECODE_TABLE: [u8; 256] = decode_table(STANDARD_ALPHABET);
+static URL_SAFE_DECODE_TABLE: [u8; 256] = decode_table(URL_SAFE_ALPHABET);
+
+///
+/// Builds the reverse lookup table of the given alphabet, which maps each byte to its
+/// position in the alphabet, or to [`INVALID_CHAR`] if it does not occur in it.
+///
+const fn decode_table(alphabet: &[u8; 64]) -> [u8; 256] {
+    let mut result = [INVALID_CHAR; 256];
+    let mut i = 0;
+    while i < alphabet.len() {
+        result[alphabet[i] as usize] = i as u8;
+        i += 1;
+    }
+    return result;
+}
+
+///
+/// The alphabet to use for base64-encoding, see RFC 4648.
+///
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Base64Alphabet {
+    /// The standard alphabet, using `+` and `/` as the last two characters
+    Standard,
+    /// The URL- and filename-safe alphabet, using `-` and `_` as the last two characters
+    UrlSafe
+}
+
+///
+/// Configuration of the base64-encoding used by [`SerializableBase64`] and
+/// [`DeserializeSeedBase64`].
+///
+/// The default configuration uses the standard alphabet with padding.
+///
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Base64Config {
+    alphabet: Base64Alphabet,
+    padding: bool
+}
+
+impl Base64Config {
+
+    pub const STANDARD: Base64Config = Base64Config { alphabet: Base64Alphabet::Standard, padding: true };
+    pub const STANDARD_NO_PAD: Base64Config = Base64Config { alphabet: Base64Alphabet::Standard, padding: false };
+    pub const URL_SAFE: Base64Config = Base64Config { alphabet: Base64Alphabet::UrlSafe, padding: true };
+    pub const URL_SAFE_NO_PAD: Base64Config = Base64Config { alphabet: Base64Alphabet::UrlSafe, padding: false };
+
+    pub const fn new(alphabet: Base64Alphabet, padding: bool) -> Self {
+        Self { alphabet, padding }
+    }
+
+    pub fn alphabet(&self) -> Base64Alphabet {
+        self.alphabet
+    }
+
+    ///
+    /// Whether the encoded string is padded with `=` to a multiple of 4 characters.
+    /// During decoding, padding is required if this is set, and forbidden otherwise.
+    ///
+    pub fn padding(&self) -> bool {
+        self.padding
+    }
+
+    fn alphabet_chars(&self) -> &'static [u8; 64] {
+        match self.alphabet {
+            Base64Alphabet::Standard => STANDARD_ALPHABET,
+            Base64Alphabet::UrlSafe => URL_SAFE_ALPHABET
+        }
+    }
+
+    fn decode_table(&self) -> &'static [u8; 256] {
+        match self.alphabet {
+            Base64Alphabet::Standard => &STANDARD_DECODE_TABLE,
+            Base64Alphabet::UrlSafe => &URL_SAFE_DECODE_TABLE
+        }
+    }
+
+    ///
+    /// Encodes the given bytes as base64 string.
+    ///
+    pub fn encode(&self, data: &[u8]) -> String {
+        let chars = self.alphabet_chars();
+        let mut result = String::with_capacity(data.len().div_ceil(3) * 4);
+        for chunk in data.chunks(3) {
+            let block = (chunk[0] as u32) << 16 | (*chunk.get(1).unwrap_or(&0) as u32) << 8 | *chunk.get(2).unwrap_or(&0) as u32;
+            for i in 0..=chunk.len() {
+                result.push(chars[((block >> (18 - 6 * i)) & 0x3F) as usize] as char);
+            }
+            if self.padding {
+                for _ in chunk.len()..3 {
+                    result.push('=');
+                }
+            }
+        }
+        return result;
+    }
+
+    ///
+    /// Decodes the given base64 string, or returns `None` if it is not
+    /// a valid encoding w.r.t. this configuration.
+    ///
+    pub fn decode(&self, data: &str) -> Option<Vec<u8>> {
+        let table = self.decode_table();
+        let decode_char = |c: u8| match table[c as usize] {
+            INVALID_CHAR => None,
+            value => Some(value as u32)
+        };
+        let mut data = data.as_bytes();
+        if self.padding {
+            if !data.len().is_multiple_of(4) {
+                return None;
+            }
+            let padding_len = data.iter().rev().take(2).take_while(|c| **c == b'=').count();
+            data = &data[..(data.len() - padding_len)];
+        }
+        if data.len() % 4 == 1 {
+            return None;
+        }
+        let mut result = Vec::with_capacity(data.len() / 4 * 3 + 2);
+        for chunk in data.chunks(4) {
+            let mut block = 0;
+            for (i, c) in chunk.iter().enumerate() {
+                block |= decode_char(*c)? << (18 - 6 * i);
+            }
+            let bytes = [(block >> 16) as u8, (block >> 8) as u8, block as u8];
+            let byte_count = chunk.len() - 1;
+            // the unused bits of a partial chunk must be zero, otherwise the encoding is not canonical
+            if bytes[byte_count..].iter().any(|b| *b != 0) {
+                return None;
+            }
+            result.extend_from_slice(&bytes[..byte_count]);
+        }
+        return Some(result);
+    }
+}
+
+impl Default for Base64Config {
+
+    fn default() -> Self {
+        Self::STANDARD
+    }
+}
+
+///
+/// Wraps a byte buffer, and implements [`Serialize`] by mapping it to a base64-encoded
+/// string for human-readable serializers, and to the bytes type in the serde data model
+/// otherwise.
+///
+/// # Example
+/// ```
+/// # use feanor_serde::base64::*;
+/// let data = [0xFF, 0x00, 0x01, 0x02];
+/// assert_eq!("\"/wABAg==\"", serde_json::to_string(&SerializableBase64::new(&data)).unwrap());
+/// assert_eq!("\"_wABAg\"", serde_json::to_string(&SerializableBase64::new_with_config(&data, Base64Config::URL_SAFE_NO_PAD)).unwrap());
+/// ```
+///
+pub struct SerializableBase64<T>
+    where T: AsRef<[u8]>
+{
+    data: T,
+    config: Base64Config
+}
+
+impl<T> SerializableBase64<T>
+    where T: AsRef<[u8]>
+{
+    pub fn new(data: T) -> Self {
+        Self::new_with_config(data, Base64Config::default())
+    }
+
+    pub fn new_with_config(data: T, config: Base64Config) -> Self {
+        Self { data, config }
+    }
+}
+
+impl<T> Serialize for SerializableBase64<T>
+    where T: AsRef<[u8]>
+{
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+        where S: Serializer
+    {
+        if serializer.is_human_readable() {
+            serializer.serialize_str(&self.config.encode(self.data.as_ref()))
+        } else {
+            serializer.serialize_bytes(self.data.as_ref())
+        }
+    }
+}
+
+///
+/// A [`DeserializeSeed`] that deserializes a byte buffer that was serialized
+/// using [`SerializableBase64`] with the same [`Base64Config`].
+///
+/// # Example
+/// ```
+/// # use feanor_serde::base64::*;
+/// # use serde::de::DeserializeSeed;
+/// let mut deserializer = serde_json::Deserializer::new(serde_json::de::StrRead::new("\"/wABAg==\""));
+/// assert_eq!(vec![0xFF, 0x00, 0x01, 0x02], DeserializeSeedBase64::new().deserialize(&mut deserializer).unwrap());
+/// ```
+///
+#[derive(Clone, Copy, Default)]
+pub struct DeserializeSeedBase64 {
+    config: Base64Config,
+    limits: Limits
+}
+
+impl DeserializeSeedBase64 {
+
+    pub fn new() -> Self {
+        Self::new_with_config(Base64Config::default())
+    }
+
+    pub fn new_with_config(config: Base64Config) -> Self {
+        Self { config, limits: Limits::UNLIMITED }
+    }
+
+    ///
+    /// Makes deserialization fail if the base64-encoded string is longer than
+    /// [`Limits::max_str_len()`], or the byte buffer is longer than [`Limits::max_bytes_len()`].
+    ///
+    pub fn with_limits(self, limits: Limits) -> Self {
+        Self { limits, ..self }
+    }
+}
+
+impl<'de> DeserializeSeed<'de> for DeserializeSeedBase64 {
+    type Value = Vec<u8>;
+
+    fn deserialize<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+        where D: Deserializer<'de>
+    {
+        struct Base64Visitor {
+            config: Base64Config,
+            limits: Limits
+        }
+
+        impl<'de> Visitor<'de> for Base64Visitor {
+            type Value = Vec<u8>;
+
+            fn expecting(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+                write!(f, "a base64-encoded string")
+            }
+
+            fn visit_str<E>(self, value: &str) -> Result<Self::Value, E>
+                where E: Error
+            {
+                self.limits.check_str_len(value.len())?;
+                let result = self.config.decode(value).ok_or_else(|| E::invalid_value(Unexpected::Str(value), &self))?;
+                self.limits.check_bytes_len(result.len())?;
+                return Ok(result);
+            }
+        }
+
+        struct BytesVisitor {
+            limits: Limits
+        }
+
+        impl<'de> Visitor<'de> for BytesVisitor {
+            type Value = Vec<u8>;
+
+            fn expecting(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+                write!(f, "a byte buffer")
+            }
+
+            fn visit_bytes<E>(self, value: &[u8]) -> Result<Self::Value, E>
+                where E: Error
+            {
+                self.limits.check_bytes_len(value.len())?;
+                Ok(value.to_owned())
+            }
+
+            fn visit_byte_buf<E>(self, value: Vec<u8>) -> Result<Self::Value, E>
+                where E: Error
+            {
+                self.limits.check_bytes_len(value.len())?;
+                Ok(value)
+            }
+
+            fn visit_seq<A>(self, mut seq: A) -> Result<Self::Value, A::Error>
+                where A: SeqAccess<'de>
+            {
+                if let Some(len) = seq.size_hint() {
+                    self.limits.check_bytes_len(len)?;
+                }
+                let mut result = Vec::with_capacity(seq.size_hint().unwrap_or(0).min(4096));
+                while let Some(byte) = seq.next_element()? {
+                    result.push(byte);
+                    self.limits.check_bytes_len(result.len())?;
+                }
+                return Ok(result);
+            }
+        }
+
+        if deserializer.is_human_readable() {
+            deserializer.deserialize_str(Base64Visitor { config: self.config, limits: self.limits })
+        } else {
+            deserializer.deserialize_byte_buf(BytesVisitor { limits: self.limits })
+        }
+    }
+}
+
+#[cfg(test)]
+use crate::test::token::{Token, TokenDeserializer, TokenSerializer};
+
+#[cfg(test)]
+fn testdata() -> Vec<Vec<u8>> {
+    vec![
+        Vec::new(),
+        vec![0],
+        vec![0xFF, 0xFE],
+        vec![1, 2, 3],
+        (0..=255).collect()
+    ]
+}
+
+#[test]
+fn test_encode_decode() {
+    assert_eq!("", Base64Config::STANDARD.encode(b""));
+    assert_eq!("Zg==", Base64Config::STANDARD.encode(b"f"));
+    assert_eq!("Zm8=", Base64Config::STANDARD.encode(b"fo"));
+    assert_eq!("Zm9v", Base64Config::STANDARD.encode(b"foo"));
+    assert_eq!("Zm9vYg==", Base64Config::STANDARD.encode(b"foob"));
+    assert_eq!("Zm9vYmE=", Base64Config::STANDARD.encode(b"fooba"));
+    assert_eq!("Zm9vYmFy", Base64Config::STANDARD.encode(b"foobar"));
+    assert_eq!("Zm9vYg", Base64Config::STANDARD_NO_PAD.encode(b"foob"));
+    assert_eq!("-_8", Base64Config::URL_SAFE_NO_PAD.encode(&[0xFB, 0xFF]));
+    assert_eq!("+/8=", Base64Config::STANDARD.encode(&[0xFB, 0xFF]));
+
+    for config in [Base64Config::STANDARD, Base64Config::STANDARD_NO_PAD, Base64Config::URL_SAFE, Base64Config::URL_SAFE_NO_PAD] {
+        for data in testdata() {
+            assert_eq!(Some(data.clone()), config.decode(&config.encode(&data)));
+        }
+    }
+
+    assert_eq!(None, Base64Config::STANDARD.decode("Zm9vYg"));
+    assert_eq!(None, Base64Config::STANDARD_NO_PAD.decode("Zm9vYg=="));
+    assert_eq!(None, Base64Config::STANDARD.decode("Zm9vY==="));
+    assert_eq!(None, Base64Config::STANDARD.decode("-_8="));
+    assert_eq!(None, Base64Config::STANDARD.decode("Zh=="));
+    assert_eq!(None, Base64Config::STANDARD_NO_PAD.decode("Zm9vY"));
+}
+
+#[test]
+fn test_decode_table() {
+    for (alphabet, table) in [(STANDARD_ALPHABET, &STANDARD_DECODE_TABLE), (URL_SAFE_ALPHABET, &URL_SAFE_DECODE_TABLE)] {
+        for c in 0..=u8::MAX {
+            assert_eq!(alphabet.iter().position(|x| *x == c).map(|pos| pos as u8).unwrap_or(INVALID_CHAR), table[c as usize]);
+        }
+    }
+    assert_eq!(None, Base64Config::STANDARD.decode("Zm9\u{FF}"));
+    assert_eq!(None, Base64Config::URL_SAFE.decode("Zm+v"));
+    assert_eq!(None, Base64Config::STANDARD.decode("Zm-v"));
+}
+
+#[test]
+fn test_serde_postcard() {
+    for data in testdata() {
+        let serialized = postcard::to_allocvec(&SerializableBase64::new(&data)).unwrap();
+        // postcard encodes bytes in the same way as a sequence of `u8`
+        assert_eq!(postcard::to_allocvec(&data).unwrap(), serialized);
+        let result = DeserializeSeedBase64::new().deserialize(
+            &mut postcard::Deserializer::from_flavor(postcard::de_flavors::Slice::new(&serialized))
+        ).unwrap();
+        assert_eq!(data, result);
+    }
+}
+
+#[test]
+fn test_serde_json() {
+    for data in testdata() {
+        for config in [Base64Config::STANDARD, Base64Config::URL_SAFE_NO_PAD] {
+            let serialized = serde_json::to_string(&SerializableBase64::new_with_config(&data, config)).unwrap();
+            assert_eq!(format!("\"{}\"", config.encode(&data)), serialized);
+            let result = DeserializeSeedBase64::new_with_config(config).deserialize(
+                &mut serde_json::Deserializer::from_str(&serialized)
+            ).unwrap();
+            assert_eq!(data, result);
+        }
+    }
+    let result = DeserializeSeedBase64::new().deserialize(&mut serde_json::Deserializer::from_str("\"Zm9v!\""));
+    assert!(result.is_err());
+}
+
+#[test]
+fn test_serde_tokens() {
+    for human_readable in [true, false] {
+        let data = vec![1, 2, 3, 4];
+        let serializer = TokenSerializer::new().with_human_readable(human_readable);
+        let tokens = SerializableBase64::new(&data).serialize(serializer).unwrap();
+        if human_readable {
+            assert_eq!(tokens, [Token::Str("AQIDBA==".to_owned())]);
+        } else {
+            assert_eq!(tokens, [Token::Bytes(data.clone())]);
+        }
+        let mut deserializer = TokenDeserializer::new(tokens).with_human_readable(human_readable);
+        let result = DeserializeSeedBase64::new().deserialize(&mut deserializer).unwrap();
+        assert_eq!(data, result);
+    }
+}
+
+#[test]
+fn test_limits() {
+    let limits = Limits::UNLIMITED.with_max_bytes_len(2).with_max_str_len(4);
+    for data in testdata() {
+        let serialized = postcard::to_allocvec(&SerializableBase64::new(&data)).unwrap();
+        let result = DeserializeSeedBase64::new().with_limits(limits).deserialize(
+            &mut postcard::Deserializer::from_flavor(postcard::de_flavors::Slice::new(&serialized))
+        );
+        assert_eq!(data.len() <= 2, result.is_ok());
+
+        let serialized = serde_json::to_string(&SerializableBase64::new(&data)).unwrap();
+        let result = DeserializeSeedBase64::new().with_limits(limits).deserialize(
+            &mut serde_json::Deserializer::from_str(&serialized)
+        );
+        assert_eq!(data.len() <= 2, result.is_ok());
+    }
+    let limits = Limits::UNLIMITED.with_max_bytes_len(3).with_max_str_len(3);
+    assert!(DeserializeSeedBase64::new_with_config(Base64Config::STANDARD_NO_PAD).with_limits(limits).deserialize(&mut serde_json::Deserializer::from_str("\"Zm8\"")).is_ok());
+    assert!(DeserializeSeedBase64::new().with_limits(limits).deserialize(&mut serde_json::Deserializer::from_str("\"Zm9v\"")).is_err());
+}
@@ -0,0 +1,362 @@
+use std::marker::PhantomData;
+
+use serde::de::{DeserializeSeed, Error, SeqAccess, Unexpected, Visitor};
+use serde::ser::{Serialize, SerializeTuple, Serializer};
+use serde::Deserializer;
+
+use crate::raw_bytes::Bytes;
+
+const DECIMAL_CHUNK: u64 = 10_000_000_000_000_000_000;
+const DECIMAL_CHUNK_DIGITS: usize = 19;
+
+///
+/// The maximal number of decimal digits accepted by [`DeserializeSeedBigInt`] from
+/// human-readable formats, which corresponds to integers of roughly 330 000 bits.
+///
+/// Converting a decimal string to binary takes time quadratic in its length, so longer
+/// strings are rejected to bound the work caused by untrusted input.
+///
+pub const MAX_DECIMAL_DIGITS: usize = 100_000;
+
+///
+/// Divides the given little-endian digits by `divisor` in place, and
+/// returns the remainder.
+///
+fn div_rem_small(digits: &mut [u64], divisor: u64) -> u64 {
+    let mut remainder: u128 = 0;
+    for digit in digits.iter_mut().rev() {
+        let current = (remainder << 64) | *digit as u128;
+        *digit = (current / divisor as u128) as u64;
+        remainder = current % divisor as u128;
+    }
+    return remainder as u64;
+}
+
+///
+/// Computes `digits * factor + summand` in place, where `digits` are
+/// little-endian digits.
+///
+fn mul_add_small(digits: &mut Vec<u64>, factor: u64, summand: u64) {
+    let mut carry = summand as u128;
+    for digit in digits.iter_mut() {
+        let current = *digit as u128 * factor as u128 + carry;
+        *digit = current as u64;
+        carry = current >> 64;
+    }
+    if carry != 0 {
+        digits.push(carry as u64);
+    }
+}
+
+fn trimmed(digits: &[u64]) -> &[u64] {
+    let len = digits.iter().rposition(|d| *d != 0).map(|i| i + 1).unwrap_or(0);
+    return &digits[..len];
+}
+
+fn to_decimal_string(negative: bool, digits: &[u64]) -> String {
+    let mut digits = trimmed(digits).to_owned();
+    if digits.is_empty() {
+        return "0".to_owned();
+    }
+    let mut chunks = Vec::new();
+    while !trimmed(&digits).is_empty() {
+        chunks.push(div_rem_small(&mut digits, DECIMAL_CHUNK));
+    }
+    let mut result = String::with_capacity(chunks.len() * DECIMAL_CHUNK_DIGITS + 1);
+    if negative {
+        result.push('-');
+    }
+    result.push_str(&chunks.last().unwrap().to_string());
+    for chunk in chunks.iter().rev().skip(1) {
+        result.push_str(&format!("{:0width$}", chunk, width = DECIMAL_CHUNK_DIGITS));
+    }
+    return result;
+}
+
+fn parse_decimal_string(value: &str) -> Option<(bool, Vec<u64>)> {
+    let (negative, value) = match value.strip_prefix('-') {
+        Some(rest) => (true, rest),
+        None => (false, value.strip_prefix('+').unwrap_or(value))
+    };
+    if value.is_empty() || !value.bytes().all(|c| c.is_ascii_digit()) {
+        return None;
+    }
+    let mut digits = Vec::with_capacity(value.len() / DECIMAL_CHUNK_DIGITS + 1);
+    let first_chunk_len = (value.len() - 1) % DECIMAL_CHUNK_DIGITS + 1;
+    mul_add_small(&mut digits, DECIMAL_CHUNK, value[..first_chunk_len].parse().unwrap());
+    for start in (first_chunk_len..value.len()).step_by(DECIMAL_CHUNK_DIGITS) {
+        mul_add_small(&mut digits, DECIMAL_CHUNK, value[start..(start + DECIMAL_CHUNK_DIGITS)].parse().unwrap());
+    }
+    let len = trimmed(&digits).len();
+    digits.truncate(len);
+    return Some((negative && !digits.is_empty(), digits));
+}
+
+///
+/// Wraps an arbitrary-precision integer, given by its sign and the little-endian
+/// base-`2^64` digits of its absolute value, and implements [`Serialize`] by mapping
+/// it to a decimal string for human-readable serializers, and to a tuple `(sign, magnitude)`
+/// otherwise. Here `sign` is a `bool` which is `true` for negative numbers, and `magnitude`
+/// are the little-endian bytes of the absolute value, without trailing zeros.
+///
+/// Since this only requires access to the digits as `&[u64]`, it can be used for any
+/// big integer implementation, e.g. those of `feanor-math` or `num-bigint`.
+///
+/// # Example
+/// ```
+/// # use feanor_serde::bigint::*;
+/// // 2^64 + 1
+/// let digits = [1, 1];
+/// assert_eq!("\"-18446744073709551617\"", serde_json::to_string(&SerializableBigInt::new(true, &digits)).unwrap());
+/// assert_eq!(vec![1, 9, 1, 0, 0, 0, 0, 0, 0, 0, 1], postcard::to_allocvec(&SerializableBigInt::new(true, &digits)).unwrap());
+/// ```
+///
+pub struct SerializableBigInt<'a> {
+    negative: bool,
+    digits: &'a [u64]
+}
+
+impl<'a> SerializableBigInt<'a> {
+
+    ///
+    /// Creates a new [`SerializableBigInt`] representing the number `(-1)^negative * sum_i digits[i] 2^(64 i)`.
+    /// Note that the sign of zero is ignored.
+    ///
+    pub const fn new(negative: bool, digits: &'a [u64]) -> Self {
+        Self { negative, digits }
+    }
+}
+
+impl<'a> Serialize for SerializableBigInt<'a> {
+
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+        where S: Serializer
+    {
+        let digits = trimmed(self.digits);
+        let negative = self.negative && !digits.is_empty();
+        if serializer.is_human_readable() {
+            serializer.serialize_str(&to_decimal_string(negative, digits))
+        } else {
+            let mut bytes = digits.iter().flat_map(|d| d.to_le_bytes()).collect::<Vec<_>>();
+            let len = bytes.iter().rposition(|b| *b != 0).map(|i| i + 1).unwrap_or(0);
+            bytes.truncate(len);
+
+            let mut tuple = serializer.serialize_tuple(2)?;
+            tuple.serialize_element(&negative)?;
+            tuple.serialize_element(&Bytes(&bytes))?;
+            return tuple.end();
+        }
+    }
+}
+
+///
+/// A [`DeserializeSeed`] that deserializes an arbitrary-precision integer that was
+/// serialized using [`SerializableBigInt`]. The result is constructed by calling the
+/// given function with the sign (`true` for negative numbers) and the little-endian
+/// base-`2^64` digits of the absolute value. The digits passed to the function never
+/// have trailing zeros, and the sign of zero is always `false`.
+///
+/// Decimal strings with more than [`MAX_DECIMAL_DIGITS`] digits are rejected.
+///
+/// # Example
+/// ```
+/// # use feanor_serde::bigint::*;
+/// # use serde::de::DeserializeSeed;
+/// let mut deserializer = serde_json::Deserializer::new(serde_json::de::StrRead::new("\"-18446744073709551617\""));
+/// let deserialize_seed = DeserializeSeedBigInt::new(|negative, digits: Vec<u64>| (negative, digits));
+/// assert_eq!((true, vec![1, 1]), deserialize_seed.deserialize(&mut deserializer).unwrap());
+/// ```
+///
+pub struct DeserializeSeedBigInt<F, T>
+    where F: FnOnce(bool, Vec<u64>) -> T
+{
+    result: PhantomData<T>,
+    create: F
+}
+
+impl<F, T> DeserializeSeedBigInt<F, T>
+    where F: FnOnce(bool, Vec<u64>) -> T
+{
+    pub const fn new(create: F) -> Self {
+        Self { result: PhantomData, create }
+    }
+}
+
+impl<'de, F, T> DeserializeSeed<'de> for DeserializeSeedBigInt<F, T>
+    where F: FnOnce(bool, Vec<u64>) -> T
+{
+    type Value = T;
+
+    fn deserialize<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+        where D: Deserializer<'de>
+    {
+        struct DecimalVisitor;
+
+        impl<'de> Visitor<'de> for DecimalVisitor {
+            type Value = (bool, Vec<u64>);
+
+            fn expecting(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+                write!(f, "a decimal integer string")
+            }
+
+            fn visit_str<E>(self, value: &str) -> Result<Self::Value, E>
+                where E: Error
+            {
+                let digit_count = value.strip_prefix(['-', '+']).unwrap_or(value).len();
+                if digit_count > MAX_DECIMAL_DIGITS {
+                    return Err(crate::errors::invalid_length(digit_count, "a decimal integer string with fewer digits", format_args!("a decimal integer string with at most {} digits", MAX_DECIMAL_DIGITS)));
+                }
+                parse_decimal_string(value).ok_or_else(|| E::invalid_value(Unexpected::Str(value), &self))
+            }
+        }
+
+        struct MagnitudeVisitor;
+
+        impl<'de> Visitor<'de> for MagnitudeVisitor {
+            type Value = Vec<u64>;
+
+            fn expecting(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+                write!(f, "little-endian magnitude bytes")
+            }
+
+            fn visit_bytes<E>(self, value: &[u8]) -> Result<Self::Value, E>
+                where E: Error
+            {
+                let mut result = value.chunks(8).map(|chunk| {
+                    let mut digit = [0; 8];
+                    digit[..chunk.len()].copy_from_slice(chunk);
+                    u64::from_le_bytes(digit)
+                }).collect::<Vec<_>>();
+                let len = trimmed(&result).len();
+                result.truncate(len);
+                return Ok(result);
+            }
+        }
+
+        struct MagnitudeSeed;
+
+        impl<'de> DeserializeSeed<'de> for MagnitudeSeed {
+            type Value = Vec<u64>;
+
+            fn deserialize<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+                where D: Deserializer<'de>
+            {
+                deserializer.deserialize_bytes(MagnitudeVisitor)
+            }
+        }
+
+        struct SignMagnitudeVisitor;
+
+        impl<'de> Visitor<'de> for SignMagnitudeVisitor {
+            type Value = (bool, Vec<u64>);
+
+            fn expecting(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+                write!(f, "a tuple with 2 elements")
+            }
+
+            fn visit_seq<A>(self, mut seq: A) -> Result<Self::Value, A::Error>
+                where A: SeqAccess<'de>
+            {
+                let negative: bool = seq.next_element()?.ok_or_else(|| A::Error::invalid_length(0, &self))?;
+                let magnitude = seq.next_element_seed(MagnitudeSeed)?.ok_or_else(|| A::Error::invalid_length(1, &self))?;
+                return Ok((negative && !magnitude.is_empty(), magnitude));
+            }
+        }
+
+        let (negative, digits) = if deserializer.is_human_readable() {
+            deserializer.deserialize_str(DecimalVisitor)?
+        } else {
+            deserializer.deserialize_tuple(2, SignMagnitudeVisitor)?
+        };
+        return Ok((self.create)(negative, digits));
+    }
+}
+
+#[cfg(test)]
+use crate::test::token::{Token, TokenDeserializer, TokenSerializer};
+
+#[cfg(test)]
+fn testdata() -> Vec<(bool, Vec<u64>, &'static str)> {
+    vec![
+        (false, vec![], "0"),
+        (false, vec![1], "1"),
+        (true, vec![1], "-1"),
+        (false, vec![u64::MAX], "18446744073709551615"),
+        (false, vec![0, 1], "18446744073709551616"),
+        (true, vec![DECIMAL_CHUNK], "-10000000000000000000"),
+        (false, vec![0, 0, 1], "340282366920938463463374607431768211456"),
+        (true, vec![u64::MAX, u64::MAX, u64::MAX], "-6277101735386680763835789423207666416102355444464034512895"),
+    ]
+}
+
+#[test]
+fn test_decimal() {
+    for (negative, digits, decimal) in testdata() {
+        assert_eq!(decimal, to_decimal_string(negative, &digits));
+        assert_eq!(Some((negative, digits)), parse_decimal_string(decimal));
+    }
+    assert_eq!("0", to_decimal_string(true, &[0, 0]));
+    assert_eq!(Some((false, vec![])), parse_decimal_string("-0000"));
+    assert_eq!(Some((false, vec![12])), parse_decimal_string("+12"));
+    assert_eq!(None, parse_decimal_string(""));
+    assert_eq!(None, parse_decimal_string("-"));
+    assert_eq!(None, parse_decimal_string("12a"));
+    assert_eq!(None, parse_decimal_string("1 2"));
+}
+
+#[test]
+fn test_max_decimal_digits() {
+    let seed = || DeserializeSeedBigInt::new(|negative, digits| (negative, digits));
+    let longest = format!("\"-{}\"", "9".repeat(MAX_DECIMAL_DIGITS));
+    let (negative, digits) = seed().deserialize(&mut serde_json::Deserializer::from_str(&longest)).unwrap();
+    assert!(negative);
+    assert_eq!(&longest[1..(longest.len() - 1)], to_decimal_string(negative, &digits));
+
+    let too_long = format!("\"{}\"", "1".repeat(MAX_DECIMAL_DIGITS + 1));
+    let error = seed().deserialize(&mut serde_json::Deserializer::from_str(&too_long)).unwrap_err();
+    assert!(error.to_string().contains(&(MAX_DECIMAL_DIGITS + 1).to_string()));
+}
+
+#[test]
+fn test_serde_postcard() {
+    for (negative, digits, _) in testdata() {
+        let serialized = postcard::to_allocvec(&SerializableBigInt::new(negative, &digits)).unwrap();
+        let result = DeserializeSeedBigInt::new(|negative, digits| (negative, digits)).deserialize(
+            &mut postcard::Deserializer::from_flavor(postcard::de_flavors::Slice::new(&serialized))
+        ).unwrap();
+        assert_eq!((negative, digits), result);
+    }
+}
+
+#[test]
+fn test_serde_json() {
+    for (negative, digits, decimal) in testdata() {
+        let serialized = serde_json::to_string(&SerializableBigInt::new(negative, &digits)).unwrap();
+        assert_eq!(format!("\"{}\"", decimal), serialized);
+        let result = DeserializeSeedBigInt::new(|negative, digits| (negative, digits)).deserialize(
+            &mut serde_json::Deserializer::from_str(&serialized)
+        ).unwrap();
+        assert_eq!((negative, digits), result);
+    }
+}
+
+#[test]
+fn test_serde_tokens() {
+    for human_readable in [true, false] {
+        let serializer = TokenSerializer::new().with_human_readable(human_readable);
+        let tokens = SerializableBigInt::new(true, &[0x0102, 0]).serialize(serializer).unwrap();
+        if human_readable {
+            assert_eq!(tokens, [Token::Str("-258".to_owned())]);
+        } else {
+            assert_eq!(tokens, [
+                Token::Tuple { len: 2 },
+                Token::Bool(true),
+                Token::Bytes(vec![0x02, 0x01]),
+                Token::TupleEnd
+            ]);
+        }
+        let mut deserializer = TokenDeserializer::new(tokens).with_human_readable(human_readable);
+        let result = DeserializeSeedBigInt::new(|negative, digits| (negative, digits)).deserialize(&mut deserializer).unwrap();
+        assert_eq!((true, vec![0x0102]), result);
+    }
+}
@@ -0,0 +1,222 @@
+use serde::de::DeserializeSeed;
+use serde::ser::Serialize;
+
+use ::bincode::Options;
+
+///
+/// Returns the [`Options`] used by [`::bincode::serialize()`] and [`::bincode::deserialize()`],
+/// i.e. little-endian fixed-size integers, `u64` length prefixes and allowing trailing bytes.
+///
+/// Note that this is different from [`::bincode::options()`], which uses variable-length
+/// integers and rejects trailing bytes.
+///
+pub fn legacy_options() -> impl Options + Copy {
+    ::bincode::DefaultOptions::new().with_fixint_encoding().allow_trailing_bytes()
+}
+
+///
+/// Deserializes a value from the given bincode-encoded bytes, using the given [`DeserializeSeed`]
+/// and the same configuration as [`::bincode::deserialize()`], see [`legacy_options()`].
+///
+/// # Compatibility
+///
+/// Like postcard, bincode is not self-describing, so the combinators of this crate that need
+/// [`serde::Deserializer::deserialize_any()`] or [`serde::Deserializer::deserialize_ignored_any()`]
+/// are not supported. In particular
+///  - [`crate::seq::DeserializeSeedSeq`] never needs to skip elements, since bincode reports the
+///    length of each sequence up front, but it fails if it runs out of seeds before the end;
+///  - sequences and maps must be serialized with a known length, e.g. using
+///    [`crate::seq::SerializableSeq::new_with_len()`] instead of [`crate::seq::SerializableSeq::new()`],
+///    otherwise serialization fails;
+///  - structs are serialized as tuples, so structs deserialized by
+///    [`crate::impl_deserialize_seed_for_dependent_struct!`] must declare their fields in the
+///    same order as the serialized struct, and fields cannot be missing;
+///  - enum variants are identified by their index, which is always written as a `u32`.
+///
+/// The only difference between postcard and the two standard bincode configurations is the
+/// encoding of integers and lengths, which are encoded with fixed size by [`legacy_options()`],
+/// and as varints (with a different varint encoding than postcard) by [`::bincode::options()`].
+/// This does not affect the behavior of the combinators, but data written with one configuration
+/// cannot be read with the other.
+///
+/// # Example
+/// ```
+/// # use feanor_serde::seq::*;
+/// # use feanor_serde::default_seed::seed_of;
+/// let serialized = feanor_serde::bincode::to_vec(SerializableSeq::new_with_len([1i64, 3, 5].into_iter(), 3)).unwrap();
+/// assert_eq!(bincode::serialize(&[1i64, 3, 5][..]).unwrap(), serialized);
+/// let seed = SeqSeedBuilder::new(seed_of::<i64>).collect_vec();
+/// assert_eq!(vec![1, 3, 5], feanor_serde::bincode::from_slice_seeded(seed, &serialized).unwrap());
+/// ```
+///
+pub fn from_slice_seeded<'de, S>(seed: S, data: &'de [u8]) -> ::bincode::Result<S::Value>
+    where S: DeserializeSeed<'de>
+{
+    from_slice_seeded_with_options(legacy_options(), seed, data)
+}
+
+///
+/// Deserializes a value from the given bincode-encoded bytes, using the given [`DeserializeSeed`]
+/// and bincode configuration. See [`from_slice_seeded()`] for details.
+///
+pub fn from_slice_seeded_with_options<'de, O, S>(options: O, seed: S, data: &'de [u8]) -> ::bincode::Result<S::Value>
+    where O: Options,
+        S: DeserializeSeed<'de>
+{
+    options.deserialize_seed(seed, data)
+}
+
+///
+/// Serializes the given value into a newly allocated [`Vec`], using the same configuration
+/// as [`::bincode::serialize()`], see [`legacy_options()`].
+///
+/// This accepts the serializable wrappers of this crate (like [`crate::seq::SerializableSeq`])
+/// by value, so that they can be created inline.
+///
+pub fn to_vec<T>(value: T) -> ::bincode::Result<Vec<u8>>
+    where T: Serialize
+{
+    to_vec_with_options(legacy_options(), value)
+}
+
+///
+/// Serializes the given value into a newly allocated [`Vec`], using the given bincode
+/// configuration.
+///
+pub fn to_vec_with_options<O, T>(options: O, value: T) -> ::bincode::Result<Vec<u8>>
+    where O: Options,
+        T: Serialize
+{
+    options.serialize(&value)
+}
+
+#[cfg(test)]
+use std::collections::BTreeMap;
+#[cfg(test)]
+use crate::default_seed::seed_of;
+#[cfg(test)]
+use crate::seq::{SerializableSeq, SeqSeedBuilder, DeserializeSeedSeq};
+#[cfg(test)]
+use crate::map::{SerializableMap, DeserializeSeedMap};
+#[cfg(test)]
+use crate::option::{SerializableOption, DeserializeSeedOption};
+#[cfg(test)]
+use crate::dependent_tuple::DeserializeSeedDependentTuple;
+#[cfg(test)]
+use crate::struct_seed::StructSeed;
+#[cfg(test)]
+use crate::enum_seed::EnumSeed;
+#[cfg(test)]
+use crate::{impl_deserialize_seed_for_dependent_enum, impl_deserialize_seed_for_dependent_struct};
+
+#[cfg(test)]
+#[derive(Debug, PartialEq, serde::Serialize)]
+struct Foo {
+    a: i64,
+    b: Option<String>,
+    c: Vec<u8>
+}
+
+#[cfg(test)]
+#[derive(Debug, PartialEq, serde::Serialize)]
+enum Bar {
+    A(i64),
+    B(String)
+}
+
+#[cfg(test)]
+struct DeserializeSeedFoo;
+
+#[cfg(test)]
+impl_deserialize_seed_for_dependent_struct!{
+    pub struct DeserializedFoo<'de> using DeserializeSeedFoo {
+        a: i64: |_| seed_of::<i64>(),
+        b: Option<String>: |_| seed_of::<Option<String>>(),
+        c: Vec<u8>: |_| seed_of::<Vec<u8>>()
+    }
+}
+
+#[cfg(test)]
+struct DeserializeSeedBar;
+
+#[cfg(test)]
+impl_deserialize_seed_for_dependent_enum!{
+    pub enum DeserializedBar<'de> using DeserializeSeedBar {
+        A(i64): |_| seed_of::<i64>(),
+        B(String): |_| seed_of::<String>()
+    }
+}
+
+#[cfg(test)]
+fn check_combinators<O>(options: O)
+    where O: Options + Copy
+{
+    let serialized = to_vec_with_options(options, SerializableSeq::new_with_len([1i64, -2, 3].into_iter(), 3)).unwrap();
+    assert_eq!(vec![1, -2, 3], from_slice_seeded_with_options(options, SeqSeedBuilder::new(seed_of::<i64>).collect_vec(), &serialized).unwrap());
+    assert!(from_slice_seeded_with_options(options, SeqSeedBuilder::from_seeds(std::iter::repeat_n(seed_of::<i64>(), 2)).collect_vec(), &serialized).is_err());
+    assert!(to_vec_with_options(options, SerializableSeq::new([1i64].into_iter())).is_err());
+
+    let map = BTreeMap::from([("a".to_owned(), 1i64), ("b".to_owned(), 2)]);
+    let serialized = to_vec_with_options(options, SerializableMap::new_with_len(map.iter(), map.len())).unwrap();
+    let seed = DeserializeSeedMap::new(std::iter::repeat((seed_of::<String>(), seed_of::<i64>())), BTreeMap::new(), |mut current, key, value| { current.insert(key, value); current });
+    assert_eq!(map, from_slice_seeded_with_options(options, seed, &serialized).unwrap());
+
+    let serialized = to_vec_with_options(options, SerializableOption::new(Some(&5i64), |x| *x)).unwrap();
+    assert_eq!(Some(5), from_slice_seeded_with_options(options, DeserializeSeedOption::new(seed_of::<i64>()), &serialized).unwrap());
+
+    // arrays are serialized as tuples, so they have no length prefix and cannot be read as a sequence
+    let serialized = to_vec_with_options(options, (3usize, [0i64, 1, 2])).unwrap();
+    let seed = DeserializeSeedDependentTuple::new(seed_of::<usize>(), |len| SeqSeedBuilder::new(seed_of::<i64>).exact_len(len).collect_vec());
+    assert!(from_slice_seeded_with_options(options, seed, &serialized).is_err());
+    let serialized = to_vec_with_options(options, (3usize, vec![0i64, 1, 2])).unwrap();
+    let seed = DeserializeSeedDependentTuple::new(seed_of::<usize>(), |len| SeqSeedBuilder::new(seed_of::<i64>).exact_len(len).collect_vec());
+    assert_eq!(vec![0, 1, 2], from_slice_seeded_with_options(options, seed, &serialized).unwrap());
+
+    let foo = Foo { a: -1, b: Some("x".to_owned()), c: vec![1, 2] };
+    let serialized = to_vec_with_options(options, &foo).unwrap();
+    let result = from_slice_seeded_with_options(options, DeserializeSeedFoo, &serialized).unwrap();
+    assert_eq!(foo, Foo { a: result.a, b: result.b, c: result.c });
+    let seed = StructSeed::new("Foo").field("a", seed_of::<i64>()).field("b", seed_of::<Option<String>>()).field("c", seed_of::<Vec<u8>>())
+        .finish_with(|((a, b), c)| Foo { a, b, c });
+    assert_eq!(foo, from_slice_seeded_with_options(options, seed, &serialized).unwrap());
+
+    for bar in [Bar::A(-1), Bar::B("x".to_owned())] {
+        let serialized = to_vec_with_options(options, &bar).unwrap();
+        let result = match from_slice_seeded_with_options(options, DeserializeSeedBar, &serialized).unwrap() {
+            DeserializedBar::A(value) => Bar::A(value.0),
+            DeserializedBar::B(value) => Bar::B(value.0)
+        };
+        assert_eq!(bar, result);
+        let seed = EnumSeed::new("Bar").variant("A", seed_of::<i64>(), Bar::A).variant("B", seed_of::<String>(), Bar::B);
+        assert_eq!(bar, from_slice_seeded_with_options(options, seed, &serialized).unwrap());
+    }
+}
+
+#[test]
+fn test_legacy_options() {
+    check_combinators(legacy_options());
+    let serialized = to_vec(SerializableSeq::new_with_len([1u32, 2].into_iter(), 2)).unwrap();
+    assert_eq!(::bincode::serialize(&[1u32, 2][..]).unwrap(), serialized);
+    assert_eq!(16, serialized.len());
+    let mut with_trailing = serialized.clone();
+    with_trailing.push(0);
+    assert_eq!(vec![1, 2], from_slice_seeded(SeqSeedBuilder::new(seed_of::<u32>).collect_vec(), &with_trailing).unwrap());
+}
+
+#[test]
+fn test_varint_options() {
+    check_combinators(::bincode::options());
+    let serialized = to_vec_with_options(::bincode::options(), SerializableSeq::new_with_len([1u32, 2].into_iter(), 2)).unwrap();
+    assert_eq!(3, serialized.len());
+    assert!(from_slice_seeded(SeqSeedBuilder::new(seed_of::<u32>).collect_vec(), &serialized).is_err());
+    let mut with_trailing = serialized.clone();
+    with_trailing.push(0);
+    assert!(from_slice_seeded_with_options(::bincode::options(), SeqSeedBuilder::new(seed_of::<u32>).collect_vec(), &with_trailing).is_err());
+}
+
+#[test]
+fn test_unsupported() {
+    let serialized = to_vec(SerializableSeq::new_with_len([1i64, 2].into_iter(), 2)).unwrap();
+    let seed = DeserializeSeedSeq::new(std::iter::repeat_n(seed_of::<i64>(), 1), Vec::new(), |mut current: Vec<i64>, next| { current.push(next); current });
+    assert!(from_slice_seeded(seed, &serialized).is_err());
+}
@@ -0,0 +1,520 @@
+use std::cell::Cell;
+use std::fmt::Display;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::{Duration, Instant};
+
+use serde::de::{DeserializeSeed, EnumAccess, Error, MapAccess, SeqAccess, VariantAccess, Visitor};
+use serde::Deserializer;
+
+///
+/// Something that can signal that a deserialization should be cancelled, as used by
+/// [`deserialize_cancellable()`].
+///
+/// This is implemented for [`AtomicBool`], e.g. shared with the UI thread via an
+/// [`std::sync::Arc`], and for closures returning `true` once deserialization should stop.
+///
+pub trait CancellationToken {
+
+    fn is_cancelled(&self) -> bool;
+}
+
+impl CancellationToken for AtomicBool {
+
+    fn is_cancelled(&self) -> bool {
+        self.load(Ordering::Relaxed)
+    }
+}
+
+impl<F> CancellationToken for F
+    where F: Fn() -> bool
+{
+    fn is_cancelled(&self) -> bool {
+        self()
+    }
+}
+
+///
+/// A [`CancellationToken`] that is triggered once the given point in time has passed,
+/// as used by [`deserialize_with_timeout()`].
+///
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Deadline {
+    deadline: Option<Instant>
+}
+
+impl Deadline {
+
+    pub const fn at(deadline: Instant) -> Self {
+        Self { deadline: Some(deadline) }
+    }
+
+    ///
+    /// Creates a [`Deadline`] that is exceeded once `timeout` has passed from now on.
+    /// Timeouts too large to be represented never expire.
+    ///
+    pub fn after(timeout: Duration) -> Self {
+        Self { deadline: Instant::now().checked_add(timeout) }
+    }
+
+    pub fn is_exceeded(&self) -> bool {
+        self.deadline.is_some_and(|deadline| Instant::now() >= deadline)
+    }
+}
+
+impl CancellationToken for Deadline {
+
+    fn is_cancelled(&self) -> bool {
+        self.is_exceeded()
+    }
+}
+
+///
+/// The error returned by [`deserialize_cancellable()`] resp. [`deserialize_with_timeout()`].
+///
+#[derive(Clone, Debug)]
+pub enum CancelError<E> {
+    ///
+    /// Deserialization was aborted, since the [`CancellationToken`] was triggered.
+    ///
+    Cancelled,
+    ///
+    /// Deserialization was aborted, since it exceeded the time budget given to
+    /// [`deserialize_with_timeout()`].
+    ///
+    TimedOut,
+    ///
+    /// Deserialization failed with the given error of the deserializer.
+    ///
+    Deserialize(E)
+}
+
+impl<E> CancelError<E> {
+
+    pub fn is_cancelled(&self) -> bool {
+        matches!(self, CancelError::Cancelled)
+    }
+
+    pub fn is_timed_out(&self) -> bool {
+        matches!(self, CancelError::TimedOut)
+    }
+}
+
+impl<E> Display for CancelError<E>
+    where E: Display
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CancelError::Cancelled => write!(f, "deserialization was cancelled"),
+            CancelError::TimedOut => write!(f, "deserialization exceeded its time budget"),
+            CancelError::Deserialize(e) => e.fmt(f)
+        }
+    }
+}
+
+impl<E> std::error::Error for CancelError<E>
+    where E: std::error::Error
+{}
+
+///
+/// Deserializes a value using the given [`DeserializeSeed`], but aborts with
+/// [`CancelError::Cancelled`] once the given [`CancellationToken`] is triggered.
+///
+/// The token is checked before deserialization starts, and then every `check_every`
+/// elements of sequences resp. entries of maps (counted across all nesting levels). This
+/// works by wrapping the deserializer, so it does not require any cooperation of the
+/// [`DeserializeSeed`].
+///
+/// # Example
+/// ```
+/// # use feanor_serde::cancel::*;
+/// # use feanor_serde::default_seed::seed_of;
+/// # use std::sync::Arc;
+/// # use std::sync::atomic::{AtomicBool, Ordering};
+/// let cancelled = Arc::new(AtomicBool::new(false));
+/// let mut deserializer = serde_json::Deserializer::from_str("[[1, 2], [3]]");
+/// let result = deserialize_cancellable(seed_of::<Vec<Vec<i64>>>(), &mut deserializer, &*cancelled, 16);
+/// assert_eq!(vec![vec![1, 2], vec![3]], result.unwrap());
+///
+/// cancelled.store(true, Ordering::Relaxed);
+/// let mut deserializer = serde_json::Deserializer::from_str("[[1, 2], [3]]");
+/// let result = deserialize_cancellable(seed_of::<Vec<Vec<i64>>>(), &mut deserializer, &*cancelled, 16);
+/// assert!(result.unwrap_err().is_cancelled());
+/// ```
+///
+pub fn deserialize_cancellable<'de, S, D, C>(seed: S, deserializer: D, token: &C, check_every: usize) -> Result<S::Value, CancelError<D::Error>>
+    where S: DeserializeSeed<'de>,
+        D: Deserializer<'de>,
+        C: ?Sized + CancellationToken
+{
+    if token.is_cancelled() {
+        return Err(CancelError::Cancelled);
+    }
+    let state = State { token: token, check_every: check_every.max(1), count: Cell::new(0), cancelled: Cell::new(false) };
+    return seed.deserialize(CancellableDeserializer { de: deserializer, state: &state }).map_err(|e| if state.cancelled.get() {
+        CancelError::Cancelled
+    } else {
+        CancelError::Deserialize(e)
+    });
+}
+
+///
+/// Deserializes a value using the given [`DeserializeSeed`], but aborts with
+/// [`CancelError::TimedOut`] once the given wall-clock time budget is exceeded.
+///
+/// As for [`deserialize_cancellable()`], the time is only checked before every element
+/// of a sequence resp. entry of a map, so a single huge string or byte buffer can still
+/// exceed the budget. Use [`crate::limits::Limits`] to bound those.
+///
+/// # Example
+/// ```
+/// # use feanor_serde::cancel::*;
+/// # use feanor_serde::default_seed::seed_of;
+/// # use std::time::Duration;
+/// let mut deserializer = serde_json::Deserializer::from_str("[1, 2, 3]");
+/// assert_eq!(vec![1, 2, 3], deserialize_with_timeout(seed_of::<Vec<i64>>(), &mut deserializer, Duration::from_secs(10)).unwrap());
+/// let mut deserializer = serde_json::Deserializer::from_str("[1, 2, 3]");
+/// assert!(deserialize_with_timeout(seed_of::<Vec<i64>>(), &mut deserializer, Duration::ZERO).unwrap_err().is_timed_out());
+/// ```
+///
+pub fn deserialize_with_timeout<'de, S, D>(seed: S, deserializer: D, timeout: Duration) -> Result<S::Value, CancelError<D::Error>>
+    where S: DeserializeSeed<'de>,
+        D: Deserializer<'de>
+{
+    return deserialize_cancellable(seed, deserializer, &Deadline::after(timeout), 1).map_err(|e| match e {
+        CancelError::Cancelled => CancelError::TimedOut,
+        e => e
+    });
+}
+
+struct State<'b, C>
+    where C: ?Sized + CancellationToken
+{
+    token: &'b C,
+    check_every: usize,
+    count: Cell<usize>,
+    cancelled: Cell<bool>
+}
+
+impl<'b, C> State<'b, C>
+    where C: ?Sized + CancellationToken
+{
+    ///
+    /// Counts an element, and fails if the token should be checked and is triggered.
+    ///
+    fn check<E>(&self) -> Result<(), E>
+        where E: Error
+    {
+        let count = self.count.get() + 1;
+        self.count.set(count);
+        if count.is_multiple_of(self.check_every) && self.token.is_cancelled() {
+            self.cancelled.set(true);
+            return Err(E::custom("deserialization was cancelled"));
+        }
+        return Ok(());
+    }
+}
+
+struct CancellableDeserializer<'b, D, C>
+    where C: ?Sized + CancellationToken
+{
+    de: D,
+    state: &'b State<'b, C>
+}
+
+struct CancellableSeed<'b, S, C>
+    where C: ?Sized + CancellationToken
+{
+    seed: S,
+    state: &'b State<'b, C>
+}
+
+impl<'b, 'de, S, C> DeserializeSeed<'de> for CancellableSeed<'b, S, C>
+    where S: DeserializeSeed<'de>,
+        C: ?Sized + CancellationToken
+{
+    type Value = S::Value;
+
+    fn deserialize<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+        where D: Deserializer<'de>
+    {
+        self.seed.deserialize(CancellableDeserializer { de: deserializer, state: self.state })
+    }
+}
+
+macro_rules! forward_deserialize {
+    ($($method:ident($($arg:ident: $type:ty),*)),*) => {
+        $(
+            fn $method<V>(self, $($arg: $type,)* visitor: V) -> Result<V::Value, Self::Error>
+                where V: Visitor<'de>
+            {
+                self.de.$method($($arg,)* Wrap { inner: visitor, state: self.state })
+            }
+        )*
+    };
+}
+
+impl<'b, 'de, D, C> Deserializer<'de> for CancellableDeserializer<'b, D, C>
+    where D: Deserializer<'de>,
+        C: ?Sized + CancellationToken
+{
+    type Error = D::Error;
+
+    forward_deserialize!{
+        deserialize_any(), deserialize_bool(), deserialize_i8(), deserialize_i16(), deserialize_i32(), deserialize_i64(), deserialize_i128(),
+        deserialize_u8(), deserialize_u16(), deserialize_u32(), deserialize_u64(), deserialize_u128(), deserialize_f32(), deserialize_f64(),
+        deserialize_char(), deserialize_str(), deserialize_string(), deserialize_bytes(), deserialize_byte_buf(), deserialize_option(),
+        deserialize_unit(), deserialize_seq(), deserialize_map(), deserialize_identifier(), deserialize_ignored_any(),
+        deserialize_unit_struct(name: &'static str), deserialize_newtype_struct(name: &'static str), deserialize_tuple(len: usize),
+        deserialize_tuple_struct(name: &'static str, len: usize), deserialize_struct(name: &'static str, fields: &'static [&'static str]),
+        deserialize_enum(name: &'static str, variants: &'static [&'static str])
+    }
+
+    fn is_human_readable(&self) -> bool {
+        self.de.is_human_readable()
+    }
+}
+
+///
+/// Wraps a [`Visitor`] resp. one of the accessors, in order to wrap nested deserializers
+/// and to check the token while iterating over sequences and maps.
+///
+struct Wrap<'b, X, C>
+    where C: ?Sized + CancellationToken
+{
+    inner: X,
+    state: &'b State<'b, C>
+}
+
+macro_rules! forward_visit {
+    ($($method:ident($type:ty)),*) => {
+        $(
+            fn $method<E>(self, value: $type) -> Result<Self::Value, E>
+                where E: Error
+            {
+                self.inner.$method(value)
+            }
+        )*
+    };
+}
+
+impl<'b, 'de, X, C> Visitor<'de> for Wrap<'b, X, C>
+    where X: Visitor<'de>,
+        C: ?Sized + CancellationToken
+{
+    type Value = X::Value;
+
+    fn expecting(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        self.inner.expecting(f)
+    }
+
+    forward_visit!{
+        visit_bool(bool), visit_i8(i8), visit_i16(i16), visit_i32(i32), visit_i64(i64), visit_i128(i128),
+        visit_u8(u8), visit_u16(u16), visit_u32(u32), visit_u64(u64), visit_u128(u128), visit_f32(f32), visit_f64(f64),
+        visit_char(char), visit_str(&str), visit_borrowed_str(&'de str), visit_string(String),
+        visit_bytes(&[u8]), visit_borrowed_bytes(&'de [u8]), visit_byte_buf(Vec<u8>)
+    }
+
+    fn visit_none<E>(self) -> Result<Self::Value, E>
+        where E: Error
+    {
+        self.inner.visit_none()
+    }
+
+    fn visit_unit<E>(self) -> Result<Self::Value, E>
+        where E: Error
+    {
+        self.inner.visit_unit()
+    }
+
+    fn visit_some<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+        where D: Deserializer<'de>
+    {
+        self.inner.visit_some(CancellableDeserializer { de: deserializer, state: self.state })
+    }
+
+    fn visit_newtype_struct<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+        where D: Deserializer<'de>
+    {
+        self.inner.visit_newtype_struct(CancellableDeserializer { de: deserializer, state: self.state })
+    }
+
+    fn visit_seq<A>(self, seq: A) -> Result<Self::Value, A::Error>
+        where A: SeqAccess<'de>
+    {
+        self.inner.visit_seq(Wrap { inner: seq, state: self.state })
+    }
+
+    fn visit_map<A>(self, map: A) -> Result<Self::Value, A::Error>
+        where A: MapAccess<'de>
+    {
+        self.inner.visit_map(Wrap { inner: map, state: self.state })
+    }
+
+    fn visit_enum<A>(self, data: A) -> Result<Self::Value, A::Error>
+        where A: EnumAccess<'de>
+    {
+        self.inner.visit_enum(Wrap { inner: data, state: self.state })
+    }
+}
+
+impl<'b, 'de, X, C> SeqAccess<'de> for Wrap<'b, X, C>
+    where X: SeqAccess<'de>,
+        C: ?Sized + CancellationToken
+{
+    type Error = X::Error;
+
+    fn next_element_seed<T>(&mut self, seed: T) -> Result<Option<T::Value>, Self::Error>
+        where T: DeserializeSeed<'de>
+    {
+        self.state.check()?;
+        self.inner.next_element_seed(CancellableSeed { seed: seed, state: self.state })
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        self.inner.size_hint()
+    }
+}
+
+impl<'b, 'de, X, C> MapAccess<'de> for Wrap<'b, X, C>
+    where X: MapAccess<'de>,
+        C: ?Sized + CancellationToken
+{
+    type Error = X::Error;
+
+    fn next_key_seed<K>(&mut self, seed: K) -> Result<Option<K::Value>, Self::Error>
+        where K: DeserializeSeed<'de>
+    {
+        self.state.check()?;
+        self.inner.next_key_seed(CancellableSeed { seed: seed, state: self.state })
+    }
+
+    fn next_value_seed<V>(&mut self, seed: V) -> Result<V::Value, Self::Error>
+        where V: DeserializeSeed<'de>
+    {
+        self.inner.next_value_seed(CancellableSeed { seed: seed, state: self.state })
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        self.inner.size_hint()
+    }
+}
+
+impl<'b, 'de, X, C> EnumAccess<'de> for Wrap<'b, X, C>
+    where X: EnumAccess<'de>,
+        C: ?Sized + CancellationToken
+{
+    type Error = X::Error;
+    type Variant = Wrap<'b, X::Variant, C>;
+
+    fn variant_seed<V>(self, seed: V) -> Result<(V::Value, Self::Variant), Self::Error>
+        where V: DeserializeSeed<'de>
+    {
+        let state = self.state;
+        let (value, access) = self.inner.variant_seed(CancellableSeed { seed: seed, state: state })?;
+        return Ok((value, Wrap { inner: access, state: state }));
+    }
+}
+
+impl<'b, 'de, X, C> VariantAccess<'de> for Wrap<'b, X, C>
+    where X: VariantAccess<'de>,
+        C: ?Sized + CancellationToken
+{
+    type Error = X::Error;
+
+    fn unit_variant(self) -> Result<(), Self::Error> {
+        self.inner.unit_variant()
+    }
+
+    fn newtype_variant_seed<T>(self, seed: T) -> Result<T::Value, Self::Error>
+        where T: DeserializeSeed<'de>
+    {
+        self.inner.newtype_variant_seed(CancellableSeed { seed: seed, state: self.state })
+    }
+
+    fn tuple_variant<V>(self, len: usize, visitor: V) -> Result<V::Value, Self::Error>
+        where V: Visitor<'de>
+    {
+        self.inner.tuple_variant(len, Wrap { inner: visitor, state: self.state })
+    }
+
+    fn struct_variant<V>(self, fields: &'static [&'static str], visitor: V) -> Result<V::Value, Self::Error>
+        where V: Visitor<'de>
+    {
+        self.inner.struct_variant(fields, Wrap { inner: visitor, state: self.state })
+    }
+}
+
+#[cfg(test)]
+use crate::default_seed::seed_of;
+#[cfg(test)]
+use crate::seq::DeserializeSeedSeq;
+
+#[test]
+fn test_serde_json() {
+    let json = "[[1, 2], [3, 4, 5], []]";
+    let checks = Cell::new(0);
+    let token = || { checks.set(checks.get() + 1); false };
+    let result = deserialize_cancellable(seed_of::<Vec<Vec<i64>>>(), &mut serde_json::Deserializer::from_str(json), &token, 1);
+    assert_eq!(vec![vec![1, 2], vec![3, 4, 5], vec![]], result.unwrap());
+    // the initial check, and one for each call to next_element
+    assert_eq!(1 + 4 + 3 + 4 + 1, checks.get());
+
+    checks.set(0);
+    assert!(deserialize_cancellable(seed_of::<Vec<Vec<i64>>>(), &mut serde_json::Deserializer::from_str(json), &token, 4).is_ok());
+    assert_eq!(1 + 3, checks.get());
+
+    let error = deserialize_cancellable(seed_of::<Vec<Vec<i64>>>(), &mut serde_json::Deserializer::from_str("[[1, \"x\"]]"), &token, 1).unwrap_err();
+    assert!(!error.is_cancelled());
+    assert!(error.to_string().starts_with("invalid type: string \"x\""));
+}
+
+#[test]
+fn test_cancel_during_deserialization() {
+    let cancelled = AtomicBool::new(false);
+    let seed = DeserializeSeedSeq::new(std::iter::repeat(seed_of::<i64>()), 0, |current, next| {
+        if current == 2 {
+            cancelled.store(true, Ordering::Relaxed);
+        }
+        current + next
+    });
+    let serialized = postcard::to_allocvec(&[1i64; 10][..]).unwrap();
+    let error = deserialize_cancellable(seed, &mut postcard::Deserializer::from_bytes(&serialized), &cancelled, 1).unwrap_err();
+    assert!(error.is_cancelled());
+    assert_eq!("deserialization was cancelled", error.to_string());
+}
+
+#[test]
+fn test_enum() {
+    #[derive(serde::Deserialize, serde::Serialize, Debug, PartialEq)]
+    enum Record {
+        A(Vec<i64>),
+        B { values: Vec<u8> }
+    }
+    let data = Record::B { values: vec![1, 2, 3] };
+    let serialized = postcard::to_allocvec(&data).unwrap();
+    let checks = Cell::new(0);
+    let token = || { checks.set(checks.get() + 1); checks.get() > 3 };
+    let error = deserialize_cancellable(std::marker::PhantomData::<Record>, &mut postcard::Deserializer::from_bytes(&serialized), &token, 1).unwrap_err();
+    assert!(error.is_cancelled());
+    let never = || false;
+    assert_eq!(data, deserialize_cancellable(std::marker::PhantomData::<Record>, &mut postcard::Deserializer::from_bytes(&serialized), &never, 1).unwrap());
+}
+
+#[test]
+fn test_timeout() {
+    let data = (0..100).collect::<Vec<i64>>();
+    let serialized = postcard::to_allocvec(&data).unwrap();
+    let result = deserialize_with_timeout(seed_of::<Vec<i64>>(), &mut postcard::Deserializer::from_bytes(&serialized), Duration::MAX);
+    assert_eq!(data, result.unwrap());
+
+    let seed = DeserializeSeedSeq::new(std::iter::repeat(seed_of::<i64>()), 0, |current, next| {
+        std::thread::sleep(Duration::from_millis(10));
+        current + next
+    });
+    let error = deserialize_with_timeout(seed, &mut postcard::Deserializer::from_bytes(&serialized), Duration::from_millis(50)).unwrap_err();
+    assert!(error.is_timed_out());
+    assert_eq!("deserialization exceeded its time budget", error.to_string());
+
+    let error = deserialize_with_timeout(seed_of::<Vec<i64>>(), &mut serde_json::Deserializer::from_str("[1, \"x\"]"), Duration::MAX).unwrap_err();
+    assert!(!error.is_timed_out() && !error.is_cancelled());
+}
@@ -0,0 +1,840 @@
+use std::marker::PhantomData;
+
+use serde::ser::*;
+
+///
+/// Serializes the wrapped value in a canonical way, i.e. such that equal values produce the
+/// same output, independent of e.g. the iteration order of a [`std::collections::HashMap`].
+/// This is useful when the serialized data is hashed or compared, e.g. for caching. See
+/// [`CanonicalSerializer`] for details.
+///
+/// # Example
+/// ```
+/// # use feanor_serde::canonical::*;
+/// # use std::collections::{BTreeMap, HashMap};
+/// let map: HashMap<_, _> = (0..100).map(|i| (i, i * i)).collect();
+/// let sorted: BTreeMap<_, _> = map.iter().map(|(k, v)| (*k, *v)).collect();
+/// assert_eq!(
+///     serde_json::to_string(&sorted).unwrap(),
+///     serde_json::to_string(&Canonical::new(&map)).unwrap()
+/// );
+/// ```
+///
+#[derive(Clone, Copy, Debug)]
+pub struct Canonical<T> {
+    value: T,
+    canonical_floats: bool
+}
+
+impl<T> Canonical<T>
+    where T: Serialize
+{
+    pub fn new(value: T) -> Self {
+        Self { value: value, canonical_floats: false }
+    }
+
+    ///
+    /// See [`CanonicalSerializer::with_canonical_floats()`].
+    ///
+    pub fn with_canonical_floats(self, canonical_floats: bool) -> Self {
+        Self { canonical_floats, ..self }
+    }
+}
+
+impl<T> Serialize for Canonical<T>
+    where T: Serialize
+{
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+        where S: Serializer
+    {
+        self.value.serialize(CanonicalSerializer::new(serializer).with_canonical_floats(self.canonical_floats))
+    }
+}
+
+///
+/// A [`Serializer`] that wraps another [`Serializer`], and makes the output canonical.
+/// More concretely
+///  - the entries of maps are sorted by their key; for this, each map is buffered in memory
+///    before it is passed to the inner serializer,
+///  - if enabled via [`CanonicalSerializer::with_canonical_floats()`], negative zero is
+///    replaced by zero, and all NaNs are replaced by [`f64::NAN`] resp. [`f32::NAN`].
+///
+/// Struct fields are not reordered, since they are always serialized in the same order, usually
+/// the order of declaration. Sequences are not reordered either, thus e.g. a
+/// [`std::collections::HashSet`] is still not serialized canonically.
+///
+/// Keys are sorted by their serde data model representation, where values of different type
+/// are ordered by the type, integers and strings in the natural way, and floats by their bits.
+/// This is deterministic, but might not agree with the [`Ord`] implementation of the key type.
+///
+pub struct CanonicalSerializer<S> {
+    inner: S,
+    canonical_floats: bool
+}
+
+impl<S> CanonicalSerializer<S>
+    where S: Serializer
+{
+    pub fn new(inner: S) -> Self {
+        Self { inner: inner, canonical_floats: false }
+    }
+
+    ///
+    /// Sets whether floats should be canonicalized, which is `false` by default.
+    ///
+    pub fn with_canonical_floats(self, canonical_floats: bool) -> Self {
+        Self { canonical_floats, ..self }
+    }
+
+    fn wrap<'a, T>(&self, value: &'a T) -> Canonical<&'a T>
+        where T: ?Sized + Serialize
+    {
+        Canonical { value: value, canonical_floats: self.canonical_floats }
+    }
+}
+
+fn canonical_f32(value: f32, canonical_floats: bool) -> f32 {
+    if !canonical_floats {
+        value
+    } else if value.is_nan() {
+        f32::NAN
+    } else if value == 0. {
+        0.
+    } else {
+        value
+    }
+}
+
+fn canonical_f64(value: f64, canonical_floats: bool) -> f64 {
+    if !canonical_floats {
+        value
+    } else if value.is_nan() {
+        f64::NAN
+    } else if value == 0. {
+        0.
+    } else {
+        value
+    }
+}
+
+impl<S> Serializer for CanonicalSerializer<S>
+    where S: Serializer
+{
+    type Ok = S::Ok;
+    type Error = S::Error;
+    type SerializeSeq = CanonicalCompound<S::SerializeSeq>;
+    type SerializeTuple = CanonicalCompound<S::SerializeTuple>;
+    type SerializeTupleStruct = CanonicalCompound<S::SerializeTupleStruct>;
+    type SerializeTupleVariant = CanonicalCompound<S::SerializeTupleVariant>;
+    type SerializeMap = CanonicalMap<S>;
+    type SerializeStruct = CanonicalCompound<S::SerializeStruct>;
+    type SerializeStructVariant = CanonicalCompound<S::SerializeStructVariant>;
+
+    fn serialize_bool(self, v: bool) -> Result<S::Ok, S::Error> { self.inner.serialize_bool(v) }
+    fn serialize_i8(self, v: i8) -> Result<S::Ok, S::Error> { self.inner.serialize_i8(v) }
+    fn serialize_i16(self, v: i16) -> Result<S::Ok, S::Error> { self.inner.serialize_i16(v) }
+    fn serialize_i32(self, v: i32) -> Result<S::Ok, S::Error> { self.inner.serialize_i32(v) }
+    fn serialize_i64(self, v: i64) -> Result<S::Ok, S::Error> { self.inner.serialize_i64(v) }
+    fn serialize_i128(self, v: i128) -> Result<S::Ok, S::Error> { self.inner.serialize_i128(v) }
+    fn serialize_u8(self, v: u8) -> Result<S::Ok, S::Error> { self.inner.serialize_u8(v) }
+    fn serialize_u16(self, v: u16) -> Result<S::Ok, S::Error> { self.inner.serialize_u16(v) }
+    fn serialize_u32(self, v: u32) -> Result<S::Ok, S::Error> { self.inner.serialize_u32(v) }
+    fn serialize_u64(self, v: u64) -> Result<S::Ok, S::Error> { self.inner.serialize_u64(v) }
+    fn serialize_u128(self, v: u128) -> Result<S::Ok, S::Error> { self.inner.serialize_u128(v) }
+    fn serialize_f32(self, v: f32) -> Result<S::Ok, S::Error> { self.inner.serialize_f32(canonical_f32(v, self.canonical_floats)) }
+    fn serialize_f64(self, v: f64) -> Result<S::Ok, S::Error> { self.inner.serialize_f64(canonical_f64(v, self.canonical_floats)) }
+    fn serialize_char(self, v: char) -> Result<S::Ok, S::Error> { self.inner.serialize_char(v) }
+    fn serialize_str(self, v: &str) -> Result<S::Ok, S::Error> { self.inner.serialize_str(v) }
+    fn serialize_bytes(self, v: &[u8]) -> Result<S::Ok, S::Error> { self.inner.serialize_bytes(v) }
+    fn serialize_none(self) -> Result<S::Ok, S::Error> { self.inner.serialize_none() }
+    fn serialize_unit(self) -> Result<S::Ok, S::Error> { self.inner.serialize_unit() }
+    fn serialize_unit_struct(self, name: &'static str) -> Result<S::Ok, S::Error> { self.inner.serialize_unit_struct(name) }
+
+    fn serialize_some<T>(self, value: &T) -> Result<S::Ok, S::Error>
+        where T: ?Sized + Serialize
+    {
+        let value = self.wrap(value);
+        self.inner.serialize_some(&value)
+    }
+
+    fn serialize_unit_variant(self, name: &'static str, variant_index: u32, variant: &'static str) -> Result<S::Ok, S::Error> {
+        self.inner.serialize_unit_variant(name, variant_index, variant)
+    }
+
+    fn serialize_newtype_struct<T>(self, name: &'static str, value: &T) -> Result<S::Ok, S::Error>
+        where T: ?Sized + Serialize
+    {
+        let value = self.wrap(value);
+        self.inner.serialize_newtype_struct(name, &value)
+    }
+
+    fn serialize_newtype_variant<T>(self, name: &'static str, variant_index: u32, variant: &'static str, value: &T) -> Result<S::Ok, S::Error>
+        where T: ?Sized + Serialize
+    {
+        let value = self.wrap(value);
+        self.inner.serialize_newtype_variant(name, variant_index, variant, &value)
+    }
+
+    fn serialize_seq(self, len: Option<usize>) -> Result<Self::SerializeSeq, S::Error> {
+        Ok(CanonicalCompound::new(self.inner.serialize_seq(len)?, self.canonical_floats))
+    }
+
+    fn serialize_tuple(self, len: usize) -> Result<Self::SerializeTuple, S::Error> {
+        Ok(CanonicalCompound::new(self.inner.serialize_tuple(len)?, self.canonical_floats))
+    }
+
+    fn serialize_tuple_struct(self, name: &'static str, len: usize) -> Result<Self::SerializeTupleStruct, S::Error> {
+        Ok(CanonicalCompound::new(self.inner.serialize_tuple_struct(name, len)?, self.canonical_floats))
+    }
+
+    fn serialize_tuple_variant(self, name: &'static str, variant_index: u32, variant: &'static str, len: usize) -> Result<Self::SerializeTupleVariant, S::Error> {
+        Ok(CanonicalCompound::new(self.inner.serialize_tuple_variant(name, variant_index, variant, len)?, self.canonical_floats))
+    }
+
+    fn serialize_map(self, len: Option<usize>) -> Result<Self::SerializeMap, S::Error> {
+        let content_serializer = ContentSerializer { is_human_readable: self.inner.is_human_readable(), canonical_floats: self.canonical_floats, sort_maps: true, error: PhantomData };
+        Ok(CanonicalMap { serializer: self.inner, content_serializer: content_serializer, entries: Vec::with_capacity(len.unwrap_or(0)), next_key: None })
+    }
+
+    fn serialize_struct(self, name: &'static str, len: usize) -> Result<Self::SerializeStruct, S::Error> {
+        Ok(CanonicalCompound::new(self.inner.serialize_struct(name, len)?, self.canonical_floats))
+    }
+
+    fn serialize_struct_variant(self, name: &'static str, variant_index: u32, variant: &'static str, len: usize) -> Result<Self::SerializeStructVariant, S::Error> {
+        Ok(CanonicalCompound::new(self.inner.serialize_struct_variant(name, variant_index, variant, len)?, self.canonical_floats))
+    }
+
+    fn is_human_readable(&self) -> bool {
+        self.inner.is_human_readable()
+    }
+}
+
+///
+/// The compound serializers of [`CanonicalSerializer`], except for maps, which wrap the
+/// corresponding compound serializer of the inner [`Serializer`].
+///
+pub struct CanonicalCompound<C> {
+    inner: C,
+    canonical_floats: bool
+}
+
+impl<C> CanonicalCompound<C> {
+
+    fn new(inner: C, canonical_floats: bool) -> Self {
+        Self { inner: inner, canonical_floats: canonical_floats }
+    }
+
+    fn wrap<'a, T>(&self, value: &'a T) -> Canonical<&'a T>
+        where T: ?Sized + Serialize
+    {
+        Canonical { value: value, canonical_floats: self.canonical_floats }
+    }
+}
+
+impl<C> SerializeSeq for CanonicalCompound<C>
+    where C: SerializeSeq
+{
+    type Ok = C::Ok;
+    type Error = C::Error;
+
+    fn serialize_element<T>(&mut self, value: &T) -> Result<(), C::Error>
+        where T: ?Sized + Serialize
+    {
+        let value = self.wrap(value);
+        self.inner.serialize_element(&value)
+    }
+
+    fn end(self) -> Result<C::Ok, C::Error> {
+        self.inner.end()
+    }
+}
+
+impl<C> SerializeTuple for CanonicalCompound<C>
+    where C: SerializeTuple
+{
+    type Ok = C::Ok;
+    type Error = C::Error;
+
+    fn serialize_element<T>(&mut self, value: &T) -> Result<(), C::Error>
+        where T: ?Sized + Serialize
+    {
+        let value = self.wrap(value);
+        self.inner.serialize_element(&value)
+    }
+
+    fn end(self) -> Result<C::Ok, C::Error> {
+        self.inner.end()
+    }
+}
+
+impl<C> SerializeTupleStruct for CanonicalCompound<C>
+    where C: SerializeTupleStruct
+{
+    type Ok = C::Ok;
+    type Error = C::Error;
+
+    fn serialize_field<T>(&mut self, value: &T) -> Result<(), C::Error>
+        where T: ?Sized + Serialize
+    {
+        let value = self.wrap(value);
+        self.inner.serialize_field(&value)
+    }
+
+    fn end(self) -> Result<C::Ok, C::Error> {
+        self.inner.end()
+    }
+}
+
+impl<C> SerializeTupleVariant for CanonicalCompound<C>
+    where C: SerializeTupleVariant
+{
+    type Ok = C::Ok;
+    type Error = C::Error;
+
+    fn serialize_field<T>(&mut self, value: &T) -> Result<(), C::Error>
+        where T: ?Sized + Serialize
+    {
+        let value = self.wrap(value);
+        self.inner.serialize_field(&value)
+    }
+
+    fn end(self) -> Result<C::Ok, C::Error> {
+        self.inner.end()
+    }
+}
+
+impl<C> SerializeStruct for CanonicalCompound<C>
+    where C: SerializeStruct
+{
+    type Ok = C::Ok;
+    type Error = C::Error;
+
+    fn serialize_field<T>(&mut self, key: &'static str, value: &T) -> Result<(), C::Error>
+        where T: ?Sized + Serialize
+    {
+        let value = self.wrap(value);
+        self.inner.serialize_field(key, &value)
+    }
+
+    fn skip_field(&mut self, key: &'static str) -> Result<(), C::Error> {
+        self.inner.skip_field(key)
+    }
+
+    fn end(self) -> Result<C::Ok, C::Error> {
+        self.inner.end()
+    }
+}
+
+impl<C> SerializeStructVariant for CanonicalCompound<C>
+    where C: SerializeStructVariant
+{
+    type Ok = C::Ok;
+    type Error = C::Error;
+
+    fn serialize_field<T>(&mut self, key: &'static str, value: &T) -> Result<(), C::Error>
+        where T: ?Sized + Serialize
+    {
+        let value = self.wrap(value);
+        self.inner.serialize_field(key, &value)
+    }
+
+    fn skip_field(&mut self, key: &'static str) -> Result<(), C::Error> {
+        self.inner.skip_field(key)
+    }
+
+    fn end(self) -> Result<C::Ok, C::Error> {
+        self.inner.end()
+    }
+}
+
+///
+/// The [`SerializeMap`] of [`CanonicalSerializer`], which buffers all entries, and passes them
+/// to the inner [`Serializer`] sorted by key.
+///
+pub struct CanonicalMap<S>
+    where S: Serializer
+{
+    serializer: S,
+    content_serializer: ContentSerializer<S::Error>,
+    entries: Vec<(Content, Content)>,
+    next_key: Option<Content>
+}
+
+impl<S> SerializeMap for CanonicalMap<S>
+    where S: Serializer
+{
+    type Ok = S::Ok;
+    type Error = S::Error;
+
+    fn serialize_key<T>(&mut self, key: &T) -> Result<(), S::Error>
+        where T: ?Sized + Serialize
+    {
+        self.next_key = Some(key.serialize(self.content_serializer)?);
+        return Ok(());
+    }
+
+    fn serialize_value<T>(&mut self, value: &T) -> Result<(), S::Error>
+        where T: ?Sized + Serialize
+    {
+        let key = self.next_key.take().ok_or_else(|| Error::custom("serialize_value() called before serialize_key()"))?;
+        self.entries.push((key, value.serialize(self.content_serializer)?));
+        return Ok(());
+    }
+
+    fn end(mut self) -> Result<S::Ok, S::Error> {
+        self.entries.sort_by(|(lhs, _), (rhs, _)| lhs.cmp(rhs));
+        let mut map = self.serializer.serialize_map(Some(self.entries.len()))?;
+        for (key, value) in &self.entries {
+            map.serialize_entry(key, value)?;
+        }
+        return map.end();
+    }
+}
+
+///
+/// A buffered value of the serde data model, as produced by [`ContentSerializer`]. In contrast to
+/// [`crate::mock::MockValue`], this keeps all names and types, so it can be passed on to another
+/// [`Serializer`] without changing the output. Floats are stored as bits, which makes the
+/// derived order total.
+///
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub(crate) enum Content {
+    Bool(bool),
+    I8(i8),
+    I16(i16),
+    I32(i32),
+    I64(i64),
+    I128(i128),
+    U8(u8),
+    U16(u16),
+    U32(u32),
+    U64(u64),
+    U128(u128),
+    F32(u32),
+    F64(u64),
+    Char(char),
+    Str(String),
+    Bytes(Vec<u8>),
+    None,
+    Some(Box<Content>),
+    Unit,
+    UnitStruct(&'static str),
+    UnitVariant(&'static str, u32, &'static str),
+    NewtypeStruct(&'static str, Box<Content>),
+    NewtypeVariant(&'static str, u32, &'static str, Box<Content>),
+    Seq(Vec<Content>),
+    Tuple(Vec<Content>),
+    TupleStruct(&'static str, Vec<Content>),
+    TupleVariant(&'static str, u32, &'static str, Vec<Content>),
+    Map(Vec<(Content, Content)>),
+    Struct(&'static str, Vec<(&'static str, Content)>),
+    StructVariant(&'static str, u32, &'static str, Vec<(&'static str, Content)>)
+}
+
+impl Serialize for Content {
+
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+        where S: Serializer
+    {
+        fn serialize_fields<C>(mut result: C, fields: &[(&'static str, Content)]) -> Result<C::Ok, C::Error>
+            where C: SerializeStruct
+        {
+            for (key, value) in fields {
+                result.serialize_field(key, value)?;
+            }
+            return result.end();
+        }
+        match self {
+            Content::Bool(v) => serializer.serialize_bool(*v),
+            Content::I8(v) => serializer.serialize_i8(*v),
+            Content::I16(v) => serializer.serialize_i16(*v),
+            Content::I32(v) => serializer.serialize_i32(*v),
+            Content::I64(v) => serializer.serialize_i64(*v),
+            Content::I128(v) => serializer.serialize_i128(*v),
+            Content::U8(v) => serializer.serialize_u8(*v),
+            Content::U16(v) => serializer.serialize_u16(*v),
+            Content::U32(v) => serializer.serialize_u32(*v),
+            Content::U64(v) => serializer.serialize_u64(*v),
+            Content::U128(v) => serializer.serialize_u128(*v),
+            Content::F32(v) => serializer.serialize_f32(f32::from_bits(*v)),
+            Content::F64(v) => serializer.serialize_f64(f64::from_bits(*v)),
+            Content::Char(v) => serializer.serialize_char(*v),
+            Content::Str(v) => serializer.serialize_str(v),
+            Content::Bytes(v) => serializer.serialize_bytes(v),
+            Content::None => serializer.serialize_none(),
+            Content::Some(v) => serializer.serialize_some(&**v),
+            Content::Unit => serializer.serialize_unit(),
+            Content::UnitStruct(name) => serializer.serialize_unit_struct(name),
+            Content::UnitVariant(name, variant_index, variant) => serializer.serialize_unit_variant(name, *variant_index, variant),
+            Content::NewtypeStruct(name, v) => serializer.serialize_newtype_struct(name, &**v),
+            Content::NewtypeVariant(name, variant_index, variant, v) => serializer.serialize_newtype_variant(name, *variant_index, variant, &**v),
+            Content::Seq(elements) => serializer.collect_seq(elements),
+            Content::Tuple(elements) => {
+                let mut result = serializer.serialize_tuple(elements.len())?;
+                for element in elements {
+                    result.serialize_element(element)?;
+                }
+                result.end()
+            },
+            Content::TupleStruct(name, elements) => {
+                let mut result = serializer.serialize_tuple_struct(name, elements.len())?;
+                for element in elements {
+                    result.serialize_field(element)?;
+                }
+                result.end()
+            },
+            Content::TupleVariant(name, variant_index, variant, elements) => {
+                let mut result = serializer.serialize_tuple_variant(name, *variant_index, variant, elements.len())?;
+                for element in elements {
+                    result.serialize_field(element)?;
+                }
+                result.end()
+            },
+            Content::Map(entries) => serializer.collect_map(entries.iter().map(|(k, v)| (k, v))),
+            Content::Struct(name, fields) => serialize_fields(serializer.serialize_struct(name, fields.len())?, fields),
+            Content::StructVariant(name, variant_index, variant, fields) => {
+                let mut result = serializer.serialize_struct_variant(name, *variant_index, variant, fields.len())?;
+                for (key, value) in fields {
+                    result.serialize_field(key, value)?;
+                }
+                result.end()
+            }
+        }
+    }
+}
+
+///
+/// A [`Serializer`] that produces [`Content`], which is canonical if `sort_maps` and
+/// `canonical_floats` are set.
+///
+pub(crate) struct ContentSerializer<E> {
+    is_human_readable: bool,
+    canonical_floats: bool,
+    sort_maps: bool,
+    error: PhantomData<E>
+}
+
+impl<E> ContentSerializer<E> {
+
+    ///
+    /// Creates a [`ContentSerializer`] that keeps the order of map entries and all floats.
+    ///
+    pub(crate) fn new(is_human_readable: bool) -> Self {
+        Self { is_human_readable: is_human_readable, canonical_floats: false, sort_maps: false, error: PhantomData }
+    }
+}
+
+impl<E> Clone for ContentSerializer<E> {
+
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<E> Copy for ContentSerializer<E> {}
+
+impl<E> Serializer for ContentSerializer<E>
+    where E: Error
+{
+    type Ok = Content;
+    type Error = E;
+    type SerializeSeq = SerializeContent<E>;
+    type SerializeTuple = SerializeContent<E>;
+    type SerializeTupleStruct = SerializeContent<E>;
+    type SerializeTupleVariant = SerializeContent<E>;
+    type SerializeMap = SerializeContent<E>;
+    type SerializeStruct = SerializeContent<E>;
+    type SerializeStructVariant = SerializeContent<E>;
+
+    fn serialize_bool(self, v: bool) -> Result<Content, E> { Ok(Content::Bool(v)) }
+    fn serialize_i8(self, v: i8) -> Result<Content, E> { Ok(Content::I8(v)) }
+    fn serialize_i16(self, v: i16) -> Result<Content, E> { Ok(Content::I16(v)) }
+    fn serialize_i32(self, v: i32) -> Result<Content, E> { Ok(Content::I32(v)) }
+    fn serialize_i64(self, v: i64) -> Result<Content, E> { Ok(Content::I64(v)) }
+    fn serialize_i128(self, v: i128) -> Result<Content, E> { Ok(Content::I128(v)) }
+    fn serialize_u8(self, v: u8) -> Result<Content, E> { Ok(Content::U8(v)) }
+    fn serialize_u16(self, v: u16) -> Result<Content, E> { Ok(Content::U16(v)) }
+    fn serialize_u32(self, v: u32) -> Result<Content, E> { Ok(Content::U32(v)) }
+    fn serialize_u64(self, v: u64) -> Result<Content, E> { Ok(Content::U64(v)) }
+    fn serialize_u128(self, v: u128) -> Result<Content, E> { Ok(Content::U128(v)) }
+    fn serialize_f32(self, v: f32) -> Result<Content, E> { Ok(Content::F32(canonical_f32(v, self.canonical_floats).to_bits())) }
+    fn serialize_f64(self, v: f64) -> Result<Content, E> { Ok(Content::F64(canonical_f64(v, self.canonical_floats).to_bits())) }
+    fn serialize_char(self, v: char) -> Result<Content, E> { Ok(Content::Char(v)) }
+    fn serialize_str(self, v: &str) -> Result<Content, E> { Ok(Content::Str(v.to_owned())) }
+    fn serialize_bytes(self, v: &[u8]) -> Result<Content, E> { Ok(Content::Bytes(v.to_owned())) }
+    fn serialize_none(self) -> Result<Content, E> { Ok(Content::None) }
+    fn serialize_unit(self) -> Result<Content, E> { Ok(Content::Unit) }
+    fn serialize_unit_struct(self, name: &'static str) -> Result<Content, E> { Ok(Content::UnitStruct(name)) }
+
+    fn serialize_some<T>(self, value: &T) -> Result<Content, E>
+        where T: ?Sized + Serialize
+    {
+        Ok(Content::Some(Box::new(value.serialize(self)?)))
+    }
+
+    fn serialize_unit_variant(self, name: &'static str, variant_index: u32, variant: &'static str) -> Result<Content, E> {
+        Ok(Content::UnitVariant(name, variant_index, variant))
+    }
+
+    fn serialize_newtype_struct<T>(self, name: &'static str, value: &T) -> Result<Content, E>
+        where T: ?Sized + Serialize
+    {
+        Ok(Content::NewtypeStruct(name, Box::new(value.serialize(self)?)))
+    }
+
+    fn serialize_newtype_variant<T>(self, name: &'static str, variant_index: u32, variant: &'static str, value: &T) -> Result<Content, E>
+        where T: ?Sized + Serialize
+    {
+        Ok(Content::NewtypeVariant(name, variant_index, variant, Box::new(value.serialize(self)?)))
+    }
+
+    fn serialize_seq(self, _len: Option<usize>) -> Result<SerializeContent<E>, E> {
+        Ok(SerializeContent::new(self, Content::Seq(Vec::new())))
+    }
+
+    fn serialize_tuple(self, _len: usize) -> Result<SerializeContent<E>, E> {
+        Ok(SerializeContent::new(self, Content::Tuple(Vec::new())))
+    }
+
+    fn serialize_tuple_struct(self, name: &'static str, _len: usize) -> Result<SerializeContent<E>, E> {
+        Ok(SerializeContent::new(self, Content::TupleStruct(name, Vec::new())))
+    }
+
+    fn serialize_tuple_variant(self, name: &'static str, variant_index: u32, variant: &'static str, _len: usize) -> Result<SerializeContent<E>, E> {
+        Ok(SerializeContent::new(self, Content::TupleVariant(name, variant_index, variant, Vec::new())))
+    }
+
+    fn serialize_map(self, _len: Option<usize>) -> Result<SerializeContent<E>, E> {
+        Ok(SerializeContent::new(self, Content::Map(Vec::new())))
+    }
+
+    fn serialize_struct(self, name: &'static str, _len: usize) -> Result<SerializeContent<E>, E> {
+        Ok(SerializeContent::new(self, Content::Struct(name, Vec::new())))
+    }
+
+    fn serialize_struct_variant(self, name: &'static str, variant_index: u32, variant: &'static str, _len: usize) -> Result<SerializeContent<E>, E> {
+        Ok(SerializeContent::new(self, Content::StructVariant(name, variant_index, variant, Vec::new())))
+    }
+
+    fn is_human_readable(&self) -> bool {
+        self.is_human_readable
+    }
+}
+
+///
+/// The compound serializer of [`ContentSerializer`], which adds elements, fields or entries
+/// to the (initially empty) compound [`Content`].
+///
+pub(crate) struct SerializeContent<E> {
+    serializer: ContentSerializer<E>,
+    content: Content,
+    next_key: Option<Content>
+}
+
+impl<E> SerializeContent<E>
+    where E: Error
+{
+    fn new(serializer: ContentSerializer<E>, content: Content) -> Self {
+        Self { serializer: serializer, content: content, next_key: None }
+    }
+
+    fn push_element<T>(&mut self, value: &T) -> Result<(), E>
+        where T: ?Sized + Serialize
+    {
+        let value = value.serialize(self.serializer)?;
+        match &mut self.content {
+            Content::Seq(elements) | Content::Tuple(elements) | Content::TupleStruct(_, elements) | Content::TupleVariant(_, _, _, elements) => elements.push(value),
+            _ => unreachable!()
+        }
+        return Ok(());
+    }
+
+    fn push_field<T>(&mut self, key: &'static str, value: &T) -> Result<(), E>
+        where T: ?Sized + Serialize
+    {
+        let value = value.serialize(self.serializer)?;
+        match &mut self.content {
+            Content::Struct(_, fields) | Content::StructVariant(_, _, _, fields) => fields.push((key, value)),
+            _ => unreachable!()
+        }
+        return Ok(());
+    }
+
+    fn finish(mut self) -> Result<Content, E> {
+        if let (true, Content::Map(entries)) = (self.serializer.sort_maps, &mut self.content) {
+            entries.sort_by(|(lhs, _), (rhs, _)| lhs.cmp(rhs));
+        }
+        return Ok(self.content);
+    }
+}
+
+impl<E> SerializeSeq for SerializeContent<E>
+    where E: Error
+{
+    type Ok = Content;
+    type Error = E;
+
+    fn serialize_element<T>(&mut self, value: &T) -> Result<(), E>
+        where T: ?Sized + Serialize
+    {
+        self.push_element(value)
+    }
+
+    fn end(self) -> Result<Content, E> {
+        self.finish()
+    }
+}
+
+impl<E> SerializeTuple for SerializeContent<E>
+    where E: Error
+{
+    type Ok = Content;
+    type Error = E;
+
+    fn serialize_element<T>(&mut self, value: &T) -> Result<(), E>
+        where T: ?Sized + Serialize
+    {
+        self.push_element(value)
+    }
+
+    fn end(self) -> Result<Content, E> {
+        self.finish()
+    }
+}
+
+impl<E> SerializeTupleStruct for SerializeContent<E>
+    where E: Error
+{
+    type Ok = Content;
+    type Error = E;
+
+    fn serialize_field<T>(&mut self, value: &T) -> Result<(), E>
+        where T: ?Sized + Serialize
+    {
+        self.push_element(value)
+    }
+
+    fn end(self) -> Result<Content, E> {
+        self.finish()
+    }
+}
+
+impl<E> SerializeTupleVariant for SerializeContent<E>
+    where E: Error
+{
+    type Ok = Content;
+    type Error = E;
+
+    fn serialize_field<T>(&mut self, value: &T) -> Result<(), E>
+        where T: ?Sized + Serialize
+    {
+        self.push_element(value)
+    }
+
+    fn end(self) -> Result<Content, E> {
+        self.finish()
+    }
+}
+
+impl<E> SerializeMap for SerializeContent<E>
+    where E: Error
+{
+    type Ok = Content;
+    type Error = E;
+
+    fn serialize_key<T>(&mut self, key: &T) -> Result<(), E>
+        where T: ?Sized + Serialize
+    {
+        self.next_key = Some(key.serialize(self.serializer)?);
+        return Ok(());
+    }
+
+    fn serialize_value<T>(&mut self, value: &T) -> Result<(), E>
+        where T: ?Sized + Serialize
+    {
+        let key = self.next_key.take().ok_or_else(|| Error::custom("serialize_value() called before serialize_key()"))?;
+        let value = value.serialize(self.serializer)?;
+        match &mut self.content {
+            Content::Map(entries) => entries.push((key, value)),
+            _ => unreachable!()
+        }
+        return Ok(());
+    }
+
+    fn end(self) -> Result<Content, E> {
+        self.finish()
+    }
+}
+
+impl<E> SerializeStruct for SerializeContent<E>
+    where E: Error
+{
+    type Ok = Content;
+    type Error = E;
+
+    fn serialize_field<T>(&mut self, key: &'static str, value: &T) -> Result<(), E>
+        where T: ?Sized + Serialize
+    {
+        self.push_field(key, value)
+    }
+
+    fn end(self) -> Result<Content, E> {
+        self.finish()
+    }
+}
+
+impl<E> SerializeStructVariant for SerializeContent<E>
+    where E: Error
+{
+    type Ok = Content;
+    type Error = E;
+
+    fn serialize_field<T>(&mut self, key: &'static str, value: &T) -> Result<(), E>
+        where T: ?Sized + Serialize
+    {
+        self.push_field(key, value)
+    }
+
+    fn end(self) -> Result<Content, E> {
+        self.finish()
+    }
+}
+
+#[cfg(test)]
+use std::collections::{BTreeMap, HashMap};
+#[cfg(test)]
+use crate::trace::trace_serialize;
+
+#[test]
+fn test_canonical_maps() {
+    #[derive(serde::Serialize)]
+    struct Foo<M1, M2> {
+        b: M1,
+        a: Option<M2>
+    }
+    let value = Foo {
+        b: (0..50).map(|i| (format!("key{}", i), vec![i as f64])).collect::<HashMap<_, _>>(),
+        a: Some((0..50).map(|i| ((i % 7, char::from(b'a' + i)), ())).collect::<HashMap<_, _>>())
+    };
+    let expected = Foo {
+        b: value.b.clone().into_iter().collect::<BTreeMap<_, _>>(),
+        a: value.a.clone().map(|a| a.into_iter().collect::<BTreeMap<_, _>>())
+    };
+    assert_eq!(trace_serialize(&expected).unwrap(), trace_serialize(&Canonical::new(&value)).unwrap());
+    assert_eq!(postcard::to_allocvec(&expected).unwrap(), postcard::to_allocvec(&Canonical::new(&value)).unwrap());
+}
+
+#[test]
+fn test_canonical_floats() {
+    let value = vec![-0.0f64, f64::from_bits(f64::NAN.to_bits() ^ 1), 1.5];
+    let canonical = trace_serialize(&Canonical::new(&value).with_canonical_floats(true)).unwrap();
+    assert_eq!(vec!["serialize_seq(Some(3))", "serialize_element", "serialize_f64(0.0)", "serialize_element", "serialize_f64(NaN)", "serialize_element", "serialize_f64(1.5)", "end"], canonical);
+    assert_eq!(
+        postcard::to_allocvec(&Canonical::new(&value).with_canonical_floats(true)).unwrap(),
+        postcard::to_allocvec(&vec![0.0, f64::NAN, 1.5]).unwrap()
+    );
+    assert_eq!(postcard::to_allocvec(&value).unwrap(), postcard::to_allocvec(&Canonical::new(&value)).unwrap());
+
+    let map = HashMap::from([(1u32, -0.0f32), (0, 2.0)]);
+    assert_eq!("{\"0\":2.0,\"1\":0.0}", serde_json::to_string(&Canonical::new(&map).with_canonical_floats(true)).unwrap());
+}
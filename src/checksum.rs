@@ -0,0 +1,172 @@
+use serde::de::{DeserializeSeed, Error, SeqAccess, Visitor};
+use serde::ser::{Serialize, SerializeTuple, Serializer};
+use serde::Deserializer;
+
+use crate::raw_bytes::{Bytes, ByteBufSeed};
+use crate::into_seed::IntoSeed;
+
+///
+/// Computes the CRC-32 (as used by e.g. zlib and PNG) of the given data.
+///
+pub fn crc32(data: &[u8]) -> u32 {
+    const TABLE: [u32; 256] = {
+        let mut table = [0; 256];
+        let mut i = 0;
+        while i < 256 {
+            let mut crc = i as u32;
+            let mut j = 0;
+            while j < 8 {
+                crc = if crc & 1 == 1 { (crc >> 1) ^ 0xEDB88320 } else { crc >> 1 };
+                j += 1;
+            }
+            table[i] = crc;
+            i += 1;
+        }
+        table
+    };
+    let mut crc = !0u32;
+    for byte in data {
+        crc = TABLE[((crc ^ *byte as u32) & 0xFF) as usize] ^ (crc >> 8);
+    }
+    return !crc;
+}
+
+///
+/// Wraps a serializable value, and implements [`Serialize`] by encoding it with postcard,
+/// and mapping it to the tuple `(checksum, payload)` in the serde data model, where `payload`
+/// is the encoded value as a byte buffer, and `checksum` is its [`crc32()`].
+///
+/// Use [`DeserializeSeedChecksummed`] to deserialize it, which fails if the payload was
+/// corrupted, instead of silently returning a wrong value.
+///
+/// # Example
+/// ```
+/// # use feanor_serde::checksum::*;
+/// # use feanor_serde::default_seed::seed_of;
+/// let mut serialized = postcard::to_allocvec(&SerializableChecksummed::new(vec![1i64, 2, 3])).unwrap();
+/// assert_eq!(vec![1, 2, 3], feanor_serde::postcard::from_slice_seeded(DeserializeSeedChecksummed::new(seed_of::<Vec<i64>>()), &serialized).unwrap());
+///
+/// *serialized.last_mut().unwrap() ^= 1;
+/// assert!(feanor_serde::postcard::from_slice_seeded(DeserializeSeedChecksummed::new(seed_of::<Vec<i64>>()), &serialized).is_err());
+/// ```
+///
+pub struct SerializableChecksummed<T>
+    where T: Serialize
+{
+    data: T
+}
+
+impl<T> SerializableChecksummed<T>
+    where T: Serialize
+{
+    pub const fn new(data: T) -> Self {
+        Self { data }
+    }
+}
+
+impl<T> Serialize for SerializableChecksummed<T>
+    where T: Serialize
+{
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+        where S: Serializer
+    {
+        let payload = ::postcard::to_allocvec(&self.data).map_err(serde::ser::Error::custom)?;
+        let mut tuple = serializer.serialize_tuple(2)?;
+        tuple.serialize_element(&crc32(&payload))?;
+        tuple.serialize_element(&Bytes(&payload))?;
+        return tuple.end();
+    }
+}
+
+///
+/// A [`DeserializeSeed`] that deserializes a value serialized using [`SerializableChecksummed`].
+/// The checksum is verified before the payload is passed to the wrapped [`DeserializeSeed`],
+/// and deserialization fails if it does not match.
+///
+/// Since the payload might not be borrowed from the input, the wrapped seed must be able to
+/// deserialize from data of any lifetime.
+///
+pub struct DeserializeSeedChecksummed<S> {
+    seed: S
+}
+
+impl<S> DeserializeSeedChecksummed<S> {
+
+    pub fn new<'de, I, M>(seed: I) -> Self
+        where I: IntoSeed<'de, M, Seed = S>
+    {
+        Self { seed: seed.into_seed() }
+    }
+}
+
+impl<'de, S, T> DeserializeSeed<'de> for DeserializeSeedChecksummed<S>
+    where S: for<'a> DeserializeSeed<'a, Value = T>
+{
+    type Value = T;
+
+    fn deserialize<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+        where D: Deserializer<'de>
+    {
+        return deserializer.deserialize_tuple(2, self);
+    }
+}
+
+impl<'de, S, T> Visitor<'de> for DeserializeSeedChecksummed<S>
+    where S: for<'a> DeserializeSeed<'a, Value = T>
+{
+    type Value = T;
+
+    fn expecting(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "a checksum and a byte buffer")
+    }
+
+    fn visit_seq<A>(self, mut seq: A) -> Result<Self::Value, A::Error>
+        where A: SeqAccess<'de>
+    {
+        let checksum: u32 = seq.next_element()?.ok_or_else(|| A::Error::invalid_length(0, &self))?;
+        let payload = seq.next_element_seed(ByteBufSeed)?.ok_or_else(|| A::Error::invalid_length(1, &self))?;
+        let actual = crc32(&payload);
+        if actual != checksum {
+            return Err(A::Error::custom(format!("checksum mismatch: expected {:08x}, but payload has checksum {:08x}", checksum, actual)));
+        }
+        let (result, rest) = crate::postcard::take_from_slice_seeded(self.seed, &payload).map_err(A::Error::custom)?;
+        if !rest.is_empty() {
+            return Err(A::Error::custom(format!("payload has {} trailing bytes", rest.len())));
+        }
+        return Ok(result);
+    }
+}
+
+#[cfg(test)]
+use std::marker::PhantomData;
+
+#[test]
+fn test_crc32() {
+    assert_eq!(0, crc32(b""));
+    assert_eq!(0xCBF43926, crc32(b"123456789"));
+}
+
+#[test]
+fn test_serde_postcard() {
+    let data = (0..100).map(|i| (i, i.to_string())).collect::<Vec<(i64, String)>>();
+    let serialized = postcard::to_allocvec(&SerializableChecksummed::new(&data)).unwrap();
+    assert_eq!(data, crate::postcard::from_slice_seeded(DeserializeSeedChecksummed::new(PhantomData::<Vec<(i64, String)>>), &serialized).unwrap());
+
+    for i in 0..serialized.len() {
+        let mut corrupted = serialized.clone();
+        corrupted[i] ^= 0x10;
+        assert!(crate::postcard::from_slice_seeded(DeserializeSeedChecksummed::new(PhantomData::<Vec<(i64, String)>>), &corrupted).is_err());
+    }
+}
+
+#[test]
+fn test_serde_json() {
+    let seed = || DeserializeSeedChecksummed::new(PhantomData::<Vec<u8>>);
+    let serialized = serde_json::to_string(&SerializableChecksummed::new(vec![1u8, 2, 3])).unwrap();
+    assert_eq!(vec![1, 2, 3], seed().deserialize(&mut serde_json::Deserializer::from_str(&serialized)).unwrap());
+
+    let payload = postcard::to_allocvec(&vec![1u8, 2, 3]).unwrap();
+    let corrupted = serde_json::to_string(&(crc32(&payload) ^ 1, &payload)).unwrap();
+    let error = seed().deserialize(&mut serde_json::Deserializer::from_str(&corrupted)).unwrap_err();
+    assert!(error.to_string().contains("checksum mismatch"));
+}
@@ -0,0 +1,74 @@
+use std::fmt::Write;
+
+use ::chrono::{DateTime, NaiveDate, NaiveDateTime, Utc};
+
+use crate::timestamp::Timestamp;
+
+///
+/// Custom formats are `strftime`-like strings as described in [`chrono::format::strftime`].
+/// When parsing, the offset may be omitted, in which case the timestamp is taken to be in UTC,
+/// and the time may be omitted, in which case it is taken to be midnight.
+///
+impl Timestamp for DateTime<Utc> {
+
+    fn from_epoch_seconds(seconds: i64) -> Option<Self> {
+        DateTime::from_timestamp(seconds, 0)
+    }
+
+    fn epoch_seconds(&self) -> Option<i64> {
+        Some(self.timestamp())
+    }
+
+    fn from_epoch_millis(millis: i64) -> Option<Self> {
+        DateTime::from_timestamp_millis(millis)
+    }
+
+    fn epoch_millis(&self) -> Option<i64> {
+        Some(self.timestamp_millis())
+    }
+
+    fn parse_rfc3339(value: &str) -> Option<Self> {
+        DateTime::parse_from_rfc3339(value).ok().map(|timestamp| timestamp.with_timezone(&Utc))
+    }
+
+    fn to_rfc3339(&self) -> Option<String> {
+        Some(DateTime::to_rfc3339(self))
+    }
+
+    fn parse_custom(value: &str, format: &str) -> Option<Self> {
+        DateTime::parse_from_str(value, format).ok().map(|timestamp| timestamp.with_timezone(&Utc))
+            .or_else(|| NaiveDateTime::parse_from_str(value, format).ok().map(|timestamp| timestamp.and_utc()))
+            .or_else(|| NaiveDate::parse_from_str(value, format).ok().map(|date| date.and_time(Default::default()).and_utc()))
+    }
+
+    fn format_custom(&self, format: &str) -> Option<String> {
+        let mut result = String::new();
+        write!(result, "{}", self.format(format)).ok()?;
+        return Some(result);
+    }
+}
+
+#[cfg(test)]
+use crate::timestamp::{SerializableTimestamp, DeserializeSeedTimestamp, TimestampFormat};
+#[cfg(test)]
+use serde::de::DeserializeSeed;
+
+#[test]
+fn test_serde_json() {
+    let timestamp = DateTime::from_timestamp_millis(-1500).unwrap();
+    let formats = [TimestampFormat::EpochSeconds, TimestampFormat::EpochMillis, TimestampFormat::Rfc3339, TimestampFormat::Custom("%Y-%m-%d %H:%M:%S%.3f")];
+    let expected = ["-2", "-1500", "\"1969-12-31T23:59:58.500+00:00\"", "\"1969-12-31 23:59:58.500\""];
+    for (format, expected) in formats.into_iter().zip(expected) {
+        assert_eq!(expected, serde_json::to_string(&SerializableTimestamp::new(&timestamp, format)).unwrap());
+        let result: DateTime<Utc> = DeserializeSeedTimestamp::new(format).deserialize(&mut serde_json::Deserializer::from_str(expected)).unwrap();
+        if format == TimestampFormat::EpochSeconds {
+            assert_eq!(DateTime::from_timestamp(-2, 0).unwrap(), result);
+        } else {
+            assert_eq!(timestamp, result);
+        }
+    }
+    let seed = DeserializeSeedTimestamp::<DateTime<Utc>>::new(TimestampFormat::Rfc3339);
+    assert_eq!(DateTime::from_timestamp(0, 0).unwrap(), seed.deserialize(&mut serde_json::Deserializer::from_str("\"1970-01-01T01:00:00+01:00\"")).unwrap());
+    assert!(seed.deserialize(&mut serde_json::Deserializer::from_str("\"1970-01-01\"")).is_err());
+    assert!(serde_json::to_string(&SerializableTimestamp::new(&timestamp, TimestampFormat::Custom("%Q"))).is_err());
+}
@@ -0,0 +1,340 @@
+use std::iter::repeat;
+use std::num::NonZeroUsize;
+use std::sync::{Mutex, PoisonError};
+use std::thread;
+
+use serde::de::DeserializeSeed;
+use serde::ser::{Error, Serialize, SerializeSeq, Serializer};
+
+use crate::progress::Progress;
+use crate::raw_bytes::Bytes;
+use crate::seq::{DeserializeSeedSeq, SerializableSeq};
+
+///
+/// Wraps an [`Iterator`] over serializable elements, and implements [`Serialize`]
+/// by splitting it into chunks of (at most) `chunk_size` elements, each of which
+/// is independently encoded with postcard. The result is a sequence of byte buffers
+/// in the serde data model, one per chunk.
+///
+/// Since the chunks are length-prefixed and independent, they can be deserialized
+/// in parallel using [`deserialize_chunked_parallel()`], which uses scoped threads of
+/// the standard library, or, if the feature `rayon` is enabled, using
+/// `deserialize_chunked_rayon()` on a rayon thread pool.
+///
+pub struct SerializableChunked<I>
+    where I: Iterator + Clone
+{
+    chunk_size: usize,
+    data: I
+}
+
+impl<I> SerializableChunked<I>
+    where I: Iterator + Clone
+{
+    pub fn new(chunk_size: usize, data: I) -> Self {
+        assert!(chunk_size > 0);
+        Self { chunk_size, data }
+    }
+}
+
+impl<I> Serialize for SerializableChunked<I>
+    where I: Iterator + Clone,
+        I::Item: Serialize
+{
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+        where S: Serializer
+    {
+        let chunk_count = self.data.clone().count().div_ceil(self.chunk_size);
+        let mut seq = serializer.serialize_seq(Some(chunk_count))?;
+        let mut data = self.data.clone();
+        for _ in 0..chunk_count {
+            let chunk = data.by_ref().take(self.chunk_size).collect::<Vec<_>>();
+            let encoded = ::postcard::to_allocvec(&SerializableSeq::new_with_len(chunk.iter(), chunk.len())).map_err(S::Error::custom)?;
+            seq.serialize_element(&Bytes(&encoded))?;
+        }
+        return seq.end();
+    }
+}
+
+///
+/// Deserializes a sequence that was serialized with postcard using [`SerializableChunked`],
+/// by deserializing the chunks in parallel on all available cores. Each element is
+/// deserialized using a clone of the given [`DeserializeSeed`], and the elements are
+/// returned in their original order.
+///
+/// # Example
+/// ```
+/// # use feanor_serde::chunked::*;
+/// # use feanor_serde::default_seed::seed_of;
+/// let data = (0..1000).collect::<Vec<i64>>();
+/// let serialized = postcard::to_allocvec(&SerializableChunked::new(100, data.iter())).unwrap();
+/// assert_eq!(data, deserialize_chunked_parallel(seed_of::<i64>(), &serialized).unwrap());
+/// ```
+///
+pub fn deserialize_chunked_parallel<S, T>(seed: S, data: &[u8]) -> ::postcard::Result<Vec<T>>
+    where S: for<'de> DeserializeSeed<'de, Value = T> + Clone + Send,
+        T: Send
+{
+    let threads = thread::available_parallelism().map(NonZeroUsize::get).unwrap_or(1);
+    return deserialize_chunked_with_threads(seed, data, threads);
+}
+
+///
+/// Same as [`deserialize_chunked_parallel()`], but uses at most the given number of
+/// threads.
+///
+pub fn deserialize_chunked_with_threads<S, T>(seed: S, data: &[u8], threads: usize) -> ::postcard::Result<Vec<T>>
+    where S: for<'de> DeserializeSeed<'de, Value = T> + Clone + Send,
+        T: Send
+{
+    deserialize_chunked_with_progress(seed, data, threads, |_| {})
+}
+
+///
+/// Same as [`deserialize_chunked_with_threads()`], but calls the given callback whenever
+/// a chunk has been deserialized. The reported [`Progress`] contains the number of elements
+/// and the number of bytes deserialized so far, as well as the total number of bytes of
+/// all chunks. The total number of elements is not known in advance.
+///
+/// Chunks may finish in any order, but the callback is never called concurrently, and the
+/// reported numbers are increasing.
+///
+/// # Example
+/// ```
+/// # use feanor_serde::chunked::*;
+/// # use feanor_serde::default_seed::seed_of;
+/// let data = (0..1000).collect::<Vec<i64>>();
+/// let serialized = postcard::to_allocvec(&SerializableChunked::new(100, data.iter())).unwrap();
+/// let mut reported = Vec::new();
+/// let result = deserialize_chunked_with_progress(seed_of::<i64>(), &serialized, 4, |progress| reported.push(progress.elements())).unwrap();
+/// assert_eq!(data, result);
+/// assert_eq!((1..=10).map(|i| i * 100).collect::<Vec<_>>(), reported);
+/// ```
+///
+pub fn deserialize_chunked_with_progress<S, T, F>(seed: S, data: &[u8], threads: usize, callback: F) -> ::postcard::Result<Vec<T>>
+    where S: for<'de> DeserializeSeed<'de, Value = T> + Clone + Send,
+        T: Send,
+        F: FnMut(Progress) + Send
+{
+    assert!(threads > 0);
+    let chunks: Vec<&[u8]> = ::postcard::from_bytes(data)?;
+    if chunks.is_empty() {
+        return Ok(Vec::new());
+    }
+    let chunks_per_thread = chunks.len().div_ceil(threads);
+    let total_bytes = chunks.iter().map(|chunk| chunk.len()).sum::<usize>();
+
+    let progress = Mutex::new((callback, 0, 0));
+    let report = |elements: usize, bytes: usize| {
+        let mut progress = progress.lock().unwrap_or_else(PoisonError::into_inner);
+        let (callback, elements_read, bytes_read) = &mut *progress;
+        *elements_read += elements;
+        *bytes_read += bytes;
+        callback(Progress::new(*elements_read, None, Some((*bytes_read, total_bytes))));
+    };
+
+    let deserialize_chunk = |seed: S, chunk: &[u8]| {
+        let result: Vec<T> = crate::postcard::from_slice_seeded(
+            DeserializeSeedSeq::new(repeat(seed), Vec::new(), |mut current, next| { current.push(next); current }),
+            chunk
+        )?;
+        report(result.len(), chunk.len());
+        return Ok(result);
+    };
+
+    let results = thread::scope(|scope| {
+        let handles = chunks.chunks(chunks_per_thread).map(|thread_chunks| {
+            let seed = seed.clone();
+            scope.spawn(move || thread_chunks.iter().map(|chunk| deserialize_chunk(seed.clone(), chunk)).collect::<::postcard::Result<Vec<_>>>())
+        }).collect::<Vec<_>>();
+        handles.into_iter().map(|handle| handle.join().unwrap_or_else(|e| std::panic::resume_unwind(e))).collect::<Vec<_>>()
+    });
+
+    let mut result = Vec::new();
+    for thread_result in results {
+        for chunk in thread_result? {
+            result.extend(chunk);
+        }
+    }
+    return Ok(result);
+}
+
+///
+/// Same as [`deserialize_chunked_parallel()`], but deserializes the chunks using the
+/// current rayon thread pool instead of spawning new threads. To control the number
+/// of threads, call this within [`rayon::ThreadPool::install()`].
+///
+/// # Example
+/// ```
+/// # use feanor_serde::chunked::*;
+/// # use feanor_serde::default_seed::seed_of;
+/// let data = (0..1000).collect::<Vec<i64>>();
+/// let serialized = postcard::to_allocvec(&SerializableChunked::new(100, data.iter())).unwrap();
+/// let pool = rayon::ThreadPoolBuilder::new().num_threads(2).build().unwrap();
+/// assert_eq!(data, pool.install(|| deserialize_chunked_rayon(seed_of::<i64>(), &serialized)).unwrap());
+/// ```
+///
+#[cfg(feature = "rayon")]
+pub fn deserialize_chunked_rayon<S, T>(seed: S, data: &[u8]) -> ::postcard::Result<Vec<T>>
+    where S: for<'de> DeserializeSeed<'de, Value = T> + Clone + Send,
+        T: Send
+{
+    use rayon::prelude::*;
+
+    let chunks: Vec<&[u8]> = ::postcard::from_bytes(data)?;
+    let results = chunks.par_iter().map_with(seed, |seed, chunk| crate::postcard::from_slice_seeded(
+        DeserializeSeedSeq::new(repeat(seed.clone()), Vec::new(), |mut current, next| { current.push(next); current }),
+        chunk
+    )).collect::<::postcard::Result<Vec<Vec<T>>>>()?;
+    return Ok(results.into_iter().flatten().collect());
+}
+
+struct SerializableEncodedChunks<'a> {
+    chunks: &'a [Vec<u8>]
+}
+
+impl<'a> Serialize for SerializableEncodedChunks<'a> {
+
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+        where S: Serializer
+    {
+        let mut seq = serializer.serialize_seq(Some(self.chunks.len()))?;
+        for chunk in self.chunks {
+            seq.serialize_element(&Bytes(chunk))?;
+        }
+        return seq.end();
+    }
+}
+
+///
+/// Serializes the given elements with postcard, in the same format as
+/// [`SerializableChunked`], but encodes the chunks in parallel on all available
+/// cores, before concatenating them into the final output.
+///
+/// # Example
+/// ```
+/// # use feanor_serde::chunked::*;
+/// let data = (0..1000).collect::<Vec<i64>>();
+/// let serialized = serialize_chunked_parallel(100, &data).unwrap();
+/// assert_eq!(postcard::to_allocvec(&SerializableChunked::new(100, data.iter())).unwrap(), serialized);
+/// ```
+///
+pub fn serialize_chunked_parallel<T>(chunk_size: usize, data: &[T]) -> ::postcard::Result<Vec<u8>>
+    where T: Serialize + Sync
+{
+    let threads = thread::available_parallelism().map(NonZeroUsize::get).unwrap_or(1);
+    return serialize_chunked_with_threads(chunk_size, data, threads);
+}
+
+///
+/// Same as [`serialize_chunked_parallel()`], but uses at most the given number of
+/// threads.
+///
+pub fn serialize_chunked_with_threads<T>(chunk_size: usize, data: &[T], threads: usize) -> ::postcard::Result<Vec<u8>>
+    where T: Serialize + Sync
+{
+    assert!(chunk_size > 0);
+    assert!(threads > 0);
+    let chunks = data.chunks(chunk_size).collect::<Vec<_>>();
+    if chunks.is_empty() {
+        return ::postcard::to_allocvec(&SerializableEncodedChunks { chunks: &[] });
+    }
+    let chunks_per_thread = chunks.len().div_ceil(threads);
+
+    let results = thread::scope(|scope| {
+        let handles = chunks.chunks(chunks_per_thread).map(|thread_chunks| {
+            scope.spawn(move || thread_chunks.iter().map(|chunk| ::postcard::to_allocvec(&SerializableSeq::new_with_len(chunk.iter(), chunk.len()))).collect::<::postcard::Result<Vec<_>>>())
+        }).collect::<Vec<_>>();
+        handles.into_iter().map(|handle| handle.join().unwrap_or_else(|e| std::panic::resume_unwind(e))).collect::<Vec<_>>()
+    });
+
+    let mut encoded = Vec::with_capacity(chunks.len());
+    for thread_result in results {
+        encoded.extend(thread_result?);
+    }
+    return ::postcard::to_allocvec(&SerializableEncodedChunks { chunks: &encoded });
+}
+
+#[cfg(test)]
+use std::marker::PhantomData;
+
+#[test]
+fn test_serde_postcard() {
+    for len in [0, 1, 99, 100, 101, 1000] {
+        let data = (0..len).map(|x| x * x - 500).collect::<Vec<i64>>();
+        let serialized = postcard::to_allocvec(&SerializableChunked::new(100, data.iter())).unwrap();
+        for threads in [1, 2, 3, 16] {
+            assert_eq!(data, deserialize_chunked_with_threads(PhantomData::<i64>, &serialized, threads).unwrap());
+        }
+        assert_eq!(data, deserialize_chunked_parallel(PhantomData::<i64>, &serialized).unwrap());
+    }
+}
+
+#[cfg(feature = "rayon")]
+#[test]
+fn test_serde_postcard_rayon() {
+    for len in [0, 1, 99, 100, 101, 1000] {
+        let data = (0..len).map(|x| x * x - 500).collect::<Vec<i64>>();
+        let serialized = postcard::to_allocvec(&SerializableChunked::new(100, data.iter())).unwrap();
+        assert_eq!(data, deserialize_chunked_rayon(PhantomData::<i64>, &serialized).unwrap());
+    }
+    assert!(deserialize_chunked_rayon(PhantomData::<i64>, &[1, 2]).is_err());
+}
+
+#[test]
+fn test_seed_cloned() {
+    #[derive(Clone)]
+    struct DeserializeSeedOffset(i64);
+
+    impl<'de> DeserializeSeed<'de> for DeserializeSeedOffset {
+        type Value = i64;
+
+        fn deserialize<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+            where D: serde::Deserializer<'de>
+        {
+            Ok(<i64 as serde::Deserialize>::deserialize(deserializer)? + self.0)
+        }
+    }
+
+    let serialized = postcard::to_allocvec(&SerializableChunked::new(2, [1, 2, 3, 4, 5].iter())).unwrap();
+    assert_eq!(vec![11, 12, 13, 14, 15], deserialize_chunked_with_threads(DeserializeSeedOffset(10), &serialized, 2).unwrap());
+}
+
+#[test]
+fn test_invalid_chunk() {
+    let serialized = postcard::to_allocvec(&SerializableChunked::new(2, [1, 2, 3].iter())).unwrap();
+    let mut invalid = serialized.clone();
+    // the length prefix of the first chunk claims more elements than are present
+    invalid[2] = 5;
+    assert!(deserialize_chunked_with_threads(PhantomData::<i64>, &invalid, 2).is_err());
+    assert!(deserialize_chunked_with_threads(PhantomData::<i64>, &serialized[..(serialized.len() - 1)], 2).is_err());
+}
+
+#[test]
+fn test_serialize_parallel() {
+    for len in [0, 1, 99, 100, 101, 1000] {
+        let data = (0..len).map(|x| x * x - 500).collect::<Vec<i64>>();
+        let expected = postcard::to_allocvec(&SerializableChunked::new(100, data.iter())).unwrap();
+        for threads in [1, 2, 3, 16] {
+            assert_eq!(expected, serialize_chunked_with_threads(100, &data, threads).unwrap());
+        }
+        let serialized = serialize_chunked_parallel(100, &data).unwrap();
+        assert_eq!(expected, serialized);
+        assert_eq!(data, deserialize_chunked_parallel(PhantomData::<i64>, &serialized).unwrap());
+    }
+}
+
+#[test]
+fn test_progress() {
+    let data = (0..25).collect::<Vec<i64>>();
+    let serialized = postcard::to_allocvec(&SerializableChunked::new(10, data.iter())).unwrap();
+    let mut reported = Vec::new();
+    let result = deserialize_chunked_with_progress(PhantomData::<i64>, &serialized, 2, |progress| reported.push(progress)).unwrap();
+    assert_eq!(data, result);
+    assert_eq!(3, reported.len());
+    let last = reported.last().unwrap();
+    assert_eq!(25, last.elements());
+    assert_eq!(None, last.size_hint());
+    assert_eq!(last.total_bytes(), last.bytes_read());
+    assert!(reported.windows(2).all(|pair| pair[0].elements() < pair[1].elements() && pair[0].bytes_read() < pair[1].bytes_read()));
+}
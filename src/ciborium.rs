@@ -0,0 +1,374 @@
+use serde::de::value::{MapDeserializer, SeqDeserializer};
+use serde::de::{DeserializeSeed, EnumAccess, Error as _, IntoDeserializer, Unexpected, VariantAccess, Visitor};
+use serde::ser::Serialize;
+use serde::{forward_to_deserialize_any, Deserializer};
+
+use ::ciborium::value::{Error, Value};
+
+///
+/// Deserializes a value from the given CBOR-encoded reader, using the given [`DeserializeSeed`].
+///
+/// Since ciborium does not provide a way to use a [`DeserializeSeed`] while reading, the input
+/// is first read into a [`Value`], and then deserialized from it using [`from_value_seeded()`].
+/// See there for how the combinators of this crate behave with CBOR.
+///
+/// Since the [`Value`] is dropped before returning, the result of the seed cannot borrow from it.
+/// For seeds whose result depends on the lifetime of the input, like the structs generated by
+/// [`crate::impl_deserialize_seed_for_dependent_struct!`], use [`from_value_seeded()`] instead.
+///
+/// # Example
+/// ```
+/// # use feanor_serde::seq::*;
+/// # use feanor_serde::default_seed::seed_of;
+/// let serialized = feanor_serde::ciborium::to_vec(SerializableSeq::new([1, 3, 5].into_iter())).unwrap();
+/// let seed = SeqSeedBuilder::new(seed_of::<i64>).collect_vec();
+/// assert_eq!(vec![1, 3, 5], feanor_serde::ciborium::from_reader_seeded(seed, &serialized[..]).unwrap());
+/// ```
+///
+pub fn from_reader_seeded<S, T, R>(seed: S, reader: R) -> Result<T, ::ciborium::de::Error<std::io::Error>>
+    where S: for<'de> DeserializeSeed<'de, Value = T>,
+        R: std::io::Read
+{
+    let value: Value = ::ciborium::from_reader(reader)?;
+    return from_value_seeded(seed, &value).map_err(|Error::Custom(message)| ::ciborium::de::Error::Semantic(None, message));
+}
+
+///
+/// Deserializes a value from the given CBOR [`Value`], using the given [`DeserializeSeed`].
+///
+/// # Compatibility
+///
+/// CBOR is self-describing, so all combinators of this crate are supported. In particular
+///  - sequences and maps may be encoded with indefinite length, so [`crate::seq::SerializableSeq::new()`]
+///    can be used without a length. Since the whole input is read into a [`Value`] first,
+///    [`serde::de::SeqAccess::size_hint()`] still reports the correct length in this case, thus
+///    e.g. [`crate::seq::DeserializeSeedSeq`] can fail early if it does not have enough seeds;
+///  - structs are encoded as maps, whose keys may be arbitrary values. Field identifiers (and
+///    variant identifiers) may be text, bytes or integers, where integers refer to the index
+///    of the field, as for [`crate::impl_deserialize_seed_for_dependent_struct!`]. Structs encoded
+///    as arrays are accepted as well;
+///  - semantic tags are ignored, i.e. a tagged value is deserialized as the value itself.
+///
+/// As ciborium, this deserializer is not human-readable.
+///
+pub fn from_value_seeded<'de, S>(seed: S, value: &'de Value) -> Result<S::Value, Error>
+    where S: DeserializeSeed<'de>
+{
+    seed.deserialize(ValueDeserializer { value: value })
+}
+
+///
+/// Serializes the given value as CBOR into a newly allocated [`Vec`].
+///
+/// This accepts the serializable wrappers of this crate (like [`crate::seq::SerializableSeq`])
+/// by value, so that they can be created inline.
+///
+pub fn to_vec<T>(value: T) -> Result<Vec<u8>, ::ciborium::ser::Error<std::io::Error>>
+    where T: Serialize
+{
+    let mut result = Vec::new();
+    ::ciborium::into_writer(&value, &mut result)?;
+    return Ok(result);
+}
+
+struct ValueDeserializer<'de> {
+    value: &'de Value
+}
+
+impl<'de> ValueDeserializer<'de> {
+
+    fn untagged(&self) -> &'de Value {
+        let mut current = self.value;
+        while let Value::Tag(_, value) = current {
+            current = value;
+        }
+        return current;
+    }
+}
+
+fn unexpected(value: &Value) -> Unexpected<'_> {
+    match value {
+        Value::Integer(_) => Unexpected::Other("integer"),
+        Value::Bytes(value) => Unexpected::Bytes(value),
+        Value::Float(value) => Unexpected::Float(*value),
+        Value::Text(value) => Unexpected::Str(value),
+        Value::Bool(value) => Unexpected::Bool(*value),
+        Value::Null => Unexpected::Unit,
+        Value::Tag(_, _) => Unexpected::Other("tag"),
+        Value::Array(_) => Unexpected::Seq,
+        Value::Map(_) => Unexpected::Map,
+        _ => Unexpected::Other("unknown CBOR value")
+    }
+}
+
+impl<'de> IntoDeserializer<'de, Error> for ValueDeserializer<'de> {
+    type Deserializer = Self;
+
+    fn into_deserializer(self) -> Self {
+        self
+    }
+}
+
+impl<'de> Deserializer<'de> for ValueDeserializer<'de> {
+    type Error = Error;
+
+    fn deserialize_any<V>(self, visitor: V) -> Result<V::Value, Self::Error>
+        where V: Visitor<'de>
+    {
+        match self.untagged() {
+            Value::Integer(value) => {
+                let value = i128::from(*value);
+                if let Ok(value) = u64::try_from(value) {
+                    visitor.visit_u64(value)
+                } else if let Ok(value) = i64::try_from(value) {
+                    visitor.visit_i64(value)
+                } else {
+                    visitor.visit_i128(value)
+                }
+            },
+            Value::Bytes(value) => visitor.visit_borrowed_bytes(value),
+            Value::Float(value) => visitor.visit_f64(*value),
+            Value::Text(value) => visitor.visit_borrowed_str(value),
+            Value::Bool(value) => visitor.visit_bool(*value),
+            Value::Null => visitor.visit_unit(),
+            Value::Array(elements) => {
+                let mut seq = SeqDeserializer::new(elements.iter().map(|value| ValueDeserializer { value: value }));
+                let result = visitor.visit_seq(&mut seq)?;
+                seq.end()?;
+                return Ok(result);
+            },
+            Value::Map(entries) => {
+                let mut map = MapDeserializer::new(entries.iter().map(|(key, value)| (ValueDeserializer { value: key }, ValueDeserializer { value: value })));
+                let result = visitor.visit_map(&mut map)?;
+                map.end()?;
+                return Ok(result);
+            },
+            value => Err(Error::invalid_type(unexpected(value), &visitor))
+        }
+    }
+
+    fn deserialize_option<V>(self, visitor: V) -> Result<V::Value, Self::Error>
+        where V: Visitor<'de>
+    {
+        match self.untagged() {
+            Value::Null => visitor.visit_none(),
+            _ => visitor.visit_some(self)
+        }
+    }
+
+    fn deserialize_newtype_struct<V>(self, _name: &'static str, visitor: V) -> Result<V::Value, Self::Error>
+        where V: Visitor<'de>
+    {
+        visitor.visit_newtype_struct(self)
+    }
+
+    fn deserialize_enum<V>(self, _name: &'static str, _variants: &'static [&'static str], visitor: V) -> Result<V::Value, Self::Error>
+        where V: Visitor<'de>
+    {
+        match self.untagged() {
+            variant @ (Value::Text(_) | Value::Integer(_)) => visitor.visit_enum(ValueEnumAccess { variant: variant, content: None }),
+            Value::Map(entries) if entries.len() == 1 => visitor.visit_enum(ValueEnumAccess { variant: &entries[0].0, content: Some(&entries[0].1) }),
+            value => Err(Error::invalid_type(unexpected(value), &"a variant name or a map with a single entry"))
+        }
+    }
+
+    fn deserialize_ignored_any<V>(self, visitor: V) -> Result<V::Value, Self::Error>
+        where V: Visitor<'de>
+    {
+        visitor.visit_unit()
+    }
+
+    fn is_human_readable(&self) -> bool {
+        false
+    }
+
+    forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string
+        bytes byte_buf unit unit_struct seq tuple tuple_struct map struct identifier
+    }
+}
+
+struct ValueEnumAccess<'de> {
+    variant: &'de Value,
+    content: Option<&'de Value>
+}
+
+impl<'de> EnumAccess<'de> for ValueEnumAccess<'de> {
+    type Error = Error;
+    type Variant = ValueVariantAccess<'de>;
+
+    fn variant_seed<V>(self, seed: V) -> Result<(V::Value, Self::Variant), Self::Error>
+        where V: DeserializeSeed<'de>
+    {
+        let variant = seed.deserialize(ValueDeserializer { value: self.variant })?;
+        return Ok((variant, ValueVariantAccess { content: self.content }));
+    }
+}
+
+struct ValueVariantAccess<'de> {
+    content: Option<&'de Value>
+}
+
+impl<'de> VariantAccess<'de> for ValueVariantAccess<'de> {
+    type Error = Error;
+
+    fn unit_variant(self) -> Result<(), Self::Error> {
+        match self.content {
+            None | Some(Value::Null) => Ok(()),
+            Some(value) => Err(Error::invalid_type(unexpected(value), &"unit variant"))
+        }
+    }
+
+    fn newtype_variant_seed<T>(self, seed: T) -> Result<T::Value, Self::Error>
+        where T: DeserializeSeed<'de>
+    {
+        match self.content {
+            Some(value) => seed.deserialize(ValueDeserializer { value: value }),
+            None => Err(Error::invalid_type(Unexpected::UnitVariant, &"newtype variant"))
+        }
+    }
+
+    fn tuple_variant<V>(self, _len: usize, visitor: V) -> Result<V::Value, Self::Error>
+        where V: Visitor<'de>
+    {
+        match self.content {
+            Some(value) => ValueDeserializer { value: value }.deserialize_any(visitor),
+            None => Err(Error::invalid_type(Unexpected::UnitVariant, &"tuple variant"))
+        }
+    }
+
+    fn struct_variant<V>(self, _fields: &'static [&'static str], visitor: V) -> Result<V::Value, Self::Error>
+        where V: Visitor<'de>
+    {
+        match self.content {
+            Some(value) => ValueDeserializer { value: value }.deserialize_any(visitor),
+            None => Err(Error::invalid_type(Unexpected::UnitVariant, &"struct variant"))
+        }
+    }
+}
+
+#[cfg(test)]
+use std::collections::BTreeMap;
+#[cfg(test)]
+use crate::default_seed::seed_of;
+#[cfg(test)]
+use crate::seq::{SerializableSeq, SeqSeedBuilder};
+#[cfg(test)]
+use crate::map::{SerializableMap, DeserializeSeedMap};
+#[cfg(test)]
+use crate::option::{SerializableOption, DeserializeSeedOption};
+#[cfg(test)]
+use crate::struct_seed::StructSeed;
+#[cfg(test)]
+use crate::enum_seed::EnumSeed;
+#[cfg(test)]
+use crate::{impl_deserialize_seed_for_dependent_enum, impl_deserialize_seed_for_dependent_struct};
+
+#[cfg(test)]
+#[derive(Debug, PartialEq, serde::Serialize)]
+struct Foo {
+    a: i64,
+    b: Option<String>,
+    c: Vec<u8>
+}
+
+#[cfg(test)]
+#[derive(Debug, PartialEq, serde::Serialize)]
+enum Bar {
+    A(i64),
+    B(String)
+}
+
+#[cfg(test)]
+struct DeserializeSeedFoo;
+
+#[cfg(test)]
+impl_deserialize_seed_for_dependent_struct!{
+    pub struct DeserializedFoo<'de> using DeserializeSeedFoo {
+        a: i64: |_| seed_of::<i64>(),
+        b: Option<String>: |_| seed_of::<Option<String>>(),
+        c: Vec<u8>: |_| seed_of::<Vec<u8>>()
+    }
+}
+
+#[cfg(test)]
+struct DeserializeSeedBar;
+
+#[cfg(test)]
+impl_deserialize_seed_for_dependent_enum!{
+    pub enum DeserializedBar<'de> using DeserializeSeedBar {
+        A(i64): |_| seed_of::<i64>(),
+        B(String): |_| seed_of::<String>()
+    }
+}
+
+#[test]
+fn test_indefinite_length() {
+    let serialized = to_vec(SerializableSeq::new([1i64, -2, 3].into_iter())).unwrap();
+    assert_eq!(0x9f, serialized[0]);
+    assert_eq!(vec![1, -2, 3], from_reader_seeded(SeqSeedBuilder::new(seed_of::<i64>).collect_vec(), &serialized[..]).unwrap());
+    assert!(from_reader_seeded(SeqSeedBuilder::new(seed_of::<i64>).max_len(2).collect_vec(), &serialized[..]).is_err());
+    assert!(from_reader_seeded(SeqSeedBuilder::from_seeds(std::iter::repeat_n(seed_of::<i64>(), 2)).collect_vec(), &serialized[..]).is_err());
+
+    let serialized = to_vec(SerializableSeq::new_with_len([1i64, -2, 3].into_iter(), 3)).unwrap();
+    assert_eq!(0x83, serialized[0]);
+    assert_eq!(vec![1, -2, 3], from_reader_seeded(SeqSeedBuilder::new(seed_of::<i64>).collect_vec(), &serialized[..]).unwrap());
+
+    let map = BTreeMap::from([("a".to_owned(), 1i64), ("b".to_owned(), 2)]);
+    let serialized = to_vec(SerializableMap::new(map.iter())).unwrap();
+    let seed = DeserializeSeedMap::new(std::iter::repeat((seed_of::<String>(), seed_of::<i64>())), BTreeMap::new(), |mut current, key, value| { current.insert(key, value); current });
+    assert_eq!(map, from_reader_seeded(seed, &serialized[..]).unwrap());
+}
+
+#[test]
+fn test_struct_keys() {
+    let foo = Foo { a: -1, b: Some("x".to_owned()), c: vec![1, 2] };
+    let serialized = to_vec(&foo).unwrap();
+    let value: Value = ::ciborium::from_reader(&serialized[..]).unwrap();
+    let result = from_value_seeded(DeserializeSeedFoo, &value).unwrap();
+    assert_eq!(foo, Foo { a: result.a, b: result.b, c: result.c });
+    let seed = || StructSeed::new("Foo").field("a", seed_of::<i64>()).field("b", seed_of::<Option<String>>()).field("c", seed_of::<Vec<u8>>())
+        .finish_with(|((a, b), c)| Foo { a, b, c });
+    assert_eq!(foo, from_value_seeded(seed(), &value).unwrap());
+
+    let value = Value::Map(vec![
+        (Value::Integer(2.into()), Value::Array(vec![])),
+        (Value::Bytes(b"b".to_vec()), Value::Null),
+        (Value::Text("a".to_owned()), Value::Tag(0, Box::new(Value::Integer(5.into()))))
+    ]);
+    let result = from_value_seeded(DeserializeSeedFoo, &value).unwrap();
+    assert_eq!(Foo { a: 5, b: None, c: vec![] }, Foo { a: result.a, b: result.b, c: result.c });
+    assert_eq!(Foo { a: 5, b: None, c: vec![] }, from_value_seeded(seed(), &value).unwrap());
+
+    let value = Value::Array(vec![Value::Integer(5.into()), Value::Null, Value::Array(vec![])]);
+    let result = from_value_seeded(DeserializeSeedFoo, &value).unwrap();
+    assert_eq!(Foo { a: 5, b: None, c: vec![] }, Foo { a: result.a, b: result.b, c: result.c });
+
+    let value = Value::Map(vec![(Value::Float(0.), Value::Null)]);
+    assert!(from_value_seeded(DeserializeSeedFoo, &value).is_err());
+}
+
+#[test]
+fn test_enums_and_options() {
+    for bar in [Bar::A(-1), Bar::B("x".to_owned())] {
+        let serialized = to_vec(&bar).unwrap();
+        let value: Value = ::ciborium::from_reader(&serialized[..]).unwrap();
+        let result = match from_value_seeded(DeserializeSeedBar, &value).unwrap() {
+            DeserializedBar::A(value) => Bar::A(value.0),
+            DeserializedBar::B(value) => Bar::B(value.0)
+        };
+        assert_eq!(bar, result);
+        let seed = EnumSeed::new("Bar").variant("A", seed_of::<i64>(), Bar::A).variant("B", seed_of::<String>(), Bar::B);
+        assert_eq!(bar, from_reader_seeded(seed, &serialized[..]).unwrap());
+    }
+    let value = Value::Map(vec![(Value::Integer(1.into()), Value::Text("y".to_owned()))]);
+    assert!(matches!(from_value_seeded(DeserializeSeedBar, &value).unwrap(), DeserializedBar::B(_)));
+    assert!(from_value_seeded(DeserializeSeedBar, &Value::Text("A".to_owned())).is_err());
+
+    let serialized = to_vec(SerializableOption::new(Some(&5i64), |x| *x)).unwrap();
+    assert_eq!(Some(5), from_reader_seeded(DeserializeSeedOption::new(seed_of::<i64>()), &serialized[..]).unwrap());
+    let serialized = to_vec(SerializableOption::new(None, |x: &i64| *x)).unwrap();
+    assert_eq!(None, from_reader_seeded(DeserializeSeedOption::new(seed_of::<i64>()), &serialized[..]).unwrap());
+
+    assert!(from_reader_seeded(seed_of::<i64>(), &[0xff][..]).is_err());
+}
@@ -0,0 +1,338 @@
+use serde::de::{DeserializeSeed, Error, SeqAccess, Visitor};
+use serde::ser::{Serialize, SerializeTuple, Serializer};
+use serde::Deserializer;
+
+use crate::raw_bytes::{Bytes, ByteBufSeed};
+use crate::into_seed::IntoSeed;
+
+///
+/// A compression algorithm used by [`SerializableCompressed`] and [`DeserializeSeedCompressed`].
+///
+/// Implementations are provided for deflate (with feature `flate2`) and zstd (with feature
+/// `zstd`), but any other algorithm can be plugged in by implementing this trait.
+///
+pub trait Compression {
+
+    ///
+    /// Compresses the given data. Errors are reported to the serializer via
+    /// [`serde::ser::Error::custom()`].
+    ///
+    fn compress(&self, data: &[u8]) -> std::io::Result<Vec<u8>>;
+
+    ///
+    /// Decompresses the given data, and returns `None` if it is invalid or if it would
+    /// decompress to more than `max_len` bytes. In the latter case, implementations should
+    /// stop early, instead of first decompressing everything.
+    ///
+    fn decompress(&self, data: &[u8], max_len: usize) -> Option<Vec<u8>>;
+}
+
+#[cfg(any(feature = "flate2", feature = "zstd"))]
+fn read_limited<R>(reader: R, max_len: usize) -> Option<Vec<u8>>
+    where R: std::io::Read
+{
+    let mut result = Vec::new();
+    std::io::Read::read_to_end(&mut std::io::Read::take(reader, max_len as u64 + 1), &mut result).ok()?;
+    if result.len() > max_len {
+        return None;
+    }
+    return Some(result);
+}
+
+///
+/// [`Compression`] using deflate, as implemented by the `flate2` crate.
+///
+#[cfg(feature = "flate2")]
+#[derive(Clone, Copy, Debug)]
+pub struct Deflate {
+    level: u32
+}
+
+#[cfg(feature = "flate2")]
+impl Deflate {
+
+    ///
+    /// Creates a new [`Deflate`] with the given compression level, between 0 and 9.
+    ///
+    pub fn new(level: u32) -> Self {
+        assert!(level <= 9, "invalid compression level {}", level);
+        Self { level }
+    }
+}
+
+#[cfg(feature = "flate2")]
+impl Default for Deflate {
+
+    fn default() -> Self {
+        Self::new(6)
+    }
+}
+
+#[cfg(feature = "flate2")]
+impl Compression for Deflate {
+
+    fn compress(&self, data: &[u8]) -> std::io::Result<Vec<u8>> {
+        let mut encoder = flate2::write::DeflateEncoder::new(Vec::new(), flate2::Compression::new(self.level));
+        std::io::Write::write_all(&mut encoder, data)?;
+        return encoder.finish();
+    }
+
+    fn decompress(&self, data: &[u8], max_len: usize) -> Option<Vec<u8>> {
+        read_limited(flate2::read::DeflateDecoder::new(data), max_len)
+    }
+}
+
+///
+/// [`Compression`] using zstd, as implemented by the `zstd` crate.
+///
+#[cfg(feature = "zstd")]
+#[derive(Clone, Copy, Debug)]
+pub struct Zstd {
+    level: i32
+}
+
+#[cfg(feature = "zstd")]
+impl Zstd {
+
+    ///
+    /// Creates a new [`Zstd`] with the given compression level, where `0` means the
+    /// default level of zstd.
+    ///
+    pub fn new(level: i32) -> Self {
+        Self { level }
+    }
+}
+
+#[cfg(feature = "zstd")]
+impl Default for Zstd {
+
+    fn default() -> Self {
+        Self::new(0)
+    }
+}
+
+#[cfg(feature = "zstd")]
+impl Compression for Zstd {
+
+    fn compress(&self, data: &[u8]) -> std::io::Result<Vec<u8>> {
+        zstd::encode_all(data, self.level)
+    }
+
+    fn decompress(&self, data: &[u8], max_len: usize) -> Option<Vec<u8>> {
+        read_limited(zstd::stream::read::Decoder::with_buffer(data).ok()?, max_len)
+    }
+}
+
+///
+/// Wraps a serializable value, and implements [`Serialize`] by encoding it with postcard,
+/// and mapping it to the tuple `(len, payload)` in the serde data model, where `payload`
+/// is the encoded value compressed with the given [`Compression`] as a byte buffer, and
+/// `len` is the length of the uncompressed encoding.
+///
+/// Use [`DeserializeSeedCompressed`] to deserialize it.
+///
+/// # Example
+/// ```
+/// # use feanor_serde::compressed::*;
+/// # use feanor_serde::default_seed::seed_of;
+/// // not a real compression, just for demonstration
+/// struct Identity;
+/// impl Compression for Identity {
+///     fn compress(&self, data: &[u8]) -> std::io::Result<Vec<u8>> { Ok(data.to_owned()) }
+///     fn decompress(&self, data: &[u8], max_len: usize) -> Option<Vec<u8>> {
+///         if data.len() <= max_len { Some(data.to_owned()) } else { None }
+///     }
+/// }
+///
+/// let serialized = postcard::to_allocvec(&SerializableCompressed::new(vec![0i64; 1000], &Identity)).unwrap();
+/// let seed = DeserializeSeedCompressed::new(seed_of::<Vec<i64>>(), &Identity, 1 << 20);
+/// assert_eq!(vec![0; 1000], feanor_serde::postcard::from_slice_seeded(seed, &serialized).unwrap());
+///
+/// // fails, since the uncompressed data is larger than the limit
+/// let seed = DeserializeSeedCompressed::new(seed_of::<Vec<i64>>(), &Identity, 100);
+/// assert!(feanor_serde::postcard::from_slice_seeded(seed, &serialized).is_err());
+/// ```
+///
+pub struct SerializableCompressed<'a, T, C>
+    where T: Serialize,
+        C: Compression
+{
+    data: T,
+    compression: &'a C
+}
+
+impl<'a, T, C> SerializableCompressed<'a, T, C>
+    where T: Serialize,
+        C: Compression
+{
+    pub const fn new(data: T, compression: &'a C) -> Self {
+        Self { data, compression }
+    }
+}
+
+impl<'a, T, C> Serialize for SerializableCompressed<'a, T, C>
+    where T: Serialize,
+        C: Compression
+{
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+        where S: Serializer
+    {
+        let payload = ::postcard::to_allocvec(&self.data).map_err(serde::ser::Error::custom)?;
+        let mut tuple = serializer.serialize_tuple(2)?;
+        tuple.serialize_element(&(payload.len() as u64))?;
+        let compressed = self.compression.compress(&payload).map_err(serde::ser::Error::custom)?;
+        tuple.serialize_element(&Bytes(&compressed))?;
+        return tuple.end();
+    }
+}
+
+///
+/// A [`DeserializeSeed`] that deserializes a value serialized using [`SerializableCompressed`].
+/// The payload is decompressed with the given [`Compression`], and then passed to the wrapped
+/// [`DeserializeSeed`].
+///
+/// To protect against decompression bombs, deserialization fails if the uncompressed data
+/// would be longer than `max_len` bytes.
+///
+/// Since the uncompressed data is not borrowed from the input, the wrapped seed must be able
+/// to deserialize from data of any lifetime.
+///
+pub struct DeserializeSeedCompressed<'a, S, C>
+    where C: Compression
+{
+    seed: S,
+    compression: &'a C,
+    max_len: usize
+}
+
+impl<'a, S, C> DeserializeSeedCompressed<'a, S, C>
+    where C: Compression
+{
+    pub fn new<'de, I, M>(seed: I, compression: &'a C, max_len: usize) -> Self
+        where I: IntoSeed<'de, M, Seed = S>
+    {
+        Self { seed: seed.into_seed(), compression, max_len }
+    }
+}
+
+impl<'a, 'de, S, C, T> DeserializeSeed<'de> for DeserializeSeedCompressed<'a, S, C>
+    where S: for<'b> DeserializeSeed<'b, Value = T>,
+        C: Compression
+{
+    type Value = T;
+
+    fn deserialize<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+        where D: Deserializer<'de>
+    {
+        return deserializer.deserialize_tuple(2, self);
+    }
+}
+
+impl<'a, 'de, S, C, T> Visitor<'de> for DeserializeSeedCompressed<'a, S, C>
+    where S: for<'b> DeserializeSeed<'b, Value = T>,
+        C: Compression
+{
+    type Value = T;
+
+    fn expecting(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "a length and a compressed byte buffer")
+    }
+
+    fn visit_seq<A>(self, mut seq: A) -> Result<Self::Value, A::Error>
+        where A: SeqAccess<'de>
+    {
+        let len: u64 = seq.next_element()?.ok_or_else(|| A::Error::invalid_length(0, &self))?;
+        if len > self.max_len as u64 {
+            return Err(A::Error::custom(format!("uncompressed length {} exceeds the limit of {} bytes", len, self.max_len)));
+        }
+        let len = len as usize;
+        let compressed = seq.next_element_seed(ByteBufSeed)?.ok_or_else(|| A::Error::invalid_length(1, &self))?;
+        let payload = self.compression.decompress(&compressed, len).ok_or_else(|| A::Error::custom("invalid compressed data"))?;
+        if payload.len() != len {
+            return Err(A::Error::custom(format!("expected {} bytes of uncompressed data, but got {}", len, payload.len())));
+        }
+        let (result, rest) = crate::postcard::take_from_slice_seeded(self.seed, &payload).map_err(A::Error::custom)?;
+        if !rest.is_empty() {
+            return Err(A::Error::custom(format!("payload has {} trailing bytes", rest.len())));
+        }
+        return Ok(result);
+    }
+}
+
+#[cfg(test)]
+use std::marker::PhantomData;
+
+#[cfg(test)]
+struct Identity;
+
+#[cfg(test)]
+impl Compression for Identity {
+
+    fn compress(&self, data: &[u8]) -> std::io::Result<Vec<u8>> {
+        Ok(data.to_owned())
+    }
+
+    fn decompress(&self, data: &[u8], max_len: usize) -> Option<Vec<u8>> {
+        if data.len() > max_len {
+            return None;
+        }
+        return Some(data.to_owned());
+    }
+}
+
+#[test]
+fn test_serde_json() {
+    let serialized = serde_json::to_string(&SerializableCompressed::new((1u8, 2u8), &Identity)).unwrap();
+    assert_eq!("[2,[1,2]]", serialized);
+    assert_eq!((1, 2), DeserializeSeedCompressed::new(PhantomData::<(u8, u8)>, &Identity, 2).deserialize(&mut serde_json::Deserializer::from_str(&serialized)).unwrap());
+
+    let error = DeserializeSeedCompressed::new(PhantomData::<(u8, u8)>, &Identity, 1).deserialize(&mut serde_json::Deserializer::from_str(&serialized)).unwrap_err();
+    assert!(error.to_string().contains("exceeds the limit"));
+    let error = DeserializeSeedCompressed::new(PhantomData::<(u8, u8)>, &Identity, 1000).deserialize(&mut serde_json::Deserializer::from_str("[3,[1,2]]")).unwrap_err();
+    assert!(error.to_string().contains("expected 3 bytes"));
+}
+
+#[test]
+fn test_compress_error() {
+
+    struct Failing;
+
+    impl Compression for Failing {
+
+        fn compress(&self, _data: &[u8]) -> std::io::Result<Vec<u8>> {
+            Err(std::io::Error::other("compression failed"))
+        }
+
+        fn decompress(&self, _data: &[u8], _max_len: usize) -> Option<Vec<u8>> {
+            None
+        }
+    }
+
+    let error = serde_json::to_string(&SerializableCompressed::new((1u8, 2u8), &Failing)).unwrap_err();
+    assert!(error.to_string().contains("compression failed"));
+}
+
+#[cfg(feature = "flate2")]
+#[test]
+fn test_deflate() {
+    let data = (0..1000).map(|i| i % 7).collect::<Vec<i64>>();
+    let serialized = postcard::to_allocvec(&SerializableCompressed::new(&data, &Deflate::default())).unwrap();
+    assert!(serialized.len() < 100);
+    assert_eq!(data, crate::postcard::from_slice_seeded(DeserializeSeedCompressed::new(PhantomData::<Vec<i64>>, &Deflate::default(), 1000 + 2), &serialized).unwrap());
+    assert!(crate::postcard::from_slice_seeded(DeserializeSeedCompressed::new(PhantomData::<Vec<i64>>, &Deflate::default(), 1000), &serialized).is_err());
+
+    // the decompressor must stop early if the length is wrong
+    let bomb = (1u64, Bytes(&Deflate::default().compress(&[0; 1 << 20]).unwrap()));
+    let serialized = postcard::to_allocvec(&bomb).unwrap();
+    assert!(crate::postcard::from_slice_seeded(DeserializeSeedCompressed::new(PhantomData::<u8>, &Deflate::default(), 10), &serialized).is_err());
+}
+
+#[cfg(feature = "zstd")]
+#[test]
+fn test_zstd() {
+    let data = (0..1000).map(|i| i % 7).collect::<Vec<i64>>();
+    let serialized = postcard::to_allocvec(&SerializableCompressed::new(&data, &Zstd::default())).unwrap();
+    assert!(serialized.len() < 100);
+    assert_eq!(data, crate::postcard::from_slice_seeded(DeserializeSeedCompressed::new(PhantomData::<Vec<i64>>, &Zstd::default(), 1000 + 2), &serialized).unwrap());
+    assert!(crate::postcard::from_slice_seeded(DeserializeSeedCompressed::new(PhantomData::<Vec<i64>>, &Zstd::default(), 1000), &serialized).is_err());
+}
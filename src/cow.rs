@@ -0,0 +1,288 @@
+use std::borrow::Cow;
+
+use serde::de::{DeserializeSeed, Error, SeqAccess, Visitor};
+use serde::{Deserializer, Serialize, Serializer};
+
+use crate::raw_bytes::{Bytes, ByteBufSeed};
+use crate::seq::{SerializableSeq, DeserializeSeedSeq};
+
+///
+/// A [`DeserializeSeed`] that deserializes a string as a [`Cow<str>`], which borrows
+/// from the input whenever the deserializer supports it, and owns the string otherwise.
+///
+/// No serialize-side wrapper is required, since [`Cow<str>`] already implements
+/// [`Serialize`] without cloning the string.
+///
+/// # Example
+/// ```
+/// # use feanor_serde::cow::*;
+/// # use serde::de::DeserializeSeed;
+/// # use std::borrow::Cow;
+/// let result = DeserializeSeedCowStr::new().deserialize(&mut serde_json::Deserializer::from_str("\"foo\"")).unwrap();
+/// assert!(matches!(result, Cow::Borrowed("foo")));
+/// let result = DeserializeSeedCowStr::new().deserialize(&mut serde_json::Deserializer::from_str("\"f\\u006fo\"")).unwrap();
+/// assert!(matches!(result, Cow::Owned(_)));
+/// assert_eq!("foo", result);
+/// ```
+///
+#[derive(Clone, Copy, Debug, Default)]
+pub struct DeserializeSeedCowStr;
+
+impl DeserializeSeedCowStr {
+
+    pub const fn new() -> Self {
+        Self
+    }
+}
+
+impl<'de> Visitor<'de> for DeserializeSeedCowStr {
+    type Value = Cow<'de, str>;
+
+    fn expecting(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "a string")
+    }
+
+    fn visit_borrowed_str<E>(self, value: &'de str) -> Result<Self::Value, E>
+        where E: Error
+    {
+        Ok(Cow::Borrowed(value))
+    }
+
+    fn visit_str<E>(self, value: &str) -> Result<Self::Value, E>
+        where E: Error
+    {
+        Ok(Cow::Owned(value.to_owned()))
+    }
+
+    fn visit_string<E>(self, value: String) -> Result<Self::Value, E>
+        where E: Error
+    {
+        Ok(Cow::Owned(value))
+    }
+}
+
+impl<'de> DeserializeSeed<'de> for DeserializeSeedCowStr {
+    type Value = Cow<'de, str>;
+
+    fn deserialize<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+        where D: Deserializer<'de>
+    {
+        deserializer.deserialize_str(self)
+    }
+}
+
+///
+/// Wraps a byte slice, e.g. the content of a [`Cow<[u8]>`], and implements [`Serialize`]
+/// by mapping it to a byte buffer in the serde data model. In contrast, [`Cow<[u8]>`] itself
+/// is serialized as a sequence of bytes.
+///
+/// Use [`DeserializeSeedCowBytes`] to deserialize it.
+///
+/// # Example
+/// ```
+/// # use feanor_serde::cow::*;
+/// # use serde::de::DeserializeSeed;
+/// # use std::borrow::Cow;
+/// let data: Cow<[u8]> = Cow::Owned(vec![1, 2, 3]);
+/// let serialized = postcard::to_allocvec(&SerializableCowBytes::new(&data)).unwrap();
+/// let result = DeserializeSeedCowBytes::new().deserialize(&mut postcard::Deserializer::from_bytes(&serialized)).unwrap();
+/// assert!(matches!(result, Cow::Borrowed(&[1, 2, 3])));
+/// ```
+///
+#[derive(Clone, Copy, Debug)]
+pub struct SerializableCowBytes<'a> {
+    data: &'a [u8]
+}
+
+impl<'a> SerializableCowBytes<'a> {
+
+    pub const fn new(data: &'a [u8]) -> Self {
+        Self { data }
+    }
+}
+
+impl<'a> Serialize for SerializableCowBytes<'a> {
+
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+        where S: Serializer
+    {
+        Bytes(self.data).serialize(serializer)
+    }
+}
+
+///
+/// A [`DeserializeSeed`] that deserializes a byte buffer as a [`Cow<[u8]>`], which borrows
+/// from the input whenever the deserializer supports it, and owns the bytes otherwise.
+/// Sequences of bytes, as produced e.g. by JSON, are accepted as well.
+///
+#[derive(Clone, Copy, Debug, Default)]
+pub struct DeserializeSeedCowBytes;
+
+impl DeserializeSeedCowBytes {
+
+    pub const fn new() -> Self {
+        Self
+    }
+}
+
+impl<'de> Visitor<'de> for DeserializeSeedCowBytes {
+    type Value = Cow<'de, [u8]>;
+
+    fn expecting(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "a byte buffer")
+    }
+
+    fn visit_borrowed_bytes<E>(self, value: &'de [u8]) -> Result<Self::Value, E>
+        where E: Error
+    {
+        Ok(Cow::Borrowed(value))
+    }
+
+    fn visit_bytes<E>(self, value: &[u8]) -> Result<Self::Value, E>
+        where E: Error
+    {
+        Ok(Cow::Owned(value.to_owned()))
+    }
+
+    fn visit_byte_buf<E>(self, value: Vec<u8>) -> Result<Self::Value, E>
+        where E: Error
+    {
+        Ok(Cow::Owned(value))
+    }
+
+    fn visit_seq<A>(self, seq: A) -> Result<Self::Value, A::Error>
+        where A: SeqAccess<'de>
+    {
+        ByteBufSeed.visit_seq(seq).map(Cow::Owned)
+    }
+}
+
+impl<'de> DeserializeSeed<'de> for DeserializeSeedCowBytes {
+    type Value = Cow<'de, [u8]>;
+
+    fn deserialize<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+        where D: Deserializer<'de>
+    {
+        deserializer.deserialize_bytes(self)
+    }
+}
+
+///
+/// Wraps a slice, e.g. the content of a [`Cow<[T]>`], and implements [`Serialize`] by
+/// mapping it to a sequence in the serde data model, whose elements are serialized by
+/// first passing them to the given function. This can e.g. wrap them in another
+/// serializable wrapper carrying additional context.
+///
+/// Use [`DeserializeSeedCowSlice`] to deserialize it.
+///
+/// # Example
+/// ```
+/// # use feanor_serde::cow::*;
+/// # use feanor_serde::newtype_struct::*;
+/// # use feanor_serde::default_seed::seed_of;
+/// # use serde::de::DeserializeSeed;
+/// # use std::borrow::Cow;
+/// let data: Cow<[i64]> = Cow::Borrowed(&[1, 2]);
+/// let json = serde_json::to_string(&SerializableCowSlice::new(&data, |x| SerializableNewtypeStruct::new("Foo", *x))).unwrap();
+/// assert_eq!("[1,2]", json);
+/// let seed = DeserializeSeedCowSlice::new(std::iter::repeat_with(|| DeserializeSeedNewtypeStruct::new("Foo", seed_of::<i64>())));
+/// assert_eq!(data, seed.deserialize(&mut serde_json::Deserializer::from_str(&json)).unwrap());
+/// ```
+///
+#[derive(Clone, Copy)]
+pub struct SerializableCowSlice<'a, T, F> {
+    data: &'a [T],
+    wrap: F
+}
+
+impl<'a, T, F, W> SerializableCowSlice<'a, T, F>
+    where F: Fn(&'a T) -> W,
+        W: Serialize
+{
+    pub const fn new(data: &'a [T], wrap: F) -> Self {
+        Self { data, wrap }
+    }
+}
+
+impl<'a, T, F, W> Serialize for SerializableCowSlice<'a, T, F>
+    where F: Fn(&'a T) -> W,
+        W: Serialize
+{
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+        where S: Serializer
+    {
+        SerializableSeq::new_with_len(self.data.iter().map(&self.wrap), self.data.len()).serialize(serializer)
+    }
+}
+
+///
+/// A [`DeserializeSeed`] that deserializes a sequence as a [`Cow<[T]>`], using the
+/// [`DeserializeSeed`]s produced by the given iterator for the elements.
+///
+/// Since the elements are produced by seeds, they cannot be borrowed from the input,
+/// and the result is always [`Cow::Owned`]. For byte buffers or primitive numbers, use
+/// [`DeserializeSeedCowBytes`] resp. [`crate::raw_bytes`] to borrow from the input.
+///
+#[derive(Clone, Copy, Debug)]
+pub struct DeserializeSeedCowSlice<V> {
+    seeds: V
+}
+
+impl<'de, V, S> DeserializeSeedCowSlice<V>
+    where V: Iterator<Item = S>,
+        S: DeserializeSeed<'de>,
+        S::Value: Clone
+{
+    pub const fn new(seeds: V) -> Self {
+        Self { seeds }
+    }
+}
+
+impl<'de, V, S, T> DeserializeSeed<'de> for DeserializeSeedCowSlice<V>
+    where V: Iterator<Item = S>,
+        S: DeserializeSeed<'de, Value = T>,
+        T: Clone + 'de
+{
+    type Value = Cow<'de, [T]>;
+
+    fn deserialize<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+        where D: Deserializer<'de>
+    {
+        DeserializeSeedSeq::new(self.seeds, Vec::new(), |mut current, next| { current.push(next); current }).deserialize(deserializer).map(Cow::Owned)
+    }
+}
+
+#[cfg(test)]
+use crate::default_seed::seed_of;
+
+#[test]
+fn test_serde_postcard() {
+    let string = Cow::Borrowed("foo");
+    let serialized = postcard::to_allocvec(&string).unwrap();
+    let result = DeserializeSeedCowStr::new().deserialize(&mut postcard::Deserializer::from_bytes(&serialized)).unwrap();
+    assert!(matches!(result, Cow::Borrowed("foo")));
+
+    let bytes: Cow<[u8]> = Cow::Owned(vec![1, 2]);
+    let serialized = postcard::to_allocvec(&SerializableCowBytes::new(&bytes)).unwrap();
+    let result = DeserializeSeedCowBytes::new().deserialize(&mut postcard::Deserializer::from_bytes(&serialized)).unwrap();
+    assert!(matches!(result, Cow::Borrowed(&[1, 2])));
+
+    let slice: Cow<[i64]> = Cow::Owned(vec![3, 4]);
+    let serialized = postcard::to_allocvec(&SerializableCowSlice::new(&slice, |x| *x)).unwrap();
+    assert_eq!(postcard::to_allocvec(&slice).unwrap(), serialized);
+    let result = DeserializeSeedCowSlice::new(std::iter::repeat(seed_of::<i64>())).deserialize(&mut postcard::Deserializer::from_bytes(&serialized)).unwrap();
+    assert_eq!(slice, result);
+}
+
+#[test]
+fn test_serde_json() {
+    let bytes: Cow<[u8]> = Cow::Borrowed(&[1, 2]);
+    let json = serde_json::to_string(&SerializableCowBytes::new(&bytes)).unwrap();
+    assert_eq!("[1,2]", json);
+    let result = DeserializeSeedCowBytes::new().deserialize(&mut serde_json::Deserializer::from_str(&json)).unwrap();
+    assert!(matches!(result, Cow::Owned(x) if x == [1, 2]));
+
+    let result = DeserializeSeedCowStr::new().deserialize(&mut serde_json::Deserializer::from_reader("\"foo\"".as_bytes())).unwrap();
+    assert!(matches!(result, Cow::Owned(x) if x == "foo"));
+    assert!(DeserializeSeedCowStr::new().deserialize(&mut serde_json::Deserializer::from_str("1")).is_err());
+}
@@ -0,0 +1,152 @@
+use std::marker::PhantomData;
+
+use serde::de::DeserializeSeed;
+use serde::{Deserialize, Deserializer};
+
+///
+/// The [`DeserializeSeed`] that deserializes a `T` using its [`Deserialize`] implementation,
+/// usually created via [`seed_of()`].
+///
+/// This behaves exactly like `PhantomData::<T>`, which also implements [`DeserializeSeed`],
+/// but makes the intention clearer, and has a more helpful [`std::fmt::Debug`] implementation.
+///
+/// # Example
+/// ```
+/// # use feanor_serde::default_seed::*;
+/// # use feanor_serde::seq::*;
+/// # use serde::de::DeserializeSeed;
+/// # use std::iter::repeat;
+/// let mut deserializer = serde_json::Deserializer::from_str("[1, 2, 3]");
+/// let seed = DeserializeSeedSeq::new(repeat(seed_of::<i64>()), 0, |current, next| current + next);
+/// assert_eq!(6, seed.deserialize(&mut deserializer).unwrap());
+/// assert_eq!("DefaultSeed<i64>", format!("{:?}", seed_of::<i64>()));
+/// ```
+///
+pub struct DefaultSeed<T> {
+    value: PhantomData<T>
+}
+
+///
+/// Returns the [`DeserializeSeed`] that deserializes a `T` using its [`Deserialize`]
+/// implementation, see [`DefaultSeed`].
+///
+pub const fn seed_of<T>() -> DefaultSeed<T> {
+    DefaultSeed { value: PhantomData }
+}
+
+///
+/// Returns the [`DeserializeSeed`] that deserializes a `T` using its [`Deserialize`]
+/// implementation, and ignores the given context.
+///
+/// This is meant to be passed (without calling it) wherever a function deriving a
+/// [`DeserializeSeed`] from some context is expected, e.g. to
+/// [`crate::dependent_tuple::DeserializeSeedDependentTuple::new()`] or as field seed in
+/// [`crate::impl_deserialize_seed_for_dependent_struct!`], so that plain [`Deserialize`]
+/// types can be used there without writing a closure.
+///
+/// # Example
+/// ```
+/// # use feanor_serde::default_seed::*;
+/// # use feanor_serde::dependent_tuple::*;
+/// # use serde::de::DeserializeSeed;
+/// let mut deserializer = serde_json::Deserializer::from_str("[3, \"abc\"]");
+/// let seed = DeserializeSeedDependentTuple::new(seed_of::<usize>(), ignore_context::<usize, String>);
+/// assert_eq!("abc", seed.deserialize(&mut deserializer).unwrap());
+/// ```
+///
+pub fn ignore_context<C, T>(_context: C) -> DefaultSeed<T> {
+    seed_of()
+}
+
+impl<T> DefaultSeed<T> {
+
+    pub const fn new() -> Self {
+        seed_of()
+    }
+}
+
+impl<T> Default for DefaultSeed<T> {
+
+    fn default() -> Self {
+        seed_of()
+    }
+}
+
+impl<T> Clone for DefaultSeed<T> {
+
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T> Copy for DefaultSeed<T> {}
+
+impl<T> std::fmt::Debug for DefaultSeed<T> {
+
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "DefaultSeed<{}>", std::any::type_name::<T>())
+    }
+}
+
+impl<T> From<PhantomData<T>> for DefaultSeed<T> {
+
+    fn from(_: PhantomData<T>) -> Self {
+        seed_of()
+    }
+}
+
+impl<'de, T> DeserializeSeed<'de> for DefaultSeed<T>
+    where T: Deserialize<'de>
+{
+    type Value = T;
+
+    fn deserialize<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+        where D: Deserializer<'de>
+    {
+        T::deserialize(deserializer)
+    }
+}
+
+impl<'de, T> DeserializeSeed<'de> for &DefaultSeed<T>
+    where T: Deserialize<'de>
+{
+    type Value = T;
+
+    fn deserialize<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+        where D: Deserializer<'de>
+    {
+        T::deserialize(deserializer)
+    }
+}
+
+#[test]
+fn test_default_seed() {
+    assert_eq!(vec![1, 2], seed_of::<Vec<i64>>().deserialize(&mut serde_json::Deserializer::from_str("[1, 2]")).unwrap());
+    let data = postcard::to_allocvec(&("a", 5u8)).unwrap();
+    assert_eq!(("a", 5), seed_of::<(&str, u8)>().deserialize(&mut postcard::Deserializer::from_bytes(&data)).unwrap());
+    assert_eq!("DefaultSeed<alloc::string::String>", format!("{:?}", DefaultSeed::<String>::new()));
+}
+
+#[cfg(test)]
+use crate::impl_deserialize_seed_for_dependent_struct;
+
+#[cfg(test)]
+struct FooDeserializeSeed;
+
+#[cfg(test)]
+impl_deserialize_seed_for_dependent_struct!{
+    pub struct Foo<'de> using FooDeserializeSeed {
+        a: i64: ignore_context,
+        b: String: ignore_context
+    }
+}
+
+#[test]
+fn test_ignore_context() {
+    let foo = FooDeserializeSeed.deserialize(&mut serde_json::Deserializer::from_str("{\"a\": 1, \"b\": \"x\"}")).unwrap();
+    assert_eq!(1, foo.a);
+    assert_eq!("x", foo.b);
+
+    let seed = crate::dependent_tuple::DeserializeSeedDependentTuple::new(seed_of::<bool>(), ignore_context::<_, Vec<i64>>);
+    assert_eq!(vec![1, 2], seed.deserialize(&mut serde_json::Deserializer::from_str("[true, [1, 2]]")).unwrap());
+}
@@ -0,0 +1,431 @@
+use serde::de::value::StrDeserializer;
+use serde::de::{DeserializeSeed, Error, IntoDeserializer, MapAccess, Unexpected, Visitor};
+use serde::ser::{Impossible, Serialize, SerializeMap, SerializeStruct, Serializer};
+use serde::{forward_to_deserialize_any, Deserializer};
+
+use crate::into_seed::IntoSeed;
+use crate::mock::{MockSerializer, MockValue};
+
+///
+/// Wraps a struct implementing [`Serialize`], e.g. via `#[derive(Serialize)]`, together with a
+/// baseline value of the same type, and serializes only the fields that differ from the baseline.
+///
+/// More concretely, the struct is serialized as a map containing only the changed fields. As in
+/// [`crate::field_index::SerializableFieldIndices`], the fields are identified by their name if the
+/// serializer is human-readable, and by their index otherwise. Fields are compared by their
+/// [`MockValue`] representation, and only the fields of the outermost struct are considered,
+/// i.e. a nested struct is either serialized completely or not at all.
+///
+/// Use [`DeserializeSeedDelta`] to apply the delta to the baseline again. Note that a field that
+/// is skipped during serialization (e.g. via `#[serde(skip_serializing_if = "...")]`) is never
+/// part of the delta, so it keeps the value of the baseline when the delta is applied.
+///
+/// # Example
+/// ```
+/// # use feanor_serde::delta::*;
+/// # use serde::de::DeserializeSeed;
+/// # use std::marker::PhantomData;
+/// #[derive(serde::Serialize, serde::Deserialize, Debug, PartialEq)]
+/// struct State {
+///     position: (i64, i64),
+///     name: String,
+///     history: Vec<i64>
+/// }
+/// let baseline = State { position: (0, 0), name: "player".to_owned(), history: vec![1, 2, 3] };
+/// let current = State { position: (1, 0), name: "player".to_owned(), history: vec![1, 2, 3] };
+/// let serialized = serde_json::to_string(&SerializableDelta::new(&current, &baseline)).unwrap();
+/// assert_eq!("{\"position\":[1,0]}", serialized);
+///
+/// let serialized = postcard::to_allocvec(&SerializableDelta::new(&current, &baseline)).unwrap();
+/// let result = DeserializeSeedDelta::new(PhantomData::<State>, &baseline).deserialize(&mut postcard::Deserializer::from_bytes(&serialized)).unwrap();
+/// assert_eq!(current, result);
+/// ```
+///
+#[derive(Clone, Copy, Debug)]
+pub struct SerializableDelta<'a, T>
+    where T: ?Sized
+{
+    value: &'a T,
+    baseline: &'a T
+}
+
+impl<'a, T> SerializableDelta<'a, T>
+    where T: ?Sized + Serialize
+{
+    pub const fn new(value: &'a T, baseline: &'a T) -> Self {
+        Self { value, baseline }
+    }
+}
+
+impl<'a, T> Serialize for SerializableDelta<'a, T>
+    where T: ?Sized + Serialize
+{
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+        where S: Serializer
+    {
+        let human_readable = serializer.is_human_readable();
+        let value_fields = struct_fields(self.value, human_readable).map_err(serde::ser::Error::custom)?.ok_or_else(not_a_struct)?;
+        let baseline_fields = struct_fields(self.baseline, human_readable).map_err(serde::ser::Error::custom)?.ok_or_else(not_a_struct)?;
+        let changed = value_fields.into_iter().filter(|field| !baseline_fields.contains(field)).map(|(key, _)| key).collect::<Vec<_>>();
+        return self.value.serialize(DeltaSerializer { ser: serializer, changed: &changed });
+    }
+}
+
+fn struct_fields<T>(value: &T, human_readable: bool) -> Result<Option<Vec<(MockValue, MockValue)>>, serde::de::value::Error>
+    where T: ?Sized + Serialize
+{
+    match value.serialize(MockSerializer::new().with_human_readable(human_readable))? {
+        MockValue::Map(entries) => Ok(Some(entries)),
+        _ => Ok(None)
+    }
+}
+
+fn not_a_struct<E>() -> E
+    where E: serde::ser::Error
+{
+    E::custom("delta serialization is only supported for structs")
+}
+
+struct DeltaSerializer<'b, S> {
+    ser: S,
+    changed: &'b [MockValue]
+}
+
+struct DeltaStruct<'b, S>
+    where S: Serializer
+{
+    map: S::SerializeMap,
+    changed: &'b [MockValue],
+    human_readable: bool,
+    index: u32
+}
+
+macro_rules! reject_serialize {
+    ($($method:ident($($arg:ident: $type:ty),*) -> $result:ty),*) => {
+        $(
+            fn $method(self, $($arg: $type),*) -> Result<$result, Self::Error> {
+                $(let _ = $arg;)*
+                Err(not_a_struct())
+            }
+        )*
+    };
+}
+
+impl<'b, S> Serializer for DeltaSerializer<'b, S>
+    where S: Serializer
+{
+    type Ok = S::Ok;
+    type Error = S::Error;
+    type SerializeSeq = Impossible<S::Ok, S::Error>;
+    type SerializeTuple = Impossible<S::Ok, S::Error>;
+    type SerializeTupleStruct = Impossible<S::Ok, S::Error>;
+    type SerializeTupleVariant = Impossible<S::Ok, S::Error>;
+    type SerializeMap = Impossible<S::Ok, S::Error>;
+    type SerializeStruct = DeltaStruct<'b, S>;
+    type SerializeStructVariant = Impossible<S::Ok, S::Error>;
+
+    reject_serialize!{
+        serialize_bool(v: bool) -> S::Ok, serialize_i8(v: i8) -> S::Ok, serialize_i16(v: i16) -> S::Ok, serialize_i32(v: i32) -> S::Ok,
+        serialize_i64(v: i64) -> S::Ok, serialize_i128(v: i128) -> S::Ok, serialize_u8(v: u8) -> S::Ok, serialize_u16(v: u16) -> S::Ok,
+        serialize_u32(v: u32) -> S::Ok, serialize_u64(v: u64) -> S::Ok, serialize_u128(v: u128) -> S::Ok, serialize_f32(v: f32) -> S::Ok,
+        serialize_f64(v: f64) -> S::Ok, serialize_char(v: char) -> S::Ok, serialize_str(v: &str) -> S::Ok, serialize_bytes(v: &[u8]) -> S::Ok,
+        serialize_none() -> S::Ok, serialize_unit() -> S::Ok, serialize_unit_struct(name: &'static str) -> S::Ok,
+        serialize_unit_variant(name: &'static str, variant_index: u32, variant: &'static str) -> S::Ok,
+        serialize_seq(len: Option<usize>) -> Self::SerializeSeq, serialize_tuple(len: usize) -> Self::SerializeTuple,
+        serialize_tuple_struct(name: &'static str, len: usize) -> Self::SerializeTupleStruct,
+        serialize_tuple_variant(name: &'static str, variant_index: u32, variant: &'static str, len: usize) -> Self::SerializeTupleVariant,
+        serialize_map(len: Option<usize>) -> Self::SerializeMap,
+        serialize_struct_variant(name: &'static str, variant_index: u32, variant: &'static str, len: usize) -> Self::SerializeStructVariant
+    }
+
+    fn serialize_some<T>(self, _value: &T) -> Result<S::Ok, S::Error>
+        where T: ?Sized + Serialize
+    {
+        Err(not_a_struct())
+    }
+
+    fn serialize_newtype_struct<T>(self, _name: &'static str, _value: &T) -> Result<S::Ok, S::Error>
+        where T: ?Sized + Serialize
+    {
+        Err(not_a_struct())
+    }
+
+    fn serialize_newtype_variant<T>(self, _name: &'static str, _variant_index: u32, _variant: &'static str, _value: &T) -> Result<S::Ok, S::Error>
+        where T: ?Sized + Serialize
+    {
+        Err(not_a_struct())
+    }
+
+    fn serialize_struct(self, _name: &'static str, _len: usize) -> Result<DeltaStruct<'b, S>, S::Error> {
+        let human_readable = self.ser.is_human_readable();
+        let map = self.ser.serialize_map(Some(self.changed.len()))?;
+        return Ok(DeltaStruct { map: map, changed: self.changed, human_readable: human_readable, index: 0 });
+    }
+
+    fn is_human_readable(&self) -> bool {
+        self.ser.is_human_readable()
+    }
+}
+
+impl<'b, S> SerializeStruct for DeltaStruct<'b, S>
+    where S: Serializer
+{
+    type Ok = S::Ok;
+    type Error = S::Error;
+
+    fn serialize_field<T>(&mut self, key: &'static str, value: &T) -> Result<(), S::Error>
+        where T: ?Sized + Serialize
+    {
+        if self.changed.iter().any(|changed| matches!(changed, MockValue::Str(name) if name == key)) {
+            if self.human_readable {
+                self.map.serialize_entry(key, value)?;
+            } else {
+                self.map.serialize_entry(&self.index, value)?;
+            }
+        }
+        self.index += 1;
+        return Ok(());
+    }
+
+    fn skip_field(&mut self, _key: &'static str) -> Result<(), S::Error> {
+        self.index += 1;
+        return Ok(());
+    }
+
+    fn end(self) -> Result<S::Ok, S::Error> {
+        self.map.end()
+    }
+}
+
+///
+/// A [`DeserializeSeed`] that deserializes a delta serialized using [`SerializableDelta`], and
+/// applies it on top of the given baseline.
+///
+/// The wrapped [`DeserializeSeed`] must deserialize a struct, i.e. call [`Deserializer::deserialize_struct()`].
+/// It then receives the fields contained in the delta, followed by all remaining fields of the
+/// baseline. Since the values of the changed fields are deserialized by the wrapped [`DeserializeSeed`]
+/// itself, this also works with formats that are not self-describing, like postcard.
+///
+pub struct DeserializeSeedDelta<'a, S, T>
+    where T: ?Sized
+{
+    seed: S,
+    baseline: &'a T
+}
+
+impl<'a, S, T> DeserializeSeedDelta<'a, S, T>
+    where T: ?Sized + Serialize
+{
+    pub fn new<'de, I, M>(seed: I, baseline: &'a T) -> Self
+        where I: IntoSeed<'de, M, Seed = S>
+    {
+        Self { seed: seed.into_seed(), baseline: baseline }
+    }
+}
+
+impl<'a, 'de, S, T> DeserializeSeed<'de> for DeserializeSeedDelta<'a, S, T>
+    where S: DeserializeSeed<'de>,
+        T: ?Sized + Serialize
+{
+    type Value = S::Value;
+
+    fn deserialize<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+        where D: Deserializer<'de>
+    {
+        let human_readable = deserializer.is_human_readable();
+        let baseline = struct_fields(self.baseline, human_readable).map_err(D::Error::custom)?.ok_or_else(|| D::Error::custom("a delta can only be applied to a struct"))?;
+        return self.seed.deserialize(DeltaDeserializer { de: deserializer, baseline: baseline, human_readable: human_readable });
+    }
+}
+
+struct DeltaDeserializer<D> {
+    de: D,
+    baseline: Vec<(MockValue, MockValue)>,
+    human_readable: bool
+}
+
+impl<'de, D> Deserializer<'de> for DeltaDeserializer<D>
+    where D: Deserializer<'de>
+{
+    type Error = D::Error;
+
+    fn deserialize_any<V>(self, _visitor: V) -> Result<V::Value, D::Error>
+        where V: Visitor<'de>
+    {
+        Err(D::Error::custom("a delta can only be applied to a struct"))
+    }
+
+    fn deserialize_struct<V>(self, _name: &'static str, fields: &'static [&'static str], visitor: V) -> Result<V::Value, D::Error>
+        where V: Visitor<'de>
+    {
+        struct DeltaVisitor<V> {
+            visitor: V,
+            fields: &'static [&'static str],
+            baseline: Vec<(MockValue, MockValue)>,
+            human_readable: bool
+        }
+
+        impl<'de, V> Visitor<'de> for DeltaVisitor<V>
+            where V: Visitor<'de>
+        {
+            type Value = V::Value;
+
+            fn expecting(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+                write!(f, "a map of changed fields")
+            }
+
+            fn visit_map<A>(self, map: A) -> Result<Self::Value, A::Error>
+                where A: MapAccess<'de>
+            {
+                return self.visitor.visit_map(DeltaMapAccess {
+                    map: map,
+                    fields: self.fields,
+                    covered: Vec::new(),
+                    delta_done: false,
+                    baseline: self.baseline.into_iter(),
+                    pending: None,
+                    human_readable: self.human_readable
+                });
+            }
+        }
+
+        return self.de.deserialize_map(DeltaVisitor { visitor: visitor, fields: fields, baseline: self.baseline, human_readable: self.human_readable });
+    }
+
+    fn is_human_readable(&self) -> bool {
+        self.human_readable
+    }
+
+    forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string
+        bytes byte_buf option unit unit_struct newtype_struct seq tuple
+        tuple_struct map enum identifier ignored_any
+    }
+}
+
+struct DeltaMapAccess<A> {
+    map: A,
+    fields: &'static [&'static str],
+    covered: Vec<&'static str>,
+    delta_done: bool,
+    baseline: std::vec::IntoIter<(MockValue, MockValue)>,
+    pending: Option<MockValue>,
+    human_readable: bool
+}
+
+impl<'de, A> DeltaMapAccess<A>
+    where A: MapAccess<'de>
+{
+    fn next_delta_field(&mut self) -> Result<Option<&'static str>, A::Error> {
+        if self.human_readable {
+            let Some(name) = self.map.next_key::<String>()? else { return Ok(None); };
+            return self.fields.iter().find(|field| **field == name).copied().map(Some).ok_or_else(|| A::Error::unknown_field(&name, self.fields));
+        } else {
+            let Some(index) = self.map.next_key::<u32>()? else { return Ok(None); };
+            return self.fields.get(index as usize).copied().map(Some).ok_or_else(|| crate::errors::invalid_value(
+                Unexpected::Unsigned(index as u64),
+                "a field index",
+                format_args!("a field index smaller than {}", self.fields.len())
+            ));
+        }
+    }
+}
+
+impl<'de, A> MapAccess<'de> for DeltaMapAccess<A>
+    where A: MapAccess<'de>
+{
+    type Error = A::Error;
+
+    fn next_key_seed<K>(&mut self, seed: K) -> Result<Option<K::Value>, A::Error>
+        where K: DeserializeSeed<'de>
+    {
+        if !self.delta_done {
+            match self.next_delta_field()? {
+                Some(field) => {
+                    self.covered.push(field);
+                    return seed.deserialize(StrDeserializer::<A::Error>::new(field)).map(Some);
+                },
+                None => self.delta_done = true
+            }
+        }
+        for (key, value) in self.baseline.by_ref() {
+            if matches!(&key, MockValue::Str(name) if self.covered.contains(&name.as_str())) {
+                continue;
+            }
+            self.pending = Some(value);
+            return seed.deserialize(IntoDeserializer::<A::Error>::into_deserializer(key)).map(Some);
+        }
+        return Ok(None);
+    }
+
+    fn next_value_seed<V>(&mut self, seed: V) -> Result<V::Value, A::Error>
+        where V: DeserializeSeed<'de>
+    {
+        match self.pending.take() {
+            Some(value) => seed.deserialize(IntoDeserializer::<A::Error>::into_deserializer(value).with_human_readable(self.human_readable)),
+            None => self.map.next_value_seed(seed)
+        }
+    }
+}
+
+#[cfg(test)]
+use crate::default_seed::seed_of;
+#[cfg(test)]
+use crate::impl_deserialize_seed_for_dependent_struct;
+#[cfg(test)]
+use std::marker::PhantomData;
+
+#[cfg(test)]
+#[derive(serde::Serialize, serde::Deserialize, Clone, Debug, PartialEq)]
+struct Foo {
+    a: i64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    b: Option<String>,
+    c: Vec<u8>
+}
+
+#[cfg(test)]
+struct DeserializeSeedFoo;
+
+#[cfg(test)]
+impl_deserialize_seed_for_dependent_struct!{
+    pub struct DeserializedFoo<'de> using DeserializeSeedFoo {
+        a: i64: |_| seed_of::<i64>(),
+        b: Option<String>: |_| seed_of::<Option<String>>(),
+        c: Vec<u8>: |_| seed_of::<Vec<u8>>()
+    }
+}
+
+#[test]
+fn test_serde_json() {
+    let baseline = Foo { a: 1, b: Some("x".to_owned()), c: vec![1, 2] };
+    let current = Foo { a: 1, b: Some("y".to_owned()), c: vec![1, 2] };
+    let serialized = serde_json::to_string(&SerializableDelta::new(&current, &baseline)).unwrap();
+    assert_eq!("{\"b\":\"y\"}", serialized);
+    assert_eq!(current, DeserializeSeedDelta::new(PhantomData::<Foo>, &baseline).deserialize(&mut serde_json::Deserializer::from_str(&serialized)).unwrap());
+
+    let result = DeserializeSeedDelta::new(DeserializeSeedFoo, &baseline).deserialize(&mut serde_json::Deserializer::from_str(&serialized)).unwrap();
+    assert_eq!((1, Some("y".to_owned()), vec![1, 2]), (result.a, result.b, result.c));
+
+    assert_eq!("{}", serde_json::to_string(&SerializableDelta::new(&baseline, &baseline)).unwrap());
+    assert_eq!(baseline, DeserializeSeedDelta::new(PhantomData::<Foo>, &baseline).deserialize(&mut serde_json::Deserializer::from_str("{}")).unwrap());
+    assert!(DeserializeSeedDelta::new(PhantomData::<Foo>, &baseline).deserialize(&mut serde_json::Deserializer::from_str("{\"d\": 1}")).is_err());
+
+    assert!(serde_json::to_string(&SerializableDelta::new(&5, &6)).is_err());
+    assert!(DeserializeSeedDelta::new(PhantomData::<i64>, &5).deserialize(&mut serde_json::Deserializer::from_str("{}")).is_err());
+}
+
+#[test]
+fn test_serde_postcard() {
+    let baseline = Foo { a: 1, b: None, c: (0..100).collect() };
+    let current = Foo { a: 2, b: Some("z".to_owned()), c: (0..100).collect() };
+    let serialized = postcard::to_allocvec(&SerializableDelta::new(&current, &baseline)).unwrap();
+    assert!(serialized.len() < 10);
+    assert_eq!(current, DeserializeSeedDelta::new(PhantomData::<Foo>, &baseline).deserialize(&mut postcard::Deserializer::from_bytes(&serialized)).unwrap());
+
+    let result = DeserializeSeedDelta::new(DeserializeSeedFoo, &baseline).deserialize(&mut postcard::Deserializer::from_bytes(&serialized)).unwrap();
+    assert_eq!((2, Some("z".to_owned()), (0..100).collect::<Vec<_>>()), (result.a, result.b, result.c));
+
+    let serialized = postcard::to_allocvec(&[(1u8, 3u8), (5, 0)].into_iter().collect::<std::collections::BTreeMap<_, _>>()).unwrap();
+    assert!(DeserializeSeedDelta::new(PhantomData::<Foo>, &baseline).deserialize(&mut postcard::Deserializer::from_bytes(&serialized)).is_err());
+}
@@ -3,6 +3,8 @@ use std::marker::PhantomData;
 use serde::de::{Error, DeserializeSeed, SeqAccess, Visitor};
 use serde::Deserializer;
 
+use crate::into_seed::IntoSeed;
+
 ///
 /// A [`DeserializeSeed`] that deserializes a tuple by deserializing its
 /// first element with the given [`DeserializeSeed`], deriving another
@@ -14,13 +16,13 @@ use serde::Deserializer;
 /// ```
 /// # use feanor_serde::seq::*;
 /// # use feanor_serde::dependent_tuple::*;
-/// # use std::marker::PhantomData;
+/// # use feanor_serde::default_seed::seed_of;
 /// # use serde::de::DeserializeSeed;
 /// let mut deserializer = serde_json::Deserializer::new(serde_json::de::StrRead::new("[3, [0, 0, 0]]"));
 /// let deserialize_seed = DeserializeSeedDependentTuple::new(
-///     PhantomData::<usize>,
+///     seed_of::<usize>(),
 ///     |len| DeserializeSeedSeq::new(
-///         (0..).map(|_| PhantomData::<i64>), 
+///         (0..).map(|_| seed_of::<i64>()), 
 ///         Vec::with_capacity(len),
 ///         |mut current, next| { current.push(next); current }
 ///     )
@@ -28,31 +30,76 @@ use serde::Deserializer;
 /// assert_eq!(vec![0, 0, 0], deserialize_seed.deserialize(&mut deserializer).unwrap());
 /// ```
 /// 
-pub struct DeserializeSeedDependentTuple<'de, T0, F, T1>
-    where T0: DeserializeSeed<'de>,
-        T1: DeserializeSeed<'de>,
-        F: FnOnce(T0::Value) -> T1
-{
-    deserializer: PhantomData<&'de ()>,
+pub struct DeserializeSeedDependentTuple<T0, F, T1> {
+    second: PhantomData<T1>,
     first: T0,
     derive_second: F
 }
 
-impl<'de, T0, F, T1> DeserializeSeedDependentTuple<'de, T0, F, T1>
+impl<'de, T0, F, T1> DeserializeSeedDependentTuple<T0, F, T1>
     where T0: DeserializeSeed<'de>,
         T1: DeserializeSeed<'de>,
         F: FnOnce(T0::Value) -> T1
 {
-    pub fn new(first: T0, derive_second: F) -> Self {
+    pub fn new<I, M>(first: I, derive_second: F) -> Self
+        where I: IntoSeed<'de, M, Seed = T0>
+    {
         Self {
-            deserializer: PhantomData,
-            first: first,
+            second: PhantomData,
+            first: first.into_seed(),
             derive_second: derive_second
         }
     }
+
+    ///
+    /// Boxes the function deriving the second seed, so that the resulting type does not
+    /// depend on its type anymore, see [`crate::seq::BoxedDeserializeSeedSeq`].
+    ///
+    pub fn boxed<'a>(self) -> BoxedDeserializeSeedDependentTuple<'a, T0, T1, T0::Value>
+        where F: 'a
+    {
+        DeserializeSeedDependentTuple {
+            second: PhantomData,
+            first: self.first,
+            derive_second: Box::new(self.derive_second)
+        }
+    }
+}
+
+///
+/// A [`DeserializeSeedDependentTuple`] whose function deriving the second seed is a trait
+/// object, as returned by [`DeserializeSeedDependentTuple::boxed()`]. Here `V0` is the type
+/// of the values produced by the seed `T0`.
+///
+pub type BoxedDeserializeSeedDependentTuple<'a, T0, T1, V0> = DeserializeSeedDependentTuple<T0, Box<dyn FnOnce(V0) -> T1 + 'a>, T1>;
+
+impl<T0, F, T1> Clone for DeserializeSeedDependentTuple<T0, F, T1>
+    where T0: Clone,
+        F: Clone
+{
+    fn clone(&self) -> Self {
+        Self {
+            second: PhantomData,
+            first: self.first.clone(),
+            derive_second: self.derive_second.clone()
+        }
+    }
 }
 
-impl<'de, T0, F, T1> DeserializeSeed<'de> for DeserializeSeedDependentTuple<'de, T0, F, T1>
+impl<T0, F, T1> Copy for DeserializeSeedDependentTuple<T0, F, T1>
+    where T0: Copy,
+        F: Copy
+{}
+
+impl<T0, F, T1> std::fmt::Debug for DeserializeSeedDependentTuple<T0, F, T1>
+    where T0: std::fmt::Debug
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("DeserializeSeedDependentTuple").field("first", &self.first).finish_non_exhaustive()
+    }
+}
+
+impl<'de, T0, F, T1> DeserializeSeed<'de> for DeserializeSeedDependentTuple<T0, F, T1>
     where T0: DeserializeSeed<'de>,
         T1: DeserializeSeed<'de>,
         F: FnOnce(T0::Value) -> T1
@@ -141,4 +188,13 @@ fn test_serde_json() {
         &mut serde_json::Deserializer::from_str(&serialized)
     ).unwrap();
     assert_eq!(data.1, result);
-}
\ No newline at end of file
+}
+
+#[test]
+fn test_boxed() {
+    let seeds: [BoxedDeserializeSeedDependentTuple<_, crate::seq::BoxedDeserializeSeedSeq<_, i64, i64>, usize>; 2] = [
+        DeserializeSeedDependentTuple::new(PhantomData::<usize>, |len| DeserializeSeedSeq::new(std::iter::repeat(PhantomData::<i64>), len as i64, |current, next| current + next).boxed()).boxed(),
+        DeserializeSeedDependentTuple::new(PhantomData::<usize>, |len| DeserializeSeedSeq::new(std::iter::repeat(PhantomData::<i64>), 0, move |current, next| current + next * len as i64).boxed()).boxed()
+    ];
+    assert_eq!([5, 6], seeds.map(|seed| seed.deserialize(&mut serde_json::Deserializer::from_str("[2, [1, 2]]")).unwrap()));
+}
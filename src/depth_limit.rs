@@ -0,0 +1,251 @@
+use std::cell::Cell;
+
+use serde::de::DeserializeSeed;
+use serde::ser::{Serialize, Serializer};
+use serde::Deserializer;
+
+use crate::into_seed::IntoSeed;
+
+///
+/// A counter for the current nesting depth, shared between all [`DepthLimited`]s
+/// resp. [`DepthLimitedSeed`]s that refer to it.
+///
+/// When (de)serializing recursive structures, wrap each recursive (de)serialization
+/// in a [`DepthLimited`] resp. [`DepthLimitedSeed`] referring to the same [`DepthLimit`].
+/// Then (de)serialization fails with an error, instead of overflowing the stack, once
+/// the nesting depth exceeds the limit.
+///
+#[derive(Debug)]
+pub struct DepthLimit {
+    max_depth: usize,
+    current_depth: Cell<usize>
+}
+
+impl DepthLimit {
+
+    pub fn new(max_depth: usize) -> Self {
+        Self { max_depth, current_depth: Cell::new(0) }
+    }
+
+    pub fn max_depth(&self) -> usize {
+        self.max_depth
+    }
+
+    ///
+    /// Returns the number of [`DepthLimited`]s resp. [`DepthLimitedSeed`]s that are
+    /// currently being (de)serialized.
+    ///
+    pub fn current_depth(&self) -> usize {
+        self.current_depth.get()
+    }
+
+    fn enter(&self) -> Option<DepthGuard<'_>> {
+        if self.current_depth.get() >= self.max_depth {
+            return None;
+        }
+        self.current_depth.set(self.current_depth.get() + 1);
+        return Some(DepthGuard { limit: self });
+    }
+}
+
+///
+/// Decrements the depth again when dropped, so that the counter remains correct
+/// even if (de)serialization fails or panics.
+///
+struct DepthGuard<'a> {
+    limit: &'a DepthLimit
+}
+
+impl<'a> Drop for DepthGuard<'a> {
+
+    fn drop(&mut self) {
+        self.limit.current_depth.set(self.limit.current_depth.get() - 1);
+    }
+}
+
+///
+/// Wraps a serializable value, and serializes it in the same way, but fails if the
+/// nesting depth of the associated [`DepthLimit`] would exceed its maximum.
+///
+pub struct DepthLimited<'a, T>
+    where T: Serialize
+{
+    data: T,
+    limit: &'a DepthLimit
+}
+
+impl<'a, T> DepthLimited<'a, T>
+    where T: Serialize
+{
+    pub const fn new(data: T, limit: &'a DepthLimit) -> Self {
+        Self { data, limit }
+    }
+}
+
+impl<'a, T> Serialize for DepthLimited<'a, T>
+    where T: Serialize
+{
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+        where S: Serializer
+    {
+        let _guard = self.limit.enter().ok_or_else(|| <S::Error as serde::ser::Error>::custom(format!("exceeded maximal nesting depth {}", self.limit.max_depth)))?;
+        return self.data.serialize(serializer);
+    }
+}
+
+///
+/// Wraps a [`DeserializeSeed`], and deserializes in the same way, but fails if the
+/// nesting depth of the associated [`DepthLimit`] would exceed its maximum.
+///
+/// # Example
+/// ```
+/// # use feanor_serde::depth_limit::*;
+/// # use feanor_serde::seq::*;
+/// # use serde::de::DeserializeSeed;
+/// # use serde::Deserializer;
+/// # use std::iter::repeat_with;
+/// // deserializes nested lists like `[[], [[]]]` and returns their maximal depth
+/// struct DeserializeSeedNested<'a>(&'a DepthLimit);
+///
+/// impl<'a, 'de> DeserializeSeed<'de> for DeserializeSeedNested<'a> {
+///     type Value = usize;
+///
+///     fn deserialize<D>(self, deserializer: D) -> Result<usize, D::Error>
+///         where D: Deserializer<'de>
+///     {
+///         let limit = self.0;
+///         let children = DeserializeSeedSeq::new(
+///             repeat_with(|| DepthLimitedSeed::new(DeserializeSeedNested(limit), limit)),
+///             0,
+///             |current, next| current.max(next)
+///         );
+///         return Ok(children.deserialize(deserializer)? + 1);
+///     }
+/// }
+///
+/// let limit = DepthLimit::new(5);
+/// let mut deserializer = serde_json::Deserializer::new(serde_json::de::StrRead::new("[[], [[]]]"));
+/// assert_eq!(3, DeserializeSeedNested(&limit).deserialize(&mut deserializer).unwrap());
+/// let mut deserializer = serde_json::Deserializer::new(serde_json::de::StrRead::new("[[[[[[[]]]]]]]"));
+/// assert!(DeserializeSeedNested(&limit).deserialize(&mut deserializer).is_err());
+/// ```
+///
+pub struct DepthLimitedSeed<'a, S> {
+    seed: S,
+    limit: &'a DepthLimit
+}
+
+impl<'a, S> DepthLimitedSeed<'a, S> {
+
+    pub fn new<'de, I, M>(seed: I, limit: &'a DepthLimit) -> Self
+        where I: IntoSeed<'de, M, Seed = S>
+    {
+        Self { seed: seed.into_seed(), limit }
+    }
+}
+
+impl<'a, 'de, S> DeserializeSeed<'de> for DepthLimitedSeed<'a, S>
+    where S: DeserializeSeed<'de>
+{
+    type Value = S::Value;
+
+    fn deserialize<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+        where D: Deserializer<'de>
+    {
+        let _guard = self.limit.enter().ok_or_else(|| <D::Error as serde::de::Error>::custom(format!("exceeded maximal nesting depth {}", self.limit.max_depth)))?;
+        return self.seed.deserialize(deserializer);
+    }
+}
+
+#[cfg(test)]
+use crate::seq::{DeserializeSeedSeq, SerializableSeq};
+#[cfg(test)]
+use std::iter::repeat_with;
+
+#[cfg(test)]
+#[derive(Debug, PartialEq)]
+struct Tree(Vec<Tree>);
+
+#[cfg(test)]
+impl Tree {
+
+    fn nested(depth: usize) -> Tree {
+        if depth == 0 {
+            Tree(Vec::new())
+        } else {
+            Tree(vec![Tree(Vec::new()), Tree::nested(depth - 1)])
+        }
+    }
+}
+
+#[cfg(test)]
+struct SerializableTree<'a>(&'a Tree, &'a DepthLimit);
+
+#[cfg(test)]
+impl<'a> Serialize for SerializableTree<'a> {
+
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+        where S: Serializer
+    {
+        SerializableSeq::new_with_len(self.0.0.iter().map(|child| DepthLimited::new(SerializableTree(child, self.1), self.1)), self.0.0.len()).serialize(serializer)
+    }
+}
+
+#[cfg(test)]
+struct DeserializeSeedTree<'a>(&'a DepthLimit);
+
+#[cfg(test)]
+impl<'a, 'de> DeserializeSeed<'de> for DeserializeSeedTree<'a> {
+    type Value = Tree;
+
+    fn deserialize<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+        where D: Deserializer<'de>
+    {
+        let limit = self.0;
+        let children = DeserializeSeedSeq::new(
+            repeat_with(|| DepthLimitedSeed::new(DeserializeSeedTree(limit), limit)),
+            Vec::new(),
+            |mut current, next| { current.push(next); current }
+        );
+        return Ok(Tree(children.deserialize(deserializer)?));
+    }
+}
+
+#[test]
+fn test_serde_postcard() {
+    let limit = DepthLimit::new(10);
+    for depth in [0, 1, 5, 10] {
+        let data = Tree::nested(depth);
+        let serialized = postcard::to_allocvec(&SerializableTree(&data, &limit)).unwrap();
+        assert_eq!(0, limit.current_depth());
+        let result = DeserializeSeedTree(&limit).deserialize(
+            &mut postcard::Deserializer::from_flavor(postcard::de_flavors::Slice::new(&serialized))
+        ).unwrap();
+        assert_eq!(0, limit.current_depth());
+        assert_eq!(data, result);
+    }
+    assert!(postcard::to_allocvec(&SerializableTree(&Tree::nested(11), &limit)).is_err());
+    assert_eq!(0, limit.current_depth());
+
+    let serialized = postcard::to_allocvec(&SerializableTree(&Tree::nested(11), &DepthLimit::new(11))).unwrap();
+    assert!(DeserializeSeedTree(&limit).deserialize(
+        &mut postcard::Deserializer::from_flavor(postcard::de_flavors::Slice::new(&serialized))
+    ).is_err());
+    assert_eq!(0, limit.current_depth());
+}
+
+#[test]
+fn test_serde_json() {
+    let limit = DepthLimit::new(10);
+    for depth in [0, 1, 5, 10] {
+        let data = Tree::nested(depth);
+        let serialized = serde_json::to_string(&SerializableTree(&data, &limit)).unwrap();
+        let result = DeserializeSeedTree(&limit).deserialize(
+            &mut serde_json::Deserializer::from_str(&serialized)
+        ).unwrap();
+        assert_eq!(data, result);
+    }
+    let result = DeserializeSeedTree(&limit).deserialize(&mut serde_json::Deserializer::from_str(&format!("{}{}", "[".repeat(12), "]".repeat(12))));
+    assert!(result.unwrap_err().to_string().contains("nesting depth"));
+    assert_eq!(0, limit.current_depth());
+}
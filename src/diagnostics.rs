@@ -0,0 +1,205 @@
+use std::cell::RefCell;
+use std::fmt::Display;
+
+use serde::de::{DeserializeSeed, IgnoredAny};
+use serde::Deserializer;
+
+///
+/// The kind of a non-fatal issue encountered during deserialization.
+///
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DiagnosticKind {
+    UnknownField,
+    DeprecatedVariant,
+    ClampedValue,
+    Other
+}
+
+///
+/// A non-fatal issue encountered during deserialization, see [`Diagnostics`].
+///
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Diagnostic {
+    kind: DiagnosticKind,
+    message: String
+}
+
+impl Diagnostic {
+
+    pub fn new(kind: DiagnosticKind, message: String) -> Self {
+        Self { kind, message }
+    }
+
+    pub fn kind(&self) -> DiagnosticKind {
+        self.kind
+    }
+
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+}
+
+impl Display for Diagnostic {
+
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:?}: {}", self.kind, self.message)
+    }
+}
+
+///
+/// A sink for non-fatal issues encountered during deserialization, like unknown
+/// fields, deprecated variants or clamped values. Deserialization still succeeds,
+/// but the issues can afterwards be reported to the user.
+///
+/// Like [`crate::depth_limit::DepthLimit`], this is meant to be stored by reference in
+/// the [`DeserializeSeed`]s (or their context), so that all of them push to the same sink.
+///
+/// # Example
+/// ```
+/// # use feanor_serde::diagnostics::*;
+/// # use feanor_serde::seq::*;
+/// # use feanor_serde::default_seed::seed_of;
+/// # use std::iter::repeat;
+/// # use serde::de::DeserializeSeed;
+/// let diagnostics = Diagnostics::new();
+/// let mut deserializer = serde_json::Deserializer::new(serde_json::de::StrRead::new("[1, 300, 5]"));
+/// let deserialize_seed = DeserializeSeedSeq::new(
+///     repeat(seed_of::<i64>()),
+///     Vec::new(),
+///     |mut current, next| { current.push(diagnostics.clamp(next, 0, 255, "byte value")); current }
+/// );
+/// assert_eq!(vec![1, 255, 5], deserialize_seed.deserialize(&mut deserializer).unwrap());
+/// assert_eq!(1, diagnostics.len());
+/// assert_eq!(DiagnosticKind::ClampedValue, diagnostics.take()[0].kind());
+/// ```
+///
+#[derive(Debug, Default)]
+pub struct Diagnostics {
+    entries: RefCell<Vec<Diagnostic>>
+}
+
+impl Diagnostics {
+
+    pub fn new() -> Self {
+        Self { entries: RefCell::new(Vec::new()) }
+    }
+
+    pub fn push(&self, kind: DiagnosticKind, message: String) {
+        self.entries.borrow_mut().push(Diagnostic::new(kind, message));
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.borrow().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.borrow().is_empty()
+    }
+
+    ///
+    /// Returns all diagnostics pushed so far, and clears the sink.
+    ///
+    pub fn take(&self) -> Vec<Diagnostic> {
+        std::mem::take(&mut *self.entries.borrow_mut())
+    }
+
+    ///
+    /// Clamps `value` to the range `[min, max]`, and records a diagnostic of kind
+    /// [`DiagnosticKind::ClampedValue`] if this changed the value.
+    ///
+    pub fn clamp<T>(&self, value: T, min: T, max: T, name: &str) -> T
+        where T: PartialOrd + Display
+    {
+        if value < min {
+            self.push(DiagnosticKind::ClampedValue, format!("{} {} was clamped to {}", name, value, min));
+            return min;
+        } else if value > max {
+            self.push(DiagnosticKind::ClampedValue, format!("{} {} was clamped to {}", name, value, max));
+            return max;
+        } else {
+            return value;
+        }
+    }
+
+    ///
+    /// Returns a [`DeserializeSeed`] that skips over any value, and records a diagnostic
+    /// of the given kind. This is useful for ignoring e.g. unknown fields.
+    ///
+    pub fn ignore<'a>(&'a self, kind: DiagnosticKind, message: String) -> DeserializeSeedIgnored<'a> {
+        DeserializeSeedIgnored { diagnostics: self, kind: kind, message: message }
+    }
+}
+
+///
+/// A [`DeserializeSeed`] that skips over any value, and records a [`Diagnostic`].
+/// Created by [`Diagnostics::ignore()`].
+///
+pub struct DeserializeSeedIgnored<'a> {
+    diagnostics: &'a Diagnostics,
+    kind: DiagnosticKind,
+    message: String
+}
+
+impl<'a, 'de> DeserializeSeed<'de> for DeserializeSeedIgnored<'a> {
+    type Value = ();
+
+    fn deserialize<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+        where D: Deserializer<'de>
+    {
+        <IgnoredAny as serde::Deserialize>::deserialize(deserializer)?;
+        self.diagnostics.push(self.kind, self.message);
+        return Ok(());
+    }
+}
+
+#[cfg(test)]
+use std::marker::PhantomData;
+#[cfg(test)]
+use std::iter::repeat;
+#[cfg(test)]
+use crate::map::DeserializeSeedMap;
+
+#[cfg(test)]
+fn deserialize_known_fields<'de, 'a, D>(diagnostics: &'a Diagnostics, deserializer: D) -> Result<Vec<(String, i64)>, D::Error>
+    where D: Deserializer<'de>
+{
+    // deserialize the value of each entry as unit if the key is unknown
+    let mut known = Vec::new();
+    let result = DeserializeSeedMap::new(
+        repeat((PhantomData::<String>, PhantomData::<serde_json::Value>)),
+        (),
+        |(), key, value| if key == "a" || key == "b" {
+            known.push((key, value.as_i64().unwrap()));
+        } else {
+            diagnostics.ignore(DiagnosticKind::UnknownField, format!("unknown field {}", key)).deserialize(value).unwrap();
+        }
+    ).deserialize(deserializer);
+    result.map(|()| known)
+}
+
+#[test]
+fn test_serde_json() {
+    let diagnostics = Diagnostics::new();
+    let result = deserialize_known_fields(&diagnostics, &mut serde_json::Deserializer::from_str(r#"{"a":1,"c":[1,2],"b":2,"d":null}"#)).unwrap();
+    assert_eq!(vec![("a".to_owned(), 1), ("b".to_owned(), 2)], result);
+    let diagnostics = diagnostics.take();
+    assert_eq!(2, diagnostics.len());
+    assert_eq!(DiagnosticKind::UnknownField, diagnostics[0].kind());
+    assert_eq!("unknown field c", diagnostics[0].message());
+    assert_eq!("UnknownField: unknown field d", diagnostics[1].to_string());
+}
+
+#[test]
+fn test_serde_postcard() {
+    let diagnostics = Diagnostics::new();
+    let serialized = postcard::to_allocvec(&(-5i64, [1u8, 2, 3], 7i64)).unwrap();
+    let mut deserializer = postcard::Deserializer::from_flavor(postcard::de_flavors::Slice::new(&serialized));
+    let first = diagnostics.clamp(<i64 as serde::Deserialize>::deserialize(&mut deserializer).unwrap(), 0, 10, "first");
+    // postcard is not self-describing, so ignoring values is not supported
+    assert!(diagnostics.ignore(DiagnosticKind::Other, "ignored".to_owned()).deserialize(&mut deserializer).is_err());
+    assert_eq!(0, first);
+    assert_eq!(1, diagnostics.len());
+    assert!(!diagnostics.is_empty());
+    assert_eq!(1, diagnostics.take().len());
+    assert!(diagnostics.is_empty());
+}
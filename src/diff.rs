@@ -0,0 +1,166 @@
+use std::fmt::Display;
+
+use serde::Serialize;
+
+use crate::mock::{MockValue, MockVariant};
+use crate::path::{Path, Segment};
+
+///
+/// A single difference between two values, as reported by [`diff()`].
+///
+/// If `expected` is `None`, the value at `path` only exists in the found value, e.g. an additional
+/// sequence element or map entry. Similarly, if `found` is `None`, the value at `path` is missing
+/// in the found value.
+///
+#[derive(Clone, Debug, PartialEq)]
+pub struct DiffEntry {
+    pub path: Path,
+    pub expected: Option<MockValue>,
+    pub found: Option<MockValue>
+}
+
+impl Display for DiffEntry {
+
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match (&self.expected, &self.found) {
+            (Some(expected), Some(found)) => write!(f, "at {}: expected {}, found {}", self.path, expected, found),
+            (Some(expected), None) => write!(f, "at {}: missing {}", self.path, expected),
+            (None, Some(found)) => write!(f, "at {}: unexpected {}", self.path, found),
+            (None, None) => write!(f, "at {}: no difference", self.path)
+        }
+    }
+}
+
+///
+/// Serializes both values into their [`MockValue`] representation, and returns all places
+/// in which they differ. This is much easier to read than comparing the serialized bytes,
+/// e.g. when a roundtrip through a binary format changed some value.
+///
+/// Sequences are compared element-wise, maps are compared by matching entries with equal
+/// keys (in order, if keys are duplicated), and enums are compared by their content if they
+/// have the same variant. Values that differ in any other way are reported as a whole.
+///
+/// # Example
+/// ```
+/// # use feanor_serde::diff::*;
+/// # use std::collections::BTreeMap;
+/// let expected = BTreeMap::from([("a", vec![1, 2]), ("b", vec![3])]);
+/// let found = BTreeMap::from([("a", vec![1, 4]), ("c", vec![3])]);
+/// let differences = diff(&expected, &found).unwrap();
+/// assert_eq!(
+///     vec!["at .a[1]: expected 2, found 4", "at .b: missing [3]", "at .c: unexpected [3]"],
+///     differences.iter().map(|entry| entry.to_string()).collect::<Vec<_>>()
+/// );
+/// ```
+///
+pub fn diff<A, B>(expected: &A, found: &B) -> Result<Vec<DiffEntry>, serde::de::value::Error>
+    where A: ?Sized + Serialize,
+        B: ?Sized + Serialize
+{
+    return Ok(diff_values(&MockValue::from_serialize(expected)?, &MockValue::from_serialize(found)?));
+}
+
+///
+/// Returns all places in which the given [`MockValue`]s differ, see [`diff()`].
+///
+pub fn diff_values(expected: &MockValue, found: &MockValue) -> Vec<DiffEntry> {
+    let mut result = Vec::new();
+    diff_at(&mut Vec::new(), expected, found, &mut result);
+    return result;
+}
+
+fn report(path: &[Segment], expected: Option<&MockValue>, found: Option<&MockValue>, result: &mut Vec<DiffEntry>) {
+    result.push(DiffEntry { path: Path::from_segments(path.to_vec()), expected: expected.cloned(), found: found.cloned() });
+}
+
+fn key_name(key: &MockValue) -> String {
+    match key {
+        MockValue::Str(key) => key.clone(),
+        key => key.to_string()
+    }
+}
+
+fn diff_at(path: &mut Vec<Segment>, expected: &MockValue, found: &MockValue, result: &mut Vec<DiffEntry>) {
+    match (expected, found) {
+        (MockValue::Some(expected), MockValue::Some(found)) | (MockValue::NewtypeStruct(expected), MockValue::NewtypeStruct(found)) => diff_at(path, expected, found, result),
+        (MockValue::Seq(expected), MockValue::Seq(found)) => diff_seq(path, expected, found, result),
+        (MockValue::Map(expected), MockValue::Map(found)) => diff_map(path, expected, found, result),
+        (MockValue::Enum(expected_variant, expected_content), MockValue::Enum(found_variant, found_content)) if expected_variant == found_variant => {
+            path.push(Segment::Enum { variant: key_name(expected_variant) });
+            match (expected_content, found_content) {
+                (MockVariant::Unit, MockVariant::Unit) => {},
+                (MockVariant::Newtype(expected_value), MockVariant::Newtype(found_value)) => diff_at(path, expected_value, found_value, result),
+                (MockVariant::Tuple(expected_elements), MockVariant::Tuple(found_elements)) => diff_seq(path, expected_elements, found_elements, result),
+                (MockVariant::Struct(expected_entries), MockVariant::Struct(found_entries)) => diff_map(path, expected_entries, found_entries, result),
+                _ => {
+                    path.pop();
+                    report(path, Some(expected), Some(found), result);
+                    return;
+                }
+            }
+            path.pop();
+        },
+        (expected, found) => if expected != found {
+            report(path, Some(expected), Some(found), result);
+        }
+    }
+}
+
+fn diff_seq(path: &mut Vec<Segment>, expected: &[MockValue], found: &[MockValue], result: &mut Vec<DiffEntry>) {
+    for i in 0..expected.len().max(found.len()) {
+        path.push(Segment::Seq { index: i });
+        match (expected.get(i), found.get(i)) {
+            (Some(expected), Some(found)) => diff_at(path, expected, found, result),
+            (expected, found) => report(path, expected, found, result)
+        }
+        path.pop();
+    }
+}
+
+fn diff_map(path: &mut Vec<Segment>, expected: &[(MockValue, MockValue)], found: &[(MockValue, MockValue)], result: &mut Vec<DiffEntry>) {
+    let mut matched = vec![false; found.len()];
+    for (key, expected_value) in expected {
+        path.push(Segment::Map { key: key_name(key) });
+        match (0..found.len()).find(|i| !matched[*i] && found[*i].0 == *key) {
+            Some(i) => {
+                matched[i] = true;
+                diff_at(path, expected_value, &found[i].1, result);
+            },
+            None => report(path, Some(expected_value), None, result)
+        }
+        path.pop();
+    }
+    for ((key, found_value), _) in found.iter().zip(matched.iter()).filter(|(_, matched)| !**matched) {
+        path.push(Segment::Map { key: key_name(key) });
+        report(path, None, Some(found_value), result);
+        path.pop();
+    }
+}
+
+#[test]
+fn test_diff() {
+    #[derive(serde::Serialize)]
+    enum Foo {
+        A(i64),
+        B { x: i64, y: Vec<i64> }
+    }
+    assert_eq!(Vec::<DiffEntry>::new(), diff(&[Foo::A(1), Foo::B { x: 1, y: vec![1, 2] }], &[Foo::A(1), Foo::B { x: 1, y: vec![1, 2] }]).unwrap());
+
+    let differences = diff(&[Foo::A(1), Foo::B { x: 1, y: vec![1, 2] }], &[Foo::B { x: 1, y: Vec::new() }, Foo::B { x: 2, y: vec![1] }]).unwrap();
+    assert_eq!(3, differences.len());
+    assert_eq!("at [0]: expected A(1), found B { \"x\": 1, \"y\": [] }", differences[0].to_string());
+    assert_eq!("at [1].B.x: expected 1, found 2", differences[1].to_string());
+    assert_eq!("at [1].B.y[1]: missing 2", differences[2].to_string());
+    assert_eq!(None, differences[2].found);
+}
+
+#[test]
+fn test_diff_duplicate_keys() {
+    let expected = MockValue::map([("a", 1i64), ("a", 2)]);
+    let found = MockValue::map([("a", 1i64), ("b", 3), ("a", 3), ("a", 4)]);
+    assert_eq!(vec![
+        "at .a: expected 2, found 3",
+        "at .b: unexpected 3",
+        "at .a: unexpected 4"
+    ], diff_values(&expected, &found).iter().map(|entry| entry.to_string()).collect::<Vec<_>>());
+}
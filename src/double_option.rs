@@ -0,0 +1,314 @@
+use serde::de::{DeserializeSeed, Error, Visitor};
+use serde::{Deserializer, Serialize, Serializer};
+
+use crate::into_seed::IntoSeed;
+use crate::rust_struct::MISSING_FIELD_MARKER;
+
+///
+/// A value that may be missing, explicitly `null`, or present, modeling the semantics of
+/// `Option<Option<T>>` for struct fields. This is useful e.g. for patch-style APIs, where
+/// a missing field means "don't touch", and a `null` field means "clear this field".
+///
+/// When used as a field in [`crate::impl_deserialize_seed_for_dependent_struct!`] with
+/// [`DeserializeSeedDoubleOption`], missing fields are deserialized as [`DoubleOption::Missing`].
+/// Both [`DoubleOption::Missing`] and [`DoubleOption::Null`] are serialized as none, so
+/// missing fields should be skipped during serialization, e.g. using
+/// `#[serde(skip_serializing_if = "DoubleOption::is_missing")]`.
+///
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum DoubleOption<T> {
+    #[default]
+    Missing,
+    Null,
+    Value(T)
+}
+
+impl<T> DoubleOption<T> {
+
+    pub fn is_missing(&self) -> bool {
+        matches!(self, DoubleOption::Missing)
+    }
+
+    pub fn is_null(&self) -> bool {
+        matches!(self, DoubleOption::Null)
+    }
+
+    pub fn as_ref(&self) -> DoubleOption<&T> {
+        match self {
+            DoubleOption::Missing => DoubleOption::Missing,
+            DoubleOption::Null => DoubleOption::Null,
+            DoubleOption::Value(value) => DoubleOption::Value(value)
+        }
+    }
+
+    pub fn map<U, F>(self, f: F) -> DoubleOption<U>
+        where F: FnOnce(T) -> U
+    {
+        match self {
+            DoubleOption::Missing => DoubleOption::Missing,
+            DoubleOption::Null => DoubleOption::Null,
+            DoubleOption::Value(value) => DoubleOption::Value(f(value))
+        }
+    }
+
+    ///
+    /// Applies this patch to the given value, i.e. leaves it unchanged if this is
+    /// [`DoubleOption::Missing`], and otherwise replaces it.
+    ///
+    pub fn apply_to(self, target: &mut Option<T>) {
+        match self {
+            DoubleOption::Missing => {},
+            DoubleOption::Null => *target = None,
+            DoubleOption::Value(value) => *target = Some(value)
+        }
+    }
+}
+
+impl<T> From<Option<Option<T>>> for DoubleOption<T> {
+
+    fn from(value: Option<Option<T>>) -> Self {
+        match value {
+            None => DoubleOption::Missing,
+            Some(None) => DoubleOption::Null,
+            Some(Some(value)) => DoubleOption::Value(value)
+        }
+    }
+}
+
+impl<T> From<DoubleOption<T>> for Option<Option<T>> {
+
+    fn from(value: DoubleOption<T>) -> Self {
+        match value {
+            DoubleOption::Missing => None,
+            DoubleOption::Null => Some(None),
+            DoubleOption::Value(value) => Some(Some(value))
+        }
+    }
+}
+
+impl<T> Serialize for DoubleOption<T>
+    where T: Serialize
+{
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+        where S: Serializer
+    {
+        match self {
+            DoubleOption::Missing | DoubleOption::Null => serializer.serialize_none(),
+            DoubleOption::Value(value) => serializer.serialize_some(value)
+        }
+    }
+}
+
+///
+/// A [`DeserializeSeed`] that deserializes a [`DoubleOption`], using the given [`DeserializeSeed`]
+/// for the value if it is present.
+///
+/// Within the format, this is an option, wrapped in a newtype struct with a private name. Since
+/// formats usually treat newtype structs as transparent, this does not change the representation.
+/// The case [`DoubleOption::Missing`] only occurs when used for a missing field in
+/// [`crate::impl_deserialize_seed_for_dependent_struct!`], see also
+/// [`crate::rust_struct::MissingFieldDeserializer`]. In particular, an explicit `null` is
+/// always deserialized as [`DoubleOption::Null`], even if the format reports it as unit.
+///
+/// # Example
+/// ```
+/// # use feanor_serde::impl_deserialize_seed_for_dependent_struct;
+/// # use feanor_serde::double_option::*;
+/// # use serde::de::DeserializeSeed;
+/// # use feanor_serde::default_seed::seed_of;
+/// struct PatchDeserializeSeed;
+/// impl_deserialize_seed_for_dependent_struct!{
+///     pub struct Patch<'de> using PatchDeserializeSeed {
+///         name: DoubleOption<String>: |_| DeserializeSeedDoubleOption::new(seed_of::<String>()),
+///         age: DoubleOption<u32>: |_| DeserializeSeedDoubleOption::new(seed_of::<u32>())
+///     }
+/// }
+/// let mut deserializer = serde_json::Deserializer::new(serde_json::de::StrRead::new(r#"{"age": null}"#));
+/// let patch = PatchDeserializeSeed.deserialize(&mut deserializer).unwrap();
+/// assert_eq!(DoubleOption::Missing, patch.name);
+/// assert_eq!(DoubleOption::Null, patch.age);
+/// ```
+///
+#[derive(Clone, Copy)]
+pub struct DeserializeSeedDoubleOption<S> {
+    seed: S
+}
+
+impl<S> DeserializeSeedDoubleOption<S> {
+
+    pub fn new<'de, I, M>(seed: I) -> Self
+        where I: IntoSeed<'de, M, Seed = S>
+    {
+        Self { seed: seed.into_seed() }
+    }
+}
+
+impl<'de, S> DeserializeSeed<'de> for DeserializeSeedDoubleOption<S>
+    where S: DeserializeSeed<'de>
+{
+    type Value = DoubleOption<S::Value>;
+
+    fn deserialize<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+        where D: Deserializer<'de>
+    {
+        struct OptionVisitor<S> {
+            seed: S
+        }
+
+        impl<'de, S> Visitor<'de> for OptionVisitor<S>
+            where S: DeserializeSeed<'de>
+        {
+            type Value = DoubleOption<S::Value>;
+
+            fn expecting(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+                write!(f, "an optional value")
+            }
+
+            fn visit_none<E>(self) -> Result<Self::Value, E>
+                where E: Error
+            {
+                Ok(DoubleOption::Null)
+            }
+
+            fn visit_unit<E>(self) -> Result<Self::Value, E>
+                where E: Error
+            {
+                Ok(DoubleOption::Null)
+            }
+
+            fn visit_some<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+                where D: Deserializer<'de>
+            {
+                self.seed.deserialize(deserializer).map(DoubleOption::Value)
+            }
+        }
+
+        // formats are transparent w.r.t. newtype structs, only `MissingFieldDeserializer`
+        // answers a request for the marker newtype struct with `visit_unit()`
+        struct MarkerVisitor<S> {
+            seed: S
+        }
+
+        impl<'de, S> Visitor<'de> for MarkerVisitor<S>
+            where S: DeserializeSeed<'de>
+        {
+            type Value = DoubleOption<S::Value>;
+
+            fn expecting(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+                write!(f, "an optional value")
+            }
+
+            fn visit_unit<E>(self) -> Result<Self::Value, E>
+                where E: Error
+            {
+                Ok(DoubleOption::Missing)
+            }
+
+            fn visit_newtype_struct<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+                where D: Deserializer<'de>
+            {
+                deserializer.deserialize_option(OptionVisitor { seed: self.seed })
+            }
+
+            fn visit_none<E>(self) -> Result<Self::Value, E>
+                where E: Error
+            {
+                OptionVisitor { seed: self.seed }.visit_none()
+            }
+
+            fn visit_some<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+                where D: Deserializer<'de>
+            {
+                OptionVisitor { seed: self.seed }.visit_some(deserializer)
+            }
+        }
+
+        return deserializer.deserialize_newtype_struct(MISSING_FIELD_MARKER, MarkerVisitor { seed: self.seed });
+    }
+}
+
+#[cfg(test)]
+use std::marker::PhantomData;
+#[cfg(test)]
+use crate::impl_deserialize_seed_for_dependent_struct;
+
+#[cfg(test)]
+#[derive(Serialize)]
+#[serde(rename = "Patch")]
+struct SerializablePatch {
+    #[serde(skip_serializing_if = "DoubleOption::is_missing")]
+    a: DoubleOption<i64>,
+    #[serde(skip_serializing_if = "DoubleOption::is_missing")]
+    b: DoubleOption<i64>
+}
+
+#[cfg(test)]
+struct DeserializeSeedPatch;
+
+#[cfg(test)]
+impl_deserialize_seed_for_dependent_struct!{
+    pub struct Patch<'de> using DeserializeSeedPatch {
+        a: DoubleOption<i64>: |_| DeserializeSeedDoubleOption::new(PhantomData::<i64>),
+        b: DoubleOption<i64>: |_| DeserializeSeedDoubleOption::new(PhantomData::<i64>)
+    }
+}
+
+#[test]
+fn test_serde_json() {
+    for (a, b) in [(DoubleOption::Missing, DoubleOption::Null), (DoubleOption::Value(1), DoubleOption::Missing), (DoubleOption::Null, DoubleOption::Value(2))] {
+        let serialized = serde_json::to_string(&SerializablePatch { a: a, b: b }).unwrap();
+        let result = DeserializeSeedPatch.deserialize(&mut serde_json::Deserializer::from_str(&serialized)).unwrap();
+        assert_eq!(a, result.a);
+        assert_eq!(b, result.b);
+    }
+    assert_eq!(r#"{"b":null}"#, serde_json::to_string(&SerializablePatch { a: DoubleOption::Missing, b: DoubleOption::Null }).unwrap());
+
+    let mut target = Some(5);
+    DoubleOption::Missing.apply_to(&mut target);
+    assert_eq!(Some(5), target);
+    DoubleOption::Null.apply_to(&mut target);
+    assert_eq!(None, target);
+    DoubleOption::Value(3).apply_to(&mut target);
+    assert_eq!(Some(3), target);
+    assert_eq!(Some(None), Option::<Option<i64>>::from(DoubleOption::<i64>::Null));
+    assert_eq!(DoubleOption::Value(2), DoubleOption::from(Some(Some(1))).map(|x| x + 1));
+}
+
+#[test]
+fn test_serde_postcard() {
+    let serialized = postcard::to_allocvec(&(DoubleOption::<i64>::Null, DoubleOption::Value(5i64))).unwrap();
+    let mut deserializer = postcard::Deserializer::from_flavor(postcard::de_flavors::Slice::new(&serialized));
+    assert_eq!(DoubleOption::Null, DeserializeSeedDoubleOption::new(PhantomData::<i64>).deserialize(&mut deserializer).unwrap());
+    assert_eq!(DoubleOption::Value(5), DeserializeSeedDoubleOption::new(PhantomData::<i64>).deserialize(&mut deserializer).unwrap());
+}
+
+#[test]
+fn test_buffered() {
+
+    #[derive(Debug)]
+    struct BufferedPatch(DoubleOption<i64>, DoubleOption<i64>);
+
+    impl<'de> serde::Deserialize<'de> for BufferedPatch {
+
+        fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+            where D: Deserializer<'de>
+        {
+            DeserializeSeedPatch.deserialize(deserializer).map(|patch| BufferedPatch(patch.a, patch.b))
+        }
+    }
+
+    // untagged enums first buffer the input, which reports `null` as unit
+    #[derive(serde::Deserialize)]
+    #[serde(untagged)]
+    enum Untagged {
+        Patch(BufferedPatch)
+    }
+
+    let Untagged::Patch(result) = serde_json::from_str(r#"{"b":null}"#).unwrap();
+    assert_eq!(DoubleOption::Missing, result.0);
+    assert_eq!(DoubleOption::Null, result.1);
+
+    let Untagged::Patch(result) = serde_json::from_str(r#"{"a":null,"b":3}"#).unwrap();
+    assert_eq!(DoubleOption::Null, result.0);
+    assert_eq!(DoubleOption::Value(3), result.1);
+}
@@ -0,0 +1,327 @@
+use std::iter::repeat;
+
+use serde::de::{DeserializeSeed, MapAccess, SeqAccess, Visitor};
+use serde::{Deserialize, Deserializer};
+
+use crate::raw_bytes::ByteBufSeed;
+use crate::seq::DeserializeSeedSeq;
+
+///
+/// The type of a value whose layout is only known at runtime, as used by [`DeserializeSeedDyn`].
+///
+#[derive(Clone, Debug, PartialEq)]
+pub enum DynType {
+    Bool,
+    I64,
+    U64,
+    F64,
+    String,
+    ///
+    /// A byte buffer, which may also be represented as a sequence of bytes, e.g. in JSON.
+    ///
+    Bytes,
+    ///
+    /// A sequence of values of the given type.
+    ///
+    Seq(Box<DynType>),
+    ///
+    /// A struct with the given fields, see [`DeserializeSeedDynRecord`].
+    ///
+    Record(Vec<FieldSpec>)
+}
+
+///
+/// The description of a single field of a struct whose layout is only known at runtime.
+///
+#[derive(Clone, Debug, PartialEq)]
+pub struct FieldSpec {
+    pub name: String,
+    pub ty: DynType,
+    ///
+    /// Whether the field is an option, in which case it may be `null` or missing,
+    /// and is then deserialized as [`DynValue::Null`].
+    ///
+    pub optional: bool
+}
+
+impl FieldSpec {
+
+    pub fn new(name: &str, ty: DynType) -> Self {
+        Self { name: name.to_owned(), ty: ty, optional: false }
+    }
+
+    pub fn optional(self) -> Self {
+        Self { optional: true, ..self }
+    }
+}
+
+///
+/// A value deserialized by [`DeserializeSeedDyn`].
+///
+#[derive(Clone, Debug, PartialEq)]
+pub enum DynValue {
+    ///
+    /// The value of an optional field that is `null` or missing.
+    ///
+    Null,
+    Bool(bool),
+    I64(i64),
+    U64(u64),
+    F64(f64),
+    String(String),
+    Bytes(Vec<u8>),
+    Seq(Vec<DynValue>),
+    ///
+    /// The values of the fields of a struct, in the order in which they are specified.
+    ///
+    Record(Vec<DynValue>)
+}
+
+///
+/// A [`DeserializeSeed`] that deserializes a value of the given [`DynType`].
+///
+#[derive(Clone, Copy, Debug)]
+pub struct DeserializeSeedDyn<'a> {
+    ty: &'a DynType
+}
+
+impl<'a> DeserializeSeedDyn<'a> {
+
+    pub const fn new(ty: &'a DynType) -> Self {
+        Self { ty }
+    }
+}
+
+impl<'a, 'de> DeserializeSeed<'de> for DeserializeSeedDyn<'a> {
+
+    type Value = DynValue;
+
+    fn deserialize<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+        where D: Deserializer<'de>
+    {
+        match self.ty {
+            DynType::Bool => bool::deserialize(deserializer).map(DynValue::Bool),
+            DynType::I64 => i64::deserialize(deserializer).map(DynValue::I64),
+            DynType::U64 => u64::deserialize(deserializer).map(DynValue::U64),
+            DynType::F64 => f64::deserialize(deserializer).map(DynValue::F64),
+            DynType::String => String::deserialize(deserializer).map(DynValue::String),
+            DynType::Bytes => ByteBufSeed.deserialize(deserializer).map(DynValue::Bytes),
+            DynType::Seq(element_type) => DeserializeSeedSeq::new(
+                repeat(DeserializeSeedDyn::new(element_type)),
+                Vec::new(),
+                |mut current: Vec<DynValue>, next| { current.push(next); current }
+            ).deserialize(deserializer).map(DynValue::Seq),
+            DynType::Record(fields) => DeserializeSeedDynRecord::new(fields).deserialize(deserializer).map(DynValue::Record)
+        }
+    }
+}
+
+///
+/// A [`DeserializeSeed`] that deserializes a struct whose layout is given at runtime,
+/// e.g. read from the header of a file, and returns the values of its fields in the
+/// order in which they are specified.
+///
+/// Since the field names are not known at compile time, the struct cannot be deserialized
+/// using [`Deserializer::deserialize_struct()`]. Instead, it is deserialized as a map if the
+/// deserializer is human-readable, and as a tuple otherwise. This matches the representation
+/// of structs in most formats, e.g. JSON and postcard.
+///
+/// # Example
+/// ```
+/// # use feanor_serde::dynamic::*;
+/// # use serde::de::DeserializeSeed;
+/// let schema = vec![
+///     FieldSpec::new("id", DynType::U64),
+///     FieldSpec::new("tags", DynType::Seq(Box::new(DynType::String))),
+///     FieldSpec::new("score", DynType::F64).optional()
+/// ];
+/// let record = DeserializeSeedDynRecord::new(&schema).deserialize(&mut serde_json::Deserializer::from_str(r#"{ "tags": ["a"], "id": 7 }"#)).unwrap();
+/// assert_eq!(vec![DynValue::U64(7), DynValue::Seq(vec![DynValue::String("a".to_owned())]), DynValue::Null], record);
+/// ```
+///
+#[derive(Clone, Copy, Debug)]
+pub struct DeserializeSeedDynRecord<'a> {
+    fields: &'a [FieldSpec]
+}
+
+impl<'a> DeserializeSeedDynRecord<'a> {
+
+    pub const fn new(fields: &'a [FieldSpec]) -> Self {
+        Self { fields }
+    }
+}
+
+impl<'a, 'de> DeserializeSeed<'de> for DeserializeSeedDynRecord<'a> {
+
+    type Value = Vec<DynValue>;
+
+    fn deserialize<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+        where D: Deserializer<'de>
+    {
+        struct ResultVisitor<'a> {
+            fields: &'a [FieldSpec]
+        }
+
+        impl<'a, 'de> Visitor<'de> for ResultVisitor<'a> {
+            type Value = Vec<DynValue>;
+
+            fn expecting(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+                write!(f, "a struct with {} fields", self.fields.len())
+            }
+
+            fn visit_seq<A>(self, mut seq: A) -> Result<Self::Value, A::Error>
+                where A: SeqAccess<'de>
+            {
+                let mut result = Vec::with_capacity(self.fields.len());
+                for field in self.fields {
+                    match seq.next_element_seed(DeserializeSeedDynField { field: field })? {
+                        Some(value) => result.push(value),
+                        None => return Err(crate::errors::invalid_length(result.len(), "a struct with more fields", format_args!("a struct with {} fields", self.fields.len())))
+                    }
+                }
+                return Ok(result);
+            }
+
+            fn visit_map<A>(self, mut map: A) -> Result<Self::Value, A::Error>
+                where A: MapAccess<'de>
+            {
+                let mut values = self.fields.iter().map(|_| None).collect::<Vec<Option<DynValue>>>();
+                while let Some(key) = map.next_key::<String>()? {
+                    let Some(index) = self.fields.iter().position(|field| field.name == key) else {
+                        return Err(crate::errors::custom("unknown field", format_args!("unknown field `{}`", key)));
+                    };
+                    if values[index].is_some() {
+                        return Err(crate::errors::custom("duplicate field", format_args!("duplicate field `{}`", key)));
+                    }
+                    values[index] = Some(map.next_value_seed(DeserializeSeedDynField { field: &self.fields[index] })?);
+                }
+                return self.fields.iter().zip(values).map(|(field, value)| match value {
+                    Some(value) => Ok(value),
+                    None if field.optional => Ok(DynValue::Null),
+                    None => Err(crate::errors::custom("missing field", format_args!("missing field `{}`", field.name)))
+                }).collect();
+            }
+        }
+
+        if deserializer.is_human_readable() {
+            return deserializer.deserialize_map(ResultVisitor { fields: self.fields });
+        } else {
+            return deserializer.deserialize_tuple(self.fields.len(), ResultVisitor { fields: self.fields });
+        }
+    }
+}
+
+struct DeserializeSeedDynField<'a> {
+    field: &'a FieldSpec
+}
+
+impl<'a, 'de> DeserializeSeed<'de> for DeserializeSeedDynField<'a> {
+
+    type Value = DynValue;
+
+    fn deserialize<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+        where D: Deserializer<'de>
+    {
+        struct OptionVisitor<'a> {
+            ty: &'a DynType
+        }
+
+        impl<'a, 'de> Visitor<'de> for OptionVisitor<'a> {
+            type Value = DynValue;
+
+            fn expecting(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+                write!(f, "an option")
+            }
+
+            fn visit_none<E>(self) -> Result<Self::Value, E> {
+                Ok(DynValue::Null)
+            }
+
+            fn visit_unit<E>(self) -> Result<Self::Value, E> {
+                Ok(DynValue::Null)
+            }
+
+            fn visit_some<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+                where D: Deserializer<'de>
+            {
+                DeserializeSeedDyn::new(self.ty).deserialize(deserializer)
+            }
+        }
+
+        if self.field.optional {
+            return deserializer.deserialize_option(OptionVisitor { ty: &self.field.ty });
+        } else {
+            return DeserializeSeedDyn::new(&self.field.ty).deserialize(deserializer);
+        }
+    }
+}
+
+#[cfg(test)]
+#[derive(serde::Serialize)]
+struct Row {
+    id: u64,
+    name: String,
+    weight: Option<f64>,
+    point: Point,
+    data: Vec<u8>
+}
+
+#[cfg(test)]
+#[derive(serde::Serialize)]
+struct Point {
+    x: i64,
+    y: i64
+}
+
+#[cfg(test)]
+fn row_schema() -> Vec<FieldSpec> {
+    vec![
+        FieldSpec::new("id", DynType::U64),
+        FieldSpec::new("name", DynType::String),
+        FieldSpec::new("weight", DynType::F64).optional(),
+        FieldSpec::new("point", DynType::Record(vec![FieldSpec::new("x", DynType::I64), FieldSpec::new("y", DynType::I64)])),
+        FieldSpec::new("data", DynType::Bytes)
+    ]
+}
+
+#[test]
+fn test_serde_json() {
+    let schema = row_schema();
+    let row = Row { id: 1, name: "a".to_owned(), weight: Some(0.5), point: Point { x: -1, y: 2 }, data: vec![3, 4] };
+    let expected = vec![
+        DynValue::U64(1),
+        DynValue::String("a".to_owned()),
+        DynValue::F64(0.5),
+        DynValue::Record(vec![DynValue::I64(-1), DynValue::I64(2)]),
+        DynValue::Bytes(vec![3, 4])
+    ];
+    let serialized = serde_json::to_string(&row).unwrap();
+    assert_eq!(expected, DeserializeSeedDynRecord::new(&schema).deserialize(&mut serde_json::Deserializer::from_str(&serialized)).unwrap());
+
+    let result = DeserializeSeedDynRecord::new(&schema).deserialize(&mut serde_json::Deserializer::from_str(r#"{"id": 1, "name": "a", "point": {"x": 0, "y": 0}, "data": []}"#)).unwrap();
+    assert_eq!(DynValue::Null, result[2]);
+
+    let error = DeserializeSeedDynRecord::new(&schema).deserialize(&mut serde_json::Deserializer::from_str(r#"{"id": 1, "point": {"x": 0, "y": 0}, "data": []}"#)).unwrap_err();
+    assert!(error.to_string().starts_with("missing field"));
+    assert!(DeserializeSeedDynRecord::new(&schema).deserialize(&mut serde_json::Deserializer::from_str(r#"{"id": 1, "id": 2}"#)).is_err());
+    assert!(DeserializeSeedDynRecord::new(&schema).deserialize(&mut serde_json::Deserializer::from_str(r#"{"other": 1}"#)).is_err());
+    assert!(DeserializeSeedDynRecord::new(&schema).deserialize(&mut serde_json::Deserializer::from_str(r#"{"id": -1}"#)).is_err());
+}
+
+#[test]
+fn test_serde_postcard() {
+    let schema = row_schema();
+    let row = Row { id: 1, name: "a".to_owned(), weight: None, point: Point { x: -1, y: 2 }, data: vec![3, 4] };
+    let serialized = postcard::to_allocvec(&vec![row]).unwrap();
+    let ty = DynType::Seq(Box::new(DynType::Record(schema)));
+    assert_eq!(
+        DynValue::Seq(vec![DynValue::Record(vec![
+            DynValue::U64(1),
+            DynValue::String("a".to_owned()),
+            DynValue::Null,
+            DynValue::Record(vec![DynValue::I64(-1), DynValue::I64(2)]),
+            DynValue::Bytes(vec![3, 4])
+        ])]),
+        DeserializeSeedDyn::new(&ty).deserialize(&mut postcard::Deserializer::from_bytes(&serialized)).unwrap()
+    );
+}
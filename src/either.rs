@@ -0,0 +1,178 @@
+use serde::de::{DeserializeSeed, EnumAccess, VariantAccess, Visitor};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+use ::either::Either;
+
+use crate::into_seed::IntoSeed;
+
+const VARIANTS: &[&str] = &["Left", "Right"];
+
+#[derive(Deserialize)]
+#[serde(variant_identifier)]
+enum Variant {
+    Left,
+    Right
+}
+
+///
+/// Wraps an [`Either`] of references, and implements [`Serialize`] by mapping it to an
+/// enum with the newtype variants `Left` and `Right`, exactly as the `serde` feature of
+/// the `either` crate does for [`Either`]. The contained value is serialized by first passing
+/// it to the function given for its side, which can e.g. wrap it in another serializable
+/// wrapper carrying additional context.
+///
+/// Use [`DeserializeSeedEither`] to deserialize it.
+///
+/// # Example
+/// ```
+/// # use feanor_serde::either::*;
+/// # use feanor_serde::seq::*;
+/// # use feanor_serde::default_seed::seed_of;
+/// # use serde::de::DeserializeSeed;
+/// # use either::Either;
+/// let data: Either<Vec<i64>, String> = Either::Left(vec![1, 2]);
+/// let json = serde_json::to_string(&SerializableEither::new(data.as_ref(), |x| SerializableSeq::new(x.iter()), |s| s)).unwrap();
+/// assert_eq!("{\"Left\":[1,2]}", json);
+/// let seed = DeserializeSeedEither::new(
+///     DeserializeSeedSeq::new(std::iter::repeat(seed_of::<i64>()), 0, |current, next| current + next),
+///     seed_of::<String>()
+/// );
+/// assert_eq!(Either::Left(3), seed.deserialize(&mut serde_json::Deserializer::from_str(&json)).unwrap());
+/// ```
+///
+#[derive(Clone, Copy)]
+pub struct SerializableEither<'a, L, R, F, G>
+    where L: ?Sized,
+        R: ?Sized
+{
+    data: Either<&'a L, &'a R>,
+    wrap_left: F,
+    wrap_right: G
+}
+
+impl<'a, L, R, F, G, U, V> SerializableEither<'a, L, R, F, G>
+    where L: ?Sized,
+        R: ?Sized,
+        F: Fn(&'a L) -> U,
+        G: Fn(&'a R) -> V,
+        U: Serialize,
+        V: Serialize
+{
+    pub const fn new(data: Either<&'a L, &'a R>, wrap_left: F, wrap_right: G) -> Self {
+        Self { data, wrap_left, wrap_right }
+    }
+}
+
+impl<'a, L, R, F, G, U, V> Serialize for SerializableEither<'a, L, R, F, G>
+    where L: ?Sized,
+        R: ?Sized,
+        F: Fn(&'a L) -> U,
+        G: Fn(&'a R) -> V,
+        U: Serialize,
+        V: Serialize
+{
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+        where S: Serializer
+    {
+        match self.data {
+            Either::Left(value) => serializer.serialize_newtype_variant("Either", 0, VARIANTS[0], &(self.wrap_left)(value)),
+            Either::Right(value) => serializer.serialize_newtype_variant("Either", 1, VARIANTS[1], &(self.wrap_right)(value))
+        }
+    }
+}
+
+///
+/// A [`DeserializeSeed`] that deserializes an [`Either`], using the first given
+/// [`DeserializeSeed`] for the `Left` variant and the second one for the `Right` variant.
+///
+#[derive(Clone, Copy, Debug)]
+pub struct DeserializeSeedEither<S, R> {
+    left_seed: S,
+    right_seed: R
+}
+
+impl<S, R> DeserializeSeedEither<S, R> {
+
+    pub fn new<'de, I, J, M, N>(left_seed: I, right_seed: J) -> Self
+        where I: IntoSeed<'de, M, Seed = S>,
+            J: IntoSeed<'de, N, Seed = R>
+    {
+        Self { left_seed: left_seed.into_seed(), right_seed: right_seed.into_seed() }
+    }
+}
+
+impl<'de, S, R> DeserializeSeed<'de> for DeserializeSeedEither<S, R>
+    where S: DeserializeSeed<'de>,
+        R: DeserializeSeed<'de>
+{
+    type Value = Either<S::Value, R::Value>;
+
+    fn deserialize<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+        where D: Deserializer<'de>
+    {
+        struct ResultVisitor<S, R> {
+            left_seed: S,
+            right_seed: R
+        }
+
+        impl<'de, S, R> Visitor<'de> for ResultVisitor<S, R>
+            where S: DeserializeSeed<'de>,
+                R: DeserializeSeed<'de>
+        {
+            type Value = Either<S::Value, R::Value>;
+
+            fn expecting(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+                write!(f, "enum Either")
+            }
+
+            fn visit_enum<A>(self, data: A) -> Result<Self::Value, A::Error>
+                where A: EnumAccess<'de>
+            {
+                let (variant, content) = data.variant::<Variant>()?;
+                match variant {
+                    Variant::Left => content.newtype_variant_seed(self.left_seed).map(Either::Left),
+                    Variant::Right => content.newtype_variant_seed(self.right_seed).map(Either::Right)
+                }
+            }
+        }
+
+        return deserializer.deserialize_enum("Either", VARIANTS, ResultVisitor {
+            left_seed: self.left_seed,
+            right_seed: self.right_seed
+        });
+    }
+}
+
+#[cfg(test)]
+use crate::seq::{SerializableSeq, DeserializeSeedSeq};
+#[cfg(test)]
+use crate::default_seed::seed_of;
+
+#[cfg(test)]
+fn sum_seed() -> impl for<'de> DeserializeSeed<'de, Value = i64> {
+    DeserializeSeedSeq::new(std::iter::repeat(seed_of::<i64>()), 0, |current, next| current + next)
+}
+
+#[test]
+fn test_serde_postcard() {
+    let data: [Either<Vec<i64>, Vec<i64>>; 2] = [Either::Left(vec![1, 2, 3]), Either::Right(vec![4])];
+    for value in &data {
+        let serialized = postcard::to_allocvec(&SerializableEither::new(value.as_ref(), |x| SerializableSeq::new_with_len(x.iter(), x.len()), |x| SerializableSeq::new_with_len(x.iter(), x.len()))).unwrap();
+        let expected = postcard::to_allocvec(&value.as_ref().either(Ok::<_, &Vec<i64>>, Err)).unwrap();
+        assert_eq!(expected, serialized);
+        let result = DeserializeSeedEither::new(sum_seed(), sum_seed).deserialize(&mut postcard::Deserializer::from_bytes(&serialized)).unwrap();
+        assert_eq!(value.as_ref().map_either(|x| x.iter().sum::<i64>(), |x| x.iter().sum::<i64>()), result);
+    }
+}
+
+#[test]
+fn test_serde_json() {
+    assert_eq!("{\"Right\":\"foo\"}", serde_json::to_string(&SerializableEither::new(Either::<&i64, _>::Right("foo"), |x| x, |s| s)).unwrap());
+
+    let seed = DeserializeSeedEither::new(sum_seed(), seed_of::<String>());
+    assert_eq!(Either::Right("foo".to_owned()), seed.deserialize(&mut serde_json::Deserializer::from_str("{\"Right\":\"foo\"}")).unwrap());
+    let seed = DeserializeSeedEither::new(sum_seed(), seed_of::<String>());
+    assert_eq!(Either::Left(0), seed.deserialize(&mut serde_json::Deserializer::from_str("{\"Left\":[]}")).unwrap());
+    let seed = DeserializeSeedEither::new(sum_seed(), seed_of::<String>());
+    assert!(seed.deserialize(&mut serde_json::Deserializer::from_str("{\"Ok\":[]}")).is_err());
+}
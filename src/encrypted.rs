@@ -0,0 +1,194 @@
+use serde::de::{DeserializeSeed, Error};
+use serde::ser::{Serialize, Serializer};
+use serde::Deserializer;
+
+use crate::raw_bytes::ByteBufSeed;
+use crate::into_seed::IntoSeed;
+
+///
+/// Encrypts the serialized bytes of a value for [`SerializableEncrypted`]. This crate does not
+/// provide any cryptography, so implement this using the cipher of your choice.
+///
+/// This is implemented for all closures `Fn(&[u8]) -> Vec<u8>`.
+///
+pub trait Encryptor {
+
+    fn encrypt(&self, plaintext: &[u8]) -> Vec<u8>;
+}
+
+impl<F> Encryptor for F
+    where F: Fn(&[u8]) -> Vec<u8>
+{
+    fn encrypt(&self, plaintext: &[u8]) -> Vec<u8> {
+        self(plaintext)
+    }
+}
+
+///
+/// Decrypts the bytes produced by an [`Encryptor`] for [`DeserializeSeedEncrypted`], and
+/// returns `None` if this fails, e.g. because the authentication tag is invalid.
+///
+/// This is implemented for all closures `Fn(&[u8]) -> Option<Vec<u8>>`.
+///
+pub trait Decryptor {
+
+    fn decrypt(&self, ciphertext: &[u8]) -> Option<Vec<u8>>;
+}
+
+impl<F> Decryptor for F
+    where F: Fn(&[u8]) -> Option<Vec<u8>>
+{
+    fn decrypt(&self, ciphertext: &[u8]) -> Option<Vec<u8>> {
+        self(ciphertext)
+    }
+}
+
+///
+/// Wraps a serializable value, and implements [`Serialize`] by encoding it with postcard,
+/// encrypting the result with the given [`Encryptor`], and mapping the ciphertext to a
+/// byte buffer in the serde data model.
+///
+/// This is meant to be used for single fields, so that sensitive data is encrypted, while
+/// the rest of the document stays readable. Use [`DeserializeSeedEncrypted`] to deserialize it.
+///
+/// # Example
+/// ```
+/// # use feanor_serde::encrypted::*;
+/// # use serde::de::DeserializeSeed;
+/// # use feanor_serde::default_seed::seed_of;
+/// // not secure, just for demonstration
+/// let encrypt = |data: &[u8]| data.iter().map(|x| x ^ 0x5A).collect::<Vec<_>>();
+/// let decrypt = |data: &[u8]| Some(data.iter().map(|x| x ^ 0x5A).collect::<Vec<_>>());
+///
+/// #[derive(serde::Serialize)]
+/// #[serde(bound = "")]
+/// struct User<'a, E: Encryptor> {
+///     name: &'a str,
+///     password: SerializableEncrypted<'a, &'a str, E>
+/// }
+/// let json = serde_json::to_value(&User { name: "admin", password: SerializableEncrypted::new("1234", &encrypt) }).unwrap();
+/// assert_eq!("admin", json["name"]);
+///
+/// let password = DeserializeSeedEncrypted::new(seed_of::<String>(), &decrypt).deserialize(&json["password"]).unwrap();
+/// assert_eq!("1234", password);
+/// ```
+///
+pub struct SerializableEncrypted<'a, T, E>
+    where T: Serialize,
+        E: Encryptor
+{
+    data: T,
+    encryptor: &'a E
+}
+
+impl<'a, T, E> SerializableEncrypted<'a, T, E>
+    where T: Serialize,
+        E: Encryptor
+{
+    pub const fn new(data: T, encryptor: &'a E) -> Self {
+        Self { data, encryptor }
+    }
+}
+
+impl<'a, T, E> Serialize for SerializableEncrypted<'a, T, E>
+    where T: Serialize,
+        E: Encryptor
+{
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+        where S: Serializer
+    {
+        let plaintext = ::postcard::to_allocvec(&self.data).map_err(serde::ser::Error::custom)?;
+        return serializer.serialize_bytes(&self.encryptor.encrypt(&plaintext));
+    }
+}
+
+///
+/// A [`DeserializeSeed`] that deserializes a value serialized using [`SerializableEncrypted`],
+/// by decrypting it with the given [`Decryptor`], and passing the plaintext to the wrapped
+/// [`DeserializeSeed`].
+///
+/// Since the plaintext is not borrowed from the input, the wrapped seed must be able to
+/// deserialize from data of any lifetime.
+///
+pub struct DeserializeSeedEncrypted<'a, S, D>
+    where D: Decryptor
+{
+    seed: S,
+    decryptor: &'a D
+}
+
+impl<'a, S, D> DeserializeSeedEncrypted<'a, S, D>
+    where D: Decryptor
+{
+    pub fn new<'de, I, M>(seed: I, decryptor: &'a D) -> Self
+        where I: IntoSeed<'de, M, Seed = S>
+    {
+        Self { seed: seed.into_seed(), decryptor }
+    }
+}
+
+impl<'a, 'de, S, D, T> DeserializeSeed<'de> for DeserializeSeedEncrypted<'a, S, D>
+    where S: for<'b> DeserializeSeed<'b, Value = T>,
+        D: Decryptor
+{
+    type Value = T;
+
+    fn deserialize<R>(self, deserializer: R) -> Result<Self::Value, R::Error>
+        where R: Deserializer<'de>
+    {
+        let ciphertext = ByteBufSeed.deserialize(deserializer)?;
+        let plaintext = self.decryptor.decrypt(&ciphertext).ok_or_else(|| R::Error::custom("decryption failed"))?;
+        let (result, rest) = crate::postcard::take_from_slice_seeded(self.seed, &plaintext).map_err(R::Error::custom)?;
+        if !rest.is_empty() {
+            return Err(R::Error::custom(format!("plaintext has {} trailing bytes", rest.len())));
+        }
+        return Ok(result);
+    }
+}
+
+#[cfg(test)]
+use std::marker::PhantomData;
+
+#[cfg(test)]
+struct XorCipher(u8);
+
+#[cfg(test)]
+impl Encryptor for XorCipher {
+
+    fn encrypt(&self, plaintext: &[u8]) -> Vec<u8> {
+        let mut result = plaintext.iter().map(|x| x ^ self.0).collect::<Vec<_>>();
+        result.push(self.0);
+        return result;
+    }
+}
+
+#[cfg(test)]
+impl Decryptor for XorCipher {
+
+    fn decrypt(&self, ciphertext: &[u8]) -> Option<Vec<u8>> {
+        let (key, data) = ciphertext.split_last()?;
+        if *key != self.0 {
+            return None;
+        }
+        return Some(data.iter().map(|x| x ^ self.0).collect());
+    }
+}
+
+#[test]
+fn test_serde_postcard() {
+    let data = (5i64, Some("secret".to_owned()));
+    let serialized = postcard::to_allocvec(&(1u8, SerializableEncrypted::new(&data, &XorCipher(3)))).unwrap();
+    let mut deserializer = postcard::Deserializer::from_flavor(postcard::de_flavors::Slice::new(&serialized[1..]));
+    assert_eq!(data, DeserializeSeedEncrypted::new(PhantomData::<(i64, Option<String>)>, &XorCipher(3)).deserialize(&mut deserializer).unwrap());
+    let mut deserializer = postcard::Deserializer::from_flavor(postcard::de_flavors::Slice::new(&serialized[1..]));
+    assert!(DeserializeSeedEncrypted::new(PhantomData::<(i64, Option<String>)>, &XorCipher(4)).deserialize(&mut deserializer).is_err());
+}
+
+#[test]
+fn test_serde_json() {
+    let serialized = serde_json::to_string(&SerializableEncrypted::new(1u8, &XorCipher(2))).unwrap();
+    assert_eq!("[3,2]", serialized);
+    assert_eq!(1, DeserializeSeedEncrypted::new(PhantomData::<u8>, &XorCipher(2)).deserialize(&mut serde_json::Deserializer::from_str(&serialized)).unwrap());
+    let error = DeserializeSeedEncrypted::new(PhantomData::<u8>, &XorCipher(3)).deserialize(&mut serde_json::Deserializer::from_str(&serialized)).unwrap_err();
+    assert!(error.to_string().contains("decryption failed"));
+}
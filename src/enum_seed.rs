@@ -0,0 +1,285 @@
+use std::marker::PhantomData;
+
+use serde::de::{DeserializeSeed, EnumAccess, VariantAccess, Visitor};
+use serde::Deserializer;
+
+use crate::into_seed::IntoSeed;
+
+///
+/// A variant of an [`EnumSeed`], consisting of its name, the [`DeserializeSeed`] for its
+/// payload and the function that maps the payload to the output of the [`EnumSeed`].
+///
+#[derive(Clone, Copy, Debug)]
+pub struct Variant<S, F> {
+    name: &'static str,
+    seed: S,
+    constructor: F
+}
+
+///
+/// Implemented by the typestates of an [`EnumSeed`] with at least one variant, i.e. by
+/// [`Variant`] and by pairs `(L, Variant<S, F>)` with `L: VariantList`.
+///
+pub trait VariantList {}
+
+impl<S, F> VariantList for Variant<S, F> {}
+
+impl<L, S, F> VariantList for (L, Variant<S, F>)
+    where L: VariantList
+{}
+
+///
+/// The variants of an [`EnumSeed`]. This is implemented for [`Variant`] and for pairs
+/// `(L, Variant<S, F>)`, as long as the constructors of all variants have the same output type.
+///
+pub trait EnumVariants<'de>: VariantList {
+
+    type Value;
+
+    const LEN: usize;
+
+    fn index_of(&self, name: &str) -> Option<usize>;
+
+    fn deserialize_variant<A>(self, index: usize, variant: A) -> Result<Self::Value, A::Error>
+        where A: VariantAccess<'de>;
+}
+
+impl<'de, S, F, T> EnumVariants<'de> for Variant<S, F>
+    where S: DeserializeSeed<'de>,
+        F: FnOnce(S::Value) -> T
+{
+    type Value = T;
+
+    const LEN: usize = 1;
+
+    fn index_of(&self, name: &str) -> Option<usize> {
+        if self.name == name { Some(0) } else { None }
+    }
+
+    fn deserialize_variant<A>(self, _index: usize, variant: A) -> Result<Self::Value, A::Error>
+        where A: VariantAccess<'de>
+    {
+        Ok((self.constructor)(variant.newtype_variant_seed(self.seed)?))
+    }
+}
+
+impl<'de, L, S, F> EnumVariants<'de> for (L, Variant<S, F>)
+    where L: EnumVariants<'de>,
+        S: DeserializeSeed<'de>,
+        F: FnOnce(S::Value) -> L::Value
+{
+    type Value = L::Value;
+
+    const LEN: usize = L::LEN + 1;
+
+    fn index_of(&self, name: &str) -> Option<usize> {
+        self.0.index_of(name).or_else(|| self.1.index_of(name).map(|_| L::LEN))
+    }
+
+    fn deserialize_variant<A>(self, index: usize, variant: A) -> Result<Self::Value, A::Error>
+        where A: VariantAccess<'de>
+    {
+        if index < L::LEN {
+            self.0.deserialize_variant(index, variant)
+        } else {
+            self.1.deserialize_variant(0, variant)
+        }
+    }
+}
+
+///
+/// A [`DeserializeSeed`] for an enum whose variants are added at runtime via [`EnumSeed::variant()`].
+/// This is a non-macro alternative to [`crate::impl_deserialize_seed_for_dependent_enum!`], for enums
+/// whose set of variants is fixed, but whose seeds are configured at runtime.
+///
+/// Each variant is a newtype variant, and has a [`DeserializeSeed`] for its payload, as well as a
+/// function that maps the payload to the common output type, e.g. the constructor of the variant
+/// of a user-defined enum. The variants are stored in the type of the seed, so mistakes lead to
+/// ordinary type errors.
+///
+/// As for the macro, variants may be identified by their name or by their index. Since the variant
+/// names are only known at runtime, they are not passed to [`Deserializer::deserialize_enum()`].
+/// This is fine for most formats, e.g. JSON and postcard, but formats that rely on the list of
+/// variants are not supported.
+///
+/// # Example
+/// ```
+/// # use feanor_serde::enum_seed::*;
+/// # use feanor_serde::default_seed::seed_of;
+/// # use serde::de::DeserializeSeed;
+/// #[derive(Debug, PartialEq)]
+/// enum Foo {
+///     A(i64),
+///     B(String)
+/// }
+/// let seed = EnumSeed::new("Foo")
+///     .variant("A", seed_of::<i64>(), Foo::A)
+///     .variant("B", seed_of::<String>(), Foo::B);
+/// let foo = seed.deserialize(&mut serde_json::Deserializer::from_str(r#"{ "B": "x" }"#)).unwrap();
+/// assert_eq!(Foo::B("x".to_owned()), foo);
+/// ```
+///
+#[derive(Clone, Copy, Debug)]
+pub struct EnumSeed<V> {
+    name: &'static str,
+    variants: V
+}
+
+impl EnumSeed<()> {
+
+    pub const fn new(name: &'static str) -> Self {
+        Self { name: name, variants: () }
+    }
+
+    pub fn variant<'de, I, M, S, F>(self, name: &'static str, seed: I, constructor: F) -> EnumSeed<Variant<S, F>>
+        where I: IntoSeed<'de, M, Seed = S>
+    {
+        EnumSeed { name: self.name, variants: Variant { name: name, seed: seed.into_seed(), constructor: constructor } }
+    }
+}
+
+impl<V> EnumSeed<V>
+    where V: VariantList
+{
+    pub fn variant<'de, I, M, S, F>(self, name: &'static str, seed: I, constructor: F) -> EnumSeed<(V, Variant<S, F>)>
+        where I: IntoSeed<'de, M, Seed = S>
+    {
+        EnumSeed { name: self.name, variants: (self.variants, Variant { name: name, seed: seed.into_seed(), constructor: constructor }) }
+    }
+}
+
+impl<'de, V> DeserializeSeed<'de> for EnumSeed<V>
+    where V: EnumVariants<'de>
+{
+    type Value = V::Value;
+
+    fn deserialize<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+        where D: Deserializer<'de>
+    {
+        enum Key {
+            Index(u64),
+            Name(String)
+        }
+
+        struct KeyVisitor;
+
+        impl<'de> Visitor<'de> for KeyVisitor {
+            type Value = Key;
+
+            fn expecting(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+                write!(f, "variant identifier")
+            }
+
+            fn visit_u64<E>(self, value: u64) -> Result<Self::Value, E> {
+                Ok(Key::Index(value))
+            }
+
+            fn visit_str<E>(self, value: &str) -> Result<Self::Value, E> {
+                Ok(Key::Name(value.to_owned()))
+            }
+
+            fn visit_bytes<E>(self, value: &[u8]) -> Result<Self::Value, E> {
+                Ok(Key::Name(String::from_utf8_lossy(value).into_owned()))
+            }
+        }
+
+        struct KeySeed;
+
+        impl<'de> DeserializeSeed<'de> for KeySeed {
+            type Value = Key;
+
+            fn deserialize<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+                where D: Deserializer<'de>
+            {
+                deserializer.deserialize_identifier(KeyVisitor)
+            }
+        }
+
+        struct ResultVisitor<'de, V> {
+            name: &'static str,
+            variants: V,
+            deserializer: PhantomData<&'de ()>
+        }
+
+        impl<'de, V> Visitor<'de> for ResultVisitor<'de, V>
+            where V: EnumVariants<'de>
+        {
+            type Value = V::Value;
+
+            fn expecting(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+                write!(f, "enum {}", self.name)
+            }
+
+            fn visit_enum<A>(self, data: A) -> Result<Self::Value, A::Error>
+                where A: EnumAccess<'de>
+            {
+                let (key, variant) = data.variant_seed(KeySeed)?;
+                let index = match key {
+                    Key::Index(index) if index < V::LEN as u64 => index as usize,
+                    Key::Index(index) => return Err(crate::errors::invalid_value(serde::de::Unexpected::Unsigned(index), "a variant index", format_args!("variant index 0 <= i < {}", V::LEN))),
+                    Key::Name(name) => match self.variants.index_of(&name) {
+                        Some(index) => index,
+                        None => return Err(crate::errors::custom("unknown variant", format_args!("unknown variant `{}` of enum {}", name, self.name)))
+                    }
+                };
+                return self.variants.deserialize_variant(index, variant);
+            }
+        }
+
+        return deserializer.deserialize_enum(self.name, &[], ResultVisitor { name: self.name, variants: self.variants, deserializer: PhantomData });
+    }
+}
+
+#[cfg(test)]
+use crate::default_seed::seed_of;
+#[cfg(test)]
+use crate::mock::{MockDeserializer, MockValue};
+
+#[cfg(test)]
+#[derive(Debug, PartialEq, serde::Serialize)]
+enum Foo {
+    A(i64),
+    B(String),
+    C(Vec<u8>)
+}
+
+#[cfg(test)]
+fn foo_seed<'de>() -> impl Copy + DeserializeSeed<'de, Value = Foo> {
+    EnumSeed::new("Foo")
+        .variant("A", seed_of::<i64>(), Foo::A)
+        .variant("B", seed_of::<String>(), Foo::B)
+        .variant("C", seed_of::<Vec<u8>>(), Foo::C)
+}
+
+#[test]
+fn test_serde_json() {
+    for foo in [Foo::A(-1), Foo::B("x".to_owned()), Foo::C(vec![1, 2])] {
+        let serialized = serde_json::to_string(&foo).unwrap();
+        assert_eq!(foo, foo_seed().deserialize(&mut serde_json::Deserializer::from_str(&serialized)).unwrap());
+    }
+    let error = foo_seed().deserialize(&mut serde_json::Deserializer::from_str(r#"{"D": 1}"#)).unwrap_err().to_string();
+    assert!(error.starts_with("unknown variant"));
+    #[cfg(feature = "detailed-errors")]
+    assert!(error.starts_with("unknown variant `D` of enum Foo"));
+    assert!(foo_seed().deserialize(&mut serde_json::Deserializer::from_str(r#"{"A": "x"}"#)).is_err());
+
+    let seed = EnumSeed::new("Bar").variant("X", seed_of::<i64>(), |x: i64| x).variant("Y", seed_of::<i64>(), |y: i64| -y);
+    assert_eq!(-2, seed.deserialize(&mut serde_json::Deserializer::from_str(r#"{"Y": 2}"#)).unwrap());
+}
+
+#[test]
+fn test_serde_postcard() {
+    for foo in [Foo::A(-1), Foo::B("x".to_owned()), Foo::C(vec![1, 2])] {
+        let serialized = postcard::to_allocvec(&foo).unwrap();
+        assert_eq!(foo, foo_seed().deserialize(&mut postcard::Deserializer::from_bytes(&serialized)).unwrap());
+    }
+    let serialized = postcard::to_allocvec(&(3u32, 1i64)).unwrap();
+    assert!(foo_seed().deserialize(&mut postcard::Deserializer::from_bytes(&serialized)).is_err());
+}
+
+#[test]
+fn test_variant_identifiers() {
+    assert_eq!(Foo::B("y".to_owned()), foo_seed().deserialize(MockDeserializer::new(MockValue::newtype_variant(1u64, "y"))).unwrap());
+    assert_eq!(Foo::A(3), foo_seed().deserialize(MockDeserializer::new(MockValue::newtype_variant(MockValue::bytes(*b"A"), 3i64))).unwrap());
+    assert!(foo_seed().deserialize(MockDeserializer::new(MockValue::newtype_variant(3u64, 3i64))).is_err());
+}
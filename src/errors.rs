@@ -0,0 +1,137 @@
+use std::fmt::Arguments;
+
+use serde::de::{Error, Expected, Unexpected};
+
+///
+/// Creates an error for a sequence or map of unexpected length, using [`Error::invalid_length()`].
+///
+/// By default, only the static description `expected` is used, so constructing the error
+/// does not allocate. If the feature `detailed-errors` is enabled, the (possibly allocating)
+/// description `detailed` is used instead.
+///
+/// This is used by [`crate::seq::DeserializeSeedSeq`], [`crate::map::DeserializeSeedMap`]
+/// and the macros [`crate::impl_deserialize_seed_for_dependent_struct!`] and
+/// [`crate::impl_deserialize_seed_for_dependent_enum!`].
+///
+/// # Example
+/// ```
+/// # use feanor_serde::errors::*;
+/// let error: serde_json::Error = invalid_length(5, "a shorter sequence", format_args!("a sequence of length at most {}", 4));
+/// if cfg!(feature = "detailed-errors") {
+///     assert_eq!("invalid length 5, expected a sequence of length at most 4", error.to_string());
+/// } else {
+///     assert_eq!("invalid length 5, expected a shorter sequence", error.to_string());
+/// }
+/// ```
+///
+pub fn invalid_length<E>(len: usize, expected: &'static str, detailed: Arguments) -> E
+    where E: Error
+{
+    #[cfg(feature = "detailed-errors")]
+    {
+        let _ = expected;
+        return E::invalid_length(len, &detailed.to_string().as_str());
+    }
+    #[cfg(not(feature = "detailed-errors"))]
+    {
+        let _ = detailed;
+        return E::invalid_length(len, &expected);
+    }
+}
+
+///
+/// Creates an error for an unexpected value, using [`Error::invalid_value()`].
+///
+/// As for [`invalid_length()`], the description `detailed` is only used if the feature
+/// `detailed-errors` is enabled, and otherwise the static `expected` is used.
+///
+pub fn invalid_value<E>(unexpected: Unexpected, expected: &'static str, detailed: Arguments) -> E
+    where E: Error
+{
+    #[cfg(feature = "detailed-errors")]
+    {
+        let _ = expected;
+        return E::invalid_value(unexpected, &detailed.to_string().as_str());
+    }
+    #[cfg(not(feature = "detailed-errors"))]
+    {
+        let _ = detailed;
+        return E::invalid_value(unexpected, &expected);
+    }
+}
+
+///
+/// Creates a custom error, using [`Error::custom()`].
+///
+/// As for [`invalid_length()`], the message `detailed` is only used if the feature
+/// `detailed-errors` is enabled, and otherwise the static `message` is used.
+///
+pub fn custom<E>(message: &'static str, detailed: Arguments) -> E
+    where E: Error
+{
+    #[cfg(feature = "detailed-errors")]
+    {
+        let _ = message;
+        return E::custom(detailed);
+    }
+    #[cfg(not(feature = "detailed-errors"))]
+    {
+        let _ = detailed;
+        return E::custom(message);
+    }
+}
+
+///
+/// Describes the expected index of an enum variant when given a variant index that is out of
+/// range, in the same way as `#[derive(Deserialize)]`, i.e. as `variant index 0 <= i < len`.
+/// The description is only formatted when the error is created, so this does not allocate
+/// by itself.
+///
+/// This is used by [`crate::impl_deserialize_seed_for_dependent_enum!`].
+///
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ExpectedVariantIndex(pub usize);
+
+impl Expected for ExpectedVariantIndex {
+
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "variant index 0 <= i < {}", self.0)
+    }
+}
+
+///
+/// Describes the expected index of a struct field when given a field index that is out of
+/// range, in the same way as `#[derive(Deserialize)]`, i.e. as `field index 0 <= i < len`.
+///
+/// This is used by [`crate::impl_deserialize_seed_for_dependent_struct!`].
+///
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ExpectedFieldIndex(pub usize);
+
+impl Expected for ExpectedFieldIndex {
+
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "field index 0 <= i < {}", self.0)
+    }
+}
+
+#[test]
+fn test_invalid_length() {
+    let error: serde_json::Error = invalid_length(3, "a pair", format_args!("a pair, not {} elements", 3));
+    #[cfg(feature = "detailed-errors")]
+    assert_eq!("invalid length 3, expected a pair, not 3 elements", error.to_string());
+    #[cfg(not(feature = "detailed-errors"))]
+    assert_eq!("invalid length 3, expected a pair", error.to_string());
+
+    let error: serde_json::Error = invalid_value(Unexpected::Unsigned(7), "a small index", format_args!("an index < {}", 5));
+    #[cfg(feature = "detailed-errors")]
+    assert_eq!("invalid value: integer `7`, expected an index < 5", error.to_string());
+    #[cfg(not(feature = "detailed-errors"))]
+    assert_eq!("invalid value: integer `7`, expected a small index", error.to_string());
+
+    let error: serde_json::Error = Error::invalid_value(Unexpected::Unsigned(7), &ExpectedVariantIndex(2));
+    assert_eq!("invalid value: integer `7`, expected variant index 0 <= i < 2", error.to_string());
+
+    let error: serde_json::Error = Error::invalid_value(Unexpected::Unsigned(7), &ExpectedFieldIndex(2));
+    assert_eq!("invalid value: integer `7`, expected field index 0 <= i < 2", error.to_string());
+}
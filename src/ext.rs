@@ -0,0 +1,108 @@
+use std::fmt::Display;
+
+use serde::de::{DeserializeSeed, Error};
+use serde::Deserializer;
+
+use crate::progress::{DeserializeSeedProgress, Progress};
+
+///
+/// Extension methods for [`DeserializeSeed`]s, implemented for all of them.
+///
+pub trait DeserializeSeedExt<'de>: DeserializeSeed<'de> + Sized {
+
+    ///
+    /// Returns a [`DeserializeSeed`] that deserializes in the same way as `self`, but
+    /// prefixes any error with the given context.
+    ///
+    /// Since the error type is only known to implement [`serde::de::Error`], the result
+    /// is constructed using [`serde::de::Error::custom()`], which means that any structured
+    /// information about the original error is lost, except for its [`Display`] output.
+    /// Furthermore, some formats (e.g. postcard) ignore the message passed to
+    /// [`serde::de::Error::custom()`], in which case the context is lost as well.
+    ///
+    /// # Example
+    /// ```
+    /// # use feanor_serde::ext::*;
+    /// # use feanor_serde::default_seed::seed_of;
+    /// # use serde::de::DeserializeSeed;
+    /// let mut deserializer = serde_json::Deserializer::new(serde_json::de::StrRead::new("\"x\""));
+    /// let error = seed_of::<i64>().context("while deserializing the modulus").deserialize(&mut deserializer).unwrap_err();
+    /// assert!(error.to_string().starts_with("while deserializing the modulus: invalid type"));
+    /// ```
+    ///
+    fn context<C>(self, context: C) -> DeserializeSeedContext<Self, C>
+        where C: Display
+    {
+        DeserializeSeedContext { seed: self, context: context }
+    }
+
+    ///
+    /// Returns a [`DeserializeSeed`] that deserializes in the same way as `self`, but
+    /// reports the progress of the outermost sequence resp. map every `every` elements,
+    /// see [`DeserializeSeedProgress`].
+    ///
+    fn with_progress<F>(self, every: usize, callback: F) -> DeserializeSeedProgress<Self, F>
+        where F: FnMut(Progress)
+    {
+        DeserializeSeedProgress::new(self, every, callback)
+    }
+}
+
+impl<'de, S> DeserializeSeedExt<'de> for S
+    where S: DeserializeSeed<'de>
+{}
+
+///
+/// A [`DeserializeSeed`] that adds context to the errors of another [`DeserializeSeed`],
+/// see [`DeserializeSeedExt::context()`].
+///
+#[derive(Clone, Copy)]
+pub struct DeserializeSeedContext<S, C> {
+    seed: S,
+    context: C
+}
+
+impl<'de, S, C> DeserializeSeed<'de> for DeserializeSeedContext<S, C>
+    where S: DeserializeSeed<'de>,
+        C: Display
+{
+    type Value = S::Value;
+
+    fn deserialize<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+        where D: Deserializer<'de>
+    {
+        let context = self.context;
+        return self.seed.deserialize(deserializer).map_err(|e| D::Error::custom(format!("{}: {}", context, e)));
+    }
+}
+
+#[cfg(test)]
+use std::marker::PhantomData;
+#[cfg(test)]
+use std::iter::repeat_with;
+#[cfg(test)]
+use crate::seq::DeserializeSeedSeq;
+
+#[test]
+fn test_serde_json() {
+    let result = PhantomData::<i64>.context("modulus").deserialize(&mut serde_json::Deserializer::from_str("5")).unwrap();
+    assert_eq!(5, result);
+
+    let seed = DeserializeSeedSeq::new(
+        repeat_with(|| PhantomData::<i64>.context("inner")),
+        Vec::new(),
+        |mut current, next| { current.push(next); current }
+    ).context("outer");
+    let error = seed.deserialize(&mut serde_json::Deserializer::from_str("[1, null]")).unwrap_err();
+    assert!(error.to_string().starts_with("outer: inner: invalid type: null"));
+}
+
+#[test]
+fn test_serde_postcard() {
+    let serialized = postcard::to_allocvec(&(1i64, 2i64)).unwrap();
+    let error = PhantomData::<(i64, i64, i64)>.context("triple").deserialize(
+        &mut postcard::Deserializer::from_flavor(postcard::de_flavors::Slice::new(&serialized))
+    ).unwrap_err();
+    // postcard does not store the message of custom errors
+    assert_eq!(postcard::Error::SerdeDeCustom, error);
+}
@@ -0,0 +1,264 @@
+use serde::ser::{Serialize, SerializeMap, SerializeStruct, Serializer};
+
+///
+/// Wraps a value implementing [`Serialize`], e.g. via `#[derive(Serialize)]`, and serializes it
+/// such that its struct fields are identified by their index instead of their name, if the
+/// serializer is not human-readable.
+///
+/// More concretely, if [`Serializer::is_human_readable()`] returns `false`, a struct is serialized
+/// as a map from the index of each field to its value. This saves the field names in self-describing
+/// binary formats like CBOR or MessagePack, which would otherwise store them with every struct.
+/// In human-readable formats like JSON, the result is the same as without the wrapper.
+///
+/// Formats that are not self-describing, like postcard, never store field names, since they
+/// serialize structs as tuples. This wrapper should not be used with them, as their deserializers
+/// cannot read the resulting map as a struct.
+///
+/// Structs deserialized with [`crate::impl_deserialize_seed_for_dependent_struct!`] accept both
+/// field names and indices, so no special handling is required on the deserialization side, as long
+/// as the fields are declared in the same order as in the serialized struct. Fields skipped during
+/// serialization (e.g. via `#[serde(skip_serializing_if = "...")]`) still count for the indices.
+///
+/// Only the outermost struct is affected, to serialize nested structs in the same way, wrap them
+/// separately, e.g. using `#[serde(serialize_with = "feanor_serde::field_index::serialize_field_indices")]`.
+///
+/// # Example
+/// ```
+/// # use feanor_serde::impl_deserialize_seed_for_dependent_struct;
+/// # use feanor_serde::field_index::*;
+/// # use feanor_serde::mock::*;
+/// # use feanor_serde::default_seed::seed_of;
+/// # use serde::de::DeserializeSeed;
+/// # use serde::Serialize;
+/// #[derive(Serialize)]
+/// struct Point {
+///     x: i64,
+///     y: i64
+/// }
+/// struct PointDeserializeSeed;
+/// impl_deserialize_seed_for_dependent_struct!{
+///     pub struct DeserializedPoint<'de> using PointDeserializeSeed {
+///         x: i64: |_| seed_of::<i64>(),
+///         y: i64: |_| seed_of::<i64>()
+///     }
+/// }
+/// let point = Point { x: 1, y: 2 };
+/// assert_eq!("{\"x\":1,\"y\":2}", serde_json::to_string(&SerializableFieldIndices::new(&point)).unwrap());
+///
+/// let serialized = SerializableFieldIndices::new(&point).serialize(MockSerializer::new().with_human_readable(false)).unwrap();
+/// assert_eq!(MockValue::map([(0u64, 1i64), (1u64, 2i64)]), serialized);
+/// let result = PointDeserializeSeed.deserialize(MockDeserializer::new(serialized)).unwrap();
+/// assert_eq!((1, 2), (result.x, result.y));
+/// ```
+///
+#[derive(Clone, Copy, Debug)]
+pub struct SerializableFieldIndices<'a, T>
+    where T: ?Sized
+{
+    data: &'a T
+}
+
+impl<'a, T> SerializableFieldIndices<'a, T>
+    where T: ?Sized + Serialize
+{
+    pub const fn new(data: &'a T) -> Self {
+        Self { data }
+    }
+}
+
+impl<'a, T> Serialize for SerializableFieldIndices<'a, T>
+    where T: ?Sized + Serialize
+{
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+        where S: Serializer
+    {
+        serialize_field_indices(self.data, serializer)
+    }
+}
+
+///
+/// Serializes the given value as [`SerializableFieldIndices`] does. The signature makes this
+/// usable with `#[serde(serialize_with = "...")]`.
+///
+pub fn serialize_field_indices<T, S>(value: &T, serializer: S) -> Result<S::Ok, S::Error>
+    where T: ?Sized + Serialize,
+        S: Serializer
+{
+    value.serialize(FieldIndexSerializer { ser: serializer })
+}
+
+struct FieldIndexSerializer<S> {
+    ser: S
+}
+
+enum FieldIndexStruct<S>
+    where S: Serializer
+{
+    Named(S::SerializeStruct),
+    Indexed(S::SerializeMap, u32)
+}
+
+macro_rules! forward_serialize {
+    ($($method:ident($($arg:ident: $type:ty),*) -> $result:ty),*) => {
+        $(
+            fn $method(self, $($arg: $type),*) -> Result<$result, Self::Error> {
+                self.ser.$method($($arg),*)
+            }
+        )*
+    };
+}
+
+impl<S> Serializer for FieldIndexSerializer<S>
+    where S: Serializer
+{
+    type Ok = S::Ok;
+    type Error = S::Error;
+    type SerializeSeq = S::SerializeSeq;
+    type SerializeTuple = S::SerializeTuple;
+    type SerializeTupleStruct = S::SerializeTupleStruct;
+    type SerializeTupleVariant = S::SerializeTupleVariant;
+    type SerializeMap = S::SerializeMap;
+    type SerializeStruct = FieldIndexStruct<S>;
+    type SerializeStructVariant = S::SerializeStructVariant;
+
+    forward_serialize!{
+        serialize_bool(v: bool) -> S::Ok, serialize_i8(v: i8) -> S::Ok, serialize_i16(v: i16) -> S::Ok, serialize_i32(v: i32) -> S::Ok,
+        serialize_i64(v: i64) -> S::Ok, serialize_i128(v: i128) -> S::Ok, serialize_u8(v: u8) -> S::Ok, serialize_u16(v: u16) -> S::Ok,
+        serialize_u32(v: u32) -> S::Ok, serialize_u64(v: u64) -> S::Ok, serialize_u128(v: u128) -> S::Ok, serialize_f32(v: f32) -> S::Ok,
+        serialize_f64(v: f64) -> S::Ok, serialize_char(v: char) -> S::Ok, serialize_str(v: &str) -> S::Ok, serialize_bytes(v: &[u8]) -> S::Ok,
+        serialize_none() -> S::Ok, serialize_unit() -> S::Ok, serialize_unit_struct(name: &'static str) -> S::Ok,
+        serialize_unit_variant(name: &'static str, variant_index: u32, variant: &'static str) -> S::Ok,
+        serialize_seq(len: Option<usize>) -> S::SerializeSeq, serialize_tuple(len: usize) -> S::SerializeTuple,
+        serialize_tuple_struct(name: &'static str, len: usize) -> S::SerializeTupleStruct,
+        serialize_tuple_variant(name: &'static str, variant_index: u32, variant: &'static str, len: usize) -> S::SerializeTupleVariant,
+        serialize_map(len: Option<usize>) -> S::SerializeMap,
+        serialize_struct_variant(name: &'static str, variant_index: u32, variant: &'static str, len: usize) -> S::SerializeStructVariant
+    }
+
+    fn serialize_some<T>(self, value: &T) -> Result<S::Ok, S::Error>
+        where T: ?Sized + Serialize
+    {
+        self.ser.serialize_some(value)
+    }
+
+    fn serialize_newtype_struct<T>(self, name: &'static str, value: &T) -> Result<S::Ok, S::Error>
+        where T: ?Sized + Serialize
+    {
+        self.ser.serialize_newtype_struct(name, value)
+    }
+
+    fn serialize_newtype_variant<T>(self, name: &'static str, variant_index: u32, variant: &'static str, value: &T) -> Result<S::Ok, S::Error>
+        where T: ?Sized + Serialize
+    {
+        self.ser.serialize_newtype_variant(name, variant_index, variant, value)
+    }
+
+    fn serialize_struct(self, name: &'static str, len: usize) -> Result<FieldIndexStruct<S>, S::Error> {
+        if self.ser.is_human_readable() {
+            self.ser.serialize_struct(name, len).map(FieldIndexStruct::Named)
+        } else {
+            self.ser.serialize_map(Some(len)).map(|map| FieldIndexStruct::Indexed(map, 0))
+        }
+    }
+
+    fn is_human_readable(&self) -> bool {
+        self.ser.is_human_readable()
+    }
+}
+
+impl<S> SerializeStruct for FieldIndexStruct<S>
+    where S: Serializer
+{
+    type Ok = S::Ok;
+    type Error = S::Error;
+
+    fn serialize_field<T>(&mut self, key: &'static str, value: &T) -> Result<(), S::Error>
+        where T: ?Sized + Serialize
+    {
+        match self {
+            FieldIndexStruct::Named(fields) => fields.serialize_field(key, value),
+            FieldIndexStruct::Indexed(map, index) => {
+                map.serialize_entry(&*index, value)?;
+                *index += 1;
+                return Ok(());
+            }
+        }
+    }
+
+    fn skip_field(&mut self, key: &'static str) -> Result<(), S::Error> {
+        match self {
+            FieldIndexStruct::Named(fields) => fields.skip_field(key),
+            FieldIndexStruct::Indexed(_, index) => {
+                *index += 1;
+                return Ok(());
+            }
+        }
+    }
+
+    fn end(self) -> Result<S::Ok, S::Error> {
+        match self {
+            FieldIndexStruct::Named(fields) => fields.end(),
+            FieldIndexStruct::Indexed(map, _) => map.end()
+        }
+    }
+}
+
+#[cfg(test)]
+use crate::mock::{MockDeserializer, MockSerializer, MockValue};
+#[cfg(test)]
+use crate::default_seed::seed_of;
+#[cfg(test)]
+use crate::impl_deserialize_seed_for_dependent_struct;
+#[cfg(test)]
+use serde::de::DeserializeSeed;
+
+#[cfg(test)]
+#[derive(serde::Serialize)]
+struct Foo {
+    a: i64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    b: Option<i64>,
+    #[serde(serialize_with = "serialize_field_indices")]
+    c: Bar
+}
+
+#[cfg(test)]
+#[derive(serde::Serialize)]
+struct Bar {
+    d: String
+}
+
+#[cfg(test)]
+struct DeserializeSeedFoo;
+
+#[cfg(test)]
+impl_deserialize_seed_for_dependent_struct!{
+    pub struct DeserializedFoo<'de> using DeserializeSeedFoo {
+        a: i64: |_| seed_of::<i64>(),
+        b: Option<i64>: |_| seed_of::<Option<i64>>(),
+        c: DeserializedBar<'de>: |_| DeserializeSeedBar
+    }
+}
+
+#[cfg(test)]
+struct DeserializeSeedBar;
+
+#[cfg(test)]
+impl_deserialize_seed_for_dependent_struct!{
+    pub struct DeserializedBar<'de> using DeserializeSeedBar {
+        d: String: |_| seed_of::<String>()
+    }
+}
+
+#[test]
+fn test_field_indices() {
+    let foo = Foo { a: 1, b: None, c: Bar { d: "x".to_owned() } };
+    assert_eq!("{\"a\":1,\"c\":{\"d\":\"x\"}}", serde_json::to_string(&SerializableFieldIndices::new(&foo)).unwrap());
+
+    let serialized = SerializableFieldIndices::new(&foo).serialize(MockSerializer::new().with_human_readable(false)).unwrap();
+    assert_eq!(MockValue::map([(MockValue::from(0u64), MockValue::from(1i64)), (MockValue::from(2u64), MockValue::map([(0u64, "x")]))]), serialized);
+    let result = DeserializeSeedFoo.deserialize(MockDeserializer::<serde::de::value::Error>::new(serialized)).unwrap();
+    assert_eq!(1, result.a);
+    assert_eq!(None, result.b);
+    assert_eq!("x", result.c.d);
+}
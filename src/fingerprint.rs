@@ -0,0 +1,209 @@
+use serde::de::{DeserializeSeed, Error, SeqAccess, Visitor};
+use serde::ser::{Serialize, SerializeTuple, Serializer};
+use serde::Deserializer;
+
+use crate::into_seed::IntoSeed;
+
+///
+/// Computes a stable 64-bit fingerprint (the FNV-1a hash) of the given schema description,
+/// usually the `SCHEMA` constant generated by [`crate::impl_deserialize_seed_for_dependent_struct!`]
+/// or [`crate::impl_deserialize_seed_for_dependent_enum!`].
+///
+/// The `SCHEMA` constants are built from the field names and types as written in the macro
+/// invocation, so nested types are only included by name. If the schema of a nested type
+/// matters, combine the descriptions before hashing them, e.g. using [`concat!()`] or by
+/// hashing them one after the other with [`schema_fingerprint_extend()`].
+///
+pub const fn schema_fingerprint(schema: &str) -> u64 {
+    schema_fingerprint_extend(0xcbf29ce484222325, schema)
+}
+
+///
+/// Extends the fingerprint `fingerprint` by the given schema description, such that
+/// `schema_fingerprint_extend(schema_fingerprint(a), b) == schema_fingerprint(a + b)`.
+///
+pub const fn schema_fingerprint_extend(fingerprint: u64, schema: &str) -> u64 {
+    let bytes = schema.as_bytes();
+    let mut result = fingerprint;
+    let mut i = 0;
+    while i < bytes.len() {
+        result ^= bytes[i] as u64;
+        result = result.wrapping_mul(0x100000001b3);
+        i += 1;
+    }
+    return result;
+}
+
+///
+/// Wraps a serializable value, and implements [`Serialize`] by mapping it to the tuple
+/// `(fingerprint, value)` in the serde data model, where `fingerprint` is a [`u64`] identifying
+/// the schema of the value, e.g. as computed by [`schema_fingerprint()`].
+///
+/// Use [`DeserializeSeedFingerprinted`] to deserialize it, which checks the fingerprint before
+/// parsing the value, and thus fails with a clear error if the data was written with an
+/// incompatible schema, instead of failing somewhere in the middle of the value, or even
+/// returning a wrong value.
+///
+/// # Example
+/// ```
+/// # use feanor_serde::impl_deserialize_seed_for_dependent_struct;
+/// # use feanor_serde::fingerprint::*;
+/// # use feanor_serde::default_seed::seed_of;
+/// # use serde::de::DeserializeSeed;
+/// struct PointDeserializeSeed;
+/// impl_deserialize_seed_for_dependent_struct!{
+///     pub struct Point<'de> using PointDeserializeSeed {
+///         x: i64: |_| seed_of::<i64>(),
+///         y: i64: |_| seed_of::<i64>()
+///     }
+/// }
+/// assert_eq!("Point { x: i64, y: i64, }", Point::SCHEMA);
+/// const FINGERPRINT: u64 = schema_fingerprint(Point::SCHEMA);
+///
+/// let serialized = serde_json::to_string(&SerializableFingerprinted::new(serde_json::json!({ "x": 1, "y": 2 }), FINGERPRINT)).unwrap();
+/// let point = DeserializeSeedFingerprinted::new(PointDeserializeSeed, FINGERPRINT).deserialize(&mut serde_json::Deserializer::from_str(&serialized)).unwrap();
+/// assert_eq!((1, 2), (point.x, point.y));
+///
+/// let error = DeserializeSeedFingerprinted::new(PointDeserializeSeed, FINGERPRINT ^ 1).deserialize(&mut serde_json::Deserializer::from_str(&serialized)).err().unwrap();
+/// assert!(error.to_string().contains("incompatible schema"));
+/// ```
+///
+pub struct SerializableFingerprinted<T>
+    where T: Serialize
+{
+    data: T,
+    fingerprint: u64
+}
+
+impl<T> SerializableFingerprinted<T>
+    where T: Serialize
+{
+    pub const fn new(data: T, fingerprint: u64) -> Self {
+        Self { data, fingerprint }
+    }
+}
+
+impl<T> Serialize for SerializableFingerprinted<T>
+    where T: Serialize
+{
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+        where S: Serializer
+    {
+        let mut tuple = serializer.serialize_tuple(2)?;
+        tuple.serialize_element(&self.fingerprint)?;
+        tuple.serialize_element(&self.data)?;
+        return tuple.end();
+    }
+}
+
+///
+/// A [`DeserializeSeed`] that deserializes a value serialized using [`SerializableFingerprinted`].
+/// The fingerprint is compared to the expected one before the value is passed to the wrapped
+/// [`DeserializeSeed`], and deserialization fails if they do not match.
+///
+pub struct DeserializeSeedFingerprinted<S> {
+    seed: S,
+    fingerprint: u64
+}
+
+impl<S> DeserializeSeedFingerprinted<S> {
+
+    pub fn new<'de, I, M>(seed: I, fingerprint: u64) -> Self
+        where I: IntoSeed<'de, M, Seed = S>
+    {
+        Self { seed: seed.into_seed(), fingerprint: fingerprint }
+    }
+}
+
+impl<'de, S> DeserializeSeed<'de> for DeserializeSeedFingerprinted<S>
+    where S: DeserializeSeed<'de>
+{
+    type Value = S::Value;
+
+    fn deserialize<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+        where D: Deserializer<'de>
+    {
+        return deserializer.deserialize_tuple(2, self);
+    }
+}
+
+impl<'de, S> Visitor<'de> for DeserializeSeedFingerprinted<S>
+    where S: DeserializeSeed<'de>
+{
+    type Value = S::Value;
+
+    fn expecting(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "a schema fingerprint and a value")
+    }
+
+    fn visit_seq<A>(self, mut seq: A) -> Result<Self::Value, A::Error>
+        where A: SeqAccess<'de>
+    {
+        let fingerprint: u64 = seq.next_element()?.ok_or_else(|| A::Error::invalid_length(0, &self))?;
+        if fingerprint != self.fingerprint {
+            return Err(A::Error::custom(format!("file was written with an incompatible schema: expected schema fingerprint {:016x}, found {:016x}", self.fingerprint, fingerprint)));
+        }
+        let expected = self.fingerprint;
+        return seq.next_element_seed(self.seed)?.ok_or_else(|| A::Error::custom(format!("missing value after schema fingerprint {:016x}", expected)));
+    }
+}
+
+#[cfg(test)]
+use crate::default_seed::seed_of;
+#[cfg(test)]
+use crate::{impl_deserialize_seed_for_dependent_struct, impl_deserialize_seed_for_dependent_enum};
+
+#[cfg(test)]
+struct DeserializeSeedFoo;
+
+#[cfg(test)]
+impl_deserialize_seed_for_dependent_struct!{
+    pub struct Foo<'de> using DeserializeSeedFoo {
+        a: i64: |_| seed_of::<i64>(),
+        b: Vec<String>: |_| seed_of::<Vec<String>>()
+    }
+}
+
+#[cfg(test)]
+struct DeserializeSeedBar;
+
+#[cfg(test)]
+impl_deserialize_seed_for_dependent_enum!{
+    pub enum Bar<'de> using DeserializeSeedBar {
+        A(i64): |_| seed_of::<i64>(),
+        B(String): |_| seed_of::<String>()
+    }
+}
+
+#[test]
+fn test_schema_fingerprint() {
+    assert_eq!(0xcbf29ce484222325, schema_fingerprint(""));
+    assert_eq!(0xaf63dc4c8601ec8c, schema_fingerprint("a"));
+    assert_eq!("Foo { a: i64, b: Vec<String>, }", Foo::SCHEMA);
+    assert_eq!("Bar { A(i64), B(String), }", Bar::SCHEMA);
+    assert_eq!(schema_fingerprint(concat!("Foo { a: i64, b: Vec<String>, }", "Bar { A(i64), B(String), }")), schema_fingerprint_extend(schema_fingerprint(Foo::SCHEMA), Bar::SCHEMA));
+    assert!(schema_fingerprint(Foo::SCHEMA) != schema_fingerprint(Bar::SCHEMA));
+}
+
+#[test]
+fn test_serde_postcard() {
+    let fingerprint = schema_fingerprint(Foo::SCHEMA);
+    let serialized = postcard::to_allocvec(&SerializableFingerprinted::new((5i64, vec!["x"]), fingerprint)).unwrap();
+    let result = DeserializeSeedFingerprinted::new(DeserializeSeedFoo, fingerprint).deserialize(&mut postcard::Deserializer::from_bytes(&serialized)).unwrap();
+    assert_eq!(5, result.a);
+    assert_eq!(vec!["x".to_owned()], result.b);
+
+    assert!(DeserializeSeedFingerprinted::new(DeserializeSeedFoo, schema_fingerprint(Bar::SCHEMA)).deserialize(&mut postcard::Deserializer::from_bytes(&serialized)).is_err());
+}
+
+#[test]
+fn test_serde_json() {
+    let fingerprint = schema_fingerprint(Bar::SCHEMA);
+    let serialized = serde_json::to_string(&SerializableFingerprinted::new(serde_json::json!({ "B": "y" }), fingerprint)).unwrap();
+    let result = DeserializeSeedFingerprinted::new(DeserializeSeedBar, fingerprint).deserialize(&mut serde_json::Deserializer::from_str(&serialized)).unwrap();
+    assert!(matches!(result, Bar::B((ref value, _)) if value == "y"));
+
+    let error = DeserializeSeedFingerprinted::new(DeserializeSeedBar, fingerprint ^ 1).deserialize(&mut serde_json::Deserializer::from_str(&serialized)).err().unwrap();
+    assert!(error.to_string().contains("file was written with an incompatible schema"));
+    assert!(DeserializeSeedFingerprinted::new(DeserializeSeedBar, fingerprint).deserialize(&mut serde_json::Deserializer::from_str(&format!("[{}]", fingerprint))).is_err());
+}
@@ -0,0 +1,171 @@
+use serde::de::{DeserializeSeed, Error, Unexpected, Visitor};
+use serde::ser::{Serialize, Serializer};
+use serde::Deserializer;
+
+///
+/// Wraps an `f64`, and implements [`Serialize`] by mapping it to the f64 type
+/// in the serde data model, except for non-finite values in human-readable
+/// serializers, which are mapped to the strings `"NaN"`, `"inf"` and `"-inf"`.
+///
+/// This is useful for formats like JSON, that cannot represent non-finite floats.
+///
+/// # Example
+/// ```
+/// # use feanor_serde::float::*;
+/// assert_eq!("[1.5,\"NaN\",\"-inf\"]", serde_json::to_string(&[
+///     SerializableF64::new(1.5),
+///     SerializableF64::new(f64::NAN),
+///     SerializableF64::new(f64::NEG_INFINITY)
+/// ]).unwrap());
+/// ```
+///
+#[derive(Clone, Copy)]
+pub struct SerializableF64 {
+    data: f64
+}
+
+impl SerializableF64 {
+
+    pub const fn new(data: f64) -> Self {
+        Self { data }
+    }
+}
+
+impl Serialize for SerializableF64 {
+
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+        where S: Serializer
+    {
+        if serializer.is_human_readable() && !self.data.is_finite() {
+            if self.data.is_nan() {
+                serializer.serialize_str("NaN")
+            } else if self.data > 0. {
+                serializer.serialize_str("inf")
+            } else {
+                serializer.serialize_str("-inf")
+            }
+        } else {
+            serializer.serialize_f64(self.data)
+        }
+    }
+}
+
+///
+/// A [`DeserializeSeed`] that deserializes an `f64` that was serialized using
+/// [`SerializableF64`].
+///
+/// # Example
+/// ```
+/// # use feanor_serde::float::*;
+/// # use serde::de::DeserializeSeed;
+/// let mut deserializer = serde_json::Deserializer::new(serde_json::de::StrRead::new("\"-inf\""));
+/// assert_eq!(f64::NEG_INFINITY, DeserializeSeedF64.deserialize(&mut deserializer).unwrap());
+/// ```
+///
+#[derive(Clone, Copy, Default)]
+pub struct DeserializeSeedF64;
+
+impl<'de> DeserializeSeed<'de> for DeserializeSeedF64 {
+    type Value = f64;
+
+    fn deserialize<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+        where D: Deserializer<'de>
+    {
+        struct FloatVisitor;
+
+        impl<'de> Visitor<'de> for FloatVisitor {
+            type Value = f64;
+
+            fn expecting(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+                write!(f, "a float, or one of \"NaN\", \"inf\" or \"-inf\"")
+            }
+
+            fn visit_f64<E>(self, value: f64) -> Result<Self::Value, E>
+                where E: Error
+            {
+                Ok(value)
+            }
+
+            fn visit_i64<E>(self, value: i64) -> Result<Self::Value, E>
+                where E: Error
+            {
+                Ok(value as f64)
+            }
+
+            fn visit_u64<E>(self, value: u64) -> Result<Self::Value, E>
+                where E: Error
+            {
+                Ok(value as f64)
+            }
+
+            fn visit_str<E>(self, value: &str) -> Result<Self::Value, E>
+                where E: Error
+            {
+                match value {
+                    "NaN" => Ok(f64::NAN),
+                    "inf" => Ok(f64::INFINITY),
+                    "-inf" => Ok(f64::NEG_INFINITY),
+                    _ => Err(E::invalid_value(Unexpected::Str(value), &self))
+                }
+            }
+        }
+
+        if deserializer.is_human_readable() {
+            deserializer.deserialize_any(FloatVisitor)
+        } else {
+            deserializer.deserialize_f64(FloatVisitor)
+        }
+    }
+}
+
+#[cfg(test)]
+use crate::test::token::{Token, TokenDeserializer, TokenSerializer};
+
+#[cfg(test)]
+fn testdata() -> Vec<f64> {
+    vec![0., -1.5, 1e300, f64::MIN_POSITIVE, f64::INFINITY, f64::NEG_INFINITY, f64::NAN]
+}
+
+#[cfg(test)]
+fn assert_same(expected: f64, actual: f64) {
+    assert!(expected == actual || (expected.is_nan() && actual.is_nan()), "expected {}, got {}", expected, actual);
+}
+
+#[test]
+fn test_serde_postcard() {
+    for data in testdata() {
+        let serialized = postcard::to_allocvec(&SerializableF64::new(data)).unwrap();
+        assert_eq!(postcard::to_allocvec(&data).unwrap(), serialized);
+        let result = DeserializeSeedF64.deserialize(
+            &mut postcard::Deserializer::from_flavor(postcard::de_flavors::Slice::new(&serialized))
+        ).unwrap();
+        assert_same(data, result);
+    }
+}
+
+#[test]
+fn test_serde_json() {
+    for data in testdata() {
+        let serialized = serde_json::to_string(&SerializableF64::new(data)).unwrap();
+        let result = DeserializeSeedF64.deserialize(
+            &mut serde_json::Deserializer::from_str(&serialized)
+        ).unwrap();
+        assert_same(data, result);
+    }
+    assert_same(3., DeserializeSeedF64.deserialize(&mut serde_json::Deserializer::from_str("3")).unwrap());
+    assert!(DeserializeSeedF64.deserialize(&mut serde_json::Deserializer::from_str("\"nan\"")).is_err());
+    assert!(DeserializeSeedF64.deserialize(&mut serde_json::Deserializer::from_str("null")).is_err());
+}
+
+#[test]
+fn test_serde_tokens() {
+    let serializer = TokenSerializer::new().with_human_readable(true);
+    assert_eq!(SerializableF64::new(f64::INFINITY).serialize(serializer).unwrap(), [Token::Str("inf".to_owned())]);
+    assert_eq!(SerializableF64::new(1.).serialize(serializer).unwrap(), [Token::F64(1.)]);
+
+    let serializer = TokenSerializer::new().with_human_readable(false);
+    let tokens = SerializableF64::new(f64::INFINITY).serialize(serializer).unwrap();
+    assert_eq!(tokens, [Token::F64(f64::INFINITY)]);
+    let mut deserializer = TokenDeserializer::new(tokens).with_human_readable(false);
+    assert_same(f64::INFINITY, DeserializeSeedF64.deserialize(&mut deserializer).unwrap());
+}
@@ -0,0 +1,269 @@
+use std::fmt::Display;
+use std::io::{ErrorKind, Read, Write};
+
+use serde::de::DeserializeSeed;
+use serde::ser::Serialize;
+
+///
+/// Error returned by [`FrameWriter`] and [`FrameReader`].
+///
+#[derive(Debug)]
+pub enum FramingError {
+    /// The underlying reader or writer failed
+    Io(std::io::Error),
+    /// Serializing or deserializing a message failed
+    Postcard(::postcard::Error),
+    /// The input ended in the middle of a frame
+    Truncated,
+    /// The length of a frame exceeds the limit set by [`FrameReader::with_max_frame_len()`],
+    /// or does not fit into the `u32` length prefix
+    FrameTooLong { len: u64, max_len: u64 },
+    /// A message did not use all bytes of its frame
+    TrailingBytes(usize)
+}
+
+impl Display for FramingError {
+
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            FramingError::Io(e) => write!(f, "io error: {}", e),
+            FramingError::Postcard(e) => write!(f, "postcard error: {}", e),
+            FramingError::Truncated => write!(f, "input ended in the middle of a frame"),
+            FramingError::FrameTooLong { len, max_len } => write!(f, "frame of length {} exceeds the limit of {} bytes", len, max_len),
+            FramingError::TrailingBytes(count) => write!(f, "message has {} trailing bytes in its frame", count)
+        }
+    }
+}
+
+impl std::error::Error for FramingError {
+
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            FramingError::Io(e) => Some(e),
+            FramingError::Postcard(e) => Some(e),
+            _ => None
+        }
+    }
+}
+
+impl From<std::io::Error> for FramingError {
+
+    fn from(value: std::io::Error) -> Self {
+        FramingError::Io(value)
+    }
+}
+
+impl From<::postcard::Error> for FramingError {
+
+    fn from(value: ::postcard::Error) -> Self {
+        FramingError::Postcard(value)
+    }
+}
+
+///
+/// Writes a sequence of messages to an [`std::io::Write`], each encoded with postcard and
+/// prefixed by its length as little-endian `u32`. Use [`FrameReader`] to read them back.
+///
+/// Since each message is written as a single frame, this can e.g. be used to append records
+/// to a log file.
+///
+/// # Example
+/// ```
+/// # use feanor_serde::framing::*;
+/// # use feanor_serde::seq::*;
+/// # use feanor_serde::default_seed::seed_of;
+/// let mut writer = FrameWriter::new(Vec::new());
+/// writer.write(SerializableSeq::new_with_len([1, 2, 3].into_iter(), 3)).unwrap();
+/// writer.write("hello").unwrap();
+/// let data = writer.into_inner();
+///
+/// let mut reader = FrameReader::new(&data[..]);
+/// assert_eq!(Some(vec![1, 2, 3]), reader.read(seed_of::<Vec<i64>>()).unwrap());
+/// assert_eq!(Some("hello".to_owned()), reader.read(seed_of::<String>()).unwrap());
+/// assert_eq!(None, reader.read(seed_of::<String>()).unwrap());
+/// ```
+///
+pub struct FrameWriter<W>
+    where W: Write
+{
+    writer: W
+}
+
+impl<W> FrameWriter<W>
+    where W: Write
+{
+    pub fn new(writer: W) -> Self {
+        Self { writer }
+    }
+
+    ///
+    /// Serializes the given value and writes it as a single frame. Like
+    /// [`crate::postcard::to_allocvec()`], this takes the value by value, so that
+    /// serializable wrappers can be created inline.
+    ///
+    pub fn write<T>(&mut self, value: T) -> Result<(), FramingError>
+        where T: Serialize
+    {
+        let payload = ::postcard::to_allocvec(&value)?;
+        let len = u32::try_from(payload.len()).map_err(|_| FramingError::FrameTooLong { len: payload.len() as u64, max_len: u32::MAX as u64 })?;
+        self.writer.write_all(&len.to_le_bytes())?;
+        self.writer.write_all(&payload)?;
+        return Ok(());
+    }
+
+    pub fn flush(&mut self) -> Result<(), FramingError> {
+        self.writer.flush()?;
+        return Ok(());
+    }
+
+    pub fn get_ref(&self) -> &W {
+        &self.writer
+    }
+
+    pub fn into_inner(self) -> W {
+        self.writer
+    }
+}
+
+///
+/// Reads the messages written by a [`FrameWriter`] from an [`std::io::Read`], one at a time,
+/// each with its own [`DeserializeSeed`].
+///
+/// Reads that return less data than requested are continued, so this works with any reader.
+/// The end of the input is only accepted between two frames; if it occurs within a frame,
+/// [`FramingError::Truncated`] is returned.
+///
+pub struct FrameReader<R>
+    where R: Read
+{
+    reader: R,
+    buffer: Vec<u8>,
+    max_frame_len: u64
+}
+
+impl<R> FrameReader<R>
+    where R: Read
+{
+    pub fn new(reader: R) -> Self {
+        Self { reader, buffer: Vec::new(), max_frame_len: u32::MAX as u64 }
+    }
+
+    ///
+    /// Fails when reading a frame longer than `max_frame_len` bytes, before reading its content.
+    ///
+    pub fn with_max_frame_len(self, max_frame_len: usize) -> Self {
+        Self { max_frame_len: max_frame_len as u64, ..self }
+    }
+
+    ///
+    /// Reads the next frame and deserializes it using the given seed. Returns `None` if
+    /// the input ended before the frame.
+    ///
+    /// Since the frame is read into an internal buffer, the seed must be able to deserialize
+    /// from data of any lifetime.
+    ///
+    pub fn read<S, T>(&mut self, seed: S) -> Result<Option<T>, FramingError>
+        where S: for<'a> DeserializeSeed<'a, Value = T>
+    {
+        let mut len = [0; 4];
+        match read_until_eof(&mut self.reader, &mut len)? {
+            0 => return Ok(None),
+            4 => {},
+            _ => return Err(FramingError::Truncated)
+        }
+        let len = u32::from_le_bytes(len) as u64;
+        if len > self.max_frame_len {
+            return Err(FramingError::FrameTooLong { len, max_len: self.max_frame_len });
+        }
+        self.buffer.clear();
+        (&mut self.reader).take(len).read_to_end(&mut self.buffer)?;
+        if (self.buffer.len() as u64) < len {
+            return Err(FramingError::Truncated);
+        }
+        let (result, rest) = crate::postcard::take_from_slice_seeded(seed, &self.buffer)?;
+        if !rest.is_empty() {
+            return Err(FramingError::TrailingBytes(rest.len()));
+        }
+        return Ok(Some(result));
+    }
+
+    pub fn get_ref(&self) -> &R {
+        &self.reader
+    }
+
+    pub fn into_inner(self) -> R {
+        self.reader
+    }
+}
+
+///
+/// Fills `buffer` from `reader`, and returns the number of bytes read, which is only
+/// less than `buffer.len()` if the reader reached its end.
+///
+fn read_until_eof<R>(reader: &mut R, buffer: &mut [u8]) -> std::io::Result<usize>
+    where R: Read
+{
+    let mut filled = 0;
+    while filled < buffer.len() {
+        match reader.read(&mut buffer[filled..]) {
+            Ok(0) => break,
+            Ok(n) => filled += n,
+            Err(e) if e.kind() == ErrorKind::Interrupted => {},
+            Err(e) => return Err(e)
+        }
+    }
+    return Ok(filled);
+}
+
+#[cfg(test)]
+use std::marker::PhantomData;
+
+#[cfg(test)]
+struct OneByteReader<'a>(&'a [u8]);
+
+#[cfg(test)]
+impl<'a> Read for OneByteReader<'a> {
+
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        if self.0.is_empty() || buf.is_empty() {
+            return Ok(0);
+        }
+        buf[0] = self.0[0];
+        self.0 = &self.0[1..];
+        return Ok(1);
+    }
+}
+
+#[test]
+fn test_write_read() {
+    let mut writer = FrameWriter::new(Vec::new());
+    for i in 0..10 {
+        writer.write((i, (0..i).map(|j| j.to_string()).collect::<Vec<_>>())).unwrap();
+    }
+    let data = writer.into_inner();
+
+    let mut reader = FrameReader::new(&data[..]);
+    for i in 0..10 {
+        assert_eq!(Some((i, (0..i).map(|j| j.to_string()).collect::<Vec<_>>())), reader.read(PhantomData::<(i32, Vec<String>)>).unwrap());
+    }
+    assert!(reader.read(PhantomData::<(i32, Vec<String>)>).unwrap().is_none());
+
+    let mut reader = FrameReader::new(OneByteReader(&data));
+    for i in 0..10 {
+        assert_eq!(Some((i, (0..i).map(|j| j.to_string()).collect::<Vec<_>>())), reader.read(PhantomData::<(i32, Vec<String>)>).unwrap());
+    }
+    assert!(reader.read(PhantomData::<(i32, Vec<String>)>).unwrap().is_none());
+}
+
+#[test]
+fn test_read_invalid() {
+    let mut writer = FrameWriter::new(Vec::new());
+    writer.write("hello").unwrap();
+    let data = writer.into_inner();
+
+    for len in 1..data.len() {
+        assert!(matches!(FrameReader::new(&data[..len]).read(PhantomData::<String>), Err(FramingError::Truncated)));
+    }
+    assert!(matches!(FrameReader::new(&data[..]).with_max_frame_len(5).read(PhantomData::<String>), Err(FramingError::FrameTooLong { len: 6, max_len: 5 })));
+    assert!(matches!(FrameReader::new(&data[..]).read(PhantomData::<u8>), Err(FramingError::TrailingBytes(5))));
+}
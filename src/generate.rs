@@ -0,0 +1,244 @@
+use std::cell::Cell;
+use std::ops::RangeInclusive;
+
+use serde::ser::{Serialize, SerializeMap, SerializeSeq, SerializeStruct, Serializer};
+
+///
+/// Describes the shape of values produced by [`Schema::generate()`].
+///
+/// Lengths and numbers are chosen uniformly from the given ranges, thus the size of the
+/// generated data is controlled by the ranges of [`Schema::Seq`] and [`Schema::Map`].
+///
+#[derive(Clone, Debug, PartialEq)]
+pub enum Schema {
+    Bool,
+    I64(RangeInclusive<i64>),
+    U64(RangeInclusive<u64>),
+    F64(RangeInclusive<f64>),
+    ///
+    /// A string of ASCII letters and digits, whose length is in the given range.
+    ///
+    Str(RangeInclusive<usize>),
+    Bytes(RangeInclusive<usize>),
+    Unit,
+    ///
+    /// An optional value, which is present with probability 1/2.
+    ///
+    Option(Box<Schema>),
+    Seq(Box<Schema>, RangeInclusive<usize>),
+    ///
+    /// A map with keys and values of the given schemas. Keys are generated independently, so
+    /// they may be duplicated.
+    ///
+    Map(Box<Schema>, Box<Schema>, RangeInclusive<usize>),
+    Struct(&'static str, Vec<(&'static str, Schema)>),
+    ///
+    /// An enum with the given variants, which are unit variants if their schema is
+    /// [`Schema::Unit`], and newtype variants otherwise.
+    ///
+    Enum(&'static str, Vec<(&'static str, Schema)>)
+}
+
+impl Schema {
+
+    pub fn option(value: Schema) -> Self {
+        Schema::Option(Box::new(value))
+    }
+
+    pub fn seq(element: Schema, len: RangeInclusive<usize>) -> Self {
+        Schema::Seq(Box::new(element), len)
+    }
+
+    pub fn map(key: Schema, value: Schema, len: RangeInclusive<usize>) -> Self {
+        Schema::Map(Box::new(key), Box::new(value), len)
+    }
+
+    ///
+    /// Returns a value that serializes to pseudo-random data of this schema. The data only
+    /// depends on `seed`, so it can be reproduced on every run, and is generated while
+    /// serializing, so even very large inputs do not need to fit into memory.
+    ///
+    /// # Example
+    /// ```
+    /// # use feanor_serde::generate::*;
+    /// # use feanor_serde::seq::*;
+    /// # use serde::de::DeserializeSeed;
+    /// # use feanor_serde::default_seed::seed_of;
+    /// # use std::iter::repeat;
+    /// let schema = Schema::seq(Schema::I64(-10..=10), 1000..=1000);
+    /// let json = serde_json::to_string(&schema.generate(42)).unwrap();
+    /// assert_eq!(json, serde_json::to_string(&schema.generate(42)).unwrap());
+    ///
+    /// let seed = DeserializeSeedSeq::new(repeat(seed_of::<i64>()), Vec::new(), |mut current, next| { current.push(next); current });
+    /// let result = seed.deserialize(&mut serde_json::Deserializer::from_str(&json)).unwrap();
+    /// assert_eq!(1000, result.len());
+    /// assert!(result.iter().all(|x| (-10..=10).contains(x)));
+    /// ```
+    ///
+    pub fn generate(&self, seed: u64) -> Generated<'_> {
+        Generated { schema: self, state: Cell::new(seed) }
+    }
+}
+
+///
+/// Pseudo-random data of a [`Schema`], generated during serialization, see [`Schema::generate()`].
+///
+/// Each call to [`Serialize::serialize()`] continues the pseudo-random sequence, so serializing
+/// the same [`Generated`] twice produces different data. To get the same data again, create a
+/// new one with the same seed.
+///
+pub struct Generated<'a> {
+    schema: &'a Schema,
+    state: Cell<u64>
+}
+
+impl<'a> Generated<'a> {
+
+    ///
+    /// The next output of splitmix64, which is fast and good enough for test data.
+    ///
+    fn next_u64(&self) -> u64 {
+        let state = self.state.get().wrapping_add(0x9E3779B97F4A7C15);
+        self.state.set(state);
+        let mut result = state;
+        result = (result ^ (result >> 30)).wrapping_mul(0xBF58476D1CE4E5B9);
+        result = (result ^ (result >> 27)).wrapping_mul(0x94D049BB133111EB);
+        return result ^ (result >> 31);
+    }
+
+    ///
+    /// Returns a uniformly random integer in `[0, bound]`, up to a negligible bias.
+    ///
+    fn below_or_eq(&self, bound: u64) -> u64 {
+        match bound.checked_add(1) {
+            Some(modulus) => ((self.next_u64() as u128 * modulus as u128) >> 64) as u64,
+            None => self.next_u64()
+        }
+    }
+
+    fn len(&self, range: &RangeInclusive<usize>) -> usize {
+        range.start() + self.below_or_eq(range.end().saturating_sub(*range.start()) as u64) as usize
+    }
+
+    fn nested<'b>(&'b self, schema: &'b Schema) -> Nested<'b> {
+        Nested { schema: schema, generator: self }
+    }
+}
+
+struct Nested<'a> {
+    schema: &'a Schema,
+    generator: &'a Generated<'a>
+}
+
+impl<'a> Serialize for Generated<'a> {
+
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+        where S: Serializer
+    {
+        self.nested(self.schema).serialize(serializer)
+    }
+}
+
+impl<'a> Serialize for Nested<'a> {
+
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+        where S: Serializer
+    {
+        const ALPHABET: &[u8] = b"abcdefghijklmnopqrstuvwxyzABCDEFGHIJKLMNOPQRSTUVWXYZ0123456789";
+        let generator = self.generator;
+        match self.schema {
+            Schema::Bool => serializer.serialize_bool(generator.next_u64() & 1 == 1),
+            Schema::I64(range) => serializer.serialize_i64(range.start().wrapping_add(generator.below_or_eq(range.end().wrapping_sub(*range.start()) as u64) as i64)),
+            Schema::U64(range) => serializer.serialize_u64(range.start() + generator.below_or_eq(range.end() - range.start())),
+            Schema::F64(range) => {
+                let unit = (generator.next_u64() >> 11) as f64 / (1u64 << 53) as f64;
+                serializer.serialize_f64(range.start() + unit * (range.end() - range.start()))
+            },
+            Schema::Str(len) => {
+                let value = (0..generator.len(len)).map(|_| ALPHABET[generator.below_or_eq(ALPHABET.len() as u64 - 1) as usize] as char).collect::<String>();
+                serializer.serialize_str(&value)
+            },
+            Schema::Bytes(len) => {
+                let value = (0..generator.len(len)).map(|_| generator.next_u64() as u8).collect::<Vec<_>>();
+                serializer.serialize_bytes(&value)
+            },
+            Schema::Unit => serializer.serialize_unit(),
+            Schema::Option(value) => if generator.next_u64() & 1 == 1 {
+                serializer.serialize_some(&generator.nested(value))
+            } else {
+                serializer.serialize_none()
+            },
+            Schema::Seq(element, len) => {
+                let len = generator.len(len);
+                let mut seq = serializer.serialize_seq(Some(len))?;
+                for _ in 0..len {
+                    seq.serialize_element(&generator.nested(element))?;
+                }
+                seq.end()
+            },
+            Schema::Map(key, value, len) => {
+                let len = generator.len(len);
+                let mut map = serializer.serialize_map(Some(len))?;
+                for _ in 0..len {
+                    map.serialize_entry(&generator.nested(key), &generator.nested(value))?;
+                }
+                map.end()
+            },
+            Schema::Struct(name, fields) => {
+                let mut result = serializer.serialize_struct(name, fields.len())?;
+                for (field, value) in fields {
+                    result.serialize_field(field, &generator.nested(value))?;
+                }
+                result.end()
+            },
+            Schema::Enum(name, variants) => {
+                assert!(!variants.is_empty(), "cannot generate a value of an enum without variants");
+                let variant_index = generator.below_or_eq(variants.len() as u64 - 1) as usize;
+                let (variant, content) = &variants[variant_index];
+                match content {
+                    Schema::Unit => serializer.serialize_unit_variant(name, variant_index as u32, variant),
+                    content => serializer.serialize_newtype_variant(name, variant_index as u32, variant, &generator.nested(content))
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+use crate::mock::MockValue;
+
+#[test]
+fn test_deterministic() {
+    let schema = Schema::Struct("Foo", vec![
+        ("a", Schema::seq(Schema::option(Schema::Str(0..=5)), 3..=6)),
+        ("b", Schema::map(Schema::U64(0..=100), Schema::Bytes(2..=2), 0..=4)),
+        ("c", Schema::Enum("Bar", vec![("X", Schema::Unit), ("Y", Schema::F64(-1.0..=1.0)), ("Z", Schema::Bool)]))
+    ]);
+    let first = MockValue::from_serialize(&schema.generate(1)).unwrap();
+    assert_eq!(first, MockValue::from_serialize(&schema.generate(1)).unwrap());
+    assert!(first != MockValue::from_serialize(&schema.generate(2)).unwrap());
+
+    for seed in 0..20 {
+        let MockValue::Map(fields) = MockValue::from_serialize(&schema.generate(seed)).unwrap() else { panic!() };
+        let MockValue::Seq(a) = &fields[0].1 else { panic!() };
+        assert!((3..=6).contains(&a.len()));
+        assert!(a.iter().all(|x| matches!(x, MockValue::None) || matches!(x, MockValue::Some(s) if matches!(&**s, MockValue::Str(s) if s.len() <= 5 && s.chars().all(|c| c.is_ascii_alphanumeric())))));
+        let MockValue::Map(b) = &fields[1].1 else { panic!() };
+        assert!(b.iter().all(|(k, v)| matches!(k, MockValue::U64(k) if *k <= 100) && matches!(v, MockValue::Bytes(v) if v.len() == 2)));
+        assert!(matches!(&fields[2].1, MockValue::Enum(..)));
+    }
+}
+
+#[test]
+fn test_ranges() {
+    for seed in 0..100 {
+        let MockValue::Seq(values) = MockValue::from_serialize(&Schema::seq(Schema::I64(i64::MIN..=i64::MAX), 1..=1).generate(seed)).unwrap() else { panic!() };
+        assert_eq!(1, values.len());
+        let MockValue::I64(value) = MockValue::from_serialize(&Schema::I64(-3..=-1).generate(seed)).unwrap() else { panic!() };
+        assert!((-3..=-1).contains(&value));
+        let MockValue::U64(value) = MockValue::from_serialize(&Schema::U64(5..=5).generate(seed)).unwrap() else { panic!() };
+        assert_eq!(5, value);
+        let MockValue::F64(value) = MockValue::from_serialize(&Schema::F64(2.0..=3.0).generate(seed)).unwrap() else { panic!() };
+        assert!((2.0..=3.0).contains(&value));
+    }
+}
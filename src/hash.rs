@@ -0,0 +1,365 @@
+use std::cell::RefCell;
+use std::hash::Hasher;
+
+use serde::ser::*;
+
+use crate::tee::TeeSerializer;
+
+///
+/// Serializes the given value using `serializer`, and at the same time feeds its sequence of
+/// calls to the serde data model into `hasher`, see [`HashSerializer`]. Returns the output of
+/// `serializer`, together with the hasher, from which the digest can be read.
+///
+/// This avoids serializing into a buffer first, just to hash it.
+///
+/// # Example
+/// ```
+/// # use feanor_serde::hash::*;
+/// # use std::collections::hash_map::DefaultHasher;
+/// # use std::hash::Hasher;
+/// let mut json = Vec::new();
+/// let ((), hasher) = serialize_hashed(&vec![1, 2, 3], &mut serde_json::Serializer::new(&mut json), DefaultHasher::new()).unwrap();
+/// assert_eq!("[1,2,3]", String::from_utf8(json).unwrap());
+/// assert_eq!(hash_serialized(&vec![1, 2, 3], DefaultHasher::new()).unwrap().finish(), hasher.finish());
+/// ```
+///
+pub fn serialize_hashed<T, S, H>(value: &T, serializer: S, hasher: H) -> Result<(S::Ok, H), S::Error>
+    where T: ?Sized + Serialize,
+        S: Serializer,
+        H: Hasher
+{
+    let hasher = RefCell::new(hasher);
+    let (result, ()) = value.serialize(TeeSerializer::new(serializer, HashSerializer::new(&hasher)))?;
+    return Ok((result, hasher.into_inner()));
+}
+
+///
+/// Feeds the sequence of calls to the serde data model made when serializing the given value
+/// into `hasher`, and returns it. See [`HashSerializer`].
+///
+pub fn hash_serialized<T, H>(value: &T, hasher: H) -> Result<H, serde::de::value::Error>
+    where T: ?Sized + Serialize,
+        H: Hasher
+{
+    let hasher = RefCell::new(hasher);
+    value.serialize(HashSerializer::new(&hasher))?;
+    return Ok(hasher.into_inner());
+}
+
+///
+/// A [`Serializer`] that does not produce any output, but feeds all calls to the serde data
+/// model, including their arguments and all struct, field and variant names, into a [`Hasher`].
+///
+/// Equal sequences of calls produce equal hashes, independent of any format. Length hints,
+/// e.g. the argument of [`Serializer::serialize_seq()`], are not hashed, since the length is
+/// already determined by the elements. Note that the hash is only deterministic if the calls
+/// are, thus e.g. a [`std::collections::HashMap`] should be wrapped in a
+/// [`crate::canonical::Canonical`] first.
+///
+pub struct HashSerializer<'a, H> {
+    hasher: &'a RefCell<H>,
+    is_human_readable: bool
+}
+
+impl<'a, H> Clone for HashSerializer<'a, H> {
+
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<'a, H> Copy for HashSerializer<'a, H> {}
+
+impl<'a, H> HashSerializer<'a, H>
+    where H: Hasher
+{
+    pub fn new(hasher: &'a RefCell<H>) -> Self {
+        Self { hasher: hasher, is_human_readable: false }
+    }
+
+    pub fn with_human_readable(self, is_human_readable: bool) -> Self {
+        Self { is_human_readable, ..self }
+    }
+
+    fn write_tag(&self, tag: u8) {
+        self.hasher.borrow_mut().write_u8(tag);
+    }
+
+    ///
+    /// Writes the length before the data, so that consecutive strings cannot be confused.
+    ///
+    fn write_bytes(&self, data: &[u8]) {
+        let mut hasher = self.hasher.borrow_mut();
+        hasher.write_usize(data.len());
+        hasher.write(data);
+    }
+
+    fn write_variant(&self, name: &str, variant_index: u32, variant: &str) {
+        self.write_bytes(name.as_bytes());
+        self.hasher.borrow_mut().write_u32(variant_index);
+        self.write_bytes(variant.as_bytes());
+    }
+
+    fn hash<F>(self, tag: u8, f: F) -> Result<(), serde::de::value::Error>
+        where F: FnOnce(&mut H)
+    {
+        self.write_tag(tag);
+        f(&mut self.hasher.borrow_mut());
+        return Ok(());
+    }
+}
+
+impl<'a, H> Serializer for HashSerializer<'a, H>
+    where H: Hasher
+{
+    type Ok = ();
+    type Error = serde::de::value::Error;
+    type SerializeSeq = Self;
+    type SerializeTuple = Self;
+    type SerializeTupleStruct = Self;
+    type SerializeTupleVariant = Self;
+    type SerializeMap = Self;
+    type SerializeStruct = Self;
+    type SerializeStructVariant = Self;
+
+    fn serialize_bool(self, v: bool) -> Result<(), Self::Error> { self.hash(0, |h| h.write_u8(v as u8)) }
+    fn serialize_i8(self, v: i8) -> Result<(), Self::Error> { self.hash(1, |h| h.write_i8(v)) }
+    fn serialize_i16(self, v: i16) -> Result<(), Self::Error> { self.hash(2, |h| h.write_i16(v)) }
+    fn serialize_i32(self, v: i32) -> Result<(), Self::Error> { self.hash(3, |h| h.write_i32(v)) }
+    fn serialize_i64(self, v: i64) -> Result<(), Self::Error> { self.hash(4, |h| h.write_i64(v)) }
+    fn serialize_i128(self, v: i128) -> Result<(), Self::Error> { self.hash(5, |h| h.write_i128(v)) }
+    fn serialize_u8(self, v: u8) -> Result<(), Self::Error> { self.hash(6, |h| h.write_u8(v)) }
+    fn serialize_u16(self, v: u16) -> Result<(), Self::Error> { self.hash(7, |h| h.write_u16(v)) }
+    fn serialize_u32(self, v: u32) -> Result<(), Self::Error> { self.hash(8, |h| h.write_u32(v)) }
+    fn serialize_u64(self, v: u64) -> Result<(), Self::Error> { self.hash(9, |h| h.write_u64(v)) }
+    fn serialize_u128(self, v: u128) -> Result<(), Self::Error> { self.hash(10, |h| h.write_u128(v)) }
+    fn serialize_f32(self, v: f32) -> Result<(), Self::Error> { self.hash(11, |h| h.write_u32(v.to_bits())) }
+    fn serialize_f64(self, v: f64) -> Result<(), Self::Error> { self.hash(12, |h| h.write_u64(v.to_bits())) }
+    fn serialize_char(self, v: char) -> Result<(), Self::Error> { self.hash(13, |h| h.write_u32(v as u32)) }
+
+    fn serialize_str(self, v: &str) -> Result<(), Self::Error> {
+        self.write_tag(14);
+        self.write_bytes(v.as_bytes());
+        return Ok(());
+    }
+
+    fn serialize_bytes(self, v: &[u8]) -> Result<(), Self::Error> {
+        self.write_tag(15);
+        self.write_bytes(v);
+        return Ok(());
+    }
+
+    fn serialize_none(self) -> Result<(), Self::Error> {
+        self.write_tag(16);
+        return Ok(());
+    }
+
+    fn serialize_some<T>(self, value: &T) -> Result<(), Self::Error>
+        where T: ?Sized + Serialize
+    {
+        self.write_tag(17);
+        return value.serialize(self);
+    }
+
+    fn serialize_unit(self) -> Result<(), Self::Error> {
+        self.write_tag(18);
+        return Ok(());
+    }
+
+    fn serialize_unit_struct(self, name: &'static str) -> Result<(), Self::Error> {
+        self.write_tag(19);
+        self.write_bytes(name.as_bytes());
+        return Ok(());
+    }
+
+    fn serialize_unit_variant(self, name: &'static str, variant_index: u32, variant: &'static str) -> Result<(), Self::Error> {
+        self.write_tag(20);
+        self.write_variant(name, variant_index, variant);
+        return Ok(());
+    }
+
+    fn serialize_newtype_struct<T>(self, name: &'static str, value: &T) -> Result<(), Self::Error>
+        where T: ?Sized + Serialize
+    {
+        self.write_tag(21);
+        self.write_bytes(name.as_bytes());
+        return value.serialize(self);
+    }
+
+    fn serialize_newtype_variant<T>(self, name: &'static str, variant_index: u32, variant: &'static str, value: &T) -> Result<(), Self::Error>
+        where T: ?Sized + Serialize
+    {
+        self.write_tag(22);
+        self.write_variant(name, variant_index, variant);
+        return value.serialize(self);
+    }
+
+    fn serialize_seq(self, _len: Option<usize>) -> Result<Self, Self::Error> {
+        self.write_tag(23);
+        return Ok(self);
+    }
+
+    fn serialize_tuple(self, _len: usize) -> Result<Self, Self::Error> {
+        self.write_tag(24);
+        return Ok(self);
+    }
+
+    fn serialize_tuple_struct(self, name: &'static str, _len: usize) -> Result<Self, Self::Error> {
+        self.write_tag(25);
+        self.write_bytes(name.as_bytes());
+        return Ok(self);
+    }
+
+    fn serialize_tuple_variant(self, name: &'static str, variant_index: u32, variant: &'static str, _len: usize) -> Result<Self, Self::Error> {
+        self.write_tag(26);
+        self.write_variant(name, variant_index, variant);
+        return Ok(self);
+    }
+
+    fn serialize_map(self, _len: Option<usize>) -> Result<Self, Self::Error> {
+        self.write_tag(27);
+        return Ok(self);
+    }
+
+    fn serialize_struct(self, name: &'static str, _len: usize) -> Result<Self, Self::Error> {
+        self.write_tag(28);
+        self.write_bytes(name.as_bytes());
+        return Ok(self);
+    }
+
+    fn serialize_struct_variant(self, name: &'static str, variant_index: u32, variant: &'static str, _len: usize) -> Result<Self, Self::Error> {
+        self.write_tag(29);
+        self.write_variant(name, variant_index, variant);
+        return Ok(self);
+    }
+
+    fn is_human_readable(&self) -> bool {
+        self.is_human_readable
+    }
+}
+
+///
+/// Tag of the call that separates the elements of a compound from what follows it.
+///
+const END_TAG: u8 = 30;
+///
+/// Tag of every element resp. entry of a compound, so that e.g. `[[], []]` and `[[[]]]` differ.
+///
+const ELEMENT_TAG: u8 = 31;
+
+macro_rules! impl_hash_compound {
+    ($trait:ident, $method:ident) => {
+        impl<'a, H> $trait for HashSerializer<'a, H>
+            where H: Hasher
+        {
+            type Ok = ();
+            type Error = serde::de::value::Error;
+
+            fn $method<T>(&mut self, value: &T) -> Result<(), Self::Error>
+                where T: ?Sized + Serialize
+            {
+                self.write_tag(ELEMENT_TAG);
+                return value.serialize(*self);
+            }
+
+            fn end(self) -> Result<(), Self::Error> {
+                self.write_tag(END_TAG);
+                return Ok(());
+            }
+        }
+    };
+}
+
+impl_hash_compound!(SerializeSeq, serialize_element);
+impl_hash_compound!(SerializeTuple, serialize_element);
+impl_hash_compound!(SerializeTupleStruct, serialize_field);
+impl_hash_compound!(SerializeTupleVariant, serialize_field);
+
+impl<'a, H> SerializeMap for HashSerializer<'a, H>
+    where H: Hasher
+{
+    type Ok = ();
+    type Error = serde::de::value::Error;
+
+    fn serialize_key<T>(&mut self, key: &T) -> Result<(), Self::Error>
+        where T: ?Sized + Serialize
+    {
+        self.write_tag(ELEMENT_TAG);
+        return key.serialize(*self);
+    }
+
+    fn serialize_value<T>(&mut self, value: &T) -> Result<(), Self::Error>
+        where T: ?Sized + Serialize
+    {
+        value.serialize(*self)
+    }
+
+    fn end(self) -> Result<(), Self::Error> {
+        self.write_tag(END_TAG);
+        return Ok(());
+    }
+}
+
+macro_rules! impl_hash_struct {
+    ($trait:ident) => {
+        impl<'a, H> $trait for HashSerializer<'a, H>
+            where H: Hasher
+        {
+            type Ok = ();
+            type Error = serde::de::value::Error;
+
+            fn serialize_field<T>(&mut self, key: &'static str, value: &T) -> Result<(), Self::Error>
+                where T: ?Sized + Serialize
+            {
+                self.write_tag(ELEMENT_TAG);
+                self.write_bytes(key.as_bytes());
+                return value.serialize(*self);
+            }
+
+            fn end(self) -> Result<(), Self::Error> {
+                self.write_tag(END_TAG);
+                return Ok(());
+            }
+        }
+    };
+}
+
+impl_hash_struct!(SerializeStruct);
+impl_hash_struct!(SerializeStructVariant);
+
+#[cfg(test)]
+use std::collections::hash_map::DefaultHasher;
+#[cfg(test)]
+use std::collections::{BTreeMap, HashMap};
+#[cfg(test)]
+use crate::canonical::Canonical;
+
+#[cfg(test)]
+fn hash<T>(value: &T) -> u64
+    where T: ?Sized + Serialize
+{
+    hash_serialized(value, DefaultHasher::new()).unwrap().finish()
+}
+
+#[test]
+fn test_hash_distinguishes() {
+    assert_eq!(hash(&vec![1i64, 2]), hash(&[1i64, 2][..]));
+    assert!(hash(&vec![vec![], vec![1i64]]) != hash(&vec![vec![1i64], vec![]]));
+    assert!(hash(&vec![vec![(); 0]; 2]) != hash(&vec![vec![vec![(); 0]]]));
+    assert!(hash(&("ab", "c")) != hash(&("a", "bc")));
+    assert!(hash(&1i64) != hash(&1u64));
+    assert!(hash(&Some(())) != hash(&None::<()>));
+
+    let map: HashMap<_, _> = (0..100).map(|i| (i, i.to_string())).collect();
+    let sorted: BTreeMap<_, _> = map.clone().into_iter().collect();
+    assert_eq!(hash(&sorted), hash(&Canonical::new(&map)));
+}
+
+#[test]
+fn test_serialize_hashed() {
+    let value = vec![(Some(1.5f64), "a".to_owned()), (None, "b".to_owned())];
+    let mut json = Vec::new();
+    let ((), hasher) = serialize_hashed(&value, &mut serde_json::Serializer::new(&mut json), DefaultHasher::new()).unwrap();
+    assert_eq!(serde_json::to_vec(&value).unwrap(), json);
+    assert_eq!(hash(&value), hasher.finish());
+}
@@ -0,0 +1,112 @@
+use std::hash::Hash;
+
+use serde::de::DeserializeSeed;
+
+use ::hashbrown::{HashMap, HashSet};
+
+use crate::map::{DeserializeSeedMap, DeserializeSeedMapAsSeq};
+use crate::seq::{SeqSeedBuilder, DeserializeSeedSeq};
+
+///
+/// The collector used by [`DeserializeSeedMap::new_hashbrown_map()`] and
+/// [`DeserializeSeedMapAsSeq::new_hashbrown_map()`].
+///
+pub type InsertIntoHashMap<K, V> = fn(HashMap<K, V>, K, V) -> HashMap<K, V>;
+
+///
+/// The collector used by [`SeqSeedBuilder::collect_hashbrown_set()`].
+///
+pub type InsertIntoHashSet<T> = fn(HashSet<T>, T) -> HashSet<T>;
+
+impl<'de, V, KS, VS> DeserializeSeedMap<V, KS, VS, HashMap<KS::Value, VS::Value>, InsertIntoHashMap<KS::Value, VS::Value>>
+    where V: Iterator<Item = (KS, VS)>,
+        KS: DeserializeSeed<'de>,
+        VS: DeserializeSeed<'de>,
+        KS::Value: Eq + Hash
+{
+    ///
+    /// Creates a [`DeserializeSeedMap`] that collects the entries into a [`hashbrown::HashMap`].
+    /// As for the [`HashMap`] of the standard library, later entries replace earlier ones
+    /// with the same key.
+    ///
+    /// # Example
+    /// ```
+    /// # use feanor_serde::map::*;
+    /// # use feanor_serde::default_seed::seed_of;
+    /// # use serde::de::DeserializeSeed;
+    /// let seed = DeserializeSeedMap::new_hashbrown_map(std::iter::repeat((seed_of::<String>(), seed_of::<i64>())));
+    /// let result = seed.deserialize(&mut serde_json::Deserializer::from_str(r#"{"a": 1, "b": 2}"#)).unwrap();
+    /// assert_eq!(Some(&2), result.get("b"));
+    /// ```
+    ///
+    pub fn new_hashbrown_map(seeds: V) -> Self {
+        Self::new(seeds, HashMap::new(), |mut current, key, value| { current.insert(key, value); current })
+    }
+}
+
+impl<'de, V, KS, VS> DeserializeSeedMapAsSeq<V, KS, VS, HashMap<KS::Value, VS::Value>, InsertIntoHashMap<KS::Value, VS::Value>>
+    where V: Iterator<Item = (KS, VS)>,
+        KS: DeserializeSeed<'de>,
+        VS: DeserializeSeed<'de>,
+        KS::Value: Eq + Hash
+{
+    ///
+    /// Creates a [`DeserializeSeedMapAsSeq`] that collects the entries into a [`hashbrown::HashMap`].
+    /// As for the [`HashMap`] of the standard library, later entries replace earlier ones
+    /// with the same key.
+    ///
+    pub fn new_hashbrown_map(seeds: V) -> Self {
+        Self::new(seeds, HashMap::new(), |mut current, key, value| { current.insert(key, value); current })
+    }
+}
+
+impl<'de, V, S> SeqSeedBuilder<V>
+    where V: Iterator<Item = S>,
+        S: DeserializeSeed<'de>,
+        S::Value: Eq + Hash
+{
+    ///
+    /// Builds a [`DeserializeSeedSeq`] that collects the elements into a [`hashbrown::HashSet`],
+    /// with initial capacity and reservation as for [`SeqSeedBuilder::collect_vec()`]. Duplicate
+    /// elements are only stored once.
+    ///
+    /// # Example
+    /// ```
+    /// # use feanor_serde::seq::*;
+    /// # use feanor_serde::default_seed::seed_of;
+    /// # use serde::de::DeserializeSeed;
+    /// let seed = SeqSeedBuilder::new(seed_of::<i64>).collect_hashbrown_set();
+    /// let result = seed.deserialize(&mut serde_json::Deserializer::from_str("[1, 3, 1]")).unwrap();
+    /// assert_eq!(2, result.len());
+    /// ```
+    ///
+    pub fn collect_hashbrown_set(self) -> DeserializeSeedSeq<V, S, HashSet<S::Value>, InsertIntoHashSet<S::Value>> {
+        let capacity = self.initial_capacity();
+        return self.fold::<_, InsertIntoHashSet<_>>(HashSet::with_capacity(capacity), |mut current, next| { current.insert(next); current }).with_reserve(HashSet::reserve);
+    }
+}
+
+#[cfg(test)]
+use crate::default_seed::seed_of;
+#[cfg(test)]
+use crate::map::SerializableMapAsSeq;
+
+#[test]
+fn test_hashbrown_map() {
+    let seed = DeserializeSeedMap::new_hashbrown_map(std::iter::repeat((seed_of::<String>(), seed_of::<i64>())));
+    let result = seed.deserialize(&mut serde_json::Deserializer::from_str(r#"{"a": 1, "b": 2, "a": 3}"#)).unwrap();
+    assert_eq!(HashMap::from([("a".to_owned(), 3), ("b".to_owned(), 2)]), result);
+
+    let data = HashMap::from([((1, 2), 3)]);
+    let serialized = postcard::to_allocvec(&SerializableMapAsSeq::new_with_len(data.iter(), data.len())).unwrap();
+    let seed = DeserializeSeedMapAsSeq::new_hashbrown_map(std::iter::repeat((seed_of::<(i64, i64)>(), seed_of::<i64>())));
+    assert_eq!(data, seed.deserialize(&mut postcard::Deserializer::from_bytes(&serialized)).unwrap());
+}
+
+#[test]
+fn test_hashbrown_set() {
+    let seed = SeqSeedBuilder::new(seed_of::<i64>).max_len(3).collect_hashbrown_set();
+    assert_eq!(HashSet::from([1, 2]), seed.deserialize(&mut serde_json::Deserializer::from_str("[1, 2, 1]")).unwrap());
+    let seed = SeqSeedBuilder::new(seed_of::<i64>).max_len(3).collect_hashbrown_set();
+    assert!(seed.deserialize(&mut serde_json::Deserializer::from_str("[1, 2, 1, 2]")).is_err());
+}
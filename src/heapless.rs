@@ -0,0 +1,190 @@
+use std::marker::PhantomData;
+
+use serde::de::{DeserializeSeed, SeqAccess, Unexpected, Visitor};
+use serde::de::Error;
+
+use ::heapless::{String, Vec};
+
+///
+/// A [`DeserializeSeed`] that deserializes a sequence into a [`heapless::Vec`] of capacity `N`,
+/// using the seeds produced by the given iterator for the elements. This does not allocate,
+/// and fails if the sequence has more than `N` elements.
+///
+/// As opposed to [`crate::seq::DeserializeSeedSeq`], the iterator producing the seeds only needs
+/// to produce `N + 1` seeds, even if it is infinite.
+///
+/// # Example
+/// ```
+/// # use feanor_serde::heapless::*;
+/// # use feanor_serde::default_seed::seed_of;
+/// # use std::iter::repeat;
+/// # use serde::de::DeserializeSeed;
+/// let mut deserializer = serde_json::Deserializer::new(serde_json::de::StrRead::new("[1, 3, 5]"));
+/// let deserialize_seed = DeserializeSeedHeaplessVec::<_, _, 4>::new(repeat(seed_of::<i64>()));
+/// assert_eq!(&[1, 3, 5], &deserialize_seed.deserialize(&mut deserializer).unwrap()[..]);
+///
+/// let mut deserializer = serde_json::Deserializer::new(serde_json::de::StrRead::new("[1, 3, 5]"));
+/// let deserialize_seed = DeserializeSeedHeaplessVec::<_, _, 2>::new(repeat(seed_of::<i64>()));
+/// assert!(deserialize_seed.deserialize(&mut deserializer).is_err());
+/// ```
+///
+pub struct DeserializeSeedHeaplessVec<V, S, const N: usize>
+    where V: Iterator<Item = S>
+{
+    seeds: V
+}
+
+impl<'de, V, S, const N: usize> DeserializeSeedHeaplessVec<V, S, N>
+    where V: Iterator<Item = S>,
+        S: DeserializeSeed<'de>
+{
+    pub const fn new(seeds: V) -> Self {
+        Self { seeds: seeds }
+    }
+}
+
+impl<'de, V, S, const N: usize> DeserializeSeed<'de> for DeserializeSeedHeaplessVec<V, S, N>
+    where V: Iterator<Item = S>,
+        S: DeserializeSeed<'de>
+{
+    type Value = Vec<S::Value, N>;
+
+    fn deserialize<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+        where D: serde::Deserializer<'de>
+    {
+        struct ResultVisitor<'de, V, S, const N: usize>
+            where V: Iterator<Item = S>,
+                S: DeserializeSeed<'de>
+        {
+            deserializer: PhantomData<&'de ()>,
+            seeds: V
+        }
+
+        impl<'de, V, S, const N: usize> Visitor<'de> for ResultVisitor<'de, V, S, N>
+            where V: Iterator<Item = S>,
+                S: DeserializeSeed<'de>
+        {
+            type Value = Vec<S::Value, N>;
+
+            fn expecting(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+                write!(f, "a sequence of at most {} elements", N)
+            }
+
+            fn visit_seq<B>(mut self, mut seq: B) -> Result<Self::Value, B::Error>
+                where B: SeqAccess<'de>
+            {
+                if let Some(len) = seq.size_hint() {
+                    if len > N {
+                        return Err(crate::errors::invalid_length(len, "a shorter sequence", format_args!("a sequence of at most {} elements", N)));
+                    }
+                }
+                let mut result = Vec::new();
+                for seed in self.seeds.by_ref() {
+                    match seq.next_element_seed(seed)? {
+                        Some(el) => if result.push(el).is_err() {
+                            return Err(crate::errors::invalid_length(N + 1, "a shorter sequence", format_args!("a sequence of at most {} elements", N)));
+                        },
+                        None => return Ok(result)
+                    }
+                }
+                return Err(Error::custom("ran out of seeds"));
+            }
+        }
+
+        return deserializer.deserialize_seq(ResultVisitor::<'de, V, S, N> {
+            deserializer: PhantomData,
+            seeds: self.seeds
+        });
+    }
+}
+
+///
+/// A [`DeserializeSeed`] that deserializes a string into a [`heapless::String`] of capacity `N`
+/// (in bytes). This does not allocate, and fails if the string is longer than `N` bytes.
+///
+/// # Example
+/// ```
+/// # use feanor_serde::heapless::*;
+/// # use serde::de::DeserializeSeed;
+/// let mut deserializer = serde_json::Deserializer::new(serde_json::de::StrRead::new("\"abc\""));
+/// assert_eq!("abc", DeserializeSeedHeaplessString::<4>::new().deserialize(&mut deserializer).unwrap().as_str());
+/// ```
+///
+#[derive(Clone, Copy, Default)]
+pub struct DeserializeSeedHeaplessString<const N: usize>;
+
+impl<const N: usize> DeserializeSeedHeaplessString<N> {
+
+    pub const fn new() -> Self {
+        Self
+    }
+}
+
+impl<'de, const N: usize> DeserializeSeed<'de> for DeserializeSeedHeaplessString<N> {
+    type Value = String<N>;
+
+    fn deserialize<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+        where D: serde::Deserializer<'de>
+    {
+        struct ResultVisitor<const N: usize>;
+
+        impl<'de, const N: usize> Visitor<'de> for ResultVisitor<N> {
+            type Value = String<N>;
+
+            fn expecting(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+                write!(f, "a string of at most {} bytes", N)
+            }
+
+            fn visit_str<E>(self, v: &str) -> Result<Self::Value, E>
+                where E: Error
+            {
+                let mut result = String::new();
+                if result.push_str(v).is_err() {
+                    return Err(crate::errors::invalid_value(Unexpected::Str(v), "a shorter string", format_args!("a string of at most {} bytes", N)));
+                }
+                return Ok(result);
+            }
+
+            fn visit_bytes<E>(self, v: &[u8]) -> Result<Self::Value, E>
+                where E: Error
+            {
+                match std::str::from_utf8(v) {
+                    Ok(v) => self.visit_str(v),
+                    Err(_) => Err(Error::invalid_value(Unexpected::Bytes(v), &self))
+                }
+            }
+        }
+
+        return deserializer.deserialize_str(ResultVisitor::<N>);
+    }
+}
+
+#[cfg(test)]
+use std::iter::repeat;
+
+#[test]
+fn test_serde_json() {
+    let result = DeserializeSeedHeaplessVec::<_, _, 3>::new(repeat(PhantomData::<i64>)).deserialize(&mut serde_json::Deserializer::from_str("[1, 2, 3]")).unwrap();
+    assert_eq!(&[1, 2, 3], &result[..]);
+    let result = DeserializeSeedHeaplessVec::<_, _, 3>::new(repeat(PhantomData::<i64>)).deserialize(&mut serde_json::Deserializer::from_str("[]")).unwrap();
+    assert!(result.is_empty());
+    assert!(DeserializeSeedHeaplessVec::<_, _, 3>::new(repeat(PhantomData::<i64>)).deserialize(&mut serde_json::Deserializer::from_str("[1, 2, 3, 4]")).is_err());
+    assert!(DeserializeSeedHeaplessVec::<_, _, 0>::new(repeat(PhantomData::<i64>)).deserialize(&mut serde_json::Deserializer::from_str("[1]")).is_err());
+
+    let result = DeserializeSeedHeaplessString::<3>::new().deserialize(&mut serde_json::Deserializer::from_str("\"abc\"")).unwrap();
+    assert_eq!("abc", result.as_str());
+    assert!(DeserializeSeedHeaplessString::<3>::new().deserialize(&mut serde_json::Deserializer::from_str("\"abcd\"")).is_err());
+}
+
+#[test]
+fn test_serde_postcard() {
+    let serialized = postcard::to_allocvec(&(vec![1i64, 2, 3], "abc")).unwrap();
+    let mut deserializer = postcard::Deserializer::from_flavor(postcard::de_flavors::Slice::new(&serialized));
+    let vec = DeserializeSeedHeaplessVec::<_, _, 4>::new(repeat(PhantomData::<i64>)).deserialize(&mut deserializer).unwrap();
+    let string = DeserializeSeedHeaplessString::<3>::new().deserialize(&mut deserializer).unwrap();
+    assert_eq!(&[1, 2, 3], &vec[..]);
+    assert_eq!("abc", string.as_str());
+
+    let mut deserializer = postcard::Deserializer::from_flavor(postcard::de_flavors::Slice::new(&serialized));
+    assert!(DeserializeSeedHeaplessVec::<_, _, 2>::new(repeat(PhantomData::<i64>)).deserialize(&mut deserializer).is_err());
+}
@@ -0,0 +1,306 @@
+use serde::de::{DeserializeSeed, Error, SeqAccess, Unexpected, Visitor};
+use serde::ser::{Serialize, Serializer};
+use serde::Deserializer;
+
+///
+/// Configuration of the hex-encoding used by [`SerializableHex`] and
+/// [`DeserializeSeedHex`].
+///
+/// The default configuration uses lowercase digits without prefix.
+///
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct HexConfig {
+    uppercase: bool,
+    prefix: bool
+}
+
+impl HexConfig {
+
+    pub const LOWER: HexConfig = HexConfig { uppercase: false, prefix: false };
+    pub const UPPER: HexConfig = HexConfig { uppercase: true, prefix: false };
+    pub const LOWER_PREFIXED: HexConfig = HexConfig { uppercase: false, prefix: true };
+    pub const UPPER_PREFIXED: HexConfig = HexConfig { uppercase: true, prefix: true };
+
+    pub const fn new(uppercase: bool, prefix: bool) -> Self {
+        Self { uppercase, prefix }
+    }
+
+    ///
+    /// Whether to use uppercase digits during encoding. Decoding always accepts
+    /// both uppercase and lowercase digits.
+    ///
+    pub fn uppercase(&self) -> bool {
+        self.uppercase
+    }
+
+    ///
+    /// Whether the encoded string starts with `0x`. During decoding, the prefix
+    /// is required if this is set, and forbidden otherwise.
+    ///
+    pub fn prefix(&self) -> bool {
+        self.prefix
+    }
+
+    ///
+    /// Encodes the given bytes as hex string.
+    ///
+    pub fn encode(&self, data: &[u8]) -> String {
+        let digits: &[u8; 16] = if self.uppercase { b"0123456789ABCDEF" } else { b"0123456789abcdef" };
+        let mut result = String::with_capacity(2 * data.len() + 2);
+        if self.prefix {
+            result.push_str("0x");
+        }
+        for byte in data {
+            result.push(digits[(byte >> 4) as usize] as char);
+            result.push(digits[(byte & 0xF) as usize] as char);
+        }
+        return result;
+    }
+
+    ///
+    /// Decodes the given hex string into the given buffer, whose length must
+    /// match the number of encoded bytes. Returns `None` if the string is not a
+    /// valid encoding of `buffer.len()` bytes w.r.t. this configuration.
+    ///
+    pub fn decode_into(&self, data: &str, buffer: &mut [u8]) -> Option<()> {
+        let data = if self.prefix { data.strip_prefix("0x")? } else { data };
+        if data.len() != 2 * buffer.len() {
+            return None;
+        }
+        let decode_digit = |c: u8| (c as char).to_digit(16).map(|d| d as u8);
+        for (chunk, byte) in data.as_bytes().chunks(2).zip(buffer.iter_mut()) {
+            *byte = (decode_digit(chunk[0])? << 4) | decode_digit(chunk[1])?;
+        }
+        return Some(());
+    }
+
+    ///
+    /// Decodes the given hex string, or returns `None` if it is not a valid
+    /// encoding w.r.t. this configuration.
+    ///
+    pub fn decode(&self, data: &str) -> Option<Vec<u8>> {
+        let len = data.len().checked_sub(if self.prefix { 2 } else { 0 })? / 2;
+        let mut result = vec![0; len];
+        self.decode_into(data, &mut result)?;
+        return Some(result);
+    }
+}
+
+///
+/// Wraps a byte buffer, and implements [`Serialize`] by mapping it to a hex-encoded
+/// string for human-readable serializers, and to the bytes type in the serde data model
+/// otherwise.
+///
+/// This is mainly intended for short byte arrays that humans need to read and compare,
+/// like hashes or IDs. For large byte buffers, consider [`crate::base64::SerializableBase64`]
+/// instead.
+///
+/// # Example
+/// ```
+/// # use feanor_serde::hex::*;
+/// let hash = [0xDE, 0xAD, 0xBE, 0xEF];
+/// assert_eq!("\"deadbeef\"", serde_json::to_string(&SerializableHex::new(&hash)).unwrap());
+/// assert_eq!("\"0xDEADBEEF\"", serde_json::to_string(&SerializableHex::new_with_config(&hash, HexConfig::UPPER_PREFIXED)).unwrap());
+/// ```
+///
+pub struct SerializableHex<T>
+    where T: AsRef<[u8]>
+{
+    data: T,
+    config: HexConfig
+}
+
+impl<T> SerializableHex<T>
+    where T: AsRef<[u8]>
+{
+    pub fn new(data: T) -> Self {
+        Self::new_with_config(data, HexConfig::default())
+    }
+
+    pub fn new_with_config(data: T, config: HexConfig) -> Self {
+        Self { data, config }
+    }
+}
+
+impl<T> Serialize for SerializableHex<T>
+    where T: AsRef<[u8]>
+{
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+        where S: Serializer
+    {
+        if serializer.is_human_readable() {
+            serializer.serialize_str(&self.config.encode(self.data.as_ref()))
+        } else {
+            serializer.serialize_bytes(self.data.as_ref())
+        }
+    }
+}
+
+///
+/// A [`DeserializeSeed`] that deserializes a byte array of length `N` that was
+/// serialized using [`SerializableHex`] with the same [`HexConfig`], and fails
+/// if the serialized data has a different length.
+///
+/// # Example
+/// ```
+/// # use feanor_serde::hex::*;
+/// # use serde::de::DeserializeSeed;
+/// let mut deserializer = serde_json::Deserializer::new(serde_json::de::StrRead::new("\"deadbeef\""));
+/// assert_eq!([0xDE, 0xAD, 0xBE, 0xEF], DeserializeSeedHex::<4>::new().deserialize(&mut deserializer).unwrap());
+/// let mut deserializer = serde_json::Deserializer::new(serde_json::de::StrRead::new("\"deadbeef\""));
+/// assert!(DeserializeSeedHex::<3>::new().deserialize(&mut deserializer).is_err());
+/// ```
+///
+#[derive(Clone, Copy, Default)]
+pub struct DeserializeSeedHex<const N: usize> {
+    config: HexConfig
+}
+
+impl<const N: usize> DeserializeSeedHex<N> {
+
+    pub fn new() -> Self {
+        Self::new_with_config(HexConfig::default())
+    }
+
+    pub fn new_with_config(config: HexConfig) -> Self {
+        Self { config }
+    }
+}
+
+impl<'de, const N: usize> DeserializeSeed<'de> for DeserializeSeedHex<N> {
+    type Value = [u8; N];
+
+    fn deserialize<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+        where D: Deserializer<'de>
+    {
+        struct HexVisitor<const N: usize> {
+            config: HexConfig
+        }
+
+        impl<'de, const N: usize> Visitor<'de> for HexVisitor<N> {
+            type Value = [u8; N];
+
+            fn expecting(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+                write!(f, "a hex-encoded string of {} bytes", N)
+            }
+
+            fn visit_str<E>(self, value: &str) -> Result<Self::Value, E>
+                where E: Error
+            {
+                let mut result = [0; N];
+                self.config.decode_into(value, &mut result).ok_or_else(|| E::invalid_value(Unexpected::Str(value), &self))?;
+                return Ok(result);
+            }
+        }
+
+        struct BytesVisitor<const N: usize>;
+
+        impl<'de, const N: usize> Visitor<'de> for BytesVisitor<N> {
+            type Value = [u8; N];
+
+            fn expecting(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+                write!(f, "a byte buffer of length {}", N)
+            }
+
+            fn visit_bytes<E>(self, value: &[u8]) -> Result<Self::Value, E>
+                where E: Error
+            {
+                value.try_into().map_err(|_| E::invalid_length(value.len(), &self))
+            }
+
+            fn visit_seq<A>(self, mut seq: A) -> Result<Self::Value, A::Error>
+                where A: SeqAccess<'de>
+            {
+                let mut result = [0; N];
+                for (i, byte) in result.iter_mut().enumerate() {
+                    *byte = seq.next_element()?.ok_or_else(|| A::Error::invalid_length(i, &self))?;
+                }
+                if seq.next_element::<u8>()?.is_some() {
+                    return Err(A::Error::invalid_length(N + 1, &self));
+                }
+                return Ok(result);
+            }
+        }
+
+        if deserializer.is_human_readable() {
+            deserializer.deserialize_str(HexVisitor { config: self.config })
+        } else {
+            deserializer.deserialize_bytes(BytesVisitor)
+        }
+    }
+}
+
+#[cfg(test)]
+use crate::test::token::{Token, TokenDeserializer, TokenSerializer};
+
+#[test]
+fn test_encode_decode() {
+    assert_eq!("", HexConfig::LOWER.encode(&[]));
+    assert_eq!("0x", HexConfig::LOWER_PREFIXED.encode(&[]));
+    assert_eq!("00ff1a", HexConfig::LOWER.encode(&[0x00, 0xFF, 0x1A]));
+    assert_eq!("00FF1A", HexConfig::UPPER.encode(&[0x00, 0xFF, 0x1A]));
+    assert_eq!("0x00FF1A", HexConfig::UPPER_PREFIXED.encode(&[0x00, 0xFF, 0x1A]));
+
+    assert_eq!(Some(vec![0x00, 0xFF, 0x1A]), HexConfig::LOWER.decode("00ff1a"));
+    assert_eq!(Some(vec![0x00, 0xFF, 0x1A]), HexConfig::LOWER.decode("00FF1a"));
+    assert_eq!(Some(vec![0x00, 0xFF, 0x1A]), HexConfig::UPPER_PREFIXED.decode("0x00ff1a"));
+    assert_eq!(Some(vec![]), HexConfig::LOWER_PREFIXED.decode("0x"));
+    assert_eq!(None, HexConfig::LOWER.decode("0x00ff1a"));
+    assert_eq!(None, HexConfig::LOWER_PREFIXED.decode("00ff1a"));
+    assert_eq!(None, HexConfig::LOWER.decode("00ff1"));
+    assert_eq!(None, HexConfig::LOWER.decode("00fg1a"));
+    assert_eq!(None, HexConfig::LOWER_PREFIXED.decode("0"));
+}
+
+#[test]
+fn test_serde_postcard() {
+    let data = [0xDE, 0xAD, 0xBE, 0xEF];
+    let serialized = postcard::to_allocvec(&SerializableHex::new(&data)).unwrap();
+    let result = DeserializeSeedHex::<4>::new().deserialize(
+        &mut postcard::Deserializer::from_flavor(postcard::de_flavors::Slice::new(&serialized))
+    ).unwrap();
+    assert_eq!(data, result);
+
+    let result = DeserializeSeedHex::<5>::new().deserialize(
+        &mut postcard::Deserializer::from_flavor(postcard::de_flavors::Slice::new(&serialized))
+    );
+    assert!(result.is_err());
+}
+
+#[test]
+fn test_serde_json() {
+    let data = [0xDE, 0xAD, 0xBE, 0xEF];
+    for config in [HexConfig::LOWER, HexConfig::UPPER, HexConfig::LOWER_PREFIXED, HexConfig::UPPER_PREFIXED] {
+        let serialized = serde_json::to_string(&SerializableHex::new_with_config(&data, config)).unwrap();
+        assert_eq!(format!("\"{}\"", config.encode(&data)), serialized);
+        let result = DeserializeSeedHex::<4>::new_with_config(config).deserialize(
+            &mut serde_json::Deserializer::from_str(&serialized)
+        ).unwrap();
+        assert_eq!(data, result);
+
+        let result = DeserializeSeedHex::<3>::new_with_config(config).deserialize(
+            &mut serde_json::Deserializer::from_str(&serialized)
+        );
+        assert!(result.is_err());
+    }
+}
+
+#[test]
+fn test_serde_tokens() {
+    for human_readable in [true, false] {
+        let data = [1, 2, 3, 4];
+        let serializer = TokenSerializer::new().with_human_readable(human_readable);
+        let tokens = SerializableHex::new(&data).serialize(serializer).unwrap();
+        if human_readable {
+            assert_eq!(tokens, [Token::Str("01020304".to_owned())]);
+        } else {
+            assert_eq!(tokens, [Token::Bytes(data.to_vec())]);
+        }
+        let mut deserializer = TokenDeserializer::new(tokens.clone()).with_human_readable(human_readable);
+        let result = DeserializeSeedHex::<4>::new().deserialize(&mut deserializer).unwrap();
+        assert_eq!(data, result);
+
+        let mut deserializer = TokenDeserializer::new(tokens).with_human_readable(human_readable);
+        assert!(DeserializeSeedHex::<2>::new().deserialize(&mut deserializer).is_err());
+    }
+}
@@ -0,0 +1,127 @@
+use serde::de::DeserializeSeed;
+use serde::ser::{Serialize, Serializer};
+use serde::Deserializer;
+
+///
+/// Selects one of two strategies, depending on whether the (de)serializer
+/// is human-readable or not, as reported by [`Serializer::is_human_readable()`]
+/// resp. [`Deserializer::is_human_readable()`].
+///
+/// If both strategies implement [`Serialize`], so does [`HumanReadableOr`]. If both
+/// strategies implement [`DeserializeSeed`] with the same value type, so does
+/// [`HumanReadableOr`]. This allows e.g. to serialize bytes as hex string in JSON,
+/// but as raw bytes in postcard, without having to choose the right wrapper at every
+/// call site.
+///
+/// # Example
+/// ```
+/// # use feanor_serde::human_readable::*;
+/// let value = HumanReadableOr::new("forty-two", 42u8);
+/// assert_eq!("\"forty-two\"", serde_json::to_string(&value).unwrap());
+/// assert_eq!(vec![42], postcard::to_allocvec(&value).unwrap());
+/// ```
+///
+pub struct HumanReadableOr<S1, S2> {
+    human_readable: S1,
+    binary: S2
+}
+
+impl<S1, S2> HumanReadableOr<S1, S2> {
+
+    ///
+    /// Creates a new [`HumanReadableOr`] that uses `human_readable` for human-readable
+    /// (de)serializers, and `binary` otherwise.
+    ///
+    pub const fn new(human_readable: S1, binary: S2) -> Self {
+        Self { human_readable, binary }
+    }
+}
+
+impl<S1, S2> Serialize for HumanReadableOr<S1, S2>
+    where S1: Serialize,
+        S2: Serialize
+{
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+        where S: Serializer
+    {
+        if serializer.is_human_readable() {
+            self.human_readable.serialize(serializer)
+        } else {
+            self.binary.serialize(serializer)
+        }
+    }
+}
+
+impl<'de, S1, S2> DeserializeSeed<'de> for HumanReadableOr<S1, S2>
+    where S1: DeserializeSeed<'de>,
+        S2: DeserializeSeed<'de, Value = S1::Value>
+{
+    type Value = S1::Value;
+
+    fn deserialize<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+        where D: Deserializer<'de>
+    {
+        if deserializer.is_human_readable() {
+            self.human_readable.deserialize(deserializer)
+        } else {
+            self.binary.deserialize(deserializer)
+        }
+    }
+}
+
+#[cfg(test)]
+use std::marker::PhantomData;
+#[cfg(test)]
+use serde::Deserialize;
+#[cfg(test)]
+use crate::test::token::{Token, TokenDeserializer, TokenSerializer};
+
+#[cfg(test)]
+struct DeserializeSeedParse;
+
+#[cfg(test)]
+impl<'de> DeserializeSeed<'de> for DeserializeSeedParse {
+    type Value = i64;
+
+    fn deserialize<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+        where D: Deserializer<'de>
+    {
+        String::deserialize(deserializer)?.parse().map_err(serde::de::Error::custom)
+    }
+}
+
+#[test]
+fn test_serde_postcard() {
+    let serialized = postcard::to_allocvec(&HumanReadableOr::new("42", 42i64)).unwrap();
+    assert_eq!(postcard::to_allocvec(&42i64).unwrap(), serialized);
+    let result = HumanReadableOr::new(DeserializeSeedParse, PhantomData::<i64>).deserialize(
+        &mut postcard::Deserializer::from_flavor(postcard::de_flavors::Slice::new(&serialized))
+    ).unwrap();
+    assert_eq!(42, result);
+}
+
+#[test]
+fn test_serde_json() {
+    let serialized = serde_json::to_string(&HumanReadableOr::new("42", 42i64)).unwrap();
+    assert_eq!("\"42\"", serialized);
+    let result = HumanReadableOr::new(DeserializeSeedParse, PhantomData::<i64>).deserialize(
+        &mut serde_json::Deserializer::from_str(&serialized)
+    ).unwrap();
+    assert_eq!(42, result);
+}
+
+#[test]
+fn test_serde_tokens() {
+    for human_readable in [true, false] {
+        let serializer = TokenSerializer::new().with_human_readable(human_readable);
+        let tokens = HumanReadableOr::new("42", 42i64).serialize(serializer).unwrap();
+        if human_readable {
+            assert_eq!(tokens, [Token::Str("42".to_owned())]);
+        } else {
+            assert_eq!(tokens, [Token::I64(42)]);
+        }
+        let mut deserializer = TokenDeserializer::new(tokens).with_human_readable(human_readable);
+        let result = HumanReadableOr::new(DeserializeSeedParse, PhantomData::<i64>).deserialize(&mut deserializer).unwrap();
+        assert_eq!(42, result);
+    }
+}
@@ -0,0 +1,160 @@
+///
+/// A lookup table from identifiers (e.g. field or variant names) to their index, as used by
+/// [`crate::impl_deserialize_seed_for_dependent_struct!`] and
+/// [`crate::impl_deserialize_seed_for_dependent_enum!`] to match identifiers.
+///
+/// The identifiers are grouped by their length when the table is built, which usually happens
+/// at compile time. A lookup then only compares the key with the identifiers of the same length,
+/// which is usually only one or two. Here `N` is the number of identifiers, and `B` is the
+/// number of length buckets, which must be [`bucket_count()`] of the identifiers.
+///
+/// # Example
+/// ```
+/// # use feanor_serde::identifier::*;
+/// const NAMES: &[&str] = &["x", "y", "radius"];
+/// const TABLE: IdentifierTable<3, { bucket_count(NAMES) }> = IdentifierTable::new(NAMES);
+/// assert_eq!(Some(1), TABLE.find(b"y"));
+/// assert_eq!(Some(2), TABLE.find(b"radius"));
+/// assert_eq!(None, TABLE.find(b"z"));
+/// assert_eq!(None, TABLE.find(b"diameter"));
+/// ```
+///
+#[derive(Clone, Copy, Debug)]
+pub struct IdentifierTable<const N: usize, const B: usize> {
+    identifiers: &'static [&'static str],
+    entries: [(u64, u64, u32); N],
+    bucket_starts: [u32; B]
+}
+
+///
+/// Returns the number of length buckets of an [`IdentifierTable`] for the given identifiers,
+/// i.e. one more than the length of the longest identifier.
+///
+pub const fn bucket_count(identifiers: &[&str]) -> usize {
+    let mut result = 0;
+    let mut i = 0;
+    while i < identifiers.len() {
+        if identifiers[i].len() > result {
+            result = identifiers[i].len();
+        }
+        i += 1;
+    }
+    return result + 1;
+}
+
+///
+/// Packs the bytes of a key into two integers, such that two keys of the same length at most 16
+/// are equal if and only if their packed representations are equal. This uses (possibly overlapping)
+/// reads of the first and last bytes, so it does not depend on the length otherwise.
+///
+#[inline]
+const fn pack(key: &[u8]) -> (u64, u64) {
+    let n = key.len();
+    if n >= 8 {
+        (read_u64(key, 0), read_u64(key, n - 8))
+    } else if n >= 4 {
+        (read_u32(key, 0) as u64, read_u32(key, n - 4) as u64)
+    } else if n > 0 {
+        (key[0] as u64 | (key[n / 2] as u64) << 8 | (key[n - 1] as u64) << 16, 0)
+    } else {
+        (0, 0)
+    }
+}
+
+#[inline]
+const fn read_u64(key: &[u8], i: usize) -> u64 {
+    let (bytes, _) = key.split_at(i + 8).0.split_at(i).1.split_first_chunk::<8>().unwrap();
+    u64::from_le_bytes(*bytes)
+}
+
+#[inline]
+const fn read_u32(key: &[u8], i: usize) -> u32 {
+    let (bytes, _) = key.split_at(i + 4).0.split_at(i).1.split_first_chunk::<4>().unwrap();
+    u32::from_le_bytes(*bytes)
+}
+
+impl<const N: usize, const B: usize> IdentifierTable<N, B> {
+
+    ///
+    /// Creates the table for the given identifiers.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `identifiers` does not have length `N`, or if `B` is not [`bucket_count()`]
+    /// of `identifiers`. When building the table in a constant, this is a compile error.
+    ///
+    pub const fn new(identifiers: &'static [&'static str]) -> Self {
+        assert!(identifiers.len() == N);
+        assert!(bucket_count(identifiers) == B);
+        let mut bucket_starts = [0; B];
+        let mut i = 0;
+        while i < N {
+            bucket_starts[identifiers[i].len()] += 1;
+            i += 1;
+        }
+        let mut current = 0;
+        let mut len = 0;
+        while len < B {
+            let count = bucket_starts[len];
+            bucket_starts[len] = current;
+            current += count;
+            len += 1;
+        }
+        let mut next = bucket_starts;
+        let mut entries = [(0, 0, 0); N];
+        let mut i = 0;
+        while i < N {
+            let len = identifiers[i].len();
+            let (low, high) = pack(identifiers[i].as_bytes());
+            entries[next[len] as usize] = (low, high, i as u32);
+            next[len] += 1;
+            i += 1;
+        }
+        return Self { identifiers, entries, bucket_starts };
+    }
+
+    ///
+    /// Returns the index of the identifier equal to `key`, if any.
+    ///
+    #[inline]
+    pub fn find(&self, key: &[u8]) -> Option<usize> {
+        let len = key.len();
+        if len >= B {
+            return None;
+        }
+        let start = self.bucket_starts[len] as usize;
+        let end = if len + 1 < B { self.bucket_starts[len + 1] as usize } else { N };
+        let (low, high) = pack(key);
+        for &(entry_low, entry_high, index) in &self.entries[start..end] {
+            if entry_low == low && entry_high == high && (len <= 16 || self.identifiers[index as usize].as_bytes() == key) {
+                return Some(index as usize);
+            }
+        }
+        return None;
+    }
+}
+
+#[test]
+fn test_find() {
+    const NAMES: &[&str] = &["ab", "c", "de", "", "fgh", "ij", "abcdefgh", "abcdefghi", "abcdefghijklmnopq", "abcdefghijklmnopr"];
+    const TABLE: IdentifierTable<10, { bucket_count(NAMES) }> = IdentifierTable::new(NAMES);
+    for (i, name) in NAMES.iter().enumerate() {
+        assert_eq!(Some(i), TABLE.find(name.as_bytes()));
+    }
+    assert_eq!(None, TABLE.find(b"a"));
+    assert_eq!(None, TABLE.find(b"ji"));
+    assert_eq!(None, TABLE.find(b"fgi"));
+    assert_eq!(None, TABLE.find(b"fghi"));
+    assert_eq!(None, TABLE.find(&[0xff, 0xfe]));
+    assert_eq!(None, TABLE.find(b"abcdefgi"));
+    assert_eq!(None, TABLE.find(b"abcdefgha"));
+    assert_eq!(None, TABLE.find(b"abcdefghijklmnopp"));
+    assert_eq!(None, TABLE.find(b"abcdefghXjklmnopq"));
+}
+
+#[test]
+fn test_empty() {
+    const TABLE: IdentifierTable<0, { bucket_count(&[]) }> = IdentifierTable::new(&[]);
+    assert_eq!(None, TABLE.find(b""));
+    assert_eq!(None, TABLE.find(b"a"));
+}
@@ -0,0 +1,419 @@
+use std::cell::{Cell, RefCell};
+use std::fmt::Display;
+
+use serde::de::{DeserializeSeed, EnumAccess, MapAccess, SeqAccess, VariantAccess, Visitor};
+use serde::Deserializer;
+
+///
+/// Deserializes a value using the given [`DeserializeSeed`], and calls `callback` for every
+/// event passing between the [`Deserializer`] and the [`DeserializeSeed`], together with its
+/// nesting depth.
+///
+/// Events are
+///  - calls to the [`Deserializer`], e.g. `deserialize_seq` or `deserialize_struct("Foo", ["a", "b"])`,
+///  - calls to the [`Visitor`], e.g. `visit_seq` or `visit_i64(5)`,
+///  - calls to the [`SeqAccess`], [`MapAccess`], [`EnumAccess`] and [`VariantAccess`], e.g.
+///    `next_element`, `next_key`, `next_value` or `variant`, as well as `end` if a sequence
+///    or map has no further elements,
+///  - errors, as `error: <message>`, logged only once at the place where they occur.
+///
+/// Events that happen during another event (e.g. the `visit_seq` during `deserialize_seq`)
+/// have a higher depth.
+///
+/// This works by wrapping the deserializer, so it does not require any cooperation
+/// of the [`DeserializeSeed`], and is mainly useful for debugging seeds that fail on
+/// non-self-describing formats like postcard.
+///
+pub fn deserialize_inspecting<'de, S, D, F>(seed: S, deserializer: D, callback: F) -> Result<S::Value, D::Error>
+    where S: DeserializeSeed<'de>,
+        D: Deserializer<'de>,
+        F: FnMut(usize, &str)
+{
+    let log = Log { callback: RefCell::new(callback), depth: Cell::new(0), error_logged: Cell::new(false) };
+    return seed.deserialize(InspectedDeserializer { de: deserializer, log: &log });
+}
+
+///
+/// Deserializes a value using the given [`DeserializeSeed`], and returns the result together
+/// with a log of all events, indented by their nesting depth. See [`deserialize_inspecting()`]
+/// for details.
+///
+/// # Example
+/// ```
+/// # use feanor_serde::inspect::*;
+/// # use feanor_serde::default_seed::seed_of;
+/// let mut deserializer = serde_json::Deserializer::new(serde_json::de::StrRead::new("[1, 2]"));
+/// let (result, log) = deserialize_logging(seed_of::<Vec<i64>>(), &mut deserializer);
+/// assert_eq!(vec![1, 2], result.unwrap());
+/// assert_eq!(r#"deserialize_seq()
+///   visit_seq
+///     next_element
+///       deserialize_i64()
+///         visit_u64(1)
+///     next_element
+///       deserialize_i64()
+///         visit_u64(2)
+///     next_element
+///     end
+/// "#, log);
+/// ```
+///
+pub fn deserialize_logging<'de, S, D>(seed: S, deserializer: D) -> (Result<S::Value, D::Error>, String)
+    where S: DeserializeSeed<'de>,
+        D: Deserializer<'de>
+{
+    let mut log = String::new();
+    let result = deserialize_inspecting(seed, deserializer, |depth, event| {
+        log.extend(std::iter::repeat_n("  ", depth));
+        log.push_str(event);
+        log.push('\n');
+    });
+    return (result, log);
+}
+
+struct Log<F>
+    where F: FnMut(usize, &str)
+{
+    callback: RefCell<F>,
+    depth: Cell<usize>,
+    error_logged: Cell<bool>
+}
+
+impl<F> Log<F>
+    where F: FnMut(usize, &str)
+{
+    fn event(&self, event: &str) {
+        self.error_logged.set(false);
+        (self.callback.borrow_mut())(self.depth.get(), event);
+    }
+
+    ///
+    /// Logs the given event, and calls `f` with increased depth.
+    ///
+    fn nested<T, E, G>(&self, event: &str, f: G) -> Result<T, E>
+        where E: Display,
+            G: FnOnce() -> Result<T, E>
+    {
+        self.event(event);
+        self.depth.set(self.depth.get() + 1);
+        let result = f();
+        // only log an error where it occurs, not again while it is propagated
+        if let Err(e) = &result {
+            if !self.error_logged.get() {
+                self.event(&format!("error: {}", e));
+                self.error_logged.set(true);
+            }
+        }
+        self.depth.set(self.depth.get() - 1);
+        return result;
+    }
+}
+
+struct InspectedDeserializer<'b, D, F>
+    where F: FnMut(usize, &str)
+{
+    de: D,
+    log: &'b Log<F>
+}
+
+struct InspectedSeed<'b, S, F>
+    where F: FnMut(usize, &str)
+{
+    seed: S,
+    log: &'b Log<F>
+}
+
+impl<'b, 'de, S, F> DeserializeSeed<'de> for InspectedSeed<'b, S, F>
+    where S: DeserializeSeed<'de>,
+        F: FnMut(usize, &str)
+{
+    type Value = S::Value;
+
+    fn deserialize<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+        where D: Deserializer<'de>
+    {
+        self.seed.deserialize(InspectedDeserializer { de: deserializer, log: self.log })
+    }
+}
+
+macro_rules! forward_deserialize {
+    ($($method:ident($($arg:ident: $type:ty),*)),*) => {
+        $(
+            fn $method<V>(self, $($arg: $type,)* visitor: V) -> Result<V::Value, Self::Error>
+                where V: Visitor<'de>
+            {
+                let log = self.log;
+                let args: &[String] = &[$(format!("{:?}", $arg)),*];
+                log.nested(&format!("{}({})", stringify!($method), args.join(", ")), || self.de.$method($($arg,)* Inspected { visitor: visitor, log: log }))
+            }
+        )*
+    };
+}
+
+impl<'b, 'de, D, F> Deserializer<'de> for InspectedDeserializer<'b, D, F>
+    where D: Deserializer<'de>,
+        F: FnMut(usize, &str)
+{
+    type Error = D::Error;
+
+    forward_deserialize!{
+        deserialize_any(), deserialize_bool(), deserialize_i8(), deserialize_i16(), deserialize_i32(), deserialize_i64(), deserialize_i128(),
+        deserialize_u8(), deserialize_u16(), deserialize_u32(), deserialize_u64(), deserialize_u128(), deserialize_f32(), deserialize_f64(),
+        deserialize_char(), deserialize_str(), deserialize_string(), deserialize_bytes(), deserialize_byte_buf(), deserialize_option(),
+        deserialize_unit(), deserialize_seq(), deserialize_map(), deserialize_identifier(), deserialize_ignored_any(),
+        deserialize_unit_struct(name: &'static str), deserialize_newtype_struct(name: &'static str), deserialize_tuple(len: usize),
+        deserialize_tuple_struct(name: &'static str, len: usize), deserialize_struct(name: &'static str, fields: &'static [&'static str]),
+        deserialize_enum(name: &'static str, variants: &'static [&'static str])
+    }
+
+    fn is_human_readable(&self) -> bool {
+        self.de.is_human_readable()
+    }
+}
+
+///
+/// Wraps a [`Visitor`] resp. one of the accessors, in order to log events.
+///
+struct Inspected<'b, X, F>
+    where F: FnMut(usize, &str)
+{
+    visitor: X,
+    log: &'b Log<F>
+}
+
+macro_rules! forward_visit {
+    ($($method:ident($type:ty)),*) => {
+        $(
+            fn $method<E>(self, value: $type) -> Result<Self::Value, E>
+                where E: serde::de::Error
+            {
+                self.log.event(&format!("{}({:?})", stringify!($method), value));
+                self.visitor.$method(value)
+            }
+        )*
+    };
+}
+
+impl<'b, 'de, X, F> Visitor<'de> for Inspected<'b, X, F>
+    where X: Visitor<'de>,
+        F: FnMut(usize, &str)
+{
+    type Value = X::Value;
+
+    fn expecting(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        self.visitor.expecting(f)
+    }
+
+    forward_visit!{
+        visit_bool(bool), visit_i8(i8), visit_i16(i16), visit_i32(i32), visit_i64(i64), visit_i128(i128),
+        visit_u8(u8), visit_u16(u16), visit_u32(u32), visit_u64(u64), visit_u128(u128), visit_f32(f32), visit_f64(f64),
+        visit_char(char), visit_str(&str), visit_borrowed_str(&'de str), visit_string(String),
+        visit_bytes(&[u8]), visit_borrowed_bytes(&'de [u8]), visit_byte_buf(Vec<u8>)
+    }
+
+    fn visit_none<E>(self) -> Result<Self::Value, E>
+        where E: serde::de::Error
+    {
+        self.log.event("visit_none");
+        self.visitor.visit_none()
+    }
+
+    fn visit_unit<E>(self) -> Result<Self::Value, E>
+        where E: serde::de::Error
+    {
+        self.log.event("visit_unit");
+        self.visitor.visit_unit()
+    }
+
+    fn visit_some<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+        where D: Deserializer<'de>
+    {
+        let log = self.log;
+        log.nested("visit_some", || self.visitor.visit_some(InspectedDeserializer { de: deserializer, log: log }))
+    }
+
+    fn visit_newtype_struct<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+        where D: Deserializer<'de>
+    {
+        let log = self.log;
+        log.nested("visit_newtype_struct", || self.visitor.visit_newtype_struct(InspectedDeserializer { de: deserializer, log: log }))
+    }
+
+    fn visit_seq<A>(self, seq: A) -> Result<Self::Value, A::Error>
+        where A: SeqAccess<'de>
+    {
+        let log = self.log;
+        log.nested("visit_seq", || self.visitor.visit_seq(Inspected { visitor: seq, log: log }))
+    }
+
+    fn visit_map<A>(self, map: A) -> Result<Self::Value, A::Error>
+        where A: MapAccess<'de>
+    {
+        let log = self.log;
+        log.nested("visit_map", || self.visitor.visit_map(Inspected { visitor: map, log: log }))
+    }
+
+    fn visit_enum<A>(self, data: A) -> Result<Self::Value, A::Error>
+        where A: EnumAccess<'de>
+    {
+        let log = self.log;
+        log.nested("visit_enum", || self.visitor.visit_enum(Inspected { visitor: data, log: log }))
+    }
+}
+
+impl<'b, 'de, X, F> SeqAccess<'de> for Inspected<'b, X, F>
+    where X: SeqAccess<'de>,
+        F: FnMut(usize, &str)
+{
+    type Error = X::Error;
+
+    fn next_element_seed<T>(&mut self, seed: T) -> Result<Option<T::Value>, Self::Error>
+        where T: DeserializeSeed<'de>
+    {
+        let log = self.log;
+        let result = log.nested("next_element", || self.visitor.next_element_seed(InspectedSeed { seed: seed, log: log }))?;
+        if result.is_none() {
+            log.event("end");
+        }
+        return Ok(result);
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        self.visitor.size_hint()
+    }
+}
+
+impl<'b, 'de, X, F> MapAccess<'de> for Inspected<'b, X, F>
+    where X: MapAccess<'de>,
+        F: FnMut(usize, &str)
+{
+    type Error = X::Error;
+
+    fn next_key_seed<K>(&mut self, seed: K) -> Result<Option<K::Value>, Self::Error>
+        where K: DeserializeSeed<'de>
+    {
+        let log = self.log;
+        let result = log.nested("next_key", || self.visitor.next_key_seed(InspectedSeed { seed: seed, log: log }))?;
+        if result.is_none() {
+            log.event("end");
+        }
+        return Ok(result);
+    }
+
+    fn next_value_seed<V>(&mut self, seed: V) -> Result<V::Value, Self::Error>
+        where V: DeserializeSeed<'de>
+    {
+        let log = self.log;
+        log.nested("next_value", || self.visitor.next_value_seed(InspectedSeed { seed: seed, log: log }))
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        self.visitor.size_hint()
+    }
+}
+
+impl<'b, 'de, X, F> EnumAccess<'de> for Inspected<'b, X, F>
+    where X: EnumAccess<'de>,
+        F: FnMut(usize, &str)
+{
+    type Error = X::Error;
+    type Variant = Inspected<'b, X::Variant, F>;
+
+    fn variant_seed<V>(self, seed: V) -> Result<(V::Value, Self::Variant), Self::Error>
+        where V: DeserializeSeed<'de>
+    {
+        let log = self.log;
+        let (value, access) = log.nested("variant", || self.visitor.variant_seed(InspectedSeed { seed: seed, log: log }))?;
+        return Ok((value, Inspected { visitor: access, log: log }));
+    }
+}
+
+impl<'b, 'de, X, F> VariantAccess<'de> for Inspected<'b, X, F>
+    where X: VariantAccess<'de>,
+        F: FnMut(usize, &str)
+{
+    type Error = X::Error;
+
+    fn unit_variant(self) -> Result<(), Self::Error> {
+        self.log.nested("unit_variant", || self.visitor.unit_variant())
+    }
+
+    fn newtype_variant_seed<T>(self, seed: T) -> Result<T::Value, Self::Error>
+        where T: DeserializeSeed<'de>
+    {
+        let log = self.log;
+        log.nested("newtype_variant", || self.visitor.newtype_variant_seed(InspectedSeed { seed: seed, log: log }))
+    }
+
+    fn tuple_variant<V>(self, len: usize, visitor: V) -> Result<V::Value, Self::Error>
+        where V: Visitor<'de>
+    {
+        let log = self.log;
+        log.nested(&format!("tuple_variant({})", len), || self.visitor.tuple_variant(len, Inspected { visitor: visitor, log: log }))
+    }
+
+    fn struct_variant<V>(self, fields: &'static [&'static str], visitor: V) -> Result<V::Value, Self::Error>
+        where V: Visitor<'de>
+    {
+        let log = self.log;
+        log.nested(&format!("struct_variant({:?})", fields), || self.visitor.struct_variant(fields, Inspected { visitor: visitor, log: log }))
+    }
+}
+
+#[cfg(test)]
+use std::marker::PhantomData;
+
+#[cfg(test)]
+#[derive(serde::Deserialize, serde::Serialize, Debug, PartialEq)]
+enum Record {
+    A(i64),
+    B { values: Vec<u8> }
+}
+
+#[test]
+fn test_serde_json() {
+    let (result, log) = deserialize_logging(PhantomData::<Option<Record>>, &mut serde_json::Deserializer::from_str(r#"{"A": 5}"#));
+    assert_eq!(Some(Record::A(5)), result.unwrap());
+    assert_eq!(r#"deserialize_option()
+  visit_some
+    deserialize_enum("Record", ["A", "B"])
+      visit_enum
+        variant
+          deserialize_identifier()
+            visit_borrowed_str("A")
+        newtype_variant
+          deserialize_i64()
+            visit_u64(5)
+"#, log);
+
+    let mut events = Vec::new();
+    let result = deserialize_inspecting(PhantomData::<Record>, &mut serde_json::Deserializer::from_str(r#"{"B": {"values": [1, -1]}}"#), |depth, event| events.push((depth, event.to_owned())));
+    assert!(result.is_err());
+    assert_eq!((9, "visit_i64(-1)".to_owned()), events[events.len() - 2]);
+    assert_eq!(9, events.last().unwrap().0);
+    assert!(events.last().unwrap().1.starts_with("error: invalid value: integer `-1`"));
+}
+
+#[test]
+fn test_serde_postcard() {
+    let serialized = postcard::to_allocvec(&Record::B { values: vec![1, 2] }).unwrap();
+    let (result, log) = deserialize_logging(PhantomData::<Record>, &mut postcard::Deserializer::from_flavor(postcard::de_flavors::Slice::new(&serialized[..3])));
+    assert!(result.is_err());
+    assert_eq!(r#"deserialize_enum("Record", ["A", "B"])
+  visit_enum
+    variant
+      deserialize_identifier()
+        visit_u32(1)
+    struct_variant(["values"])
+      visit_seq
+        next_element
+          deserialize_seq()
+            visit_seq
+              next_element
+                deserialize_u8()
+                  visit_u8(1)
+              next_element
+                deserialize_u8()
+                  error: Hit the end of buffer, expected more data
+"#, log);
+}
@@ -0,0 +1,371 @@
+use serde::de::{DeserializeSeed, Error, SeqAccess, Unexpected, Visitor};
+use serde::ser::{Serialize, SerializeTuple, Serializer};
+use serde::Deserializer;
+
+///
+/// Encoding of 128-bit integers used by [`SerializableU128`] and [`SerializableI128`],
+/// resp. the deserialization strategy used by [`DeserializeSeedU128`] and [`DeserializeSeedI128`].
+///
+/// Since a serializer is consumed when serializing a value, it is not possible to
+/// fall back to another encoding once `serialize_u128()` failed. Hence, the encoding
+/// has to be chosen in advance. However, the visitors used during deserialization accept
+/// all encodings, so when using a self-describing format, [`Int128Encoding::Any`] can be
+/// used to deserialize values independent of the encoding used to serialize them.
+///
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub enum Int128Encoding {
+    /// Use `serialize_u128()` resp. `serialize_i128()` of the serde data model
+    #[default]
+    Native,
+    /// Use a tuple `(high, low)` of two `u64`s, containing the upper resp. lower 64 bits
+    /// of the (two's complement) representation of the value
+    Pair,
+    /// Use the bytes type of the serde data model, containing the 16 little-endian bytes
+    /// of the (two's complement) representation of the value
+    Bytes,
+    /// Only valid for deserialization, and uses `deserialize_any()` to accept any of the
+    /// other encodings; This requires a self-describing format. Note that some formats
+    /// (e.g. `serde_json`) do not support 128-bit integers in `deserialize_any()`, thus
+    /// in this case, [`Int128Encoding::Native`] only works for values that fit into 64 bits
+    Any
+}
+
+fn serialize_bits<S>(bits: u128, signed: bool, encoding: Int128Encoding, serializer: S) -> Result<S::Ok, S::Error>
+    where S: Serializer
+{
+    match encoding {
+        Int128Encoding::Native if signed => serializer.serialize_i128(bits as i128),
+        Int128Encoding::Native => serializer.serialize_u128(bits),
+        Int128Encoding::Pair => {
+            let mut tuple = serializer.serialize_tuple(2)?;
+            tuple.serialize_element(&((bits >> 64) as u64))?;
+            tuple.serialize_element(&(bits as u64))?;
+            tuple.end()
+        },
+        Int128Encoding::Bytes => serializer.serialize_bytes(&bits.to_le_bytes()),
+        Int128Encoding::Any => Err(<S::Error as serde::ser::Error>::custom("Int128Encoding::Any can only be used for deserialization"))
+    }
+}
+
+struct Int128Visitor {
+    signed: bool
+}
+
+impl Int128Visitor {
+
+    fn check_signed<E>(&self, value: i128) -> Result<u128, E>
+        where E: Error
+    {
+        if !self.signed && value < 0 {
+            if let Ok(value) = i64::try_from(value) {
+                return Err(E::invalid_value(Unexpected::Signed(value), self));
+            }
+            return Err(E::invalid_value(Unexpected::Other(&format!("integer `{}`", value)), self));
+        }
+        return Ok(value as u128);
+    }
+
+    fn check_unsigned<E>(&self, value: u128) -> Result<u128, E>
+        where E: Error
+    {
+        if self.signed && value > i128::MAX as u128 {
+            return Err(E::custom(format!("integer {} is out of range for i128", value)));
+        }
+        return Ok(value);
+    }
+}
+
+impl<'de> Visitor<'de> for Int128Visitor {
+    type Value = u128;
+
+    fn expecting(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        if self.signed {
+            write!(f, "an i128, either as integer, as pair of u64 or as 16 bytes")
+        } else {
+            write!(f, "a u128, either as integer, as pair of u64 or as 16 bytes")
+        }
+    }
+
+    fn visit_i64<E>(self, value: i64) -> Result<Self::Value, E>
+        where E: Error
+    {
+        self.check_signed(value as i128)
+    }
+
+    fn visit_i128<E>(self, value: i128) -> Result<Self::Value, E>
+        where E: Error
+    {
+        self.check_signed(value)
+    }
+
+    fn visit_u64<E>(self, value: u64) -> Result<Self::Value, E>
+        where E: Error
+    {
+        self.check_unsigned(value as u128)
+    }
+
+    fn visit_u128<E>(self, value: u128) -> Result<Self::Value, E>
+        where E: Error
+    {
+        self.check_unsigned(value)
+    }
+
+    fn visit_bytes<E>(self, value: &[u8]) -> Result<Self::Value, E>
+        where E: Error
+    {
+        let bytes: [u8; 16] = value.try_into().map_err(|_| E::invalid_length(value.len(), &self))?;
+        return Ok(u128::from_le_bytes(bytes));
+    }
+
+    fn visit_seq<A>(self, mut seq: A) -> Result<Self::Value, A::Error>
+        where A: SeqAccess<'de>
+    {
+        // self-describing formats may represent bytes as sequence, so accept both a pair of `u64` and 16 `u8`
+        let mut elements = [0u64; 16];
+        let mut len = 0;
+        while let Some(element) = seq.next_element()? {
+            if len >= elements.len() {
+                return Err(A::Error::invalid_length(len + 1, &self));
+            }
+            elements[len] = element;
+            len += 1;
+        }
+        if len == 2 {
+            return Ok(((elements[0] as u128) << 64) | elements[1] as u128);
+        } else if len == 16 && elements.iter().all(|x| *x <= u8::MAX as u64) {
+            return Ok(u128::from_le_bytes(std::array::from_fn(|i| elements[i] as u8)));
+        } else {
+            return Err(A::Error::invalid_length(len, &self));
+        }
+    }
+}
+
+fn deserialize_bits<'de, D>(signed: bool, encoding: Int128Encoding, deserializer: D) -> Result<u128, D::Error>
+    where D: Deserializer<'de>
+{
+    let visitor = Int128Visitor { signed };
+    match encoding {
+        Int128Encoding::Native if signed => deserializer.deserialize_i128(visitor),
+        Int128Encoding::Native => deserializer.deserialize_u128(visitor),
+        Int128Encoding::Pair => deserializer.deserialize_tuple(2, visitor),
+        Int128Encoding::Bytes => deserializer.deserialize_bytes(visitor),
+        Int128Encoding::Any => deserializer.deserialize_any(visitor)
+    }
+}
+
+///
+/// Wraps a `u128`, and implements [`Serialize`] using the given [`Int128Encoding`].
+/// This is useful for serializers that don't support `serialize_u128()`.
+///
+/// # Example
+/// ```
+/// # use feanor_serde::int128::*;
+/// let value = SerializableU128::new_with_encoding(u64::MAX as u128 + 2, Int128Encoding::Pair);
+/// assert_eq!("[1,1]", serde_json::to_string(&value).unwrap());
+/// ```
+///
+#[derive(Clone, Copy)]
+pub struct SerializableU128 {
+    data: u128,
+    encoding: Int128Encoding
+}
+
+impl SerializableU128 {
+
+    pub fn new(data: u128) -> Self {
+        Self::new_with_encoding(data, Int128Encoding::default())
+    }
+
+    pub fn new_with_encoding(data: u128, encoding: Int128Encoding) -> Self {
+        Self { data, encoding }
+    }
+}
+
+impl Serialize for SerializableU128 {
+
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+        where S: Serializer
+    {
+        serialize_bits(self.data, false, self.encoding, serializer)
+    }
+}
+
+///
+/// Wraps an `i128`, and implements [`Serialize`] using the given [`Int128Encoding`].
+/// This is useful for serializers that don't support `serialize_i128()`.
+///
+#[derive(Clone, Copy)]
+pub struct SerializableI128 {
+    data: i128,
+    encoding: Int128Encoding
+}
+
+impl SerializableI128 {
+
+    pub fn new(data: i128) -> Self {
+        Self::new_with_encoding(data, Int128Encoding::default())
+    }
+
+    pub fn new_with_encoding(data: i128, encoding: Int128Encoding) -> Self {
+        Self { data, encoding }
+    }
+}
+
+impl Serialize for SerializableI128 {
+
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+        where S: Serializer
+    {
+        serialize_bits(self.data as u128, true, self.encoding, serializer)
+    }
+}
+
+///
+/// A [`DeserializeSeed`] that deserializes a `u128` that was serialized using
+/// [`SerializableU128`].
+///
+/// # Example
+/// ```
+/// # use feanor_serde::int128::*;
+/// # use serde::de::DeserializeSeed;
+/// for json in ["[1,1]", "[1,0,0,0,0,0,0,0,1,0,0,0,0,0,0,0]"] {
+///     let mut deserializer = serde_json::Deserializer::new(serde_json::de::StrRead::new(json));
+///     let deserialize_seed = DeserializeSeedU128::new_with_encoding(Int128Encoding::Any);
+///     assert_eq!(u64::MAX as u128 + 2, deserialize_seed.deserialize(&mut deserializer).unwrap());
+/// }
+/// ```
+///
+#[derive(Clone, Copy, Default)]
+pub struct DeserializeSeedU128 {
+    encoding: Int128Encoding
+}
+
+impl DeserializeSeedU128 {
+
+    pub fn new() -> Self {
+        Self::new_with_encoding(Int128Encoding::default())
+    }
+
+    pub fn new_with_encoding(encoding: Int128Encoding) -> Self {
+        Self { encoding }
+    }
+}
+
+impl<'de> DeserializeSeed<'de> for DeserializeSeedU128 {
+    type Value = u128;
+
+    fn deserialize<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+        where D: Deserializer<'de>
+    {
+        deserialize_bits(false, self.encoding, deserializer)
+    }
+}
+
+///
+/// A [`DeserializeSeed`] that deserializes an `i128` that was serialized using
+/// [`SerializableI128`].
+///
+#[derive(Clone, Copy, Default)]
+pub struct DeserializeSeedI128 {
+    encoding: Int128Encoding
+}
+
+impl DeserializeSeedI128 {
+
+    pub fn new() -> Self {
+        Self::new_with_encoding(Int128Encoding::default())
+    }
+
+    pub fn new_with_encoding(encoding: Int128Encoding) -> Self {
+        Self { encoding }
+    }
+}
+
+impl<'de> DeserializeSeed<'de> for DeserializeSeedI128 {
+    type Value = i128;
+
+    fn deserialize<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+        where D: Deserializer<'de>
+    {
+        deserialize_bits(true, self.encoding, deserializer).map(|bits| bits as i128)
+    }
+}
+
+#[cfg(test)]
+use crate::test::token::{Token, TokenDeserializer, TokenSerializer};
+
+#[cfg(test)]
+const ENCODINGS: [Int128Encoding; 3] = [Int128Encoding::Native, Int128Encoding::Pair, Int128Encoding::Bytes];
+
+#[test]
+fn test_serde_postcard() {
+    for encoding in ENCODINGS {
+        for value in [0, 1, u64::MAX as u128 + 1, u128::MAX] {
+            let serialized = postcard::to_allocvec(&SerializableU128::new_with_encoding(value, encoding)).unwrap();
+            let result = DeserializeSeedU128::new_with_encoding(encoding).deserialize(
+                &mut postcard::Deserializer::from_flavor(postcard::de_flavors::Slice::new(&serialized))
+            ).unwrap();
+            assert_eq!(value, result);
+        }
+        for value in [0, -1, i64::MIN as i128 - 1, i128::MIN, i128::MAX] {
+            let serialized = postcard::to_allocvec(&SerializableI128::new_with_encoding(value, encoding)).unwrap();
+            let result = DeserializeSeedI128::new_with_encoding(encoding).deserialize(
+                &mut postcard::Deserializer::from_flavor(postcard::de_flavors::Slice::new(&serialized))
+            ).unwrap();
+            assert_eq!(value, result);
+        }
+    }
+}
+
+#[test]
+fn test_serde_json() {
+    for encoding in ENCODINGS {
+        for value in [0, 1, u64::MAX as u128 + 1, u128::MAX] {
+            let serialized = serde_json::to_string(&SerializableU128::new_with_encoding(value, encoding)).unwrap();
+            // serde_json does not support 128-bit integers in `deserialize_any()`
+            let any_supported = encoding != Int128Encoding::Native || value <= u64::MAX as u128;
+            for deserialize_encoding in [encoding, Int128Encoding::Any].into_iter().filter(|e| any_supported || *e != Int128Encoding::Any) {
+                let result = DeserializeSeedU128::new_with_encoding(deserialize_encoding).deserialize(
+                    &mut serde_json::Deserializer::from_str(&serialized)
+                ).unwrap();
+                assert_eq!(value, result);
+            }
+        }
+        for value in [0, -1, i64::MIN as i128 - 1, i128::MIN, i128::MAX] {
+            let serialized = serde_json::to_string(&SerializableI128::new_with_encoding(value, encoding)).unwrap();
+            let any_supported = encoding != Int128Encoding::Native || i64::try_from(value).is_ok();
+            for deserialize_encoding in [encoding, Int128Encoding::Any].into_iter().filter(|e| any_supported || *e != Int128Encoding::Any) {
+                let result = DeserializeSeedI128::new_with_encoding(deserialize_encoding).deserialize(
+                    &mut serde_json::Deserializer::from_str(&serialized)
+                ).unwrap();
+                assert_eq!(value, result);
+            }
+        }
+    }
+    assert!(DeserializeSeedU128::new_with_encoding(Int128Encoding::Any).deserialize(&mut serde_json::Deserializer::from_str("-1")).is_err());
+    assert!(DeserializeSeedI128::new_with_encoding(Int128Encoding::Any).deserialize(&mut serde_json::Deserializer::from_str(&u128::MAX.to_string())).is_err());
+    assert!(DeserializeSeedU128::new_with_encoding(Int128Encoding::Any).deserialize(&mut serde_json::Deserializer::from_str("[1,2,3]")).is_err());
+    assert!(serde_json::to_string(&SerializableU128::new_with_encoding(0, Int128Encoding::Any)).is_err());
+}
+
+#[test]
+fn test_serde_tokens() {
+    let serializer = TokenSerializer::new();
+    let tokens = SerializableI128::new_with_encoding(-1, Int128Encoding::Pair).serialize(serializer).unwrap();
+    assert_eq!(tokens, [
+        Token::Tuple { len: 2 },
+        Token::U64(u64::MAX),
+        Token::U64(u64::MAX),
+        Token::TupleEnd
+    ]);
+    let mut deserializer = TokenDeserializer::new(tokens);
+    assert_eq!(-1, DeserializeSeedI128::new_with_encoding(Int128Encoding::Pair).deserialize(&mut deserializer).unwrap());
+}
+
+#[test]
+fn test_negative_out_of_range() {
+    let error = DeserializeSeedU128::new_with_encoding(Int128Encoding::Native).deserialize(&mut TokenDeserializer::new([Token::I128(i128::MIN)])).unwrap_err();
+    assert!(error.to_string().starts_with("invalid value: integer `-170141183460469231731687303715884105728`, expected"), "{}", error);
+    let error = DeserializeSeedU128::new_with_encoding(Int128Encoding::Native).deserialize(&mut TokenDeserializer::new([Token::I128(-1)])).unwrap_err();
+    assert!(error.to_string().starts_with("invalid value: integer `-1`, expected"), "{}", error);
+}
@@ -0,0 +1,105 @@
+use std::marker::PhantomData;
+
+use serde::de::DeserializeSeed;
+use serde::Deserialize;
+
+use crate::default_seed::DefaultSeed;
+
+///
+/// Conversion into a [`DeserializeSeed`], accepted by the constructors of the wrapping
+/// [`DeserializeSeed`]s of this crate (like [`crate::newtype_struct::DeserializeSeedNewtypeStruct::new()`]).
+///
+/// It is implemented for
+///  - all [`DeserializeSeed`]s, which are returned unchanged,
+///  - closures `FnOnce() -> S` returning a [`DeserializeSeed`], which are called once,
+///  - pairs `(context, PhantomData<T>)` for `T: Deserialize`, which give a [`DefaultSeed<T>`]
+///    and ignore the context; this allows passing a context uniformly, even to seeds that
+///    do not need it.
+///
+/// The parameter `Marker` only exists to keep these implementations apart, and is
+/// always inferred, so it never needs to be specified explicitly.
+///
+/// # Example
+/// ```
+/// # use feanor_serde::newtype_struct::*;
+/// # use feanor_serde::default_seed::seed_of;
+/// # use serde::de::DeserializeSeed;
+/// # use std::marker::PhantomData;
+/// let modulus = 7;
+/// let seeds = [
+///     DeserializeSeedNewtypeStruct::new("Foo", seed_of::<i64>()),
+///     DeserializeSeedNewtypeStruct::new("Foo", || seed_of::<i64>()),
+///     DeserializeSeedNewtypeStruct::new("Foo", (&modulus, PhantomData::<i64>))
+/// ];
+/// for seed in seeds {
+///     assert_eq!(1, seed.deserialize(&mut serde_json::Deserializer::from_str("1")).unwrap());
+/// }
+/// ```
+///
+pub trait IntoSeed<'de, Marker> {
+
+    type Seed: DeserializeSeed<'de>;
+
+    fn into_seed(self) -> Self::Seed;
+}
+
+///
+/// Marker for the implementation of [`IntoSeed`] for all [`DeserializeSeed`]s.
+///
+pub enum IsSeed {}
+
+///
+/// Marker for the implementation of [`IntoSeed`] for closures returning a [`DeserializeSeed`].
+///
+pub enum IsFactory {}
+
+///
+/// Marker for the implementation of [`IntoSeed`] for pairs `(context, PhantomData<T>)`.
+///
+pub enum IsContextPair {}
+
+impl<'de, S> IntoSeed<'de, IsSeed> for S
+    where S: DeserializeSeed<'de>
+{
+    type Seed = S;
+
+    fn into_seed(self) -> Self::Seed {
+        self
+    }
+}
+
+impl<'de, F, S> IntoSeed<'de, IsFactory> for F
+    where F: FnOnce() -> S,
+        S: DeserializeSeed<'de>
+{
+    type Seed = S;
+
+    fn into_seed(self) -> Self::Seed {
+        self()
+    }
+}
+
+impl<'de, C, T> IntoSeed<'de, IsContextPair> for (C, PhantomData<T>)
+    where T: Deserialize<'de>
+{
+    type Seed = DefaultSeed<T>;
+
+    fn into_seed(self) -> Self::Seed {
+        DefaultSeed::new()
+    }
+}
+
+#[cfg(test)]
+fn into_seed<'de, I, M>(seed: I) -> I::Seed
+    where I: IntoSeed<'de, M>
+{
+    seed.into_seed()
+}
+
+#[test]
+fn test_into_seed() {
+    let context = "unused".to_owned();
+    assert_eq!(1, into_seed(PhantomData::<i64>).deserialize(&mut serde_json::Deserializer::from_str("1")).unwrap());
+    assert_eq!(vec![1], into_seed(|| PhantomData::<Vec<i64>>).deserialize(&mut serde_json::Deserializer::from_str("[1]")).unwrap());
+    assert_eq!("a", into_seed((&context, PhantomData::<String>)).deserialize(&mut serde_json::Deserializer::from_str("\"a\"")).unwrap());
+}
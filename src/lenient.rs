@@ -0,0 +1,427 @@
+use std::marker::PhantomData;
+
+use serde::de::{DeserializeSeed, Error, Unexpected, Visitor};
+use serde::Deserializer;
+
+use crate::into_seed::IntoSeed;
+
+///
+/// A type that can be deserialized leniently by [`DeserializeSeedLenient`], i.e. from
+/// values of a different type in the serde data model.
+///
+/// This is implemented for all primitive integer types, `f32`, `f64`, `bool` and [`String`].
+///
+pub trait LenientValue: Sized + Default {
+
+    ///
+    /// A description of the expected values, used in error messages.
+    ///
+    const EXPECTING: &'static str;
+
+    fn from_i128(value: i128) -> Option<Self>;
+
+    fn from_f64(value: f64) -> Option<Self>;
+
+    ///
+    /// Parses the given string, from which surrounding whitespace has already been removed.
+    ///
+    fn from_trimmed_str(value: &str) -> Option<Self>;
+
+    fn from_bool(value: bool) -> Option<Self> {
+        Self::from_i128(value as i128)
+    }
+}
+
+macro_rules! impl_lenient_value_for_int {
+    ($($int:ty),*) => {
+        $(
+            impl LenientValue for $int {
+
+                const EXPECTING: &'static str = concat!("a value convertible to ", stringify!($int));
+
+                fn from_i128(value: i128) -> Option<Self> {
+                    <$int>::try_from(value).ok()
+                }
+
+                fn from_f64(value: f64) -> Option<Self> {
+                    if value.fract() == 0. && value >= <$int>::MIN as f64 && value <= <$int>::MAX as f64 {
+                        Some(value as $int)
+                    } else {
+                        None
+                    }
+                }
+
+                fn from_trimmed_str(value: &str) -> Option<Self> {
+                    value.parse().ok()
+                }
+            }
+        )*
+    };
+}
+
+impl_lenient_value_for_int!{ i8, i16, i32, i64, i128, isize, u8, u16, u32, u64, u128, usize }
+
+macro_rules! impl_lenient_value_for_float {
+    ($($float:ty),*) => {
+        $(
+            impl LenientValue for $float {
+
+                const EXPECTING: &'static str = concat!("a value convertible to ", stringify!($float));
+
+                fn from_i128(value: i128) -> Option<Self> {
+                    Some(value as $float)
+                }
+
+                fn from_f64(value: f64) -> Option<Self> {
+                    Some(value as $float)
+                }
+
+                fn from_trimmed_str(value: &str) -> Option<Self> {
+                    value.parse().ok()
+                }
+            }
+        )*
+    };
+}
+
+impl_lenient_value_for_float!{ f32, f64 }
+
+impl LenientValue for bool {
+
+    const EXPECTING: &'static str = "a boolean, 0 or 1";
+
+    fn from_i128(value: i128) -> Option<Self> {
+        match value {
+            0 => Some(false),
+            1 => Some(true),
+            _ => None
+        }
+    }
+
+    fn from_f64(value: f64) -> Option<Self> {
+        if value == 0. {
+            Some(false)
+        } else if value == 1. {
+            Some(true)
+        } else {
+            None
+        }
+    }
+
+    fn from_trimmed_str(value: &str) -> Option<Self> {
+        match value {
+            "true" | "1" => Some(true),
+            "false" | "0" => Some(false),
+            _ => None
+        }
+    }
+
+    fn from_bool(value: bool) -> Option<Self> {
+        Some(value)
+    }
+}
+
+impl LenientValue for String {
+
+    const EXPECTING: &'static str = "a string, number or boolean";
+
+    fn from_i128(value: i128) -> Option<Self> {
+        Some(value.to_string())
+    }
+
+    fn from_f64(value: f64) -> Option<Self> {
+        Some(value.to_string())
+    }
+
+    fn from_trimmed_str(value: &str) -> Option<Self> {
+        Some(value.to_owned())
+    }
+
+    fn from_bool(value: bool) -> Option<Self> {
+        Some(value.to_string())
+    }
+}
+
+///
+/// A [`DeserializeSeed`] that deserializes a [`LenientValue`] while accepting common
+/// sloppy encodings, namely
+///  - numbers and booleans given as strings, e.g. `"42"` or `"true"`,
+///  - strings with surrounding whitespace, e.g. `" 42 "`,
+///  - booleans given as `0` or `1` (and vice versa),
+///  - `null`, which is deserialized as the default value of the type.
+///
+/// This requires [`serde::Deserializer::deserialize_any()`], and thus only works with
+/// self-describing formats like JSON.
+///
+/// # Example
+/// ```
+/// # use feanor_serde::lenient::*;
+/// # use feanor_serde::seq::*;
+/// # use std::iter::repeat;
+/// # use serde::de::DeserializeSeed;
+/// let mut deserializer = serde_json::Deserializer::new(serde_json::de::StrRead::new("[1, \" 2\", null, 4.0]"));
+/// let deserialize_seed = DeserializeSeedSeq::new(
+///     repeat(DeserializeSeedLenient::<i64>::new()),
+///     Vec::new(),
+///     |mut current, next| { current.push(next); current }
+/// );
+/// assert_eq!(vec![1, 2, 0, 4], deserialize_seed.deserialize(&mut deserializer).unwrap());
+/// ```
+///
+pub struct DeserializeSeedLenient<T>
+    where T: LenientValue
+{
+    value: PhantomData<T>
+}
+
+impl<T> DeserializeSeedLenient<T>
+    where T: LenientValue
+{
+    pub const fn new() -> Self {
+        Self { value: PhantomData }
+    }
+}
+
+impl<T> Default for DeserializeSeedLenient<T>
+    where T: LenientValue
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> Clone for DeserializeSeedLenient<T>
+    where T: LenientValue
+{
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T> Copy for DeserializeSeedLenient<T>
+    where T: LenientValue
+{}
+
+impl<'de, T> DeserializeSeed<'de> for &DeserializeSeedLenient<T>
+    where T: LenientValue
+{
+    type Value = T;
+
+    fn deserialize<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+        where D: Deserializer<'de>
+    {
+        (*self).deserialize(deserializer)
+    }
+}
+
+impl<'de, T> DeserializeSeed<'de> for DeserializeSeedLenient<T>
+    where T: LenientValue
+{
+    type Value = T;
+
+    fn deserialize<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+        where D: Deserializer<'de>
+    {
+        struct ResultVisitor<T>
+            where T: LenientValue
+        {
+            value: PhantomData<T>
+        }
+
+        impl<'de, T> Visitor<'de> for ResultVisitor<T>
+            where T: LenientValue
+        {
+            type Value = T;
+
+            fn expecting(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+                write!(f, "{}", T::EXPECTING)
+            }
+
+            fn visit_bool<E>(self, v: bool) -> Result<Self::Value, E>
+                where E: Error
+            {
+                T::from_bool(v).ok_or_else(|| E::invalid_value(Unexpected::Bool(v), &self))
+            }
+
+            fn visit_i64<E>(self, v: i64) -> Result<Self::Value, E>
+                where E: Error
+            {
+                T::from_i128(v as i128).ok_or_else(|| E::invalid_value(Unexpected::Signed(v), &self))
+            }
+
+            fn visit_i128<E>(self, v: i128) -> Result<Self::Value, E>
+                where E: Error
+            {
+                T::from_i128(v).ok_or_else(|| E::invalid_value(Unexpected::Other("128-bit integer"), &self))
+            }
+
+            fn visit_u64<E>(self, v: u64) -> Result<Self::Value, E>
+                where E: Error
+            {
+                T::from_i128(v as i128).ok_or_else(|| E::invalid_value(Unexpected::Unsigned(v), &self))
+            }
+
+            fn visit_u128<E>(self, v: u128) -> Result<Self::Value, E>
+                where E: Error
+            {
+                i128::try_from(v).ok().and_then(T::from_i128).ok_or_else(|| E::invalid_value(Unexpected::Other("128-bit integer"), &self))
+            }
+
+            fn visit_f64<E>(self, v: f64) -> Result<Self::Value, E>
+                where E: Error
+            {
+                T::from_f64(v).ok_or_else(|| E::invalid_value(Unexpected::Float(v), &self))
+            }
+
+            fn visit_str<E>(self, v: &str) -> Result<Self::Value, E>
+                where E: Error
+            {
+                T::from_trimmed_str(v.trim()).ok_or_else(|| E::invalid_value(Unexpected::Str(v), &self))
+            }
+
+            fn visit_unit<E>(self) -> Result<Self::Value, E>
+                where E: Error
+            {
+                Ok(T::default())
+            }
+
+            fn visit_none<E>(self) -> Result<Self::Value, E>
+                where E: Error
+            {
+                Ok(T::default())
+            }
+
+            fn visit_some<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+                where D: Deserializer<'de>
+            {
+                deserializer.deserialize_any(self)
+            }
+        }
+
+        return deserializer.deserialize_any(ResultVisitor { value: PhantomData });
+    }
+}
+
+///
+/// A [`DeserializeSeed`] that wraps another [`DeserializeSeed`], but deserializes
+/// `null` (or more precisely, the none value of an option) as the default value.
+///
+/// As opposed to [`DeserializeSeedLenient`], this does not require
+/// [`serde::Deserializer::deserialize_any()`], but it expects the value to be encoded
+/// as an option in non-self-describing formats.
+///
+/// # Example
+/// ```
+/// # use feanor_serde::lenient::*;
+/// # use feanor_serde::default_seed::seed_of;
+/// # use serde::de::DeserializeSeed;
+/// let mut deserializer = serde_json::Deserializer::new(serde_json::de::StrRead::new("null"));
+/// assert_eq!(Vec::<i64>::new(), DeserializeSeedNullAsDefault::new(seed_of::<Vec<i64>>()).deserialize(&mut deserializer).unwrap());
+/// ```
+///
+#[derive(Clone, Copy)]
+pub struct DeserializeSeedNullAsDefault<S> {
+    seed: S
+}
+
+impl<S> DeserializeSeedNullAsDefault<S> {
+
+    pub fn new<'de, I, M>(seed: I) -> Self
+        where I: IntoSeed<'de, M, Seed = S>
+    {
+        Self { seed: seed.into_seed() }
+    }
+}
+
+impl<'de, S> DeserializeSeed<'de> for DeserializeSeedNullAsDefault<S>
+    where S: DeserializeSeed<'de>,
+        S::Value: Default
+{
+    type Value = S::Value;
+
+    fn deserialize<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+        where D: Deserializer<'de>
+    {
+        struct ResultVisitor<S> {
+            seed: S
+        }
+
+        impl<'de, S> Visitor<'de> for ResultVisitor<S>
+            where S: DeserializeSeed<'de>,
+                S::Value: Default
+        {
+            type Value = S::Value;
+
+            fn expecting(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+                write!(f, "an optional value")
+            }
+
+            fn visit_none<E>(self) -> Result<Self::Value, E>
+                where E: Error
+            {
+                Ok(S::Value::default())
+            }
+
+            fn visit_unit<E>(self) -> Result<Self::Value, E>
+                where E: Error
+            {
+                Ok(S::Value::default())
+            }
+
+            fn visit_some<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+                where D: Deserializer<'de>
+            {
+                self.seed.deserialize(deserializer)
+            }
+        }
+
+        return deserializer.deserialize_option(ResultVisitor { seed: self.seed });
+    }
+}
+
+#[cfg(test)]
+fn deserialize_lenient<T: LenientValue>(json: &str) -> Result<T, serde_json::Error> {
+    DeserializeSeedLenient::<T>::new().deserialize(&mut serde_json::Deserializer::from_str(json))
+}
+
+#[test]
+fn test_serde_json() {
+    assert_eq!(42, deserialize_lenient::<i64>("42").unwrap());
+    assert_eq!(42, deserialize_lenient::<i64>("\" 42\\n\"").unwrap());
+    assert_eq!(42, deserialize_lenient::<u8>("42.0").unwrap());
+    assert_eq!(1, deserialize_lenient::<i32>("true").unwrap());
+    assert_eq!(0, deserialize_lenient::<i32>("null").unwrap());
+    assert!(deserialize_lenient::<u8>("256").is_err());
+    assert!(deserialize_lenient::<u8>("-1").is_err());
+    assert!(deserialize_lenient::<i64>("1.5").is_err());
+    assert!(deserialize_lenient::<i64>("\"abc\"").is_err());
+    assert!(deserialize_lenient::<i64>("[1]").is_err());
+
+    assert_eq!(1.5, deserialize_lenient::<f64>("\"1.5 \"").unwrap());
+    assert_eq!(2., deserialize_lenient::<f32>("2").unwrap());
+
+    assert!(deserialize_lenient::<bool>("1").unwrap());
+    assert!(!deserialize_lenient::<bool>("\" false\"").unwrap());
+    assert!(!deserialize_lenient::<bool>("null").unwrap());
+    assert!(deserialize_lenient::<bool>("2").is_err());
+
+    assert_eq!("abc", deserialize_lenient::<String>("\"  abc \"").unwrap());
+    assert_eq!("12", deserialize_lenient::<String>("12").unwrap());
+    assert_eq!("true", deserialize_lenient::<String>("true").unwrap());
+    assert_eq!("", deserialize_lenient::<String>("null").unwrap());
+
+    assert_eq!(vec![1i64], DeserializeSeedNullAsDefault::new(PhantomData::<Vec<i64>>).deserialize(&mut serde_json::Deserializer::from_str("[1]")).unwrap());
+    assert_eq!(0, DeserializeSeedNullAsDefault::new(DeserializeSeedLenient::<i64>::new()).deserialize(&mut serde_json::Deserializer::from_str("null")).unwrap());
+}
+
+#[test]
+fn test_serde_postcard() {
+    let serialized = postcard::to_allocvec(&(None::<i64>, Some(5i64))).unwrap();
+    let mut deserializer = postcard::Deserializer::from_flavor(postcard::de_flavors::Slice::new(&serialized));
+    assert_eq!(0, DeserializeSeedNullAsDefault::new(PhantomData::<i64>).deserialize(&mut deserializer).unwrap());
+    assert_eq!(5, DeserializeSeedNullAsDefault::new(PhantomData::<i64>).deserialize(&mut deserializer).unwrap());
+
+    // postcard is not self-describing, so lenient deserialization is not supported
+    let serialized = postcard::to_allocvec(&5i64).unwrap();
+    assert!(DeserializeSeedLenient::<i64>::new().deserialize(&mut postcard::Deserializer::from_flavor(postcard::de_flavors::Slice::new(&serialized))).is_err());
+}
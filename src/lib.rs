@@ -1,7 +1,118 @@
 #![doc = include_str!("../Readme.md")]
+#![allow(clippy::needless_return)]
+#![allow(clippy::redundant_field_names)]
 
 pub mod seq;
+pub mod map;
+pub mod routing;
 pub mod newtype_struct;
 pub mod rust_struct;
 pub mod rust_enum;
-pub mod dependent_tuple;
\ No newline at end of file
+pub mod identifier;
+pub mod struct_seed;
+pub mod enum_seed;
+pub mod field_index;
+pub mod dependent_tuple;
+pub mod named_value;
+pub mod any;
+pub mod dynamic;
+pub mod human_readable;
+pub mod base64;
+pub mod hex;
+pub mod bigint;
+pub mod int128;
+pub mod float;
+pub mod matrix;
+pub mod sparse;
+pub mod tensor;
+pub mod polynomial;
+pub mod depth_limit;
+pub mod limits;
+pub mod path;
+pub mod ext;
+pub mod diagnostics;
+pub mod errors;
+pub mod partial;
+pub mod lenient;
+pub mod double_option;
+pub mod option;
+pub mod result;
+pub mod cow;
+pub mod timestamp;
+pub mod trace;
+pub mod inspect;
+pub mod cancel;
+pub mod progress;
+pub mod metrics;
+pub mod rename;
+pub mod overlay;
+pub mod mock;
+pub mod diff;
+pub mod delta;
+pub mod size;
+pub mod generate;
+pub mod canonical;
+pub mod redact;
+pub mod tee;
+pub mod hash;
+pub mod fingerprint;
+pub mod reflect;
+pub mod run_length;
+pub mod raw_bytes;
+pub mod stream;
+pub mod owned;
+pub mod default_seed;
+pub mod seeded_default;
+pub mod seed_factory;
+pub mod scratch;
+pub mod into_seed;
+pub mod prelude;
+
+#[cfg(feature = "postcard")]
+pub mod postcard;
+#[cfg(feature = "postcard")]
+pub mod chunked;
+#[cfg(feature = "postcard")]
+pub mod checksum;
+#[cfg(feature = "postcard")]
+pub mod signed;
+#[cfg(feature = "postcard")]
+pub mod encrypted;
+#[cfg(feature = "postcard")]
+pub mod compressed;
+#[cfg(feature = "postcard")]
+pub mod transformed;
+#[cfg(feature = "postcard")]
+pub mod framing;
+#[cfg(feature = "heapless")]
+pub mod heapless;
+#[cfg(feature = "arrayvec")]
+pub mod arrayvec;
+#[cfg(feature = "either")]
+pub mod either;
+#[cfg(feature = "smallvec")]
+pub mod smallvec;
+#[cfg(feature = "tinyvec")]
+pub mod tinyvec;
+#[cfg(feature = "hashbrown")]
+pub mod hashbrown;
+#[cfg(feature = "chrono")]
+pub mod chrono;
+#[cfg(feature = "time")]
+pub mod time;
+#[cfg(feature = "bincode")]
+pub mod bincode;
+#[cfg(feature = "ciborium")]
+pub mod ciborium;
+#[cfg(feature = "rmp-serde")]
+pub mod rmp_serde;
+#[cfg(feature = "ndarray")]
+pub mod ndarray;
+#[cfg(feature = "wasm")]
+pub mod wasm;
+#[cfg(feature = "arbitrary")]
+pub mod arbitrary;
+#[cfg(feature = "proptest")]
+pub mod proptest;
+#[cfg(any(test, feature = "test"))]
+pub mod test;
@@ -0,0 +1,180 @@
+use serde::de::Error;
+
+use crate::depth_limit::DepthLimit;
+
+///
+/// Upper bounds on the size of untrusted input, that can be attached to the
+/// [`serde::de::DeserializeSeed`]s of this crate using their `with_limits()` function.
+///
+/// Currently, this is supported by
+///  - [`crate::seq::DeserializeSeedSeq`], which enforces [`Limits::max_seq_len()`]
+///  - [`crate::map::DeserializeSeedMap`] and [`crate::map::DeserializeSeedMapAsSeq`],
+///    which enforce [`Limits::max_map_entries()`]
+///  - [`crate::map::DeserializeSeedStringKey`], which enforces [`Limits::max_str_len()`]
+///  - [`crate::base64::DeserializeSeedBase64`], which enforces [`Limits::max_str_len()`]
+///    on the encoded string and [`Limits::max_bytes_len()`] on the decoded bytes
+///
+/// The maximal depth can be enforced by creating a [`DepthLimit`] via [`Limits::depth_limit()`].
+///
+/// By default, all limits are `usize::MAX`, i.e. no limits are enforced.
+///
+/// # Example
+/// ```
+/// # use feanor_serde::seq::*;
+/// # use feanor_serde::limits::*;
+/// # use feanor_serde::default_seed::seed_of;
+/// # use std::iter::repeat;
+/// # use serde::de::DeserializeSeed;
+/// let limits = Limits::UNLIMITED.with_max_seq_len(2);
+/// let mut deserializer = serde_json::Deserializer::new(serde_json::de::StrRead::new("[1, 3, 5]"));
+/// let deserialize_seed = DeserializeSeedSeq::new(
+///     repeat(seed_of::<i64>()),
+///     Vec::new(),
+///     |mut current, next| { current.push(next); current }
+/// ).with_limits(limits);
+/// assert!(deserialize_seed.deserialize(&mut deserializer).is_err());
+/// ```
+///
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Limits {
+    max_seq_len: usize,
+    max_str_len: usize,
+    max_bytes_len: usize,
+    max_map_entries: usize,
+    max_depth: usize
+}
+
+impl Limits {
+
+    pub const UNLIMITED: Limits = Limits {
+        max_seq_len: usize::MAX,
+        max_str_len: usize::MAX,
+        max_bytes_len: usize::MAX,
+        max_map_entries: usize::MAX,
+        max_depth: usize::MAX
+    };
+
+    pub const fn with_max_seq_len(self, max_seq_len: usize) -> Self {
+        Self { max_seq_len, ..self }
+    }
+
+    pub const fn with_max_str_len(self, max_str_len: usize) -> Self {
+        Self { max_str_len, ..self }
+    }
+
+    pub const fn with_max_bytes_len(self, max_bytes_len: usize) -> Self {
+        Self { max_bytes_len, ..self }
+    }
+
+    pub const fn with_max_map_entries(self, max_map_entries: usize) -> Self {
+        Self { max_map_entries, ..self }
+    }
+
+    pub const fn with_max_depth(self, max_depth: usize) -> Self {
+        Self { max_depth, ..self }
+    }
+
+    ///
+    /// The maximal number of elements in a sequence.
+    ///
+    pub const fn max_seq_len(&self) -> usize {
+        self.max_seq_len
+    }
+
+    ///
+    /// The maximal length of a string, in bytes.
+    ///
+    pub const fn max_str_len(&self) -> usize {
+        self.max_str_len
+    }
+
+    ///
+    /// The maximal length of a byte buffer.
+    ///
+    pub const fn max_bytes_len(&self) -> usize {
+        self.max_bytes_len
+    }
+
+    ///
+    /// The maximal number of entries in a map.
+    ///
+    pub const fn max_map_entries(&self) -> usize {
+        self.max_map_entries
+    }
+
+    ///
+    /// The maximal nesting depth, as enforced by [`DepthLimit`].
+    ///
+    pub const fn max_depth(&self) -> usize {
+        self.max_depth
+    }
+
+    ///
+    /// Creates a new [`DepthLimit`] that enforces [`Limits::max_depth()`].
+    ///
+    pub fn depth_limit(&self) -> DepthLimit {
+        DepthLimit::new(self.max_depth)
+    }
+
+    pub(crate) fn check_seq_len<E>(&self, len: usize) -> Result<(), E>
+        where E: Error
+    {
+        check(len, self.max_seq_len, "sequence length", "a shorter sequence")
+    }
+
+    pub(crate) fn check_str_len<E>(&self, len: usize) -> Result<(), E>
+        where E: Error
+    {
+        check(len, self.max_str_len, "string length", "a shorter string")
+    }
+
+    pub(crate) fn check_bytes_len<E>(&self, len: usize) -> Result<(), E>
+        where E: Error
+    {
+        check(len, self.max_bytes_len, "byte buffer length", "a shorter byte buffer")
+    }
+
+    pub(crate) fn check_map_entries<E>(&self, len: usize) -> Result<(), E>
+        where E: Error
+    {
+        check(len, self.max_map_entries, "number of map entries", "a map with fewer entries")
+    }
+}
+
+fn check<E>(len: usize, max: usize, name: &str, expected: &'static str) -> Result<(), E>
+    where E: Error
+{
+    if len > max {
+        #[cfg(feature = "detailed-errors")]
+        {
+            let _ = expected;
+            return Err(E::custom(format!("{} {} exceeds the limit {}", name, len, max)));
+        }
+        #[cfg(not(feature = "detailed-errors"))]
+        {
+            let _ = name;
+            return Err(E::invalid_length(len, &expected));
+        }
+    }
+    return Ok(());
+}
+
+impl Default for Limits {
+
+    fn default() -> Self {
+        Self::UNLIMITED
+    }
+}
+
+#[test]
+fn test_limits() {
+    let limits = Limits::UNLIMITED.with_max_seq_len(1).with_max_str_len(2).with_max_bytes_len(3).with_max_map_entries(4).with_max_depth(5);
+    assert_eq!(1, limits.max_seq_len());
+    assert_eq!(2, limits.max_str_len());
+    assert_eq!(3, limits.max_bytes_len());
+    assert_eq!(4, limits.max_map_entries());
+    assert_eq!(5, limits.depth_limit().max_depth());
+    assert!(limits.check_seq_len::<serde_json::Error>(1).is_ok());
+    assert!(limits.check_seq_len::<serde_json::Error>(2).is_err());
+    assert_eq!(Limits::UNLIMITED, Limits::default());
+}
@@ -0,0 +1,895 @@
+use std::marker::PhantomData;
+
+use serde::de::{DeserializeSeed, Error, IgnoredAny, MapAccess, SeqAccess, Unexpected, Visitor};
+use serde::ser::{Serialize, SerializeMap, SerializeSeq, Serializer};
+use serde::Deserializer;
+
+use crate::limits::Limits;
+use crate::seq::{DeserializeSeedSeq, LengthMismatchError};
+use crate::into_seed::IntoSeed;
+
+///
+/// Wraps an [`Iterator`] over pairs of serializable keys and values, and implements
+/// [`Serialize`] by mapping the sequence of entries to the map type in the serde data
+/// model.
+///
+#[derive(Clone, Copy)]
+pub struct SerializableMap<I>
+    where I: Iterator + Clone
+{
+    data: I,
+    len: Option<usize>
+}
+
+impl<I> SerializableMap<I>
+    where I: Iterator + Clone
+{
+    pub const fn new(data: I) -> Self {
+        Self { data: data, len: None }
+    }
+
+    ///
+    /// Creates a new [`SerializableMap`] that reports the given length to the serializer.
+    /// Some formats (e.g. postcard) require this.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `len` is inconsistent with the size hint of `data`. Use
+    /// [`SerializableMap::try_new_with_len()`] to handle this case without panicking.
+    ///
+    pub fn new_with_len(data: I, len: usize) -> Self {
+        Self::try_new_with_len(data, len).unwrap()
+    }
+
+    ///
+    /// Creates a new [`SerializableMap`] that reports the given length to the serializer,
+    /// or returns an error if `len` is inconsistent with the size hint of `data`.
+    ///
+    pub fn try_new_with_len(data: I, len: usize) -> Result<Self, LengthMismatchError> {
+        LengthMismatchError::check(data.size_hint(), len)?;
+        return Ok(Self { data: data, len: Some(len) });
+    }
+}
+
+impl<I> std::fmt::Debug for SerializableMap<I>
+    where I: Iterator + Clone
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("SerializableMap").field("len", &self.len).finish_non_exhaustive()
+    }
+}
+
+impl<I, K, V> Serialize for SerializableMap<I>
+    where I: Iterator<Item = (K, V)> + Clone,
+        K: Serialize,
+        V: Serialize
+{
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+        where S: Serializer
+    {
+        let mut map = serializer.serialize_map(self.len)?;
+        for (key, value) in self.data.clone() {
+            map.serialize_entry(&key, &value)?;
+        }
+        return map.end();
+    }
+}
+
+///
+/// A [`DeserializeSeed`] that deserializes a map by deserializing each
+/// key and value with a given pair of [`DeserializeSeed`]s, and combining
+/// the result with a given combinator.
+///
+/// As for [`crate::seq::DeserializeSeedSeq`], the iterator producing the seeds
+/// must contain at least one more pair of seeds than the map to deserialize has entries.
+///
+/// # Example
+/// ```
+/// # use feanor_serde::map::*;
+/// # use feanor_serde::default_seed::seed_of;
+/// # use std::iter::repeat;
+/// # use std::collections::BTreeMap;
+/// # use serde::de::DeserializeSeed;
+/// let mut deserializer = serde_json::Deserializer::new(serde_json::de::StrRead::new(r#"{ "a": 1, "b": 2 }"#));
+/// let deserialize_seed = DeserializeSeedMap::new(
+///     repeat((seed_of::<String>(), seed_of::<i64>())),
+///     BTreeMap::new(),
+///     |mut current, key, value| { current.insert(key, value); current }
+/// );
+/// assert_eq!(BTreeMap::from([("a".to_owned(), 1), ("b".to_owned(), 2)]), deserialize_seed.deserialize(&mut deserializer).unwrap());
+/// ```
+///
+pub struct DeserializeSeedMap<V, KS, VS, T, C>
+    where V: Iterator<Item = (KS, VS)>
+{
+    element_seed: PhantomData<(KS, VS)>,
+    seeds: V,
+    initial: T,
+    collector: C,
+    limits: Limits
+}
+
+impl<'de, V, KS, VS, T, C> DeserializeSeedMap<V, KS, VS, T, C>
+    where V: Iterator<Item = (KS, VS)>,
+        KS: DeserializeSeed<'de>,
+        VS: DeserializeSeed<'de>,
+        C: FnMut(T, KS::Value, VS::Value) -> T
+{
+    pub const fn new(seeds: V, initial: T, collector: C) -> Self {
+        Self {
+            element_seed: PhantomData,
+            seeds: seeds,
+            initial: initial,
+            collector: collector,
+            limits: Limits::UNLIMITED
+        }
+    }
+
+    ///
+    /// Makes deserialization fail if the map has more than [`Limits::max_map_entries()`]
+    /// entries.
+    ///
+    pub const fn with_limits(self, limits: Limits) -> Self {
+        let mut result = self;
+        result.limits = limits;
+        return result;
+    }
+
+    ///
+    /// Boxes the seed iterator and the collector, so that the resulting type does not
+    /// depend on the types of closures anymore, see [`crate::seq::BoxedDeserializeSeedSeq`].
+    ///
+    pub fn boxed<'a>(self) -> BoxedDeserializeSeedMap<'a, KS, VS, T, KS::Value, VS::Value>
+        where V: 'a,
+            C: 'a
+    {
+        let result: BoxedDeserializeSeedMap<'a, KS, VS, T, KS::Value, VS::Value> = DeserializeSeedMap::new(Box::new(self.seeds), self.initial, Box::new(self.collector));
+        return result.with_limits(self.limits);
+    }
+}
+
+///
+/// A [`DeserializeSeedMap`] whose seed iterator and collector are trait objects, as returned by
+/// [`DeserializeSeedMap::boxed()`]. Here `K` and `V` are the types of the keys and values produced
+/// by the seeds `KS` and `VS`.
+///
+pub type BoxedDeserializeSeedMap<'a, KS, VS, T, K, V> = DeserializeSeedMap<Box<dyn Iterator<Item = (KS, VS)> + 'a>, KS, VS, T, Box<dyn FnMut(T, K, V) -> T + 'a>>;
+
+impl<V, KS, VS, T, C> Clone for DeserializeSeedMap<V, KS, VS, T, C>
+    where V: Iterator<Item = (KS, VS)> + Clone,
+        T: Clone,
+        C: Clone
+{
+    fn clone(&self) -> Self {
+        Self {
+            element_seed: PhantomData,
+            seeds: self.seeds.clone(),
+            initial: self.initial.clone(),
+            collector: self.collector.clone(),
+            limits: self.limits
+        }
+    }
+}
+
+impl<V, KS, VS, T, C> Copy for DeserializeSeedMap<V, KS, VS, T, C>
+    where V: Iterator<Item = (KS, VS)> + Copy,
+        T: Copy,
+        C: Copy
+{}
+
+impl<V, KS, VS, T, C> std::fmt::Debug for DeserializeSeedMap<V, KS, VS, T, C>
+    where V: Iterator<Item = (KS, VS)>
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("DeserializeSeedMap").field("limits", &self.limits).finish_non_exhaustive()
+    }
+}
+
+///
+/// Deserializes using a clone of the referenced seed, so a configured seed can be reused
+/// without reconstructing it.
+///
+impl<'de, V, KS, VS, T, C> DeserializeSeed<'de> for &DeserializeSeedMap<V, KS, VS, T, C>
+    where V: Iterator<Item = (KS, VS)> + Clone,
+        KS: DeserializeSeed<'de>,
+        VS: DeserializeSeed<'de>,
+        T: Clone,
+        C: Clone + FnMut(T, KS::Value, VS::Value) -> T
+{
+    type Value = T;
+
+    fn deserialize<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+        where D: Deserializer<'de>
+    {
+        self.clone().deserialize(deserializer)
+    }
+}
+
+impl<'de, V, KS, VS, T, C> DeserializeSeed<'de> for DeserializeSeedMap<V, KS, VS, T, C>
+    where V: Iterator<Item = (KS, VS)>,
+        KS: DeserializeSeed<'de>,
+        VS: DeserializeSeed<'de>,
+        C: FnMut(T, KS::Value, VS::Value) -> T
+{
+    type Value = T;
+
+    fn deserialize<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+        where D: Deserializer<'de>
+    {
+        struct ResultVisitor<'de, V, KS, VS, T, C>
+            where V: Iterator<Item = (KS, VS)>,
+                KS: DeserializeSeed<'de>,
+                VS: DeserializeSeed<'de>,
+                C: FnMut(T, KS::Value, VS::Value) -> T
+        {
+            deserializer: PhantomData<&'de ()>,
+            element_seed: PhantomData<(KS, VS)>,
+            seeds: V,
+            initial: T,
+            collector: C,
+            limits: Limits
+        }
+
+        impl<'de, V, KS, VS, T, C> Visitor<'de> for ResultVisitor<'de, V, KS, VS, T, C>
+            where V: Iterator<Item = (KS, VS)>,
+                KS: DeserializeSeed<'de>,
+                VS: DeserializeSeed<'de>,
+                C: FnMut(T, KS::Value, VS::Value) -> T
+        {
+            type Value = T;
+
+            fn expecting(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+                write!(f, "a map")
+            }
+
+            fn visit_map<M>(mut self, mut map: M) -> Result<Self::Value, M::Error>
+                where M: MapAccess<'de>
+            {
+                if let Some(len) = map.size_hint() {
+                    self.limits.check_map_entries(len)?;
+                }
+                let mut result = self.initial;
+                let mut current_len = 0;
+                for (key_seed, value_seed) in self.seeds.by_ref() {
+                    if let Some(key) = map.next_key_seed(key_seed)? {
+                        current_len += 1;
+                        self.limits.check_map_entries(current_len)?;
+                        let value = map.next_value_seed(value_seed)?;
+                        result = (self.collector)(result, key, value);
+                    } else {
+                        return Ok(result);
+                    }
+                }
+                if map.next_key::<IgnoredAny>()?.is_none() {
+                    return Ok(result);
+                }
+                return Err(crate::errors::invalid_length(current_len + 1, "a map with fewer entries", format_args!("a map with at most {} entries", current_len)))
+            }
+        }
+
+        return deserializer.deserialize_map(ResultVisitor {
+            deserializer: PhantomData,
+            element_seed: PhantomData,
+            collector: self.collector,
+            initial: self.initial,
+            seeds: self.seeds,
+            limits: self.limits
+        });
+    }
+}
+
+///
+/// Wraps a map key, and implements [`Serialize`] by converting it to a string
+/// using the given function if the serializer is human-readable, and serializing
+/// the key itself otherwise.
+///
+/// This is useful since many human-readable formats (most notably JSON) only support
+/// string keys in maps, while binary formats can use the key directly.
+///
+/// # Example
+/// ```
+/// # use feanor_serde::map::*;
+/// let map = [(1, "a"), (2, "b")];
+/// let serializable = SerializableMap::new(map.iter().map(|(key, value)| (SerializableStringKey::new(key, |key: &&i64| key.to_string()), value)));
+/// assert_eq!(r#"{"1":"a","2":"b"}"#, serde_json::to_string(&serializable).unwrap());
+/// ```
+///
+pub struct SerializableStringKey<K, F>
+    where K: Serialize,
+        F: Fn(&K) -> String
+{
+    key: K,
+    to_string: F
+}
+
+impl<K, F> SerializableStringKey<K, F>
+    where K: Serialize,
+        F: Fn(&K) -> String
+{
+    pub const fn new(key: K, to_string: F) -> Self {
+        Self { key, to_string }
+    }
+}
+
+impl<K, F> Serialize for SerializableStringKey<K, F>
+    where K: Serialize,
+        F: Fn(&K) -> String
+{
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+        where S: Serializer
+    {
+        if serializer.is_human_readable() {
+            serializer.serialize_str(&(self.to_string)(&self.key))
+        } else {
+            self.key.serialize(serializer)
+        }
+    }
+}
+
+///
+/// A [`DeserializeSeed`] for map keys that were serialized using [`SerializableStringKey`].
+/// For human-readable deserializers, a string is deserialized and converted to the key using
+/// the given function, otherwise the key is deserialized using the given [`DeserializeSeed`].
+///
+/// # Example
+/// ```
+/// # use feanor_serde::map::*;
+/// # use feanor_serde::default_seed::seed_of;
+/// # use std::iter::repeat;
+/// # use std::collections::BTreeMap;
+/// # use serde::de::DeserializeSeed;
+/// let mut deserializer = serde_json::Deserializer::new(serde_json::de::StrRead::new(r#"{ "1": "a", "2": "b" }"#));
+/// let deserialize_seed = DeserializeSeedMap::new(
+///     repeat((DeserializeSeedStringKey::new(seed_of::<i64>(), |key: &str| key.parse::<i64>().map_err(|e| e.to_string())), seed_of::<String>())),
+///     BTreeMap::new(),
+///     |mut current, key, value| { current.insert(key, value); current }
+/// );
+/// assert_eq!(BTreeMap::from([(1, "a".to_owned()), (2, "b".to_owned())]), deserialize_seed.deserialize(&mut deserializer).unwrap());
+/// ```
+///
+#[derive(Clone, Copy)]
+pub struct DeserializeSeedStringKey<S, F> {
+    seed: S,
+    from_string: F,
+    limits: Limits
+}
+
+impl<'de, S, F> DeserializeSeedStringKey<S, F>
+    where S: DeserializeSeed<'de>,
+        F: FnOnce(&str) -> Result<S::Value, String>
+{
+    pub fn new<I, M>(seed: I, from_string: F) -> Self
+        where I: IntoSeed<'de, M, Seed = S>
+    {
+        Self { seed: seed.into_seed(), from_string, limits: Limits::UNLIMITED }
+    }
+
+    ///
+    /// Makes deserialization from human-readable formats fail if the string-encoded key
+    /// is longer than [`Limits::max_str_len()`].
+    ///
+    pub const fn with_limits(self, limits: Limits) -> Self {
+        let mut result = self;
+        result.limits = limits;
+        return result;
+    }
+}
+
+impl<S, F> std::fmt::Debug for DeserializeSeedStringKey<S, F>
+    where S: std::fmt::Debug
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("DeserializeSeedStringKey").field("seed", &self.seed).field("limits", &self.limits).finish_non_exhaustive()
+    }
+}
+
+impl<'de, S, F> DeserializeSeed<'de> for DeserializeSeedStringKey<S, F>
+    where S: DeserializeSeed<'de>,
+        F: FnOnce(&str) -> Result<S::Value, String>
+{
+    type Value = S::Value;
+
+    fn deserialize<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+        where D: Deserializer<'de>
+    {
+        struct StringKeyVisitor<T, F>
+            where F: FnOnce(&str) -> Result<T, String>
+        {
+            result: PhantomData<T>,
+            from_string: F,
+            limits: Limits
+        }
+
+        impl<'de, T, F> Visitor<'de> for StringKeyVisitor<T, F>
+            where F: FnOnce(&str) -> Result<T, String>
+        {
+            type Value = T;
+
+            fn expecting(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+                write!(f, "a string-encoded map key")
+            }
+
+            fn visit_str<E>(self, value: &str) -> Result<Self::Value, E>
+                where E: Error
+            {
+                self.limits.check_str_len(value.len())?;
+                (self.from_string)(value).map_err(|e| E::invalid_value(Unexpected::Str(value), &e.as_str()))
+            }
+        }
+
+        if deserializer.is_human_readable() {
+            deserializer.deserialize_str(StringKeyVisitor { result: PhantomData, from_string: self.from_string, limits: self.limits })
+        } else {
+            self.seed.deserialize(deserializer)
+        }
+    }
+}
+
+///
+/// Wraps an [`Iterator`] over pairs of serializable keys and values, and implements
+/// [`Serialize`] by mapping it to a sequence of 2-tuples `(key, value)` in the serde
+/// data model.
+///
+/// In contrast to [`SerializableMap`], this also works for formats that don't support
+/// maps, or keys that are not supported as map keys by the format. Furthermore, the order
+/// of entries is explicitly part of the serialized data.
+///
+/// # Example
+/// ```
+/// # use feanor_serde::map::*;
+/// let map = [((1, 2), "a"), ((3, 4), "b")];
+/// assert_eq!(r#"[[[1,2],"a"],[[3,4],"b"]]"#, serde_json::to_string(&SerializableMapAsSeq::new(map.iter().map(|(k, v)| (k, v)))).unwrap());
+/// ```
+///
+#[derive(Clone, Copy)]
+pub struct SerializableMapAsSeq<I>
+    where I: Iterator + Clone
+{
+    data: I,
+    len: Option<usize>
+}
+
+impl<I> SerializableMapAsSeq<I>
+    where I: Iterator + Clone
+{
+    pub const fn new(data: I) -> Self {
+        Self { data: data, len: None }
+    }
+
+    ///
+    /// Creates a new [`SerializableMapAsSeq`] that reports the given length to the serializer.
+    /// Some formats (e.g. postcard) require this.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `len` is inconsistent with the size hint of `data`. Use
+    /// [`SerializableMapAsSeq::try_new_with_len()`] to handle this case without panicking.
+    ///
+    pub fn new_with_len(data: I, len: usize) -> Self {
+        Self::try_new_with_len(data, len).unwrap()
+    }
+
+    ///
+    /// Creates a new [`SerializableMapAsSeq`] that reports the given length to the serializer,
+    /// or returns an error if `len` is inconsistent with the size hint of `data`.
+    ///
+    pub fn try_new_with_len(data: I, len: usize) -> Result<Self, LengthMismatchError> {
+        LengthMismatchError::check(data.size_hint(), len)?;
+        return Ok(Self { data: data, len: Some(len) });
+    }
+}
+
+impl<I> std::fmt::Debug for SerializableMapAsSeq<I>
+    where I: Iterator + Clone
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("SerializableMapAsSeq").field("len", &self.len).finish_non_exhaustive()
+    }
+}
+
+impl<I, K, V> Serialize for SerializableMapAsSeq<I>
+    where I: Iterator<Item = (K, V)> + Clone,
+        K: Serialize,
+        V: Serialize
+{
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+        where S: Serializer
+    {
+        let mut seq = serializer.serialize_seq(self.len)?;
+        for entry in self.data.clone() {
+            seq.serialize_element(&entry)?;
+        }
+        return seq.end();
+    }
+}
+
+struct DeserializeSeedEntry<KS, VS> {
+    key_seed: KS,
+    value_seed: VS
+}
+
+impl<'de, KS, VS> DeserializeSeed<'de> for DeserializeSeedEntry<KS, VS>
+    where KS: DeserializeSeed<'de>,
+        VS: DeserializeSeed<'de>
+{
+    type Value = (KS::Value, VS::Value);
+
+    fn deserialize<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+        where D: Deserializer<'de>
+    {
+        struct EntryVisitor<KS, VS> {
+            key_seed: KS,
+            value_seed: VS
+        }
+
+        impl<'de, KS, VS> Visitor<'de> for EntryVisitor<KS, VS>
+            where KS: DeserializeSeed<'de>,
+                VS: DeserializeSeed<'de>
+        {
+            type Value = (KS::Value, VS::Value);
+
+            fn expecting(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+                write!(f, "a tuple with 2 elements")
+            }
+
+            fn visit_seq<A>(self, mut seq: A) -> Result<Self::Value, A::Error>
+                where A: SeqAccess<'de>
+            {
+                let key = seq.next_element_seed(self.key_seed)?.ok_or_else(|| <A::Error as Error>::invalid_length(0, &"a tuple with 2 elements"))?;
+                let value = seq.next_element_seed(self.value_seed)?.ok_or_else(|| <A::Error as Error>::invalid_length(1, &"a tuple with 2 elements"))?;
+                return Ok((key, value));
+            }
+        }
+
+        return deserializer.deserialize_tuple(2, EntryVisitor { key_seed: self.key_seed, value_seed: self.value_seed });
+    }
+}
+
+///
+/// A [`DeserializeSeed`] that deserializes a map that was serialized using [`SerializableMapAsSeq`],
+/// i.e. as a sequence of 2-tuples `(key, value)`. Each key and value is deserialized with a given
+/// pair of [`DeserializeSeed`]s, and the results are combined with a given combinator, in the order
+/// in which they were serialized.
+///
+/// As for [`crate::seq::DeserializeSeedSeq`], the iterator producing the seeds
+/// must contain at least one more pair of seeds than the map to deserialize has entries.
+///
+/// # Example
+/// ```
+/// # use feanor_serde::map::*;
+/// # use feanor_serde::default_seed::seed_of;
+/// # use std::iter::repeat;
+/// # use serde::de::DeserializeSeed;
+/// let mut deserializer = serde_json::Deserializer::new(serde_json::de::StrRead::new(r#"[[[1,2],"a"],[[3,4],"b"]]"#));
+/// let deserialize_seed = DeserializeSeedMapAsSeq::new(
+///     repeat((seed_of::<(i64, i64)>(), seed_of::<String>())),
+///     Vec::new(),
+///     |mut current, key, value| { current.push((key, value)); current }
+/// );
+/// assert_eq!(vec![((1, 2), "a".to_owned()), ((3, 4), "b".to_owned())], deserialize_seed.deserialize(&mut deserializer).unwrap());
+/// ```
+///
+pub struct DeserializeSeedMapAsSeq<V, KS, VS, T, C>
+    where V: Iterator<Item = (KS, VS)>
+{
+    element_seed: PhantomData<(KS, VS)>,
+    seeds: V,
+    initial: T,
+    collector: C,
+    limits: Limits
+}
+
+impl<'de, V, KS, VS, T, C> DeserializeSeedMapAsSeq<V, KS, VS, T, C>
+    where V: Iterator<Item = (KS, VS)>,
+        KS: DeserializeSeed<'de>,
+        VS: DeserializeSeed<'de>,
+        C: FnMut(T, KS::Value, VS::Value) -> T
+{
+    pub const fn new(seeds: V, initial: T, collector: C) -> Self {
+        Self {
+            element_seed: PhantomData,
+            seeds: seeds,
+            initial: initial,
+            collector: collector,
+            limits: Limits::UNLIMITED
+        }
+    }
+
+    ///
+    /// Makes deserialization fail if the map has more than [`Limits::max_map_entries()`]
+    /// entries.
+    ///
+    pub const fn with_limits(self, limits: Limits) -> Self {
+        let mut result = self;
+        result.limits = limits;
+        return result;
+    }
+
+    ///
+    /// Boxes the seed iterator and the collector, so that the resulting type does not
+    /// depend on the types of closures anymore, see [`crate::seq::BoxedDeserializeSeedSeq`].
+    ///
+    pub fn boxed<'a>(self) -> BoxedDeserializeSeedMapAsSeq<'a, KS, VS, T, KS::Value, VS::Value>
+        where V: 'a,
+            C: 'a
+    {
+        let result: BoxedDeserializeSeedMapAsSeq<'a, KS, VS, T, KS::Value, VS::Value> = DeserializeSeedMapAsSeq::new(Box::new(self.seeds), self.initial, Box::new(self.collector));
+        return result.with_limits(self.limits);
+    }
+}
+
+///
+/// A [`DeserializeSeedMapAsSeq`] whose seed iterator and collector are trait objects, as returned by
+/// [`DeserializeSeedMapAsSeq::boxed()`]. Here `K` and `V` are the types of the keys and values produced
+/// by the seeds `KS` and `VS`.
+///
+pub type BoxedDeserializeSeedMapAsSeq<'a, KS, VS, T, K, V> = DeserializeSeedMapAsSeq<Box<dyn Iterator<Item = (KS, VS)> + 'a>, KS, VS, T, Box<dyn FnMut(T, K, V) -> T + 'a>>;
+
+impl<V, KS, VS, T, C> Clone for DeserializeSeedMapAsSeq<V, KS, VS, T, C>
+    where V: Iterator<Item = (KS, VS)> + Clone,
+        T: Clone,
+        C: Clone
+{
+    fn clone(&self) -> Self {
+        Self {
+            element_seed: PhantomData,
+            seeds: self.seeds.clone(),
+            initial: self.initial.clone(),
+            collector: self.collector.clone(),
+            limits: self.limits
+        }
+    }
+}
+
+impl<V, KS, VS, T, C> Copy for DeserializeSeedMapAsSeq<V, KS, VS, T, C>
+    where V: Iterator<Item = (KS, VS)> + Copy,
+        T: Copy,
+        C: Copy
+{}
+
+impl<V, KS, VS, T, C> std::fmt::Debug for DeserializeSeedMapAsSeq<V, KS, VS, T, C>
+    where V: Iterator<Item = (KS, VS)>
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("DeserializeSeedMapAsSeq").field("limits", &self.limits).finish_non_exhaustive()
+    }
+}
+
+///
+/// Deserializes using a clone of the referenced seed, so a configured seed can be reused
+/// without reconstructing it.
+///
+impl<'de, V, KS, VS, T, C> DeserializeSeed<'de> for &DeserializeSeedMapAsSeq<V, KS, VS, T, C>
+    where V: Iterator<Item = (KS, VS)> + Clone,
+        KS: DeserializeSeed<'de>,
+        VS: DeserializeSeed<'de>,
+        T: Clone,
+        C: Clone + FnMut(T, KS::Value, VS::Value) -> T
+{
+    type Value = T;
+
+    fn deserialize<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+        where D: Deserializer<'de>
+    {
+        self.clone().deserialize(deserializer)
+    }
+}
+
+impl<'de, V, KS, VS, T, C> DeserializeSeed<'de> for DeserializeSeedMapAsSeq<V, KS, VS, T, C>
+    where V: Iterator<Item = (KS, VS)>,
+        KS: DeserializeSeed<'de>,
+        VS: DeserializeSeed<'de>,
+        C: FnMut(T, KS::Value, VS::Value) -> T
+{
+    type Value = T;
+
+    fn deserialize<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+        where D: Deserializer<'de>
+    {
+        let mut collector = self.collector;
+        return DeserializeSeedSeq::new(
+            self.seeds.map(|(key_seed, value_seed)| DeserializeSeedEntry { key_seed, value_seed }),
+            self.initial,
+            |current, (key, value)| collector(current, key, value)
+        ).with_limits(Limits::UNLIMITED.with_max_seq_len(self.limits.max_map_entries())).deserialize(deserializer);
+    }
+}
+
+#[cfg(test)]
+use std::iter::repeat;
+#[cfg(test)]
+use std::collections::BTreeMap;
+
+#[cfg(test)]
+fn testdata() -> Vec<BTreeMap<(i64, i64), String>> {
+    vec![
+        BTreeMap::new(),
+        BTreeMap::from([((1, 2), "a".to_owned())]),
+        BTreeMap::from([((1, 2), "a".to_owned()), ((-1, 0), "b".to_owned()), ((3, 3), "".to_owned())])
+    ]
+}
+
+#[cfg(test)]
+fn serializable_testdata(data: &BTreeMap<(i64, i64), String>) -> impl '_ + Serialize {
+    SerializableMap::new_with_len(
+        data.iter().map(|(key, value)| (SerializableStringKey::new(key, |(a, b): &&(i64, i64)| format!("{},{}", a, b)), value)),
+        data.len()
+    )
+}
+
+#[cfg(test)]
+fn deserialize_seed_testdata<'de>() -> impl DeserializeSeed<'de, Value = BTreeMap<(i64, i64), String>> {
+    let parse_key = |key: &str| {
+        let (a, b) = key.split_once(',').ok_or("expected two integers separated by ,")?;
+        Ok((a.parse::<i64>().map_err(|e| e.to_string())?, b.parse::<i64>().map_err(|e| e.to_string())?))
+    };
+    DeserializeSeedMap::new(
+        repeat((DeserializeSeedStringKey::new(PhantomData::<(i64, i64)>, parse_key), PhantomData::<String>)),
+        BTreeMap::new(),
+        |mut current, key, value| { current.insert(key, value); current }
+    )
+}
+
+#[test]
+fn test_serde_postcard() {
+    for data in testdata() {
+        let serialized = postcard::to_allocvec(&serializable_testdata(&data)).unwrap();
+        assert_eq!(postcard::to_allocvec(&data).unwrap(), serialized);
+        let result = deserialize_seed_testdata().deserialize(
+            &mut postcard::Deserializer::from_flavor(postcard::de_flavors::Slice::new(&serialized))
+        ).unwrap();
+        assert_eq!(data, result);
+    }
+}
+
+#[test]
+fn test_serde_json() {
+    for data in testdata() {
+        let serialized = serde_json::to_string(&serializable_testdata(&data)).unwrap();
+        let result = deserialize_seed_testdata().deserialize(
+            &mut serde_json::Deserializer::from_str(&serialized)
+        ).unwrap();
+        assert_eq!(data, result);
+    }
+    assert_eq!(r#"{"-1,0":"b","1,2":"a","3,3":""}"#, serde_json::to_string(&serializable_testdata(&testdata()[2])).unwrap());
+    assert!(deserialize_seed_testdata().deserialize(&mut serde_json::Deserializer::from_str(r#"{"1;2":"a"}"#)).is_err());
+}
+
+#[test]
+fn test_deserialize_map_partially() {
+    let result = DeserializeSeedMap::new(
+        (0..1).map(|_| (PhantomData::<String>, PhantomData::<i64>)),
+        Vec::new(),
+        |mut current, key, value| { current.push((key, value)); current }
+    ).deserialize(&mut serde_json::Deserializer::from_str(r#"{"a":1,"b":2}"#));
+    assert!(result.is_err());
+
+    let result = DeserializeSeedMap::new(
+        (0..).map(|_| (PhantomData::<String>, PhantomData::<i64>)).take(2),
+        Vec::new(),
+        |mut current, key, value| { current.push((key, value)); current }
+    ).deserialize(&mut serde_json::Deserializer::from_str(r#"{"a":1,"b":2}"#)).unwrap();
+    assert_eq!(vec![("a".to_owned(), 1), ("b".to_owned(), 2)], result);
+
+    let result = DeserializeSeedMap::new(
+        (0..3).map(|_| (PhantomData::<String>, PhantomData::<i64>)),
+        Vec::new(),
+        |mut current, key, value| { current.push((key, value)); current }
+    ).deserialize(&mut serde_json::Deserializer::from_str(r#"{"a":1,"b":2}"#)).unwrap();
+    assert_eq!(vec![("a".to_owned(), 1), ("b".to_owned(), 2)], result);
+}
+
+#[test]
+fn test_map_as_seq_serde_postcard() {
+    for data in testdata() {
+        let serialized = postcard::to_allocvec(&SerializableMapAsSeq::new_with_len(data.iter(), data.len())).unwrap();
+        let result = DeserializeSeedMapAsSeq::new(
+            repeat((PhantomData::<(i64, i64)>, PhantomData::<String>)),
+            BTreeMap::new(),
+            |mut current, key, value| { current.insert(key, value); current }
+        ).deserialize(
+            &mut postcard::Deserializer::from_flavor(postcard::de_flavors::Slice::new(&serialized))
+        ).unwrap();
+        assert_eq!(data, result);
+    }
+}
+
+#[test]
+fn test_map_as_seq_serde_json() {
+    for data in testdata() {
+        let serialized = serde_json::to_string(&SerializableMapAsSeq::new(data.iter())).unwrap();
+        let result = DeserializeSeedMapAsSeq::new(
+            repeat((PhantomData::<(i64, i64)>, PhantomData::<String>)),
+            BTreeMap::new(),
+            |mut current, key, value| { current.insert(key, value); current }
+        ).deserialize(
+            &mut serde_json::Deserializer::from_str(&serialized)
+        ).unwrap();
+        assert_eq!(data, result);
+    }
+    let result = DeserializeSeedMapAsSeq::new(
+        repeat((PhantomData::<i64>, PhantomData::<i64>)),
+        Vec::new(),
+        |mut current, key, value| { current.push((key, value)); current }
+    ).deserialize(&mut serde_json::Deserializer::from_str("[[1,2],[3]]"));
+    assert!(result.is_err());
+}
+
+#[test]
+fn test_limits() {
+    let limits = Limits::UNLIMITED.with_max_map_entries(2).with_max_str_len(3);
+    for data in testdata() {
+        let serialized = postcard::to_allocvec(&SerializableMap::new_with_len(data.iter(), data.len())).unwrap();
+        let result = DeserializeSeedMap::new(
+            repeat((PhantomData::<(i64, i64)>, PhantomData::<String>)),
+            BTreeMap::new(),
+            |mut current, key, value| { current.insert(key, value); current }
+        ).with_limits(limits).deserialize(
+            &mut postcard::Deserializer::from_flavor(postcard::de_flavors::Slice::new(&serialized))
+        );
+        assert_eq!(data.len() <= 2, result.is_ok());
+
+        let serialized = serde_json::to_string(&SerializableMapAsSeq::new(data.iter())).unwrap();
+        let result = DeserializeSeedMapAsSeq::new(
+            repeat((PhantomData::<(i64, i64)>, PhantomData::<String>)),
+            BTreeMap::new(),
+            |mut current, key, value| { current.insert(key, value); current }
+        ).with_limits(limits).deserialize(
+            &mut serde_json::Deserializer::from_str(&serialized)
+        );
+        assert_eq!(data.len() <= 2, result.is_ok());
+    }
+
+    let deserialize = |json: &str| DeserializeSeedMap::new(
+        repeat((DeserializeSeedStringKey::new(PhantomData::<i64>, |key: &str| key.parse::<i64>().map_err(|e| e.to_string())).with_limits(limits), PhantomData::<i64>)),
+        Vec::new(),
+        |mut current, key, value| { current.push((key, value)); current }
+    ).with_limits(limits).deserialize(&mut serde_json::Deserializer::from_str(json));
+    assert!(deserialize(r#"{"100":1,"-5":2}"#).is_ok());
+    assert!(deserialize(r#"{"1000":1}"#).is_err());
+    assert!(deserialize(r#"{"1":1,"2":2,"3":3}"#).is_err());
+}
+
+#[test]
+fn test_boxed() {
+    let limits = Limits::UNLIMITED.with_max_map_entries(2);
+    for data in testdata() {
+        let serialized = serde_json::to_string(&serializable_testdata(&data)).unwrap();
+        let parse_key = |key: &str| {
+            let (a, b) = key.split_once(',').ok_or("expected two integers separated by ,")?;
+            Ok((a.parse::<i64>().map_err(|e| e.to_string())?, b.parse::<i64>().map_err(|e| e.to_string())?))
+        };
+        let seed: BoxedDeserializeSeedMap<_, _, _, (i64, i64), String> = DeserializeSeedMap::new(
+            repeat((DeserializeSeedStringKey::new(PhantomData::<(i64, i64)>, parse_key), PhantomData::<String>)),
+            BTreeMap::new(),
+            |mut current, key, value| { current.insert(key, value); current }
+        ).with_limits(limits).boxed();
+        let result = seed.deserialize(&mut serde_json::Deserializer::from_str(&serialized));
+        assert_eq!(data.len() <= 2, result.is_ok());
+        if let Ok(result) = result {
+            assert_eq!(data, result);
+        }
+    }
+
+    let data = BTreeMap::from([((1, 2), "a".to_owned())]);
+    let serialized = postcard::to_allocvec(&SerializableMapAsSeq::new_with_len(data.iter(), data.len())).unwrap();
+    let seed = DeserializeSeedMapAsSeq::new(
+        repeat((PhantomData::<(i64, i64)>, PhantomData::<String>)),
+        BTreeMap::new(),
+        |mut current, key, value| { current.insert(key, value); current }
+    ).boxed();
+    assert_eq!(data, seed.deserialize(&mut postcard::Deserializer::from_bytes(&serialized)).unwrap());
+}
+
+#[test]
+fn test_try_new_with_len() {
+    let data = [(1, 2), (3, 4)];
+    assert!(SerializableMap::try_new_with_len(data.iter().copied(), 2).is_ok());
+    let error = SerializableMap::try_new_with_len(data.iter().copied(), 3).err().unwrap();
+    assert_eq!(3, error.requested_len());
+    assert_eq!((2, Some(2)), error.size_hint());
+    assert!(SerializableMap::try_new_with_len(data.iter().copied(), 1).is_err());
+    assert!(SerializableMapAsSeq::try_new_with_len(data.iter().copied(), 2).is_ok());
+    assert!(SerializableMapAsSeq::try_new_with_len(data.iter().copied(), 3).is_err());
+    assert!(SerializableMapAsSeq::try_new_with_len(data.iter().copied().filter(|(k, _)| *k > 1), 1).is_ok());
+}
@@ -0,0 +1,264 @@
+use std::marker::PhantomData;
+
+use serde::de::{DeserializeSeed, Error, IgnoredAny, SeqAccess, Visitor};
+use serde::ser::{Serialize, SerializeTuple, Serializer};
+use serde::Deserializer;
+
+use crate::seq::SerializableSeq;
+
+///
+/// Wraps an [`Iterator`] over the entries of a matrix in row-major order, and implements
+/// [`Serialize`] by mapping it to the tuple `(rows, cols, data)` in the serde data model,
+/// where `data` is a sequence of `rows * cols` entries.
+///
+pub struct SerializableMatrix<I>
+    where I: Iterator + Clone
+{
+    rows: usize,
+    cols: usize,
+    data: I
+}
+
+impl<I> SerializableMatrix<I>
+    where I: Iterator + Clone
+{
+    ///
+    /// Creates a new [`SerializableMatrix`] for a `rows x cols` matrix whose entries
+    /// are given by `data` in row-major order.
+    ///
+    pub const fn new(rows: usize, cols: usize, data: I) -> Self {
+        Self { rows, cols, data }
+    }
+}
+
+impl<I> Serialize for SerializableMatrix<I>
+    where I: Iterator + Clone,
+        I::Item: Serialize
+{
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+        where S: Serializer
+    {
+        let len = self.rows.checked_mul(self.cols).ok_or_else(|| <S::Error as serde::ser::Error>::custom(format!("matrix dimensions {}x{} overflow usize", self.rows, self.cols)))?;
+        let mut tuple = serializer.serialize_tuple(3)?;
+        tuple.serialize_element(&self.rows)?;
+        tuple.serialize_element(&self.cols)?;
+        tuple.serialize_element(&SerializableSeq::new_with_len(self.data.clone(), len))?;
+        return tuple.end();
+    }
+}
+
+///
+/// A [`DeserializeSeed`] that deserializes a matrix that was serialized using
+/// [`SerializableMatrix`]. The entry at position `(i, j)` is deserialized using
+/// the [`DeserializeSeed`] returned by `element_seed(i, j)`, and the entries are
+/// collected into a [`Vec`] in row-major order, whose capacity is allocated in
+/// advance. Finally, the result is constructed by calling `finish(rows, cols, data)`.
+///
+/// # Example
+/// ```
+/// # use feanor_serde::matrix::*;
+/// # use feanor_serde::default_seed::seed_of;
+/// # use serde::de::DeserializeSeed;
+/// let mut deserializer = serde_json::Deserializer::new(serde_json::de::StrRead::new("[2, 3, [1, 2, 3, 4, 5, 6]]"));
+/// let deserialize_seed = DeserializeSeedMatrix::new(
+///     |_i, _j| seed_of::<i64>(),
+///     |rows, cols, data| (rows, cols, data)
+/// );
+/// assert_eq!((2, 3, vec![1, 2, 3, 4, 5, 6]), deserialize_seed.deserialize(&mut deserializer).unwrap());
+/// ```
+///
+pub struct DeserializeSeedMatrix<F, S, G, T>
+    where F: FnMut(usize, usize) -> S
+{
+    result: PhantomData<T>,
+    element_seed: F,
+    finish: G
+}
+
+impl<'de, F, S, G, T> DeserializeSeedMatrix<F, S, G, T>
+    where F: FnMut(usize, usize) -> S,
+        S: DeserializeSeed<'de>,
+        G: FnOnce(usize, usize, Vec<S::Value>) -> T
+{
+    pub const fn new(element_seed: F, finish: G) -> Self {
+        Self { result: PhantomData, element_seed, finish }
+    }
+}
+
+struct DeserializeSeedEntries<'a, 'de, F, S>
+    where F: FnMut(usize, usize) -> S,
+        S: DeserializeSeed<'de>
+{
+    deserializer: PhantomData<&'de ()>,
+    rows: usize,
+    cols: usize,
+    element_seed: &'a mut F
+}
+
+impl<'a, 'de, F, S> Visitor<'de> for DeserializeSeedEntries<'a, 'de, F, S>
+    where F: FnMut(usize, usize) -> S,
+        S: DeserializeSeed<'de>
+{
+    type Value = Vec<S::Value>;
+
+    fn expecting(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "a sequence of {}x{} matrix entries", self.rows, self.cols)
+    }
+
+    fn visit_seq<A>(self, mut seq: A) -> Result<Self::Value, A::Error>
+        where A: SeqAccess<'de>
+    {
+        // the product has already been checked for overflow
+        let len = self.rows * self.cols;
+        let mut result = Vec::new();
+        result.try_reserve_exact(len).map_err(|_| <A::Error as Error>::custom(format!("failed to allocate storage for {}x{} matrix", self.rows, self.cols)))?;
+        for i in 0..self.rows {
+            for j in 0..self.cols {
+                match seq.next_element_seed((self.element_seed)(i, j))? {
+                    Some(entry) => result.push(entry),
+                    None => return Err(<A::Error as Error>::invalid_length(result.len(), &self))
+                }
+            }
+        }
+        if seq.next_element::<IgnoredAny>()?.is_some() {
+            return Err(<A::Error as Error>::invalid_length(len + 1, &self));
+        }
+        return Ok(result);
+    }
+}
+
+impl<'a, 'de, F, S> DeserializeSeed<'de> for DeserializeSeedEntries<'a, 'de, F, S>
+    where F: FnMut(usize, usize) -> S,
+        S: DeserializeSeed<'de>
+{
+    type Value = Vec<S::Value>;
+
+    fn deserialize<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+        where D: Deserializer<'de>
+    {
+        return deserializer.deserialize_seq(self);
+    }
+}
+
+impl<'de, F, S, G, T> DeserializeSeed<'de> for DeserializeSeedMatrix<F, S, G, T>
+    where F: FnMut(usize, usize) -> S,
+        S: DeserializeSeed<'de>,
+        G: FnOnce(usize, usize, Vec<S::Value>) -> T
+{
+    type Value = T;
+
+    fn deserialize<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+        where D: Deserializer<'de>
+    {
+        struct ResultVisitor<'de, F, S, G, T>
+            where F: FnMut(usize, usize) -> S,
+                S: DeserializeSeed<'de>,
+                G: FnOnce(usize, usize, Vec<S::Value>) -> T
+        {
+            deserializer: PhantomData<&'de ()>,
+            element_seed: F,
+            finish: G
+        }
+
+        impl<'de, F, S, G, T> Visitor<'de> for ResultVisitor<'de, F, S, G, T>
+            where F: FnMut(usize, usize) -> S,
+                S: DeserializeSeed<'de>,
+                G: FnOnce(usize, usize, Vec<S::Value>) -> T
+        {
+            type Value = T;
+
+            fn expecting(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+                write!(f, "a tuple (rows, cols, data)")
+            }
+
+            fn visit_seq<A>(mut self, mut seq: A) -> Result<Self::Value, A::Error>
+                where A: SeqAccess<'de>
+            {
+                let rows: usize = seq.next_element()?.ok_or_else(|| <A::Error as Error>::invalid_length(0, &self))?;
+                let cols: usize = seq.next_element()?.ok_or_else(|| <A::Error as Error>::invalid_length(1, &self))?;
+                if rows.checked_mul(cols).is_none() {
+                    return Err(<A::Error as Error>::custom(format!("matrix dimensions {}x{} overflow usize", rows, cols)));
+                }
+                let data = seq.next_element_seed(DeserializeSeedEntries {
+                    deserializer: PhantomData,
+                    rows: rows,
+                    cols: cols,
+                    element_seed: &mut self.element_seed
+                })?.ok_or_else(|| <A::Error as Error>::invalid_length(2, &self))?;
+                return Ok((self.finish)(rows, cols, data));
+            }
+        }
+
+        return deserializer.deserialize_tuple(3, ResultVisitor {
+            deserializer: PhantomData,
+            element_seed: self.element_seed,
+            finish: self.finish
+        });
+    }
+}
+
+#[cfg(test)]
+fn testdata() -> Vec<(usize, usize, Vec<i64>)> {
+    vec![
+        (0, 0, vec![]),
+        (0, 3, vec![]),
+        (1, 1, vec![1]),
+        (2, 3, vec![1, 2, 3, 4, 5, 6]),
+        (3, 2, vec![1, 2, 3, 4, 5, 6])
+    ]
+}
+
+#[test]
+fn test_serde_postcard() {
+    for (rows, cols, data) in testdata() {
+        let serialized = postcard::to_allocvec(&SerializableMatrix::new(rows, cols, data.iter())).unwrap();
+        let result = DeserializeSeedMatrix::new(|_, _| PhantomData::<i64>, |rows, cols, data| (rows, cols, data)).deserialize(
+            &mut postcard::Deserializer::from_flavor(postcard::de_flavors::Slice::new(&serialized))
+        ).unwrap();
+        assert_eq!((rows, cols, data), result);
+    }
+}
+
+#[test]
+fn test_serde_json() {
+    for (rows, cols, data) in testdata() {
+        let serialized = serde_json::to_string(&SerializableMatrix::new(rows, cols, data.iter())).unwrap();
+        let result = DeserializeSeedMatrix::new(|_, _| PhantomData::<i64>, |rows, cols, data| (rows, cols, data)).deserialize(
+            &mut serde_json::Deserializer::from_str(&serialized)
+        ).unwrap();
+        assert_eq!((rows, cols, data), result);
+    }
+}
+
+#[test]
+fn test_element_seed_position() {
+    struct DeserializeSeedOffset(i64);
+
+    impl<'de> DeserializeSeed<'de> for DeserializeSeedOffset {
+        type Value = i64;
+
+        fn deserialize<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+            where D: Deserializer<'de>
+        {
+            Ok(<i64 as serde::Deserialize>::deserialize(deserializer)? + self.0)
+        }
+    }
+
+    let result = DeserializeSeedMatrix::new(|i, j| DeserializeSeedOffset(10 * i as i64 + j as i64), |_, _, data| data).deserialize(
+        &mut serde_json::Deserializer::from_str("[2, 2, [0, 0, 0, 0]]")
+    ).unwrap();
+    assert_eq!(vec![0, 1, 10, 11], result);
+}
+
+#[test]
+fn test_invalid_dimensions() {
+    let deserialize = |json: &str| DeserializeSeedMatrix::new(|_, _| PhantomData::<i64>, |rows, cols, data| (rows, cols, data)).deserialize(
+        &mut serde_json::Deserializer::from_str(json)
+    );
+    assert!(deserialize("[2, 2, [1, 2, 3]]").is_err());
+    assert!(deserialize("[2, 2, [1, 2, 3, 4, 5]]").is_err());
+    assert!(deserialize(&format!("[{}, 3, []]", usize::MAX)).is_err());
+    assert!(deserialize("[2, 2]").is_err());
+
+    assert!(serde_json::to_string(&SerializableMatrix::new(usize::MAX, 2, [0].iter())).is_err());
+}
@@ -0,0 +1,439 @@
+use std::cell::Cell;
+
+use serde::de::{DeserializeSeed, EnumAccess, Error, MapAccess, SeqAccess, VariantAccess, Visitor};
+use serde::Deserializer;
+
+use crate::into_seed::IntoSeed;
+
+///
+/// Statistics about the data deserialized by all [`MeteredSeed`]s that refer to it.
+///
+/// The counters are never reset, so a single [`Metrics`] can accumulate statistics
+/// over many deserializations, e.g. of all records of a file.
+///
+#[derive(Debug, Default)]
+pub struct Metrics {
+    elements: Cell<usize>,
+    map_entries: Cell<usize>,
+    string_bytes: Cell<usize>,
+    byte_buf_bytes: Cell<usize>,
+    current_depth: Cell<usize>,
+    max_depth: Cell<usize>
+}
+
+impl Metrics {
+
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    ///
+    /// Returns the number of elements of sequences (including tuples and structs in formats
+    /// that serialize them as sequences) deserialized so far.
+    ///
+    pub fn elements(&self) -> usize {
+        self.elements.get()
+    }
+
+    ///
+    /// Returns the number of entries of maps (including structs in formats that serialize
+    /// them as maps) deserialized so far.
+    ///
+    pub fn map_entries(&self) -> usize {
+        self.map_entries.get()
+    }
+
+    ///
+    /// Returns the total length in bytes of all strings deserialized so far, including
+    /// identifiers like field names.
+    ///
+    pub fn string_bytes(&self) -> usize {
+        self.string_bytes.get()
+    }
+
+    ///
+    /// Returns the total length of all byte buffers deserialized so far.
+    ///
+    pub fn byte_buf_bytes(&self) -> usize {
+        self.byte_buf_bytes.get()
+    }
+
+    ///
+    /// Returns the maximal nesting depth of sequences, maps and enums encountered so far.
+    /// A single integer has depth 0, and `[[1], []]` has depth 2.
+    ///
+    pub fn max_depth(&self) -> usize {
+        self.max_depth.get()
+    }
+
+    fn add(counter: &Cell<usize>, value: usize) {
+        counter.set(counter.get().saturating_add(value));
+    }
+
+    fn nested<T, G>(&self, f: G) -> T
+        where G: FnOnce() -> T
+    {
+        let depth = self.current_depth.get() + 1;
+        self.current_depth.set(depth);
+        self.max_depth.set(self.max_depth.get().max(depth));
+        let result = f();
+        self.current_depth.set(depth - 1);
+        return result;
+    }
+}
+
+///
+/// Wraps a [`DeserializeSeed`], and deserializes in the same way, but records statistics
+/// about the deserialized data in the associated [`Metrics`].
+///
+/// This works by wrapping the deserializer, so it does not require any cooperation of the
+/// [`DeserializeSeed`], and also counts the data of nested values.
+///
+/// # Example
+/// ```
+/// # use feanor_serde::metrics::*;
+/// # use feanor_serde::default_seed::seed_of;
+/// # use serde::de::DeserializeSeed;
+/// # use std::collections::BTreeMap;
+/// let metrics = Metrics::new();
+/// for json in [r#"{"ab": [1, 2], "c": [3]}"#, r#"{"d": []}"#] {
+///     let seed = MeteredSeed::new(seed_of::<BTreeMap<String, Vec<i64>>>(), &metrics);
+///     seed.deserialize(&mut serde_json::Deserializer::from_str(json)).unwrap();
+/// }
+/// assert_eq!(3, metrics.map_entries());
+/// assert_eq!(3, metrics.elements());
+/// assert_eq!(4, metrics.string_bytes());
+/// assert_eq!(2, metrics.max_depth());
+/// ```
+///
+pub struct MeteredSeed<'a, S> {
+    seed: S,
+    metrics: &'a Metrics
+}
+
+impl<'a, S> MeteredSeed<'a, S> {
+
+    pub fn new<'de, I, M>(seed: I, metrics: &'a Metrics) -> Self
+        where I: IntoSeed<'de, M, Seed = S>
+    {
+        Self { seed: seed.into_seed(), metrics }
+    }
+}
+
+impl<'a, 'de, S> DeserializeSeed<'de> for MeteredSeed<'a, S>
+    where S: DeserializeSeed<'de>
+{
+    type Value = S::Value;
+
+    fn deserialize<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+        where D: Deserializer<'de>
+    {
+        self.seed.deserialize(MeteredDeserializer { de: deserializer, metrics: self.metrics })
+    }
+}
+
+struct MeteredDeserializer<'a, D> {
+    de: D,
+    metrics: &'a Metrics
+}
+
+macro_rules! forward_deserialize {
+    ($($method:ident($($arg:ident: $type:ty),*)),*) => {
+        $(
+            fn $method<V>(self, $($arg: $type,)* visitor: V) -> Result<V::Value, Self::Error>
+                where V: Visitor<'de>
+            {
+                self.de.$method($($arg,)* Metered { inner: visitor, metrics: self.metrics })
+            }
+        )*
+    };
+}
+
+impl<'a, 'de, D> Deserializer<'de> for MeteredDeserializer<'a, D>
+    where D: Deserializer<'de>
+{
+    type Error = D::Error;
+
+    forward_deserialize!{
+        deserialize_any(), deserialize_bool(), deserialize_i8(), deserialize_i16(), deserialize_i32(), deserialize_i64(), deserialize_i128(),
+        deserialize_u8(), deserialize_u16(), deserialize_u32(), deserialize_u64(), deserialize_u128(), deserialize_f32(), deserialize_f64(),
+        deserialize_char(), deserialize_str(), deserialize_string(), deserialize_bytes(), deserialize_byte_buf(), deserialize_option(),
+        deserialize_unit(), deserialize_seq(), deserialize_map(), deserialize_identifier(), deserialize_ignored_any(),
+        deserialize_unit_struct(name: &'static str), deserialize_newtype_struct(name: &'static str), deserialize_tuple(len: usize),
+        deserialize_tuple_struct(name: &'static str, len: usize), deserialize_struct(name: &'static str, fields: &'static [&'static str]),
+        deserialize_enum(name: &'static str, variants: &'static [&'static str])
+    }
+
+    fn is_human_readable(&self) -> bool {
+        self.de.is_human_readable()
+    }
+}
+
+///
+/// Wraps a [`DeserializeSeed`], a [`Visitor`] resp. one of the accessors, in order to
+/// record statistics.
+///
+struct Metered<'a, X> {
+    inner: X,
+    metrics: &'a Metrics
+}
+
+impl<'a, 'de, S> DeserializeSeed<'de> for Metered<'a, S>
+    where S: DeserializeSeed<'de>
+{
+    type Value = S::Value;
+
+    fn deserialize<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+        where D: Deserializer<'de>
+    {
+        self.inner.deserialize(MeteredDeserializer { de: deserializer, metrics: self.metrics })
+    }
+}
+
+macro_rules! forward_visit {
+    ($($method:ident($type:ty)),*) => {
+        $(
+            fn $method<E>(self, value: $type) -> Result<Self::Value, E>
+                where E: Error
+            {
+                self.inner.$method(value)
+            }
+        )*
+    };
+}
+
+impl<'a, 'de, X> Visitor<'de> for Metered<'a, X>
+    where X: Visitor<'de>
+{
+    type Value = X::Value;
+
+    fn expecting(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        self.inner.expecting(f)
+    }
+
+    forward_visit!{
+        visit_bool(bool), visit_i8(i8), visit_i16(i16), visit_i32(i32), visit_i64(i64), visit_i128(i128),
+        visit_u8(u8), visit_u16(u16), visit_u32(u32), visit_u64(u64), visit_u128(u128), visit_f32(f32), visit_f64(f64),
+        visit_char(char)
+    }
+
+    fn visit_str<E>(self, value: &str) -> Result<Self::Value, E>
+        where E: Error
+    {
+        Metrics::add(&self.metrics.string_bytes, value.len());
+        self.inner.visit_str(value)
+    }
+
+    fn visit_borrowed_str<E>(self, value: &'de str) -> Result<Self::Value, E>
+        where E: Error
+    {
+        Metrics::add(&self.metrics.string_bytes, value.len());
+        self.inner.visit_borrowed_str(value)
+    }
+
+    fn visit_string<E>(self, value: String) -> Result<Self::Value, E>
+        where E: Error
+    {
+        Metrics::add(&self.metrics.string_bytes, value.len());
+        self.inner.visit_string(value)
+    }
+
+    fn visit_bytes<E>(self, value: &[u8]) -> Result<Self::Value, E>
+        where E: Error
+    {
+        Metrics::add(&self.metrics.byte_buf_bytes, value.len());
+        self.inner.visit_bytes(value)
+    }
+
+    fn visit_borrowed_bytes<E>(self, value: &'de [u8]) -> Result<Self::Value, E>
+        where E: Error
+    {
+        Metrics::add(&self.metrics.byte_buf_bytes, value.len());
+        self.inner.visit_borrowed_bytes(value)
+    }
+
+    fn visit_byte_buf<E>(self, value: Vec<u8>) -> Result<Self::Value, E>
+        where E: Error
+    {
+        Metrics::add(&self.metrics.byte_buf_bytes, value.len());
+        self.inner.visit_byte_buf(value)
+    }
+
+    fn visit_none<E>(self) -> Result<Self::Value, E>
+        where E: Error
+    {
+        self.inner.visit_none()
+    }
+
+    fn visit_unit<E>(self) -> Result<Self::Value, E>
+        where E: Error
+    {
+        self.inner.visit_unit()
+    }
+
+    fn visit_some<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+        where D: Deserializer<'de>
+    {
+        self.inner.visit_some(MeteredDeserializer { de: deserializer, metrics: self.metrics })
+    }
+
+    fn visit_newtype_struct<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+        where D: Deserializer<'de>
+    {
+        self.inner.visit_newtype_struct(MeteredDeserializer { de: deserializer, metrics: self.metrics })
+    }
+
+    fn visit_seq<A>(self, seq: A) -> Result<Self::Value, A::Error>
+        where A: SeqAccess<'de>
+    {
+        let metrics = self.metrics;
+        metrics.nested(|| self.inner.visit_seq(Metered { inner: seq, metrics: metrics }))
+    }
+
+    fn visit_map<A>(self, map: A) -> Result<Self::Value, A::Error>
+        where A: MapAccess<'de>
+    {
+        let metrics = self.metrics;
+        metrics.nested(|| self.inner.visit_map(Metered { inner: map, metrics: metrics }))
+    }
+
+    fn visit_enum<A>(self, data: A) -> Result<Self::Value, A::Error>
+        where A: EnumAccess<'de>
+    {
+        let metrics = self.metrics;
+        metrics.nested(|| self.inner.visit_enum(Metered { inner: data, metrics: metrics }))
+    }
+}
+
+impl<'a, 'de, X> SeqAccess<'de> for Metered<'a, X>
+    where X: SeqAccess<'de>
+{
+    type Error = X::Error;
+
+    fn next_element_seed<T>(&mut self, seed: T) -> Result<Option<T::Value>, Self::Error>
+        where T: DeserializeSeed<'de>
+    {
+        let result = self.inner.next_element_seed(Metered { inner: seed, metrics: self.metrics })?;
+        if result.is_some() {
+            Metrics::add(&self.metrics.elements, 1);
+        }
+        return Ok(result);
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        self.inner.size_hint()
+    }
+}
+
+impl<'a, 'de, X> MapAccess<'de> for Metered<'a, X>
+    where X: MapAccess<'de>
+{
+    type Error = X::Error;
+
+    fn next_key_seed<K>(&mut self, seed: K) -> Result<Option<K::Value>, Self::Error>
+        where K: DeserializeSeed<'de>
+    {
+        let result = self.inner.next_key_seed(Metered { inner: seed, metrics: self.metrics })?;
+        if result.is_some() {
+            Metrics::add(&self.metrics.map_entries, 1);
+        }
+        return Ok(result);
+    }
+
+    fn next_value_seed<V>(&mut self, seed: V) -> Result<V::Value, Self::Error>
+        where V: DeserializeSeed<'de>
+    {
+        self.inner.next_value_seed(Metered { inner: seed, metrics: self.metrics })
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        self.inner.size_hint()
+    }
+}
+
+impl<'a, 'de, X> EnumAccess<'de> for Metered<'a, X>
+    where X: EnumAccess<'de>
+{
+    type Error = X::Error;
+    type Variant = Metered<'a, X::Variant>;
+
+    fn variant_seed<V>(self, seed: V) -> Result<(V::Value, Self::Variant), Self::Error>
+        where V: DeserializeSeed<'de>
+    {
+        let metrics = self.metrics;
+        let (value, access) = self.inner.variant_seed(Metered { inner: seed, metrics: metrics })?;
+        return Ok((value, Metered { inner: access, metrics: metrics }));
+    }
+}
+
+impl<'a, 'de, X> VariantAccess<'de> for Metered<'a, X>
+    where X: VariantAccess<'de>
+{
+    type Error = X::Error;
+
+    fn unit_variant(self) -> Result<(), Self::Error> {
+        self.inner.unit_variant()
+    }
+
+    fn newtype_variant_seed<T>(self, seed: T) -> Result<T::Value, Self::Error>
+        where T: DeserializeSeed<'de>
+    {
+        self.inner.newtype_variant_seed(Metered { inner: seed, metrics: self.metrics })
+    }
+
+    fn tuple_variant<V>(self, len: usize, visitor: V) -> Result<V::Value, Self::Error>
+        where V: Visitor<'de>
+    {
+        self.inner.tuple_variant(len, Metered { inner: visitor, metrics: self.metrics })
+    }
+
+    fn struct_variant<V>(self, fields: &'static [&'static str], visitor: V) -> Result<V::Value, Self::Error>
+        where V: Visitor<'de>
+    {
+        self.inner.struct_variant(fields, Metered { inner: visitor, metrics: self.metrics })
+    }
+}
+
+#[cfg(test)]
+use std::marker::PhantomData;
+
+#[cfg(test)]
+#[derive(serde::Deserialize, serde::Serialize, Debug, PartialEq)]
+enum Record {
+    A(i64),
+    B { name: String, values: Vec<Vec<u8>> }
+}
+
+#[test]
+fn test_serde_json() {
+    let metrics = Metrics::new();
+    let result = MeteredSeed::new(PhantomData::<Vec<Record>>, &metrics).deserialize(&mut serde_json::Deserializer::from_str(r#"[{"A": 5}, {"B": {"name": "xyz", "values": [[1, 2], []]}}]"#)).unwrap();
+    assert_eq!(2, result.len());
+    // the elements of the outer list, of the list of lists, and of the inner lists
+    assert_eq!(2 + 2 + 2, metrics.elements());
+    assert_eq!(2, metrics.map_entries());
+    // the variant names, the field names and the string "xyz"
+    assert_eq!(1 + 1 + 4 + 6 + 3, metrics.string_bytes());
+    assert_eq!(0, metrics.byte_buf_bytes());
+    // list > enum > struct > list > list
+    assert_eq!(5, metrics.max_depth());
+}
+
+#[test]
+fn test_serde_postcard() {
+    let data = vec![Record::A(5), Record::B { name: "xyz".to_owned(), values: vec![vec![1, 2], vec![]] }];
+    let serialized = postcard::to_allocvec(&data).unwrap();
+    let metrics = Metrics::new();
+    assert_eq!(data, MeteredSeed::new(PhantomData::<Vec<Record>>, &metrics).deserialize(&mut postcard::Deserializer::from_bytes(&serialized)).unwrap());
+    // postcard serializes the struct variant as a sequence of its two fields
+    assert_eq!(2 + 2 + 2 + 2, metrics.elements());
+    assert_eq!(0, metrics.map_entries());
+    assert_eq!(3, metrics.string_bytes());
+    assert_eq!(5, metrics.max_depth());
+
+    let serialized = postcard::to_allocvec(&crate::raw_bytes::Bytes(&[1, 2, 3])).unwrap();
+    let metrics = Metrics::new();
+    MeteredSeed::new(crate::raw_bytes::ByteBufSeed, &metrics).deserialize(&mut postcard::Deserializer::from_bytes(&serialized)).unwrap();
+    assert_eq!(3, metrics.byte_buf_bytes());
+    assert_eq!(0, metrics.max_depth());
+}
@@ -0,0 +1,1173 @@
+use std::fmt::Display;
+use std::marker::PhantomData;
+
+use serde::de::value::{MapDeserializer, SeqDeserializer};
+use serde::de::{DeserializeSeed, EnumAccess, Error, IntoDeserializer, VariantAccess, Visitor};
+use serde::ser::{Serialize, SerializeMap, SerializeSeq, SerializeStruct, SerializeStructVariant, SerializeTuple, SerializeTupleStruct, SerializeTupleVariant, Serializer};
+use serde::{forward_to_deserialize_any, Deserializer};
+
+///
+/// An in-memory value of the serde data model, built from explicit instructions, that can be
+/// deserialized using [`MockDeserializer`].
+///
+/// In contrast to real formats, this allows to produce input that well-behaved serializers never
+/// produce, e.g. maps with duplicate keys, struct fields in unexpected order, or fields and enum
+/// variants identified by their index instead of their name. This is useful to test the behavior
+/// of [`DeserializeSeed`]s on such edge cases.
+///
+/// The deserializer is self-describing, i.e. all values are passed to the visitor as they are,
+/// independent of the method of [`Deserializer`] that was called. The only exceptions are
+/// [`Deserializer::deserialize_option()`], which treats values other than [`MockValue::None`]
+/// and [`MockValue::Some`] as present, and [`Deserializer::deserialize_enum()`], which treats
+/// strings and integers as unit variants.
+///
+/// # Example
+/// ```
+/// # use feanor_serde::impl_deserialize_seed_for_dependent_struct;
+/// # use feanor_serde::mock::*;
+/// # use serde::de::DeserializeSeed;
+/// # use feanor_serde::default_seed::seed_of;
+/// struct PointDeserializeSeed;
+/// impl_deserialize_seed_for_dependent_struct!{
+///     pub struct Point<'de> using PointDeserializeSeed {
+///         x: i64: |_| seed_of::<i64>(),
+///         y: i64: |_| seed_of::<i64>()
+///     }
+/// }
+/// // fields out of order, and `x` identified by its index
+/// let input = MockValue::map([(MockValue::from("y"), MockValue::from(2i64)), (MockValue::from(0u64), MockValue::from(1i64))]);
+/// let point = PointDeserializeSeed.deserialize(MockDeserializer::new(input)).unwrap();
+/// assert_eq!(1, point.x);
+/// assert_eq!(2, point.y);
+///
+/// // duplicate field
+/// let input = MockValue::map([("x", 1i64), ("x", 1i64), ("y", 2i64)]);
+/// assert!(PointDeserializeSeed.deserialize(MockDeserializer::new(input)).is_err());
+/// ```
+///
+#[derive(Clone, Debug, PartialEq)]
+pub enum MockValue {
+    Bool(bool),
+    I64(i64),
+    U64(u64),
+    F64(f64),
+    Char(char),
+    Str(String),
+    Bytes(Vec<u8>),
+    Unit,
+    None,
+    Some(Box<MockValue>),
+    NewtypeStruct(Box<MockValue>),
+    Seq(Vec<MockValue>),
+    Map(Vec<(MockValue, MockValue)>),
+    Enum(Box<MockValue>, MockVariant)
+}
+
+///
+/// The content of a [`MockValue::Enum`].
+///
+#[derive(Clone, Debug, PartialEq)]
+pub enum MockVariant {
+    Unit,
+    Newtype(Box<MockValue>),
+    Tuple(Vec<MockValue>),
+    Struct(Vec<(MockValue, MockValue)>)
+}
+
+impl MockValue {
+
+    pub fn bytes<I>(data: I) -> Self
+        where I: IntoIterator<Item = u8>
+    {
+        MockValue::Bytes(data.into_iter().collect())
+    }
+
+    pub fn some<T>(value: T) -> Self
+        where T: Into<MockValue>
+    {
+        MockValue::Some(Box::new(value.into()))
+    }
+
+    pub fn newtype_struct<T>(value: T) -> Self
+        where T: Into<MockValue>
+    {
+        MockValue::NewtypeStruct(Box::new(value.into()))
+    }
+
+    pub fn seq<I>(elements: I) -> Self
+        where I: IntoIterator,
+            I::Item: Into<MockValue>
+    {
+        MockValue::Seq(elements.into_iter().map(Into::into).collect())
+    }
+
+    ///
+    /// Creates a map with the given entries, in the given order. Keys may be duplicated.
+    /// This is also used for structs, where the keys are the field identifiers, which can
+    /// either be strings or integers.
+    ///
+    pub fn map<I, K, V>(entries: I) -> Self
+        where I: IntoIterator<Item = (K, V)>,
+            K: Into<MockValue>,
+            V: Into<MockValue>
+    {
+        MockValue::Map(entries.into_iter().map(|(k, v)| (k.into(), v.into())).collect())
+    }
+
+    ///
+    /// Creates an enum value with the given variant. The variant identifier can either be
+    /// a string or an integer.
+    ///
+    pub fn unit_variant<I>(variant: I) -> Self
+        where I: Into<MockValue>
+    {
+        MockValue::Enum(Box::new(variant.into()), MockVariant::Unit)
+    }
+
+    pub fn newtype_variant<I, T>(variant: I, value: T) -> Self
+        where I: Into<MockValue>,
+            T: Into<MockValue>
+    {
+        MockValue::Enum(Box::new(variant.into()), MockVariant::Newtype(Box::new(value.into())))
+    }
+
+    pub fn tuple_variant<I, J>(variant: I, elements: J) -> Self
+        where I: Into<MockValue>,
+            J: IntoIterator,
+            J::Item: Into<MockValue>
+    {
+        MockValue::Enum(Box::new(variant.into()), MockVariant::Tuple(elements.into_iter().map(Into::into).collect()))
+    }
+
+    pub fn struct_variant<I, J, K, V>(variant: I, fields: J) -> Self
+        where I: Into<MockValue>,
+            J: IntoIterator<Item = (K, V)>,
+            K: Into<MockValue>,
+            V: Into<MockValue>
+    {
+        MockValue::Enum(Box::new(variant.into()), MockVariant::Struct(fields.into_iter().map(|(k, v)| (k.into(), v.into())).collect()))
+    }
+
+    ///
+    /// Returns all values that are obtained from this one by a single "near-valid" modification,
+    /// as produced by sloppy or adversarial serializers. In particular, this includes
+    ///  - removing, duplicating or swapping adjacent entries of maps, and removing or duplicating
+    ///    the last element of sequences,
+    ///  - replacing string identifiers (i.e. map keys and enum variants) by the corresponding
+    ///    bytes, and map keys by their position within the map (which is the field index if
+    ///    this represents a struct with fields in declaration order),
+    ///  - replacing enum variants by an unknown one,
+    ///  - applying any of the above to a nested value.
+    ///
+    /// This is meant to drive [`DeserializeSeed`]s over inputs that exercise code paths
+    /// (e.g. numeric or bytes identifiers) that common formats never reach. Note that many of
+    /// the returned values are still valid inputs for the same seed.
+    ///
+    /// # Example
+    /// ```
+    /// # use feanor_serde::impl_deserialize_seed_for_dependent_struct;
+    /// # use feanor_serde::mock::*;
+    /// # use serde::de::DeserializeSeed;
+    /// # use feanor_serde::default_seed::seed_of;
+    /// struct PointDeserializeSeed;
+    /// impl_deserialize_seed_for_dependent_struct!{
+    ///     pub struct Point<'de> using PointDeserializeSeed {
+    ///         x: i64: |_| seed_of::<i64>(),
+    ///         y: i64: |_| seed_of::<i64>()
+    ///     }
+    /// }
+    /// let input = MockValue::map([("x", 1i64), ("y", 2i64)]);
+    /// for mutated in input.mutations() {
+    ///     if let Ok(point) = PointDeserializeSeed.deserialize(MockDeserializer::new(mutated)) {
+    ///         assert_eq!((1, 2), (point.x, point.y));
+    ///     }
+    /// }
+    /// ```
+    ///
+    pub fn mutations(&self) -> Vec<MockValue> {
+        let mut result = Vec::new();
+        match self {
+            MockValue::Some(value) => result.extend(value.mutations().into_iter().map(MockValue::some)),
+            MockValue::NewtypeStruct(value) => result.extend(value.mutations().into_iter().map(MockValue::newtype_struct)),
+            MockValue::Seq(elements) => mutate_seq(elements, MockValue::Seq, &mut result),
+            MockValue::Map(entries) => mutate_map(entries, MockValue::Map, &mut result),
+            MockValue::Enum(variant, content) => {
+                let with_variant = |variant: MockValue| MockValue::Enum(Box::new(variant), content.clone());
+                if let MockValue::Str(name) = &**variant {
+                    result.push(with_variant(MockValue::bytes(name.bytes())));
+                }
+                result.push(with_variant(MockValue::Str("<unknown variant>".to_owned())));
+                result.push(with_variant(MockValue::U64(u32::MAX as u64)));
+                match content {
+                    MockVariant::Unit => {},
+                    MockVariant::Newtype(value) => result.extend(value.mutations().into_iter().map(|value| MockValue::Enum(variant.clone(), MockVariant::Newtype(Box::new(value))))),
+                    MockVariant::Tuple(elements) => mutate_seq(elements, |elements| MockValue::Enum(variant.clone(), MockVariant::Tuple(elements)), &mut result),
+                    MockVariant::Struct(entries) => mutate_map(entries, |entries| MockValue::Enum(variant.clone(), MockVariant::Struct(entries)), &mut result)
+                }
+            },
+            _ => {}
+        }
+        return result;
+    }
+}
+
+fn mutate_seq<F>(elements: &[MockValue], mut create: F, result: &mut Vec<MockValue>)
+    where F: FnMut(Vec<MockValue>) -> MockValue
+{
+    if let Some(last) = elements.last() {
+        result.push(create(elements[..(elements.len() - 1)].to_vec()));
+        result.push(create(elements.iter().chain([last]).cloned().collect()));
+    }
+    for i in 0..elements.len() {
+        for mutated in elements[i].mutations() {
+            let mut elements = elements.to_vec();
+            elements[i] = mutated;
+            result.push(create(elements));
+        }
+    }
+}
+
+fn mutate_map<F>(entries: &[(MockValue, MockValue)], mut create: F, result: &mut Vec<MockValue>)
+    where F: FnMut(Vec<(MockValue, MockValue)>) -> MockValue
+{
+    for i in 0..entries.len() {
+        let mut removed = entries.to_vec();
+        removed.remove(i);
+        result.push(create(removed));
+
+        let mut duplicated = entries.to_vec();
+        duplicated.insert(i, entries[i].clone());
+        result.push(create(duplicated));
+
+        if i + 1 < entries.len() {
+            let mut swapped = entries.to_vec();
+            swapped.swap(i, i + 1);
+            result.push(create(swapped));
+        }
+
+        let mut replaced_keys = vec![MockValue::U64(i as u64)];
+        if let MockValue::Str(key) = &entries[i].0 {
+            replaced_keys.push(MockValue::bytes(key.bytes()));
+        }
+        replaced_keys.extend(entries[i].0.mutations());
+        for key in replaced_keys {
+            let mut entries = entries.to_vec();
+            entries[i].0 = key;
+            result.push(create(entries));
+        }
+        for value in entries[i].1.mutations() {
+            let mut entries = entries.to_vec();
+            entries[i].1 = value;
+            result.push(create(entries));
+        }
+    }
+}
+
+macro_rules! impl_from_for_mock_value {
+    ($($from:ty => $variant:ident as $as:ty),*) => {
+        $(
+            impl From<$from> for MockValue {
+
+                fn from(value: $from) -> Self {
+                    MockValue::$variant(value as $as)
+                }
+            }
+        )*
+    };
+}
+
+impl_from_for_mock_value!{
+    i8 => I64 as i64, i16 => I64 as i64, i32 => I64 as i64, i64 => I64 as i64,
+    u8 => U64 as u64, u16 => U64 as u64, u32 => U64 as u64, u64 => U64 as u64,
+    f32 => F64 as f64, f64 => F64 as f64
+}
+
+impl From<bool> for MockValue {
+
+    fn from(value: bool) -> Self {
+        MockValue::Bool(value)
+    }
+}
+
+impl From<char> for MockValue {
+
+    fn from(value: char) -> Self {
+        MockValue::Char(value)
+    }
+}
+
+impl From<&str> for MockValue {
+
+    fn from(value: &str) -> Self {
+        MockValue::Str(value.to_owned())
+    }
+}
+
+impl From<String> for MockValue {
+
+    fn from(value: String) -> Self {
+        MockValue::Str(value)
+    }
+}
+
+impl From<()> for MockValue {
+
+    fn from(_: ()) -> Self {
+        MockValue::Unit
+    }
+}
+
+impl<T> From<Option<T>> for MockValue
+    where T: Into<MockValue>
+{
+    fn from(value: Option<T>) -> Self {
+        match value {
+            None => MockValue::None,
+            Some(value) => MockValue::some(value)
+        }
+    }
+}
+
+///
+/// A [`Serializer`] that produces the [`MockValue`] corresponding to the serialized data.
+/// This can be used to create [`MockValue`]s from Rust values, or to inspect and compare
+/// serialized data independent of a concrete format, see also [`crate::diff`].
+///
+/// Since [`MockValue`] does not store names of structs and enums, they are dropped, and
+/// structs are serialized as maps with string keys. Integers are widened to 64 bits, and
+/// 128-bit integers are only supported if they fit into 64 bits.
+///
+/// # Example
+/// ```
+/// # use feanor_serde::mock::*;
+/// # use serde::Serialize;
+/// assert_eq!(MockValue::seq([MockValue::from(1i64), MockValue::from("a")]), MockValue::from_serialize(&(1i32, "a")).unwrap());
+/// ```
+///
+#[derive(Clone, Copy, Debug)]
+pub struct MockSerializer {
+    is_human_readable: bool
+}
+
+impl MockSerializer {
+
+    pub fn new() -> Self {
+        Self { is_human_readable: true }
+    }
+
+    pub fn with_human_readable(self, is_human_readable: bool) -> Self {
+        Self { is_human_readable: is_human_readable }
+    }
+}
+
+impl Default for MockSerializer {
+
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl MockValue {
+
+    ///
+    /// Serializes the given value using a [`MockSerializer`].
+    ///
+    pub fn from_serialize<T>(value: &T) -> Result<MockValue, serde::de::value::Error>
+        where T: ?Sized + Serialize
+    {
+        value.serialize(MockSerializer::new())
+    }
+}
+
+impl Serializer for MockSerializer {
+    type Ok = MockValue;
+    type Error = serde::de::value::Error;
+    type SerializeSeq = SerializeMockSeq;
+    type SerializeTuple = SerializeMockSeq;
+    type SerializeTupleStruct = SerializeMockSeq;
+    type SerializeTupleVariant = SerializeMockSeq;
+    type SerializeMap = SerializeMockMap;
+    type SerializeStruct = SerializeMockMap;
+    type SerializeStructVariant = SerializeMockMap;
+
+    fn serialize_bool(self, v: bool) -> Result<MockValue, Self::Error> { Ok(MockValue::from(v)) }
+    fn serialize_i8(self, v: i8) -> Result<MockValue, Self::Error> { Ok(MockValue::from(v)) }
+    fn serialize_i16(self, v: i16) -> Result<MockValue, Self::Error> { Ok(MockValue::from(v)) }
+    fn serialize_i32(self, v: i32) -> Result<MockValue, Self::Error> { Ok(MockValue::from(v)) }
+    fn serialize_i64(self, v: i64) -> Result<MockValue, Self::Error> { Ok(MockValue::from(v)) }
+    fn serialize_u8(self, v: u8) -> Result<MockValue, Self::Error> { Ok(MockValue::from(v)) }
+    fn serialize_u16(self, v: u16) -> Result<MockValue, Self::Error> { Ok(MockValue::from(v)) }
+    fn serialize_u32(self, v: u32) -> Result<MockValue, Self::Error> { Ok(MockValue::from(v)) }
+    fn serialize_u64(self, v: u64) -> Result<MockValue, Self::Error> { Ok(MockValue::from(v)) }
+    fn serialize_f32(self, v: f32) -> Result<MockValue, Self::Error> { Ok(MockValue::from(v)) }
+    fn serialize_f64(self, v: f64) -> Result<MockValue, Self::Error> { Ok(MockValue::from(v)) }
+    fn serialize_char(self, v: char) -> Result<MockValue, Self::Error> { Ok(MockValue::from(v)) }
+    fn serialize_str(self, v: &str) -> Result<MockValue, Self::Error> { Ok(MockValue::from(v)) }
+    fn serialize_bytes(self, v: &[u8]) -> Result<MockValue, Self::Error> { Ok(MockValue::bytes(v.iter().copied())) }
+    fn serialize_none(self) -> Result<MockValue, Self::Error> { Ok(MockValue::None) }
+    fn serialize_unit(self) -> Result<MockValue, Self::Error> { Ok(MockValue::Unit) }
+    fn serialize_unit_struct(self, _name: &'static str) -> Result<MockValue, Self::Error> { Ok(MockValue::Unit) }
+
+    fn serialize_i128(self, v: i128) -> Result<MockValue, Self::Error> {
+        if let Ok(v) = i64::try_from(v) {
+            Ok(MockValue::I64(v))
+        } else if let Ok(v) = u64::try_from(v) {
+            Ok(MockValue::U64(v))
+        } else {
+            Err(serde::ser::Error::custom(format!("integer {} does not fit into 64 bits", v)))
+        }
+    }
+
+    fn serialize_u128(self, v: u128) -> Result<MockValue, Self::Error> {
+        match u64::try_from(v) {
+            Ok(v) => Ok(MockValue::U64(v)),
+            Err(_) => Err(serde::ser::Error::custom(format!("integer {} does not fit into 64 bits", v)))
+        }
+    }
+
+    fn serialize_some<T>(self, value: &T) -> Result<MockValue, Self::Error>
+        where T: ?Sized + Serialize
+    {
+        Ok(MockValue::Some(Box::new(value.serialize(self)?)))
+    }
+
+    fn serialize_unit_variant(self, _name: &'static str, _variant_index: u32, variant: &'static str) -> Result<MockValue, Self::Error> {
+        Ok(MockValue::unit_variant(variant))
+    }
+
+    fn serialize_newtype_struct<T>(self, _name: &'static str, value: &T) -> Result<MockValue, Self::Error>
+        where T: ?Sized + Serialize
+    {
+        Ok(MockValue::NewtypeStruct(Box::new(value.serialize(self)?)))
+    }
+
+    fn serialize_newtype_variant<T>(self, _name: &'static str, _variant_index: u32, variant: &'static str, value: &T) -> Result<MockValue, Self::Error>
+        where T: ?Sized + Serialize
+    {
+        Ok(MockValue::Enum(Box::new(MockValue::from(variant)), MockVariant::Newtype(Box::new(value.serialize(self)?))))
+    }
+
+    fn serialize_seq(self, len: Option<usize>) -> Result<SerializeMockSeq, Self::Error> {
+        Ok(SerializeMockSeq { serializer: self, variant: None, elements: Vec::with_capacity(len.unwrap_or(0)) })
+    }
+
+    fn serialize_tuple(self, len: usize) -> Result<SerializeMockSeq, Self::Error> {
+        self.serialize_seq(Some(len))
+    }
+
+    fn serialize_tuple_struct(self, _name: &'static str, len: usize) -> Result<SerializeMockSeq, Self::Error> {
+        self.serialize_seq(Some(len))
+    }
+
+    fn serialize_tuple_variant(self, _name: &'static str, _variant_index: u32, variant: &'static str, len: usize) -> Result<SerializeMockSeq, Self::Error> {
+        Ok(SerializeMockSeq { serializer: self, variant: Some(variant), elements: Vec::with_capacity(len) })
+    }
+
+    fn serialize_map(self, len: Option<usize>) -> Result<SerializeMockMap, Self::Error> {
+        Ok(SerializeMockMap { serializer: self, variant: None, entries: Vec::with_capacity(len.unwrap_or(0)), next_key: None })
+    }
+
+    fn serialize_struct(self, _name: &'static str, len: usize) -> Result<SerializeMockMap, Self::Error> {
+        self.serialize_map(Some(len))
+    }
+
+    fn serialize_struct_variant(self, _name: &'static str, _variant_index: u32, variant: &'static str, len: usize) -> Result<SerializeMockMap, Self::Error> {
+        Ok(SerializeMockMap { serializer: self, variant: Some(variant), entries: Vec::with_capacity(len), next_key: None })
+    }
+
+    fn is_human_readable(&self) -> bool {
+        self.is_human_readable
+    }
+}
+
+///
+/// The [`SerializeSeq`] of [`MockSerializer`], also used for tuples.
+///
+pub struct SerializeMockSeq {
+    serializer: MockSerializer,
+    variant: Option<&'static str>,
+    elements: Vec<MockValue>
+}
+
+impl SerializeSeq for SerializeMockSeq {
+    type Ok = MockValue;
+    type Error = serde::de::value::Error;
+
+    fn serialize_element<T>(&mut self, value: &T) -> Result<(), Self::Error>
+        where T: ?Sized + Serialize
+    {
+        self.elements.push(value.serialize(self.serializer)?);
+        return Ok(());
+    }
+
+    fn end(self) -> Result<MockValue, Self::Error> {
+        match self.variant {
+            Some(variant) => Ok(MockValue::tuple_variant(variant, self.elements)),
+            None => Ok(MockValue::Seq(self.elements))
+        }
+    }
+}
+
+impl SerializeTuple for SerializeMockSeq {
+    type Ok = MockValue;
+    type Error = serde::de::value::Error;
+
+    fn serialize_element<T>(&mut self, value: &T) -> Result<(), Self::Error>
+        where T: ?Sized + Serialize
+    {
+        SerializeSeq::serialize_element(self, value)
+    }
+
+    fn end(self) -> Result<MockValue, Self::Error> {
+        SerializeSeq::end(self)
+    }
+}
+
+impl SerializeTupleStruct for SerializeMockSeq {
+    type Ok = MockValue;
+    type Error = serde::de::value::Error;
+
+    fn serialize_field<T>(&mut self, value: &T) -> Result<(), Self::Error>
+        where T: ?Sized + Serialize
+    {
+        SerializeSeq::serialize_element(self, value)
+    }
+
+    fn end(self) -> Result<MockValue, Self::Error> {
+        SerializeSeq::end(self)
+    }
+}
+
+impl SerializeTupleVariant for SerializeMockSeq {
+    type Ok = MockValue;
+    type Error = serde::de::value::Error;
+
+    fn serialize_field<T>(&mut self, value: &T) -> Result<(), Self::Error>
+        where T: ?Sized + Serialize
+    {
+        SerializeSeq::serialize_element(self, value)
+    }
+
+    fn end(self) -> Result<MockValue, Self::Error> {
+        SerializeSeq::end(self)
+    }
+}
+
+///
+/// The [`SerializeMap`] of [`MockSerializer`], also used for structs.
+///
+pub struct SerializeMockMap {
+    serializer: MockSerializer,
+    variant: Option<&'static str>,
+    entries: Vec<(MockValue, MockValue)>,
+    next_key: Option<MockValue>
+}
+
+impl SerializeMap for SerializeMockMap {
+    type Ok = MockValue;
+    type Error = serde::de::value::Error;
+
+    fn serialize_key<T>(&mut self, key: &T) -> Result<(), Self::Error>
+        where T: ?Sized + Serialize
+    {
+        self.next_key = Some(key.serialize(self.serializer)?);
+        return Ok(());
+    }
+
+    fn serialize_value<T>(&mut self, value: &T) -> Result<(), Self::Error>
+        where T: ?Sized + Serialize
+    {
+        let key = self.next_key.take().ok_or_else(|| serde::ser::Error::custom("serialize_value() called before serialize_key()"))?;
+        self.entries.push((key, value.serialize(self.serializer)?));
+        return Ok(());
+    }
+
+    fn end(self) -> Result<MockValue, Self::Error> {
+        match self.variant {
+            Some(variant) => Ok(MockValue::struct_variant(variant, self.entries)),
+            None => Ok(MockValue::Map(self.entries))
+        }
+    }
+}
+
+impl SerializeStruct for SerializeMockMap {
+    type Ok = MockValue;
+    type Error = serde::de::value::Error;
+
+    fn serialize_field<T>(&mut self, key: &'static str, value: &T) -> Result<(), Self::Error>
+        where T: ?Sized + Serialize
+    {
+        self.entries.push((MockValue::from(key), value.serialize(self.serializer)?));
+        return Ok(());
+    }
+
+    fn end(self) -> Result<MockValue, Self::Error> {
+        SerializeMap::end(self)
+    }
+}
+
+impl SerializeStructVariant for SerializeMockMap {
+    type Ok = MockValue;
+    type Error = serde::de::value::Error;
+
+    fn serialize_field<T>(&mut self, key: &'static str, value: &T) -> Result<(), Self::Error>
+        where T: ?Sized + Serialize
+    {
+        SerializeStruct::serialize_field(self, key, value)
+    }
+
+    fn end(self) -> Result<MockValue, Self::Error> {
+        SerializeMap::end(self)
+    }
+}
+
+impl Display for MockValue {
+
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        fn write_seq(f: &mut std::fmt::Formatter<'_>, elements: &[MockValue]) -> std::fmt::Result {
+            write!(f, "[")?;
+            for (i, element) in elements.iter().enumerate() {
+                if i > 0 {
+                    write!(f, ", ")?;
+                }
+                write!(f, "{}", element)?;
+            }
+            return write!(f, "]");
+        }
+        fn write_map(f: &mut std::fmt::Formatter<'_>, entries: &[(MockValue, MockValue)]) -> std::fmt::Result {
+            write!(f, "{{")?;
+            for (i, (key, value)) in entries.iter().enumerate() {
+                if i > 0 {
+                    write!(f, ",")?;
+                }
+                write!(f, " {}: {}", key, value)?;
+            }
+            return write!(f, " }}");
+        }
+        // variant names are written without quotes, e.g. `A(1)` instead of `"A"(1)`
+        let variant_name = |variant: &MockValue| match variant {
+            MockValue::Str(name) => name.clone(),
+            variant => variant.to_string()
+        };
+        match self {
+            MockValue::Bool(value) => write!(f, "{}", value),
+            MockValue::I64(value) => write!(f, "{}", value),
+            MockValue::U64(value) => write!(f, "{}", value),
+            MockValue::F64(value) => write!(f, "{:?}", value),
+            MockValue::Char(value) => write!(f, "{:?}", value),
+            MockValue::Str(value) => write!(f, "{:?}", value),
+            MockValue::Bytes(value) => write!(f, "b\"{}\"", value.escape_ascii()),
+            MockValue::Unit => write!(f, "()"),
+            MockValue::None => write!(f, "None"),
+            MockValue::Some(value) => write!(f, "Some({})", value),
+            MockValue::NewtypeStruct(value) => write!(f, "({})", value),
+            MockValue::Seq(elements) => write_seq(f, elements),
+            MockValue::Map(entries) => write_map(f, entries),
+            MockValue::Enum(variant, MockVariant::Unit) => write!(f, "{}", variant_name(variant)),
+            MockValue::Enum(variant, MockVariant::Newtype(value)) => write!(f, "{}({})", variant_name(variant), value),
+            MockValue::Enum(variant, MockVariant::Tuple(elements)) => {
+                write!(f, "{}", variant_name(variant))?;
+                write_seq(f, elements)
+            },
+            MockValue::Enum(variant, MockVariant::Struct(entries)) => {
+                write!(f, "{} ", variant_name(variant))?;
+                write_map(f, entries)
+            }
+        }
+    }
+}
+
+impl MockValue {
+
+    ///
+    /// Returns a wrapper whose [`Display`] implementation prints this value in an indented
+    /// multi-line format, meant for test failure messages and debug logs. By default, values
+    /// are annotated with their type and byte strings longer than 32 bytes are truncated.
+    ///
+    /// # Example
+    /// ```
+    /// # use feanor_serde::mock::*;
+    /// let value = MockValue::map([("a", MockValue::seq([1i64, 2])), ("b", MockValue::bytes([0; 100]))]);
+    /// assert_eq!(r#"map(2) {
+    ///   "a": str => seq(2) [
+    ///     1: i64,
+    ///     2: i64
+    ///   ],
+    ///   "b": str => b"\x00\x00\x00\x00"...: bytes(100)
+    /// }"#, value.pretty().with_max_bytes(4).to_string());
+    /// ```
+    ///
+    pub fn pretty(&self) -> PrettyMockValue<'_> {
+        PrettyMockValue { value: self, max_bytes: 32, annotate_types: true }
+    }
+}
+
+///
+/// Pretty-printing wrapper around a [`MockValue`], see [`MockValue::pretty()`].
+///
+#[derive(Clone, Copy)]
+pub struct PrettyMockValue<'a> {
+    value: &'a MockValue,
+    max_bytes: usize,
+    annotate_types: bool
+}
+
+impl<'a> PrettyMockValue<'a> {
+
+    ///
+    /// Sets the number of bytes after which byte strings are truncated.
+    ///
+    pub fn with_max_bytes(self, max_bytes: usize) -> Self {
+        Self { max_bytes: max_bytes, ..self }
+    }
+
+    pub fn with_type_annotations(self, annotate_types: bool) -> Self {
+        Self { annotate_types: annotate_types, ..self }
+    }
+
+    fn write(&self, f: &mut std::fmt::Formatter<'_>, value: &MockValue, depth: usize) -> std::fmt::Result {
+        let annotated = |f: &mut std::fmt::Formatter<'_>, value: &dyn Display, ty: &str| if self.annotate_types {
+            write!(f, "{}: {}", value, ty)
+        } else {
+            write!(f, "{}", value)
+        };
+        match value {
+            MockValue::Bool(value) => annotated(f, value, "bool"),
+            MockValue::I64(value) => annotated(f, value, "i64"),
+            MockValue::U64(value) => annotated(f, value, "u64"),
+            MockValue::F64(value) => annotated(f, &format!("{:?}", value), "f64"),
+            MockValue::Char(value) => annotated(f, &format!("{:?}", value), "char"),
+            MockValue::Str(value) => annotated(f, &format!("{:?}", value), "str"),
+            MockValue::Bytes(value) if value.len() > self.max_bytes => annotated(f, &format!("b\"{}\"...", value[..self.max_bytes].escape_ascii()), &format!("bytes({})", value.len())),
+            MockValue::Bytes(value) => annotated(f, &format!("b\"{}\"", value.escape_ascii()), &format!("bytes({})", value.len())),
+            MockValue::Unit => write!(f, "()"),
+            MockValue::None => write!(f, "None"),
+            MockValue::Some(value) => {
+                write!(f, "Some(")?;
+                self.write(f, value, depth)?;
+                write!(f, ")")
+            },
+            MockValue::NewtypeStruct(value) => {
+                write!(f, "newtype(")?;
+                self.write(f, value, depth)?;
+                write!(f, ")")
+            },
+            MockValue::Seq(elements) => {
+                if self.annotate_types {
+                    write!(f, "seq({}) ", elements.len())?;
+                }
+                self.write_seq(f, elements, depth)
+            },
+            MockValue::Map(entries) => {
+                if self.annotate_types {
+                    write!(f, "map({}) ", entries.len())?;
+                }
+                self.write_map(f, entries, depth)
+            },
+            MockValue::Enum(variant, content) => {
+                match &**variant {
+                    MockValue::Str(name) => write!(f, "{}", name)?,
+                    variant => write!(f, "{}", variant)?
+                }
+                match content {
+                    MockVariant::Unit => Ok(()),
+                    MockVariant::Newtype(value) => {
+                        write!(f, "(")?;
+                        self.write(f, value, depth)?;
+                        write!(f, ")")
+                    },
+                    MockVariant::Tuple(elements) => {
+                        write!(f, " ")?;
+                        self.write_seq(f, elements, depth)
+                    },
+                    MockVariant::Struct(entries) => {
+                        write!(f, " ")?;
+                        self.write_map(f, entries, depth)
+                    }
+                }
+            }
+        }
+    }
+
+    fn write_seq(&self, f: &mut std::fmt::Formatter<'_>, elements: &[MockValue], depth: usize) -> std::fmt::Result {
+        if elements.is_empty() {
+            return write!(f, "[]");
+        }
+        write!(f, "[")?;
+        for (i, element) in elements.iter().enumerate() {
+            write!(f, "{}\n{:indent$}", if i == 0 { "" } else { "," }, "", indent = 2 * (depth + 1))?;
+            self.write(f, element, depth + 1)?;
+        }
+        return write!(f, "\n{:indent$}]", "", indent = 2 * depth);
+    }
+
+    fn write_map(&self, f: &mut std::fmt::Formatter<'_>, entries: &[(MockValue, MockValue)], depth: usize) -> std::fmt::Result {
+        if entries.is_empty() {
+            return write!(f, "{{}}");
+        }
+        write!(f, "{{")?;
+        for (i, (key, value)) in entries.iter().enumerate() {
+            write!(f, "{}\n{:indent$}", if i == 0 { "" } else { "," }, "", indent = 2 * (depth + 1))?;
+            self.write(f, key, depth + 1)?;
+            write!(f, " => ")?;
+            self.write(f, value, depth + 1)?;
+        }
+        return write!(f, "\n{:indent$}}}", "", indent = 2 * depth);
+    }
+}
+
+impl<'a> Display for PrettyMockValue<'a> {
+
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.write(f, self.value, 0)
+    }
+}
+
+impl<'de, E> IntoDeserializer<'de, E> for MockValue
+    where E: Error
+{
+    type Deserializer = MockDeserializer<E>;
+
+    fn into_deserializer(self) -> Self::Deserializer {
+        MockDeserializer::create(self)
+    }
+}
+
+///
+/// A self-describing [`Deserializer`] that produces a given [`MockValue`], see there for details.
+///
+/// By default, the deserializer uses [`serde::de::value::Error`], but it can produce any
+/// error type, e.g. to test the error messages of a [`DeserializeSeed`] as they would be
+/// formatted by a specific format.
+///
+pub struct MockDeserializer<E = serde::de::value::Error> {
+    value: MockValue,
+    is_human_readable: bool,
+    error: PhantomData<E>
+}
+
+impl MockDeserializer {
+
+    pub fn new(value: MockValue) -> Self {
+        Self::create(value)
+    }
+}
+
+impl<E> MockDeserializer<E> {
+
+    fn create(value: MockValue) -> Self {
+        Self { value: value, is_human_readable: true, error: PhantomData }
+    }
+
+    ///
+    /// Changes the error type produced by this deserializer.
+    ///
+    pub fn with_error_type<F>(self) -> MockDeserializer<F> {
+        MockDeserializer { value: self.value, is_human_readable: self.is_human_readable, error: PhantomData }
+    }
+
+    ///
+    /// Sets the value returned by [`Deserializer::is_human_readable()`], which is `true` by default.
+    /// Note that this only applies to the outermost value, nested values are always deserialized
+    /// as human-readable.
+    ///
+    pub fn with_human_readable(self, is_human_readable: bool) -> Self {
+        Self { is_human_readable: is_human_readable, ..self }
+    }
+}
+
+fn visit_seq<'de, V, E>(elements: Vec<MockValue>, visitor: V) -> Result<V::Value, E>
+    where V: Visitor<'de>,
+        E: Error
+{
+    let mut seq = SeqDeserializer::new(elements.into_iter());
+    let result = visitor.visit_seq(&mut seq)?;
+    seq.end()?;
+    return Ok(result);
+}
+
+fn visit_map<'de, V, E>(entries: Vec<(MockValue, MockValue)>, visitor: V) -> Result<V::Value, E>
+    where V: Visitor<'de>,
+        E: Error
+{
+    let mut map = MapDeserializer::new(entries.into_iter());
+    let result = visitor.visit_map(&mut map)?;
+    map.end()?;
+    return Ok(result);
+}
+
+impl<'de, E> Deserializer<'de> for MockDeserializer<E>
+    where E: Error
+{
+    type Error = E;
+
+    fn deserialize_any<V>(self, visitor: V) -> Result<V::Value, Self::Error>
+        where V: Visitor<'de>
+    {
+        match self.value {
+            MockValue::Bool(value) => visitor.visit_bool(value),
+            MockValue::I64(value) => visitor.visit_i64(value),
+            MockValue::U64(value) => visitor.visit_u64(value),
+            MockValue::F64(value) => visitor.visit_f64(value),
+            MockValue::Char(value) => visitor.visit_char(value),
+            MockValue::Str(value) => visitor.visit_string(value),
+            MockValue::Bytes(value) => visitor.visit_byte_buf(value),
+            MockValue::Unit => visitor.visit_unit(),
+            MockValue::None => visitor.visit_none(),
+            MockValue::Some(value) => visitor.visit_some(MockDeserializer::create(*value)),
+            MockValue::NewtypeStruct(value) => visitor.visit_newtype_struct(MockDeserializer::create(*value)),
+            MockValue::Seq(elements) => visit_seq(elements, visitor),
+            MockValue::Map(entries) => visit_map(entries, visitor),
+            MockValue::Enum(variant, content) => visitor.visit_enum(MockEnumAccess { variant: *variant, content: content, error: PhantomData })
+        }
+    }
+
+    fn deserialize_option<V>(self, visitor: V) -> Result<V::Value, Self::Error>
+        where V: Visitor<'de>
+    {
+        match self.value {
+            MockValue::None => visitor.visit_none(),
+            MockValue::Some(value) => visitor.visit_some(MockDeserializer::create(*value)),
+            MockValue::Unit => visitor.visit_unit(),
+            _ => visitor.visit_some(self)
+        }
+    }
+
+    fn deserialize_newtype_struct<V>(self, _name: &'static str, visitor: V) -> Result<V::Value, Self::Error>
+        where V: Visitor<'de>
+    {
+        match self.value {
+            MockValue::NewtypeStruct(value) => visitor.visit_newtype_struct(MockDeserializer::create(*value)),
+            _ => visitor.visit_newtype_struct(self)
+        }
+    }
+
+    fn deserialize_enum<V>(self, _name: &'static str, _variants: &'static [&'static str], visitor: V) -> Result<V::Value, Self::Error>
+        where V: Visitor<'de>
+    {
+        match self.value {
+            variant @ (MockValue::Str(_) | MockValue::U64(_) | MockValue::I64(_)) => visitor.visit_enum(MockEnumAccess { variant: variant, content: MockVariant::Unit, error: PhantomData }),
+            _ => self.deserialize_any(visitor)
+        }
+    }
+
+    fn is_human_readable(&self) -> bool {
+        self.is_human_readable
+    }
+
+    forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string
+        bytes byte_buf unit unit_struct seq tuple
+        tuple_struct map struct identifier ignored_any
+    }
+}
+
+struct MockEnumAccess<E> {
+    variant: MockValue,
+    content: MockVariant,
+    error: PhantomData<E>
+}
+
+impl<'de, E> EnumAccess<'de> for MockEnumAccess<E>
+    where E: Error
+{
+    type Error = E;
+    type Variant = MockVariantAccess<E>;
+
+    fn variant_seed<V>(self, seed: V) -> Result<(V::Value, Self::Variant), Self::Error>
+        where V: DeserializeSeed<'de>
+    {
+        let variant = seed.deserialize(MockDeserializer::create(self.variant))?;
+        return Ok((variant, MockVariantAccess { content: self.content, error: PhantomData }));
+    }
+}
+
+struct MockVariantAccess<E> {
+    content: MockVariant,
+    error: PhantomData<E>
+}
+
+impl<E> MockVariantAccess<E>
+    where E: Error
+{
+    fn unexpected(&self, expected: impl Display) -> E {
+        let found = match &self.content {
+            MockVariant::Unit => "unit variant",
+            MockVariant::Newtype(_) => "newtype variant",
+            MockVariant::Tuple(_) => "tuple variant",
+            MockVariant::Struct(_) => "struct variant"
+        };
+        return E::custom(format!("invalid type: {}, expected {}", found, expected));
+    }
+}
+
+impl<'de, E> VariantAccess<'de> for MockVariantAccess<E>
+    where E: Error
+{
+    type Error = E;
+
+    fn unit_variant(self) -> Result<(), Self::Error> {
+        match self.content {
+            MockVariant::Unit => Ok(()),
+            _ => Err(self.unexpected("unit variant"))
+        }
+    }
+
+    fn newtype_variant_seed<T>(self, seed: T) -> Result<T::Value, Self::Error>
+        where T: DeserializeSeed<'de>
+    {
+        match self.content {
+            MockVariant::Newtype(value) => seed.deserialize(MockDeserializer::create(*value)),
+            _ => Err(self.unexpected("newtype variant"))
+        }
+    }
+
+    fn tuple_variant<V>(self, _len: usize, visitor: V) -> Result<V::Value, Self::Error>
+        where V: Visitor<'de>
+    {
+        match self.content {
+            MockVariant::Tuple(elements) => visit_seq(elements, visitor),
+            _ => Err(self.unexpected("tuple variant"))
+        }
+    }
+
+    fn struct_variant<V>(self, _fields: &'static [&'static str], visitor: V) -> Result<V::Value, Self::Error>
+        where V: Visitor<'de>
+    {
+        match self.content {
+            MockVariant::Struct(entries) => visit_map(entries, visitor),
+            MockVariant::Tuple(elements) => visit_seq(elements, visitor),
+            _ => Err(self.unexpected("struct variant"))
+        }
+    }
+}
+
+#[cfg(test)]
+use std::iter::{repeat, repeat_n};
+#[cfg(test)]
+use crate::map::DeserializeSeedMap;
+#[cfg(test)]
+use crate::seq::DeserializeSeedSeq;
+#[cfg(test)]
+use crate::impl_deserialize_seed_for_dependent_struct;
+#[cfg(test)]
+use crate::impl_deserialize_seed_for_dependent_enum;
+
+#[cfg(test)]
+struct DeserializeSeedRecord;
+
+#[cfg(test)]
+impl_deserialize_seed_for_dependent_struct!{
+    pub struct Record<'de> using DeserializeSeedRecord {
+        a: i64: |_| PhantomData::<i64>,
+        b: String: |_| PhantomData::<String>
+    }
+}
+
+#[test]
+fn test_struct() {
+    let result = DeserializeSeedRecord.deserialize(MockDeserializer::new(MockValue::map([(MockValue::from("b"), MockValue::from("x")), (MockValue::from("a"), MockValue::from(1i64))]))).unwrap();
+    assert_eq!(1, result.a);
+    assert_eq!("x", result.b);
+
+    let result = DeserializeSeedRecord.deserialize(MockDeserializer::new(MockValue::map([(MockValue::from(1u64), MockValue::from("x")), (MockValue::from(0u64), MockValue::from(1i64))]))).unwrap();
+    assert_eq!(1, result.a);
+    assert_eq!("x", result.b);
+
+    let result = DeserializeSeedRecord.deserialize(MockDeserializer::new(MockValue::seq([MockValue::from(1i64), MockValue::from("x")]))).unwrap();
+    assert_eq!(1, result.a);
+    assert_eq!("x", result.b);
+
+    assert!(DeserializeSeedRecord.deserialize(MockDeserializer::new(MockValue::map([(MockValue::from("a"), MockValue::from(1i64)), (MockValue::from("a"), MockValue::from(2i64)), (MockValue::from("b"), MockValue::from("x"))]))).is_err());
+    assert!(DeserializeSeedRecord.deserialize(MockDeserializer::new(MockValue::map([(MockValue::from("a"), MockValue::from(1i64))]))).is_err());
+}
+
+#[test]
+fn test_seq_and_map() {
+    let seed = DeserializeSeedSeq::new(repeat_n(PhantomData::<i64>, 2), Vec::new(), |mut current, next| { current.push(next); current });
+    assert_eq!(vec![1, 2], seed.deserialize(MockDeserializer::new(MockValue::seq([1i64, 2]))).unwrap());
+
+    let seed = DeserializeSeedSeq::new(repeat_n(PhantomData::<i64>, 2), Vec::new(), |mut current, next| { current.push(next); current });
+    assert!(seed.deserialize(MockDeserializer::new(MockValue::seq([1i64, 2, 3]))).is_err());
+
+    let seed = DeserializeSeedMap::new(repeat((PhantomData::<i64>, PhantomData::<i64>)), Vec::new(), |mut current, k, v| { current.push((k, v)); current });
+    assert_eq!(vec![(1, 2), (1, 3)], seed.deserialize(MockDeserializer::new(MockValue::map([(1i64, 2i64), (1, 3)]))).unwrap());
+}
+
+#[cfg(test)]
+struct DeserializeSeedShape;
+
+#[cfg(test)]
+impl_deserialize_seed_for_dependent_enum!{
+    pub enum Shape<'de> using DeserializeSeedShape {
+        Circle(f64): |_| PhantomData::<f64>,
+        Point(()): |_| PhantomData::<()>
+    }
+}
+
+#[test]
+fn test_enum() {
+    assert!(matches!(DeserializeSeedShape.deserialize(MockDeserializer::new(MockValue::newtype_variant("Circle", 1.5))), Ok(Shape::Circle((r, _))) if r == 1.5));
+    assert!(matches!(DeserializeSeedShape.deserialize(MockDeserializer::new(MockValue::newtype_variant(0u64, 1.5))), Ok(Shape::Circle((r, _))) if r == 1.5));
+    assert!(DeserializeSeedShape.deserialize(MockDeserializer::new(MockValue::newtype_variant(2u64, 1.5))).is_err());
+    assert!(DeserializeSeedShape.deserialize(MockDeserializer::new(MockValue::unit_variant("Circle"))).is_err());
+}
+
+#[test]
+fn test_option_and_human_readable() {
+    assert_eq!(Some(1), PhantomData::<Option<i64>>.deserialize(MockDeserializer::new(MockValue::from(1i64))).unwrap());
+    assert_eq!(None, PhantomData::<Option<i64>>.deserialize(MockDeserializer::new(MockValue::from(None::<i64>))).unwrap());
+    assert!(!MockDeserializer::new(MockValue::Unit).with_human_readable(false).is_human_readable());
+}
+
+#[test]
+fn test_mutations() {
+    let input = MockValue::map([("a", MockValue::seq([1i64])), ("b", MockValue::unit_variant("X"))]);
+    let mutations = input.mutations();
+    assert!(mutations.contains(&MockValue::map([("b", MockValue::unit_variant("X")), ("a", MockValue::seq([1i64]))])));
+    assert!(mutations.contains(&MockValue::map([("a", MockValue::seq([1i64])), ("a", MockValue::seq([1i64])), ("b", MockValue::unit_variant("X"))])));
+    assert!(mutations.contains(&MockValue::map([(MockValue::from("a"), MockValue::seq([1i64])), (MockValue::from(1u64), MockValue::unit_variant("X"))])));
+    assert!(mutations.contains(&MockValue::map([(MockValue::bytes(*b"a"), MockValue::seq([1i64])), (MockValue::from("b"), MockValue::unit_variant("X"))])));
+    assert!(mutations.contains(&MockValue::map([("a", MockValue::seq([1i64, 1])), ("b", MockValue::unit_variant("X"))])));
+    assert!(mutations.contains(&MockValue::map([("a", MockValue::seq([1i64])), ("b", MockValue::unit_variant(MockValue::bytes(*b"X")))])));
+    assert!(!mutations.contains(&input));
+    assert!(MockValue::from(1i64).mutations().is_empty());
+}
+
+#[test]
+fn test_serialize() {
+    #[derive(serde::Serialize)]
+    enum Foo {
+        A { x: i32 },
+        B(u8, u8)
+    }
+    let value = (Some(Foo::A { x: -1 }), [Foo::B(1, 2)], 5u128);
+    let expected = MockValue::seq([
+        MockValue::some(MockValue::struct_variant("A", [("x", -1i64)])),
+        MockValue::seq([MockValue::tuple_variant("B", [1u64, 2])]),
+        MockValue::from(5u64)
+    ]);
+    assert_eq!(expected, MockValue::from_serialize(&value).unwrap());
+    assert_eq!(r#"[Some(A { "x": -1 }), [B[1, 2]], 5]"#, expected.to_string());
+    assert!(MockValue::from_serialize(&u128::MAX).is_err());
+}
+
+#[test]
+fn test_pretty() {
+    let value = MockValue::seq([MockValue::some(MockValue::struct_variant("A", [("x", -1i64)])), MockValue::unit_variant("B"), MockValue::Seq(Vec::new())]);
+    assert_eq!("seq(3) [
+  Some(A {
+    \"x\": str => -1: i64
+  }),
+  B,
+  seq(0) []
+]", value.pretty().to_string());
+    assert_eq!("[
+  Some(A {
+    \"x\" => -1
+  }),
+  B,
+  []
+]", value.pretty().with_type_annotations(false).to_string());
+    assert_eq!("b\"ab\"...", MockValue::bytes(*b"abc").pretty().with_max_bytes(2).with_type_annotations(false).to_string());
+}
@@ -0,0 +1,249 @@
+use std::marker::PhantomData;
+
+use serde::de::{DeserializeSeed, Error, MapAccess, Unexpected, Visitor};
+use serde::ser::{Serialize, SerializeMap, Serializer};
+use serde::Deserializer;
+
+use crate::into_seed::IntoSeed;
+
+///
+/// Wraps a serializable object, and implements [`Serialize`] by mapping
+/// to the map type in the serde data model, with a single entry whose key
+/// is the given name and whose value is the wrapped object.
+///
+/// This is mainly useful for formats that require a map at the top level
+/// (like TOML), since it allows storing values whose natural serde shape
+/// is a sequence or a scalar, e.g. a [`crate::seq::SerializableSeq`].
+///
+/// As for [`crate::newtype_struct::SerializableNewtypeStruct`], the wrapped object can
+/// be a reference, also to unsized data like `str` or slices.
+///
+#[derive(Clone, Copy, Debug)]
+pub struct SerializableNamedValue<T>
+    where T: Serialize
+{
+    name: &'static str,
+    data: T
+}
+
+impl<T> SerializableNamedValue<T>
+    where T: Serialize
+{
+    pub const fn new(name: &'static str, data: T) -> Self {
+        Self { name, data }
+    }
+}
+
+impl<T> Serialize for SerializableNamedValue<T>
+    where T: Serialize
+{
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+        where S: Serializer
+    {
+        let mut map = serializer.serialize_map(Some(1))?;
+        map.serialize_entry(self.name, &self.data)?;
+        return map.end();
+    }
+}
+
+///
+/// A [`DeserializeSeed`] that deserializes a map with a single entry, whose key
+/// must be the given name, by deserializing the value with the given [`DeserializeSeed`].
+///
+/// This is the counterpart to [`SerializableNamedValue`].
+///
+/// # Example
+/// ```
+/// # use feanor_serde::named_value::*;
+/// # use feanor_serde::seq::*;
+/// # use feanor_serde::default_seed::seed_of;
+/// # use std::iter::repeat;
+/// # use serde::de::DeserializeSeed;
+/// let mut deserializer = serde_json::Deserializer::new(serde_json::de::StrRead::new(r#"{ "data": [1, 3, 5] }"#));
+/// let deserialize_seed = DeserializeSeedNamedValue::new("data", DeserializeSeedSeq::new(
+///     repeat(seed_of::<i64>()),
+///     Vec::new(),
+///     |mut current, next| { current.push(next); current }
+/// ));
+/// assert_eq!(vec![1, 3, 5], deserialize_seed.deserialize(&mut deserializer).unwrap());
+/// ```
+///
+#[derive(Clone, Copy, Debug)]
+pub struct DeserializeSeedNamedValue<S> {
+    name: &'static str,
+    seed: S
+}
+
+impl<'de, S> DeserializeSeedNamedValue<S>
+    where S: DeserializeSeed<'de>
+{
+    pub fn new<I, M>(name: &'static str, seed: I) -> Self
+        where I: IntoSeed<'de, M, Seed = S>
+    {
+        Self { name, seed: seed.into_seed() }
+    }
+
+    ///
+    /// Same as [`DeserializeSeedNamedValue::new()`], but takes the seed directly instead of anything
+    /// implementing [`IntoSeed`], which allows it to be a `const fn`.
+    ///
+    pub const fn from_seed(name: &'static str, seed: S) -> Self {
+        Self { name, seed }
+    }
+}
+
+///
+/// Deserializes using a reference to the wrapped seed, so a configured seed can be reused
+/// without reconstructing it, as long as the wrapped seed supports this.
+///
+impl<'a, 'de, S> DeserializeSeed<'de> for &'a DeserializeSeedNamedValue<S>
+    where &'a S: DeserializeSeed<'de>
+{
+    type Value = <&'a S as DeserializeSeed<'de>>::Value;
+
+    fn deserialize<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+        where D: Deserializer<'de>
+    {
+        DeserializeSeedNamedValue { name: self.name, seed: &self.seed }.deserialize(deserializer)
+    }
+}
+
+impl<'de, S> DeserializeSeed<'de> for DeserializeSeedNamedValue<S>
+    where S: DeserializeSeed<'de>
+{
+    type Value = S::Value;
+
+    fn deserialize<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+        where D: Deserializer<'de>
+    {
+        struct KeyVisitor {
+            name: &'static str
+        }
+
+        impl<'de> Visitor<'de> for KeyVisitor {
+            type Value = ();
+
+            fn expecting(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
+                write!(formatter, "the key {}", self.name)
+            }
+
+            fn visit_str<E>(self, value: &str) -> Result<Self::Value, E>
+                where E: Error
+            {
+                if value == self.name {
+                    return Ok(());
+                } else {
+                    return Err(E::invalid_value(Unexpected::Str(value), &self));
+                }
+            }
+
+            fn visit_bytes<E>(self, value: &[u8]) -> Result<Self::Value, E>
+                where E: Error
+            {
+                if value == self.name.as_bytes() {
+                    return Ok(());
+                } else {
+                    return Err(E::invalid_value(Unexpected::Bytes(value), &self));
+                }
+            }
+        }
+
+        struct KeySeed {
+            name: &'static str
+        }
+
+        impl<'de> DeserializeSeed<'de> for KeySeed {
+            type Value = ();
+
+            fn deserialize<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+                where D: Deserializer<'de>
+            {
+                deserializer.deserialize_str(KeyVisitor { name: self.name })
+            }
+        }
+
+        struct NamedValueVisitor<'de, S: DeserializeSeed<'de>> {
+            seed: S,
+            name: &'static str,
+            deserializer: PhantomData<&'de ()>
+        }
+
+        impl<'de, S: DeserializeSeed<'de>> Visitor<'de> for NamedValueVisitor<'de, S> {
+            type Value = S::Value;
+
+            fn expecting(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
+                write!(formatter, "a map with the single key {}", self.name)
+            }
+
+            fn visit_map<M>(self, mut map: M) -> Result<Self::Value, M::Error>
+                where M: MapAccess<'de>
+            {
+                if map.next_key_seed(KeySeed { name: self.name })?.is_none() {
+                    return Err(<M::Error as Error>::missing_field(self.name));
+                }
+                let result = map.next_value_seed(self.seed)?;
+                if map.next_key_seed(KeySeed { name: self.name })?.is_some() {
+                    return Err(<M::Error as Error>::duplicate_field(self.name));
+                }
+                return Ok(result);
+            }
+        }
+
+        return deserializer.deserialize_map(NamedValueVisitor { seed: self.seed, name: self.name, deserializer: PhantomData });
+    }
+}
+
+#[cfg(test)]
+use crate::seq::{SerializableSeq, DeserializeSeedSeq};
+#[cfg(test)]
+use std::iter::repeat;
+
+#[test]
+fn test_serde_postcard() {
+    let data = vec![1, 3, 4];
+    let serialized = postcard::to_allocvec(&SerializableNamedValue::new("data", SerializableSeq::new_with_len(data.iter().copied(), data.len()))).unwrap();
+    let result = DeserializeSeedNamedValue::new("data", DeserializeSeedSeq::new(repeat(PhantomData::<i64>), Vec::new(), |mut current, next| { current.push(next); current })).deserialize(
+        &mut postcard::Deserializer::from_flavor(postcard::de_flavors::Slice::new(&serialized))
+    ).unwrap();
+    assert_eq!(data, result);
+
+    let result = DeserializeSeedNamedValue::new("other", DeserializeSeedSeq::new(repeat(PhantomData::<i64>), Vec::new(), |mut current, next| { current.push(next); current })).deserialize(
+        &mut postcard::Deserializer::from_flavor(postcard::de_flavors::Slice::new(&serialized))
+    );
+    assert!(result.is_err());
+}
+
+#[test]
+fn test_serde_json() {
+    let data = vec![1, 3, 4];
+    let serialized = serde_json::to_string(&SerializableNamedValue::new("data", SerializableSeq::new(data.iter().copied()))).unwrap();
+    assert_eq!(r#"{"data":[1,3,4]}"#, serialized);
+    let result = DeserializeSeedNamedValue::new("data", DeserializeSeedSeq::new(repeat(PhantomData::<i64>), Vec::new(), |mut current, next| { current.push(next); current })).deserialize(
+        &mut serde_json::Deserializer::from_str(&serialized)
+    ).unwrap();
+    assert_eq!(data, result);
+
+    let result = DeserializeSeedNamedValue::new("other", PhantomData::<i64>).deserialize(
+        &mut serde_json::Deserializer::from_str(r#"{"data":1}"#)
+    );
+    assert!(result.is_err());
+
+    let result = DeserializeSeedNamedValue::new("data", PhantomData::<i64>).deserialize(
+        &mut serde_json::Deserializer::from_str(r#"{"data":1,"data":2}"#)
+    );
+    assert!(result.is_err());
+
+    let result = DeserializeSeedNamedValue::new("data", PhantomData::<i64>).deserialize(
+        &mut serde_json::Deserializer::from_str(r#"{}"#)
+    );
+    assert!(result.is_err());
+}
+
+#[test]
+fn test_serialize_reference() {
+    let data = vec![1i64, 2, 3];
+    let slice: &[i64] = &data;
+    let serialized = postcard::to_allocvec(&SerializableNamedValue::new("data", slice)).unwrap();
+    assert_eq!(postcard::to_allocvec(&SerializableNamedValue::new("data", data.clone())).unwrap(), serialized);
+    assert_eq!("{\"data\":\"abc\"}", serde_json::to_string(&SerializableNamedValue::new("data", "abc")).unwrap());
+}
@@ -0,0 +1,168 @@
+use std::marker::PhantomData;
+
+use serde::de::DeserializeSeed;
+use serde::ser::{Serialize, Serializer};
+use serde::Deserializer;
+
+use ::ndarray::{Array, ArrayBase, Data, Dimension, IxDyn};
+
+use crate::tensor::{DeserializeSeedTensor, SerializableTensor};
+
+///
+/// Wraps an [`ndarray::ArrayBase`] and implements [`Serialize`] by serializing its shape and its
+/// entries in row-major order, as done by [`SerializableTensor`]. The entries are serialized by
+/// mapping them through `wrap`, which allows serializing entries that only implement [`Serialize`]
+/// through a wrapper.
+///
+/// # Example
+/// ```
+/// # use feanor_serde::ndarray::*;
+/// let array = ndarray::array![[1, 2, 3], [4, 5, 6]];
+/// assert_eq!("[2,2,3,[1,2,3,4,5,6]]", serde_json::to_string(&SerializableArray::new(&array, |x| *x)).unwrap());
+/// ```
+///
+pub struct SerializableArray<'a, S, D, F>
+    where S: Data,
+        D: Dimension
+{
+    array: &'a ArrayBase<S, D>,
+    wrap: F
+}
+
+impl<'a, S, D, F, W> SerializableArray<'a, S, D, F>
+    where S: Data,
+        D: Dimension,
+        F: Fn(&'a S::Elem) -> W,
+        W: Serialize
+{
+    pub const fn new(array: &'a ArrayBase<S, D>, wrap: F) -> Self {
+        Self { array: array, wrap: wrap }
+    }
+}
+
+impl<'a, S, D, F, W> Serialize for SerializableArray<'a, S, D, F>
+    where S: Data,
+        D: Dimension,
+        F: Fn(&'a S::Elem) -> W,
+        W: Serialize
+{
+    fn serialize<T>(&self, serializer: T) -> Result<T::Ok, T::Error>
+        where T: Serializer
+    {
+        SerializableTensor::new(self.array.shape(), self.array.iter().map(&self.wrap)).serialize(serializer)
+    }
+}
+
+///
+/// A [`DeserializeSeed`] that deserializes an [`ndarray::Array`] that was serialized using
+/// [`SerializableArray`]. The entry at row-major position `k` is deserialized using the
+/// [`DeserializeSeed`] returned by `element_seed(k)`, which e.g. allows deserializing arrays
+/// of entries that require a context.
+///
+/// The dimension type `D` is preserved, i.e. deserialization fails if the serialized array has
+/// a different number of dimensions than `D`, unless `D` is [`ndarray::IxDyn`].
+///
+/// # Example
+/// ```
+/// # use feanor_serde::ndarray::*;
+/// # use feanor_serde::default_seed::seed_of;
+/// # use serde::de::DeserializeSeed;
+/// let seed = DeserializeSeedArray::<_, ndarray::Ix2>::new(|_k| seed_of::<i64>());
+/// let array = seed.deserialize(&mut serde_json::Deserializer::from_str("[2, 2, 3, [1, 2, 3, 4, 5, 6]]")).unwrap();
+/// assert_eq!(ndarray::array![[1, 2, 3], [4, 5, 6]], array);
+/// ```
+///
+pub struct DeserializeSeedArray<F, D> {
+    dimension: PhantomData<D>,
+    element_seed: F
+}
+
+impl<'de, F, S, D> DeserializeSeedArray<F, D>
+    where F: FnMut(usize) -> S,
+        S: DeserializeSeed<'de>,
+        D: Dimension
+{
+    pub const fn new(element_seed: F) -> Self {
+        Self { dimension: PhantomData, element_seed: element_seed }
+    }
+}
+
+impl<'de, F, S, D> DeserializeSeed<'de> for DeserializeSeedArray<F, D>
+    where F: FnMut(usize) -> S,
+        S: DeserializeSeed<'de>,
+        D: Dimension
+{
+    type Value = Array<S::Value, D>;
+
+    fn deserialize<T>(self, deserializer: T) -> Result<Self::Value, T::Error>
+        where T: Deserializer<'de>
+    {
+        let (shape, data) = DeserializeSeedTensor::new(
+            self.element_seed,
+            |shape: &[usize]| (shape.to_vec(), Vec::with_capacity(shape.iter().product::<usize>().min(4096))),
+            |(shape, mut data), entry| { data.push(entry); (shape, data) }
+        ).deserialize(deserializer)?;
+        if D::NDIM.is_some_and(|ndim| ndim != shape.len()) {
+            return Err(crate::errors::custom("array has the wrong number of dimensions", format_args!("expected an array with {} dimensions, found shape {:?}", D::NDIM.unwrap(), shape)));
+        }
+        let result = Array::from_shape_vec(IxDyn(&shape), data).map_err(|_| crate::errors::custom::<T::Error>("invalid array shape", format_args!("invalid array shape {:?}", shape)))?;
+        return result.into_dimensionality::<D>().map_err(|_| crate::errors::custom("array has the wrong number of dimensions", format_args!("cannot convert array of shape {:?} to the requested dimension", shape)));
+    }
+}
+
+#[cfg(test)]
+use crate::default_seed::seed_of;
+#[cfg(test)]
+use ::ndarray::{array, ArrayD, Ix0, Ix1, Ix2, Ix3};
+
+#[test]
+fn test_serde_json() {
+    let array = array![[1i64, 2, 3], [4, 5, 6]];
+    let serialized = serde_json::to_string(&SerializableArray::new(&array, |x| *x)).unwrap();
+    assert_eq!(array, DeserializeSeedArray::<_, Ix2>::new(|_| seed_of::<i64>()).deserialize(&mut serde_json::Deserializer::from_str(&serialized)).unwrap());
+    let dynamic = DeserializeSeedArray::<_, IxDyn>::new(|_| seed_of::<i64>()).deserialize(&mut serde_json::Deserializer::from_str(&serialized)).unwrap();
+    assert_eq!(array.clone().into_dyn(), dynamic);
+    assert!(DeserializeSeedArray::<_, Ix1>::new(|_| seed_of::<i64>()).deserialize(&mut serde_json::Deserializer::from_str(&serialized)).is_err());
+    assert!(DeserializeSeedArray::<_, Ix3>::new(|_| seed_of::<i64>()).deserialize(&mut serde_json::Deserializer::from_str(&serialized)).is_err());
+
+    let transposed = array.t();
+    let serialized = serde_json::to_string(&SerializableArray::new(&transposed, |x| *x)).unwrap();
+    assert_eq!("[2,3,2,[1,4,2,5,3,6]]", serialized);
+
+    let scalar = ::ndarray::arr0(7i64);
+    let serialized = serde_json::to_string(&SerializableArray::new(&scalar, |x| *x)).unwrap();
+    assert_eq!(scalar, DeserializeSeedArray::<_, Ix0>::new(|_| seed_of::<i64>()).deserialize(&mut serde_json::Deserializer::from_str(&serialized)).unwrap());
+}
+
+#[test]
+fn test_serde_postcard() {
+    let array = ArrayD::from_shape_vec(IxDyn(&[2, 0, 3]), Vec::<String>::new()).unwrap();
+    let serialized = postcard::to_allocvec(&SerializableArray::new(&array, |x| x.as_str())).unwrap();
+    let result = DeserializeSeedArray::<_, IxDyn>::new(|_| seed_of::<String>()).deserialize(&mut postcard::Deserializer::from_bytes(&serialized)).unwrap();
+    assert_eq!(array, result);
+
+    let array = array![["a".to_owned(), "b".to_owned()], ["c".to_owned(), "d".to_owned()]];
+    let serialized = postcard::to_allocvec(&SerializableArray::new(&array, |x| x.as_str())).unwrap();
+    let result = DeserializeSeedArray::<_, Ix2>::new(|_| seed_of::<String>()).deserialize(&mut postcard::Deserializer::from_bytes(&serialized)).unwrap();
+    assert_eq!(array, result);
+}
+
+#[test]
+fn test_element_seed_position() {
+    struct DeserializeSeedOffset(i64);
+
+    impl<'de> DeserializeSeed<'de> for DeserializeSeedOffset {
+        type Value = i64;
+
+        fn deserialize<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+            where D: Deserializer<'de>
+        {
+            Ok(<i64 as serde::Deserialize>::deserialize(deserializer)? + self.0)
+        }
+    }
+
+    let result = DeserializeSeedArray::<_, Ix2>::new(|k| DeserializeSeedOffset(k as i64)).deserialize(
+        &mut serde_json::Deserializer::from_str("[2, 2, 2, [0, 0, 0, 0]]")
+    ).unwrap();
+    assert_eq!(array![[0, 1], [2, 3]], result);
+}
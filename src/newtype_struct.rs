@@ -4,11 +4,27 @@ use serde::de::{DeserializeSeed, Visitor};
 use serde::ser::{Serialize, Serializer};
 use serde::Deserializer;
 
+use crate::into_seed::IntoSeed;
+
 ///
 /// Wraps an serializable object, and implements [`Serialize`] by mapping
 /// to the newtype_struct type in the serde data model, using the wrapped object
 /// as the content of the newtype struct.
 /// 
+/// The wrapped object can also be a reference, including references to unsized
+/// data like `str` or slices, since these implement [`Serialize`] as well. Hence,
+/// there is no need to move or clone large data just to serialize it.
+///
+/// # Example
+/// ```
+/// # use feanor_serde::newtype_struct::*;
+/// let data = vec![1, 2, 3];
+/// assert_eq!("[1,2,3]", serde_json::to_string(&SerializableNewtypeStruct::new("Foo", &data)).unwrap());
+/// assert_eq!("[2,3]", serde_json::to_string(&SerializableNewtypeStruct::new("Foo", &data[1..])).unwrap());
+/// assert_eq!("\"abc\"", serde_json::to_string(&SerializableNewtypeStruct::new("Foo", "abc")).unwrap());
+/// ```
+///
+#[derive(Clone, Copy, Debug)]
 pub struct SerializableNewtypeStruct<T>
     where T: Serialize
 {
@@ -19,7 +35,7 @@ pub struct SerializableNewtypeStruct<T>
 impl<T> SerializableNewtypeStruct<T>
     where T: Serialize
 {
-    pub fn new(name: &'static str, data: T) -> Self {
+    pub const fn new(name: &'static str, data: T) -> Self {
         Self { name, data }
     }
 }
@@ -41,30 +57,54 @@ impl<T> Serialize for SerializableNewtypeStruct<T>
 /// # Example
 /// ```
 /// # use feanor_serde::newtype_struct::*;
-/// # use std::marker::PhantomData;
+/// # use feanor_serde::default_seed::seed_of;
 /// # use serde::de::DeserializeSeed;
 /// let mut deserializer = serde_json::Deserializer::new(serde_json::de::StrRead::new("1"));
-/// let deserialize_seed = DeserializeSeedNewtypeStruct::new("Foo", PhantomData::<i64>);
+/// let deserialize_seed = DeserializeSeedNewtypeStruct::new("Foo", seed_of::<i64>());
 /// assert_eq!(1, deserialize_seed.deserialize(&mut deserializer).unwrap());
 /// ```
 /// 
-pub struct DeserializeSeedNewtypeStruct<'de, S>
-    where S: DeserializeSeed<'de>
-{
-    deserializer: PhantomData<&'de ()>,
+#[derive(Clone, Copy, Debug)]
+pub struct DeserializeSeedNewtypeStruct<S> {
     name: &'static str,
     seed: S
 }
 
-impl<'de, S> DeserializeSeedNewtypeStruct<'de, S>
+impl<'de, S> DeserializeSeedNewtypeStruct<S>
     where S: DeserializeSeed<'de>
 {
-    pub fn new(name: &'static str, seed: S) -> Self {
-        Self { deserializer: PhantomData, name, seed }
+    pub fn new<I, M>(name: &'static str, seed: I) -> Self
+        where I: IntoSeed<'de, M, Seed = S>
+    {
+        Self { name, seed: seed.into_seed() }
+    }
+
+    ///
+    /// Same as [`DeserializeSeedNewtypeStruct::new()`], but takes the seed directly instead of anything
+    /// implementing [`IntoSeed`], which allows it to be a `const fn`.
+    ///
+    pub const fn from_seed(name: &'static str, seed: S) -> Self {
+        Self { name, seed }
     }
 }
 
-impl<'de, S> DeserializeSeed<'de> for DeserializeSeedNewtypeStruct<'de, S>
+///
+/// Deserializes using a reference to the wrapped seed, so a configured seed can be reused
+/// without reconstructing it, as long as the wrapped seed supports this.
+///
+impl<'a, 'de, S> DeserializeSeed<'de> for &'a DeserializeSeedNewtypeStruct<S>
+    where &'a S: DeserializeSeed<'de>
+{
+    type Value = <&'a S as DeserializeSeed<'de>>::Value;
+
+    fn deserialize<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+        where D: Deserializer<'de>
+    {
+        DeserializeSeedNewtypeStruct { name: self.name, seed: &self.seed }.deserialize(deserializer)
+    }
+}
+
+impl<'de, S> DeserializeSeed<'de> for DeserializeSeedNewtypeStruct<S>
     where S: DeserializeSeed<'de>
 {
     type Value = S::Value;
@@ -138,4 +178,36 @@ fn test_serde_json() {
         );
         assert!(result.is_err() || result.unwrap() == payload);
     }
-}
\ No newline at end of file
+}
+
+#[test]
+fn test_clone_debug() {
+    let seed = DeserializeSeedNewtypeStruct::new("Foo", PhantomData::<i64>);
+    let copy = seed;
+    assert_eq!(1, seed.deserialize(&mut serde_json::Deserializer::from_str("1")).unwrap());
+    assert_eq!(2, copy.deserialize(&mut serde_json::Deserializer::from_str("2")).unwrap());
+    assert_eq!("DeserializeSeedNewtypeStruct { name: \"Foo\", seed: PhantomData<i64> }", format!("{:?}", seed));
+    assert_eq!("SerializableNewtypeStruct { name: \"Foo\", data: 1 }", format!("{:?}", SerializableNewtypeStruct::new("Foo", 1)));
+}
+
+#[test]
+fn test_deserialize_by_reference() {
+    let seed = DeserializeSeedNewtypeStruct::new("Foo", crate::seq::SeqSeedBuilder::new(crate::default_seed::seed_of::<i64>).max_len(2).collect_vec());
+    for (json, expected) in [("[1]", vec![1]), ("[2, 3]", vec![2, 3])] {
+        assert_eq!(expected, (&seed).deserialize(&mut serde_json::Deserializer::from_str(json)).unwrap());
+    }
+    assert!((&seed).deserialize(&mut serde_json::Deserializer::from_str("[1, 2, 3]")).is_err());
+
+    let seed = DeserializeSeedNewtypeStruct::new("Foo", crate::default_seed::seed_of::<String>());
+    let data = postcard::to_allocvec(&SerializableNewtypeStruct::new("Foo", "abc")).unwrap();
+    assert_eq!("abc", (&seed).deserialize(&mut postcard::Deserializer::from_bytes(&data)).unwrap());
+}
+
+#[test]
+fn test_const_construction() {
+    static SEED: DeserializeSeedNewtypeStruct<crate::default_seed::DefaultSeed<i64>> = DeserializeSeedNewtypeStruct::from_seed("Foo", crate::default_seed::seed_of());
+    const DATA: SerializableNewtypeStruct<i64> = SerializableNewtypeStruct::new("Foo", 5);
+
+    let serialized = postcard::to_allocvec(&DATA).unwrap();
+    assert_eq!(5, (&SEED).deserialize(&mut postcard::Deserializer::from_bytes(&serialized)).unwrap());
+}
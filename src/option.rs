@@ -0,0 +1,174 @@
+use serde::de::{DeserializeSeed, Error, Visitor};
+use serde::{Deserializer, Serialize, Serializer};
+
+use crate::into_seed::IntoSeed;
+
+///
+/// Wraps an optional reference, and implements [`Serialize`] by mapping it to the option
+/// type in the serde data model. The value is serialized by first passing it to the given
+/// function, which can e.g. wrap it in another serializable wrapper carrying additional
+/// context.
+///
+/// Use [`DeserializeSeedOption`] to deserialize it.
+///
+/// # Example
+/// ```
+/// # use feanor_serde::option::*;
+/// # use feanor_serde::newtype_struct::*;
+/// # use feanor_serde::default_seed::seed_of;
+/// # use serde::de::DeserializeSeed;
+/// let value = Some(&5);
+/// let json = serde_json::to_string(&SerializableOption::new(value, |x| SerializableNewtypeStruct::new("Foo", *x))).unwrap();
+/// assert_eq!("5", json);
+/// let seed = DeserializeSeedOption::new(DeserializeSeedNewtypeStruct::new("Foo", seed_of::<i64>()));
+/// assert_eq!(Some(5), seed.deserialize(&mut serde_json::Deserializer::from_str(&json)).unwrap());
+///
+/// let json = serde_json::to_string(&SerializableOption::new(None, |x: &i64| SerializableNewtypeStruct::new("Foo", *x))).unwrap();
+/// assert_eq!("null", json);
+/// ```
+///
+#[derive(Clone, Copy)]
+pub struct SerializableOption<'a, T, F>
+    where T: ?Sized
+{
+    data: Option<&'a T>,
+    wrap: F
+}
+
+impl<'a, T, F, W> SerializableOption<'a, T, F>
+    where T: ?Sized,
+        F: Fn(&'a T) -> W,
+        W: Serialize
+{
+    pub const fn new(data: Option<&'a T>, wrap: F) -> Self {
+        Self { data, wrap }
+    }
+}
+
+impl<'a, T, F, W> Serialize for SerializableOption<'a, T, F>
+    where T: ?Sized,
+        F: Fn(&'a T) -> W,
+        W: Serialize
+{
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+        where S: Serializer
+    {
+        match self.data {
+            None => serializer.serialize_none(),
+            Some(value) => serializer.serialize_some(&(self.wrap)(value))
+        }
+    }
+}
+
+///
+/// A [`DeserializeSeed`] that deserializes an option, using the given [`DeserializeSeed`]
+/// for the value if it is present.
+///
+/// When used for a field in [`crate::impl_deserialize_seed_for_dependent_struct!`], a missing
+/// field is deserialized as `None`.
+///
+#[derive(Clone, Copy, Debug)]
+pub struct DeserializeSeedOption<S> {
+    seed: S
+}
+
+impl<S> DeserializeSeedOption<S> {
+
+    pub fn new<'de, I, M>(seed: I) -> Self
+        where I: IntoSeed<'de, M, Seed = S>
+    {
+        Self { seed: seed.into_seed() }
+    }
+}
+
+impl<'de, S> DeserializeSeed<'de> for DeserializeSeedOption<S>
+    where S: DeserializeSeed<'de>
+{
+    type Value = Option<S::Value>;
+
+    fn deserialize<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+        where D: Deserializer<'de>
+    {
+        struct ResultVisitor<S> {
+            seed: S
+        }
+
+        impl<'de, S> Visitor<'de> for ResultVisitor<S>
+            where S: DeserializeSeed<'de>
+        {
+            type Value = Option<S::Value>;
+
+            fn expecting(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+                write!(f, "an optional value")
+            }
+
+            fn visit_none<E>(self) -> Result<Self::Value, E>
+                where E: Error
+            {
+                Ok(None)
+            }
+
+            fn visit_unit<E>(self) -> Result<Self::Value, E>
+                where E: Error
+            {
+                Ok(None)
+            }
+
+            fn visit_some<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+                where D: Deserializer<'de>
+            {
+                self.seed.deserialize(deserializer).map(Some)
+            }
+        }
+
+        return deserializer.deserialize_option(ResultVisitor { seed: self.seed });
+    }
+}
+
+#[cfg(test)]
+use crate::seq::{SerializableSeq, DeserializeSeedSeq};
+#[cfg(test)]
+use crate::default_seed::seed_of;
+#[cfg(test)]
+use crate::impl_deserialize_seed_for_dependent_struct;
+
+#[cfg(test)]
+fn sum_seed() -> impl for<'de> DeserializeSeed<'de, Value = i64> {
+    DeserializeSeedSeq::new(std::iter::repeat(seed_of::<i64>()), 0, |current, next| current + next)
+}
+
+#[test]
+fn test_serde_postcard() {
+    let data = vec![1i64, 2, 3];
+    for value in [Some(&data), None] {
+        let serialized = postcard::to_allocvec(&SerializableOption::new(value, |x: &Vec<i64>| SerializableSeq::new_with_len(x.iter(), x.len()))).unwrap();
+        assert_eq!(postcard::to_allocvec(&value).unwrap(), serialized);
+        let result = DeserializeSeedOption::new(sum_seed()).deserialize(&mut postcard::Deserializer::from_bytes(&serialized)).unwrap();
+        assert_eq!(value.map(|x| x.iter().sum::<i64>()), result);
+    }
+}
+
+#[cfg(test)]
+struct DeserializeSeedFoo;
+
+#[cfg(test)]
+impl_deserialize_seed_for_dependent_struct!{
+    pub struct Foo<'de> using DeserializeSeedFoo {
+        a: Option<i64>: |_| DeserializeSeedOption::new(sum_seed()),
+        b: Option<i64>: |_| DeserializeSeedOption::new(sum_seed())
+    }
+}
+
+#[test]
+fn test_serde_json() {
+    let data: [i64; 2] = [1, 2];
+    let slice: &[i64] = &data;
+    assert_eq!("[1,2]", serde_json::to_string(&SerializableOption::new(Some(slice), |x: &[i64]| SerializableSeq::new(x.iter()))).unwrap());
+
+    let foo = DeserializeSeedFoo.deserialize(&mut serde_json::Deserializer::from_str(r#"{"a": [1, 2], "b": null}"#)).unwrap();
+    assert_eq!(Some(3), foo.a);
+    assert_eq!(None, foo.b);
+    let foo = DeserializeSeedFoo.deserialize(&mut serde_json::Deserializer::from_str(r#"{"b": []}"#)).unwrap();
+    assert_eq!(None, foo.a);
+    assert_eq!(Some(0), foo.b);
+}
@@ -0,0 +1,410 @@
+use std::cell::RefCell;
+use std::marker::PhantomData;
+
+use serde::de::value::StrDeserializer;
+use serde::de::{DeserializeSeed, Error, IgnoredAny, IntoDeserializer, MapAccess, SeqAccess, Unexpected, Visitor};
+use serde::{forward_to_deserialize_any, Deserializer};
+
+use crate::into_seed::IntoSeed;
+use crate::path::{Path, Segment};
+
+///
+/// The result of [`DeserializeSeedOverlay`].
+///
+#[derive(Debug)]
+pub struct Overlaid<T> {
+    ///
+    /// The deserialized value, including all overrides.
+    ///
+    pub value: T,
+    ///
+    /// The paths of all fields whose value was taken from the override source, in the
+    /// order in which they were deserialized.
+    ///
+    pub overridden: Vec<Path>
+}
+
+///
+/// A [`DeserializeSeed`] that deserializes a struct using the given [`DeserializeSeed`], but
+/// substitutes the values of fields for which the given override source returns a value.
+///
+/// The override source is a function that gets the [`Path`] of a struct field, e.g. `.server.port`,
+/// and returns a string to use instead of the value in the input, e.g. read from an environment
+/// variable. The string is parsed according to the type requested by the seed of the field, which
+/// works for primitive types, strings, options of those and unit enum variants.
+///
+/// Overrides are considered for all fields of the outermost struct, and for fields of structs that
+/// are directly stored in a field of another struct. Fields that are missing in the input are
+/// overridden as well, but only if their parent struct is present. Since fields are identified by
+/// their name, this is meant for self-describing formats that store structs as maps, like JSON.
+///
+/// # Example
+/// ```
+/// # use feanor_serde::impl_deserialize_seed_for_dependent_struct;
+/// # use feanor_serde::overlay::*;
+/// # use feanor_serde::path::Path;
+/// # use feanor_serde::default_seed::seed_of;
+/// # use serde::de::DeserializeSeed;
+/// # use std::collections::BTreeMap;
+/// struct ConfigDeserializeSeed;
+/// impl_deserialize_seed_for_dependent_struct!{
+///     pub struct Config<'de> using ConfigDeserializeSeed {
+///         host: String: |_| seed_of::<String>(),
+///         port: u16: |_| seed_of::<u16>(),
+///         verbose: bool: |_| seed_of::<bool>()
+///     }
+/// }
+/// // e.g. read from environment variables instead
+/// let environment = BTreeMap::from([("APP_PORT", "8081"), ("APP_VERBOSE", "true")]);
+/// let overrides = |path: &Path| environment.get(format!("APP{}", path).replace('.', "_").to_uppercase().as_str()).map(|value| value.to_string());
+///
+/// let json = r#"{ "host": "localhost", "port": 8080 }"#;
+/// let result = DeserializeSeedOverlay::new(ConfigDeserializeSeed, overrides).deserialize(&mut serde_json::Deserializer::from_str(json)).unwrap();
+/// assert_eq!(("localhost", 8081, true), (result.value.host.as_str(), result.value.port, result.value.verbose));
+/// assert_eq!(vec![".port", ".verbose"], result.overridden.iter().map(|path| path.to_string()).collect::<Vec<_>>());
+/// ```
+///
+pub struct DeserializeSeedOverlay<S, F> {
+    seed: S,
+    overrides: F
+}
+
+impl<S, F> DeserializeSeedOverlay<S, F>
+    where F: Fn(&Path) -> Option<String>
+{
+    pub fn new<'de, I, M>(seed: I, overrides: F) -> Self
+        where I: IntoSeed<'de, M, Seed = S>
+    {
+        Self { seed: seed.into_seed(), overrides: overrides }
+    }
+}
+
+impl<'de, S, F> DeserializeSeed<'de> for DeserializeSeedOverlay<S, F>
+    where S: DeserializeSeed<'de>,
+        F: Fn(&Path) -> Option<String>
+{
+    type Value = Overlaid<S::Value>;
+
+    fn deserialize<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+        where D: Deserializer<'de>
+    {
+        let state = State { overrides: self.overrides, overridden: RefCell::new(Vec::new()) };
+        let value = self.seed.deserialize(OverlayDeserializer { de: deserializer, path: Path::default(), state: &state })?;
+        return Ok(Overlaid { value: value, overridden: state.overridden.into_inner() });
+    }
+}
+
+struct State<F> {
+    overrides: F,
+    overridden: RefCell<Vec<Path>>
+}
+
+fn child_path(path: &Path, key: &str) -> Path {
+    let mut segments = path.segments().to_vec();
+    segments.push(Segment::Map { key: key.to_owned() });
+    return Path::from_segments(segments);
+}
+
+struct OverlayDeserializer<'a, D, F> {
+    de: D,
+    path: Path,
+    state: &'a State<F>
+}
+
+struct OverlaySeed<'a, S, F> {
+    seed: S,
+    path: Path,
+    state: &'a State<F>
+}
+
+impl<'a, 'de, S, F> DeserializeSeed<'de> for OverlaySeed<'a, S, F>
+    where S: DeserializeSeed<'de>,
+        F: Fn(&Path) -> Option<String>
+{
+    type Value = S::Value;
+
+    fn deserialize<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+        where D: Deserializer<'de>
+    {
+        self.seed.deserialize(OverlayDeserializer { de: deserializer, path: self.path, state: self.state })
+    }
+}
+
+macro_rules! forward_deserialize {
+    ($($method:ident($($arg:ident: $type:ty),*)),*) => {
+        $(
+            fn $method<V>(self, $($arg: $type,)* visitor: V) -> Result<V::Value, Self::Error>
+                where V: Visitor<'de>
+            {
+                self.de.$method($($arg,)* visitor)
+            }
+        )*
+    };
+}
+
+impl<'a, 'de, D, F> Deserializer<'de> for OverlayDeserializer<'a, D, F>
+    where D: Deserializer<'de>,
+        F: Fn(&Path) -> Option<String>
+{
+    type Error = D::Error;
+
+    forward_deserialize!{
+        deserialize_any(), deserialize_bool(), deserialize_i8(), deserialize_i16(), deserialize_i32(), deserialize_i64(), deserialize_i128(),
+        deserialize_u8(), deserialize_u16(), deserialize_u32(), deserialize_u64(), deserialize_u128(), deserialize_f32(), deserialize_f64(),
+        deserialize_char(), deserialize_str(), deserialize_string(), deserialize_bytes(), deserialize_byte_buf(), deserialize_option(),
+        deserialize_unit(), deserialize_seq(), deserialize_map(), deserialize_identifier(), deserialize_ignored_any(),
+        deserialize_unit_struct(name: &'static str), deserialize_newtype_struct(name: &'static str), deserialize_tuple(len: usize),
+        deserialize_tuple_struct(name: &'static str, len: usize), deserialize_enum(name: &'static str, variants: &'static [&'static str])
+    }
+
+    fn deserialize_struct<V>(self, name: &'static str, fields: &'static [&'static str], visitor: V) -> Result<V::Value, Self::Error>
+        where V: Visitor<'de>
+    {
+        self.de.deserialize_struct(name, fields, StructVisitor { visitor: visitor, fields: fields, path: self.path, state: self.state })
+    }
+
+    fn is_human_readable(&self) -> bool {
+        self.de.is_human_readable()
+    }
+}
+
+struct StructVisitor<'a, X, F> {
+    visitor: X,
+    fields: &'static [&'static str],
+    path: Path,
+    state: &'a State<F>
+}
+
+impl<'a, 'de, X, F> Visitor<'de> for StructVisitor<'a, X, F>
+    where X: Visitor<'de>,
+        F: Fn(&Path) -> Option<String>
+{
+    type Value = X::Value;
+
+    fn expecting(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        self.visitor.expecting(f)
+    }
+
+    fn visit_seq<A>(self, seq: A) -> Result<Self::Value, A::Error>
+        where A: SeqAccess<'de>
+    {
+        self.visitor.visit_seq(seq)
+    }
+
+    fn visit_map<A>(self, map: A) -> Result<Self::Value, A::Error>
+        where A: MapAccess<'de>
+    {
+        self.visitor.visit_map(OverlayMap {
+            map: map,
+            fields: self.fields,
+            seen: vec![false; self.fields.len()],
+            path: self.path,
+            state: self.state,
+            input_done: false,
+            next_field: 0,
+            pending: None
+        })
+    }
+}
+
+enum Pending {
+    Input(Path),
+    Override { value: String, path: Path, from_input: bool }
+}
+
+struct OverlayMap<'a, A, F> {
+    map: A,
+    fields: &'static [&'static str],
+    seen: Vec<bool>,
+    path: Path,
+    state: &'a State<F>,
+    input_done: bool,
+    next_field: usize,
+    pending: Option<Pending>
+}
+
+impl<'a, 'de, A, F> MapAccess<'de> for OverlayMap<'a, A, F>
+    where A: MapAccess<'de>,
+        F: Fn(&Path) -> Option<String>
+{
+    type Error = A::Error;
+
+    fn next_key_seed<K>(&mut self, seed: K) -> Result<Option<K::Value>, A::Error>
+        where K: DeserializeSeed<'de>
+    {
+        if !self.input_done {
+            match self.map.next_key::<String>()? {
+                Some(key) => {
+                    if let Some(index) = self.fields.iter().position(|field| *field == key) {
+                        self.seen[index] = true;
+                    }
+                    let path = child_path(&self.path, &key);
+                    self.pending = Some(match (self.state.overrides)(&path) {
+                        Some(value) => Pending::Override { value: value, path: path, from_input: true },
+                        None => Pending::Input(path)
+                    });
+                    return seed.deserialize(StrDeserializer::<A::Error>::new(&key)).map(Some);
+                },
+                None => self.input_done = true
+            }
+        }
+        while self.next_field < self.fields.len() {
+            let index = self.next_field;
+            self.next_field += 1;
+            if self.seen[index] {
+                continue;
+            }
+            let path = child_path(&self.path, self.fields[index]);
+            if let Some(value) = (self.state.overrides)(&path) {
+                self.pending = Some(Pending::Override { value: value, path: path, from_input: false });
+                return seed.deserialize(StrDeserializer::<A::Error>::new(self.fields[index])).map(Some);
+            }
+        }
+        return Ok(None);
+    }
+
+    fn next_value_seed<V>(&mut self, seed: V) -> Result<V::Value, A::Error>
+        where V: DeserializeSeed<'de>
+    {
+        match self.pending.take() {
+            Some(Pending::Override { value, path, from_input }) => {
+                if from_input {
+                    self.map.next_value::<IgnoredAny>()?;
+                }
+                let result = seed.deserialize(OverrideValueDeserializer::<A::Error> { value: value, error: PhantomData }).map_err(|e| A::Error::custom(format!("override for {}: {}", path, e)))?;
+                self.state.overridden.borrow_mut().push(path);
+                return Ok(result);
+            },
+            Some(Pending::Input(path)) => self.map.next_value_seed(OverlaySeed { seed: seed, path: path, state: self.state }),
+            None => self.map.next_value_seed(seed)
+        }
+    }
+}
+
+///
+/// A [`Deserializer`] that parses an override string according to the requested type.
+///
+struct OverrideValueDeserializer<E> {
+    value: String,
+    error: PhantomData<E>
+}
+
+macro_rules! parse_deserialize {
+    ($($method:ident($type:ty) => $visit:ident),*) => {
+        $(
+            fn $method<V>(self, visitor: V) -> Result<V::Value, E>
+                where V: Visitor<'de>
+            {
+                match self.value.trim().parse::<$type>() {
+                    Ok(value) => visitor.$visit(value),
+                    Err(_) => Err(E::invalid_value(Unexpected::Str(&self.value), &concat!("a string containing a value of type ", stringify!($type))))
+                }
+            }
+        )*
+    };
+}
+
+impl<'de, E> Deserializer<'de> for OverrideValueDeserializer<E>
+    where E: Error
+{
+    type Error = E;
+
+    fn deserialize_any<V>(self, visitor: V) -> Result<V::Value, E>
+        where V: Visitor<'de>
+    {
+        visitor.visit_string(self.value)
+    }
+
+    parse_deserialize!{
+        deserialize_bool(bool) => visit_bool, deserialize_i8(i8) => visit_i8, deserialize_i16(i16) => visit_i16, deserialize_i32(i32) => visit_i32,
+        deserialize_i64(i64) => visit_i64, deserialize_i128(i128) => visit_i128, deserialize_u8(u8) => visit_u8, deserialize_u16(u16) => visit_u16,
+        deserialize_u32(u32) => visit_u32, deserialize_u64(u64) => visit_u64, deserialize_u128(u128) => visit_u128, deserialize_f32(f32) => visit_f32,
+        deserialize_f64(f64) => visit_f64, deserialize_char(char) => visit_char
+    }
+
+    fn deserialize_option<V>(self, visitor: V) -> Result<V::Value, E>
+        where V: Visitor<'de>
+    {
+        visitor.visit_some(self)
+    }
+
+    fn deserialize_newtype_struct<V>(self, _name: &'static str, visitor: V) -> Result<V::Value, E>
+        where V: Visitor<'de>
+    {
+        visitor.visit_newtype_struct(self)
+    }
+
+    fn deserialize_enum<V>(self, _name: &'static str, _variants: &'static [&'static str], visitor: V) -> Result<V::Value, E>
+        where V: Visitor<'de>
+    {
+        visitor.visit_enum(IntoDeserializer::<E>::into_deserializer(self.value))
+    }
+
+    forward_to_deserialize_any! {
+        str string bytes byte_buf unit unit_struct seq tuple
+        tuple_struct map struct identifier ignored_any
+    }
+}
+
+#[cfg(test)]
+use crate::default_seed::seed_of;
+#[cfg(test)]
+use crate::impl_deserialize_seed_for_dependent_struct;
+#[cfg(test)]
+use std::collections::BTreeMap;
+
+#[cfg(test)]
+#[derive(serde::Deserialize, Debug, PartialEq)]
+pub enum Level {
+    Debug,
+    Info
+}
+
+#[cfg(test)]
+struct DeserializeSeedConfig;
+
+#[cfg(test)]
+impl_deserialize_seed_for_dependent_struct!{
+    pub struct Config<'de> using DeserializeSeedConfig {
+        name: String: |_| seed_of::<String>(),
+        server: Server<'de>: |_| DeserializeSeedServer,
+        level: Level: |_| seed_of::<Level>()
+    }
+}
+
+#[cfg(test)]
+struct DeserializeSeedServer;
+
+#[cfg(test)]
+impl_deserialize_seed_for_dependent_struct!{
+    pub struct Server<'de> using DeserializeSeedServer {
+        port: u16: |_| seed_of::<u16>(),
+        timeout: Option<f64>: |_| seed_of::<Option<f64>>()
+    }
+}
+
+#[test]
+fn test_overlay() {
+    fn deserialize<'de>(json: &'de str, overrides: &[(&str, &str)]) -> Result<Overlaid<Config<'de>>, serde_json::Error> {
+        let overrides = overrides.iter().copied().collect::<BTreeMap<_, _>>();
+        DeserializeSeedOverlay::new(DeserializeSeedConfig, |path: &Path| overrides.get(path.to_string().as_str()).map(|value| value.to_string())).deserialize(&mut serde_json::Deserializer::from_str(json))
+    }
+    let json = r#"{"name": "test", "server": {"port": 80}, "level": "Info"}"#;
+
+    let result = deserialize(json, &[]).unwrap();
+    assert_eq!(("test", 80, None, Level::Info), (result.value.name.as_str(), result.value.server.port, result.value.server.timeout, result.value.level));
+    assert!(result.overridden.is_empty());
+
+    let result = deserialize(json, &[(".server.port", "8080"), (".server.timeout", "1.5"), (".level", "Debug"), (".name", "  spaces ")]).unwrap();
+    assert_eq!(("  spaces ", 8080, Some(1.5), Level::Debug), (result.value.name.as_str(), result.value.server.port, result.value.server.timeout, result.value.level));
+    assert_eq!(vec![".name", ".server.port", ".server.timeout", ".level"], result.overridden.iter().map(|path| path.to_string()).collect::<Vec<_>>());
+
+    let result = deserialize(r#"{"server": {"port": 80, "timeout": null}}"#, &[(".name", "x"), (".level", "Info")]).unwrap();
+    assert_eq!(("x", 80, None, Level::Info), (result.value.name.as_str(), result.value.server.port, result.value.server.timeout, result.value.level));
+    assert_eq!(vec![".name", ".level"], result.overridden.iter().map(|path| path.to_string()).collect::<Vec<_>>());
+
+    let error = deserialize(json, &[(".server.port", "eighty")]).err().unwrap();
+    assert!(error.to_string().starts_with("override for .server.port: invalid value"));
+    assert!(deserialize(json, &[(".server.port", "70000")]).is_err());
+    assert!(deserialize(json, &[(".level", "Warn")]).is_err());
+}
@@ -0,0 +1,191 @@
+use serde::de::DeserializeSeed;
+
+///
+/// A [`DeserializeSeed`] that can deserialize from data of any lifetime, producing values
+/// of type `T`. This is the analogue of [`serde::de::DeserializeOwned`] for [`DeserializeSeed`]s,
+/// and is implemented for all types satisfying the bound `for<'de> DeserializeSeed<'de, Value = T>`.
+///
+/// Most combinators of this crate (like [`crate::seq::DeserializeSeedSeq`]) implement this,
+/// as long as all the seeds they wrap do. Together with `Send + 'static`, this is what is
+/// required to move seeds between threads or async tasks, and to deserialize from buffers
+/// that do not outlive the seed.
+///
+pub trait DeserializeSeedOwned<T>: for<'de> DeserializeSeed<'de, Value = T> {}
+
+impl<S, T> DeserializeSeedOwned<T> for S
+    where S: for<'de> DeserializeSeed<'de, Value = T>
+{}
+
+///
+/// A type-erased postcard decoder, which can be stored without naming the types of the
+/// seeds, and moved between threads or async tasks. Create it using [`SeededDecoder::into_erased()`].
+///
+#[cfg(feature = "postcard")]
+pub type ErasedDecoder<T> = Box<dyn FnMut(&[u8]) -> ::postcard::Result<T> + Send>;
+
+///
+/// Decodes postcard-encoded messages (e.g. the frames of a network protocol), each with a
+/// new [`DeserializeSeed`] produced by the given factory.
+///
+/// # Example
+/// ```
+/// # use feanor_serde::owned::*;
+/// # use feanor_serde::seq::*;
+/// # use feanor_serde::default_seed::seed_of;
+/// # use std::iter::repeat;
+/// let mut decoder = SeededDecoder::new(|| DeserializeSeedSeq::new(
+///     repeat(seed_of::<i64>()),
+///     0,
+///     |current, next| current + next
+/// )).into_erased();
+///
+/// let frame = postcard::to_allocvec(&vec![1, 2, 3]).unwrap();
+/// let handle = std::thread::spawn(move || decoder(&frame).unwrap());
+/// assert_eq!(6, handle.join().unwrap());
+/// ```
+///
+#[cfg(feature = "postcard")]
+pub struct SeededDecoder<F> {
+    seeds: F
+}
+
+#[cfg(feature = "postcard")]
+impl<F, S, T> SeededDecoder<F>
+    where F: FnMut() -> S,
+        S: for<'de> DeserializeSeed<'de, Value = T>
+{
+    pub fn new(seeds: F) -> Self {
+        Self { seeds }
+    }
+
+    ///
+    /// Deserializes the given message. Like [`crate::postcard::from_slice_seeded()`], any
+    /// unused bytes at the end are ignored.
+    ///
+    pub fn decode(&mut self, message: &[u8]) -> ::postcard::Result<T> {
+        crate::postcard::from_slice_seeded((self.seeds)(), message)
+    }
+
+    pub fn into_erased(mut self) -> ErasedDecoder<T>
+        where F: Send + 'static
+    {
+        Box::new(move |message| self.decode(message))
+    }
+}
+
+///
+/// A [`tokio_util::codec::Decoder`] and [`tokio_util::codec::Encoder`] for length-delimited
+/// postcard messages, which deserializes each message with a new [`DeserializeSeed`] produced
+/// by the given factory.
+///
+/// Frames are handled by [`tokio_util::codec::LengthDelimitedCodec`], so this can be used with
+/// e.g. [`tokio_util::codec::Framed`] to send and receive messages over a TCP stream.
+///
+#[cfg(feature = "tokio-util")]
+pub struct SeededCodec<F> {
+    frames: tokio_util::codec::LengthDelimitedCodec,
+    decoder: SeededDecoder<F>
+}
+
+#[cfg(feature = "tokio-util")]
+impl<F, S, T> SeededCodec<F>
+    where F: FnMut() -> S,
+        S: for<'de> DeserializeSeed<'de, Value = T>
+{
+    pub fn new(seeds: F) -> Self {
+        Self { frames: tokio_util::codec::LengthDelimitedCodec::new(), decoder: SeededDecoder::new(seeds) }
+    }
+
+    ///
+    /// Makes decoding fail if a frame is longer than `max_frame_len` bytes, see
+    /// [`tokio_util::codec::LengthDelimitedCodec::set_max_frame_length()`].
+    ///
+    pub fn with_max_frame_len(mut self, max_frame_len: usize) -> Self {
+        self.frames.set_max_frame_length(max_frame_len);
+        return self;
+    }
+}
+
+#[cfg(feature = "tokio-util")]
+impl<F, S, T> tokio_util::codec::Decoder for SeededCodec<F>
+    where F: FnMut() -> S,
+        S: for<'de> DeserializeSeed<'de, Value = T>
+{
+    type Item = T;
+    type Error = std::io::Error;
+
+    fn decode(&mut self, src: &mut bytes::BytesMut) -> Result<Option<Self::Item>, Self::Error> {
+        match self.frames.decode(src)? {
+            Some(frame) => self.decoder.decode(&frame).map(Some).map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e)),
+            None => Ok(None)
+        }
+    }
+}
+
+#[cfg(feature = "tokio-util")]
+impl<F, V> tokio_util::codec::Encoder<V> for SeededCodec<F>
+    where V: serde::Serialize
+{
+    type Error = std::io::Error;
+
+    fn encode(&mut self, item: V, dst: &mut bytes::BytesMut) -> Result<(), Self::Error> {
+        let payload = ::postcard::to_allocvec(&item).map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e))?;
+        return self.frames.encode(bytes::Bytes::from(payload), dst);
+    }
+}
+
+#[cfg(test)]
+use std::marker::PhantomData;
+#[cfg(test)]
+use std::iter::repeat;
+#[cfg(test)]
+use crate::seq::DeserializeSeedSeq;
+
+#[cfg(test)]
+fn assert_owned_send<S, T>(_seed: &S)
+    where S: DeserializeSeedOwned<T> + Send + 'static
+{}
+
+#[test]
+fn test_combinators_are_owned_send() {
+    assert_owned_send(&DeserializeSeedSeq::new(repeat(PhantomData::<i64>), Vec::new(), |mut current, next| { current.push(next); current }));
+    assert_owned_send(&crate::map::DeserializeSeedMap::new(repeat((PhantomData::<String>, PhantomData::<i64>)), Vec::new(), |mut current, k, v| { current.push((k, v)); current }));
+    assert_owned_send(&crate::newtype_struct::DeserializeSeedNewtypeStruct::new("Foo", PhantomData::<i64>));
+    assert_owned_send(&crate::dependent_tuple::DeserializeSeedDependentTuple::new(PhantomData::<usize>, |len| DeserializeSeedSeq::new(std::iter::repeat_n(PhantomData::<i64>, len), Vec::new(), |mut current, next| { current.push(next); current })));
+    assert_owned_send(&crate::lenient::DeserializeSeedLenient::<i64>::new());
+    assert_owned_send(&crate::matrix::DeserializeSeedMatrix::new(|_, _| PhantomData::<i64>, |rows, cols, data| (rows, cols, data)));
+}
+
+#[test]
+fn test_deserialize_on_other_thread() {
+    let seed = DeserializeSeedSeq::new(repeat(PhantomData::<String>), Vec::new(), |mut current, next| { current.push(next); current });
+    let data = serde_json::to_string(&["a", "b"]).unwrap();
+    let result = std::thread::spawn(move || seed.deserialize(&mut serde_json::Deserializer::from_str(&data)).unwrap()).join().unwrap();
+    assert_eq!(vec!["a".to_owned(), "b".to_owned()], result);
+}
+
+#[cfg(feature = "tokio-util")]
+#[test]
+fn test_seeded_codec() {
+    use tokio_util::codec::{Decoder, Encoder};
+
+    let mut codec = SeededCodec::new(|| DeserializeSeedSeq::new(repeat(PhantomData::<i64>), 0, |current, next| current + next));
+    let mut buffer = bytes::BytesMut::new();
+    codec.encode(vec![1i64, 2, 3], &mut buffer).unwrap();
+    codec.encode(vec![4i64], &mut buffer).unwrap();
+
+    let mut partial = buffer.split_to(3);
+    assert_eq!(None, codec.decode(&mut partial).unwrap());
+    partial.unsplit(buffer);
+    assert_eq!(Some(6), codec.decode(&mut partial).unwrap());
+    assert_eq!(Some(4), codec.decode(&mut partial).unwrap());
+    assert_eq!(None, codec.decode(&mut partial).unwrap());
+
+    let mut codec = SeededCodec::new(|| PhantomData::<bool>);
+    let mut buffer = bytes::BytesMut::new();
+    codec.encode(2u8, &mut buffer).unwrap();
+    assert_eq!(std::io::ErrorKind::InvalidData, codec.decode(&mut buffer).unwrap_err().kind());
+
+    codec.encode((true, true), &mut buffer).unwrap();
+    assert!(SeededCodec::new(|| PhantomData::<bool>).with_max_frame_len(1).decode(&mut buffer).is_err());
+}
@@ -0,0 +1,182 @@
+use std::cell::RefCell;
+use std::marker::PhantomData;
+
+use serde::de::{DeserializeSeed, Error, SeqAccess, Visitor};
+use serde::Deserializer;
+
+///
+/// The result of [`deserialize_seq_partial()`].
+///
+#[derive(Debug)]
+pub struct PartialResult<T, E> {
+    ///
+    /// The value built from all elements that were successfully deserialized and collected.
+    ///
+    pub value: T,
+    ///
+    /// The error that stopped deserialization, if any.
+    ///
+    pub error: Option<E>,
+    ///
+    /// The indices of the elements that were deserialized, but rejected by the collector,
+    /// together with the reason.
+    ///
+    pub skipped: Vec<(usize, String)>
+}
+
+impl<T, E> PartialResult<T, E> {
+
+    ///
+    /// Returns whether all elements were deserialized and collected successfully.
+    ///
+    pub fn is_complete(&self) -> bool {
+        self.error.is_none() && self.skipped.is_empty()
+    }
+}
+
+///
+/// Deserializes a sequence like [`crate::seq::DeserializeSeedSeq`], but salvages as
+/// much data as possible if it is partially invalid.
+///
+/// More concretely, there are two kinds of failures:
+///  - An element is deserialized successfully, but rejected by the collector (which returns
+///    `Err`). In this case, the element is skipped, and deserialization continues with the
+///    next element.
+///  - Deserialization of an element fails. Since the state of the deserializer is unspecified
+///    afterwards, deserialization stops, and the error is returned together with the value
+///    built from the previous elements.
+///
+/// As for [`crate::seq::DeserializeSeedSeq`], the iterator producing the seeds must contain
+/// at least one more seed than the sequence has elements.
+///
+/// # Example
+/// ```
+/// # use feanor_serde::partial::*;
+/// # use feanor_serde::default_seed::seed_of;
+/// # use std::iter::repeat;
+/// let mut deserializer = serde_json::Deserializer::new(serde_json::de::StrRead::new("[1, -2, 3, \"x\", 5]"));
+/// let result = deserialize_seq_partial(
+///     &mut deserializer,
+///     repeat(seed_of::<i64>()),
+///     Vec::new(),
+///     |current, next| if next >= 0 { current.push(next); Ok(()) } else { Err(format!("negative value {}", next)) }
+/// );
+/// assert_eq!(vec![1, 3], result.value);
+/// assert_eq!(vec![(1, "negative value -2".to_owned())], result.skipped);
+/// assert!(result.error.is_some());
+/// ```
+///
+pub fn deserialize_seq_partial<'de, D, V, S, T, C>(deserializer: D, seeds: V, initial: T, collector: C) -> PartialResult<T, D::Error>
+    where D: Deserializer<'de>,
+        V: Iterator<Item = S>,
+        S: DeserializeSeed<'de>,
+        C: FnMut(&mut T, S::Value) -> Result<(), String>
+{
+    struct ResultVisitor<'a, 'de, V, S, T, C>
+        where V: Iterator<Item = S>,
+            S: DeserializeSeed<'de>,
+            C: FnMut(&mut T, S::Value) -> Result<(), String>
+    {
+        deserializer: PhantomData<&'de ()>,
+        seeds: V,
+        current: &'a RefCell<T>,
+        skipped: &'a RefCell<Vec<(usize, String)>>,
+        collector: C
+    }
+
+    impl<'a, 'de, V, S, T, C> Visitor<'de> for ResultVisitor<'a, 'de, V, S, T, C>
+        where V: Iterator<Item = S>,
+            S: DeserializeSeed<'de>,
+            C: FnMut(&mut T, S::Value) -> Result<(), String>
+    {
+        type Value = ();
+
+        fn expecting(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+            write!(f, "a sequence of elements")
+        }
+
+        fn visit_seq<B>(mut self, mut seq: B) -> Result<Self::Value, B::Error>
+            where B: SeqAccess<'de>
+        {
+            let mut index = 0;
+            for seed in self.seeds.by_ref() {
+                match seq.next_element_seed(seed)? {
+                    Some(el) => {
+                        if let Err(reason) = (self.collector)(&mut *self.current.borrow_mut(), el) {
+                            self.skipped.borrow_mut().push((index, reason));
+                        }
+                        index += 1;
+                    },
+                    None => return Ok(())
+                }
+            }
+            return Err(Error::custom(format!("ran out of seeds after {} elements", index)));
+        }
+    }
+
+    let current = RefCell::new(initial);
+    let skipped = RefCell::new(Vec::new());
+    let result = deserializer.deserialize_seq(ResultVisitor {
+        deserializer: PhantomData,
+        seeds: seeds,
+        current: &current,
+        skipped: &skipped,
+        collector: collector
+    });
+    return PartialResult {
+        value: current.into_inner(),
+        error: result.err(),
+        skipped: skipped.into_inner()
+    };
+}
+
+#[cfg(test)]
+use std::iter::repeat;
+
+#[cfg(test)]
+fn collect_positive(current: &mut Vec<i64>, next: i64) -> Result<(), String> {
+    if next > 0 {
+        current.push(next);
+        return Ok(());
+    } else {
+        return Err(format!("{} is not positive", next));
+    }
+}
+
+#[test]
+fn test_serde_json() {
+    let result = deserialize_seq_partial(&mut serde_json::Deserializer::from_str("[1, 2, 3]"), repeat(PhantomData::<i64>), Vec::new(), collect_positive);
+    assert!(result.is_complete());
+    assert_eq!(vec![1, 2, 3], result.value);
+
+    let result = deserialize_seq_partial(&mut serde_json::Deserializer::from_str("[1, 0, 3, -1]"), repeat(PhantomData::<i64>), Vec::new(), collect_positive);
+    assert!(result.error.is_none());
+    assert_eq!(vec![1, 3], result.value);
+    assert_eq!(vec![(1, "0 is not positive".to_owned()), (3, "-1 is not positive".to_owned())], result.skipped);
+
+    let result = deserialize_seq_partial(&mut serde_json::Deserializer::from_str("[1, 2, null, 4]"), repeat(PhantomData::<i64>), Vec::new(), collect_positive);
+    assert!(result.error.is_some());
+    assert_eq!(vec![1, 2], result.value);
+
+    let result = deserialize_seq_partial(&mut serde_json::Deserializer::from_str("[1, 2, 3]"), std::iter::repeat_n(PhantomData::<i64>, 2), Vec::new(), collect_positive);
+    assert!(result.error.is_some());
+    assert_eq!(vec![1, 2], result.value);
+
+    let result = deserialize_seq_partial(&mut serde_json::Deserializer::from_str("{}"), repeat(PhantomData::<i64>), Vec::new(), collect_positive);
+    assert!(result.error.is_some());
+    assert!(result.value.is_empty());
+}
+
+#[test]
+fn test_serde_postcard() {
+    let serialized = postcard::to_allocvec(&vec![1i64, -1, 2, 3]).unwrap();
+    let result = deserialize_seq_partial(
+        &mut postcard::Deserializer::from_flavor(postcard::de_flavors::Slice::new(&serialized[..(serialized.len() - 1)])),
+        repeat(PhantomData::<i64>),
+        Vec::new(),
+        collect_positive
+    );
+    assert!(result.error.is_some());
+    assert_eq!(vec![1, 2], result.value);
+    assert_eq!(1, result.skipped.len());
+}
@@ -0,0 +1,544 @@
+use std::cell::RefCell;
+use std::fmt::Display;
+
+use serde::de::{DeserializeSeed, EnumAccess, MapAccess, SeqAccess, VariantAccess, Visitor};
+use serde::Deserializer;
+
+///
+/// A single step in a [`Path`].
+///
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Segment {
+    ///
+    /// The element with the given index of a sequence or tuple.
+    ///
+    Seq { index: usize },
+    ///
+    /// The value associated to the given key of a map or struct.
+    ///
+    Map { key: String },
+    ///
+    /// The content of the given enum variant.
+    ///
+    Enum { variant: String },
+    ///
+    /// The value associated to a map key that could not be recorded.
+    ///
+    Unknown
+}
+
+impl Display for Segment {
+
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Segment::Seq { index } => write!(f, "[{}]", index),
+            Segment::Map { key } => write!(f, ".{}", key),
+            Segment::Enum { variant } => write!(f, ".{}", variant),
+            Segment::Unknown => write!(f, ".?")
+        }
+    }
+}
+
+///
+/// The location of a value within the serialized data, e.g. `.records[17].coeffs[3]`.
+///
+#[derive(Clone, Debug, PartialEq, Eq, Default)]
+pub struct Path {
+    segments: Vec<Segment>
+}
+
+impl Path {
+
+    pub(crate) fn from_segments(segments: Vec<Segment>) -> Self {
+        Self { segments: segments }
+    }
+
+    pub fn segments(&self) -> &[Segment] {
+        &self.segments
+    }
+}
+
+impl Display for Path {
+
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.segments.is_empty() {
+            return write!(f, ".");
+        }
+        for segment in &self.segments {
+            write!(f, "{}", segment)?;
+        }
+        return Ok(());
+    }
+}
+
+///
+/// An error produced by a deserializer, together with the [`Path`] of the value
+/// during whose deserialization it occured.
+///
+#[derive(Clone, Debug)]
+pub struct PathError<E> {
+    path: Path,
+    inner: E
+}
+
+impl<E> PathError<E> {
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    pub fn inner(&self) -> &E {
+        &self.inner
+    }
+
+    pub fn into_inner(self) -> E {
+        self.inner
+    }
+}
+
+impl<E> Display for PathError<E>
+    where E: Display
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "at {}: {}", self.path, self.inner)
+    }
+}
+
+impl<E> std::error::Error for PathError<E>
+    where E: std::error::Error
+{}
+
+///
+/// Deserializes a value using the given [`DeserializeSeed`], and if deserialization
+/// fails, reports the [`Path`] of the innermost value whose deserialization failed.
+///
+/// This works by wrapping the deserializer, so it does not require any cooperation
+/// of the [`DeserializeSeed`].
+///
+/// # Example
+/// ```
+/// # use feanor_serde::path::*;
+/// # use feanor_serde::seq::*;
+/// # use feanor_serde::default_seed::seed_of;
+/// # use std::iter::repeat;
+/// # use std::collections::BTreeMap;
+/// let mut deserializer = serde_json::Deserializer::new(serde_json::de::StrRead::new(r#"{ "records": [[1, 2], [3, "x"]] }"#));
+/// let error = deserialize_tracking_path(seed_of::<BTreeMap<String, Vec<Vec<i64>>>>(), &mut deserializer).unwrap_err();
+/// assert_eq!(".records[1][1]", error.path().to_string());
+/// ```
+///
+pub fn deserialize_tracking_path<'de, S, D>(seed: S, deserializer: D) -> Result<S::Value, PathError<D::Error>>
+    where S: DeserializeSeed<'de>,
+        D: Deserializer<'de>
+{
+    let track = Track { path: RefCell::new(None) };
+    let result = seed.deserialize(TrackedDeserializer {
+        de: deserializer,
+        chain: &Chain::Root,
+        track: &track,
+        capture: None
+    });
+    return result.map_err(|inner| PathError {
+        path: track.path.into_inner().unwrap_or_default(),
+        inner: inner
+    });
+}
+
+enum Chain<'a> {
+    Root,
+    Segment { parent: &'a Chain<'a>, segment: Segment }
+}
+
+impl<'a> Chain<'a> {
+
+    fn to_path(&self) -> Path {
+        let mut segments = Vec::new();
+        let mut current = self;
+        while let Chain::Segment { parent, segment } = current {
+            segments.push(segment.clone());
+            current = parent;
+        }
+        segments.reverse();
+        return Path { segments };
+    }
+}
+
+///
+/// Records the path of the first (i.e. innermost) error.
+///
+struct Track {
+    path: RefCell<Option<Path>>
+}
+
+impl Track {
+
+    fn trigger<E>(&self, chain: &Chain, error: E) -> E {
+        let mut path = self.path.borrow_mut();
+        if path.is_none() {
+            *path = Some(chain.to_path());
+        }
+        return error;
+    }
+}
+
+struct TrackedDeserializer<'a, 'b, D> {
+    de: D,
+    chain: &'a Chain<'a>,
+    track: &'b Track,
+    capture: Option<&'b RefCell<Option<String>>>
+}
+
+struct TrackedSeed<'a, 'b, S> {
+    seed: S,
+    chain: &'a Chain<'a>,
+    track: &'b Track,
+    capture: Option<&'b RefCell<Option<String>>>
+}
+
+impl<'a, 'b, 'de, S> DeserializeSeed<'de> for TrackedSeed<'a, 'b, S>
+    where S: DeserializeSeed<'de>
+{
+    type Value = S::Value;
+
+    fn deserialize<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+        where D: Deserializer<'de>
+    {
+        self.seed.deserialize(TrackedDeserializer { de: deserializer, chain: self.chain, track: self.track, capture: self.capture })
+    }
+}
+
+macro_rules! forward_deserialize {
+    ($($method:ident($($arg:ident: $type:ty),*)),*) => {
+        $(
+            fn $method<V>(self, $($arg: $type,)* visitor: V) -> Result<V::Value, Self::Error>
+                where V: Visitor<'de>
+            {
+                let (chain, track) = (self.chain, self.track);
+                self.de.$method($($arg,)* Wrap { visitor: visitor, chain: chain, track: track, capture: self.capture }).map_err(|e| track.trigger(chain, e))
+            }
+        )*
+    };
+}
+
+impl<'a, 'b, 'de, D> Deserializer<'de> for TrackedDeserializer<'a, 'b, D>
+    where D: Deserializer<'de>
+{
+    type Error = D::Error;
+
+    forward_deserialize!{
+        deserialize_any(), deserialize_bool(), deserialize_i8(), deserialize_i16(), deserialize_i32(), deserialize_i64(), deserialize_i128(),
+        deserialize_u8(), deserialize_u16(), deserialize_u32(), deserialize_u64(), deserialize_u128(), deserialize_f32(), deserialize_f64(),
+        deserialize_char(), deserialize_str(), deserialize_string(), deserialize_bytes(), deserialize_byte_buf(), deserialize_option(),
+        deserialize_unit(), deserialize_seq(), deserialize_map(), deserialize_identifier(), deserialize_ignored_any(),
+        deserialize_unit_struct(name: &'static str), deserialize_newtype_struct(name: &'static str), deserialize_tuple(len: usize),
+        deserialize_tuple_struct(name: &'static str, len: usize), deserialize_struct(name: &'static str, fields: &'static [&'static str]),
+        deserialize_enum(name: &'static str, variants: &'static [&'static str])
+    }
+
+    fn is_human_readable(&self) -> bool {
+        self.de.is_human_readable()
+    }
+}
+
+///
+/// Wraps a [`Visitor`], in order to wrap nested deserializers resp. accessors, and to
+/// record map keys and enum variants if `capture` is set.
+///
+struct Wrap<'a, 'b, X> {
+    visitor: X,
+    chain: &'a Chain<'a>,
+    track: &'b Track,
+    capture: Option<&'b RefCell<Option<String>>>
+}
+
+impl<'a, 'b, X> Wrap<'a, 'b, X> {
+
+    fn record<T>(&self, value: T)
+        where T: ToString
+    {
+        if let Some(capture) = self.capture {
+            *capture.borrow_mut() = Some(value.to_string());
+        }
+    }
+}
+
+macro_rules! forward_visit {
+    ($($method:ident($type:ty)),*) => {
+        $(
+            fn $method<E>(self, value: $type) -> Result<Self::Value, E>
+                where E: serde::de::Error
+            {
+                self.record(&value);
+                self.visitor.$method(value)
+            }
+        )*
+    };
+}
+
+impl<'a, 'b, 'de, X> Visitor<'de> for Wrap<'a, 'b, X>
+    where X: Visitor<'de>
+{
+    type Value = X::Value;
+
+    fn expecting(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        self.visitor.expecting(f)
+    }
+
+    forward_visit!{
+        visit_bool(bool), visit_i8(i8), visit_i16(i16), visit_i32(i32), visit_i64(i64), visit_i128(i128),
+        visit_u8(u8), visit_u16(u16), visit_u32(u32), visit_u64(u64), visit_u128(u128), visit_f32(f32), visit_f64(f64),
+        visit_char(char), visit_str(&str), visit_borrowed_str(&'de str), visit_string(String)
+    }
+
+    fn visit_bytes<E>(self, value: &[u8]) -> Result<Self::Value, E>
+        where E: serde::de::Error
+    {
+        self.record(String::from_utf8_lossy(value));
+        self.visitor.visit_bytes(value)
+    }
+
+    fn visit_borrowed_bytes<E>(self, value: &'de [u8]) -> Result<Self::Value, E>
+        where E: serde::de::Error
+    {
+        self.record(String::from_utf8_lossy(value));
+        self.visitor.visit_borrowed_bytes(value)
+    }
+
+    fn visit_byte_buf<E>(self, value: Vec<u8>) -> Result<Self::Value, E>
+        where E: serde::de::Error
+    {
+        self.record(String::from_utf8_lossy(&value));
+        self.visitor.visit_byte_buf(value)
+    }
+
+    fn visit_none<E>(self) -> Result<Self::Value, E>
+        where E: serde::de::Error
+    {
+        self.visitor.visit_none()
+    }
+
+    fn visit_unit<E>(self) -> Result<Self::Value, E>
+        where E: serde::de::Error
+    {
+        self.visitor.visit_unit()
+    }
+
+    fn visit_some<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+        where D: Deserializer<'de>
+    {
+        self.visitor.visit_some(TrackedDeserializer { de: deserializer, chain: self.chain, track: self.track, capture: None })
+    }
+
+    fn visit_newtype_struct<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+        where D: Deserializer<'de>
+    {
+        self.visitor.visit_newtype_struct(TrackedDeserializer { de: deserializer, chain: self.chain, track: self.track, capture: None })
+    }
+
+    fn visit_seq<A>(self, seq: A) -> Result<Self::Value, A::Error>
+        where A: SeqAccess<'de>
+    {
+        self.visitor.visit_seq(TrackedSeq { seq: seq, chain: self.chain, track: self.track, index: 0 })
+    }
+
+    fn visit_map<A>(self, map: A) -> Result<Self::Value, A::Error>
+        where A: MapAccess<'de>
+    {
+        self.visitor.visit_map(TrackedMap { map: map, chain: self.chain, track: self.track, key: RefCell::new(None) })
+    }
+
+    fn visit_enum<A>(self, data: A) -> Result<Self::Value, A::Error>
+        where A: EnumAccess<'de>
+    {
+        self.visitor.visit_enum(TrackedEnum { data: data, chain: self.chain, track: self.track })
+    }
+}
+
+struct TrackedSeq<'a, 'b, X> {
+    seq: X,
+    chain: &'a Chain<'a>,
+    track: &'b Track,
+    index: usize
+}
+
+impl<'a, 'b, 'de, X> SeqAccess<'de> for TrackedSeq<'a, 'b, X>
+    where X: SeqAccess<'de>
+{
+    type Error = X::Error;
+
+    fn next_element_seed<T>(&mut self, seed: T) -> Result<Option<T::Value>, Self::Error>
+        where T: DeserializeSeed<'de>
+    {
+        let chain = Chain::Segment { parent: self.chain, segment: Segment::Seq { index: self.index } };
+        let result = self.seq.next_element_seed(TrackedSeed { seed: seed, chain: &chain, track: self.track, capture: None }).map_err(|e| self.track.trigger(&chain, e))?;
+        self.index += 1;
+        return Ok(result);
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        self.seq.size_hint()
+    }
+}
+
+struct TrackedMap<'a, 'b, X> {
+    map: X,
+    chain: &'a Chain<'a>,
+    track: &'b Track,
+    key: RefCell<Option<String>>
+}
+
+impl<'a, 'b, 'de, X> MapAccess<'de> for TrackedMap<'a, 'b, X>
+    where X: MapAccess<'de>
+{
+    type Error = X::Error;
+
+    fn next_key_seed<K>(&mut self, seed: K) -> Result<Option<K::Value>, Self::Error>
+        where K: DeserializeSeed<'de>
+    {
+        *self.key.borrow_mut() = None;
+        let (chain, track) = (self.chain, self.track);
+        self.map.next_key_seed(TrackedSeed { seed: seed, chain: chain, track: track, capture: Some(&self.key) }).map_err(|e| track.trigger(chain, e))
+    }
+
+    fn next_value_seed<V>(&mut self, seed: V) -> Result<V::Value, Self::Error>
+        where V: DeserializeSeed<'de>
+    {
+        let segment = match self.key.borrow_mut().take() {
+            Some(key) => Segment::Map { key },
+            None => Segment::Unknown
+        };
+        let chain = Chain::Segment { parent: self.chain, segment: segment };
+        self.map.next_value_seed(TrackedSeed { seed: seed, chain: &chain, track: self.track, capture: None }).map_err(|e| self.track.trigger(&chain, e))
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        self.map.size_hint()
+    }
+}
+
+struct TrackedEnum<'a, 'b, X> {
+    data: X,
+    chain: &'a Chain<'a>,
+    track: &'b Track
+}
+
+impl<'a, 'b, 'de, X> EnumAccess<'de> for TrackedEnum<'a, 'b, X>
+    where X: EnumAccess<'de>
+{
+    type Error = X::Error;
+    type Variant = TrackedVariant<'a, 'b, X::Variant>;
+
+    fn variant_seed<V>(self, seed: V) -> Result<(V::Value, Self::Variant), Self::Error>
+        where V: DeserializeSeed<'de>
+    {
+        let variant = RefCell::new(None);
+        let (value, access) = self.data.variant_seed(TrackedSeed { seed: seed, chain: self.chain, track: self.track, capture: Some(&variant) }).map_err(|e| self.track.trigger(self.chain, e))?;
+        let segment = match variant.into_inner() {
+            Some(variant) => Segment::Enum { variant },
+            None => Segment::Unknown
+        };
+        return Ok((value, TrackedVariant { access: access, parent: self.chain, segment: segment, track: self.track }));
+    }
+}
+
+struct TrackedVariant<'a, 'b, X> {
+    access: X,
+    parent: &'a Chain<'a>,
+    segment: Segment,
+    track: &'b Track
+}
+
+impl<'a, 'b, 'de, X> VariantAccess<'de> for TrackedVariant<'a, 'b, X>
+    where X: VariantAccess<'de>
+{
+    type Error = X::Error;
+
+    fn unit_variant(self) -> Result<(), Self::Error> {
+        let chain = Chain::Segment { parent: self.parent, segment: self.segment };
+        self.access.unit_variant().map_err(|e| self.track.trigger(&chain, e))
+    }
+
+    fn newtype_variant_seed<T>(self, seed: T) -> Result<T::Value, Self::Error>
+        where T: DeserializeSeed<'de>
+    {
+        let chain = Chain::Segment { parent: self.parent, segment: self.segment };
+        self.access.newtype_variant_seed(TrackedSeed { seed: seed, chain: &chain, track: self.track, capture: None }).map_err(|e| self.track.trigger(&chain, e))
+    }
+
+    fn tuple_variant<V>(self, len: usize, visitor: V) -> Result<V::Value, Self::Error>
+        where V: Visitor<'de>
+    {
+        let chain = Chain::Segment { parent: self.parent, segment: self.segment };
+        self.access.tuple_variant(len, Wrap { visitor: visitor, chain: &chain, track: self.track, capture: None }).map_err(|e| self.track.trigger(&chain, e))
+    }
+
+    fn struct_variant<V>(self, fields: &'static [&'static str], visitor: V) -> Result<V::Value, Self::Error>
+        where V: Visitor<'de>
+    {
+        let chain = Chain::Segment { parent: self.parent, segment: self.segment };
+        self.access.struct_variant(fields, Wrap { visitor: visitor, chain: &chain, track: self.track, capture: None }).map_err(|e| self.track.trigger(&chain, e))
+    }
+}
+
+#[cfg(test)]
+use std::marker::PhantomData;
+#[cfg(test)]
+use std::collections::BTreeMap;
+
+#[cfg(test)]
+#[derive(serde::Deserialize, serde::Serialize, Debug, PartialEq)]
+enum Record {
+    Unit,
+    Coeffs { coeffs: Vec<i64> },
+    Pair(i64, Option<i64>)
+}
+
+#[test]
+fn test_serde_json() {
+    let deserialize = |json: &str| deserialize_tracking_path(PhantomData::<BTreeMap<String, Vec<Record>>>, &mut serde_json::Deserializer::from_str(json));
+
+    let data = r#"{"records":["Unit",{"Coeffs":{"coeffs":[1,2]}},{"Pair":[1,null]}]}"#;
+    assert_eq!(3, deserialize(data).unwrap()["records"].len());
+
+    let error = deserialize(r#"{"records":["Unit",{"Coeffs":{"coeffs":[1,2,"x"]}}]}"#).unwrap_err();
+    assert_eq!(".records[1].Coeffs.coeffs[2]", error.path().to_string());
+    assert!(error.to_string().starts_with("at .records[1].Coeffs.coeffs[2]: "));
+
+    let error = deserialize(r#"{"records":[{"Pair":[1,"x"]}]}"#).unwrap_err();
+    assert_eq!(".records[0].Pair[1]", error.path().to_string());
+
+    let error = deserialize(r#"{"records":["Other"]}"#).unwrap_err();
+    assert_eq!(".records[0]", error.path().to_string());
+
+    let error = deserialize(r#"{"records":[], "other": 1}"#).unwrap_err();
+    assert_eq!(".other", error.path().to_string());
+
+    let error = deserialize(r#"[]"#).unwrap_err();
+    assert_eq!(".", error.path().to_string());
+}
+
+#[test]
+fn test_serde_postcard() {
+    let data = vec![Record::Unit, Record::Coeffs { coeffs: vec![1, 2, 3] }, Record::Pair(1, Some(2))];
+    let serialized = postcard::to_allocvec(&data).unwrap();
+    let result = deserialize_tracking_path(PhantomData::<Vec<Record>>, &mut postcard::Deserializer::from_flavor(postcard::de_flavors::Slice::new(&serialized))).unwrap();
+    assert_eq!(data, result);
+
+    let error = deserialize_tracking_path(PhantomData::<Vec<Record>>, &mut postcard::Deserializer::from_flavor(postcard::de_flavors::Slice::new(&serialized[..(serialized.len() - 1)]))).unwrap_err();
+    // postcard identifies variants by their index
+    assert_eq!("[2].2[1]", error.path().to_string());
+}
+
+#[test]
+fn test_seed() {
+    use crate::seq::DeserializeSeedSeq;
+    use std::iter::{repeat, repeat_with};
+
+    let seed = DeserializeSeedSeq::new(
+        repeat_with(|| DeserializeSeedSeq::new(repeat(PhantomData::<i64>), 0, |current, next| current + next)),
+        Vec::new(),
+        |mut current, next| { current.push(next); current }
+    );
+    let error = deserialize_tracking_path(seed, &mut serde_json::Deserializer::from_str("[[1, 2], [3, 4, null]]")).unwrap_err();
+    assert_eq!("[1][2]", error.path().to_string());
+}
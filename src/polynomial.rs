@@ -0,0 +1,292 @@
+use serde::de::{DeserializeSeed, EnumAccess, SeqAccess, VariantAccess, Visitor};
+use serde::ser::{Serialize, Serializer};
+use serde::{Deserialize, Deserializer};
+
+use crate::seq::SerializableSeq;
+use crate::sparse::DeserializeSeedEntries;
+
+const VARIANTS: &[&str] = &["Dense", "Sparse"];
+
+#[derive(Deserialize)]
+#[serde(variant_identifier)]
+enum Mode {
+    Dense,
+    Sparse
+}
+
+///
+/// Wraps an [`Iterator`] over the coefficients `c0, c1, ...` of a polynomial, and
+/// implements [`Serialize`] by mapping it to an enum in the serde data model, which
+/// is either
+///  - the newtype variant `Dense` containing the sequence `[c0, c1, ...]` of all coefficients, or
+///  - the newtype variant `Sparse` containing the sequence `[(degree, coeff), ...]` of all
+///    coefficients for which `is_zero` returns `false`.
+///
+/// Which representation is used is determined by the flag `sparse`, which is usually
+/// chosen based on the context.
+///
+/// # Example
+/// ```
+/// # use feanor_serde::polynomial::*;
+/// let data = [1, 0, 0, 2];
+/// assert_eq!("{\"Dense\":[1,0,0,2]}", serde_json::to_string(&SerializablePolynomial::new(data.iter(), false, |c| **c == 0)).unwrap());
+/// assert_eq!("{\"Sparse\":[[0,1],[3,2]]}", serde_json::to_string(&SerializablePolynomial::new(data.iter(), true, |c| **c == 0)).unwrap());
+/// ```
+///
+pub struct SerializablePolynomial<I, F>
+    where I: Iterator + Clone,
+        F: Fn(&I::Item) -> bool
+{
+    data: I,
+    sparse: bool,
+    is_zero: F
+}
+
+impl<I, F> SerializablePolynomial<I, F>
+    where I: Iterator + Clone,
+        F: Fn(&I::Item) -> bool
+{
+    pub const fn new(data: I, sparse: bool, is_zero: F) -> Self {
+        Self { data, sparse, is_zero }
+    }
+}
+
+impl<I, F> Serialize for SerializablePolynomial<I, F>
+    where I: Iterator + Clone,
+        I::Item: Serialize,
+        F: Fn(&I::Item) -> bool
+{
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+        where S: Serializer
+    {
+        if self.sparse {
+            let entries = self.data.clone().enumerate().filter(|(_, c)| !(self.is_zero)(c));
+            serializer.serialize_newtype_variant("Polynomial", 1, VARIANTS[1], &SerializableSeq::new_with_len(entries.clone(), entries.count()))
+        } else {
+            serializer.serialize_newtype_variant("Polynomial", 0, VARIANTS[0], &SerializableSeq::new_with_len(self.data.clone(), self.data.clone().count()))
+        }
+    }
+}
+
+struct DeserializeSeedDense<'a, F, C, T> {
+    coeff_seed: &'a mut F,
+    collector: &'a mut C,
+    current: T
+}
+
+impl<'a, 'de, F, S, C, T> Visitor<'de> for DeserializeSeedDense<'a, F, C, T>
+    where F: FnMut(usize) -> S,
+        S: DeserializeSeed<'de>,
+        C: FnMut(T, usize, S::Value) -> T
+{
+    type Value = T;
+
+    fn expecting(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "a sequence of coefficients")
+    }
+
+    fn visit_seq<A>(self, mut seq: A) -> Result<Self::Value, A::Error>
+        where A: SeqAccess<'de>
+    {
+        let mut result = self.current;
+        let mut degree = 0;
+        while let Some(coeff) = seq.next_element_seed((self.coeff_seed)(degree))? {
+            result = (self.collector)(result, degree, coeff);
+            degree += 1;
+        }
+        return Ok(result);
+    }
+}
+
+impl<'a, 'de, F, S, C, T> DeserializeSeed<'de> for DeserializeSeedDense<'a, F, C, T>
+    where F: FnMut(usize) -> S,
+        S: DeserializeSeed<'de>,
+        C: FnMut(T, usize, S::Value) -> T
+{
+    type Value = T;
+
+    fn deserialize<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+        where D: Deserializer<'de>
+    {
+        deserializer.deserialize_seq(self)
+    }
+}
+
+///
+/// A [`DeserializeSeed`] that deserializes a polynomial that was serialized using
+/// [`SerializablePolynomial`], in either representation. The coefficient of degree `d`
+/// is deserialized using the [`DeserializeSeed`] returned by `coeff_seed(d)`, and each
+/// coefficient is then passed to `collector`, together with its degree.
+///
+/// In the sparse representation, only the nonzero coefficients are passed to `collector`,
+/// and deserialization fails if the degrees are not strictly increasing.
+///
+/// # Example
+/// ```
+/// # use feanor_serde::polynomial::*;
+/// # use feanor_serde::default_seed::seed_of;
+/// # use serde::de::DeserializeSeed;
+/// let deserialize_seed = || DeserializeSeedPolynomial::new(
+///     |_degree| seed_of::<i64>(),
+///     Vec::new(),
+///     |mut current, degree, coeff| { current.resize(degree + 1, 0); current[degree] = coeff; current }
+/// );
+/// let mut deserializer = serde_json::Deserializer::new(serde_json::de::StrRead::new("{\"Dense\":[1,0,0,2]}"));
+/// assert_eq!(vec![1, 0, 0, 2], deserialize_seed().deserialize(&mut deserializer).unwrap());
+/// let mut deserializer = serde_json::Deserializer::new(serde_json::de::StrRead::new("{\"Sparse\":[[0,1],[3,2]]}"));
+/// assert_eq!(vec![1, 0, 0, 2], deserialize_seed().deserialize(&mut deserializer).unwrap());
+/// ```
+///
+pub struct DeserializeSeedPolynomial<F, S, C, T>
+    where F: FnMut(usize) -> S
+{
+    coeff_seed: F,
+    initial: T,
+    collector: C
+}
+
+impl<'de, F, S, C, T> DeserializeSeedPolynomial<F, S, C, T>
+    where F: FnMut(usize) -> S,
+        S: DeserializeSeed<'de>,
+        C: FnMut(T, usize, S::Value) -> T
+{
+    pub const fn new(coeff_seed: F, initial: T, collector: C) -> Self {
+        Self { coeff_seed, initial, collector }
+    }
+}
+
+impl<'de, F, S, C, T> DeserializeSeed<'de> for DeserializeSeedPolynomial<F, S, C, T>
+    where F: FnMut(usize) -> S,
+        S: DeserializeSeed<'de>,
+        C: FnMut(T, usize, S::Value) -> T
+{
+    type Value = T;
+
+    fn deserialize<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+        where D: Deserializer<'de>
+    {
+        struct ResultVisitor<F, C, T> {
+            coeff_seed: F,
+            initial: T,
+            collector: C
+        }
+
+        impl<'de, F, S, C, T> Visitor<'de> for ResultVisitor<F, C, T>
+            where F: FnMut(usize) -> S,
+                S: DeserializeSeed<'de>,
+                C: FnMut(T, usize, S::Value) -> T
+        {
+            type Value = T;
+
+            fn expecting(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+                write!(f, "a dense or sparse polynomial")
+            }
+
+            fn visit_enum<A>(mut self, data: A) -> Result<Self::Value, A::Error>
+                where A: EnumAccess<'de>
+            {
+                let (mode, variant) = data.variant::<Mode>()?;
+                match mode {
+                    Mode::Dense => variant.newtype_variant_seed(DeserializeSeedDense {
+                        coeff_seed: &mut self.coeff_seed,
+                        collector: &mut self.collector,
+                        current: self.initial
+                    }),
+                    Mode::Sparse => variant.newtype_variant_seed(DeserializeSeedEntries {
+                        len: usize::MAX,
+                        value_seed: &mut self.coeff_seed,
+                        collector: &mut self.collector,
+                        current: self.initial
+                    })
+                }
+            }
+        }
+
+        return deserializer.deserialize_enum("Polynomial", VARIANTS, ResultVisitor {
+            coeff_seed: self.coeff_seed,
+            initial: self.initial,
+            collector: self.collector
+        });
+    }
+}
+
+#[cfg(test)]
+use std::marker::PhantomData;
+
+#[cfg(test)]
+fn testdata() -> Vec<Vec<i64>> {
+    vec![
+        Vec::new(),
+        vec![0],
+        vec![1],
+        vec![0, 0, 0, 5],
+        vec![1, 0, -1, 0, 0, 0, 0, 2]
+    ]
+}
+
+#[cfg(test)]
+fn deserialize_seed_testdata<'de>() -> impl DeserializeSeed<'de, Value = Vec<i64>> {
+    DeserializeSeedPolynomial::new(
+        |_| PhantomData::<i64>,
+        Vec::new(),
+        |mut current, degree, coeff| { current.resize(degree + 1, 0); current[degree] = coeff; current }
+    )
+}
+
+#[cfg(test)]
+fn trim(mut data: Vec<i64>) -> Vec<i64> {
+    while data.last() == Some(&0) {
+        data.pop();
+    }
+    return data;
+}
+
+#[test]
+fn test_serde_postcard() {
+    for data in testdata() {
+        for sparse in [false, true] {
+            let serialized = postcard::to_allocvec(&SerializablePolynomial::new(data.iter(), sparse, |c| **c == 0)).unwrap();
+            let result = deserialize_seed_testdata().deserialize(
+                &mut postcard::Deserializer::from_flavor(postcard::de_flavors::Slice::new(&serialized))
+            ).unwrap();
+            assert_eq!(if sparse { trim(data.clone()) } else { data.clone() }, result);
+        }
+    }
+}
+
+#[test]
+fn test_serde_json() {
+    for data in testdata() {
+        for sparse in [false, true] {
+            let serialized = serde_json::to_string(&SerializablePolynomial::new(data.iter(), sparse, |c| **c == 0)).unwrap();
+            let result = deserialize_seed_testdata().deserialize(
+                &mut serde_json::Deserializer::from_str(&serialized)
+            ).unwrap();
+            assert_eq!(if sparse { trim(data.clone()) } else { data.clone() }, result);
+        }
+    }
+    assert!(deserialize_seed_testdata().deserialize(&mut serde_json::Deserializer::from_str("{\"Sparse\":[[3,1],[1,2]]}")).is_err());
+    assert!(deserialize_seed_testdata().deserialize(&mut serde_json::Deserializer::from_str("{\"Other\":[1]}")).is_err());
+}
+
+#[test]
+fn test_coeff_seed_degree() {
+    struct DeserializeSeedOffset(i64);
+
+    impl<'de> DeserializeSeed<'de> for DeserializeSeedOffset {
+        type Value = i64;
+
+        fn deserialize<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+            where D: Deserializer<'de>
+        {
+            Ok(<i64 as serde::Deserialize>::deserialize(deserializer)? + self.0)
+        }
+    }
+
+    for json in ["{\"Dense\":[0,0,0]}", "{\"Sparse\":[[0,0],[1,0],[2,0]]}"] {
+        let result = DeserializeSeedPolynomial::new(|d| DeserializeSeedOffset(10 * d as i64), Vec::new(), |mut current, _, coeff| { current.push(coeff); current }).deserialize(
+            &mut serde_json::Deserializer::from_str(json)
+        ).unwrap();
+        assert_eq!(vec![0, 10, 20], result);
+    }
+}
@@ -0,0 +1,119 @@
+use serde::de::DeserializeSeed;
+use serde::ser::Serialize;
+
+use ::postcard::de_flavors::Slice;
+use ::postcard::Deserializer;
+
+///
+/// Deserializes a value from the given postcard-encoded bytes, using the given
+/// [`DeserializeSeed`]. The unused portion (if any) of the byte slice is ignored.
+///
+/// This is the analogue of [`::postcard::from_bytes()`] for [`DeserializeSeed`]s.
+///
+/// # Example
+/// ```
+/// # use feanor_serde::seq::*;
+/// # use feanor_serde::default_seed::seed_of;
+/// # use std::iter::repeat;
+/// let serialized = feanor_serde::postcard::to_allocvec(&SerializableSeq::new_with_len([1, 3, 5].into_iter(), 3)).unwrap();
+/// let deserialize_seed = DeserializeSeedSeq::new(
+///     repeat(seed_of::<i64>()),
+///     Vec::new(),
+///     |mut current, next| { current.push(next); current }
+/// );
+/// assert_eq!(vec![1, 3, 5], feanor_serde::postcard::from_slice_seeded(deserialize_seed, &serialized).unwrap());
+/// ```
+///
+pub fn from_slice_seeded<'de, S>(seed: S, data: &'de [u8]) -> ::postcard::Result<S::Value>
+    where S: DeserializeSeed<'de>
+{
+    let mut deserializer = Deserializer::from_flavor(Slice::new(data));
+    return seed.deserialize(&mut deserializer);
+}
+
+///
+/// Deserializes a value from the given postcard-encoded bytes, using the given
+/// [`DeserializeSeed`], and returns it together with the unused portion of the
+/// byte slice.
+///
+/// This is the analogue of [`::postcard::take_from_bytes()`] for [`DeserializeSeed`]s.
+///
+pub fn take_from_slice_seeded<'de, S>(seed: S, data: &'de [u8]) -> ::postcard::Result<(S::Value, &'de [u8])>
+    where S: DeserializeSeed<'de>
+{
+    let mut deserializer = Deserializer::from_flavor(Slice::new(data));
+    let result = seed.deserialize(&mut deserializer)?;
+    return Ok((result, deserializer.finalize()?));
+}
+
+///
+/// Serializes the given value into a newly allocated [`Vec`].
+///
+/// This is just [`::postcard::to_allocvec()`], but accepts the serializable wrappers
+/// of this crate (like [`crate::seq::SerializableSeq`]) by value, so that they can
+/// be created inline.
+///
+pub fn to_allocvec<T>(value: T) -> ::postcard::Result<Vec<u8>>
+    where T: Serialize
+{
+    ::postcard::to_allocvec(&value)
+}
+
+///
+/// Serializes the given value into the given buffer, and returns the used part
+/// of the buffer.
+///
+/// This is just [`::postcard::to_slice()`], but accepts the serializable wrappers
+/// of this crate (like [`crate::seq::SerializableSeq`]) by value, so that they can
+/// be created inline.
+///
+pub fn to_slice<T>(value: T, buffer: &mut [u8]) -> ::postcard::Result<&mut [u8]>
+    where T: Serialize
+{
+    ::postcard::to_slice(&value, buffer)
+}
+
+#[cfg(test)]
+use std::marker::PhantomData;
+#[cfg(test)]
+use std::iter::repeat;
+#[cfg(test)]
+use crate::seq::{SerializableSeq, DeserializeSeedSeq};
+
+#[test]
+fn test_from_slice_seeded() {
+    let data = vec![1, 3, 4];
+    let serialized = to_allocvec(SerializableSeq::new_with_len(data.iter().copied(), data.len())).unwrap();
+    let result = from_slice_seeded(
+        DeserializeSeedSeq::new(repeat(PhantomData::<i64>), Vec::new(), |mut current, next| { current.push(next); current }),
+        &serialized
+    ).unwrap();
+    assert_eq!(data, result);
+}
+
+#[test]
+fn test_take_from_slice_seeded() {
+    let data = vec![1, 3, 4];
+    let mut serialized = to_allocvec(SerializableSeq::new_with_len(data.iter().copied(), data.len())).unwrap();
+    serialized.extend(to_allocvec(42i64).unwrap());
+    let (result, remaining) = take_from_slice_seeded(
+        DeserializeSeedSeq::new(repeat(PhantomData::<i64>), Vec::new(), |mut current, next| { current.push(next); current }),
+        &serialized
+    ).unwrap();
+    assert_eq!(data, result);
+    let (result, remaining) = take_from_slice_seeded(PhantomData::<i64>, remaining).unwrap();
+    assert_eq!(42, result);
+    assert!(remaining.is_empty());
+}
+
+#[test]
+fn test_to_slice() {
+    let data = [1, 3, 4];
+    let mut buffer = [0u8; 16];
+    let expected = to_allocvec(SerializableSeq::new_with_len(data.iter().copied(), data.len())).unwrap();
+    let serialized = to_slice(SerializableSeq::new_with_len(data.iter().copied(), data.len()), &mut buffer).unwrap();
+    assert_eq!(&expected[..], &serialized[..]);
+
+    let mut buffer = [0u8; 2];
+    assert!(to_slice(SerializableSeq::new_with_len(data.iter().copied(), data.len()), &mut buffer).is_err());
+}
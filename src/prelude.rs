@@ -0,0 +1,52 @@
+//!
+//! Re-exports the most commonly used items of this crate, together with the serde traits
+//! they are used with, so that a single `use feanor_serde::prelude::*;` suffices in most cases.
+//!
+//! # Example
+//! ```
+//! use feanor_serde::prelude::*;
+//!
+//! let serialized = serde_json::to_string(&SerializableSeq::new([1, 2, 3].into_iter())).unwrap();
+//! let seed = SeqSeedBuilder::new(seed_of::<i64>).fold(0, |current, next| current + next);
+//! assert_eq!(6, seed.deserialize(&mut serde_json::Deserializer::from_str(&serialized)).unwrap());
+//! ```
+//!
+
+pub use serde::de::DeserializeSeed;
+pub use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+pub use crate::seq::{SerializableSeq, DeserializeSeedSeq, SeqSeedBuilder};
+pub use crate::map::{SerializableMap, DeserializeSeedMap, SerializableMapAsSeq, DeserializeSeedMapAsSeq};
+pub use crate::newtype_struct::{SerializableNewtypeStruct, DeserializeSeedNewtypeStruct};
+pub use crate::named_value::{SerializableNamedValue, DeserializeSeedNamedValue};
+pub use crate::dependent_tuple::DeserializeSeedDependentTuple;
+pub use crate::option::{SerializableOption, DeserializeSeedOption};
+pub use crate::result::{SerializableResult, DeserializeSeedResult};
+pub use crate::human_readable::HumanReadableOr;
+pub use crate::limits::Limits;
+pub use crate::ext::DeserializeSeedExt;
+pub use crate::owned::DeserializeSeedOwned;
+pub use crate::default_seed::{DefaultSeed, seed_of, ignore_context};
+pub use crate::seeded_default::SeededDefault;
+pub use crate::seed_factory::SeedFactory;
+pub use crate::into_seed::IntoSeed;
+pub use crate::{impl_deserialize_seed_for_dependent_struct, impl_deserialize_seed_for_dependent_enum};
+
+#[cfg(test)]
+struct FooDeserializeSeed;
+
+#[cfg(test)]
+impl_deserialize_seed_for_dependent_struct!{
+    pub struct Foo<'de> using FooDeserializeSeed {
+        a: Vec<i64>: |_| SeqSeedBuilder::new(seed_of::<i64>).max_len(2).collect_vec(),
+        b: String: ignore_context
+    }
+}
+
+#[test]
+fn test_prelude() {
+    let foo = FooDeserializeSeed.deserialize(&mut serde_json::Deserializer::from_str("{\"a\": [1, 2], \"b\": \"x\"}")).unwrap();
+    assert_eq!(vec![1, 2], foo.a);
+    assert_eq!("x", foo.b);
+    assert!(FooDeserializeSeed.deserialize(&mut serde_json::Deserializer::from_str("{\"a\": [1, 2, 3], \"b\": \"x\"}")).is_err());
+}
@@ -0,0 +1,343 @@
+use serde::de::{DeserializeSeed, EnumAccess, Error, MapAccess, SeqAccess, Visitor};
+use serde::Deserializer;
+
+use crate::into_seed::IntoSeed;
+
+///
+/// The progress of a deserialization, as passed to the callback of [`DeserializeSeedProgress`]
+/// resp. [`crate::chunked::deserialize_chunked_with_progress()`].
+///
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Progress {
+    elements: usize,
+    size_hint: Option<usize>,
+    bytes: Option<(usize, usize)>
+}
+
+impl Progress {
+
+    pub(crate) const fn new(elements: usize, size_hint: Option<usize>, bytes: Option<(usize, usize)>) -> Self {
+        Self { elements, size_hint, bytes }
+    }
+
+    ///
+    /// Returns the number of elements resp. map entries deserialized so far.
+    ///
+    pub fn elements(&self) -> usize {
+        self.elements
+    }
+
+    ///
+    /// Returns the total number of elements resp. map entries, if the deserializer
+    /// reports it up front.
+    ///
+    pub fn size_hint(&self) -> Option<usize> {
+        self.size_hint
+    }
+
+    ///
+    /// Returns the number of bytes processed so far, if this is known.
+    ///
+    pub fn bytes_read(&self) -> Option<usize> {
+        self.bytes.map(|(read, _)| read)
+    }
+
+    ///
+    /// Returns the total number of bytes to process, if this is known.
+    ///
+    pub fn total_bytes(&self) -> Option<usize> {
+        self.bytes.map(|(_, total)| total)
+    }
+}
+
+///
+/// Wraps a [`DeserializeSeed`], and deserializes in the same way, but calls the given
+/// callback every `every` elements of the outermost sequence resp. entries of the outermost
+/// map, and once more when the end of the sequence resp. map is reached.
+///
+/// Nested sequences and maps are not counted, so when deserializing e.g. a large list of
+/// records with [`crate::seq::DeserializeSeedSeq`], the callback reports the number of
+/// records deserialized so far. Sequences and maps inside an [`Option`] or a newtype
+/// struct count as outermost.
+///
+/// # Example
+/// ```
+/// # use feanor_serde::progress::*;
+/// # use feanor_serde::ext::*;
+/// # use feanor_serde::default_seed::seed_of;
+/// # use serde::de::DeserializeSeed;
+/// let serialized = postcard::to_allocvec(&vec![vec![1, 2]; 5]).unwrap();
+/// let mut reported = Vec::new();
+/// let seed = seed_of::<Vec<Vec<i64>>>().with_progress(2, |progress| reported.push((progress.elements(), progress.size_hint())));
+/// assert_eq!(vec![vec![1, 2]; 5], seed.deserialize(&mut postcard::Deserializer::from_bytes(&serialized)).unwrap());
+/// assert_eq!(vec![(2, Some(5)), (4, Some(5)), (5, Some(5))], reported);
+/// ```
+///
+pub struct DeserializeSeedProgress<S, F>
+    where F: FnMut(Progress)
+{
+    seed: S,
+    every: usize,
+    callback: F
+}
+
+impl<S, F> DeserializeSeedProgress<S, F>
+    where F: FnMut(Progress)
+{
+    ///
+    /// Creates a new [`DeserializeSeedProgress`], see also [`crate::ext::DeserializeSeedExt::with_progress()`].
+    ///
+    /// # Panics
+    ///
+    /// Panics if `every` is zero.
+    ///
+    pub fn new<'de, I, M>(seed: I, every: usize, callback: F) -> Self
+        where I: IntoSeed<'de, M, Seed = S>
+    {
+        assert!(every > 0);
+        Self { seed: seed.into_seed(), every, callback }
+    }
+}
+
+impl<'de, S, F> DeserializeSeed<'de> for DeserializeSeedProgress<S, F>
+    where S: DeserializeSeed<'de>,
+        F: FnMut(Progress)
+{
+    type Value = S::Value;
+
+    fn deserialize<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+        where D: Deserializer<'de>
+    {
+        self.seed.deserialize(ProgressDeserializer { de: deserializer, every: self.every, callback: self.callback })
+    }
+}
+
+struct ProgressDeserializer<D, F> {
+    de: D,
+    every: usize,
+    callback: F
+}
+
+macro_rules! forward_deserialize {
+    ($($method:ident($($arg:ident: $type:ty),*)),*) => {
+        $(
+            fn $method<V>(self, $($arg: $type,)* visitor: V) -> Result<V::Value, Self::Error>
+                where V: Visitor<'de>
+            {
+                self.de.$method($($arg,)* ProgressVisitor { visitor: visitor, every: self.every, callback: self.callback })
+            }
+        )*
+    };
+}
+
+impl<'de, D, F> Deserializer<'de> for ProgressDeserializer<D, F>
+    where D: Deserializer<'de>,
+        F: FnMut(Progress)
+{
+    type Error = D::Error;
+
+    forward_deserialize!{
+        deserialize_any(), deserialize_bool(), deserialize_i8(), deserialize_i16(), deserialize_i32(), deserialize_i64(), deserialize_i128(),
+        deserialize_u8(), deserialize_u16(), deserialize_u32(), deserialize_u64(), deserialize_u128(), deserialize_f32(), deserialize_f64(),
+        deserialize_char(), deserialize_str(), deserialize_string(), deserialize_bytes(), deserialize_byte_buf(), deserialize_option(),
+        deserialize_unit(), deserialize_seq(), deserialize_map(), deserialize_identifier(), deserialize_ignored_any(),
+        deserialize_unit_struct(name: &'static str), deserialize_newtype_struct(name: &'static str), deserialize_tuple(len: usize),
+        deserialize_tuple_struct(name: &'static str, len: usize), deserialize_struct(name: &'static str, fields: &'static [&'static str]),
+        deserialize_enum(name: &'static str, variants: &'static [&'static str])
+    }
+
+    fn is_human_readable(&self) -> bool {
+        self.de.is_human_readable()
+    }
+}
+
+struct ProgressVisitor<X, F> {
+    visitor: X,
+    every: usize,
+    callback: F
+}
+
+macro_rules! forward_visit {
+    ($($method:ident($type:ty)),*) => {
+        $(
+            fn $method<E>(self, value: $type) -> Result<Self::Value, E>
+                where E: Error
+            {
+                self.visitor.$method(value)
+            }
+        )*
+    };
+}
+
+impl<'de, X, F> Visitor<'de> for ProgressVisitor<X, F>
+    where X: Visitor<'de>,
+        F: FnMut(Progress)
+{
+    type Value = X::Value;
+
+    fn expecting(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        self.visitor.expecting(f)
+    }
+
+    forward_visit!{
+        visit_bool(bool), visit_i8(i8), visit_i16(i16), visit_i32(i32), visit_i64(i64), visit_i128(i128),
+        visit_u8(u8), visit_u16(u16), visit_u32(u32), visit_u64(u64), visit_u128(u128), visit_f32(f32), visit_f64(f64),
+        visit_char(char), visit_str(&str), visit_borrowed_str(&'de str), visit_string(String),
+        visit_bytes(&[u8]), visit_borrowed_bytes(&'de [u8]), visit_byte_buf(Vec<u8>)
+    }
+
+    fn visit_none<E>(self) -> Result<Self::Value, E>
+        where E: Error
+    {
+        self.visitor.visit_none()
+    }
+
+    fn visit_unit<E>(self) -> Result<Self::Value, E>
+        where E: Error
+    {
+        self.visitor.visit_unit()
+    }
+
+    fn visit_some<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+        where D: Deserializer<'de>
+    {
+        self.visitor.visit_some(ProgressDeserializer { de: deserializer, every: self.every, callback: self.callback })
+    }
+
+    fn visit_newtype_struct<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+        where D: Deserializer<'de>
+    {
+        self.visitor.visit_newtype_struct(ProgressDeserializer { de: deserializer, every: self.every, callback: self.callback })
+    }
+
+    fn visit_seq<A>(self, seq: A) -> Result<Self::Value, A::Error>
+        where A: SeqAccess<'de>
+    {
+        self.visitor.visit_seq(ProgressAccess { access: seq, every: self.every, callback: self.callback, count: 0 })
+    }
+
+    fn visit_map<A>(self, map: A) -> Result<Self::Value, A::Error>
+        where A: MapAccess<'de>
+    {
+        self.visitor.visit_map(ProgressAccess { access: map, every: self.every, callback: self.callback, count: 0 })
+    }
+
+    fn visit_enum<A>(self, data: A) -> Result<Self::Value, A::Error>
+        where A: EnumAccess<'de>
+    {
+        self.visitor.visit_enum(data)
+    }
+}
+
+struct ProgressAccess<X, F> {
+    access: X,
+    every: usize,
+    callback: F,
+    count: usize
+}
+
+impl<X, F> ProgressAccess<X, F>
+    where F: FnMut(Progress)
+{
+    ///
+    /// Counts an element if there is one, and calls the callback if required.
+    ///
+    fn record(&mut self, has_element: bool, size_hint: Option<usize>) {
+        if has_element {
+            self.count += 1;
+            if self.count.is_multiple_of(self.every) {
+                (self.callback)(Progress::new(self.count, size_hint, None));
+            }
+        } else if !self.count.is_multiple_of(self.every) {
+            (self.callback)(Progress::new(self.count, size_hint, None));
+        }
+    }
+}
+
+impl<'de, X, F> SeqAccess<'de> for ProgressAccess<X, F>
+    where X: SeqAccess<'de>,
+        F: FnMut(Progress)
+{
+    type Error = X::Error;
+
+    fn next_element_seed<T>(&mut self, seed: T) -> Result<Option<T::Value>, Self::Error>
+        where T: DeserializeSeed<'de>
+    {
+        let size_hint = self.access.size_hint().map(|remaining| remaining + self.count);
+        let result = self.access.next_element_seed(seed)?;
+        self.record(result.is_some(), size_hint);
+        return Ok(result);
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        self.access.size_hint()
+    }
+}
+
+impl<'de, X, F> MapAccess<'de> for ProgressAccess<X, F>
+    where X: MapAccess<'de>,
+        F: FnMut(Progress)
+{
+    type Error = X::Error;
+
+    fn next_key_seed<K>(&mut self, seed: K) -> Result<Option<K::Value>, Self::Error>
+        where K: DeserializeSeed<'de>
+    {
+        let size_hint = self.access.size_hint().map(|remaining| remaining + self.count);
+        let result = self.access.next_key_seed(seed)?;
+        if result.is_none() {
+            self.record(false, size_hint);
+        }
+        return Ok(result);
+    }
+
+    fn next_value_seed<V>(&mut self, seed: V) -> Result<V::Value, Self::Error>
+        where V: DeserializeSeed<'de>
+    {
+        let size_hint = self.access.size_hint().map(|remaining| remaining + self.count + 1);
+        let result = self.access.next_value_seed(seed)?;
+        self.record(true, size_hint);
+        return Ok(result);
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        self.access.size_hint()
+    }
+}
+
+#[cfg(test)]
+use crate::default_seed::seed_of;
+#[cfg(test)]
+use crate::seq::DeserializeSeedSeq;
+#[cfg(test)]
+use std::collections::BTreeMap;
+
+#[test]
+fn test_serde_json() {
+    let mut reported = Vec::new();
+    let seed = DeserializeSeedProgress::new(seed_of::<Vec<i64>>(), 2, |progress| reported.push((progress.elements(), progress.size_hint())));
+    assert_eq!(vec![1, 2, 3, 4], seed.deserialize(&mut serde_json::Deserializer::from_str("[1, 2, 3, 4]")).unwrap());
+    assert_eq!(vec![(2, None), (4, None)], reported);
+
+    let mut reported = Vec::new();
+    let seed = DeserializeSeedProgress::new(seed_of::<Option<BTreeMap<String, Vec<i64>>>>(), 1, |progress| reported.push(progress.elements()));
+    let result = seed.deserialize(&mut serde_json::Deserializer::from_str(r#"{"a": [1, 2, 3], "b": []}"#)).unwrap().unwrap();
+    assert_eq!(2, result.len());
+    assert_eq!(vec![1, 2], reported);
+}
+
+#[test]
+fn test_serde_postcard() {
+    let data = (0..10).map(|i| (i, vec![i; i as usize])).collect::<BTreeMap<i64, Vec<i64>>>();
+    let serialized = postcard::to_allocvec(&data).unwrap();
+    let mut reported = Vec::new();
+    let seed = DeserializeSeedProgress::new(seed_of::<BTreeMap<i64, Vec<i64>>>(), 4, |progress| reported.push((progress.elements(), progress.size_hint())));
+    assert_eq!(data, seed.deserialize(&mut postcard::Deserializer::from_bytes(&serialized)).unwrap());
+    assert_eq!(vec![(4, Some(10)), (8, Some(10)), (10, Some(10))], reported);
+
+    let serialized = postcard::to_allocvec(&[1i64, 2, 3][..]).unwrap();
+    let mut reported = Vec::new();
+    let seed = DeserializeSeedProgress::new(DeserializeSeedSeq::new(std::iter::repeat(seed_of::<i64>()), 0, |current, next| current + next), 1, |progress| reported.push(progress));
+    assert_eq!(6, seed.deserialize(&mut postcard::Deserializer::from_bytes(&serialized)).unwrap());
+    assert_eq!(vec![Progress::new(1, Some(3), None), Progress::new(2, Some(3), None), Progress::new(3, Some(3), None)], reported);
+}
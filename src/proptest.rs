@@ -0,0 +1,144 @@
+use ::proptest::prelude::*;
+use ::proptest::collection::vec;
+use ::proptest::sample::select;
+
+use crate::mock::{MockValue, MockVariant};
+
+///
+/// The maximal nesting depth of [`MockValue`]s generated by [`mock_value()`].
+///
+pub const MAX_DEPTH: u32 = 4;
+
+///
+/// The maximal number of elements of sequences, maps and strings in [`MockValue`]s generated
+/// by [`mock_value()`].
+///
+pub const MAX_LEN: usize = 8;
+
+///
+/// A [`Strategy`] generating arbitrary [`MockValue`]s of nesting depth at most [`MAX_DEPTH`].
+///
+/// Map keys and enum variants are drawn from short strings and small indices, so that they
+/// match the fields resp. variants of a struct resp. enum reasonably often.
+///
+pub fn mock_value() -> impl Strategy<Value = MockValue> {
+    let leaf = prop_oneof![
+        any::<bool>().prop_map(MockValue::Bool),
+        any::<i64>().prop_map(MockValue::I64),
+        any::<u64>().prop_map(MockValue::U64),
+        any::<f64>().prop_map(MockValue::F64),
+        any::<char>().prop_map(MockValue::Char),
+        identifier_str().prop_map(MockValue::Str),
+        vec(any::<u8>(), 0..=MAX_LEN).prop_map(MockValue::Bytes),
+        Just(MockValue::Unit),
+        Just(MockValue::None)
+    ];
+    leaf.prop_recursive(MAX_DEPTH, 64, MAX_LEN as u32, |inner| prop_oneof![
+        inner.clone().prop_map(MockValue::some),
+        inner.clone().prop_map(MockValue::newtype_struct),
+        vec(inner.clone(), 0..=MAX_LEN).prop_map(MockValue::Seq),
+        vec((identifier(), inner.clone()), 0..=MAX_LEN).prop_map(MockValue::Map),
+        (identifier(), mock_variant(inner)).prop_map(|(variant, content)| MockValue::Enum(Box::new(variant), content))
+    ])
+}
+
+///
+/// A [`Strategy`] generating [`MockVariant`]s whose content is generated by `content`.
+///
+pub fn mock_variant<S>(content: S) -> impl Strategy<Value = MockVariant>
+    where S: Strategy<Value = MockValue> + Clone
+{
+    prop_oneof![
+        Just(MockVariant::Unit),
+        content.clone().prop_map(|value| MockVariant::Newtype(Box::new(value))),
+        vec(content.clone(), 0..=MAX_LEN).prop_map(MockVariant::Tuple),
+        vec((identifier(), content), 0..=MAX_LEN).prop_map(MockVariant::Struct)
+    ]
+}
+
+///
+/// A [`Strategy`] generating [`MockValue`]s that are "near-valid" w.r.t. the given value,
+/// namely the value itself and the results of applying one or two of the modifications of
+/// [`MockValue::mutations()`] to it.
+///
+/// # Example
+/// ```
+/// # use feanor_serde::impl_deserialize_seed_for_dependent_struct;
+/// # use feanor_serde::proptest::*;
+/// # use feanor_serde::mock::*;
+/// # use feanor_serde::default_seed::seed_of;
+/// # use serde::de::DeserializeSeed;
+/// # use proptest::prelude::*;
+/// struct PointDeserializeSeed;
+/// impl_deserialize_seed_for_dependent_struct!{
+///     pub struct Point<'de> using PointDeserializeSeed {
+///         x: i64: |_| seed_of::<i64>(),
+///         y: i64: |_| seed_of::<i64>()
+///     }
+/// }
+/// proptest!(|(input in near_valid(MockValue::map([("x", 1i64), ("y", 2i64)])))| {
+///     if let Ok(point) = PointDeserializeSeed.deserialize(MockDeserializer::new(input)) {
+///         prop_assert_eq!((1, 2), (point.x, point.y));
+///     }
+/// });
+/// ```
+///
+pub fn near_valid(value: MockValue) -> impl Strategy<Value = MockValue> {
+    let mut candidates = value.mutations();
+    candidates.push(value);
+    select(candidates).prop_flat_map(|value| {
+        let mut candidates = value.mutations();
+        candidates.push(value);
+        select(candidates)
+    })
+}
+
+fn identifier_str() -> impl Strategy<Value = String> {
+    vec(select(&['a', 'b', 'c', 'd', 'e'][..]), 0..=MAX_LEN).prop_map(|chars| chars.into_iter().collect())
+}
+
+fn identifier() -> impl Strategy<Value = MockValue> {
+    prop_oneof![
+        identifier_str().prop_map(MockValue::Str),
+        identifier_str().prop_map(|name| MockValue::Bytes(name.into_bytes())),
+        (0..=(MAX_LEN as u64)).prop_map(MockValue::U64)
+    ]
+}
+
+#[cfg(test)]
+use std::marker::PhantomData;
+#[cfg(test)]
+use serde::de::DeserializeSeed;
+#[cfg(test)]
+use crate::mock::MockDeserializer;
+#[cfg(test)]
+use crate::impl_deserialize_seed_for_dependent_enum;
+
+#[cfg(test)]
+struct DeserializeSeedShape;
+
+#[cfg(test)]
+impl_deserialize_seed_for_dependent_enum!{
+    pub enum Shape<'de> using DeserializeSeedShape {
+        Circle(f64): |_| PhantomData::<f64>,
+        Rect((u32, u32)): |_| PhantomData::<(u32, u32)>
+    }
+}
+
+#[cfg(test)]
+proptest! {
+
+    #[test]
+    fn test_macro_seed_on_mock_value(input in mock_value()) {
+        let _ = DeserializeSeedShape.deserialize(MockDeserializer::new(input));
+    }
+
+    #[test]
+    fn test_macro_seed_on_near_valid(input in near_valid(MockValue::newtype_variant("Rect", MockValue::seq([3u32, 4u32])))) {
+        let is_valid = input == MockValue::newtype_variant("Rect", MockValue::seq([3u32, 4u32]));
+        let result = DeserializeSeedShape.deserialize(MockDeserializer::new(input));
+        if is_valid {
+            prop_assert!(matches!(result, Ok(Shape::Rect(((3, 4), _)))));
+        }
+    }
+}
@@ -0,0 +1,788 @@
+use std::borrow::Cow;
+use std::marker::PhantomData;
+
+use serde::de::{DeserializeSeed, Error, IgnoredAny, SeqAccess, Visitor};
+use serde::ser::{Serialize, SerializeTuple, Serializer};
+use serde::Deserializer;
+
+///
+/// The byte order used by [`SerializableRawBytes`] to encode the elements.
+///
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub enum Endianness {
+    #[default]
+    Little,
+    Big
+}
+
+impl Endianness {
+
+    ///
+    /// Returns the byte order of the target platform.
+    ///
+    pub const fn native() -> Self {
+        if cfg!(target_endian = "big") {
+            Endianness::Big
+        } else {
+            Endianness::Little
+        }
+    }
+}
+
+///
+/// Primitive numeric types that can be encoded as a fixed number of raw bytes,
+/// and thus be serialized using [`SerializableRawBytes`].
+///
+pub trait RawBytesElement: Copy + 'static {
+
+    ///
+    /// The number of bytes in the encoding of a single element.
+    ///
+    const SIZE: usize;
+
+    ///
+    /// Appends the encoding of `self` to `out`.
+    ///
+    fn write_bytes(self, endianness: Endianness, out: &mut Vec<u8>);
+
+    ///
+    /// Decodes an element from `bytes`, which must have length [`RawBytesElement::SIZE`].
+    ///
+    fn read_bytes(bytes: &[u8], endianness: Endianness) -> Self;
+
+    ///
+    /// Reinterprets `bytes` as a slice of elements in native byte order, if this is
+    /// possible without copying, i.e. if `bytes` is correctly aligned and its length
+    /// is a multiple of [`RawBytesElement::SIZE`]. The default implementation always
+    /// returns `None`.
+    ///
+    fn cast_slice(bytes: &[u8]) -> Option<&[Self]> {
+        let _ = bytes;
+        return None;
+    }
+}
+
+macro_rules! impl_raw_bytes_element {
+    ($($t:ty),*) => {
+        $(
+            impl RawBytesElement for $t {
+                const SIZE: usize = std::mem::size_of::<$t>();
+
+                fn write_bytes(self, endianness: Endianness, out: &mut Vec<u8>) {
+                    match endianness {
+                        Endianness::Little => out.extend_from_slice(&self.to_le_bytes()),
+                        Endianness::Big => out.extend_from_slice(&self.to_be_bytes())
+                    }
+                }
+
+                fn read_bytes(bytes: &[u8], endianness: Endianness) -> Self {
+                    let bytes = bytes.try_into().unwrap();
+                    match endianness {
+                        Endianness::Little => <$t>::from_le_bytes(bytes),
+                        Endianness::Big => <$t>::from_be_bytes(bytes)
+                    }
+                }
+
+                fn cast_slice(bytes: &[u8]) -> Option<&[Self]> {
+                    // SAFETY: every bit pattern is a valid value of this primitive type
+                    let (prefix, result, suffix) = unsafe { bytes.align_to::<$t>() };
+                    if prefix.is_empty() && suffix.is_empty() {
+                        return Some(result);
+                    } else {
+                        return None;
+                    }
+                }
+            }
+        )*
+    };
+}
+
+impl_raw_bytes_element!{ u16, u32, u64, i16, i32, i64, f32, f64 }
+
+pub(crate) struct Bytes<'a>(pub(crate) &'a [u8]);
+
+impl<'a> Serialize for Bytes<'a> {
+
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+        where S: Serializer
+    {
+        serializer.serialize_bytes(self.0)
+    }
+}
+
+///
+/// Deserializes a byte buffer, which might also be represented as a sequence of bytes,
+/// e.g. in JSON.
+///
+pub(crate) struct ByteBufSeed;
+
+impl<'de> DeserializeSeed<'de> for ByteBufSeed {
+    type Value = Vec<u8>;
+
+    fn deserialize<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+        where D: Deserializer<'de>
+    {
+        deserializer.deserialize_byte_buf(self)
+    }
+}
+
+impl<'de> Visitor<'de> for ByteBufSeed {
+    type Value = Vec<u8>;
+
+    fn expecting(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "a byte buffer")
+    }
+
+    fn visit_bytes<E>(self, value: &[u8]) -> Result<Self::Value, E>
+        where E: Error
+    {
+        Ok(value.to_owned())
+    }
+
+    fn visit_byte_buf<E>(self, value: Vec<u8>) -> Result<Self::Value, E>
+        where E: Error
+    {
+        Ok(value)
+    }
+
+    fn visit_seq<A>(self, mut seq: A) -> Result<Self::Value, A::Error>
+        where A: SeqAccess<'de>
+    {
+        let mut result = Vec::with_capacity(seq.size_hint().unwrap_or(0).min(4096));
+        while let Some(byte) = seq.next_element()? {
+            result.push(byte);
+        }
+        return Ok(result);
+    }
+}
+
+///
+/// Wraps a slice of primitive numbers, and implements [`Serialize`] by mapping it
+/// to the tuple `(len, big_endian, bytes)` in the serde data model, where `bytes`
+/// is a single byte buffer containing the concatenated encodings of all elements.
+///
+/// Compared to [`crate::seq::SerializableSeq`], this avoids one call into the
+/// serializer per element, which makes a significant difference for large arrays.
+///
+/// # Example
+/// ```
+/// # use feanor_serde::raw_bytes::*;
+/// let data: [u32; 2] = [1, 256];
+/// assert_eq!("[2,false,[1,0,0,0,0,1,0,0]]", serde_json::to_string(&SerializableRawBytes::new(&data)).unwrap());
+/// assert_eq!("[2,true,[0,0,0,1,0,0,1,0]]", serde_json::to_string(&SerializableRawBytes::new_with_endianness(&data, Endianness::Big)).unwrap());
+/// ```
+///
+pub struct SerializableRawBytes<'a, T>
+    where T: RawBytesElement
+{
+    data: &'a [T],
+    endianness: Endianness
+}
+
+impl<'a, T> SerializableRawBytes<'a, T>
+    where T: RawBytesElement
+{
+    ///
+    /// Creates a new [`SerializableRawBytes`] that encodes the elements in little endian.
+    ///
+    pub fn new(data: &'a [T]) -> Self {
+        Self::new_with_endianness(data, Endianness::Little)
+    }
+
+    pub fn new_with_endianness(data: &'a [T], endianness: Endianness) -> Self {
+        Self { data, endianness }
+    }
+}
+
+impl<'a, T> Serialize for SerializableRawBytes<'a, T>
+    where T: RawBytesElement
+{
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+        where S: Serializer
+    {
+        let mut bytes = Vec::with_capacity(self.data.len() * T::SIZE);
+        for x in self.data {
+            x.write_bytes(self.endianness, &mut bytes);
+        }
+        let mut tuple = serializer.serialize_tuple(3)?;
+        tuple.serialize_element(&self.data.len())?;
+        tuple.serialize_element(&(self.endianness == Endianness::Big))?;
+        tuple.serialize_element(&Bytes(&bytes))?;
+        return tuple.end();
+    }
+}
+
+struct DeserializeSeedElements<T>
+    where T: RawBytesElement
+{
+    element: PhantomData<T>,
+    len: usize,
+    endianness: Endianness
+}
+
+impl<T> DeserializeSeedElements<T>
+    where T: RawBytesElement
+{
+    fn decode<E>(&self, bytes: &[u8]) -> Result<Vec<T>, E>
+        where E: Error
+    {
+        if self.len.checked_mul(T::SIZE) != Some(bytes.len()) {
+            return Err(E::invalid_length(bytes.len(), self));
+        }
+        let mut result = Vec::new();
+        result.try_reserve_exact(self.len).map_err(|_| E::custom(format!("failed to allocate storage for {} elements", self.len)))?;
+        result.extend(bytes.chunks_exact(T::SIZE).map(|chunk| T::read_bytes(chunk, self.endianness)));
+        return Ok(result);
+    }
+}
+
+impl<'de, T> Visitor<'de> for DeserializeSeedElements<T>
+    where T: RawBytesElement
+{
+    type Value = Vec<T>;
+
+    fn expecting(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "a byte buffer encoding {} elements of size {}", self.len, T::SIZE)
+    }
+
+    fn visit_bytes<E>(self, value: &[u8]) -> Result<Self::Value, E>
+        where E: Error
+    {
+        self.decode(value)
+    }
+
+    fn visit_seq<A>(self, mut seq: A) -> Result<Self::Value, A::Error>
+        where A: SeqAccess<'de>
+    {
+        let expected_len = self.len.checked_mul(T::SIZE).ok_or_else(|| <A::Error as Error>::invalid_length(0, &self))?;
+        let mut bytes = Vec::new();
+        while bytes.len() < expected_len {
+            match seq.next_element()? {
+                Some(byte) => bytes.push(byte),
+                None => return Err(<A::Error as Error>::invalid_length(bytes.len(), &self))
+            }
+        }
+        if seq.next_element::<IgnoredAny>()?.is_some() {
+            return Err(<A::Error as Error>::invalid_length(expected_len + 1, &self));
+        }
+        return self.decode(&bytes);
+    }
+}
+
+impl<'de, T> DeserializeSeed<'de> for DeserializeSeedElements<T>
+    where T: RawBytesElement
+{
+    type Value = Vec<T>;
+
+    fn deserialize<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+        where D: Deserializer<'de>
+    {
+        return deserializer.deserialize_bytes(self);
+    }
+}
+
+///
+/// A [`DeserializeSeed`] that deserializes a slice of primitive numbers that was
+/// serialized using [`SerializableRawBytes`] into a [`Vec`]. The byte order is
+/// read from the serialized data, so any [`Endianness`] can be decoded.
+///
+/// # Example
+/// ```
+/// # use feanor_serde::raw_bytes::*;
+/// # use serde::de::DeserializeSeed;
+/// let mut deserializer = serde_json::Deserializer::new(serde_json::de::StrRead::new("[2,false,[1,0,0,0,0,1,0,0]]"));
+/// assert_eq!(vec![1, 256], DeserializeSeedRawBytes::<u32>::new().deserialize(&mut deserializer).unwrap());
+/// ```
+///
+pub struct DeserializeSeedRawBytes<T>
+    where T: RawBytesElement
+{
+    element: PhantomData<T>
+}
+
+impl<T> DeserializeSeedRawBytes<T>
+    where T: RawBytesElement
+{
+    pub const fn new() -> Self {
+        Self { element: PhantomData }
+    }
+}
+
+impl<T> Default for DeserializeSeedRawBytes<T>
+    where T: RawBytesElement
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> Clone for DeserializeSeedRawBytes<T>
+    where T: RawBytesElement
+{
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T> Copy for DeserializeSeedRawBytes<T>
+    where T: RawBytesElement
+{}
+
+impl<'de, T> DeserializeSeed<'de> for DeserializeSeedRawBytes<T>
+    where T: RawBytesElement
+{
+    type Value = Vec<T>;
+
+    fn deserialize<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+        where D: Deserializer<'de>
+    {
+        struct ResultVisitor<T>
+            where T: RawBytesElement
+        {
+            element: PhantomData<T>
+        }
+
+        impl<'de, T> Visitor<'de> for ResultVisitor<T>
+            where T: RawBytesElement
+        {
+            type Value = Vec<T>;
+
+            fn expecting(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+                write!(f, "a tuple (len, big_endian, bytes)")
+            }
+
+            fn visit_seq<A>(self, mut seq: A) -> Result<Self::Value, A::Error>
+                where A: SeqAccess<'de>
+            {
+                let len: usize = seq.next_element()?.ok_or_else(|| <A::Error as Error>::invalid_length(0, &self))?;
+                let big_endian: bool = seq.next_element()?.ok_or_else(|| <A::Error as Error>::invalid_length(1, &self))?;
+                let endianness = if big_endian { Endianness::Big } else { Endianness::Little };
+                return seq.next_element_seed(DeserializeSeedElements { element: PhantomData, len: len, endianness: endianness })?.ok_or_else(|| <A::Error as Error>::invalid_length(2, &self));
+            }
+        }
+
+        return deserializer.deserialize_tuple(3, ResultVisitor { element: self.element });
+    }
+}
+
+struct DeserializeSeedBorrowedElements<T>
+    where T: RawBytesElement
+{
+    elements: DeserializeSeedElements<T>
+}
+
+impl<'de, T> Visitor<'de> for DeserializeSeedBorrowedElements<T>
+    where T: RawBytesElement
+{
+    type Value = Cow<'de, [T]>;
+
+    fn expecting(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "a byte buffer encoding {} elements of size {}", self.elements.len, T::SIZE)
+    }
+
+    fn visit_borrowed_bytes<E>(self, value: &'de [u8]) -> Result<Self::Value, E>
+        where E: Error
+    {
+        if self.elements.endianness == Endianness::native() && self.elements.len.checked_mul(T::SIZE) == Some(value.len()) {
+            if let Some(result) = T::cast_slice(value) {
+                return Ok(Cow::Borrowed(result));
+            }
+        }
+        return self.elements.decode(value).map(Cow::Owned);
+    }
+
+    fn visit_bytes<E>(self, value: &[u8]) -> Result<Self::Value, E>
+        where E: Error
+    {
+        self.elements.decode(value).map(Cow::Owned)
+    }
+
+    fn visit_seq<A>(self, seq: A) -> Result<Self::Value, A::Error>
+        where A: SeqAccess<'de>
+    {
+        self.elements.visit_seq(seq).map(Cow::Owned)
+    }
+}
+
+impl<'de, T> DeserializeSeed<'de> for DeserializeSeedBorrowedElements<T>
+    where T: RawBytesElement
+{
+    type Value = Cow<'de, [T]>;
+
+    fn deserialize<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+        where D: Deserializer<'de>
+    {
+        return deserializer.deserialize_bytes(self);
+    }
+}
+
+///
+/// A [`DeserializeSeed`] that deserializes a slice of primitive numbers that was
+/// serialized using [`SerializableRawBytes`], without copying if possible.
+///
+/// More concretely, if the deserializer provides the byte buffer borrowed from its
+/// input, the data was serialized in native byte order, and the buffer is correctly
+/// aligned, the result borrows the input as [`Cow::Borrowed`]. Otherwise, the data is
+/// decoded into a [`Cow::Owned`], exactly as by [`DeserializeSeedRawBytes`].
+///
+/// Note that alignment depends on the position of the data within the input, so
+/// the serializer should use [`Endianness::native()`] and the input buffer should
+/// be laid out accordingly (e.g. a memory-mapped file) to benefit from this. To rely
+/// on zero-copy deserialization instead, use [`DeserializeSeedRawBytesSlice`].
+///
+/// # Example
+/// ```
+/// # use feanor_serde::raw_bytes::*;
+/// # use serde::de::DeserializeSeed;
+/// let data: [u64; 3] = [1, 2, 3];
+/// let serialized = postcard::to_allocvec(&SerializableRawBytes::new_with_endianness(&data, Endianness::native())).unwrap();
+/// let mut deserializer = postcard::Deserializer::from_flavor(postcard::de_flavors::Slice::new(&serialized));
+/// // depending on the alignment of `serialized`, this may or may not borrow
+/// assert_eq!(&data[..], &*DeserializeSeedRawBytesBorrowed::<u64>::new().deserialize(&mut deserializer).unwrap());
+/// ```
+///
+pub struct DeserializeSeedRawBytesBorrowed<T>
+    where T: RawBytesElement
+{
+    element: PhantomData<T>
+}
+
+impl<T> DeserializeSeedRawBytesBorrowed<T>
+    where T: RawBytesElement
+{
+    pub const fn new() -> Self {
+        Self { element: PhantomData }
+    }
+}
+
+impl<T> Default for DeserializeSeedRawBytesBorrowed<T>
+    where T: RawBytesElement
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> Clone for DeserializeSeedRawBytesBorrowed<T>
+    where T: RawBytesElement
+{
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T> Copy for DeserializeSeedRawBytesBorrowed<T>
+    where T: RawBytesElement
+{}
+
+impl<'de, T> DeserializeSeed<'de> for DeserializeSeedRawBytesBorrowed<T>
+    where T: RawBytesElement
+{
+    type Value = Cow<'de, [T]>;
+
+    fn deserialize<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+        where D: Deserializer<'de>
+    {
+        struct ResultVisitor<T>
+            where T: RawBytesElement
+        {
+            element: PhantomData<T>
+        }
+
+        impl<'de, T> Visitor<'de> for ResultVisitor<T>
+            where T: RawBytesElement
+        {
+            type Value = Cow<'de, [T]>;
+
+            fn expecting(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+                write!(f, "a tuple (len, big_endian, bytes)")
+            }
+
+            fn visit_seq<A>(self, mut seq: A) -> Result<Self::Value, A::Error>
+                where A: SeqAccess<'de>
+            {
+                let len: usize = seq.next_element()?.ok_or_else(|| <A::Error as Error>::invalid_length(0, &self))?;
+                let big_endian: bool = seq.next_element()?.ok_or_else(|| <A::Error as Error>::invalid_length(1, &self))?;
+                let endianness = if big_endian { Endianness::Big } else { Endianness::Little };
+                let elements = DeserializeSeedElements { element: PhantomData, len: len, endianness: endianness };
+                return seq.next_element_seed(DeserializeSeedBorrowedElements { elements: elements })?.ok_or_else(|| <A::Error as Error>::invalid_length(2, &self));
+            }
+        }
+
+        return deserializer.deserialize_tuple(3, ResultVisitor { element: self.element });
+    }
+}
+
+struct DeserializeSeedSliceElements<T>
+    where T: RawBytesElement
+{
+    elements: DeserializeSeedElements<T>
+}
+
+impl<'de, T> Visitor<'de> for DeserializeSeedSliceElements<T>
+    where T: RawBytesElement
+{
+    type Value = &'de [T];
+
+    fn expecting(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "a borrowed byte buffer encoding {} elements of size {}", self.elements.len, T::SIZE)
+    }
+
+    fn visit_borrowed_bytes<E>(self, value: &'de [u8]) -> Result<Self::Value, E>
+        where E: Error
+    {
+        if self.elements.len.checked_mul(T::SIZE) != Some(value.len()) {
+            return Err(E::invalid_length(value.len(), &self));
+        }
+        if self.elements.endianness != Endianness::native() {
+            return Err(E::custom("raw bytes are not in native byte order"));
+        }
+        return T::cast_slice(value).ok_or_else(|| E::custom("raw bytes are not correctly aligned"));
+    }
+}
+
+impl<'de, T> DeserializeSeed<'de> for DeserializeSeedSliceElements<T>
+    where T: RawBytesElement
+{
+    type Value = &'de [T];
+
+    fn deserialize<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+        where D: Deserializer<'de>
+    {
+        return deserializer.deserialize_bytes(self);
+    }
+}
+
+///
+/// A [`DeserializeSeed`] that deserializes a slice of primitive numbers that was
+/// serialized using [`SerializableRawBytes`] by borrowing it from the input, and fails
+/// if this is not possible.
+///
+/// In contrast to [`DeserializeSeedRawBytesBorrowed`], this never copies. Instead, it
+/// returns an error if the deserializer does not provide the byte buffer borrowed from
+/// its input, if the data was not serialized in native byte order, or if the buffer is
+/// not correctly aligned. Use [`DeserializeSeedRawBytesBorrowed`] if a fallback to
+/// decoding the data is desired.
+///
+/// # Example
+/// ```
+/// # use feanor_serde::raw_bytes::*;
+/// # use serde::de::DeserializeSeed;
+/// // JSON cannot provide the bytes borrowed from the input
+/// let mut deserializer = serde_json::Deserializer::new(serde_json::de::StrRead::new("[2,false,[1,0,0,0,0,1,0,0]]"));
+/// assert!(DeserializeSeedRawBytesSlice::<u32>::new().deserialize(&mut deserializer).is_err());
+/// ```
+///
+pub struct DeserializeSeedRawBytesSlice<T>
+    where T: RawBytesElement
+{
+    element: PhantomData<T>
+}
+
+impl<T> DeserializeSeedRawBytesSlice<T>
+    where T: RawBytesElement
+{
+    pub const fn new() -> Self {
+        Self { element: PhantomData }
+    }
+}
+
+impl<T> Default for DeserializeSeedRawBytesSlice<T>
+    where T: RawBytesElement
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> Clone for DeserializeSeedRawBytesSlice<T>
+    where T: RawBytesElement
+{
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T> Copy for DeserializeSeedRawBytesSlice<T>
+    where T: RawBytesElement
+{}
+
+impl<'de, T> DeserializeSeed<'de> for DeserializeSeedRawBytesSlice<T>
+    where T: RawBytesElement
+{
+    type Value = &'de [T];
+
+    fn deserialize<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+        where D: Deserializer<'de>
+    {
+        struct ResultVisitor<T>
+            where T: RawBytesElement
+        {
+            element: PhantomData<T>
+        }
+
+        impl<'de, T> Visitor<'de> for ResultVisitor<T>
+            where T: RawBytesElement
+        {
+            type Value = &'de [T];
+
+            fn expecting(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+                write!(f, "a tuple (len, big_endian, bytes)")
+            }
+
+            fn visit_seq<A>(self, mut seq: A) -> Result<Self::Value, A::Error>
+                where A: SeqAccess<'de>
+            {
+                let len: usize = seq.next_element()?.ok_or_else(|| <A::Error as Error>::invalid_length(0, &self))?;
+                let big_endian: bool = seq.next_element()?.ok_or_else(|| <A::Error as Error>::invalid_length(1, &self))?;
+                let endianness = if big_endian { Endianness::Big } else { Endianness::Little };
+                let elements = DeserializeSeedElements { element: PhantomData, len: len, endianness: endianness };
+                return seq.next_element_seed(DeserializeSeedSliceElements { elements: elements })?.ok_or_else(|| <A::Error as Error>::invalid_length(2, &self));
+            }
+        }
+
+        return deserializer.deserialize_tuple(3, ResultVisitor { element: self.element });
+    }
+}
+
+#[cfg(test)]
+fn testdata_u64() -> Vec<Vec<u64>> {
+    vec![
+        Vec::new(),
+        vec![0],
+        vec![1, 2, 3],
+        vec![u64::MAX, 1 << 63, 0x0123456789ABCDEF]
+    ]
+}
+
+#[cfg(test)]
+fn testdata_f64() -> Vec<Vec<f64>> {
+    vec![
+        Vec::new(),
+        vec![0.],
+        vec![-1.5, 1e300, f64::MIN_POSITIVE, f64::INFINITY]
+    ]
+}
+
+#[test]
+fn test_serde_postcard() {
+    for endianness in [Endianness::Little, Endianness::Big] {
+        for data in testdata_u64() {
+            let serialized = postcard::to_allocvec(&SerializableRawBytes::new_with_endianness(&data, endianness)).unwrap();
+            let result = DeserializeSeedRawBytes::<u64>::new().deserialize(
+                &mut postcard::Deserializer::from_flavor(postcard::de_flavors::Slice::new(&serialized))
+            ).unwrap();
+            assert_eq!(data, result);
+        }
+        for data in testdata_f64() {
+            let serialized = postcard::to_allocvec(&SerializableRawBytes::new_with_endianness(&data, endianness)).unwrap();
+            let result = DeserializeSeedRawBytes::<f64>::new().deserialize(
+                &mut postcard::Deserializer::from_flavor(postcard::de_flavors::Slice::new(&serialized))
+            ).unwrap();
+            assert_eq!(data, result);
+        }
+    }
+    let data: [u32; 2] = [1, 256];
+    assert_eq!(vec![2, 0, 8, 1, 0, 0, 0, 0, 1, 0, 0], postcard::to_allocvec(&SerializableRawBytes::new(&data)).unwrap());
+}
+
+#[test]
+fn test_serde_json() {
+    for endianness in [Endianness::Little, Endianness::Big] {
+        for data in testdata_u64() {
+            let serialized = serde_json::to_string(&SerializableRawBytes::new_with_endianness(&data, endianness)).unwrap();
+            let result = DeserializeSeedRawBytes::<u64>::new().deserialize(
+                &mut serde_json::Deserializer::from_str(&serialized)
+            ).unwrap();
+            assert_eq!(data, result);
+        }
+    }
+}
+
+#[test]
+fn test_invalid_length() {
+    let deserialize = |json: &str| DeserializeSeedRawBytes::<u32>::new().deserialize(&mut serde_json::Deserializer::from_str(json));
+    assert!(deserialize("[2,false,[1,0,0,0,0,1,0]]").is_err());
+    assert!(deserialize("[2,false,[1,0,0,0,0,1,0,0,0]]").is_err());
+    assert!(deserialize(&format!("[{},false,[]]", usize::MAX)).is_err());
+
+    let serialized = postcard::to_allocvec(&(3usize, false, Bytes(&[0; 8]))).unwrap();
+    assert!(DeserializeSeedRawBytes::<u32>::new().deserialize(
+        &mut postcard::Deserializer::from_flavor(postcard::de_flavors::Slice::new(&serialized))
+    ).is_err());
+}
+
+#[test]
+fn test_serde_postcard_borrowed() {
+    for data in testdata_u64() {
+        let serialized = postcard::to_allocvec(&SerializableRawBytes::new_with_endianness(&data, Endianness::native())).unwrap();
+        // the header consists of three single-byte varints, so we can align the data
+        assert!(data.len() < 16);
+        let mut buffer = vec![0u8; serialized.len() + 8];
+        let offset = (0..8).find(|i| (buffer.as_ptr() as usize + i + 3).is_multiple_of(std::mem::align_of::<u64>())).unwrap();
+        buffer[offset..(offset + serialized.len())].copy_from_slice(&serialized);
+        let input = &buffer[offset..(offset + serialized.len())];
+
+        let result = DeserializeSeedRawBytesBorrowed::<u64>::new().deserialize(
+            &mut postcard::Deserializer::from_flavor(postcard::de_flavors::Slice::new(input))
+        ).unwrap();
+        assert_eq!(&data[..], &*result);
+        assert!(matches!(result, Cow::Borrowed(_)));
+
+        buffer.copy_within(offset..(offset + serialized.len()), offset + 1);
+        let result = DeserializeSeedRawBytesBorrowed::<u64>::new().deserialize(
+            &mut postcard::Deserializer::from_flavor(postcard::de_flavors::Slice::new(&buffer[(offset + 1)..(offset + 1 + serialized.len())]))
+        ).unwrap();
+        assert_eq!(&data[..], &*result);
+        assert!(data.is_empty() || matches!(result, Cow::Owned(_)));
+    }
+    for data in testdata_f64() {
+        let serialized = postcard::to_allocvec(&SerializableRawBytes::new_with_endianness(&data, Endianness::Big)).unwrap();
+        let result = DeserializeSeedRawBytesBorrowed::<f64>::new().deserialize(
+            &mut postcard::Deserializer::from_flavor(postcard::de_flavors::Slice::new(&serialized))
+        ).unwrap();
+        assert_eq!(&data[..], &*result);
+    }
+}
+
+#[test]
+fn test_serde_json_borrowed() {
+    let result = DeserializeSeedRawBytesBorrowed::<u32>::new().deserialize(
+        &mut serde_json::Deserializer::from_str("[2,false,[1,0,0,0,0,1,0,0]]")
+    ).unwrap();
+    assert_eq!(&[1, 256][..], &*result);
+    assert!(matches!(result, Cow::Owned(_)));
+}
+
+#[test]
+fn test_serde_postcard_slice() {
+    let non_native = if Endianness::native() == Endianness::Little { Endianness::Big } else { Endianness::Little };
+    for data in testdata_u64() {
+        let serialized = postcard::to_allocvec(&SerializableRawBytes::new_with_endianness(&data, Endianness::native())).unwrap();
+        let mut buffer = vec![0u8; serialized.len() + 8];
+        let offset = (0..8).find(|i| (buffer.as_ptr() as usize + i + 3).is_multiple_of(std::mem::align_of::<u64>())).unwrap();
+        buffer[offset..(offset + serialized.len())].copy_from_slice(&serialized);
+
+        let result = DeserializeSeedRawBytesSlice::<u64>::new().deserialize(
+            &mut postcard::Deserializer::from_flavor(postcard::de_flavors::Slice::new(&buffer[offset..(offset + serialized.len())]))
+        ).unwrap();
+        assert_eq!(&data[..], result);
+
+        buffer.copy_within(offset..(offset + serialized.len()), offset + 1);
+        let result = DeserializeSeedRawBytesSlice::<u64>::new().deserialize(
+            &mut postcard::Deserializer::from_flavor(postcard::de_flavors::Slice::new(&buffer[(offset + 1)..(offset + 1 + serialized.len())]))
+        );
+        assert!(data.is_empty() || result.is_err());
+    }
+    let serialized = postcard::to_allocvec(&SerializableRawBytes::new_with_endianness(&[1u64], non_native)).unwrap();
+    assert!(DeserializeSeedRawBytesSlice::<u64>::new().deserialize(
+        &mut postcard::Deserializer::from_flavor(postcard::de_flavors::Slice::new(&serialized))
+    ).is_err());
+}
+
+#[test]
+fn test_serde_json_slice() {
+    assert!(DeserializeSeedRawBytesSlice::<u32>::new().deserialize(
+        &mut serde_json::Deserializer::from_str("[2,false,[1,0,0,0,0,1,0,0]]")
+    ).is_err());
+}
@@ -0,0 +1,502 @@
+use serde::ser::*;
+
+use crate::canonical::ContentSerializer;
+use crate::mock::MockValue;
+use crate::path::Segment;
+
+///
+/// One step of a path given to [`Redactions`], where `None` is a wildcard.
+///
+#[derive(Clone, Debug, PartialEq, Eq)]
+enum PatternSegment {
+    Index(Option<usize>),
+    Key(Option<String>)
+}
+
+impl PatternSegment {
+
+    fn matches(&self, segment: &Segment) -> bool {
+        match (self, segment) {
+            (PatternSegment::Index(None), Segment::Seq { .. }) => true,
+            (PatternSegment::Index(Some(expected)), Segment::Seq { index }) => expected == index,
+            (PatternSegment::Key(None), Segment::Map { .. } | Segment::Enum { .. } | Segment::Unknown) => true,
+            (PatternSegment::Key(Some(expected)), Segment::Map { key }) => expected == key,
+            (PatternSegment::Key(Some(expected)), Segment::Enum { variant }) => expected == variant,
+            _ => false
+        }
+    }
+}
+
+fn parse_pattern(path: &str) -> Vec<PatternSegment> {
+    let mut result = Vec::new();
+    let mut rest = path;
+    while !rest.is_empty() {
+        if let Some(tail) = rest.strip_prefix('.') {
+            let end = tail.find(['.', '[']).unwrap_or(tail.len());
+            let key = &tail[..end];
+            assert!(!key.is_empty(), "invalid path \"{}\": empty key", path);
+            result.push(PatternSegment::Key(if key == "*" { None } else { Some(key.to_owned()) }));
+            rest = &tail[end..];
+        } else if let Some(tail) = rest.strip_prefix('[') {
+            let end = tail.find(']').unwrap_or_else(|| panic!("invalid path \"{}\": unclosed \"[\"", path));
+            let index = &tail[..end];
+            result.push(PatternSegment::Index(if index == "*" { None } else { Some(index.parse().unwrap_or_else(|_| panic!("invalid path \"{}\": invalid index \"{}\"", path, index))) }));
+            rest = &tail[(end + 1)..];
+        } else {
+            panic!("invalid path \"{}\": expected \".\" or \"[\"", path);
+        }
+    }
+    assert!(!result.is_empty(), "invalid path \"{}\": cannot redact the root value", path);
+    return result;
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+enum Rule {
+    Drop,
+    Replace(String)
+}
+
+///
+/// A set of paths whose values should be dropped or replaced when serializing a [`Redacted`].
+///
+/// Paths are written in the same way as [`crate::path::Path`] displays them, e.g.
+/// `.records[17].password`, where struct fields, string keys of maps and enum variants are all
+/// given by `.name`. Additionally, `.*` resp. `[*]` matches any key resp. any index.
+///
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Redactions {
+    rules: Vec<(Vec<PatternSegment>, Rule)>
+}
+
+impl Redactions {
+
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    ///
+    /// Drops the values at the given path, i.e. omits the struct field, map entry or sequence
+    /// element entirely.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `path` is not a valid, non-empty path.
+    ///
+    pub fn with_dropped(mut self, path: &str) -> Self {
+        self.rules.push((parse_pattern(path), Rule::Drop));
+        return self;
+    }
+
+    ///
+    /// Replaces the values at the given path by the string `replacement`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `path` is not a valid, non-empty path.
+    ///
+    pub fn with_replaced(mut self, path: &str, replacement: &str) -> Self {
+        self.rules.push((parse_pattern(path), Rule::Replace(replacement.to_owned())));
+        return self;
+    }
+
+    ///
+    /// Returns the first rule whose pattern matches `path`.
+    ///
+    fn find(&self, path: &[Segment]) -> Option<&Rule> {
+        self.rules.iter()
+            .find(|(pattern, _)| pattern.len() == path.len() && pattern.iter().zip(path.iter()).all(|(p, s)| p.matches(s)))
+            .map(|(_, rule)| rule)
+    }
+
+    fn is_dropped(&self, path: &[Segment]) -> bool {
+        self.find(path) == Some(&Rule::Drop)
+    }
+
+    ///
+    /// Returns whether some child of the value at `path`, or some child of the content of its
+    /// enum variant, might be dropped.
+    ///
+    fn may_drop_child(&self, path: &[Segment]) -> bool {
+        self.rules.iter().any(|(pattern, rule)| *rule == Rule::Drop
+            && pattern.len() > path.len()
+            && pattern.len() <= path.len() + 2
+            && pattern.iter().zip(path.iter()).all(|(p, s)| p.matches(s)))
+    }
+}
+
+///
+/// Wraps a serializable value, and serializes it in the same way, except that values at
+/// the paths given by [`Redactions`] are dropped or replaced. This is useful to e.g. write
+/// sanitized copies of data containing secrets.
+///
+/// Lengths of structs, maps and sequences are adjusted when values are dropped, thus
+/// the result can be read by formats that depend on them, like postcard. To do so, values
+/// that might have dropped children are buffered in memory before passing them on.
+///
+/// # Example
+/// ```
+/// # use feanor_serde::redact::*;
+/// # use std::collections::BTreeMap;
+/// #[derive(serde::Serialize)]
+/// struct User { name: String, password: String, tokens: BTreeMap<String, u64> }
+///
+/// let users = vec![User { name: "admin".to_owned(), password: "1234".to_owned(), tokens: BTreeMap::from([("api".to_owned(), 42), ("other".to_owned(), 1)]) }];
+/// let redactions = Redactions::new().with_replaced("[*].password", "<redacted>").with_dropped("[*].tokens.api");
+/// assert_eq!(
+///     r#"[{"name":"admin","password":"<redacted>","tokens":{"other":1}}]"#,
+///     serde_json::to_string(&Redacted::new(&users, &redactions)).unwrap()
+/// );
+/// ```
+///
+pub struct Redacted<'a, T> {
+    value: T,
+    redactions: &'a Redactions,
+    path: Vec<Segment>
+}
+
+impl<'a, T> Redacted<'a, T>
+    where T: Serialize
+{
+    pub fn new(value: T, redactions: &'a Redactions) -> Self {
+        Self { value: value, redactions: redactions, path: Vec::new() }
+    }
+}
+
+impl<'a, T> Serialize for Redacted<'a, T>
+    where T: Serialize
+{
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+        where S: Serializer
+    {
+        if let Some(Rule::Replace(replacement)) = self.redactions.find(&self.path) {
+            return serializer.serialize_str(replacement);
+        }
+        if self.redactions.may_drop_child(&self.path) {
+            // the lengths are only known after dropping, so buffer the value first
+            let content = self.value.serialize(RedactingSerializer { inner: ContentSerializer::<S::Error>::new(serializer.is_human_readable()), redactions: self.redactions, path: self.path.clone() })?;
+            return content.serialize(serializer);
+        }
+        return self.value.serialize(RedactingSerializer { inner: serializer, redactions: self.redactions, path: self.path.clone() });
+    }
+}
+
+///
+/// The [`Serializer`] used by [`Redacted`], which omits children at dropped paths, without
+/// adjusting lengths.
+///
+struct RedactingSerializer<'a, S> {
+    inner: S,
+    redactions: &'a Redactions,
+    path: Vec<Segment>
+}
+
+fn child_path(path: &[Segment], segment: Segment) -> Vec<Segment> {
+    let mut result = Vec::with_capacity(path.len() + 1);
+    result.extend_from_slice(path);
+    result.push(segment);
+    return result;
+}
+
+impl<'a, S> RedactingSerializer<'a, S> {
+
+    fn wrap<'b, T>(&self, value: &'b T, path: Vec<Segment>) -> Redacted<'a, &'b T>
+        where T: ?Sized + Serialize
+    {
+        Redacted { value: value, redactions: self.redactions, path: path }
+    }
+
+    fn compound<C>(redactions: &'a Redactions, inner: C, path: Vec<Segment>) -> RedactingCompound<'a, C> {
+        RedactingCompound { inner: inner, redactions: redactions, path: path, index: 0, value_path: None }
+    }
+}
+
+impl<'a, S> Serializer for RedactingSerializer<'a, S>
+    where S: Serializer
+{
+    type Ok = S::Ok;
+    type Error = S::Error;
+    type SerializeSeq = RedactingCompound<'a, S::SerializeSeq>;
+    type SerializeTuple = RedactingCompound<'a, S::SerializeTuple>;
+    type SerializeTupleStruct = RedactingCompound<'a, S::SerializeTupleStruct>;
+    type SerializeTupleVariant = RedactingCompound<'a, S::SerializeTupleVariant>;
+    type SerializeMap = RedactingCompound<'a, S::SerializeMap>;
+    type SerializeStruct = RedactingCompound<'a, S::SerializeStruct>;
+    type SerializeStructVariant = RedactingCompound<'a, S::SerializeStructVariant>;
+
+    fn serialize_bool(self, v: bool) -> Result<S::Ok, S::Error> { self.inner.serialize_bool(v) }
+    fn serialize_i8(self, v: i8) -> Result<S::Ok, S::Error> { self.inner.serialize_i8(v) }
+    fn serialize_i16(self, v: i16) -> Result<S::Ok, S::Error> { self.inner.serialize_i16(v) }
+    fn serialize_i32(self, v: i32) -> Result<S::Ok, S::Error> { self.inner.serialize_i32(v) }
+    fn serialize_i64(self, v: i64) -> Result<S::Ok, S::Error> { self.inner.serialize_i64(v) }
+    fn serialize_i128(self, v: i128) -> Result<S::Ok, S::Error> { self.inner.serialize_i128(v) }
+    fn serialize_u8(self, v: u8) -> Result<S::Ok, S::Error> { self.inner.serialize_u8(v) }
+    fn serialize_u16(self, v: u16) -> Result<S::Ok, S::Error> { self.inner.serialize_u16(v) }
+    fn serialize_u32(self, v: u32) -> Result<S::Ok, S::Error> { self.inner.serialize_u32(v) }
+    fn serialize_u64(self, v: u64) -> Result<S::Ok, S::Error> { self.inner.serialize_u64(v) }
+    fn serialize_u128(self, v: u128) -> Result<S::Ok, S::Error> { self.inner.serialize_u128(v) }
+    fn serialize_f32(self, v: f32) -> Result<S::Ok, S::Error> { self.inner.serialize_f32(v) }
+    fn serialize_f64(self, v: f64) -> Result<S::Ok, S::Error> { self.inner.serialize_f64(v) }
+    fn serialize_char(self, v: char) -> Result<S::Ok, S::Error> { self.inner.serialize_char(v) }
+    fn serialize_str(self, v: &str) -> Result<S::Ok, S::Error> { self.inner.serialize_str(v) }
+    fn serialize_bytes(self, v: &[u8]) -> Result<S::Ok, S::Error> { self.inner.serialize_bytes(v) }
+    fn serialize_none(self) -> Result<S::Ok, S::Error> { self.inner.serialize_none() }
+    fn serialize_unit(self) -> Result<S::Ok, S::Error> { self.inner.serialize_unit() }
+    fn serialize_unit_struct(self, name: &'static str) -> Result<S::Ok, S::Error> { self.inner.serialize_unit_struct(name) }
+
+    fn serialize_some<T>(self, value: &T) -> Result<S::Ok, S::Error>
+        where T: ?Sized + Serialize
+    {
+        let value = self.wrap(value, self.path.clone());
+        self.inner.serialize_some(&value)
+    }
+
+    fn serialize_unit_variant(self, name: &'static str, variant_index: u32, variant: &'static str) -> Result<S::Ok, S::Error> {
+        self.inner.serialize_unit_variant(name, variant_index, variant)
+    }
+
+    fn serialize_newtype_struct<T>(self, name: &'static str, value: &T) -> Result<S::Ok, S::Error>
+        where T: ?Sized + Serialize
+    {
+        let value = self.wrap(value, self.path.clone());
+        self.inner.serialize_newtype_struct(name, &value)
+    }
+
+    fn serialize_newtype_variant<T>(self, name: &'static str, variant_index: u32, variant: &'static str, value: &T) -> Result<S::Ok, S::Error>
+        where T: ?Sized + Serialize
+    {
+        let value = self.wrap(value, child_path(&self.path, Segment::Enum { variant: variant.to_owned() }));
+        self.inner.serialize_newtype_variant(name, variant_index, variant, &value)
+    }
+
+    fn serialize_seq(self, len: Option<usize>) -> Result<Self::SerializeSeq, S::Error> {
+        Ok(Self::compound(self.redactions, self.inner.serialize_seq(len)?, self.path))
+    }
+
+    fn serialize_tuple(self, len: usize) -> Result<Self::SerializeTuple, S::Error> {
+        Ok(Self::compound(self.redactions, self.inner.serialize_tuple(len)?, self.path))
+    }
+
+    fn serialize_tuple_struct(self, name: &'static str, len: usize) -> Result<Self::SerializeTupleStruct, S::Error> {
+        Ok(Self::compound(self.redactions, self.inner.serialize_tuple_struct(name, len)?, self.path))
+    }
+
+    fn serialize_tuple_variant(self, name: &'static str, variant_index: u32, variant: &'static str, len: usize) -> Result<Self::SerializeTupleVariant, S::Error> {
+        let path = child_path(&self.path, Segment::Enum { variant: variant.to_owned() });
+        Ok(Self::compound(self.redactions, self.inner.serialize_tuple_variant(name, variant_index, variant, len)?, path))
+    }
+
+    fn serialize_map(self, len: Option<usize>) -> Result<Self::SerializeMap, S::Error> {
+        Ok(Self::compound(self.redactions, self.inner.serialize_map(len)?, self.path))
+    }
+
+    fn serialize_struct(self, name: &'static str, len: usize) -> Result<Self::SerializeStruct, S::Error> {
+        Ok(Self::compound(self.redactions, self.inner.serialize_struct(name, len)?, self.path))
+    }
+
+    fn serialize_struct_variant(self, name: &'static str, variant_index: u32, variant: &'static str, len: usize) -> Result<Self::SerializeStructVariant, S::Error> {
+        let path = child_path(&self.path, Segment::Enum { variant: variant.to_owned() });
+        Ok(Self::compound(self.redactions, self.inner.serialize_struct_variant(name, variant_index, variant, len)?, path))
+    }
+
+    fn is_human_readable(&self) -> bool {
+        self.inner.is_human_readable()
+    }
+}
+
+///
+/// The compound serializers of [`RedactingSerializer`], which track the path of the
+/// current child, and skip it if it is dropped.
+///
+struct RedactingCompound<'a, C> {
+    inner: C,
+    redactions: &'a Redactions,
+    path: Vec<Segment>,
+    index: usize,
+    ///
+    /// The path of the value belonging to the last serialized map key, or `None` if the
+    /// entry is dropped.
+    ///
+    value_path: Option<Vec<Segment>>
+}
+
+impl<'a, C> RedactingCompound<'a, C> {
+
+    ///
+    /// Returns the next element of a sequence, or `None` if it is dropped.
+    ///
+    fn next_element<'b, T>(&mut self, value: &'b T) -> Option<Redacted<'a, &'b T>>
+        where T: ?Sized + Serialize
+    {
+        let path = child_path(&self.path, Segment::Seq { index: self.index });
+        self.index += 1;
+        return self.child(value, path);
+    }
+
+    fn child<'b, T>(&self, value: &'b T, path: Vec<Segment>) -> Option<Redacted<'a, &'b T>>
+        where T: ?Sized + Serialize
+    {
+        if self.redactions.is_dropped(&path) {
+            None
+        } else {
+            Some(Redacted { value: value, redactions: self.redactions, path: path })
+        }
+    }
+}
+
+fn key_segment<K>(key: &K) -> Segment
+    where K: ?Sized + Serialize
+{
+    match MockValue::from_serialize(key) {
+        Ok(MockValue::Str(key)) => Segment::Map { key: key },
+        Ok(MockValue::Char(key)) => Segment::Map { key: key.to_string() },
+        Ok(MockValue::I64(key)) => Segment::Map { key: key.to_string() },
+        Ok(MockValue::U64(key)) => Segment::Map { key: key.to_string() },
+        Ok(MockValue::Bool(key)) => Segment::Map { key: key.to_string() },
+        _ => Segment::Unknown
+    }
+}
+
+macro_rules! impl_redacting_seq {
+    ($trait:ident, $method:ident) => {
+        impl<'a, C> $trait for RedactingCompound<'a, C>
+            where C: $trait
+        {
+            type Ok = C::Ok;
+            type Error = C::Error;
+
+            fn $method<T>(&mut self, value: &T) -> Result<(), C::Error>
+                where T: ?Sized + Serialize
+            {
+                match self.next_element(value) {
+                    Some(value) => self.inner.$method(&value),
+                    None => Ok(())
+                }
+            }
+
+            fn end(self) -> Result<C::Ok, C::Error> {
+                self.inner.end()
+            }
+        }
+    };
+}
+
+impl_redacting_seq!(SerializeSeq, serialize_element);
+impl_redacting_seq!(SerializeTuple, serialize_element);
+impl_redacting_seq!(SerializeTupleStruct, serialize_field);
+impl_redacting_seq!(SerializeTupleVariant, serialize_field);
+
+macro_rules! impl_redacting_struct {
+    ($trait:ident) => {
+        impl<'a, C> $trait for RedactingCompound<'a, C>
+            where C: $trait
+        {
+            type Ok = C::Ok;
+            type Error = C::Error;
+
+            fn serialize_field<T>(&mut self, key: &'static str, value: &T) -> Result<(), C::Error>
+                where T: ?Sized + Serialize
+            {
+                match self.child(value, child_path(&self.path, Segment::Map { key: key.to_owned() })) {
+                    Some(value) => self.inner.serialize_field(key, &value),
+                    None => self.inner.skip_field(key)
+                }
+            }
+
+            fn skip_field(&mut self, key: &'static str) -> Result<(), C::Error> {
+                self.inner.skip_field(key)
+            }
+
+            fn end(self) -> Result<C::Ok, C::Error> {
+                self.inner.end()
+            }
+        }
+    };
+}
+
+impl_redacting_struct!(SerializeStruct);
+impl_redacting_struct!(SerializeStructVariant);
+
+impl<'a, C> SerializeMap for RedactingCompound<'a, C>
+    where C: SerializeMap
+{
+    type Ok = C::Ok;
+    type Error = C::Error;
+
+    fn serialize_key<T>(&mut self, key: &T) -> Result<(), C::Error>
+        where T: ?Sized + Serialize
+    {
+        let path = child_path(&self.path, key_segment(key));
+        if self.redactions.is_dropped(&path) {
+            self.value_path = None;
+            return Ok(());
+        }
+        self.value_path = Some(path);
+        return self.inner.serialize_key(key);
+    }
+
+    fn serialize_value<T>(&mut self, value: &T) -> Result<(), C::Error>
+        where T: ?Sized + Serialize
+    {
+        match self.value_path.take() {
+            Some(path) => self.inner.serialize_value(&Redacted { value: value, redactions: self.redactions, path: path }),
+            None => Ok(())
+        }
+    }
+
+    fn end(self) -> Result<C::Ok, C::Error> {
+        self.inner.end()
+    }
+}
+
+#[cfg(test)]
+use std::collections::BTreeMap;
+
+#[cfg(test)]
+#[derive(serde::Serialize, serde::Deserialize, Debug, PartialEq)]
+enum Secret {
+    Token(String),
+    Login { user: String, password: Option<String> }
+}
+
+#[cfg(test)]
+#[derive(serde::Serialize, serde::Deserialize, Debug, PartialEq)]
+struct State {
+    secrets: Vec<Secret>,
+    settings: BTreeMap<String, i64>,
+    values: (i64, i64, i64)
+}
+
+#[test]
+fn test_serde_json() {
+    let state = State {
+        secrets: vec![Secret::Token("abc".to_owned()), Secret::Login { user: "admin".to_owned(), password: Some("1234".to_owned()) }],
+        settings: BTreeMap::from([("a".to_owned(), 1), ("key".to_owned(), 2)]),
+        values: (1, 2, 3)
+    };
+    let redactions = Redactions::new()
+        .with_replaced("[*]", "should not match")
+        .with_replaced(".secrets[*].Token", "<token>")
+        .with_dropped(".secrets[*].Login.password")
+        .with_dropped(".settings.key")
+        .with_dropped(".values[1]");
+    assert_eq!(
+        r#"{"secrets":[{"Token":"<token>"},{"Login":{"user":"admin"}}],"settings":{"a":1},"values":[1,3]}"#,
+        serde_json::to_string(&Redacted::new(&state, &redactions)).unwrap()
+    );
+    assert_eq!(serde_json::to_string(&state).unwrap(), serde_json::to_string(&Redacted::new(&state, &Redactions::new())).unwrap());
+}
+
+#[test]
+fn test_serde_postcard() {
+    let settings = BTreeMap::from([("a".to_owned(), vec![Some(1)]), ("b".to_owned(), vec![None, Some(2)])]);
+    let redactions = Redactions::new().with_dropped(".a").with_dropped(".b[0]");
+    let serialized = postcard::to_allocvec(&Redacted::new(&settings, &redactions)).unwrap();
+    let result: BTreeMap<String, Vec<Option<i64>>> = postcard::from_bytes(&serialized).unwrap();
+    assert_eq!(BTreeMap::from([("b".to_owned(), vec![Some(2)])]), result);
+}
+
+#[test]
+#[should_panic(expected = "invalid path")]
+fn test_invalid_path() {
+    let _ = Redactions::new().with_dropped(".a[x]");
+}
@@ -0,0 +1,121 @@
+///
+/// Describes a field of a struct generated by [`crate::impl_deserialize_seed_for_dependent_struct!`],
+/// as found in its associated constant `FIELDS`.
+///
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct FieldInfo {
+    /// The name of the field
+    pub name: &'static str,
+    /// The index of the field, which can be used instead of its name in the serialized data
+    pub index: usize,
+    /// The type of the field, as written in the macro invocation
+    pub type_name: &'static str
+}
+
+impl FieldInfo {
+
+    pub const fn new(name: &'static str, index: usize, type_name: &'static str) -> Self {
+        Self { name: name, index: index, type_name: type_name }
+    }
+}
+
+///
+/// Describes a variant of an enum generated by [`crate::impl_deserialize_seed_for_dependent_enum!`],
+/// as found in its associated constant `VARIANTS`.
+///
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct VariantInfo {
+    /// The name of the variant
+    pub name: &'static str,
+    /// The index of the variant, which can be used instead of its name in the serialized data
+    pub index: usize,
+    /// The type of the payload of the variant, as written in the macro invocation
+    pub type_name: &'static str
+}
+
+impl VariantInfo {
+
+    pub const fn new(name: &'static str, index: usize, type_name: &'static str) -> Self {
+        Self { name: name, index: index, type_name: type_name }
+    }
+}
+
+///
+/// Sets the index of each [`FieldInfo`] to its position. Used by the macros, which cannot
+/// count the fields themselves.
+///
+#[doc(hidden)]
+pub const fn index_fields<const N: usize>(mut fields: [FieldInfo; N]) -> [FieldInfo; N] {
+    let mut i = 0;
+    while i < N {
+        fields[i].index = i;
+        i += 1;
+    }
+    return fields;
+}
+
+///
+/// Sets the index of each [`VariantInfo`] to its position. Used by the macros, which cannot
+/// count the variants themselves.
+///
+#[doc(hidden)]
+pub const fn index_variants<const N: usize>(mut variants: [VariantInfo; N]) -> [VariantInfo; N] {
+    let mut i = 0;
+    while i < N {
+        variants[i].index = i;
+        i += 1;
+    }
+    return variants;
+}
+
+#[cfg(test)]
+use crate::default_seed::seed_of;
+#[cfg(test)]
+use crate::mock::{MockDeserializer, MockValue};
+#[cfg(test)]
+use serde::de::DeserializeSeed;
+#[cfg(test)]
+use crate::{impl_deserialize_seed_for_dependent_enum, impl_deserialize_seed_for_dependent_struct};
+
+#[cfg(test)]
+struct DeserializeSeedFoo;
+
+#[cfg(test)]
+impl_deserialize_seed_for_dependent_struct!{
+    pub struct Foo<'de> using DeserializeSeedFoo {
+        a: i64: |_| seed_of::<i64>(),
+        b: Vec<String>: |_| seed_of::<Vec<String>>()
+    }
+}
+
+#[cfg(test)]
+struct DeserializeSeedBar;
+
+#[cfg(test)]
+impl_deserialize_seed_for_dependent_enum!{
+    pub enum Bar<'de> using DeserializeSeedBar {
+        A(i64): |_| seed_of::<i64>(),
+        B(String): |_| seed_of::<String>(),
+        C(()): |_| seed_of::<()>()
+    }
+}
+
+#[test]
+fn test_fields() {
+    assert_eq!(&[FieldInfo::new("a", 0, "i64"), FieldInfo::new("b", 1, "Vec<String>")], Foo::FIELDS);
+
+    let input = MockValue::map([(MockValue::from(Foo::FIELDS[1].index as u64), MockValue::seq(["x"])), (MockValue::from(Foo::FIELDS[0].index as u64), MockValue::from(1i64))]);
+    let foo = DeserializeSeedFoo.deserialize(MockDeserializer::new(input)).unwrap();
+    assert_eq!((1, vec!["x".to_owned()]), (foo.a, foo.b));
+}
+
+#[test]
+fn test_variants() {
+    assert_eq!(&[VariantInfo::new("A", 0, "i64"), VariantInfo::new("B", 1, "String"), VariantInfo::new("C", 2, "()")], Bar::VARIANTS);
+
+    let input = MockValue::newtype_variant(Bar::VARIANTS[1].index as u64, "x");
+    match DeserializeSeedBar.deserialize(MockDeserializer::new(input)).unwrap() {
+        Bar::B(value) => assert_eq!("x", value.0),
+        _ => unreachable!()
+    }
+}
@@ -0,0 +1,409 @@
+use std::collections::HashMap;
+
+use serde::de::{DeserializeSeed, EnumAccess, MapAccess, SeqAccess, VariantAccess, Visitor};
+use serde::Deserializer;
+
+use crate::into_seed::IntoSeed;
+
+///
+/// A mapping from old to new identifiers, used by [`RenamingDeserializer`] to read data
+/// whose struct fields or enum variants have been renamed since it was written.
+///
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Renames {
+    renames: HashMap<String, String>
+}
+
+impl Renames {
+
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    ///
+    /// Adds a rename, such that the identifier `old` is deserialized as `new`.
+    ///
+    pub fn with_rename(mut self, old: &str, new: &str) -> Self {
+        self.renames.insert(old.to_owned(), new.to_owned());
+        return self;
+    }
+
+    ///
+    /// Returns the new name of the given identifier, or `None` if it is not renamed.
+    ///
+    pub fn get(&self, old: &str) -> Option<&str> {
+        self.renames.get(old).map(|new| new.as_str())
+    }
+}
+
+///
+/// Wraps a [`Deserializer`], and replaces all identifiers, i.e. struct field names and enum
+/// variant names, according to the given [`Renames`], before they reach the seed.
+///
+/// This allows reading legacy data in which fields or variants have different names, without
+/// having to add aliases to each seed. Only string identifiers are renamed, thus formats that
+/// identify fields and variants by their index, like postcard, are unaffected. Map keys that
+/// are not identifiers, e.g. the keys of a [`HashMap<String, _>`], are never renamed.
+///
+/// # Example
+/// ```
+/// # use feanor_serde::impl_deserialize_seed_for_dependent_struct;
+/// # use feanor_serde::rename::*;
+/// # use serde::de::DeserializeSeed;
+/// # use feanor_serde::default_seed::seed_of;
+/// struct PointDeserializeSeed;
+/// impl_deserialize_seed_for_dependent_struct!{
+///     pub struct DeserializedPoint<'de> using PointDeserializeSeed {
+///         x: i64: |_| seed_of::<i64>(),
+///         y: i64: |_| seed_of::<i64>()
+///     }
+/// }
+/// let renames = Renames::new().with_rename("horizontal", "x").with_rename("vertical", "y");
+/// let mut deserializer = serde_json::Deserializer::from_str(r#"{"horizontal": 1, "vertical": 2}"#);
+/// let point = PointDeserializeSeed.deserialize(RenamingDeserializer::new(&mut deserializer, &renames)).unwrap();
+/// assert_eq!((1, 2), (point.x, point.y));
+/// ```
+///
+pub struct RenamingDeserializer<'a, D> {
+    de: D,
+    renames: &'a Renames
+}
+
+impl<'a, D> RenamingDeserializer<'a, D> {
+
+    pub fn new(de: D, renames: &'a Renames) -> Self {
+        Self { de: de, renames: renames }
+    }
+}
+
+///
+/// Wraps a [`DeserializeSeed`], and deserializes it from a [`RenamingDeserializer`].
+///
+pub struct RenamingSeed<'a, S> {
+    seed: S,
+    renames: &'a Renames
+}
+
+impl<'a, S> RenamingSeed<'a, S> {
+
+    pub fn new<'de, I, M>(seed: I, renames: &'a Renames) -> Self
+        where I: IntoSeed<'de, M, Seed = S>
+    {
+        Self { seed: seed.into_seed(), renames: renames }
+    }
+}
+
+impl<'a, 'de, S> DeserializeSeed<'de> for RenamingSeed<'a, S>
+    where S: DeserializeSeed<'de>
+{
+    type Value = S::Value;
+
+    fn deserialize<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+        where D: Deserializer<'de>
+    {
+        self.seed.deserialize(RenamingDeserializer { de: deserializer, renames: self.renames })
+    }
+}
+
+macro_rules! forward_deserialize {
+    ($($method:ident($($arg:ident: $type:ty),*)),*) => {
+        $(
+            fn $method<V>(self, $($arg: $type,)* visitor: V) -> Result<V::Value, Self::Error>
+                where V: Visitor<'de>
+            {
+                self.de.$method($($arg,)* Renaming { visitor: visitor, renames: self.renames, identifier: false })
+            }
+        )*
+    };
+}
+
+impl<'a, 'de, D> Deserializer<'de> for RenamingDeserializer<'a, D>
+    where D: Deserializer<'de>
+{
+    type Error = D::Error;
+
+    forward_deserialize!{
+        deserialize_any(), deserialize_bool(), deserialize_i8(), deserialize_i16(), deserialize_i32(), deserialize_i64(), deserialize_i128(),
+        deserialize_u8(), deserialize_u16(), deserialize_u32(), deserialize_u64(), deserialize_u128(), deserialize_f32(), deserialize_f64(),
+        deserialize_char(), deserialize_str(), deserialize_string(), deserialize_bytes(), deserialize_byte_buf(), deserialize_option(),
+        deserialize_unit(), deserialize_seq(), deserialize_map(), deserialize_ignored_any(),
+        deserialize_unit_struct(name: &'static str), deserialize_newtype_struct(name: &'static str), deserialize_tuple(len: usize),
+        deserialize_tuple_struct(name: &'static str, len: usize), deserialize_struct(name: &'static str, fields: &'static [&'static str]),
+        deserialize_enum(name: &'static str, variants: &'static [&'static str])
+    }
+
+    fn deserialize_identifier<V>(self, visitor: V) -> Result<V::Value, Self::Error>
+        where V: Visitor<'de>
+    {
+        self.de.deserialize_identifier(Renaming { visitor: visitor, renames: self.renames, identifier: true })
+    }
+
+    fn is_human_readable(&self) -> bool {
+        self.de.is_human_readable()
+    }
+}
+
+///
+/// Wraps a [`Visitor`] resp. one of the accessors, in order to wrap all nested deserializers.
+/// If `identifier` is set, the wrapped [`Visitor`] is visiting an identifier, which is renamed.
+///
+struct Renaming<'a, X> {
+    visitor: X,
+    renames: &'a Renames,
+    identifier: bool
+}
+
+impl<'a, X> Renaming<'a, X> {
+
+    fn renamed(&self, value: &str) -> Option<&'a str> {
+        if self.identifier {
+            self.renames.get(value)
+        } else {
+            None
+        }
+    }
+
+    fn wrap<Y>(&self, visitor: Y) -> Renaming<'a, Y> {
+        Renaming { visitor: visitor, renames: self.renames, identifier: false }
+    }
+}
+
+macro_rules! forward_visit {
+    ($($method:ident($type:ty)),*) => {
+        $(
+            fn $method<E>(self, value: $type) -> Result<Self::Value, E>
+                where E: serde::de::Error
+            {
+                self.visitor.$method(value)
+            }
+        )*
+    };
+}
+
+impl<'a, 'de, X> Visitor<'de> for Renaming<'a, X>
+    where X: Visitor<'de>
+{
+    type Value = X::Value;
+
+    fn expecting(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        self.visitor.expecting(f)
+    }
+
+    forward_visit!{
+        visit_bool(bool), visit_i8(i8), visit_i16(i16), visit_i32(i32), visit_i64(i64), visit_i128(i128),
+        visit_u8(u8), visit_u16(u16), visit_u32(u32), visit_u64(u64), visit_u128(u128), visit_f32(f32), visit_f64(f64),
+        visit_char(char)
+    }
+
+    fn visit_str<E>(self, value: &str) -> Result<Self::Value, E>
+        where E: serde::de::Error
+    {
+        match self.renamed(value) {
+            Some(new) => self.visitor.visit_str(new),
+            None => self.visitor.visit_str(value)
+        }
+    }
+
+    fn visit_borrowed_str<E>(self, value: &'de str) -> Result<Self::Value, E>
+        where E: serde::de::Error
+    {
+        match self.renamed(value) {
+            Some(new) => self.visitor.visit_str(new),
+            None => self.visitor.visit_borrowed_str(value)
+        }
+    }
+
+    fn visit_string<E>(self, value: String) -> Result<Self::Value, E>
+        where E: serde::de::Error
+    {
+        match self.renamed(&value) {
+            Some(new) => self.visitor.visit_str(new),
+            None => self.visitor.visit_string(value)
+        }
+    }
+
+    fn visit_bytes<E>(self, value: &[u8]) -> Result<Self::Value, E>
+        where E: serde::de::Error
+    {
+        match std::str::from_utf8(value).ok().and_then(|value| self.renamed(value)) {
+            Some(new) => self.visitor.visit_bytes(new.as_bytes()),
+            None => self.visitor.visit_bytes(value)
+        }
+    }
+
+    fn visit_borrowed_bytes<E>(self, value: &'de [u8]) -> Result<Self::Value, E>
+        where E: serde::de::Error
+    {
+        match std::str::from_utf8(value).ok().and_then(|value| self.renamed(value)) {
+            Some(new) => self.visitor.visit_bytes(new.as_bytes()),
+            None => self.visitor.visit_borrowed_bytes(value)
+        }
+    }
+
+    fn visit_byte_buf<E>(self, value: Vec<u8>) -> Result<Self::Value, E>
+        where E: serde::de::Error
+    {
+        match std::str::from_utf8(&value).ok().and_then(|value| self.renamed(value)) {
+            Some(new) => self.visitor.visit_bytes(new.as_bytes()),
+            None => self.visitor.visit_byte_buf(value)
+        }
+    }
+
+    fn visit_none<E>(self) -> Result<Self::Value, E>
+        where E: serde::de::Error
+    {
+        self.visitor.visit_none()
+    }
+
+    fn visit_unit<E>(self) -> Result<Self::Value, E>
+        where E: serde::de::Error
+    {
+        self.visitor.visit_unit()
+    }
+
+    fn visit_some<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+        where D: Deserializer<'de>
+    {
+        self.visitor.visit_some(RenamingDeserializer { de: deserializer, renames: self.renames })
+    }
+
+    fn visit_newtype_struct<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+        where D: Deserializer<'de>
+    {
+        self.visitor.visit_newtype_struct(RenamingDeserializer { de: deserializer, renames: self.renames })
+    }
+
+    fn visit_seq<A>(self, seq: A) -> Result<Self::Value, A::Error>
+        where A: SeqAccess<'de>
+    {
+        let seq = self.wrap(seq);
+        self.visitor.visit_seq(seq)
+    }
+
+    fn visit_map<A>(self, map: A) -> Result<Self::Value, A::Error>
+        where A: MapAccess<'de>
+    {
+        let map = self.wrap(map);
+        self.visitor.visit_map(map)
+    }
+
+    fn visit_enum<A>(self, data: A) -> Result<Self::Value, A::Error>
+        where A: EnumAccess<'de>
+    {
+        let data = self.wrap(data);
+        self.visitor.visit_enum(data)
+    }
+}
+
+impl<'a, 'de, X> SeqAccess<'de> for Renaming<'a, X>
+    where X: SeqAccess<'de>
+{
+    type Error = X::Error;
+
+    fn next_element_seed<T>(&mut self, seed: T) -> Result<Option<T::Value>, Self::Error>
+        where T: DeserializeSeed<'de>
+    {
+        self.visitor.next_element_seed(RenamingSeed::new(seed, self.renames))
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        self.visitor.size_hint()
+    }
+}
+
+impl<'a, 'de, X> MapAccess<'de> for Renaming<'a, X>
+    where X: MapAccess<'de>
+{
+    type Error = X::Error;
+
+    fn next_key_seed<K>(&mut self, seed: K) -> Result<Option<K::Value>, Self::Error>
+        where K: DeserializeSeed<'de>
+    {
+        self.visitor.next_key_seed(RenamingSeed::new(seed, self.renames))
+    }
+
+    fn next_value_seed<V>(&mut self, seed: V) -> Result<V::Value, Self::Error>
+        where V: DeserializeSeed<'de>
+    {
+        self.visitor.next_value_seed(RenamingSeed::new(seed, self.renames))
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        self.visitor.size_hint()
+    }
+}
+
+impl<'a, 'de, X> EnumAccess<'de> for Renaming<'a, X>
+    where X: EnumAccess<'de>
+{
+    type Error = X::Error;
+    type Variant = Renaming<'a, X::Variant>;
+
+    fn variant_seed<V>(self, seed: V) -> Result<(V::Value, Self::Variant), Self::Error>
+        where V: DeserializeSeed<'de>
+    {
+        let renames = self.renames;
+        let (value, access) = self.visitor.variant_seed(RenamingSeed::new(seed, renames))?;
+        return Ok((value, Renaming { visitor: access, renames: renames, identifier: false }));
+    }
+}
+
+impl<'a, 'de, X> VariantAccess<'de> for Renaming<'a, X>
+    where X: VariantAccess<'de>
+{
+    type Error = X::Error;
+
+    fn unit_variant(self) -> Result<(), Self::Error> {
+        self.visitor.unit_variant()
+    }
+
+    fn newtype_variant_seed<T>(self, seed: T) -> Result<T::Value, Self::Error>
+        where T: DeserializeSeed<'de>
+    {
+        self.visitor.newtype_variant_seed(RenamingSeed::new(seed, self.renames))
+    }
+
+    fn tuple_variant<V>(self, len: usize, visitor: V) -> Result<V::Value, Self::Error>
+        where V: Visitor<'de>
+    {
+        let visitor = self.wrap(visitor);
+        self.visitor.tuple_variant(len, visitor)
+    }
+
+    fn struct_variant<V>(self, fields: &'static [&'static str], visitor: V) -> Result<V::Value, Self::Error>
+        where V: Visitor<'de>
+    {
+        let visitor = self.wrap(visitor);
+        self.visitor.struct_variant(fields, visitor)
+    }
+}
+
+#[cfg(test)]
+use std::marker::PhantomData;
+
+#[cfg(test)]
+#[derive(serde::Deserialize, serde::Serialize, Debug, PartialEq)]
+enum Record {
+    A(i64),
+    B { values: Vec<u8>, names: HashMap<String, i64> }
+}
+
+#[test]
+fn test_serde_json() {
+    let renames = Renames::new().with_rename("Old", "B").with_rename("old_values", "values").with_rename("key", "other");
+    let mut deserializer = serde_json::Deserializer::from_str(r#"[{"A": 1}, {"Old": {"old_values": [1, 2], "names": {"key": 3}}}]"#);
+    let result = RenamingSeed::new(PhantomData::<Vec<Record>>, &renames).deserialize(&mut deserializer).unwrap();
+    assert_eq!(vec![Record::A(1), Record::B { values: vec![1, 2], names: HashMap::from([("key".to_owned(), 3)]) }], result);
+
+    let mut deserializer = serde_json::Deserializer::from_str(r#"{"Old": {"values": [], "names": {}}}"#);
+    assert!(PhantomData::<Record>.deserialize(&mut deserializer).is_err());
+}
+
+#[test]
+fn test_serde_postcard() {
+    let renames = Renames::new().with_rename("A", "B");
+    let value = Record::A(5);
+    let serialized = postcard::to_allocvec(&value).unwrap();
+    let mut deserializer = postcard::Deserializer::from_flavor(postcard::de_flavors::Slice::new(&serialized));
+    assert_eq!(value, RenamingSeed::new(PhantomData::<Record>, &renames).deserialize(&mut deserializer).unwrap());
+}
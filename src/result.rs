@@ -0,0 +1,175 @@
+use serde::de::{DeserializeSeed, EnumAccess, VariantAccess, Visitor};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+use crate::into_seed::IntoSeed;
+
+const VARIANTS: &[&str] = &["Ok", "Err"];
+
+#[derive(Deserialize)]
+#[serde(variant_identifier)]
+enum Variant {
+    Ok,
+    Err
+}
+
+///
+/// Wraps a [`Result`] of references, and implements [`Serialize`] by mapping it to an
+/// enum with the newtype variants `Ok` and `Err`, exactly as serde does for [`Result`].
+/// The contained value is serialized by first passing it to the function given for its
+/// variant, which can e.g. wrap it in another serializable wrapper carrying additional
+/// context.
+///
+/// Use [`DeserializeSeedResult`] to deserialize it.
+///
+/// # Example
+/// ```
+/// # use feanor_serde::result::*;
+/// # use feanor_serde::seq::*;
+/// # use feanor_serde::default_seed::seed_of;
+/// # use serde::de::DeserializeSeed;
+/// let data: Result<Vec<i64>, String> = Ok(vec![1, 2]);
+/// let json = serde_json::to_string(&SerializableResult::new(data.as_ref(), |x| SerializableSeq::new(x.iter()), |e| e)).unwrap();
+/// assert_eq!("{\"Ok\":[1,2]}", json);
+/// let seed = DeserializeSeedResult::new(
+///     DeserializeSeedSeq::new(std::iter::repeat(seed_of::<i64>()), 0, |current, next| current + next),
+///     seed_of::<String>()
+/// );
+/// assert_eq!(Ok(3), seed.deserialize(&mut serde_json::Deserializer::from_str(&json)).unwrap());
+/// ```
+///
+#[derive(Clone, Copy)]
+pub struct SerializableResult<'a, T, E, F, G>
+    where T: ?Sized,
+        E: ?Sized
+{
+    data: Result<&'a T, &'a E>,
+    wrap_ok: F,
+    wrap_err: G
+}
+
+impl<'a, T, E, F, G, U, V> SerializableResult<'a, T, E, F, G>
+    where T: ?Sized,
+        E: ?Sized,
+        F: Fn(&'a T) -> U,
+        G: Fn(&'a E) -> V,
+        U: Serialize,
+        V: Serialize
+{
+    pub const fn new(data: Result<&'a T, &'a E>, wrap_ok: F, wrap_err: G) -> Self {
+        Self { data, wrap_ok, wrap_err }
+    }
+}
+
+impl<'a, T, E, F, G, U, V> Serialize for SerializableResult<'a, T, E, F, G>
+    where T: ?Sized,
+        E: ?Sized,
+        F: Fn(&'a T) -> U,
+        G: Fn(&'a E) -> V,
+        U: Serialize,
+        V: Serialize
+{
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+        where S: Serializer
+    {
+        match self.data {
+            Ok(value) => serializer.serialize_newtype_variant("Result", 0, VARIANTS[0], &(self.wrap_ok)(value)),
+            Err(error) => serializer.serialize_newtype_variant("Result", 1, VARIANTS[1], &(self.wrap_err)(error))
+        }
+    }
+}
+
+///
+/// A [`DeserializeSeed`] that deserializes a [`Result`], using the first given
+/// [`DeserializeSeed`] for the `Ok` variant and the second one for the `Err` variant.
+///
+#[derive(Clone, Copy, Debug)]
+pub struct DeserializeSeedResult<S, R> {
+    ok_seed: S,
+    err_seed: R
+}
+
+impl<S, R> DeserializeSeedResult<S, R> {
+
+    pub fn new<'de, I, J, M, N>(ok_seed: I, err_seed: J) -> Self
+        where I: IntoSeed<'de, M, Seed = S>,
+            J: IntoSeed<'de, N, Seed = R>
+    {
+        Self { ok_seed: ok_seed.into_seed(), err_seed: err_seed.into_seed() }
+    }
+}
+
+impl<'de, S, R> DeserializeSeed<'de> for DeserializeSeedResult<S, R>
+    where S: DeserializeSeed<'de>,
+        R: DeserializeSeed<'de>
+{
+    type Value = Result<S::Value, R::Value>;
+
+    fn deserialize<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+        where D: Deserializer<'de>
+    {
+        struct ResultVisitor<S, R> {
+            ok_seed: S,
+            err_seed: R
+        }
+
+        impl<'de, S, R> Visitor<'de> for ResultVisitor<S, R>
+            where S: DeserializeSeed<'de>,
+                R: DeserializeSeed<'de>
+        {
+            type Value = Result<S::Value, R::Value>;
+
+            fn expecting(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+                write!(f, "enum Result")
+            }
+
+            fn visit_enum<A>(self, data: A) -> Result<Self::Value, A::Error>
+                where A: EnumAccess<'de>
+            {
+                let (variant, content) = data.variant::<Variant>()?;
+                match variant {
+                    Variant::Ok => content.newtype_variant_seed(self.ok_seed).map(Ok),
+                    Variant::Err => content.newtype_variant_seed(self.err_seed).map(Err)
+                }
+            }
+        }
+
+        return deserializer.deserialize_enum("Result", VARIANTS, ResultVisitor {
+            ok_seed: self.ok_seed,
+            err_seed: self.err_seed
+        });
+    }
+}
+
+#[cfg(test)]
+use crate::seq::{SerializableSeq, DeserializeSeedSeq};
+#[cfg(test)]
+use crate::default_seed::seed_of;
+
+#[cfg(test)]
+fn sum_seed() -> impl for<'de> DeserializeSeed<'de, Value = i64> {
+    DeserializeSeedSeq::new(std::iter::repeat(seed_of::<i64>()), 0, |current, next| current + next)
+}
+
+#[test]
+fn test_serde_postcard() {
+    let data = [Ok(vec![1i64, 2, 3]), Err(vec![4i64])];
+    for value in &data {
+        let serialized = postcard::to_allocvec(&SerializableResult::new(value.as_ref(), |x| SerializableSeq::new_with_len(x.iter(), x.len()), |e| SerializableSeq::new_with_len(e.iter(), e.len()))).unwrap();
+        assert_eq!(postcard::to_allocvec(value).unwrap(), serialized);
+        let result = DeserializeSeedResult::new(sum_seed(), sum_seed).deserialize(&mut postcard::Deserializer::from_bytes(&serialized)).unwrap();
+        assert_eq!(value.as_ref().map(|x| x.iter().sum::<i64>()).map_err(|e| e.iter().sum::<i64>()), result);
+    }
+}
+
+#[test]
+fn test_serde_json() {
+    let error: &str = "failed";
+    assert_eq!("{\"Err\":\"failed\"}", serde_json::to_string(&SerializableResult::new(Err::<&i64, _>(error), |x| x, |e| e)).unwrap());
+
+    let seed = DeserializeSeedResult::new(sum_seed(), seed_of::<String>());
+    assert_eq!(Err("failed".to_owned()), seed.deserialize(&mut serde_json::Deserializer::from_str("{\"Err\":\"failed\"}")).unwrap());
+    let seed = DeserializeSeedResult::new(sum_seed(), seed_of::<String>());
+    assert_eq!(Ok(0), seed.deserialize(&mut serde_json::Deserializer::from_str("{\"Ok\":[]}")).unwrap());
+    let seed = DeserializeSeedResult::new(sum_seed(), seed_of::<String>());
+    assert!(seed.deserialize(&mut serde_json::Deserializer::from_str("{\"Some\":[]}")).is_err());
+}
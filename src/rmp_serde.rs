@@ -0,0 +1,200 @@
+use serde::de::DeserializeSeed;
+use serde::ser::Serialize;
+
+use ::rmp_serde::decode::Error;
+
+///
+/// Deserializes a value from the given MessagePack-encoded bytes, using the given [`DeserializeSeed`].
+/// The unused portion (if any) of the byte slice is ignored.
+///
+/// # Compatibility
+///
+/// MessagePack is self-describing, so all combinators of this crate are supported. However, note that
+///  - rmp-serde is not human-readable by default, so [`crate::field_index::SerializableFieldIndices`]
+///    writes structs as maps with integer keys;
+///  - by default, i.e. when using [`to_vec()`], structs are serialized as arrays, without field names.
+///    Such structs can only be deserialized by [`crate::impl_deserialize_seed_for_dependent_struct!`]
+///    if the fields are declared in the same order as in the serialized struct. When serialized with
+///    [`to_vec_named()`], structs are maps with the field names as keys, which the macro accepts in
+///    any order. In both cases, the macro also accepts maps with field indices as keys;
+///  - enum variants are serialized by name, as a string for unit variants and as a map with a
+///    single entry otherwise.
+///
+/// # Example
+/// ```
+/// # use feanor_serde::seq::*;
+/// # use feanor_serde::default_seed::seed_of;
+/// let serialized = feanor_serde::rmp_serde::to_vec(SerializableSeq::new([1, 3, 5].into_iter())).unwrap();
+/// let seed = SeqSeedBuilder::new(seed_of::<i64>).collect_vec();
+/// assert_eq!(vec![1, 3, 5], feanor_serde::rmp_serde::from_slice_seeded(seed, &serialized).unwrap());
+/// ```
+///
+pub fn from_slice_seeded<'de, S>(seed: S, data: &'de [u8]) -> Result<S::Value, Error>
+    where S: DeserializeSeed<'de>
+{
+    let mut deserializer = ::rmp_serde::Deserializer::from_read_ref(data);
+    return seed.deserialize(&mut deserializer);
+}
+
+///
+/// Serializes the given value into a newly allocated [`Vec`], writing structs as arrays.
+///
+/// This is just [`::rmp_serde::to_vec()`], but accepts the serializable wrappers of this crate
+/// (like [`crate::seq::SerializableSeq`]) by value, so that they can be created inline.
+///
+pub fn to_vec<T>(value: T) -> Result<Vec<u8>, ::rmp_serde::encode::Error>
+    where T: Serialize
+{
+    ::rmp_serde::to_vec(&value)
+}
+
+///
+/// Serializes the given value into a newly allocated [`Vec`], writing structs as maps
+/// with the field names as keys.
+///
+/// This is just [`::rmp_serde::to_vec_named()`], but accepts the serializable wrappers of this
+/// crate (like [`crate::seq::SerializableSeq`]) by value, so that they can be created inline.
+///
+pub fn to_vec_named<T>(value: T) -> Result<Vec<u8>, ::rmp_serde::encode::Error>
+    where T: Serialize
+{
+    ::rmp_serde::to_vec_named(&value)
+}
+
+#[cfg(test)]
+use std::collections::BTreeMap;
+#[cfg(test)]
+use crate::default_seed::seed_of;
+#[cfg(test)]
+use crate::field_index::SerializableFieldIndices;
+#[cfg(test)]
+use crate::seq::{SerializableSeq, SeqSeedBuilder};
+#[cfg(test)]
+use crate::map::{SerializableMap, DeserializeSeedMap};
+#[cfg(test)]
+use crate::option::{SerializableOption, DeserializeSeedOption};
+#[cfg(test)]
+use crate::struct_seed::StructSeed;
+#[cfg(test)]
+use crate::enum_seed::EnumSeed;
+#[cfg(test)]
+use crate::{impl_deserialize_seed_for_dependent_enum, impl_deserialize_seed_for_dependent_struct};
+
+#[cfg(test)]
+#[derive(Debug, PartialEq, serde::Serialize)]
+struct Foo {
+    a: i64,
+    b: Option<String>,
+    c: Vec<u8>
+}
+
+#[cfg(test)]
+#[derive(Debug, PartialEq, serde::Serialize)]
+struct FooReordered {
+    c: Vec<u8>,
+    a: i64,
+    b: Option<String>
+}
+
+#[cfg(test)]
+#[derive(Debug, PartialEq, serde::Serialize)]
+enum Bar {
+    A(i64),
+    B(String)
+}
+
+#[cfg(test)]
+struct DeserializeSeedFoo;
+
+#[cfg(test)]
+impl_deserialize_seed_for_dependent_struct!{
+    pub struct DeserializedFoo<'de> using DeserializeSeedFoo {
+        a: i64: |_| seed_of::<i64>(),
+        b: Option<String>: |_| seed_of::<Option<String>>(),
+        c: Vec<u8>: |_| seed_of::<Vec<u8>>()
+    }
+}
+
+#[cfg(test)]
+struct DeserializeSeedBar;
+
+#[cfg(test)]
+impl_deserialize_seed_for_dependent_enum!{
+    pub enum DeserializedBar<'de> using DeserializeSeedBar {
+        A(i64): |_| seed_of::<i64>(),
+        B(String): |_| seed_of::<String>()
+    }
+}
+
+#[cfg(test)]
+fn deserialize_foo(data: &[u8]) -> Result<Foo, Error> {
+    let result = from_slice_seeded(DeserializeSeedFoo, data)?;
+    return Ok(Foo { a: result.a, b: result.b, c: result.c });
+}
+
+#[cfg(test)]
+fn foo_seed<'de>() -> impl DeserializeSeed<'de, Value = Foo> {
+    StructSeed::new("Foo").field("a", seed_of::<i64>()).field("b", seed_of::<Option<String>>()).field("c", seed_of::<Vec<u8>>())
+        .finish_with(|((a, b), c)| Foo { a, b, c })
+}
+
+#[test]
+fn test_compact() {
+    let foo = Foo { a: -1, b: Some("x".to_owned()), c: vec![1, 2] };
+    let serialized = to_vec(&foo).unwrap();
+    assert_eq!(0x93, serialized[0]);
+    assert_eq!(foo, deserialize_foo(&serialized).unwrap());
+    assert_eq!(foo, from_slice_seeded(foo_seed(), &serialized).unwrap());
+
+    let reordered = FooReordered { c: vec![1, 2], a: -1, b: Some("x".to_owned()) };
+    assert!(deserialize_foo(&to_vec(&reordered).unwrap()).is_err());
+
+    let serialized = to_vec(SerializableFieldIndices::new(&foo)).unwrap();
+    assert_eq!(0x83, serialized[0]);
+    assert_eq!(foo, deserialize_foo(&serialized).unwrap());
+    assert_eq!(foo, from_slice_seeded(foo_seed(), &serialized).unwrap());
+}
+
+#[test]
+fn test_named() {
+    let foo = Foo { a: -1, b: None, c: vec![] };
+    let serialized = to_vec_named(&foo).unwrap();
+    assert_eq!(0x83, serialized[0]);
+    assert_eq!(foo, deserialize_foo(&serialized).unwrap());
+    assert_eq!(foo, from_slice_seeded(foo_seed(), &serialized).unwrap());
+
+    let reordered = FooReordered { c: vec![], a: -1, b: None };
+    assert_eq!(foo, deserialize_foo(&to_vec_named(&reordered).unwrap()).unwrap());
+    assert_eq!(foo, from_slice_seeded(foo_seed(), &to_vec_named(&reordered).unwrap()).unwrap());
+
+    let serialized = to_vec_named(SerializableMap::new_with_len([("a", 5i64)].into_iter(), 1)).unwrap();
+    assert!(from_slice_seeded(foo_seed(), &serialized).is_err());
+    assert!(deserialize_foo(&serialized).is_err());
+}
+
+#[test]
+fn test_combinators() {
+    for serialized in [to_vec(SerializableSeq::new([1i64, -2, 3].into_iter())).unwrap(), to_vec(SerializableSeq::new_with_len([1i64, -2, 3].into_iter(), 3)).unwrap()] {
+        assert_eq!(vec![1, -2, 3], from_slice_seeded(SeqSeedBuilder::new(seed_of::<i64>).collect_vec(), &serialized).unwrap());
+        assert!(from_slice_seeded(SeqSeedBuilder::from_seeds(std::iter::repeat_n(seed_of::<i64>(), 2)).collect_vec(), &serialized).is_err());
+    }
+
+    let map = BTreeMap::from([("a".to_owned(), 1i64), ("b".to_owned(), 2)]);
+    let serialized = to_vec(SerializableMap::new(map.iter())).unwrap();
+    let seed = DeserializeSeedMap::new(std::iter::repeat((seed_of::<String>(), seed_of::<i64>())), BTreeMap::new(), |mut current, key, value| { current.insert(key, value); current });
+    assert_eq!(map, from_slice_seeded(seed, &serialized).unwrap());
+
+    let serialized = to_vec(SerializableOption::new(Some(&5i64), |x| *x)).unwrap();
+    assert_eq!(Some(5), from_slice_seeded(DeserializeSeedOption::new(seed_of::<i64>()), &serialized).unwrap());
+
+    for bar in [Bar::A(-1), Bar::B("x".to_owned())] {
+        let serialized = to_vec(&bar).unwrap();
+        let result = match from_slice_seeded(DeserializeSeedBar, &serialized).unwrap() {
+            DeserializedBar::A(value) => Bar::A(value.0),
+            DeserializedBar::B(value) => Bar::B(value.0)
+        };
+        assert_eq!(bar, result);
+        let seed = EnumSeed::new("Bar").variant("A", seed_of::<i64>(), Bar::A).variant("B", seed_of::<String>(), Bar::B);
+        assert_eq!(bar, from_slice_seeded(seed, &serialized).unwrap());
+    }
+}
@@ -0,0 +1,276 @@
+use std::marker::PhantomData;
+
+use serde::de::{DeserializeSeed, IgnoredAny, MapAccess, Visitor};
+use serde::Deserializer;
+
+///
+/// A route of a [`DeserializeSeedRoutedMap`], which handles all map entries whose key
+/// starts with a given prefix.
+///
+pub trait MapRoute<'de> {
+
+    type Output;
+
+    fn prefix(&self) -> &str;
+
+    ///
+    /// Deserializes the value of the next entry of the map, whose key without the prefix
+    /// is `key`.
+    ///
+    fn deserialize_entry<A>(&mut self, key: String, map: &mut A) -> Result<(), A::Error>
+        where A: MapAccess<'de>;
+
+    fn finish(self) -> Self::Output;
+}
+
+///
+/// A [`MapRoute`] that deserializes the values of all entries with the given prefix using
+/// [`DeserializeSeed`]s created by the given function from the key (without the prefix), and
+/// combines them using the given collector, similar to [`crate::map::DeserializeSeedMap`].
+///
+pub struct Route<F, T, C> {
+    prefix: &'static str,
+    value_seed: F,
+    current: Option<T>,
+    collector: C
+}
+
+impl<F, T, C> Route<F, T, C> {
+
+    pub const fn new(prefix: &'static str, value_seed: F, initial: T, collector: C) -> Self {
+        Self { prefix: prefix, value_seed: value_seed, current: Some(initial), collector: collector }
+    }
+}
+
+impl<'de, F, S, T, C> MapRoute<'de> for Route<F, T, C>
+    where F: FnMut(&str) -> S,
+        S: DeserializeSeed<'de>,
+        C: FnMut(T, String, S::Value) -> T
+{
+    type Output = T;
+
+    fn prefix(&self) -> &str {
+        self.prefix
+    }
+
+    fn deserialize_entry<A>(&mut self, mut key: String, map: &mut A) -> Result<(), A::Error>
+        where A: MapAccess<'de>
+    {
+        key.drain(..self.prefix.len());
+        let value = map.next_value_seed((self.value_seed)(&key))?;
+        let current = self.current.take().unwrap();
+        self.current = Some((self.collector)(current, key, value));
+        return Ok(());
+    }
+
+    fn finish(self) -> T {
+        self.current.unwrap()
+    }
+}
+
+///
+/// A [`MapRoute`] that skips the values of all entries with the given prefix. In particular,
+/// `IgnoredRoute::new("")` can be used as last route to ignore all remaining entries.
+///
+/// Skipping values requires [`Deserializer::deserialize_ignored_any()`], so this only works
+/// for self-describing formats.
+///
+#[derive(Clone, Copy, Debug)]
+pub struct IgnoredRoute {
+    prefix: &'static str
+}
+
+impl IgnoredRoute {
+
+    pub const fn new(prefix: &'static str) -> Self {
+        Self { prefix }
+    }
+}
+
+impl<'de> MapRoute<'de> for IgnoredRoute {
+
+    type Output = ();
+
+    fn prefix(&self) -> &str {
+        self.prefix
+    }
+
+    fn deserialize_entry<A>(&mut self, _key: String, map: &mut A) -> Result<(), A::Error>
+        where A: MapAccess<'de>
+    {
+        map.next_value::<IgnoredAny>()?;
+        return Ok(());
+    }
+
+    fn finish(self) {}
+}
+
+///
+/// A tuple of [`MapRoute`]s, as used by [`DeserializeSeedRoutedMap`].
+///
+pub trait MapRoutes<'de> {
+
+    type Output;
+
+    ///
+    /// Deserializes the value of the next entry of the map using the first route whose
+    /// prefix matches the key. Returns the key if no route matches.
+    ///
+    fn deserialize_entry<A>(&mut self, key: String, map: &mut A) -> Result<Result<(), String>, A::Error>
+        where A: MapAccess<'de>;
+
+    fn finish(self) -> Self::Output;
+}
+
+macro_rules! impl_map_routes_for_tuple {
+    ($($route:ident: $index:tt),*) => {
+        impl<'de, $($route),*> MapRoutes<'de> for ($($route,)*)
+            where $($route: MapRoute<'de>),*
+        {
+            type Output = ($($route::Output,)*);
+
+            fn deserialize_entry<A>(&mut self, key: String, map: &mut A) -> Result<Result<(), String>, A::Error>
+                where A: MapAccess<'de>
+            {
+                $(
+                    if key.starts_with(self.$index.prefix()) {
+                        self.$index.deserialize_entry(key, map)?;
+                        return Ok(Ok(()));
+                    }
+                )*
+                return Ok(Err(key));
+            }
+
+            fn finish(self) -> Self::Output {
+                ($(self.$index.finish(),)*)
+            }
+        }
+    };
+}
+
+impl_map_routes_for_tuple!{ R0: 0 }
+impl_map_routes_for_tuple!{ R0: 0, R1: 1 }
+impl_map_routes_for_tuple!{ R0: 0, R1: 1, R2: 2 }
+impl_map_routes_for_tuple!{ R0: 0, R1: 1, R2: 2, R3: 3 }
+impl_map_routes_for_tuple!{ R0: 0, R1: 1, R2: 2, R3: 3, R4: 4 }
+impl_map_routes_for_tuple!{ R0: 0, R1: 1, R2: 2, R3: 3, R4: 4, R5: 5 }
+impl_map_routes_for_tuple!{ R0: 0, R1: 1, R2: 2, R3: 3, R4: 4, R5: 5, R6: 6 }
+impl_map_routes_for_tuple!{ R0: 0, R1: 1, R2: 2, R3: 3, R4: 4, R5: 5, R6: 6, R7: 7 }
+
+///
+/// A [`DeserializeSeed`] that deserializes a map with string keys, and routes each entry
+/// to one of the given [`MapRoute`]s based on the prefix of its key. Each route accumulates
+/// its own output, and the result is the tuple of these outputs.
+///
+/// The routes are given as a tuple, and each entry is handled by the first route whose prefix
+/// matches its key. Deserialization fails if no route matches a key, so to ignore or collect all
+/// remaining entries, add a route with the empty prefix at the end, e.g. [`IgnoredRoute`].
+///
+/// # Example
+/// ```
+/// # use feanor_serde::routing::*;
+/// # use feanor_serde::default_seed::seed_of;
+/// # use std::collections::BTreeMap;
+/// # use serde::de::DeserializeSeed;
+/// let json = r#"{ "db.host": "localhost", "net.port": 80, "db.user": "admin", "net.timeout": 30, "log": true }"#;
+/// let seed = DeserializeSeedRoutedMap::new((
+///     Route::new("db.", |_: &str| seed_of::<String>(), BTreeMap::new(), |mut db: BTreeMap<_, _>, key, value| { db.insert(key, value); db }),
+///     Route::new("net.", |_: &str| seed_of::<u64>(), BTreeMap::new(), |mut net: BTreeMap<_, _>, key, value| { net.insert(key, value); net }),
+///     IgnoredRoute::new("")
+/// ));
+/// let (db, net, ()) = seed.deserialize(&mut serde_json::Deserializer::from_str(json)).unwrap();
+/// assert_eq!(BTreeMap::from([("host".to_owned(), "localhost".to_owned()), ("user".to_owned(), "admin".to_owned())]), db);
+/// assert_eq!(BTreeMap::from([("port".to_owned(), 80), ("timeout".to_owned(), 30)]), net);
+/// ```
+///
+pub struct DeserializeSeedRoutedMap<R> {
+    routes: R
+}
+
+impl<R> DeserializeSeedRoutedMap<R> {
+
+    pub const fn new(routes: R) -> Self {
+        Self { routes }
+    }
+}
+
+impl<'de, R> DeserializeSeed<'de> for DeserializeSeedRoutedMap<R>
+    where R: MapRoutes<'de>
+{
+    type Value = R::Output;
+
+    fn deserialize<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+        where D: Deserializer<'de>
+    {
+        struct ResultVisitor<'de, R> {
+            routes: R,
+            deserializer: PhantomData<&'de ()>
+        }
+
+        impl<'de, R> Visitor<'de> for ResultVisitor<'de, R>
+            where R: MapRoutes<'de>
+        {
+            type Value = R::Output;
+
+            fn expecting(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+                write!(f, "a map with string keys")
+            }
+
+            fn visit_map<A>(mut self, mut map: A) -> Result<Self::Value, A::Error>
+                where A: MapAccess<'de>
+            {
+                while let Some(key) = map.next_key::<String>()? {
+                    if let Err(key) = self.routes.deserialize_entry(key, &mut map)? {
+                        return Err(crate::errors::custom("map key does not match any route", format_args!("map key \"{}\" does not match any route", key)));
+                    }
+                }
+                return Ok(self.routes.finish());
+            }
+        }
+
+        return deserializer.deserialize_map(ResultVisitor { routes: self.routes, deserializer: PhantomData });
+    }
+}
+
+#[cfg(test)]
+use crate::default_seed::seed_of;
+#[cfg(test)]
+use std::collections::BTreeMap;
+
+#[test]
+fn test_serde_json() {
+    let push = |mut current: Vec<(String, i64)>, key, value| { current.push((key, value)); current };
+    let seed = || DeserializeSeedRoutedMap::new((
+        Route::new("a.b.", |_: &str| seed_of::<i64>(), Vec::new(), push),
+        Route::new("a.", |_: &str| seed_of::<i64>(), Vec::new(), push)
+    ));
+    let (ab, a) = seed().deserialize(&mut serde_json::Deserializer::from_str(r#"{"a.x": 1, "a.b.y": 2, "a.b.": 3, "a.z": 4}"#)).unwrap();
+    assert_eq!(vec![("y".to_owned(), 2), ("".to_owned(), 3)], ab);
+    assert_eq!(vec![("x".to_owned(), 1), ("z".to_owned(), 4)], a);
+
+    let (ab, a) = seed().deserialize(&mut serde_json::Deserializer::from_str("{}")).unwrap();
+    assert!(ab.is_empty() && a.is_empty());
+
+    let error = seed().deserialize(&mut serde_json::Deserializer::from_str(r#"{"a.x": 1, "b": 2}"#)).err().unwrap();
+    assert!(error.to_string().contains("does not match any route"));
+    assert!(seed().deserialize(&mut serde_json::Deserializer::from_str(r#"{"a.x": "1"}"#)).is_err());
+
+    let (rest,) = DeserializeSeedRoutedMap::new((Route::new("", |_: &str| seed_of::<i64>(), 0, |sum, _, value| sum + value),))
+        .deserialize(&mut serde_json::Deserializer::from_str(r#"{"a": 1, "b": 2}"#)).unwrap();
+    assert_eq!(3, rest);
+}
+
+#[test]
+fn test_serde_postcard() {
+    let data = BTreeMap::from([("x.a".to_owned(), 1i64), ("x.b".to_owned(), 2), ("y".to_owned(), 3)]);
+    let serialized = postcard::to_allocvec(&data).unwrap();
+    let seed = DeserializeSeedRoutedMap::new((
+        Route::new("x.", |_: &str| seed_of::<i64>(), BTreeMap::new(), |mut current: BTreeMap<String, i64>, key, value| { current.insert(key, value); current }),
+        Route::new("", |_: &str| seed_of::<i64>(), 0, |sum, _, value| sum + value)
+    ));
+    let (x, rest) = seed.deserialize(&mut postcard::Deserializer::from_bytes(&serialized)).unwrap();
+    assert_eq!(BTreeMap::from([("a".to_owned(), 1), ("b".to_owned(), 2)]), x);
+    assert_eq!(3, rest);
+
+    assert!(DeserializeSeedRoutedMap::new((IgnoredRoute::new(""),)).deserialize(&mut postcard::Deserializer::from_bytes(&serialized)).is_err());
+}
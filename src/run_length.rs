@@ -0,0 +1,277 @@
+use std::marker::PhantomData;
+
+use serde::de::{DeserializeSeed, Error, SeqAccess, Visitor};
+use serde::ser::{Serialize, SerializeSeq, Serializer};
+use serde::Deserializer;
+
+///
+/// Wraps an [`Iterator`] over serializable elements, and implements [`Serialize`]
+/// by mapping it to a sequence of runs `(count, value)` in the serde data model,
+/// where each run represents `count` consecutive elements equal to `value`.
+///
+/// # Example
+/// ```
+/// # use feanor_serde::run_length::*;
+/// let data = [0, 0, 0, 0, 1, 0, 0];
+/// assert_eq!("[[4,0],[1,1],[2,0]]", serde_json::to_string(&SerializableRunLength::new(data.iter())).unwrap());
+/// ```
+///
+pub struct SerializableRunLength<I>
+    where I: Iterator + Clone,
+        I::Item: PartialEq
+{
+    data: I
+}
+
+impl<I> SerializableRunLength<I>
+    where I: Iterator + Clone,
+        I::Item: PartialEq
+{
+    pub const fn new(data: I) -> Self {
+        Self { data }
+    }
+
+    fn runs(&self) -> impl Iterator<Item = (usize, I::Item)> {
+        let mut data = self.data.clone().peekable();
+        std::iter::from_fn(move || {
+            let value = data.next()?;
+            let mut count = 1;
+            while data.next_if_eq(&value).is_some() {
+                count += 1;
+            }
+            return Some((count, value));
+        })
+    }
+}
+
+impl<I> Serialize for SerializableRunLength<I>
+    where I: Iterator + Clone,
+        I::Item: PartialEq + Serialize
+{
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+        where S: Serializer
+    {
+        let mut seq = serializer.serialize_seq(Some(self.runs().count()))?;
+        for run in self.runs() {
+            seq.serialize_element(&run)?;
+        }
+        return seq.end();
+    }
+}
+
+struct DeserializeSeedRun<S> {
+    value_seed: S
+}
+
+impl<'de, S> Visitor<'de> for DeserializeSeedRun<S>
+    where S: DeserializeSeed<'de>
+{
+    type Value = (usize, S::Value);
+
+    fn expecting(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "a tuple (count, value)")
+    }
+
+    fn visit_seq<A>(self, mut seq: A) -> Result<Self::Value, A::Error>
+        where A: SeqAccess<'de>
+    {
+        let count = seq.next_element()?.ok_or_else(|| <A::Error as Error>::invalid_length(0, &"a tuple (count, value)"))?;
+        let value = seq.next_element_seed(self.value_seed)?.ok_or_else(|| <A::Error as Error>::invalid_length(1, &"a tuple (count, value)"))?;
+        return Ok((count, value));
+    }
+}
+
+impl<'de, S> DeserializeSeed<'de> for DeserializeSeedRun<S>
+    where S: DeserializeSeed<'de>
+{
+    type Value = (usize, S::Value);
+
+    fn deserialize<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+        where D: Deserializer<'de>
+    {
+        deserializer.deserialize_tuple(2, self)
+    }
+}
+
+///
+/// A [`DeserializeSeed`] that deserializes a sequence that was serialized using
+/// [`SerializableRunLength`]. The value of each run is deserialized using the next
+/// [`DeserializeSeed`] from the given iterator, and each run `(count, value)` is then
+/// passed to the given collector, which can either expand or fold it.
+///
+/// To protect against decompression bombs, deserialization fails if the total number
+/// of elements represented by the runs exceeds the given bound `max_len`.
+///
+/// As for [`crate::seq::DeserializeSeedSeq`], the iterator producing the seeds must
+/// contain at least one more seed than there are runs to deserialize.
+///
+/// # Example
+/// ```
+/// # use feanor_serde::run_length::*;
+/// # use feanor_serde::default_seed::seed_of;
+/// # use std::iter::{repeat, repeat_n};
+/// # use serde::de::DeserializeSeed;
+/// let mut deserializer = serde_json::Deserializer::new(serde_json::de::StrRead::new("[[4,0],[1,1],[2,0]]"));
+/// let deserialize_seed = DeserializeSeedRunLength::new(
+///     repeat(seed_of::<i64>()),
+///     1000,
+///     Vec::new(),
+///     |mut current, count, value| { current.extend(repeat_n(value, count)); current }
+/// );
+/// assert_eq!(vec![0, 0, 0, 0, 1, 0, 0], deserialize_seed.deserialize(&mut deserializer).unwrap());
+/// ```
+///
+pub struct DeserializeSeedRunLength<V, S, T, C>
+    where V: Iterator<Item = S>
+{
+    element_seed: PhantomData<S>,
+    seeds: V,
+    max_len: usize,
+    initial: T,
+    collector: C
+}
+
+impl<'de, V, S, T, C> DeserializeSeedRunLength<V, S, T, C>
+    where V: Iterator<Item = S>,
+        S: DeserializeSeed<'de>,
+        C: FnMut(T, usize, S::Value) -> T
+{
+    pub const fn new(seeds: V, max_len: usize, initial: T, collector: C) -> Self {
+        Self {
+            element_seed: PhantomData,
+            seeds: seeds,
+            max_len: max_len,
+            initial: initial,
+            collector: collector
+        }
+    }
+}
+
+impl<'de, V, S, T, C> DeserializeSeed<'de> for DeserializeSeedRunLength<V, S, T, C>
+    where V: Iterator<Item = S>,
+        S: DeserializeSeed<'de>,
+        C: FnMut(T, usize, S::Value) -> T
+{
+    type Value = T;
+
+    fn deserialize<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+        where D: Deserializer<'de>
+    {
+        struct ResultVisitor<'de, V, S, T, C>
+            where V: Iterator<Item = S>,
+                S: DeserializeSeed<'de>,
+                C: FnMut(T, usize, S::Value) -> T
+        {
+            deserializer: PhantomData<&'de ()>,
+            element_seed: PhantomData<S>,
+            seeds: V,
+            max_len: usize,
+            initial: T,
+            collector: C
+        }
+
+        impl<'de, V, S, T, C> Visitor<'de> for ResultVisitor<'de, V, S, T, C>
+            where V: Iterator<Item = S>,
+                S: DeserializeSeed<'de>,
+                C: FnMut(T, usize, S::Value) -> T
+        {
+            type Value = T;
+
+            fn expecting(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+                write!(f, "a sequence of runs (count, value)")
+            }
+
+            fn visit_seq<B>(mut self, mut seq: B) -> Result<Self::Value, B::Error>
+                where B: SeqAccess<'de>
+            {
+                let mut result = self.initial;
+                let mut current_runs = 0;
+                let mut current_len: usize = 0;
+                for seed in self.seeds.by_ref() {
+                    if let Some((count, value)) = seq.next_element_seed(DeserializeSeedRun { value_seed: seed })? {
+                        current_runs += 1;
+                        current_len = match current_len.checked_add(count) {
+                            Some(len) if len <= self.max_len => len,
+                            _ => return Err(Error::custom(format!("run-length encoded sequence exceeds the maximal length {}", self.max_len)))
+                        };
+                        result = (self.collector)(result, count, value);
+                    } else {
+                        return Ok(result);
+                    }
+                }
+                return Err(Error::invalid_length(current_runs + 1, &format!("a sequence of at most {} runs", current_runs).as_str()))
+            }
+        }
+
+        return deserializer.deserialize_seq(ResultVisitor {
+            deserializer: PhantomData,
+            element_seed: PhantomData,
+            seeds: self.seeds,
+            max_len: self.max_len,
+            initial: self.initial,
+            collector: self.collector
+        });
+    }
+}
+
+#[cfg(test)]
+use std::iter::{repeat, repeat_n};
+
+#[cfg(test)]
+fn testdata() -> Vec<Vec<i64>> {
+    vec![
+        Vec::new(),
+        vec![1],
+        vec![1, 1, 1],
+        vec![1, 2, 3],
+        vec![0, 0, 0, 0, 0, 5, 5, 0, 0, 0]
+    ]
+}
+
+#[cfg(test)]
+fn deserialize_seed_testdata<'de>(max_len: usize) -> impl DeserializeSeed<'de, Value = Vec<i64>> {
+    DeserializeSeedRunLength::new(
+        repeat(PhantomData::<i64>),
+        max_len,
+        Vec::new(),
+        |mut current, count, value| { current.extend(repeat_n(value, count)); current }
+    )
+}
+
+#[test]
+fn test_serde_postcard() {
+    for data in testdata() {
+        let serialized = postcard::to_allocvec(&SerializableRunLength::new(data.iter())).unwrap();
+        let result = deserialize_seed_testdata(data.len()).deserialize(
+            &mut postcard::Deserializer::from_flavor(postcard::de_flavors::Slice::new(&serialized))
+        ).unwrap();
+        assert_eq!(data, result);
+    }
+}
+
+#[test]
+fn test_serde_json() {
+    for data in testdata() {
+        let serialized = serde_json::to_string(&SerializableRunLength::new(data.iter())).unwrap();
+        let result = deserialize_seed_testdata(data.len()).deserialize(
+            &mut serde_json::Deserializer::from_str(&serialized)
+        ).unwrap();
+        assert_eq!(data, result);
+    }
+    assert_eq!("[[5,0],[2,5],[3,0]]", serde_json::to_string(&SerializableRunLength::new(testdata()[4].iter())).unwrap());
+}
+
+#[test]
+fn test_max_len() {
+    assert!(deserialize_seed_testdata(10).deserialize(&mut serde_json::Deserializer::from_str("[[5,0],[6,1]]")).is_err());
+    assert_eq!(11, deserialize_seed_testdata(11).deserialize(&mut serde_json::Deserializer::from_str("[[5,0],[6,1]]")).unwrap().len());
+
+    let fold = |json: &str| DeserializeSeedRunLength::new(
+        repeat(PhantomData::<i64>),
+        usize::MAX,
+        0,
+        |current, count, value| current + count as i64 * value
+    ).deserialize(&mut serde_json::Deserializer::from_str(json));
+    assert_eq!(3, fold(&format!("[[{},0],[3,1]]", usize::MAX - 3)).unwrap());
+    assert!(fold(&format!("[[{},0],[1,1]]", usize::MAX)).is_err());
+}
@@ -6,7 +6,7 @@
 /// ```
 /// # use feanor_serde::*;
 /// # use serde::de::DeserializeSeed;
-/// # use std::marker::PhantomData;
+/// # use feanor_serde::default_seed::seed_of;
 /// struct DeserializeSeedEither<S1, S2>
 ///     where S1: for<'de> DeserializeSeed<'de>,
 ///         S2: for<'de> DeserializeSeed<'de>
@@ -22,14 +22,13 @@
 ///         where S1: for<'de2> DeserializeSeed<'de2>,
 ///             S2: for<'de2> DeserializeSeed<'de2>
 /// }
-
 /// 
 /// let mut deserializer = serde_json::Deserializer::new(serde_json::de::StrRead::new(r#"{
 ///     "First": 1
 /// }"#));
 /// let deserialize_seed = DeserializeSeedEither {
-///     seed1: PhantomData::<i64>,
-///     seed2: PhantomData::<String>
+///     seed1: seed_of::<i64>(),
+///     seed2: seed_of::<String>()
 /// };
 /// let result = deserialize_seed.deserialize(&mut deserializer).unwrap();
 /// match result {
@@ -60,6 +59,16 @@ macro_rules! impl_deserialize_seed_for_dependent_enum {
         {
             $($variant(($type, std::marker::PhantomData<&'de ()>))),*
         }
+        impl<'de, $($deserialize_result_gen_args)*> $deserialize_result_enum_name<'de, $($deserialize_result_gen_args)*>
+            where $($constraints)*
+        {
+            /// A textual description of the variants of this enum, as used for `feanor_serde::fingerprint`.
+            #[allow(dead_code)]
+            pub const SCHEMA: &'static str = concat!(stringify!($deserialize_result_enum_name), " { ", $(stringify!($variant), "(", stringify!($type), "), ",)* "}");
+            /// The names, indices and payload types of the variants of this enum, see `feanor_serde::reflect`.
+            #[allow(dead_code)]
+            pub const VARIANTS: &'static [$crate::reflect::VariantInfo] = &$crate::reflect::index_variants([$($crate::reflect::VariantInfo::new(stringify!($variant), 0, stringify!($type))),*]);
+        }
         impl<'de, $($gen_args)*> serde::de::DeserializeSeed<'de> for $deserialize_seed_type
             where $($constraints)*
         {
@@ -70,13 +79,19 @@ macro_rules! impl_deserialize_seed_for_dependent_enum {
             {
                 use serde::de::*;
 
-                type Field = u32;
-
                 const fn get_const_len<const N: usize>(_: [&'static str; N]) -> usize {
                     N
                 }
                 const FIELDS: &[&'static str] = &[$(stringify!($variant)),*];
                 const FIELD_COUNT: usize = get_const_len([$(stringify!($variant)),*]);
+                static FIELD_TABLE: $crate::identifier::IdentifierTable<FIELD_COUNT, { $crate::identifier::bucket_count(FIELDS) }> = $crate::identifier::IdentifierTable::new(FIELDS);
+
+                #[allow(non_camel_case_types)]
+                #[derive(Clone, Copy)]
+                enum Field {
+                    $($variant),*
+                }
+                const FIELD_INDICES: [Field; FIELD_COUNT] = [$(Field::$variant),*];
 
                 struct FieldVisitor;
                 impl<'de> Visitor<'de> for FieldVisitor {
@@ -90,39 +105,29 @@ macro_rules! impl_deserialize_seed_for_dependent_enum {
                     fn visit_u64<E>(self, value: u64) -> Result<Self::Value, E>
                         where E: Error
                     {
-                        if value >= FIELD_COUNT as u64 {Err(serde::de::Error::invalid_value(serde::de::Unexpected::Unsigned(value), &format!("variant index should be < {}", FIELD_COUNT).as_str()))
+                        if value >= FIELD_COUNT as u64 {
+                            Err(Error::invalid_value(serde::de::Unexpected::Unsigned(value), &$crate::errors::ExpectedVariantIndex(FIELD_COUNT)))
                         } else {
-                            Ok(value as u32)
+                            Ok(FIELD_INDICES[value as usize])
                         }
                     }
 
-                    #[allow(unused_assignments)]
                     fn visit_str<E>(self, value: &str) -> Result<Self::Value, E>
                         where E: Error
                     {
-                        let mut current = 0;
-                        $(
-                            if value == stringify!($variant) {
-                                return Ok(current);
-                            }
-                            current += 1;
-                        )*
-                        return Err(serde::de::Error::unknown_variant(value, FIELDS));
+                        match FIELD_TABLE.find(value.as_bytes()) {
+                            Some(index) => Ok(FIELD_INDICES[index]),
+                            None => Err(::serde::de::Error::unknown_variant(value, FIELDS))
+                        }
                     }
 
-                    #[allow(unused_assignments)]
                     fn visit_bytes<E>(self, value: &[u8]) -> Result<Self::Value, E>
                         where E: Error
                     {
-                        let mut current = 0;
-                        $(
-                            if value == stringify!($variant).as_bytes() {
-                                return Ok(current);
-                            }
-                            current += 1;
-                        )*
-                        let value = &String::from_utf8_lossy(value);
-                        return Err(serde::de::Error::unknown_variant(value, FIELDS));
+                        match FIELD_TABLE.find(value) {
+                            Some(index) => Ok(FIELD_INDICES[index]),
+                            None => Err(::serde::de::Error::unknown_variant(&::std::string::String::from_utf8_lossy(value), FIELDS))
+                        }
                     }
                 }
 
@@ -153,22 +158,16 @@ macro_rules! impl_deserialize_seed_for_dependent_enum {
                         std::fmt::Formatter::write_str(f, concat!("enum ", stringify!($deserialize_result_enum_name)))
                     }
 
-                    #[allow(unused_assignments)]
                     fn visit_enum<A>(self, data: A) -> Result<Self::Value, A::Error>
                         where A: serde::de::EnumAccess<'de>
                     {
                         let variant = serde::de::EnumAccess::variant_seed(data, FieldDeserializer)?;
-                        let mut current = 0;
-                        $(
-                            if variant.0 == current {
-                                return Ok($deserialize_result_enum_name::$variant((
-                                    serde::de::VariantAccess::newtype_variant_seed(variant.1, ($local_deserialize_seed)(self.deserialize_seed_base))?,
-                                    std::marker::PhantomData
-                                )));
-                            }
-                            current += 1;
-                        )*
-                        unreachable!()
+                        match variant.0 {
+                            $(Field::$variant => Ok($deserialize_result_enum_name::$variant((
+                                serde::de::VariantAccess::newtype_variant_seed(variant.1, ($local_deserialize_seed)(self.deserialize_seed_base))?,
+                                std::marker::PhantomData
+                            )))),*
+                        }
                     }
                 }
 
@@ -242,4 +241,43 @@ fn test_serde_json() {
         Foo::B(m) => assert_eq!("the answer", m.0),
         _ => unreachable!()
     }
-}
\ No newline at end of file
+}
+
+#[test]
+fn test_serde_mock_identifiers() {
+    use crate::mock::*;
+
+    struct DeserializeSeedFoo;
+
+    impl_deserialize_seed_for_dependent_enum! {
+        pub enum Foo<'de> using DeserializeSeedFoo {
+            A(i64): |_| std::marker::PhantomData,
+            B(String): |_| std::marker::PhantomData
+        }
+    }
+
+    match DeserializeSeedFoo.deserialize(MockDeserializer::new(MockValue::newtype_variant(1u64, "the answer"))).unwrap() {
+        Foo::B(m) => assert_eq!("the answer", m.0),
+        _ => unreachable!()
+    }
+    match DeserializeSeedFoo.deserialize(MockDeserializer::new(MockValue::newtype_variant(MockValue::bytes(*b"A"), 42i64))).unwrap() {
+        Foo::A(m) => assert_eq!(42, m.0),
+        _ => unreachable!()
+    }
+    assert!(DeserializeSeedFoo.deserialize(MockDeserializer::new(MockValue::newtype_variant(2u64, 42i64))).is_err());
+    assert!(DeserializeSeedFoo.deserialize(MockDeserializer::new(MockValue::newtype_variant(MockValue::bytes(*b"C"), 42i64))).is_err());
+
+    let input = MockValue::newtype_variant("B", "the answer");
+    let mut successes = 0;
+    for mutated in input.mutations() {
+        if let Ok(result) = DeserializeSeedFoo.deserialize(MockDeserializer::new(mutated)) {
+            match result {
+                Foo::B(m) => assert_eq!("the answer", m.0),
+                _ => unreachable!()
+            }
+            successes += 1;
+        }
+    }
+    // only the bytes identifier
+    assert_eq!(1, successes);
+}
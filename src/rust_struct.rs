@@ -17,12 +17,12 @@
 /// ```rust
 /// # use feanor_serde::impl_deserialize_seed_for_dependent_struct;
 /// # use serde::*;
-/// # use std::marker::PhantomData;
+/// # use feanor_serde::default_seed::seed_of;
 /// struct FooDeserializeSeed;
 /// impl_deserialize_seed_for_dependent_struct!{
 ///     pub struct Foo<'de> using FooDeserializeSeed {
-///         a: i64: |_| PhantomData::<i64>,
-///         b: String: |_| PhantomData::<String>
+///         a: i64: |_| seed_of::<i64>(),
+///         b: String: |_| seed_of::<String>()
 ///     }
 /// }
 /// fn deserializer<'de>() -> impl serde::de::DeserializeSeed<'de, Value = Foo<'de>> {
@@ -32,14 +32,14 @@
 /// is roughly equivalent to `deserializer()` as in
 /// ```rust
 /// # use serde::*;
-/// # use std::marker::PhantomData;
+/// # use feanor_serde::default_seed::seed_of;
 /// #[derive(Deserialize)]
 /// struct Foo {
 ///     a: i64,
 ///     b: String
 /// }
 /// fn deserializer<'de>() -> impl serde::de::DeserializeSeed<'de, Value = Foo> {
-///     PhantomData::<Foo>
+///     seed_of::<Foo>()
 /// }
 /// ```
 /// 
@@ -110,6 +110,62 @@
 /// }
 /// ```
 /// 
+/// # Schema
+/// 
+/// The generated struct has an associated constant `SCHEMA`, which describes its name and
+/// the names and types of its fields, e.g. `"Foo { a: i64, b: Vec<String>, }"`. This can be
+/// used to detect incompatible data using [`crate::fingerprint`].
+/// 
+/// Furthermore, the associated constant `FIELDS` contains a [`crate::reflect::FieldInfo`] for each
+/// field, which can be used by tools that need to inspect the struct, e.g. for schema export.
+/// 
+/// # Patches
+/// 
+/// Optionally, the macro can additionally generate a patch struct, which has the same
+/// fields as the struct, but wrapped in [`Option`]. It is deserialized by a given seed, which
+/// must be a newtype around the seed of the struct, and applied to an instance of the struct
+/// using `apply()`.
+/// ```rust
+/// # use feanor_serde::impl_deserialize_seed_for_dependent_struct;
+/// # use serde::de::DeserializeSeed;
+/// # use feanor_serde::default_seed::seed_of;
+/// struct FooDeserializeSeed;
+/// struct FooPatchDeserializeSeed(FooDeserializeSeed);
+/// 
+/// impl_deserialize_seed_for_dependent_struct!{
+///     pub struct Foo<'de> using FooDeserializeSeed {
+///         a: i64: |_| seed_of::<i64>(),
+///         b: Option<String>: |_| seed_of::<Option<String>>()
+///     } with patch FooPatch using FooPatchDeserializeSeed
+/// }
+/// 
+/// let mut foo = FooDeserializeSeed.deserialize(&mut serde_json::Deserializer::from_str(r#"{ "a": 1, "b": "x" }"#)).unwrap();
+/// let patch = FooPatchDeserializeSeed(FooDeserializeSeed).deserialize(&mut serde_json::Deserializer::from_str(r#"{ "b": null }"#)).unwrap();
+/// patch.apply(&mut foo);
+/// assert_eq!((1, None), (foo.a, foo.b));
+/// ```
+/// Fields that are missing in a map are not changed by the patch, while fields that are
+/// present (even if `null`) are deserialized using the seed of the field. When deserializing from
+/// a sequence, e.g. in formats like postcard, each field is instead deserialized as an option, where
+/// none means that the field is not changed. This matches serializing a struct with the same
+/// fields wrapped in [`Option`], using `#[serde(skip_serializing_if = "Option::is_none")]` only
+/// for human-readable formats.
+/// 
+/// # Field identifiers
+/// 
+/// Fields can be identified both by their name and by their index in the declaration of the
+/// struct. Hence, structs serialized with [`crate::field_index::SerializableFieldIndices`],
+/// which uses indices instead of names in binary formats, can be deserialized without any
+/// further configuration.
+/// 
+/// # Optional fields
+/// 
+/// When deserializing from a map, a missing field is deserialized from a
+/// [`crate::rust_struct::MissingFieldDeserializer`]. Hence, fields of type [`Option`] may
+/// be missing, in which case they are `None`, and fields of type [`crate::double_option::DoubleOption`]
+/// can distinguish missing fields from explicitly `null` ones. All other missing fields
+/// cause an error.
+/// 
 /// # But the lifetimes aren't exactly what they should be!?
 /// 
 /// Well, it depends on what you are trying to express. I implemented what I consider
@@ -122,12 +178,12 @@
 /// # use feanor_serde::impl_deserialize_seed_for_dependent_struct;
 /// # use serde::*;
 /// # use serde::de::DeserializeSeed;
-/// # use std::marker::PhantomData;
+/// # use feanor_serde::default_seed::seed_of;
 /// struct FooDeserializeSeed;
 /// 
 /// impl_deserialize_seed_for_dependent_struct!{
 ///     pub struct Foo<'de> using FooDeserializeSeed {
-///         a: String: |_| PhantomData::<String>
+///         a: String: |_| seed_of::<String>()
 ///     }
 /// }
 /// 
@@ -143,11 +199,11 @@
 /// # use feanor_serde::impl_deserialize_seed_for_dependent_struct;
 /// # use serde::*;
 /// # use serde::de::DeserializeSeed;
-/// # use std::marker::PhantomData;
+/// # use feanor_serde::default_seed::seed_of;
 /// # struct FooDeserializeSeed;
 /// # impl_deserialize_seed_for_dependent_struct!{
 /// #     pub struct Foo<'de> using FooDeserializeSeed {
-/// #         a: String: |_| PhantomData::<String>
+/// #         a: String: |_| seed_of::<String>()
 /// #     }
 /// # }
 /// struct FooOwned {
@@ -171,17 +227,212 @@ macro_rules! impl_deserialize_seed_for_dependent_struct {
             $($field: $type: $local_deserialize_seed),*
         } where }
     };
+    (
+        pub struct $deserialize_result_struct_name:ident<'de> using $deserialize_seed_type:ty {
+            $($field:ident: $type:ty: $local_deserialize_seed:expr),*
+        } with patch $patch_struct_name:ident using $patch_seed_type:ty
+    ) => {
+        impl_deserialize_seed_for_dependent_struct!{ <{'de,}> pub struct $deserialize_result_struct_name<{'de,}> using $deserialize_seed_type {
+            $($field: $type: $local_deserialize_seed),*
+        } with patch $patch_struct_name using $patch_seed_type where }
+    };
+    (
+        <{'de, $($gen_args:tt)*}> pub struct $deserialize_result_struct_name:ident<{'de, $($deserialize_result_gen_args:tt)*}> using $deserialize_seed_type:ty {
+            $($field:ident: $type:ty: $local_deserialize_seed:expr),*
+        } with patch $patch_struct_name:ident using $patch_seed_type:ty where $($constraints:tt)*
+    ) => {
+        impl_deserialize_seed_for_dependent_struct!{ <{'de, $($gen_args)*}> pub struct $deserialize_result_struct_name<{'de, $($deserialize_result_gen_args)*}> using $deserialize_seed_type {
+            $($field: $type: $local_deserialize_seed),*
+        } where $($constraints)* }
+
+        #[allow(dead_code)]
+        pub struct $patch_struct_name<'de, $($deserialize_result_gen_args)*> 
+            where $($constraints)*
+        {
+            deserializer: std::marker::PhantomData<&'de ()>,
+            $(pub $field: Option<$type>),*
+        }
+        impl<'de, $($deserialize_result_gen_args)*> $patch_struct_name<'de, $($deserialize_result_gen_args)*>
+            where $($constraints)*
+        {
+            /// Overwrites all fields of `target` that are set in this patch.
+            #[allow(dead_code)]
+            pub fn apply(self, target: &mut $deserialize_result_struct_name<'de, $($deserialize_result_gen_args)*>) {
+                $(
+                    if let Some(value) = self.$field {
+                        target.$field = value;
+                    }
+                )*
+            }
+
+            /// Returns whether no field is set in this patch, i.e. applying it does not change anything.
+            #[allow(dead_code)]
+            pub fn is_empty(&self) -> bool {
+                true $(&& self.$field.is_none())*
+            }
+        }
+        impl<'de, $($gen_args)*> serde::de::DeserializeSeed<'de> for $patch_seed_type
+            where $($constraints)*
+        {
+            type Value = $patch_struct_name<'de, $($deserialize_result_gen_args)*>;
+
+            fn deserialize<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+                where D: serde::Deserializer<'de> 
+            {
+                use serde::de::*;
+
+                const fn get_const_len<const N: usize>(_: [&'static str; N]) -> usize {
+                    N
+                }
+                const FIELD_COUNT: usize = get_const_len([$(stringify!($field)),*]);
+                const FIELDS: &[&str] = &[$(stringify!($field)),*];
+                static FIELD_TABLE: $crate::identifier::IdentifierTable<FIELD_COUNT, { $crate::identifier::bucket_count(FIELDS) }> = $crate::identifier::IdentifierTable::new(FIELDS);
+
+                #[allow(non_camel_case_types)]
+                #[derive(Clone, Copy)]
+                enum Field {
+                    $($field),*
+                }
+                const FIELD_INDICES: [Field; FIELD_COUNT] = [$(Field::$field),*];
+
+                struct FieldVisitor;
+                impl<'de> Visitor<'de> for FieldVisitor {
+
+                    type Value = Field;
+
+                    fn expecting(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+                        std::fmt::Formatter::write_str(f, "field identifier")
+                    }
+
+                    fn visit_u64<E>(self, value: u64) -> Result<Self::Value, E>
+                        where E: Error
+                    {
+                        if value >= FIELD_COUNT as u64 {
+                            Err(E::invalid_value(Unexpected::Unsigned(value), &$crate::errors::ExpectedFieldIndex(FIELD_COUNT)))
+                        } else {
+                            Ok(FIELD_INDICES[value as usize])
+                        }
+                    }
+
+                    fn visit_str<E>(self, value: &str) -> Result<Self::Value, E>
+                        where E: Error
+                    {
+                        match FIELD_TABLE.find(value.as_bytes()) {
+                            Some(index) => Ok(FIELD_INDICES[index]),
+                            None => Err(E::unknown_field(value, FIELDS))
+                        }
+                    }
+
+                    fn visit_bytes<E>(self, value: &[u8]) -> Result<Self::Value, E>
+                        where E: Error
+                    {
+                        match FIELD_TABLE.find(value) {
+                            Some(index) => Ok(FIELD_INDICES[index]),
+                            None => Err(E::unknown_field(&::std::string::String::from_utf8_lossy(value), FIELDS))
+                        }
+                    }
+                }
+
+                struct FieldDeserializer;
+                impl<'de> DeserializeSeed<'de> for FieldDeserializer {
+                    type Value = Field;
+
+                    fn deserialize<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+                        where D: serde::Deserializer<'de> 
+                    {
+                        deserializer.deserialize_identifier(FieldVisitor)
+                    }
+                }
+
+                struct ResultVisitor<'de, $($gen_args)*>
+                    where $($constraints)*
+                {
+                    deserializer: std::marker::PhantomData<&'de ()>,
+                    deserialize_seed_base: $patch_seed_type
+                }
+
+                impl<'de, $($gen_args)*> Visitor<'de> for ResultVisitor<'de, $($gen_args)*>
+                    where $($constraints)*
+                {
+                    type Value = $patch_struct_name<'de, $($deserialize_result_gen_args)*>;
+
+                    fn expecting(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+                        std::fmt::Formatter::write_str(f, concat!("patch for struct ", stringify!($deserialize_result_struct_name)))
+                    }
+
+                    #[allow(unused_assignments)]
+                    fn visit_seq<A>(self, mut seq: A) -> Result<Self::Value, A::Error>
+                        where A: SeqAccess<'de>
+                    {
+                        let mut encountered_fields = 0;
+                        Ok($patch_struct_name {
+                            deserializer: std::marker::PhantomData,
+                            $($field: {
+                                let current_deserialize_seed = $crate::option::DeserializeSeedOption::new(($local_deserialize_seed)(&self.deserialize_seed_base.0));
+                                let field_value = match seq.next_element_seed(current_deserialize_seed)? {
+                                    Some(value) => value,
+                                    None => return Err($crate::errors::invalid_length(encountered_fields, concat!("patch for struct ", stringify!($deserialize_result_struct_name)), format_args!("patch for struct {} with {} elements", stringify!($deserialize_result_struct_name), FIELD_COUNT)))
+                                };
+                                encountered_fields += 1;
+                                field_value
+                            }),*
+                        })
+                    }
+
+                    fn visit_map<M>(self, mut map: M) -> Result<Self::Value, M::Error>
+                        where M: MapAccess<'de>
+                    {
+                        $(
+                            let mut $field: Option<$type> = None;
+                        )*
+                        while let Some(key) = map.next_key_seed(FieldDeserializer)? {
+                            match key {
+                                $(Field::$field => {
+                                    if $field.is_some() {
+                                        return Err(<M::Error as Error>::duplicate_field(stringify!($field)));
+                                    }
+                                    let current_deserialize_seed = ($local_deserialize_seed)(&self.deserialize_seed_base.0);
+                                    $field = Some(map.next_value_seed(current_deserialize_seed)?);
+                                }),*
+                            }
+                        }
+                        return Ok($patch_struct_name { 
+                            deserializer: std::marker::PhantomData,
+                            $($field),*
+                        });
+                    }
+                }
+
+                return deserializer.deserialize_struct(
+                    stringify!($patch_struct_name),
+                    &[$(stringify!($field)),*],
+                    ResultVisitor { deserialize_seed_base: self, deserializer: std::marker::PhantomData }
+                )
+            }
+        }
+    };
     (
         <{'de, $($gen_args:tt)*}> pub struct $deserialize_result_struct_name:ident<{'de, $($deserialize_result_gen_args:tt)*}> using $deserialize_seed_type:ty {
             $($field:ident: $type:ty: $local_deserialize_seed:expr),*
         } where $($constraints:tt)*
     ) => {
+        #[allow(dead_code)]
         pub struct $deserialize_result_struct_name<'de, $($deserialize_result_gen_args)*> 
             where $($constraints)*
         {
             deserializer: std::marker::PhantomData<&'de ()>,
             $(pub $field: $type),*
         }
+        impl<'de, $($deserialize_result_gen_args)*> $deserialize_result_struct_name<'de, $($deserialize_result_gen_args)*>
+            where $($constraints)*
+        {
+            /// A textual description of the fields of this struct, as used for `feanor_serde::fingerprint`.
+            #[allow(dead_code)]
+            pub const SCHEMA: &'static str = concat!(stringify!($deserialize_result_struct_name), " { ", $(stringify!($field), ": ", stringify!($type), ", ",)* "}");
+            /// The names, indices and types of the fields of this struct, see `feanor_serde::reflect`.
+            #[allow(dead_code)]
+            pub const FIELDS: &'static [$crate::reflect::FieldInfo] = &$crate::reflect::index_fields([$($crate::reflect::FieldInfo::new(stringify!($field), 0, stringify!($type))),*]);
+        }
         impl<'de, $($gen_args)*> serde::de::DeserializeSeed<'de> for $deserialize_seed_type
             where $($constraints)*
         {
@@ -192,12 +443,19 @@ macro_rules! impl_deserialize_seed_for_dependent_struct {
             {
                 use serde::de::*;
 
-                type Field = Result<u32, String>;
-
                 const fn get_const_len<const N: usize>(_: [&'static str; N]) -> usize {
                     N
                 }
                 const FIELD_COUNT: usize = get_const_len([$(stringify!($field)),*]);
+                const FIELDS: &[&str] = &[$(stringify!($field)),*];
+                static FIELD_TABLE: $crate::identifier::IdentifierTable<FIELD_COUNT, { $crate::identifier::bucket_count(FIELDS) }> = $crate::identifier::IdentifierTable::new(FIELDS);
+
+                #[allow(non_camel_case_types)]
+                #[derive(Clone, Copy)]
+                enum Field {
+                    $($field),*
+                }
+                const FIELD_INDICES: [Field; FIELD_COUNT] = [$(Field::$field),*];
 
                 struct FieldVisitor;
                 impl<'de> Visitor<'de> for FieldVisitor {
@@ -212,38 +470,28 @@ macro_rules! impl_deserialize_seed_for_dependent_struct {
                         where E: Error
                     {
                         if value >= FIELD_COUNT as u64 {
-                            Ok(Err(format!("field {}", value)))
+                            Err(E::invalid_value(Unexpected::Unsigned(value), &$crate::errors::ExpectedFieldIndex(FIELD_COUNT)))
                         } else {
-                            Ok(Ok(value as u32))
+                            Ok(FIELD_INDICES[value as usize])
                         }
                     }
 
-                    #[allow(unused_assignments)]
                     fn visit_str<E>(self, value: &str) -> Result<Self::Value, E>
                         where E: Error
                     {
-                        let mut current = 0;
-                        $(
-                            if value == stringify!($field) {
-                                return Ok(Ok(current));
-                            }
-                            current += 1;
-                        )*
-                        return Ok(Err(value.to_owned()));
+                        match FIELD_TABLE.find(value.as_bytes()) {
+                            Some(index) => Ok(FIELD_INDICES[index]),
+                            None => Err(E::unknown_field(value, FIELDS))
+                        }
                     }
 
-                    #[allow(unused_assignments)]
                     fn visit_bytes<E>(self, value: &[u8]) -> Result<Self::Value, E>
                         where E: Error
                     {
-                        let mut current = 0;
-                        $(
-                            if value == stringify!($field).as_bytes() {
-                                return Ok(Ok(current));
-                            }
-                            current += 1;
-                        )*
-                        return Ok(Err(str::from_utf8(value).unwrap_or("non-utf8-bytes").to_owned()));
+                        match FIELD_TABLE.find(value) {
+                            Some(index) => Ok(FIELD_INDICES[index]),
+                            None => Err(E::unknown_field(&::std::string::String::from_utf8_lossy(value), FIELDS))
+                        }
                     }
                 }
 
@@ -285,7 +533,7 @@ macro_rules! impl_deserialize_seed_for_dependent_struct {
                                 let current_deserialize_seed = ($local_deserialize_seed)(&self.deserialize_seed_base);
                                 let field_value = match seq.next_element_seed(current_deserialize_seed)? {
                                     Some(value) => value,
-                                    None => return Err(Error::invalid_length(encountered_fields, &format!("struct {} with {} elements", stringify!($deserialize_result_struct_name), FIELD_COUNT).as_str()))
+                                    None => return Err($crate::errors::invalid_length(encountered_fields, concat!("struct ", stringify!($deserialize_result_struct_name)), format_args!("struct {} with {} elements", stringify!($deserialize_result_struct_name), FIELD_COUNT)))
                                 };
                                 encountered_fields += 1;
                                 field_value
@@ -293,7 +541,6 @@ macro_rules! impl_deserialize_seed_for_dependent_struct {
                         })
                     }
 
-                    #[allow(unused_assignments)]
                     fn visit_map<M>(self, mut map: M) -> Result<Self::Value, M::Error>
                         where M: MapAccess<'de>
                     {
@@ -302,25 +549,21 @@ macro_rules! impl_deserialize_seed_for_dependent_struct {
                         )*
                         while let Some(key) = map.next_key_seed(FieldDeserializer)? {
                             match key {
-                                Ok(key) => {
-                                    let mut current = 0;
-                                    $(
-                                        if key == current {
-                                            if $field.is_some() {
-                                                return Err(<M::Error as Error>::duplicate_field(stringify!($field)));
-                                            }
-                                            let current_deserialize_seed = ($local_deserialize_seed)(&self.deserialize_seed_base);
-                                            $field = Some(map.next_value_seed(current_deserialize_seed)?);
-                                        }
-                                        current += 1;
-                                    )*
-                                },
-                                Err(field) => { return Err(<M::Error as Error>::unknown_field(field.as_str(), &[$(stringify!($field)),*])); }
+                                $(Field::$field => {
+                                    if $field.is_some() {
+                                        return Err(<M::Error as Error>::duplicate_field(stringify!($field)));
+                                    }
+                                    let current_deserialize_seed = ($local_deserialize_seed)(&self.deserialize_seed_base);
+                                    $field = Some(map.next_value_seed(current_deserialize_seed)?);
+                                }),*
                             }
                         }
                         $(
                             let $field: $type = match $field {
-                                None => return Err(<M::Error as Error>::missing_field(stringify!($field))),
+                                None => {
+                                    let current_deserialize_seed = ($local_deserialize_seed)(&self.deserialize_seed_base);
+                                    current_deserialize_seed.deserialize($crate::rust_struct::MissingFieldDeserializer::<M::Error>::new(stringify!($field)))?
+                                },
                                 Some(value) => value
                             };
                         )*
@@ -341,10 +584,81 @@ macro_rules! impl_deserialize_seed_for_dependent_struct {
     };
 }
 
+///
+/// Name of the newtype struct requested by [`crate::double_option::DeserializeSeedDoubleOption`],
+/// used to detect whether it is deserialized from a [`MissingFieldDeserializer`].
+///
+pub(crate) const MISSING_FIELD_MARKER: &str = "$feanor_serde::private::MissingField";
+
+///
+/// A [`serde::Deserializer`] representing a field that is missing in the input, used by
+/// [`crate::impl_deserialize_seed_for_dependent_struct!`] to support optional fields.
+///
+/// It fails with [`serde::de::Error::missing_field()`] for all requests, except for
+/// [`serde::Deserializer::deserialize_option()`], for which it calls
+/// [`serde::de::Visitor::visit_none()`], so fields of type [`Option`] may be missing.
+/// Furthermore, [`crate::double_option::DeserializeSeedDoubleOption`] requests a newtype
+/// struct with a private marker name, which this deserializer answers by calling
+/// [`serde::de::Visitor::visit_unit()`]. This is mapped to
+/// [`crate::double_option::DoubleOption::Missing`], which allows distinguishing missing
+/// fields from fields that are explicitly `null`, independent of how the format represents
+/// `null`.
+///
+pub struct MissingFieldDeserializer<E>
+    where E: serde::de::Error
+{
+    field: &'static str,
+    error: std::marker::PhantomData<E>
+}
+
+impl<E> MissingFieldDeserializer<E>
+    where E: serde::de::Error
+{
+    pub fn new(field: &'static str) -> Self {
+        Self { field: field, error: std::marker::PhantomData }
+    }
+}
+
+impl<'de, E> serde::Deserializer<'de> for MissingFieldDeserializer<E>
+    where E: serde::de::Error
+{
+    type Error = E;
+
+    fn deserialize_any<V>(self, _visitor: V) -> Result<V::Value, Self::Error>
+        where V: serde::de::Visitor<'de>
+    {
+        Err(E::missing_field(self.field))
+    }
+
+    fn deserialize_option<V>(self, visitor: V) -> Result<V::Value, Self::Error>
+        where V: serde::de::Visitor<'de>
+    {
+        visitor.visit_none()
+    }
+
+    fn deserialize_newtype_struct<V>(self, name: &'static str, visitor: V) -> Result<V::Value, Self::Error>
+        where V: serde::de::Visitor<'de>
+    {
+        if name == MISSING_FIELD_MARKER {
+            visitor.visit_unit()
+        } else {
+            Err(E::missing_field(self.field))
+        }
+    }
+
+    serde::forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string
+        bytes byte_buf unit unit_struct seq tuple
+        tuple_struct map struct enum identifier ignored_any
+    }
+}
+
 #[cfg(test)]
 use serde::Serialize;
 #[cfg(test)]
 use serde::de::DeserializeSeed;
+#[cfg(test)]
+use crate::test::token::{TokenDeserializer, TokenSerializer};
 
 #[test]
 fn test_serde_postcard() {
@@ -400,7 +714,7 @@ fn test_serde_json() {
 }
 
 #[test]
-fn test_serde_serdeassert() {
+fn test_serde_tokens() {
     #[derive(Serialize)]
     #[serde(rename = "Foo")]
     struct SerializableFoo {
@@ -417,16 +731,16 @@ fn test_serde_serdeassert() {
         }
     }
     
-    let serializer = serde_assert::Serializer::builder().is_human_readable(true).build();
-    let tokens = SerializableFoo { a: 42, b: "the answer".to_owned() }.serialize(&serializer).unwrap();
-    let mut deserializer = serde_assert::Deserializer::builder(tokens).is_human_readable(true).build();
+    let serializer = TokenSerializer::new().with_human_readable(true);
+    let tokens = SerializableFoo { a: 42, b: "the answer".to_owned() }.serialize(serializer).unwrap();
+    let mut deserializer = TokenDeserializer::new(tokens).with_human_readable(true);
     let result = DeserializeSeedFoo.deserialize(&mut deserializer).unwrap();
     assert_eq!(42, result.a);
     assert_eq!("the answer", result.b);
 
-    let serializer = serde_assert::Serializer::builder().is_human_readable(false).build();
-    let tokens = SerializableFoo { a: 42, b: "the answer".to_owned() }.serialize(&serializer).unwrap();
-    let mut deserializer = serde_assert::Deserializer::builder(tokens).is_human_readable(false).build();
+    let serializer = TokenSerializer::new().with_human_readable(false);
+    let tokens = SerializableFoo { a: 42, b: "the answer".to_owned() }.serialize(serializer).unwrap();
+    let mut deserializer = TokenDeserializer::new(tokens).with_human_readable(false);
     let result = DeserializeSeedFoo.deserialize(&mut deserializer).unwrap();
     assert_eq!(42, result.a);
     assert_eq!("the answer", result.b);
@@ -451,17 +765,159 @@ fn test_serde_additional_field() {
         }
     }
     
-    let serializer = serde_assert::Serializer::builder().is_human_readable(true).build();
-    let tokens = SerializableFoo { a: 42, c: 63, b: "the answer".to_owned() }.serialize(&serializer).unwrap();
-    let mut deserializer = serde_assert::Deserializer::builder(tokens).is_human_readable(true).build();
+    let serializer = TokenSerializer::new().with_human_readable(true);
+    let tokens = SerializableFoo { a: 42, c: 63, b: "the answer".to_owned() }.serialize(serializer).unwrap();
+    let mut deserializer = TokenDeserializer::new(tokens).with_human_readable(true);
     let result = DeserializeSeedFoo.deserialize(&mut deserializer);
     assert!(result.is_err());
-    assert!(result.err().unwrap().to_string().contains("unknown field"));
+    assert!(result.err().unwrap().to_string().contains("unknown field `c`"));
 
-    let serializer = serde_assert::Serializer::builder().is_human_readable(false).build();
-    let tokens = SerializableFoo { a: 42, c: 63, b: "the answer".to_owned() }.serialize(&serializer).unwrap();
-    let mut deserializer = serde_assert::Deserializer::builder(tokens).is_human_readable(false).build();
+    let serializer = TokenSerializer::new().with_human_readable(false);
+    let tokens = SerializableFoo { a: 42, c: 63, b: "the answer".to_owned() }.serialize(serializer).unwrap();
+    let mut deserializer = TokenDeserializer::new(tokens).with_human_readable(false);
     let result = DeserializeSeedFoo.deserialize(&mut deserializer);
     assert!(result.is_err());
-    assert!(result.err().unwrap().to_string().contains("unknown field"));
-}
\ No newline at end of file
+    assert!(result.err().unwrap().to_string().contains("unknown field `c`"));
+}
+
+#[test]
+fn test_serde_missing_field() {
+    struct DeserializeSeedFoo;
+
+    impl_deserialize_seed_for_dependent_struct! {
+        pub struct Foo<'de> using DeserializeSeedFoo {
+            a: i64: |_| std::marker::PhantomData,
+            b: Option<String>: |_| std::marker::PhantomData
+        }
+    }
+
+    let result = DeserializeSeedFoo.deserialize(&mut serde_json::Deserializer::from_str(r#"{"a":1}"#)).unwrap();
+    assert_eq!(1, result.a);
+    assert_eq!(None, result.b);
+
+    let result = DeserializeSeedFoo.deserialize(&mut serde_json::Deserializer::from_str(r#"{"a":1,"b":"x"}"#)).unwrap();
+    assert_eq!(Some("x".to_owned()), result.b);
+
+    let result = DeserializeSeedFoo.deserialize(&mut serde_json::Deserializer::from_str(r#"{"b":"x"}"#));
+    assert_eq!("missing field `a` at line 1 column 9", result.err().unwrap().to_string());
+}
+
+#[test]
+fn test_serde_mock_identifiers() {
+    use crate::mock::*;
+
+    struct DeserializeSeedFoo;
+
+    impl_deserialize_seed_for_dependent_struct! {
+        pub struct Foo<'de> using DeserializeSeedFoo {
+            a: i64: |_| std::marker::PhantomData,
+            b: String: |_| std::marker::PhantomData
+        }
+    }
+
+    let result = DeserializeSeedFoo.deserialize(MockDeserializer::new(MockValue::map([(MockValue::from(1u64), MockValue::from("the answer")), (MockValue::from(0u64), MockValue::from(42i64))]))).unwrap();
+    assert_eq!(42, result.a);
+    assert_eq!("the answer", result.b);
+
+    let result = DeserializeSeedFoo.deserialize(MockDeserializer::new(MockValue::map([(MockValue::bytes(*b"a"), MockValue::from(42i64)), (MockValue::bytes(*b"b"), MockValue::from("the answer"))]))).unwrap();
+    assert_eq!(42, result.a);
+    assert_eq!("the answer", result.b);
+
+    assert!(DeserializeSeedFoo.deserialize(MockDeserializer::new(MockValue::map([(MockValue::from(0u64), MockValue::from(42i64)), (MockValue::from(2u64), MockValue::from("the answer"))]))).is_err());
+    assert!(DeserializeSeedFoo.deserialize(MockDeserializer::new(MockValue::map([(MockValue::from(0u64), MockValue::from(42i64)), (MockValue::bytes([0xFF]), MockValue::from("the answer"))]))).is_err());
+
+    let input = MockValue::map([(MockValue::from("a"), MockValue::from(42i64)), (MockValue::from("b"), MockValue::from("the answer"))]);
+    let mut successes = 0;
+    for mutated in input.mutations() {
+        if let Ok(result) = DeserializeSeedFoo.deserialize(MockDeserializer::new(mutated)) {
+            assert_eq!(42, result.a);
+            assert_eq!("the answer", result.b);
+            successes += 1;
+        }
+    }
+    // swapped entries, two numeric and two bytes identifiers
+    assert_eq!(5, successes);
+}
+
+#[test]
+fn test_identifiers_of_same_length() {
+    use crate::mock::*;
+
+    struct DeserializeSeedFoo;
+
+    impl_deserialize_seed_for_dependent_struct! {
+        pub struct Foo<'de> using DeserializeSeedFoo {
+            ab: i64: |_| std::marker::PhantomData,
+            b: i64: |_| std::marker::PhantomData,
+            ba: i64: |_| std::marker::PhantomData,
+            abc: i64: |_| std::marker::PhantomData
+        }
+    }
+
+    let result = DeserializeSeedFoo.deserialize(&mut serde_json::Deserializer::from_str(r#"{"abc":4,"ba":3,"b":2,"ab":1}"#)).unwrap();
+    assert_eq!((1, 2, 3, 4), (result.ab, result.b, result.ba, result.abc));
+
+    let result = DeserializeSeedFoo.deserialize(MockDeserializer::new(MockValue::map([(MockValue::bytes(*b"ba"), 3i64), (MockValue::bytes(*b"abc"), 4i64), (MockValue::bytes(*b"ab"), 1i64), (MockValue::bytes(*b"b"), 2i64)]))).unwrap();
+    assert_eq!((1, 2, 3, 4), (result.ab, result.b, result.ba, result.abc));
+
+    let result = DeserializeSeedFoo.deserialize(&mut serde_json::Deserializer::from_str(r#"{"ab":1,"bb":2}"#));
+    assert!(result.err().unwrap().to_string().contains("unknown field `bb`, expected one of `ab`, `b`, `ba`, `abc`"));
+    let result = DeserializeSeedFoo.deserialize(MockDeserializer::new(MockValue::map([(MockValue::bytes([b'a', 0xFF]), 1i64)])));
+    assert!(result.err().unwrap().to_string().contains("unknown field `a\u{FFFD}`"));
+}
+
+#[test]
+fn test_patch() {
+    #[derive(Serialize)]
+    #[serde(rename = "Foo")]
+    struct SerializableFooPatch {
+        a: Option<i64>,
+        b: Option<Option<String>>
+    }
+
+    struct DeserializeSeedFoo;
+    struct DeserializeSeedFooPatch(DeserializeSeedFoo);
+
+    impl_deserialize_seed_for_dependent_struct! {
+        pub struct Foo<'de> using DeserializeSeedFoo {
+            a: i64: |_| std::marker::PhantomData,
+            b: Option<String>: |_| std::marker::PhantomData
+        } with patch FooPatch using DeserializeSeedFooPatch
+    }
+
+    let mut foo = DeserializeSeedFoo.deserialize(&mut serde_json::Deserializer::from_str(r#"{"a": 1, "b": "x"}"#)).unwrap();
+    let patch = DeserializeSeedFooPatch(DeserializeSeedFoo).deserialize(&mut serde_json::Deserializer::from_str(r#"{"a": 2}"#)).unwrap();
+    assert!(!patch.is_empty());
+    patch.apply(&mut foo);
+    assert_eq!((2, Some("x")), (foo.a, foo.b.as_deref()));
+
+    let patch = DeserializeSeedFooPatch(DeserializeSeedFoo).deserialize(&mut serde_json::Deserializer::from_str(r#"{}"#)).unwrap();
+    assert!(patch.is_empty());
+    patch.apply(&mut foo);
+    assert_eq!((2, Some("x")), (foo.a, foo.b.as_deref()));
+
+    assert!(DeserializeSeedFooPatch(DeserializeSeedFoo).deserialize(&mut serde_json::Deserializer::from_str(r#"{"a": null}"#)).is_err());
+    assert!(DeserializeSeedFooPatch(DeserializeSeedFoo).deserialize(&mut serde_json::Deserializer::from_str(r#"{"c": 1}"#)).is_err());
+
+    let serialized = postcard::to_allocvec(&SerializableFooPatch { a: None, b: Some(None) }).unwrap();
+    let patch = DeserializeSeedFooPatch(DeserializeSeedFoo).deserialize(&mut postcard::Deserializer::from_bytes(&serialized)).unwrap();
+    patch.apply(&mut foo);
+    assert_eq!((2, None), (foo.a, foo.b.as_deref()));
+}
+
+#[test]
+fn test_patch_generic() {
+    struct DeserializeSeedBar<S>(S);
+    struct DeserializeSeedBarPatch<S>(DeserializeSeedBar<S>);
+
+    impl_deserialize_seed_for_dependent_struct! {
+        <{'de, S}> pub struct Bar<{'de, S}> using DeserializeSeedBar<S> {
+            a: S::Value: |seed: &DeserializeSeedBar<S>| seed.0.clone()
+        } with patch BarPatch using DeserializeSeedBarPatch<S> where S: DeserializeSeed<'de> + Clone
+    }
+
+    let seed = crate::default_seed::seed_of::<i64>();
+    let mut bar = DeserializeSeedBar(seed).deserialize(&mut serde_json::Deserializer::from_str(r#"{"a": 1}"#)).unwrap();
+    DeserializeSeedBarPatch(DeserializeSeedBar(seed)).deserialize(&mut serde_json::Deserializer::from_str(r#"{"a": 3}"#)).unwrap().apply(&mut bar);
+    assert_eq!(3, bar.a);
+}
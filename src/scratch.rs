@@ -0,0 +1,299 @@
+use std::cell::RefCell;
+use std::ops::{Deref, DerefMut};
+
+use serde::de::{DeserializeSeed, Error, Unexpected, Visitor};
+use serde::Deserializer;
+
+use crate::seq::{SeqSeedBuilder, DeserializeSeedSeq};
+
+///
+/// A buffer that can be stored in a [`ScratchPool`], i.e. that can be cleared without
+/// freeing its allocation.
+///
+pub trait Scratch: Default {
+
+    fn clear(&mut self);
+}
+
+impl Scratch for String {
+
+    fn clear(&mut self) {
+        String::clear(self)
+    }
+}
+
+impl<T> Scratch for Vec<T> {
+
+    fn clear(&mut self) {
+        Vec::clear(self)
+    }
+}
+
+///
+/// A caller-owned pool of buffers, which seeds can borrow instead of allocating fresh
+/// buffers during every call to [`DeserializeSeed::deserialize()`].
+///
+/// Buffers are taken from the pool as [`Pooled`] values, which are returned to the pool
+/// (after clearing them) once they are dropped. Hence, when deserializing many small records
+/// one after the other, each record can reuse the allocations of the previous ones, as long
+/// as the previous records are dropped before the next ones are deserialized.
+///
+/// # Example
+/// ```
+/// # use feanor_serde::scratch::*;
+/// # use serde::de::DeserializeSeed;
+/// let pool = ScratchPool::new();
+/// for json in ["\"a\"", "\"bc\"", "\"def\""] {
+///     let value = DeserializeSeedPooledString::new(&pool).deserialize(&mut serde_json::Deserializer::from_str(json)).unwrap();
+///     assert_eq!(json.trim_matches('"'), &*value);
+/// }
+/// // all values used the same buffer
+/// assert_eq!(1, pool.free_count());
+/// ```
+///
+pub struct ScratchPool<T> {
+    free: RefCell<Vec<T>>
+}
+
+impl<T> ScratchPool<T>
+    where T: Scratch
+{
+    pub const fn new() -> Self {
+        Self { free: RefCell::new(Vec::new()) }
+    }
+
+    ///
+    /// Takes a cleared buffer from the pool, or creates a new one if the pool is empty.
+    ///
+    pub fn take(&self) -> Pooled<'_, T> {
+        let value = self.free.borrow_mut().pop().unwrap_or_default();
+        return Pooled { pool: self, value: value };
+    }
+
+    ///
+    /// Returns the number of buffers that are currently stored in the pool, i.e. that
+    /// are not in use.
+    ///
+    pub fn free_count(&self) -> usize {
+        self.free.borrow().len()
+    }
+
+    fn give_back(&self, mut value: T) {
+        value.clear();
+        self.free.borrow_mut().push(value);
+    }
+}
+
+impl<T> Default for ScratchPool<T>
+    where T: Scratch
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> std::fmt::Debug for ScratchPool<T> {
+
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ScratchPool").field("free_count", &self.free.borrow().len()).finish()
+    }
+}
+
+///
+/// A buffer borrowed from a [`ScratchPool`], which is returned to the pool when dropped.
+///
+pub struct Pooled<'a, T>
+    where T: Scratch
+{
+    pool: &'a ScratchPool<T>,
+    value: T
+}
+
+impl<'a, T> Pooled<'a, T>
+    where T: Scratch
+{
+    ///
+    /// Returns the buffer, which is then not returned to the pool anymore.
+    ///
+    pub fn into_inner(self) -> T {
+        let mut this = std::mem::ManuallyDrop::new(self);
+        return std::mem::take(&mut this.value);
+    }
+}
+
+impl<'a, T> Deref for Pooled<'a, T>
+    where T: Scratch
+{
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        &self.value
+    }
+}
+
+impl<'a, T> DerefMut for Pooled<'a, T>
+    where T: Scratch
+{
+    fn deref_mut(&mut self) -> &mut T {
+        &mut self.value
+    }
+}
+
+impl<'a, T> Drop for Pooled<'a, T>
+    where T: Scratch
+{
+    fn drop(&mut self) {
+        self.pool.give_back(std::mem::take(&mut self.value));
+    }
+}
+
+impl<'a, T> std::fmt::Debug for Pooled<'a, T>
+    where T: Scratch + std::fmt::Debug
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.value.fmt(f)
+    }
+}
+
+///
+/// A [`DeserializeSeed`] that deserializes a string into a buffer taken from the given
+/// [`ScratchPool`].
+///
+#[derive(Clone, Copy, Debug)]
+pub struct DeserializeSeedPooledString<'a> {
+    pool: &'a ScratchPool<String>
+}
+
+impl<'a> DeserializeSeedPooledString<'a> {
+
+    pub const fn new(pool: &'a ScratchPool<String>) -> Self {
+        Self { pool }
+    }
+}
+
+impl<'a, 'de> DeserializeSeed<'de> for DeserializeSeedPooledString<'a> {
+
+    type Value = Pooled<'a, String>;
+
+    fn deserialize<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+        where D: Deserializer<'de>
+    {
+        struct ResultVisitor<'a> {
+            pool: &'a ScratchPool<String>
+        }
+
+        impl<'a, 'de> Visitor<'de> for ResultVisitor<'a> {
+            type Value = Pooled<'a, String>;
+
+            fn expecting(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+                write!(f, "a string")
+            }
+
+            fn visit_str<E>(self, value: &str) -> Result<Self::Value, E>
+                where E: Error
+            {
+                let mut result = self.pool.take();
+                result.push_str(value);
+                return Ok(result);
+            }
+
+            fn visit_bytes<E>(self, value: &[u8]) -> Result<Self::Value, E>
+                where E: Error
+            {
+                let value = std::str::from_utf8(value).map_err(|_| E::invalid_value(Unexpected::Bytes(value), &self))?;
+                return self.visit_str(value);
+            }
+        }
+
+        return deserializer.deserialize_str(ResultVisitor { pool: self.pool });
+    }
+}
+
+///
+/// A [`Vec`] borrowed from a [`ScratchPool`], as returned by [`SeqSeedBuilder::collect_pooled_vec()`].
+///
+pub type PooledVec<'a, T> = Pooled<'a, Vec<T>>;
+
+///
+/// The collector used by [`SeqSeedBuilder::collect_pooled_vec()`].
+///
+pub type PushToPooledVec<'a, T> = fn(PooledVec<'a, T>, T) -> PooledVec<'a, T>;
+
+impl<'de, V, S> SeqSeedBuilder<V>
+    where V: Iterator<Item = S>,
+        S: DeserializeSeed<'de>
+{
+    ///
+    /// Builds a [`DeserializeSeedSeq`] that collects the elements into a [`Vec`] taken
+    /// from the given [`ScratchPool`]. The buffer is taken when calling this function,
+    /// and reserved as in [`SeqSeedBuilder::collect_vec()`].
+    ///
+    /// # Example
+    /// ```
+    /// # use feanor_serde::scratch::*;
+    /// # use feanor_serde::seq::*;
+    /// # use feanor_serde::default_seed::seed_of;
+    /// # use serde::de::DeserializeSeed;
+    /// let pool = ScratchPool::new();
+    /// let mut sum = 0;
+    /// for json in ["[1, 2]", "[3, 4, 5]"] {
+    ///     let seed = SeqSeedBuilder::new(seed_of::<i64>).collect_pooled_vec(&pool);
+    ///     let values = seed.deserialize(&mut serde_json::Deserializer::from_str(json)).unwrap();
+    ///     sum += values.iter().sum::<i64>();
+    /// }
+    /// assert_eq!(15, sum);
+    /// assert_eq!(1, pool.free_count());
+    /// ```
+    ///
+    pub fn collect_pooled_vec<'a>(self, pool: &'a ScratchPool<Vec<S::Value>>) -> DeserializeSeedSeq<V, S, PooledVec<'a, S::Value>, PushToPooledVec<'a, S::Value>> {
+        let mut initial = pool.take();
+        initial.reserve(self.initial_capacity());
+        return self.fold::<_, PushToPooledVec<'a, _>>(initial, |mut current, next| { current.push(next); current }).with_reserve(|current, len| current.reserve(len));
+    }
+}
+
+#[cfg(test)]
+use crate::default_seed::seed_of;
+#[cfg(test)]
+use crate::impl_deserialize_seed_for_dependent_struct;
+
+#[cfg(test)]
+struct DeserializeSeedRecord<'a> {
+    strings: &'a ScratchPool<String>,
+    values: &'a ScratchPool<Vec<i64>>
+}
+
+#[cfg(test)]
+impl_deserialize_seed_for_dependent_struct!{
+    <{'de, 'a}> pub struct Record<{'de, 'a}> using DeserializeSeedRecord<'a> {
+        name: Pooled<'a, String>: |seed: &DeserializeSeedRecord<'a>| DeserializeSeedPooledString::new(seed.strings),
+        values: PooledVec<'a, i64>: |seed: &DeserializeSeedRecord<'a>| SeqSeedBuilder::new(seed_of::<i64>).collect_pooled_vec(seed.values)
+    } where
+}
+
+#[test]
+fn test_pooled_records() {
+    let strings = ScratchPool::new();
+    let values = ScratchPool::new();
+    let data = [("a", vec![1, 2]), ("bcd", vec![]), ("ef", vec![3, 4, 5])];
+    for (name, expected) in &data {
+        let json = format!("{{\"name\": \"{}\", \"values\": {:?}}}", name, expected);
+        let record = DeserializeSeedRecord { strings: &strings, values: &values }.deserialize(&mut serde_json::Deserializer::from_str(&json)).unwrap();
+        assert_eq!(*name, &*record.name);
+        assert_eq!(expected, &*record.values);
+        assert_eq!(0, strings.free_count());
+    }
+    assert_eq!(1, strings.free_count());
+    assert_eq!(1, values.free_count());
+
+    let value = DeserializeSeedPooledString::new(&strings).deserialize(&mut serde_json::Deserializer::from_str("\"xyz\"")).unwrap();
+    assert!(value.capacity() >= 3);
+    assert_eq!("xyz", value.into_inner());
+    assert_eq!(0, strings.free_count());
+    assert!(DeserializeSeedPooledString::new(&strings).deserialize(&mut serde_json::Deserializer::from_str("1")).is_err());
+    assert_eq!(0, strings.free_count());
+
+    let serialized = postcard::to_allocvec(&[1i64, 2, 3][..]).unwrap();
+    let result = SeqSeedBuilder::new(seed_of::<i64>).collect_pooled_vec(&values).deserialize(&mut postcard::Deserializer::from_bytes(&serialized)).unwrap();
+    assert_eq!(vec![1, 2, 3], *result);
+}
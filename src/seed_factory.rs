@@ -0,0 +1,163 @@
+use serde::de::DeserializeSeed;
+use serde::Deserializer;
+
+///
+/// Wraps a function producing [`DeserializeSeed`]s, and can be used both as an (infinite)
+/// [`Iterator`] over seeds, e.g. for [`crate::seq::DeserializeSeedSeq`], and as a seed
+/// itself, which is [`Clone`] whenever the function is.
+///
+/// This makes it possible to reuse seeds that do not implement [`Clone`], without building
+/// an iterator via [`std::iter::repeat_with()`] at every call site. If the seeds are derived
+/// from some shared context, use [`from_context()`] instead.
+///
+/// # Example
+/// ```
+/// # use feanor_serde::seed_factory::*;
+/// # use feanor_serde::seq::*;
+/// # use feanor_serde::default_seed::seed_of;
+/// # use serde::de::DeserializeSeed;
+/// let seed_of_sums = SeedFactory::new(|| DeserializeSeedSeq::new(
+///     std::iter::repeat(seed_of::<i64>()),
+///     0,
+///     |current, next| current + next
+/// ));
+/// let mut deserializer = serde_json::Deserializer::from_str("[[1, 2], [3], []]");
+/// let deserialize_seed = DeserializeSeedSeq::new(
+///     seed_of_sums,
+///     Vec::new(),
+///     |mut current, next| { current.push(next); current }
+/// );
+/// assert_eq!(vec![3, 3, 0], deserialize_seed.deserialize(&mut deserializer).unwrap());
+/// ```
+///
+#[derive(Clone, Copy)]
+pub struct SeedFactory<F> {
+    factory: F
+}
+
+impl<F, S> SeedFactory<F>
+    where F: FnMut() -> S
+{
+    pub const fn new(factory: F) -> Self {
+        Self { factory }
+    }
+}
+
+///
+/// Creates a [`SeedFactory`] producing seeds by calling the given function on the given context.
+///
+/// # Example
+/// ```
+/// # use feanor_serde::seed_factory::*;
+/// # use feanor_serde::seq::*;
+/// # use feanor_serde::limits::*;
+/// # use feanor_serde::default_seed::seed_of;
+/// # use serde::de::DeserializeSeed;
+/// let limits = Limits::UNLIMITED.with_max_seq_len(2);
+/// let seeds = from_context(&limits, |limits| DeserializeSeedSeq::new(
+///     std::iter::repeat(seed_of::<i64>()),
+///     0,
+///     |current, next| current + next
+/// ).with_limits(*limits));
+/// let mut deserializer = serde_json::Deserializer::from_str("[[1, 2], [3]]");
+/// let deserialize_seed = DeserializeSeedSeq::new(seeds.clone(), Vec::new(), |mut current, next| { current.push(next); current });
+/// assert_eq!(vec![3, 3], deserialize_seed.deserialize(&mut deserializer).unwrap());
+///
+/// let mut deserializer = serde_json::Deserializer::from_str("[[1, 2], [3, 4, 5]]");
+/// let deserialize_seed = DeserializeSeedSeq::new(seeds, Vec::new(), |mut current, next| { current.push(next); current });
+/// assert!(deserialize_seed.deserialize(&mut deserializer).is_err());
+/// ```
+///
+pub fn from_context<'a, C, G, S>(context: &'a C, factory: G) -> SeedFactory<impl Clone + Fn() -> S + 'a>
+    where C: ?Sized,
+        G: Clone + Fn(&C) -> S + 'a
+{
+    let factory = move || factory(context);
+    return SeedFactory::new(factory);
+}
+
+impl<F, S> Iterator for SeedFactory<F>
+    where F: FnMut() -> S
+{
+    type Item = S;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        Some((self.factory)())
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (usize::MAX, None)
+    }
+}
+
+impl<'de, F, S> DeserializeSeed<'de> for SeedFactory<F>
+    where F: FnOnce() -> S,
+        S: DeserializeSeed<'de>
+{
+    type Value = S::Value;
+
+    fn deserialize<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+        where D: Deserializer<'de>
+    {
+        (self.factory)().deserialize(deserializer)
+    }
+}
+
+///
+/// Deserializes using a new seed produced by the referenced factory.
+///
+impl<'de, F, S> DeserializeSeed<'de> for &SeedFactory<F>
+    where F: Fn() -> S,
+        S: DeserializeSeed<'de>
+{
+    type Value = S::Value;
+
+    fn deserialize<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+        where D: Deserializer<'de>
+    {
+        (self.factory)().deserialize(deserializer)
+    }
+}
+
+#[cfg(test)]
+use crate::seq::DeserializeSeedSeq;
+
+///
+/// A seed that does not implement [`Clone`].
+///
+#[cfg(test)]
+struct DeserializeSeedOffset {
+    offset: Box<i64>
+}
+
+#[cfg(test)]
+impl<'de> DeserializeSeed<'de> for DeserializeSeedOffset {
+
+    type Value = i64;
+
+    fn deserialize<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+        where D: Deserializer<'de>
+    {
+        <i64 as serde::Deserialize>::deserialize(deserializer).map(|x| x + *self.offset)
+    }
+}
+
+#[test]
+fn test_seed_factory() {
+    let seeds = SeedFactory::new(|| DeserializeSeedOffset { offset: Box::new(10) });
+    let seed = DeserializeSeedSeq::new(seeds, Vec::new(), |mut current, next| { current.push(next); current });
+    assert_eq!(vec![11, 12], seed.deserialize(&mut serde_json::Deserializer::from_str("[1, 2]")).unwrap());
+
+    let element_seed = SeedFactory::new(|| DeserializeSeedOffset { offset: Box::new(-1) });
+    let seed = crate::map::DeserializeSeedMap::new(std::iter::repeat((crate::default_seed::seed_of::<String>(), element_seed)), Vec::new(), |mut current, k, v| { current.push((k, v)); current });
+    assert_eq!(vec![("a".to_owned(), 0)], seed.deserialize(&mut serde_json::Deserializer::from_str("{\"a\": 1}")).unwrap());
+}
+
+#[test]
+fn test_from_context() {
+    let offset = 5;
+    let seeds = from_context(&offset, |offset| DeserializeSeedOffset { offset: Box::new(*offset) });
+    let seed = DeserializeSeedSeq::new(seeds.clone(), Vec::new(), |mut current, next| { current.push(next); current });
+    assert_eq!(vec![6, 7], seed.deserialize(&mut serde_json::Deserializer::from_str("[1, 2]")).unwrap());
+    assert_eq!(8, seeds.deserialize(&mut serde_json::Deserializer::from_str("3")).unwrap());
+}
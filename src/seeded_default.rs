@@ -0,0 +1,127 @@
+use std::marker::PhantomData;
+use std::ops::{Deref, DerefMut};
+
+use serde::de::DeserializeSeed;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+///
+/// Wraps a value of type `T`, and implements [`Deserialize`] by deserializing it with
+/// `S::default()`, where `S` is a [`DeserializeSeed`].
+///
+/// This allows using seeds that do not require any runtime data with APIs that only accept
+/// types implementing [`Deserialize`], like `#[derive(Deserialize)]`, web framework extractors
+/// or configuration crates. [`Serialize`] is implemented transparently, by serializing the
+/// wrapped value.
+///
+/// # Example
+/// ```
+/// # use feanor_serde::seeded_default::*;
+/// # use feanor_serde::lenient::*;
+/// # use serde::Deserialize;
+/// #[derive(Deserialize)]
+/// struct Config {
+///     threads: SeededDefault<i64, DeserializeSeedLenient<i64>>,
+///     verbose: SeededDefault<bool, DeserializeSeedLenient<bool>>
+/// }
+/// let config: Config = serde_json::from_str("{\"threads\": \"4\", \"verbose\": 1}").unwrap();
+/// assert_eq!(4, *config.threads);
+/// assert!(config.verbose.into_inner());
+/// ```
+///
+pub struct SeededDefault<T, S> {
+    value: T,
+    seed: PhantomData<fn() -> S>
+}
+
+impl<T, S> SeededDefault<T, S> {
+
+    pub const fn new(value: T) -> Self {
+        Self { value, seed: PhantomData }
+    }
+
+    pub fn into_inner(self) -> T {
+        self.value
+    }
+}
+
+impl<T, S> Deref for SeededDefault<T, S> {
+
+    type Target = T;
+
+    fn deref(&self) -> &Self::Target {
+        &self.value
+    }
+}
+
+impl<T, S> DerefMut for SeededDefault<T, S> {
+
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.value
+    }
+}
+
+impl<T, S> Clone for SeededDefault<T, S>
+    where T: Clone
+{
+    fn clone(&self) -> Self {
+        Self::new(self.value.clone())
+    }
+}
+
+impl<T, S> Copy for SeededDefault<T, S>
+    where T: Copy
+{}
+
+impl<T, S> PartialEq for SeededDefault<T, S>
+    where T: PartialEq
+{
+    fn eq(&self, other: &Self) -> bool {
+        self.value == other.value
+    }
+}
+
+impl<T, S> Eq for SeededDefault<T, S>
+    where T: Eq
+{}
+
+impl<T, S> std::fmt::Debug for SeededDefault<T, S>
+    where T: std::fmt::Debug
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.value.fmt(f)
+    }
+}
+
+impl<T, S> Serialize for SeededDefault<T, S>
+    where T: Serialize
+{
+    fn serialize<Ser>(&self, serializer: Ser) -> Result<Ser::Ok, Ser::Error>
+        where Ser: Serializer
+    {
+        self.value.serialize(serializer)
+    }
+}
+
+impl<'de, T, S> Deserialize<'de> for SeededDefault<T, S>
+    where S: Default + DeserializeSeed<'de, Value = T>
+{
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+        where D: Deserializer<'de>
+    {
+        S::default().deserialize(deserializer).map(Self::new)
+    }
+}
+
+#[cfg(test)]
+use crate::lenient::DeserializeSeedLenient;
+
+#[test]
+fn test_seeded_default() {
+    let values: Vec<SeededDefault<i64, DeserializeSeedLenient<i64>>> = serde_json::from_str("[1, \"2\", null, 4.0]").unwrap();
+    assert_eq!(vec![1, 2, 0, 4], values.into_iter().map(SeededDefault::into_inner).collect::<Vec<_>>());
+
+    let value: SeededDefault<Vec<i64>, crate::default_seed::DefaultSeed<Vec<i64>>> = serde_json::from_str("[1, 2]").unwrap();
+    assert_eq!("[1,2]", serde_json::to_string(&value).unwrap());
+    assert_eq!(SeededDefault::new(vec![1, 2]), value);
+    assert!(serde_json::from_str::<SeededDefault<i64, DeserializeSeedLenient<i64>>>("[]").is_err());
+}
@@ -1,13 +1,23 @@
 use std::marker::PhantomData;
 
-use serde::de::{DeserializeSeed, Error, SeqAccess, Visitor};
+use serde::de::{DeserializeSeed, IgnoredAny, SeqAccess, Visitor};
 use serde::ser::{Serialize, SerializeSeq, Serializer};
 
+use crate::limits::Limits;
+use crate::seed_factory::SeedFactory;
+
+///
+/// The maximal number of elements reserved up front by [`DeserializeSeedSeq::with_reserve()`],
+/// independent of the length reported by the deserializer.
+///
+pub const MAX_PREALLOC: usize = 4096;
+
 ///
 /// Wraps an [`Iterator`] over serializable elements, and implements
 /// [`Serialize`] by mapping the sequence of elements to the seq type
 /// in the serde data model.
 /// 
+#[derive(Clone, Copy)]
 pub struct SerializableSeq<I>
     where I: Iterator + Clone
 {
@@ -18,14 +28,121 @@ pub struct SerializableSeq<I>
 impl<I> SerializableSeq<I>
     where I: Iterator + Clone
 {
-    pub fn new(data: I) -> Self {
+    pub const fn new(data: I) -> Self {
         Self { data: data, len: None }
     }
 
+    ///
+    /// Creates a new [`SerializableSeq`] that reports the given length to the serializer.
+    /// Some formats (e.g. postcard) require this.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `len` is inconsistent with the size hint of `data`. Use
+    /// [`SerializableSeq::try_new_with_len()`] to handle this case without panicking.
+    ///
     pub fn new_with_len(data: I, len: usize) -> Self {
-        assert!(data.size_hint().0 <= len);
-        assert!(data.size_hint().1.is_none() || data.size_hint().1.unwrap() >= len);
-        Self { data: data, len: Some(len) }
+        Self::try_new_with_len(data, len).unwrap()
+    }
+
+    ///
+    /// Creates a new [`SerializableSeq`] that reports the given length to the serializer,
+    /// or returns an error if `len` is inconsistent with the size hint of `data`.
+    ///
+    pub fn try_new_with_len(data: I, len: usize) -> Result<Self, LengthMismatchError> {
+        LengthMismatchError::check(data.size_hint(), len)?;
+        return Ok(Self { data: data, len: Some(len) });
+    }
+
+    ///
+    /// Creates a new [`SerializableSeq`] that determines the length reported to the
+    /// serializer according to the given [`LengthPolicy`].
+    ///
+    /// # Example
+    /// ```
+    /// # use feanor_serde::seq::*;
+    /// let serialized = postcard::to_allocvec(&SerializableSeq::with_length_policy([1, 3, 5].into_iter(), LengthPolicy::SizeHint)).unwrap();
+    /// assert_eq!(postcard::to_allocvec(&[1, 3, 5][..]).unwrap(), serialized);
+    /// ```
+    ///
+    pub fn with_length_policy(data: I, policy: LengthPolicy) -> Self {
+        let len = match policy {
+            LengthPolicy::Unknown => None,
+            LengthPolicy::TrustCaller(len) => Some(len),
+            LengthPolicy::SizeHint => match data.size_hint() {
+                (lower, Some(upper)) if lower == upper => Some(lower),
+                _ => None
+            }
+        };
+        Self { data: data, len: len }
+    }
+}
+
+///
+/// Determines which length [`SerializableSeq`] reports to the serializer,
+/// see [`SerializableSeq::with_length_policy()`].
+///
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum LengthPolicy {
+    ///
+    /// Do not report a length. Some formats (e.g. postcard) do not support this.
+    ///
+    Unknown,
+    ///
+    /// Report the given length, without checking it against the size hint of the iterator.
+    /// If the iterator produces a different number of elements, the serialized data may
+    /// be invalid.
+    ///
+    TrustCaller(usize),
+    ///
+    /// Report the length given by the size hint of the iterator if it is exact, and
+    /// otherwise do not report a length.
+    ///
+    SizeHint
+}
+
+///
+/// Error returned by [`SerializableSeq::try_new_with_len()`] and the corresponding functions
+/// in [`crate::map`] if the given length is inconsistent with the size hint of the iterator.
+///
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct LengthMismatchError {
+    len: usize,
+    size_hint: (usize, Option<usize>)
+}
+
+impl LengthMismatchError {
+
+    pub(crate) fn check(size_hint: (usize, Option<usize>), len: usize) -> Result<(), Self> {
+        if size_hint.0 > len || size_hint.1.is_some_and(|upper| upper < len) {
+            return Err(LengthMismatchError { len: len, size_hint: size_hint });
+        }
+        return Ok(());
+    }
+
+    pub fn requested_len(&self) -> usize {
+        self.len
+    }
+
+    pub fn size_hint(&self) -> (usize, Option<usize>) {
+        self.size_hint
+    }
+}
+
+impl std::fmt::Display for LengthMismatchError {
+
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "length {} is inconsistent with the size hint {:?} of the iterator", self.len, self.size_hint)
+    }
+}
+
+impl std::error::Error for LengthMismatchError {}
+
+impl<I> std::fmt::Debug for SerializableSeq<I>
+    where I: Iterator + Clone
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("SerializableSeq").field("len", &self.len).finish_non_exhaustive()
     }
 }
 
@@ -51,55 +168,200 @@ impl<I> Serialize for SerializableSeq<I>
 /// 
 /// # Length of the seed sequence
 /// 
-/// The iterator producing the seeds must contain at least one more seed than the
+/// The iterator producing the seeds should contain at least one more seed than the
 /// sequence to deserialize has elements. The reason is that for generic deserializers,
 /// we don't know whether we reached the end unless we try to deserialize an element
 /// beyond the end. However, to do that, we need a seed.
 /// 
+/// If the iterator runs out of seeds, we instead use the remaining length reported by the
+/// deserializer, or, if it does not report it, try to skip the next element using [`IgnoredAny`].
+/// Then
+///  - if the sequence has no further element, deserialization succeeds;
+///  - if the sequence has a further element, deserialization fails with an
+///    [`serde::de::Error::invalid_length()`] error, since the sequence has too many elements;
+///  - if skipping the element fails, e.g. because the input is malformed or the format does
+///    not support skipping elements, deserialization fails with the error of the deserializer.
+/// 
+/// If the iterator reports an upper bound in its [`Iterator::size_hint()`] (as e.g. every
+/// [`ExactSizeIterator`] does) and the deserializer reports the length of the sequence up
+/// front, deserialization fails before reading any element if there are not enough seeds.
+/// 
 /// # Example
 /// ```
 /// # use feanor_serde::seq::*;
-/// # use std::marker::PhantomData;
+/// # use feanor_serde::default_seed::seed_of;
 /// # use std::iter::repeat;
 /// # use serde::de::DeserializeSeed;
 /// let mut deserializer = serde_json::Deserializer::new(serde_json::de::StrRead::new("[1, 3, 5]"));
 /// let deserialize_seed = DeserializeSeedSeq::new(
-///     repeat(PhantomData::<i64>),
+///     repeat(seed_of::<i64>()),
 ///     Vec::new(),
 ///     |mut current, next| { current.push(next); current }
 /// );
 /// assert_eq!(vec![1, 3, 5], deserialize_seed.deserialize(&mut deserializer).unwrap());
 /// ```
 /// 
-pub struct DeserializeSeedSeq<'de, V, S, T, C>
-    where V: Iterator<Item = S>,
-        S: DeserializeSeed<'de>,
-        C: FnMut(T, S::Value) -> T
+pub struct DeserializeSeedSeq<V, S, T, C>
+    where V: Iterator<Item = S>
 {
-    deserializer: PhantomData<&'de ()>,
     element_seed: PhantomData<S>,
     seeds: V,
     initial: T,
-    collector: C
+    collector: C,
+    limits: Limits,
+    exact_len: Option<usize>,
+    reserve: Option<fn(&mut T, usize)>
 }
 
-impl<'de, V, S, T, C> DeserializeSeedSeq<'de, V, S, T, C>
+impl<'de, V, S, T, C> DeserializeSeedSeq<V, S, T, C>
     where V: Iterator<Item = S>,
         S: DeserializeSeed<'de>,
         C: FnMut(T, S::Value) -> T
 {
-    pub fn new(seeds: V, initial: T, collector: C) -> Self {
+    pub const fn new(seeds: V, initial: T, collector: C) -> Self {
         Self {
-            deserializer: PhantomData,
             element_seed: PhantomData,
             seeds: seeds,
             initial: initial,
-            collector: collector
+            collector: collector,
+            limits: Limits::UNLIMITED,
+            exact_len: None,
+            reserve: None
+        }
+    }
+
+    ///
+    /// Makes deserialization fail if the sequence has more than [`Limits::max_seq_len()`]
+    /// elements.
+    ///
+    pub const fn with_limits(self, limits: Limits) -> Self {
+        let mut result = self;
+        result.limits = limits;
+        return result;
+    }
+
+    ///
+    /// Calls `reserve` with the initial value and the length of the sequence before
+    /// deserializing the first element, e.g. to reserve capacity in a [`Vec`].
+    ///
+    /// This only happens if the deserializer reports the length of the sequence up front
+    /// and the seed iterator is guaranteed to provide enough seeds, according to its
+    /// [`Iterator::size_hint()`]. If the seed iterator does not report an upper bound (e.g.
+    /// [`std::iter::repeat()`]), the lower bound is used instead. In any case, at most
+    /// [`MAX_PREALLOC`] elements are reserved, since the length reported by the deserializer
+    /// might be untrusted. Further elements are then reserved by the collector as usual.
+    ///
+    /// # Example
+    /// ```
+    /// # use feanor_serde::seq::*;
+    /// # use feanor_serde::default_seed::seed_of;
+    /// # use serde::de::DeserializeSeed;
+    /// let serialized = postcard::to_allocvec(&[1i64, 3, 5][..]).unwrap();
+    /// let seed = DeserializeSeedSeq::new(std::iter::repeat_n(seed_of::<i64>(), 10), Vec::new(), |mut current, next| { current.push(next); current }).with_reserve(Vec::reserve_exact);
+    /// let result = seed.deserialize(&mut postcard::Deserializer::from_bytes(&serialized)).unwrap();
+    /// assert_eq!(vec![1, 3, 5], result);
+    /// assert_eq!(3, result.capacity());
+    /// ```
+    ///
+    pub const fn with_reserve(self, reserve: fn(&mut T, usize)) -> Self {
+        let mut result = self;
+        result.reserve = Some(reserve);
+        return result;
+    }
+
+    ///
+    /// Boxes the seed iterator and the collector, so that the resulting type does not
+    /// depend on the types of closures anymore, see [`BoxedDeserializeSeedSeq`].
+    ///
+    pub fn boxed<'a>(self) -> BoxedDeserializeSeedSeq<'a, S, T, S::Value>
+        where V: 'a,
+            C: 'a
+    {
+        let mut result: BoxedDeserializeSeedSeq<'a, S, T, S::Value> = DeserializeSeedSeq::new(Box::new(self.seeds), self.initial, Box::new(self.collector));
+        result.limits = self.limits;
+        result.exact_len = self.exact_len;
+        result.reserve = self.reserve;
+        return result;
+    }
+}
+
+///
+/// A [`DeserializeSeedSeq`] whose seed iterator and collector are trait objects, as
+/// returned by [`DeserializeSeedSeq::boxed()`]. Here `V` is the type of the values
+/// produced by the seeds `S`.
+///
+/// Every closure has its own type, thus each nesting of [`DeserializeSeedSeq`]s and other
+/// combinators is instantiated separately for each deserializer it is used with, which
+/// can significantly increase compile times and binary size. Using boxed combinators avoids
+/// this, at the cost of dynamic dispatch and an allocation per combinator.
+///
+/// # Example
+/// ```
+/// # use feanor_serde::seq::*;
+/// # use feanor_serde::default_seed::{DefaultSeed, seed_of};
+/// # use serde::de::DeserializeSeed;
+/// fn sum_seed<'a>(offset: &'a i64) -> BoxedDeserializeSeedSeq<'a, DefaultSeed<i64>, i64, i64> {
+///     DeserializeSeedSeq::new(std::iter::repeat(seed_of::<i64>()), 0, move |current, next| current + next + offset).boxed()
+/// }
+/// let offset = 1;
+/// let seed = DeserializeSeedSeq::new(std::iter::repeat_with(|| sum_seed(&offset)), 0, |current, next| current + next).boxed();
+/// assert_eq!(9, seed.deserialize(&mut serde_json::Deserializer::from_str("[[1, 2], [3]]")).unwrap());
+/// ```
+///
+pub type BoxedDeserializeSeedSeq<'a, S, T, V> = DeserializeSeedSeq<Box<dyn Iterator<Item = S> + 'a>, S, T, Box<dyn FnMut(T, V) -> T + 'a>>;
+
+impl<V, S, T, C> Clone for DeserializeSeedSeq<V, S, T, C>
+    where V: Iterator<Item = S> + Clone,
+        T: Clone,
+        C: Clone
+{
+    fn clone(&self) -> Self {
+        Self {
+            element_seed: PhantomData,
+            seeds: self.seeds.clone(),
+            initial: self.initial.clone(),
+            collector: self.collector.clone(),
+            limits: self.limits,
+            exact_len: self.exact_len,
+            reserve: self.reserve
         }
     }
 }
 
-impl<'de, V, S, T, C> DeserializeSeed<'de> for DeserializeSeedSeq<'de, V, S, T, C>
+impl<V, S, T, C> Copy for DeserializeSeedSeq<V, S, T, C>
+    where V: Iterator<Item = S> + Copy,
+        T: Copy,
+        C: Copy
+{}
+
+impl<V, S, T, C> std::fmt::Debug for DeserializeSeedSeq<V, S, T, C>
+    where V: Iterator<Item = S>
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("DeserializeSeedSeq").field("limits", &self.limits).field("exact_len", &self.exact_len).finish_non_exhaustive()
+    }
+}
+
+///
+/// Deserializes using a clone of the referenced seed, so a configured seed can be reused
+/// without reconstructing it.
+///
+impl<'de, V, S, T, C> DeserializeSeed<'de> for &DeserializeSeedSeq<V, S, T, C>
+    where V: Iterator<Item = S> + Clone,
+        S: DeserializeSeed<'de>,
+        T: Clone,
+        C: Clone + FnMut(T, S::Value) -> T
+{
+    type Value = T;
+
+    fn deserialize<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+        where D: serde::Deserializer<'de>
+    {
+        self.clone().deserialize(deserializer)
+    }
+}
+
+impl<'de, V, S, T, C> DeserializeSeed<'de> for DeserializeSeedSeq<V, S, T, C>
     where V: Iterator<Item = S>, 
         S: DeserializeSeed<'de>,
         C: FnMut(T, S::Value) -> T
@@ -118,7 +380,10 @@ impl<'de, V, S, T, C> DeserializeSeed<'de> for DeserializeSeedSeq<'de, V, S, T,
             element_seed: PhantomData<S>,
             seeds: V,
             initial: T,
-            collector: C
+            collector: C,
+            limits: Limits,
+            exact_len: Option<usize>,
+            reserve: Option<fn(&mut T, usize)>
         }
 
         impl<'de, V, S, T, C> Visitor<'de> for ResultVisitor<'de, V, S, T, C>
@@ -129,24 +394,58 @@ impl<'de, V, S, T, C> DeserializeSeed<'de> for DeserializeSeedSeq<'de, V, S, T,
             type Value = T;
 
             fn expecting(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
-                write!(f, "a sequence of elements")
+                match self.exact_len {
+                    Some(len) => write!(f, "a sequence of {} elements", len),
+                    None => write!(f, "a sequence of elements")
+                }
             }
 
             fn visit_seq<B>(mut self, mut seq: B) -> Result<Self::Value, B::Error>
                 where B: SeqAccess<'de>
             {
                 let mut result = self.initial;
+                if let Some(len) = seq.size_hint() {
+                    self.limits.check_seq_len(len)?;
+                    check_exact_len(self.exact_len, len)?;
+                    match self.seeds.size_hint() {
+                        (_, Some(seed_count)) if len > seed_count => {
+                            return Err(crate::errors::invalid_length(len, "a shorter sequence", format_args!("a sequence of length at most {}", seed_count)));
+                        },
+                        // the length is reported by the input, so don't trust it too much, even if the
+                        // seeds are bounded (e.g. `take(1 << 40)` does not bound anything in practice)
+                        (_, Some(_)) => if let Some(reserve) = self.reserve {
+                            reserve(&mut result, len.min(MAX_PREALLOC));
+                        },
+                        // without an upper bound, fall back to the lower bound (e.g. `usize::MAX` for `repeat()`)
+                        (min_seed_count, None) => if let Some(reserve) = self.reserve.filter(|_| len <= min_seed_count) {
+                            reserve(&mut result, len.min(MAX_PREALLOC));
+                        }
+                    }
+                }
                 let mut current_len = 0;
-                while let Some(seed) = self.seeds.next() {
+                for seed in self.seeds.by_ref() {
                     let el = seq.next_element_seed(seed)?;
                     if let Some(el) = el {
                         current_len += 1;
+                        self.limits.check_seq_len(current_len)?;
+                        if self.exact_len.is_some_and(|exact_len| current_len > exact_len) {
+                            check_exact_len(self.exact_len, current_len)?;
+                        }
                         result = (self.collector)(result, el);
                     } else {
+                        check_exact_len(self.exact_len, current_len)?;
                         return Ok(result);
                     }
                 }
-                return Err(Error::invalid_length(current_len, &format!("a sequence of length at most {}", current_len - 1).as_str()))
+                // if the deserializer knows the number of remaining elements, we don't have to skip one
+                let remaining = match seq.size_hint() {
+                    Some(remaining) => remaining,
+                    None => seq.next_element::<IgnoredAny>()?.map_or(0, |_| 1)
+                };
+                if remaining > 0 {
+                    return Err(crate::errors::invalid_length(current_len + remaining, "a shorter sequence", format_args!("a sequence of length at most {}", current_len)));
+                }
+                return check_exact_len(self.exact_len, current_len).map(|()| result);
             }
         }
 
@@ -155,11 +454,163 @@ impl<'de, V, S, T, C> DeserializeSeed<'de> for DeserializeSeedSeq<'de, V, S, T,
             element_seed: PhantomData,
             collector: self.collector,
             initial: self.initial,
-            seeds: self.seeds
+            seeds: self.seeds,
+            limits: self.limits,
+            exact_len: self.exact_len,
+            reserve: self.reserve
         });
     }
 }
 
+///
+/// Builder for a [`DeserializeSeedSeq`], which allows configuring how a sequence is
+/// deserialized in a single place.
+///
+/// # Example
+/// ```
+/// # use feanor_serde::seq::*;
+/// # use feanor_serde::default_seed::seed_of;
+/// # use serde::de::DeserializeSeed;
+/// let seed = SeqSeedBuilder::new(seed_of::<i64>).exact_len(3).collect_vec();
+/// assert_eq!(vec![1, 3, 5], seed.deserialize(&mut serde_json::Deserializer::from_str("[1, 3, 5]")).unwrap());
+///
+/// let seed = SeqSeedBuilder::new(seed_of::<i64>).exact_len(3).collect_vec();
+/// assert!(seed.deserialize(&mut serde_json::Deserializer::from_str("[1, 3]")).is_err());
+///
+/// let seed = SeqSeedBuilder::new(seed_of::<i64>).max_len(2).fold(0, |current, next| current + next);
+/// assert_eq!(4, seed.deserialize(&mut serde_json::Deserializer::from_str("[1, 3]")).unwrap());
+/// ```
+///
+#[derive(Clone, Copy)]
+pub struct SeqSeedBuilder<V>
+    where V: Iterator
+{
+    seeds: V,
+    limits: Limits,
+    exact_len: Option<usize>,
+    capacity: Option<usize>
+}
+
+impl<V> std::fmt::Debug for SeqSeedBuilder<V>
+    where V: Iterator
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("SeqSeedBuilder").field("limits", &self.limits).field("exact_len", &self.exact_len).field("capacity", &self.capacity).finish_non_exhaustive()
+    }
+}
+
+impl<F, S> SeqSeedBuilder<SeedFactory<F>>
+    where F: FnMut() -> S
+{
+    ///
+    /// Creates a new [`SeqSeedBuilder`] that deserializes each element with a seed
+    /// produced by the given function.
+    ///
+    pub fn new(seed_factory: F) -> Self {
+        Self::from_seeds(SeedFactory::new(seed_factory))
+    }
+}
+
+impl<V> SeqSeedBuilder<V>
+    where V: Iterator
+{
+    ///
+    /// Creates a new [`SeqSeedBuilder`] that deserializes the elements with the seeds
+    /// returned by the given iterator, see also [`DeserializeSeedSeq::new()`].
+    ///
+    pub const fn from_seeds(seeds: V) -> Self {
+        Self { seeds, limits: Limits::UNLIMITED, exact_len: None, capacity: None }
+    }
+
+    ///
+    /// Makes deserialization fail if the sequence has more than `max_len` elements.
+    ///
+    pub const fn max_len(self, max_len: usize) -> Self {
+        let mut result = self;
+        result.limits = result.limits.with_max_seq_len(max_len);
+        return result;
+    }
+
+    ///
+    /// Makes deserialization fail if the sequence does not have exactly `len` elements.
+    ///
+    pub const fn exact_len(self, len: usize) -> Self {
+        let mut result = self;
+        result.exact_len = Some(len);
+        return result;
+    }
+
+    ///
+    /// Sets the initial capacity of the collection created by [`SeqSeedBuilder::collect_vec()`]
+    /// and similar functions. By default, this is the length given to [`SeqSeedBuilder::exact_len()`],
+    /// if any.
+    ///
+    pub const fn with_capacity(self, capacity: usize) -> Self {
+        let mut result = self;
+        result.capacity = Some(capacity);
+        return result;
+    }
+
+    ///
+    /// Replaces the limit on the sequence length by [`Limits::max_seq_len()`], see also
+    /// [`DeserializeSeedSeq::with_limits()`].
+    ///
+    pub const fn with_limits(self, limits: Limits) -> Self {
+        let mut result = self;
+        result.limits = limits;
+        return result;
+    }
+}
+
+///
+/// The collector used by [`SeqSeedBuilder::collect_vec()`].
+///
+pub type PushToVec<T> = fn(Vec<T>, T) -> Vec<T>;
+
+impl<'de, V, S> SeqSeedBuilder<V>
+    where V: Iterator<Item = S>,
+        S: DeserializeSeed<'de>
+{
+    ///
+    /// Builds a [`DeserializeSeedSeq`] that combines the elements using the given
+    /// collector, as in [`DeserializeSeedSeq::new()`].
+    ///
+    pub fn fold<T, C>(self, initial: T, collector: C) -> DeserializeSeedSeq<V, S, T, C>
+        where C: FnMut(T, S::Value) -> T
+    {
+        let mut result = DeserializeSeedSeq::new(self.seeds, initial, collector).with_limits(self.limits);
+        result.exact_len = self.exact_len;
+        return result;
+    }
+
+    ///
+    /// Builds a [`DeserializeSeedSeq`] that collects the elements into a [`Vec`].
+    /// If the deserializer reports the length of the sequence, the [`Vec`] is additionally
+    /// reserved as in [`DeserializeSeedSeq::with_reserve()`].
+    ///
+    pub fn collect_vec(self) -> DeserializeSeedSeq<V, S, Vec<S::Value>, PushToVec<S::Value>> {
+        let capacity = self.initial_capacity();
+        return self.fold::<_, PushToVec<_>>(Vec::with_capacity(capacity), |mut current, next| { current.push(next); current }).with_reserve(Vec::reserve);
+    }
+}
+
+impl<V> SeqSeedBuilder<V>
+    where V: Iterator
+{
+    pub(crate) fn initial_capacity(&self) -> usize {
+        self.capacity.or(self.exact_len).unwrap_or(0)
+    }
+}
+
+fn check_exact_len<E>(exact_len: Option<usize>, len: usize) -> Result<(), E>
+    where E: serde::de::Error
+{
+    match exact_len {
+        Some(exact_len) if exact_len != len => Err(crate::errors::invalid_length(len, "a sequence of different length", format_args!("a sequence of length {}", exact_len))),
+        _ => Ok(())
+    }
+}
+
 #[cfg(test)]
 use std::iter::{repeat, repeat_with};
 
@@ -232,4 +683,190 @@ fn test_deserialize_sequence_partially_postcard() {
         &mut postcard::Deserializer::from_flavor(postcard::de_flavors::Slice::new(&serialized))
     ).unwrap();
     assert_eq!(data, result);
-}
\ No newline at end of file
+}
+
+#[test]
+fn test_limits() {
+    let limits = Limits::UNLIMITED.with_max_seq_len(2);
+    for data in testdata() {
+        let serialized = postcard::to_allocvec(&SerializableSeq::new_with_len(data.iter().copied(), data.len())).unwrap();
+        let result = DeserializeSeedSeq::new(repeat(PhantomData::<i64>), Vec::new(), |mut current, next| { current.push(next); current }).with_limits(limits).deserialize(
+            &mut postcard::Deserializer::from_flavor(postcard::de_flavors::Slice::new(&serialized))
+        );
+        assert_eq!(data.len() <= 2, result.is_ok());
+
+        let serialized = serde_json::to_string(&SerializableSeq::new(data.iter().copied())).unwrap();
+        let result = DeserializeSeedSeq::new(repeat(PhantomData::<i64>), Vec::new(), |mut current, next| { current.push(next); current }).with_limits(limits).deserialize(
+            &mut serde_json::Deserializer::from_str(&serialized)
+        );
+        assert_eq!(data.len() <= 2, result.is_ok());
+    }
+}
+
+#[test]
+fn test_length_policy() {
+    assert!(SerializableSeq::try_new_with_len([1, 2, 3].into_iter(), 3).is_ok());
+    let error = SerializableSeq::try_new_with_len([1, 2, 3].into_iter(), 2).err().unwrap();
+    assert_eq!(2, error.requested_len());
+    assert_eq!((3, Some(3)), error.size_hint());
+    assert!(SerializableSeq::try_new_with_len([1, 2, 3].into_iter(), 4).is_err());
+    assert!(SerializableSeq::try_new_with_len([1, 2, 3].into_iter().filter(|x| *x > 1), 2).is_ok());
+
+    let data = [1i64, 2, 3];
+    let expected = serde_json::to_string(&data).unwrap();
+    assert_eq!(expected, serde_json::to_string(&SerializableSeq::with_length_policy(data.iter(), LengthPolicy::Unknown)).unwrap());
+    assert_eq!(expected, serde_json::to_string(&SerializableSeq::with_length_policy(data.iter(), LengthPolicy::SizeHint)).unwrap());
+    assert!(postcard::to_allocvec(&SerializableSeq::with_length_policy(data.iter().filter(|x| **x > 1), LengthPolicy::SizeHint)).is_err());
+    let serialized = postcard::to_allocvec(&SerializableSeq::with_length_policy(data.iter().filter(|x| **x > 1), LengthPolicy::TrustCaller(2))).unwrap();
+    assert_eq!(postcard::to_allocvec(&[2i64, 3][..]).unwrap(), serialized);
+}
+
+#[test]
+fn test_seed_count() {
+    let deserialize_with_seeds = |json: &str, seeds: usize| DeserializeSeedSeq::new(
+        std::iter::repeat_n(PhantomData::<i64>, seeds),
+        Vec::new(),
+        |mut current, next| { current.push(next); current }
+    ).deserialize(&mut serde_json::Deserializer::from_str(json));
+
+    assert_eq!(Vec::<i64>::new(), deserialize_with_seeds("[]", 0).unwrap());
+    assert!(deserialize_with_seeds("[1]", 0).unwrap_err().to_string().starts_with("invalid length 1"));
+    assert_eq!(vec![1, 2], deserialize_with_seeds("[1, 2]", 2).unwrap());
+    assert_eq!(vec![1, 2], deserialize_with_seeds("[1, 2]", 3).unwrap());
+    assert!(deserialize_with_seeds("[1, 2, 3]", 2).unwrap_err().to_string().starts_with("invalid length 3"));
+    assert!(deserialize_with_seeds("[1, 2, x]", 2).unwrap_err().to_string().starts_with("expected value"));
+    assert!(deserialize_with_seeds("[1, 2", 2).unwrap_err().is_eof());
+
+    let deserialize_with_seeds = |data: &[i64], seeds: usize| {
+        let serialized = postcard::to_allocvec(data).unwrap();
+        DeserializeSeedSeq::new(
+            std::iter::repeat_n(PhantomData::<i64>, seeds),
+            Vec::new(),
+            |mut current, next| { current.push(next); current }
+        ).deserialize(&mut postcard::Deserializer::from_flavor(postcard::de_flavors::Slice::new(&serialized)))
+    };
+    assert_eq!(Vec::<i64>::new(), deserialize_with_seeds(&[], 0).unwrap());
+    assert_eq!(vec![1, 2], deserialize_with_seeds(&[1, 2], 2).unwrap());
+    // postcard reports the length up front, so this fails before deserializing any element
+    assert_eq!(postcard::Error::SerdeDeCustom, deserialize_with_seeds(&[1, 2, 3], 2).unwrap_err());
+    assert_eq!(postcard::Error::SerdeDeCustom, deserialize_with_seeds(&[1], 0).unwrap_err());
+
+    // postcard cannot skip elements, but it reports the number of remaining elements, so too
+    // many elements are detected even without an upper bound on the seeds
+    let serialized = postcard::to_allocvec(&[1i64, 2, 3][..]).unwrap();
+    let error = DeserializeSeedSeq::new(std::iter::repeat_n(PhantomData::<i64>, 2).chain(std::iter::from_fn(|| None)), Vec::new(), |mut current, next| { current.push(next); current })
+        .deserialize(&mut postcard::Deserializer::from_bytes(&serialized)).unwrap_err();
+    assert_eq!(postcard::Error::SerdeDeCustom, error);
+}
+
+#[test]
+fn test_seed_count_fails_early() {
+    let mut deserialized = 0;
+    let serialized = postcard::to_allocvec(&[1i64, 2, 3][..]).unwrap();
+    let seed = DeserializeSeedSeq::new(
+        std::iter::repeat_n(PhantomData::<i64>, 2),
+        (),
+        |(), _| { deserialized += 1; }
+    );
+    assert!(seed.deserialize(&mut postcard::Deserializer::from_bytes(&serialized)).is_err());
+    assert_eq!(0, deserialized);
+
+    let seed = SeqSeedBuilder::from_seeds(std::iter::repeat_n(PhantomData::<i64>, 5)).collect_vec();
+    let result = seed.deserialize(&mut postcard::Deserializer::from_bytes(&serialized)).unwrap();
+    assert_eq!(vec![1, 2, 3], result);
+    assert!(result.capacity() >= 3);
+
+    let error: serde_json::Error = DeserializeSeedSeq::new(std::iter::repeat_n(PhantomData::<i64>, 2), (), |(), _| ())
+        .deserialize(serde::de::value::SeqDeserializer::new([1i64, 2, 3].into_iter())).unwrap_err();
+    assert!(error.to_string().starts_with("invalid length 3"));
+}
+
+#[test]
+fn test_reserve_unbounded_seeds() {
+    let serialized = postcard::to_allocvec(&[1i64, 2, 3][..]).unwrap();
+    let collect = |(mut current, reserved): (Vec<i64>, Option<usize>), next| { current.push(next); (current, reserved) };
+    let record_reserve: fn(&mut (Vec<i64>, Option<usize>), usize) = |(_, reserved), len| { *reserved = Some(len); };
+
+    let seed = DeserializeSeedSeq::new(std::iter::repeat(PhantomData::<i64>), (Vec::new(), None), collect).with_reserve(record_reserve);
+    assert_eq!((vec![1, 2, 3], Some(3)), seed.deserialize(&mut postcard::Deserializer::from_bytes(&serialized)).unwrap());
+
+    // the lower bound of the size hint is 0, so the seeds might run out
+    let seed = DeserializeSeedSeq::new(std::iter::repeat(PhantomData::<i64>).take_while(|_| true), (Vec::new(), None), collect).with_reserve(record_reserve);
+    assert_eq!((vec![1, 2, 3], None), seed.deserialize(&mut postcard::Deserializer::from_bytes(&serialized)).unwrap());
+
+    // a huge length is not reserved up front
+    let serialized = postcard::to_allocvec(&(u32::MAX as usize)).unwrap();
+    let seed = DeserializeSeedSeq::new(std::iter::repeat(PhantomData::<i64>), Vec::new(), |mut current, next| { current.push(next); current }).with_reserve(Vec::reserve_exact);
+    assert!(seed.deserialize(&mut postcard::Deserializer::from_bytes(&serialized)).is_err());
+}
+
+#[test]
+fn test_reserve_bounded_seeds() {
+    let collect = |(mut current, reserved): (Vec<i64>, Option<usize>), next| { current.push(next); (current, reserved) };
+    let record_reserve: fn(&mut (Vec<i64>, Option<usize>), usize) = |(_, reserved), len| { *reserved = Some(len); };
+    let check_reserve: fn(&mut Vec<i64>, usize) = |current, len| {
+        assert!(len <= MAX_PREALLOC);
+        current.reserve_exact(len);
+    };
+
+    let serialized = postcard::to_allocvec(&[1i64, 2, 3][..]).unwrap();
+    let seed = DeserializeSeedSeq::new((0..usize::MAX).map(|_| PhantomData::<i64>), (Vec::new(), None), collect).with_reserve(record_reserve);
+    assert_eq!((vec![1, 2, 3], Some(3)), seed.deserialize(&mut postcard::Deserializer::from_bytes(&serialized)).unwrap());
+
+    // a length prefix of 2^40 followed by a single element
+    let mut serialized = postcard::to_allocvec(&(1u64 << 40)).unwrap();
+    serialized.extend(postcard::to_allocvec(&1i64).unwrap());
+    let seed = DeserializeSeedSeq::new((0..usize::MAX).map(|_| PhantomData::<i64>), Vec::new(), |mut current, next| { current.push(next); current }).with_reserve(check_reserve);
+    assert!(seed.deserialize(&mut postcard::Deserializer::from_bytes(&serialized)).is_err());
+    let seed = DeserializeSeedSeq::new(std::iter::repeat_n(PhantomData::<i64>, 1 << 40), Vec::new(), |mut current, next| { current.push(next); current }).with_reserve(check_reserve);
+    assert!(seed.deserialize(&mut postcard::Deserializer::from_bytes(&serialized)).is_err());
+}
+
+#[test]
+fn test_seq_seed_builder() {
+    let deserialize = |seed: DeserializeSeedSeq<_, _, _, _>, json: &str| seed.deserialize(&mut serde_json::Deserializer::from_str(json));
+    let builder = || SeqSeedBuilder::new(|| PhantomData::<i64>);
+
+    assert_eq!(vec![1, 2], deserialize(builder().exact_len(2).collect_vec(), "[1, 2]").unwrap());
+    assert!(deserialize(builder().exact_len(2).collect_vec(), "[1]").unwrap_err().to_string().starts_with("invalid length 1"));
+    assert!(deserialize(builder().exact_len(2).collect_vec(), "[1, 2, 3]").unwrap_err().to_string().starts_with("invalid length 3"));
+    assert!(deserialize(builder().max_len(2).collect_vec(), "[1, 2, 3]").is_err());
+    assert_eq!(vec![1, 2, 3], deserialize(builder().max_len(3).with_capacity(10).collect_vec(), "[1, 2, 3]").unwrap());
+
+    let seed = SeqSeedBuilder::from_seeds(std::iter::repeat_n(PhantomData::<i64>, 3)).exact_len(2).fold(0, |current, next| current + next);
+    assert_eq!(3, seed.deserialize(&mut serde_json::Deserializer::from_str("[1, 2]")).unwrap());
+
+    // postcard reports the length up front
+    let serialized = postcard::to_allocvec(&[1i64, 2, 3][..]).unwrap();
+    let seed = builder().exact_len(2).collect_vec();
+    assert!(seed.deserialize(&mut postcard::Deserializer::from_bytes(&serialized)).is_err());
+}
+
+#[test]
+fn test_clone_debug() {
+    let seed = DeserializeSeedSeq::new(repeat(PhantomData::<i64>), 0, |current, next| current + next).with_limits(Limits::UNLIMITED.with_max_seq_len(2));
+    assert_eq!(3, seed.clone().deserialize(&mut serde_json::Deserializer::from_str("[1, 2]")).unwrap());
+    assert!(format!("{:?}", seed).starts_with("DeserializeSeedSeq { limits: Limits { max_seq_len: 2"));
+    assert!(seed.deserialize(&mut serde_json::Deserializer::from_str("[1, 2, 3]")).is_err());
+
+    let data = SerializableSeq::new_with_len([1, 2].iter(), 2);
+    assert_eq!("SerializableSeq { len: Some(2), .. }", format!("{:?}", data));
+    assert_eq!(serde_json::to_string(&data).unwrap(), serde_json::to_string(&data.clone()).unwrap());
+}
+
+#[test]
+fn test_boxed() {
+    let seed = SeqSeedBuilder::from_seeds(repeat(PhantomData::<i64>)).max_len(3).exact_len(2).fold(0, |current, next| current + next).boxed();
+    assert_eq!(4, seed.deserialize(&mut serde_json::Deserializer::from_str("[1, 3]")).unwrap());
+
+    let deserialize = |json: &str| SeqSeedBuilder::from_seeds(repeat(PhantomData::<i64>)).exact_len(2).fold(0, |current, next| current + next).boxed().deserialize(&mut serde_json::Deserializer::from_str(json));
+    assert!(deserialize("[1]").is_err());
+    assert!(deserialize("[1, 2, 3]").is_err());
+
+    let offset = 1;
+    let seeds: [BoxedDeserializeSeedSeq<_, i64, i64>; 2] = [
+        DeserializeSeedSeq::new(repeat(PhantomData::<i64>), 0, |current, next| current + next).boxed(),
+        DeserializeSeedSeq::new(repeat(PhantomData::<i64>), 0, |current, next| current + next + offset).boxed()
+    ];
+    assert_eq!([4, 6], seeds.map(|seed| seed.deserialize(&mut serde_json::Deserializer::from_str("[1, 3]")).unwrap()));
+}
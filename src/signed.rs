@@ -0,0 +1,204 @@
+use serde::de::{DeserializeSeed, Error, SeqAccess, Visitor};
+use serde::ser::{Serialize, SerializeTuple, Serializer};
+use serde::Deserializer;
+
+use crate::raw_bytes::{Bytes, ByteBufSeed};
+use crate::into_seed::IntoSeed;
+
+///
+/// Computes the signature of a payload for [`SerializableSigned`]. This crate does not
+/// provide any cryptography, so implement this e.g. using an HMAC or ed25519 library.
+///
+/// This is implemented for all closures `Fn(&[u8]) -> Vec<u8>`.
+///
+pub trait Signer {
+
+    fn sign(&self, payload: &[u8]) -> Vec<u8>;
+}
+
+impl<F> Signer for F
+    where F: Fn(&[u8]) -> Vec<u8>
+{
+    fn sign(&self, payload: &[u8]) -> Vec<u8> {
+        self(payload)
+    }
+}
+
+///
+/// Checks the signature of a payload for [`DeserializeSeedSigned`].
+///
+/// This is implemented for all closures `Fn(&[u8], &[u8]) -> bool`, which get the payload
+/// and the signature.
+///
+pub trait Verifier {
+
+    fn verify(&self, payload: &[u8], signature: &[u8]) -> bool;
+}
+
+impl<F> Verifier for F
+    where F: Fn(&[u8], &[u8]) -> bool
+{
+    fn verify(&self, payload: &[u8], signature: &[u8]) -> bool {
+        self(payload, signature)
+    }
+}
+
+///
+/// Wraps a serializable value, and implements [`Serialize`] by encoding it with postcard,
+/// and mapping it to the tuple `(signature, payload)` in the serde data model, where `payload`
+/// is the encoded value as a byte buffer, and `signature` is the byte buffer computed from it
+/// by the given [`Signer`].
+///
+/// Use [`DeserializeSeedSigned`] to deserialize it, which verifies the signature before
+/// deserializing the payload.
+///
+/// # Example
+/// ```
+/// # use feanor_serde::signed::*;
+/// # use feanor_serde::default_seed::seed_of;
+/// // not secure, just for demonstration
+/// let sign = |payload: &[u8]| vec![payload.iter().fold(42u8, |x, y| x.wrapping_mul(31) ^ y)];
+/// let verify = |payload: &[u8], signature: &[u8]| sign(payload) == signature;
+///
+/// let serialized = postcard::to_allocvec(&SerializableSigned::new(vec![1i64, 2, 3], &sign)).unwrap();
+/// let seed = DeserializeSeedSigned::new(seed_of::<Vec<i64>>(), &verify);
+/// assert_eq!(vec![1, 2, 3], feanor_serde::postcard::from_slice_seeded(seed, &serialized).unwrap());
+/// ```
+///
+pub struct SerializableSigned<'a, T, G>
+    where T: Serialize,
+        G: Signer
+{
+    data: T,
+    signer: &'a G
+}
+
+impl<'a, T, G> SerializableSigned<'a, T, G>
+    where T: Serialize,
+        G: Signer
+{
+    pub const fn new(data: T, signer: &'a G) -> Self {
+        Self { data, signer }
+    }
+}
+
+impl<'a, T, G> Serialize for SerializableSigned<'a, T, G>
+    where T: Serialize,
+        G: Signer
+{
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+        where S: Serializer
+    {
+        let payload = ::postcard::to_allocvec(&self.data).map_err(serde::ser::Error::custom)?;
+        let signature = self.signer.sign(&payload);
+        let mut tuple = serializer.serialize_tuple(2)?;
+        tuple.serialize_element(&Bytes(&signature))?;
+        tuple.serialize_element(&Bytes(&payload))?;
+        return tuple.end();
+    }
+}
+
+///
+/// A [`DeserializeSeed`] that deserializes a value serialized using [`SerializableSigned`].
+/// The signature is checked by the given [`Verifier`], and only if it is valid, the payload
+/// is passed to the wrapped [`DeserializeSeed`].
+///
+/// Since the payload might not be borrowed from the input, the wrapped seed must be able to
+/// deserialize from data of any lifetime.
+///
+pub struct DeserializeSeedSigned<'a, S, V>
+    where V: Verifier
+{
+    seed: S,
+    verifier: &'a V
+}
+
+impl<'a, S, V> DeserializeSeedSigned<'a, S, V>
+    where V: Verifier
+{
+    pub fn new<'de, I, M>(seed: I, verifier: &'a V) -> Self
+        where I: IntoSeed<'de, M, Seed = S>
+    {
+        Self { seed: seed.into_seed(), verifier }
+    }
+}
+
+impl<'a, 'de, S, V, T> DeserializeSeed<'de> for DeserializeSeedSigned<'a, S, V>
+    where S: for<'b> DeserializeSeed<'b, Value = T>,
+        V: Verifier
+{
+    type Value = T;
+
+    fn deserialize<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+        where D: Deserializer<'de>
+    {
+        return deserializer.deserialize_tuple(2, self);
+    }
+}
+
+impl<'a, 'de, S, V, T> Visitor<'de> for DeserializeSeedSigned<'a, S, V>
+    where S: for<'b> DeserializeSeed<'b, Value = T>,
+        V: Verifier
+{
+    type Value = T;
+
+    fn expecting(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "a signature and a byte buffer")
+    }
+
+    fn visit_seq<A>(self, mut seq: A) -> Result<Self::Value, A::Error>
+        where A: SeqAccess<'de>
+    {
+        let signature = seq.next_element_seed(ByteBufSeed)?.ok_or_else(|| A::Error::invalid_length(0, &self))?;
+        let payload = seq.next_element_seed(ByteBufSeed)?.ok_or_else(|| A::Error::invalid_length(1, &self))?;
+        if !self.verifier.verify(&payload, &signature) {
+            return Err(A::Error::custom("invalid signature"));
+        }
+        let (result, rest) = crate::postcard::take_from_slice_seeded(self.seed, &payload).map_err(A::Error::custom)?;
+        if !rest.is_empty() {
+            return Err(A::Error::custom(format!("payload has {} trailing bytes", rest.len())));
+        }
+        return Ok(result);
+    }
+}
+
+#[cfg(test)]
+use std::marker::PhantomData;
+
+#[cfg(test)]
+struct XorSigner(u8);
+
+#[cfg(test)]
+impl Signer for XorSigner {
+
+    fn sign(&self, payload: &[u8]) -> Vec<u8> {
+        vec![payload.iter().fold(self.0, |x, y| x ^ y)]
+    }
+}
+
+#[cfg(test)]
+impl Verifier for XorSigner {
+
+    fn verify(&self, payload: &[u8], signature: &[u8]) -> bool {
+        self.sign(payload) == signature
+    }
+}
+
+#[test]
+fn test_serde_postcard() {
+    let data = vec![(1i64, "a".to_owned()), (2, "b".to_owned())];
+    let serialized = postcard::to_allocvec(&SerializableSigned::new(&data, &XorSigner(7))).unwrap();
+    assert_eq!(data, crate::postcard::from_slice_seeded(DeserializeSeedSigned::new(PhantomData::<Vec<(i64, String)>>, &XorSigner(7)), &serialized).unwrap());
+    let error = crate::postcard::from_slice_seeded(DeserializeSeedSigned::new(PhantomData::<Vec<(i64, String)>>, &XorSigner(8)), &serialized).unwrap_err();
+    assert_eq!(postcard::Error::SerdeDeCustom, error);
+}
+
+#[test]
+fn test_serde_json() {
+    let serialized = serde_json::to_string(&SerializableSigned::new(5u8, &XorSigner(1))).unwrap();
+    assert_eq!("[[4],[5]]", serialized);
+    let result = DeserializeSeedSigned::new(PhantomData::<u8>, &XorSigner(1)).deserialize(&mut serde_json::Deserializer::from_str(&serialized));
+    assert_eq!(5, result.unwrap());
+    let result = DeserializeSeedSigned::new(PhantomData::<u8>, &XorSigner(1)).deserialize(&mut serde_json::Deserializer::from_str("[[5],[5]]"));
+    assert!(result.unwrap_err().to_string().contains("invalid signature"));
+}
@@ -0,0 +1,417 @@
+use serde::ser::*;
+
+///
+/// Describes how many bytes a format uses to encode each part of the serde data model,
+/// for use with [`CountingSerializer`].
+///
+/// Methods for tuples, structs and their fields are not part of this trait, since binary formats
+/// usually do not encode them at all. Hence, this is only suitable for such formats, and
+/// [`PostcardSizeRules`] is the canonical example.
+///
+pub trait SizeRules {
+
+    fn bool(&self, value: bool) -> usize;
+
+    ///
+    /// The size of a signed integer, whose type has `width` bytes.
+    ///
+    fn signed(&self, value: i128, width: usize) -> usize;
+
+    ///
+    /// The size of an unsigned integer, whose type has `width` bytes.
+    ///
+    fn unsigned(&self, value: u128, width: usize) -> usize;
+
+    fn f32(&self, value: f32) -> usize;
+
+    fn f64(&self, value: f64) -> usize;
+
+    fn char(&self, value: char) -> usize {
+        self.str(value.encode_utf8(&mut [0; 4]))
+    }
+
+    fn str(&self, value: &str) -> usize;
+
+    fn bytes(&self, value: &[u8]) -> usize;
+
+    ///
+    /// The size of the tag of an optional value, excluding the value itself.
+    ///
+    fn option(&self, is_some: bool) -> usize;
+
+    ///
+    /// The size of the tag of an enum variant, excluding its content.
+    ///
+    fn variant(&self, variant_index: u32, variant: &'static str) -> usize;
+
+    ///
+    /// The size of the length prefix of a sequence or map. Returns an error if the format
+    /// requires the length, but it is not given.
+    ///
+    fn len(&self, len: Option<usize>) -> Result<usize, serde::de::value::Error>;
+
+    fn is_human_readable(&self) -> bool {
+        false
+    }
+}
+
+///
+/// The [`SizeRules`] of postcard, which encodes integers wider than one byte as varints,
+/// and prefixes strings, bytes, sequences and maps with their length.
+///
+#[derive(Clone, Copy, Debug, Default)]
+pub struct PostcardSizeRules;
+
+fn varint_size(value: u128) -> usize {
+    let bits = 128 - value.leading_zeros() as usize;
+    return bits.div_ceil(7).max(1);
+}
+
+impl SizeRules for PostcardSizeRules {
+
+    fn bool(&self, _value: bool) -> usize { 1 }
+    fn f32(&self, _value: f32) -> usize { 4 }
+    fn f64(&self, _value: f64) -> usize { 8 }
+    fn str(&self, value: &str) -> usize { varint_size(value.len() as u128) + value.len() }
+    fn bytes(&self, value: &[u8]) -> usize { varint_size(value.len() as u128) + value.len() }
+    fn option(&self, _is_some: bool) -> usize { 1 }
+    fn variant(&self, variant_index: u32, _variant: &'static str) -> usize { varint_size(variant_index as u128) }
+
+    fn signed(&self, value: i128, width: usize) -> usize {
+        if width == 1 {
+            return 1;
+        }
+        // zigzag encoding
+        let zigzag = ((value << 1) ^ (value >> 127)) as u128;
+        return varint_size(zigzag);
+    }
+
+    fn unsigned(&self, value: u128, width: usize) -> usize {
+        if width == 1 {
+            return 1;
+        }
+        return varint_size(value);
+    }
+
+    fn len(&self, len: Option<usize>) -> Result<usize, serde::de::value::Error> {
+        match len {
+            Some(len) => Ok(varint_size(len as u128)),
+            None => Err(Error::custom("postcard requires the length of sequences and maps"))
+        }
+    }
+}
+
+///
+/// Returns the number of bytes that `value` takes when serialized to postcard,
+/// see [`CountingSerializer`].
+///
+/// # Example
+/// ```
+/// # use feanor_serde::size::*;
+/// # use feanor_serde::seq::*;
+/// let data = [1i64, 300, -5];
+/// let value = SerializableSeq::new_with_len(data.iter(), data.len());
+/// let mut buffer = Vec::with_capacity(postcard_serialized_size(&value).unwrap());
+/// buffer.extend(postcard::to_allocvec(&value).unwrap());
+/// assert_eq!(buffer.capacity(), buffer.len());
+/// ```
+///
+pub fn postcard_serialized_size<T>(value: &T) -> Result<usize, serde::de::value::Error>
+    where T: ?Sized + Serialize
+{
+    value.serialize(CountingSerializer::new(&PostcardSizeRules))
+}
+
+///
+/// A [`Serializer`] that does not produce any output, but computes the number of bytes
+/// the serialized value would take in a format described by the given [`SizeRules`].
+/// This is cheaper than serializing the value, and allows to allocate buffers of exactly
+/// the right size.
+///
+#[derive(Debug)]
+pub struct CountingSerializer<'a, R: ?Sized> {
+    rules: &'a R
+}
+
+impl<'a, R: ?Sized> Clone for CountingSerializer<'a, R> {
+
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<'a, R: ?Sized> Copy for CountingSerializer<'a, R> {}
+
+impl<'a, R> CountingSerializer<'a, R>
+    where R: ?Sized + SizeRules
+{
+    pub fn new(rules: &'a R) -> Self {
+        Self { rules: rules }
+    }
+
+    fn compound(self, header: usize) -> Result<CountCompound<'a, R>, serde::de::value::Error> {
+        Ok(CountCompound { serializer: self, count: header })
+    }
+}
+
+impl<'a, R> Serializer for CountingSerializer<'a, R>
+    where R: ?Sized + SizeRules
+{
+    type Ok = usize;
+    type Error = serde::de::value::Error;
+    type SerializeSeq = CountCompound<'a, R>;
+    type SerializeTuple = CountCompound<'a, R>;
+    type SerializeTupleStruct = CountCompound<'a, R>;
+    type SerializeTupleVariant = CountCompound<'a, R>;
+    type SerializeMap = CountCompound<'a, R>;
+    type SerializeStruct = CountCompound<'a, R>;
+    type SerializeStructVariant = CountCompound<'a, R>;
+
+    fn serialize_bool(self, v: bool) -> Result<usize, Self::Error> { Ok(self.rules.bool(v)) }
+    fn serialize_i8(self, v: i8) -> Result<usize, Self::Error> { Ok(self.rules.signed(v as i128, 1)) }
+    fn serialize_i16(self, v: i16) -> Result<usize, Self::Error> { Ok(self.rules.signed(v as i128, 2)) }
+    fn serialize_i32(self, v: i32) -> Result<usize, Self::Error> { Ok(self.rules.signed(v as i128, 4)) }
+    fn serialize_i64(self, v: i64) -> Result<usize, Self::Error> { Ok(self.rules.signed(v as i128, 8)) }
+    fn serialize_i128(self, v: i128) -> Result<usize, Self::Error> { Ok(self.rules.signed(v, 16)) }
+    fn serialize_u8(self, v: u8) -> Result<usize, Self::Error> { Ok(self.rules.unsigned(v as u128, 1)) }
+    fn serialize_u16(self, v: u16) -> Result<usize, Self::Error> { Ok(self.rules.unsigned(v as u128, 2)) }
+    fn serialize_u32(self, v: u32) -> Result<usize, Self::Error> { Ok(self.rules.unsigned(v as u128, 4)) }
+    fn serialize_u64(self, v: u64) -> Result<usize, Self::Error> { Ok(self.rules.unsigned(v as u128, 8)) }
+    fn serialize_u128(self, v: u128) -> Result<usize, Self::Error> { Ok(self.rules.unsigned(v, 16)) }
+    fn serialize_f32(self, v: f32) -> Result<usize, Self::Error> { Ok(self.rules.f32(v)) }
+    fn serialize_f64(self, v: f64) -> Result<usize, Self::Error> { Ok(self.rules.f64(v)) }
+    fn serialize_char(self, v: char) -> Result<usize, Self::Error> { Ok(self.rules.char(v)) }
+    fn serialize_str(self, v: &str) -> Result<usize, Self::Error> { Ok(self.rules.str(v)) }
+    fn serialize_bytes(self, v: &[u8]) -> Result<usize, Self::Error> { Ok(self.rules.bytes(v)) }
+    fn serialize_none(self) -> Result<usize, Self::Error> { Ok(self.rules.option(false)) }
+    fn serialize_unit(self) -> Result<usize, Self::Error> { Ok(0) }
+    fn serialize_unit_struct(self, _name: &'static str) -> Result<usize, Self::Error> { Ok(0) }
+
+    fn serialize_some<T>(self, value: &T) -> Result<usize, Self::Error>
+        where T: ?Sized + Serialize
+    {
+        Ok(self.rules.option(true) + value.serialize(self)?)
+    }
+
+    fn serialize_unit_variant(self, _name: &'static str, variant_index: u32, variant: &'static str) -> Result<usize, Self::Error> {
+        Ok(self.rules.variant(variant_index, variant))
+    }
+
+    fn serialize_newtype_struct<T>(self, _name: &'static str, value: &T) -> Result<usize, Self::Error>
+        where T: ?Sized + Serialize
+    {
+        value.serialize(self)
+    }
+
+    fn serialize_newtype_variant<T>(self, _name: &'static str, variant_index: u32, variant: &'static str, value: &T) -> Result<usize, Self::Error>
+        where T: ?Sized + Serialize
+    {
+        Ok(self.rules.variant(variant_index, variant) + value.serialize(self)?)
+    }
+
+    fn serialize_seq(self, len: Option<usize>) -> Result<CountCompound<'a, R>, Self::Error> {
+        self.compound(self.rules.len(len)?)
+    }
+
+    fn serialize_tuple(self, _len: usize) -> Result<CountCompound<'a, R>, Self::Error> {
+        self.compound(0)
+    }
+
+    fn serialize_tuple_struct(self, _name: &'static str, _len: usize) -> Result<CountCompound<'a, R>, Self::Error> {
+        self.compound(0)
+    }
+
+    fn serialize_tuple_variant(self, _name: &'static str, variant_index: u32, variant: &'static str, _len: usize) -> Result<CountCompound<'a, R>, Self::Error> {
+        self.compound(self.rules.variant(variant_index, variant))
+    }
+
+    fn serialize_map(self, len: Option<usize>) -> Result<CountCompound<'a, R>, Self::Error> {
+        self.compound(self.rules.len(len)?)
+    }
+
+    fn serialize_struct(self, _name: &'static str, _len: usize) -> Result<CountCompound<'a, R>, Self::Error> {
+        self.compound(0)
+    }
+
+    fn serialize_struct_variant(self, _name: &'static str, variant_index: u32, variant: &'static str, _len: usize) -> Result<CountCompound<'a, R>, Self::Error> {
+        self.compound(self.rules.variant(variant_index, variant))
+    }
+
+    fn is_human_readable(&self) -> bool {
+        self.rules.is_human_readable()
+    }
+}
+
+///
+/// The compound serializer of [`CountingSerializer`], which sums up the sizes of all
+/// elements, fields or entries.
+///
+pub struct CountCompound<'a, R: ?Sized> {
+    serializer: CountingSerializer<'a, R>,
+    count: usize
+}
+
+impl<'a, R> CountCompound<'a, R>
+    where R: ?Sized + SizeRules
+{
+    fn add<T>(&mut self, value: &T) -> Result<(), serde::de::value::Error>
+        where T: ?Sized + Serialize
+    {
+        self.count += value.serialize(self.serializer)?;
+        return Ok(());
+    }
+}
+
+impl<'a, R> SerializeSeq for CountCompound<'a, R>
+    where R: ?Sized + SizeRules
+{
+    type Ok = usize;
+    type Error = serde::de::value::Error;
+
+    fn serialize_element<T>(&mut self, value: &T) -> Result<(), Self::Error>
+        where T: ?Sized + Serialize
+    {
+        self.add(value)
+    }
+
+    fn end(self) -> Result<usize, Self::Error> {
+        Ok(self.count)
+    }
+}
+
+impl<'a, R> SerializeTuple for CountCompound<'a, R>
+    where R: ?Sized + SizeRules
+{
+    type Ok = usize;
+    type Error = serde::de::value::Error;
+
+    fn serialize_element<T>(&mut self, value: &T) -> Result<(), Self::Error>
+        where T: ?Sized + Serialize
+    {
+        self.add(value)
+    }
+
+    fn end(self) -> Result<usize, Self::Error> {
+        Ok(self.count)
+    }
+}
+
+impl<'a, R> SerializeTupleStruct for CountCompound<'a, R>
+    where R: ?Sized + SizeRules
+{
+    type Ok = usize;
+    type Error = serde::de::value::Error;
+
+    fn serialize_field<T>(&mut self, value: &T) -> Result<(), Self::Error>
+        where T: ?Sized + Serialize
+    {
+        self.add(value)
+    }
+
+    fn end(self) -> Result<usize, Self::Error> {
+        Ok(self.count)
+    }
+}
+
+impl<'a, R> SerializeTupleVariant for CountCompound<'a, R>
+    where R: ?Sized + SizeRules
+{
+    type Ok = usize;
+    type Error = serde::de::value::Error;
+
+    fn serialize_field<T>(&mut self, value: &T) -> Result<(), Self::Error>
+        where T: ?Sized + Serialize
+    {
+        self.add(value)
+    }
+
+    fn end(self) -> Result<usize, Self::Error> {
+        Ok(self.count)
+    }
+}
+
+impl<'a, R> SerializeMap for CountCompound<'a, R>
+    where R: ?Sized + SizeRules
+{
+    type Ok = usize;
+    type Error = serde::de::value::Error;
+
+    fn serialize_key<T>(&mut self, key: &T) -> Result<(), Self::Error>
+        where T: ?Sized + Serialize
+    {
+        self.add(key)
+    }
+
+    fn serialize_value<T>(&mut self, value: &T) -> Result<(), Self::Error>
+        where T: ?Sized + Serialize
+    {
+        self.add(value)
+    }
+
+    fn end(self) -> Result<usize, Self::Error> {
+        Ok(self.count)
+    }
+}
+
+impl<'a, R> SerializeStruct for CountCompound<'a, R>
+    where R: ?Sized + SizeRules
+{
+    type Ok = usize;
+    type Error = serde::de::value::Error;
+
+    fn serialize_field<T>(&mut self, _key: &'static str, value: &T) -> Result<(), Self::Error>
+        where T: ?Sized + Serialize
+    {
+        self.add(value)
+    }
+
+    fn end(self) -> Result<usize, Self::Error> {
+        Ok(self.count)
+    }
+}
+
+impl<'a, R> SerializeStructVariant for CountCompound<'a, R>
+    where R: ?Sized + SizeRules
+{
+    type Ok = usize;
+    type Error = serde::de::value::Error;
+
+    fn serialize_field<T>(&mut self, _key: &'static str, value: &T) -> Result<(), Self::Error>
+        where T: ?Sized + Serialize
+    {
+        self.add(value)
+    }
+
+    fn end(self) -> Result<usize, Self::Error> {
+        Ok(self.count)
+    }
+}
+
+#[test]
+fn test_postcard_size() {
+    #[derive(serde::Serialize)]
+    enum Foo {
+        A,
+        B(i16, String),
+        C { x: Option<u32>, y: char }
+    }
+    fn check<T: ?Sized + Serialize>(value: &T) {
+        assert_eq!(postcard::to_allocvec(value).unwrap().len(), postcard_serialized_size(value).unwrap());
+    }
+    check(&(true, 0u8, 255u8, -128i8, 127u16, 128u16, u64::MAX, i64::MIN, -1i32, 64i32, -65i32));
+    check(&(u128::MAX, i128::MIN, 1.5f32, 2.5f64, 'ö', "a".repeat(200)));
+    check(&vec![Foo::A, Foo::B(-300, "abc".to_owned()), Foo::C { x: Some(70000), y: 'x' }, Foo::C { x: None, y: '€' }]);
+    check(&std::collections::BTreeMap::from([(1u32, vec![1u8; 130]), (2, Vec::new())]));
+    check(&((), Some(Some(())), None::<i8>));
+}
+
+#[test]
+fn test_unknown_len() {
+    struct UnknownLen;
+    impl Serialize for UnknownLen {
+        fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+            serializer.serialize_seq(None)?.end()
+        }
+    }
+    assert!(postcard_serialized_size(&UnknownLen).is_err());
+}
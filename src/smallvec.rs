@@ -0,0 +1,62 @@
+use serde::de::DeserializeSeed;
+
+use ::smallvec::{Array, SmallVec};
+
+use crate::seq::{SeqSeedBuilder, DeserializeSeedSeq};
+
+///
+/// The collector used by [`SeqSeedBuilder::collect_smallvec()`].
+///
+pub type PushToSmallVec<A> = fn(SmallVec<A>, <A as Array>::Item) -> SmallVec<A>;
+
+impl<'de, V, S> SeqSeedBuilder<V>
+    where V: Iterator<Item = S>,
+        S: DeserializeSeed<'de>
+{
+    ///
+    /// Builds a [`DeserializeSeedSeq`] that collects the elements into a [`SmallVec`].
+    /// Sequences that fit into the inline storage of the [`SmallVec`] are deserialized
+    /// without allocating.
+    ///
+    /// The initial capacity is chosen as for [`SeqSeedBuilder::collect_vec()`], thus if
+    /// it exceeds the inline capacity, the storage is allocated upfront. The storage is also
+    /// reserved as in [`SeqSeedBuilder::collect_vec()`] once the length of the sequence is known.
+    ///
+    /// # Example
+    /// ```
+    /// # use feanor_serde::seq::*;
+    /// # use feanor_serde::default_seed::seed_of;
+    /// # use serde::de::DeserializeSeed;
+    /// let seed = SeqSeedBuilder::new(seed_of::<i64>).collect_smallvec::<[i64; 4]>();
+    /// let result = seed.deserialize(&mut serde_json::Deserializer::from_str("[1, 3, 5]")).unwrap();
+    /// assert_eq!(&[1, 3, 5], &result[..]);
+    /// assert!(!result.spilled());
+    /// ```
+    ///
+    pub fn collect_smallvec<A>(self) -> DeserializeSeedSeq<V, S, SmallVec<A>, PushToSmallVec<A>>
+        where A: Array<Item = S::Value>
+    {
+        let capacity = self.initial_capacity();
+        return self.fold::<_, PushToSmallVec<_>>(SmallVec::with_capacity(capacity), |mut current, next| { current.push(next); current }).with_reserve(SmallVec::reserve);
+    }
+}
+
+#[cfg(test)]
+use crate::default_seed::seed_of;
+
+#[test]
+fn test_collect_smallvec() {
+    let deserialize = |seed: DeserializeSeedSeq<_, _, SmallVec<[i64; 2]>, _>, json: &str| seed.deserialize(&mut serde_json::Deserializer::from_str(json));
+
+    let result = deserialize(SeqSeedBuilder::new(seed_of::<i64>).collect_smallvec(), "[1, 2]").unwrap();
+    assert_eq!(&[1, 2], &result[..]);
+    assert!(!result.spilled());
+
+    let result = deserialize(SeqSeedBuilder::new(seed_of::<i64>).collect_smallvec(), "[1, 2, 3]").unwrap();
+    assert_eq!(&[1, 2, 3], &result[..]);
+    assert!(result.spilled());
+
+    let result = deserialize(SeqSeedBuilder::new(seed_of::<i64>).exact_len(3).collect_smallvec(), "[1, 2, 3]").unwrap();
+    assert_eq!(&[1, 2, 3], &result[..]);
+    assert!(deserialize(SeqSeedBuilder::new(seed_of::<i64>).max_len(2).collect_smallvec(), "[1, 2, 3]").is_err());
+}
@@ -0,0 +1,499 @@
+use serde::de::{DeserializeSeed, Error, SeqAccess, Visitor};
+use serde::ser::{Serialize, SerializeTuple, Serializer};
+use serde::Deserializer;
+
+use crate::seq::SerializableSeq;
+
+///
+/// Wraps an [`Iterator`] over the nonzero entries `(index, value)` of a vector of
+/// length `len`, and implements [`Serialize`] by mapping it to the tuple `(len, entries)`
+/// in the serde data model, where `entries` is a sequence of 2-tuples `(index, value)`.
+///
+/// The indices should be strictly increasing and smaller than `len`, since this
+/// is checked by [`DeserializeSeedSparseVector`].
+///
+/// # Example
+/// ```
+/// # use feanor_serde::sparse::*;
+/// let data = [0, 0, 3, 0, 0, 0, 1];
+/// let serializable = SerializableSparseVector::new(data.len(), data.iter().enumerate().filter(|(_, x)| **x != 0));
+/// assert_eq!("[7,[[2,3],[6,1]]]", serde_json::to_string(&serializable).unwrap());
+/// ```
+///
+pub struct SerializableSparseVector<I>
+    where I: Iterator + Clone
+{
+    len: usize,
+    entries: I
+}
+
+impl<I> SerializableSparseVector<I>
+    where I: Iterator + Clone
+{
+    pub const fn new(len: usize, entries: I) -> Self {
+        Self { len, entries }
+    }
+}
+
+impl<I, T> Serialize for SerializableSparseVector<I>
+    where I: Iterator<Item = (usize, T)> + Clone,
+        T: Serialize
+{
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+        where S: Serializer
+    {
+        let mut tuple = serializer.serialize_tuple(2)?;
+        tuple.serialize_element(&self.len)?;
+        // compute the number of entries in advance, since some formats require the length of sequences
+        tuple.serialize_element(&SerializableSeq::new_with_len(self.entries.clone(), self.entries.clone().count()))?;
+        return tuple.end();
+    }
+}
+
+///
+/// Wraps an [`Iterator`] over the nonzero entries `((row, col), value)` of a `rows x cols`
+/// matrix, and implements [`Serialize`] by mapping it to the tuple `(rows, cols, entries)`
+/// in the serde data model, where `entries` is a sequence of 2-tuples `(index, value)`, with
+/// `index = row * cols + col` being the position of the entry in row-major order.
+///
+/// The entries should be given in row-major order, since this is checked by
+/// [`DeserializeSeedSparseMatrix`].
+///
+pub struct SerializableSparseMatrix<I>
+    where I: Iterator + Clone
+{
+    rows: usize,
+    cols: usize,
+    entries: I
+}
+
+impl<I> SerializableSparseMatrix<I>
+    where I: Iterator + Clone
+{
+    pub const fn new(rows: usize, cols: usize, entries: I) -> Self {
+        Self { rows, cols, entries }
+    }
+}
+
+impl<I, T> Serialize for SerializableSparseMatrix<I>
+    where I: Iterator<Item = ((usize, usize), T)> + Clone,
+        T: Serialize
+{
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+        where S: Serializer
+    {
+        let cols = self.cols;
+        let mut tuple = serializer.serialize_tuple(3)?;
+        tuple.serialize_element(&self.rows)?;
+        tuple.serialize_element(&self.cols)?;
+        tuple.serialize_element(&SerializableSeq::new_with_len(self.entries.clone().map(move |((i, j), x)| (i * cols + j, x)), self.entries.clone().count()))?;
+        return tuple.end();
+    }
+}
+
+///
+/// Deserializes the sequence of entries, checking that the indices are strictly increasing
+/// and smaller than `len`. Since the value seed may depend on the index, the index of the next
+/// entry is unknown when its seed has to be created. Hence, the seed is derived from the index
+/// after deserializing the index, by deserializing each entry as a tuple manually.
+///
+pub(crate) struct DeserializeSeedEntries<'a, F, C, T> {
+    pub(crate) len: usize,
+    pub(crate) value_seed: &'a mut F,
+    pub(crate) collector: &'a mut C,
+    pub(crate) current: T
+}
+
+impl<'a, 'de, F, S, C, T> Visitor<'de> for DeserializeSeedEntries<'a, F, C, T>
+    where F: FnMut(usize) -> S,
+        S: DeserializeSeed<'de>,
+        C: FnMut(T, usize, S::Value) -> T
+{
+    type Value = T;
+
+    fn expecting(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "a sequence of entries (index, value) with strictly increasing indices smaller than {}", self.len)
+    }
+
+    fn visit_seq<A>(self, mut seq: A) -> Result<Self::Value, A::Error>
+        where A: SeqAccess<'de>
+    {
+        struct IndexedSeed<'b, F> {
+            value_seed: &'b mut F,
+            len: usize,
+            min_index: usize
+        }
+
+        impl<'b, 'de, F, S> DeserializeSeed<'de> for IndexedSeed<'b, F>
+            where F: FnMut(usize) -> S,
+                S: DeserializeSeed<'de>
+        {
+            type Value = (usize, S::Value);
+
+            fn deserialize<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+                where D: Deserializer<'de>
+            {
+                struct IndexedVisitor<'b, F> {
+                    value_seed: &'b mut F,
+                    len: usize,
+                    min_index: usize
+                }
+
+                impl<'b, 'de, F, S> Visitor<'de> for IndexedVisitor<'b, F>
+                    where F: FnMut(usize) -> S,
+                        S: DeserializeSeed<'de>
+                {
+                    type Value = (usize, S::Value);
+
+                    fn expecting(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+                        write!(f, "a tuple (index, value)")
+                    }
+
+                    fn visit_seq<A>(self, mut seq: A) -> Result<Self::Value, A::Error>
+                        where A: SeqAccess<'de>
+                    {
+                        let index: usize = seq.next_element()?.ok_or_else(|| <A::Error as Error>::invalid_length(0, &self))?;
+                        if index >= self.len {
+                            return Err(<A::Error as Error>::custom(format!("index {} out of range for length {}", index, self.len)));
+                        }
+                        if index < self.min_index {
+                            return Err(<A::Error as Error>::custom(format!("indices must be strictly increasing, but found {} after {}", index, self.min_index - 1)));
+                        }
+                        let value = seq.next_element_seed((self.value_seed)(index))?.ok_or_else(|| <A::Error as Error>::invalid_length(1, &self))?;
+                        return Ok((index, value));
+                    }
+                }
+
+                return deserializer.deserialize_tuple(2, IndexedVisitor { value_seed: self.value_seed, len: self.len, min_index: self.min_index });
+            }
+        }
+
+        let mut result = self.current;
+        let mut min_index = 0;
+        while let Some((index, value)) = seq.next_element_seed(IndexedSeed { value_seed: &mut *self.value_seed, len: self.len, min_index: min_index })? {
+            min_index = index + 1;
+            result = (self.collector)(result, index, value);
+        }
+        return Ok(result);
+    }
+}
+
+impl<'a, 'de, F, S, C, T> DeserializeSeed<'de> for DeserializeSeedEntries<'a, F, C, T>
+    where F: FnMut(usize) -> S,
+        S: DeserializeSeed<'de>,
+        C: FnMut(T, usize, S::Value) -> T
+{
+    type Value = T;
+
+    fn deserialize<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+        where D: Deserializer<'de>
+    {
+        deserializer.deserialize_seq(self)
+    }
+}
+
+///
+/// A [`DeserializeSeed`] that deserializes a sparse vector that was serialized using
+/// [`SerializableSparseVector`]. The value of the entry at index `i` is deserialized using
+/// the [`DeserializeSeed`] returned by `value_seed(i)`. The result is built by first calling
+/// `initial(len)`, and then passing each entry to `collector`.
+///
+/// Deserialization fails if the indices are not strictly increasing or not smaller than `len`.
+///
+/// # Example
+/// ```
+/// # use feanor_serde::sparse::*;
+/// # use feanor_serde::default_seed::seed_of;
+/// # use serde::de::DeserializeSeed;
+/// let mut deserializer = serde_json::Deserializer::new(serde_json::de::StrRead::new("[7,[[2,3],[6,1]]]"));
+/// let deserialize_seed = DeserializeSeedSparseVector::new(
+///     |_index| seed_of::<i64>(),
+///     |len| vec![0; len],
+///     |mut current, index, value| { current[index] = value; current }
+/// );
+/// assert_eq!(vec![0, 0, 3, 0, 0, 0, 1], deserialize_seed.deserialize(&mut deserializer).unwrap());
+/// ```
+///
+pub struct DeserializeSeedSparseVector<F, S, I, C, T>
+    where F: FnMut(usize) -> S,
+        I: FnOnce(usize) -> T
+{
+    value_seed: F,
+    initial: I,
+    collector: C
+}
+
+impl<'de, F, S, I, C, T> DeserializeSeedSparseVector<F, S, I, C, T>
+    where F: FnMut(usize) -> S,
+        S: DeserializeSeed<'de>,
+        I: FnOnce(usize) -> T,
+        C: FnMut(T, usize, S::Value) -> T
+{
+    pub const fn new(value_seed: F, initial: I, collector: C) -> Self {
+        Self { value_seed, initial, collector }
+    }
+}
+
+impl<'de, F, S, I, C, T> DeserializeSeed<'de> for DeserializeSeedSparseVector<F, S, I, C, T>
+    where F: FnMut(usize) -> S,
+        S: DeserializeSeed<'de>,
+        I: FnOnce(usize) -> T,
+        C: FnMut(T, usize, S::Value) -> T
+{
+    type Value = T;
+
+    fn deserialize<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+        where D: Deserializer<'de>
+    {
+        struct ResultVisitor<F, I, C> {
+            value_seed: F,
+            initial: I,
+            collector: C
+        }
+
+        impl<'de, F, S, I, C, T> Visitor<'de> for ResultVisitor<F, I, C>
+            where F: FnMut(usize) -> S,
+                S: DeserializeSeed<'de>,
+                I: FnOnce(usize) -> T,
+                C: FnMut(T, usize, S::Value) -> T
+        {
+            type Value = T;
+
+            fn expecting(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+                write!(f, "a tuple (len, entries)")
+            }
+
+            fn visit_seq<A>(mut self, mut seq: A) -> Result<Self::Value, A::Error>
+                where A: SeqAccess<'de>
+            {
+                let len: usize = seq.next_element()?.ok_or_else(|| <A::Error as Error>::invalid_length(0, &self))?;
+                let result = seq.next_element_seed(DeserializeSeedEntries {
+                    len: len,
+                    value_seed: &mut self.value_seed,
+                    collector: &mut self.collector,
+                    current: (self.initial)(len)
+                })?.ok_or_else(|| <A::Error as Error>::invalid_length(1, &"a tuple (len, entries)"))?;
+                return Ok(result);
+            }
+        }
+
+        return deserializer.deserialize_tuple(2, ResultVisitor {
+            value_seed: self.value_seed,
+            initial: self.initial,
+            collector: self.collector
+        });
+    }
+}
+
+///
+/// A [`DeserializeSeed`] that deserializes a sparse matrix that was serialized using
+/// [`SerializableSparseMatrix`]. The value of the entry at position `(i, j)` is deserialized
+/// using the [`DeserializeSeed`] returned by `value_seed(i, j)`. The result is built by first
+/// calling `initial(rows, cols)`, and then passing each entry to `collector`.
+///
+/// Deserialization fails if the entries are not in strictly increasing row-major order,
+/// or out of range.
+///
+/// # Example
+/// ```
+/// # use feanor_serde::sparse::*;
+/// # use feanor_serde::default_seed::seed_of;
+/// # use serde::de::DeserializeSeed;
+/// let mut deserializer = serde_json::Deserializer::new(serde_json::de::StrRead::new("[2,3,[[1,5],[3,7]]]"));
+/// let deserialize_seed = DeserializeSeedSparseMatrix::new(
+///     |_i, _j| seed_of::<i64>(),
+///     |rows, cols| vec![vec![0; cols]; rows],
+///     |mut current, (i, j), value| { current[i][j] = value; current }
+/// );
+/// assert_eq!(vec![vec![0, 5, 0], vec![7, 0, 0]], deserialize_seed.deserialize(&mut deserializer).unwrap());
+/// ```
+///
+pub struct DeserializeSeedSparseMatrix<F, S, I, C, T>
+    where F: FnMut(usize, usize) -> S,
+        I: FnOnce(usize, usize) -> T
+{
+    value_seed: F,
+    initial: I,
+    collector: C
+}
+
+impl<'de, F, S, I, C, T> DeserializeSeedSparseMatrix<F, S, I, C, T>
+    where F: FnMut(usize, usize) -> S,
+        S: DeserializeSeed<'de>,
+        I: FnOnce(usize, usize) -> T,
+        C: FnMut(T, (usize, usize), S::Value) -> T
+{
+    pub const fn new(value_seed: F, initial: I, collector: C) -> Self {
+        Self { value_seed, initial, collector }
+    }
+}
+
+impl<'de, F, S, I, C, T> DeserializeSeed<'de> for DeserializeSeedSparseMatrix<F, S, I, C, T>
+    where F: FnMut(usize, usize) -> S,
+        S: DeserializeSeed<'de>,
+        I: FnOnce(usize, usize) -> T,
+        C: FnMut(T, (usize, usize), S::Value) -> T
+{
+    type Value = T;
+
+    fn deserialize<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+        where D: Deserializer<'de>
+    {
+        struct ResultVisitor<F, I, C> {
+            value_seed: F,
+            initial: I,
+            collector: C
+        }
+
+        impl<'de, F, S, I, C, T> Visitor<'de> for ResultVisitor<F, I, C>
+            where F: FnMut(usize, usize) -> S,
+                S: DeserializeSeed<'de>,
+                I: FnOnce(usize, usize) -> T,
+                C: FnMut(T, (usize, usize), S::Value) -> T
+        {
+            type Value = T;
+
+            fn expecting(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+                write!(f, "a tuple (rows, cols, entries)")
+            }
+
+            fn visit_seq<A>(mut self, mut seq: A) -> Result<Self::Value, A::Error>
+                where A: SeqAccess<'de>
+            {
+                let rows: usize = seq.next_element()?.ok_or_else(|| <A::Error as Error>::invalid_length(0, &self))?;
+                let cols: usize = seq.next_element()?.ok_or_else(|| <A::Error as Error>::invalid_length(1, &self))?;
+                let len = rows.checked_mul(cols).ok_or_else(|| <A::Error as Error>::custom(format!("matrix dimensions {}x{} overflow usize", rows, cols)))?;
+                let value_seed = &mut self.value_seed;
+                let collector = &mut self.collector;
+                let result = seq.next_element_seed(DeserializeSeedEntries {
+                    len: len,
+                    value_seed: &mut |index| value_seed(index / cols, index % cols),
+                    collector: &mut |current, index, value| collector(current, (index / cols, index % cols), value),
+                    current: (self.initial)(rows, cols)
+                })?.ok_or_else(|| <A::Error as Error>::invalid_length(2, &"a tuple (rows, cols, entries)"))?;
+                return Ok(result);
+            }
+        }
+
+        return deserializer.deserialize_tuple(3, ResultVisitor {
+            value_seed: self.value_seed,
+            initial: self.initial,
+            collector: self.collector
+        });
+    }
+}
+
+#[cfg(test)]
+use std::marker::PhantomData;
+
+#[cfg(test)]
+fn testdata() -> Vec<Vec<i64>> {
+    vec![
+        vec![],
+        vec![0, 0, 0],
+        vec![1, 0, 2],
+        vec![0, 0, 0, 0, 5, 0, 0, 0, 0, 0, 0, 6]
+    ]
+}
+
+#[cfg(test)]
+fn deserialize_seed_testdata<'de>() -> impl DeserializeSeed<'de, Value = Vec<i64>> {
+    DeserializeSeedSparseVector::new(
+        |_| PhantomData::<i64>,
+        |len| vec![0; len],
+        |mut current, index, value| { current[index] = value; current }
+    )
+}
+
+#[test]
+fn test_serde_postcard() {
+    for data in testdata() {
+        let serialized = postcard::to_allocvec(&SerializableSparseVector::new(data.len(), data.iter().enumerate().filter(|(_, x)| **x != 0))).unwrap();
+        let result = deserialize_seed_testdata().deserialize(
+            &mut postcard::Deserializer::from_flavor(postcard::de_flavors::Slice::new(&serialized))
+        ).unwrap();
+        assert_eq!(data, result);
+    }
+}
+
+#[test]
+fn test_serde_json() {
+    for data in testdata() {
+        let serialized = serde_json::to_string(&SerializableSparseVector::new(data.len(), data.iter().enumerate().filter(|(_, x)| **x != 0))).unwrap();
+        let result = deserialize_seed_testdata().deserialize(
+            &mut serde_json::Deserializer::from_str(&serialized)
+        ).unwrap();
+        assert_eq!(data, result);
+    }
+}
+
+#[test]
+fn test_invalid_indices() {
+    assert!(deserialize_seed_testdata().deserialize(&mut serde_json::Deserializer::from_str("[3,[[3,1]]]")).is_err());
+    assert!(deserialize_seed_testdata().deserialize(&mut serde_json::Deserializer::from_str("[3,[[1,1],[1,1]]]")).is_err());
+    assert!(deserialize_seed_testdata().deserialize(&mut serde_json::Deserializer::from_str("[3,[[2,1],[1,1]]]")).is_err());
+    assert!(deserialize_seed_testdata().deserialize(&mut serde_json::Deserializer::from_str("[3,[[2]]]")).is_err());
+    assert_eq!(vec![0, 1, 1], deserialize_seed_testdata().deserialize(&mut serde_json::Deserializer::from_str("[3,[[1,1],[2,1]]]")).unwrap());
+}
+
+#[test]
+fn test_value_seed_index() {
+    struct DeserializeSeedOffset(i64);
+
+    impl<'de> DeserializeSeed<'de> for DeserializeSeedOffset {
+        type Value = i64;
+
+        fn deserialize<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+            where D: Deserializer<'de>
+        {
+            Ok(<i64 as serde::Deserialize>::deserialize(deserializer)? + self.0)
+        }
+    }
+
+    let result = DeserializeSeedSparseVector::new(
+        |index| DeserializeSeedOffset(index as i64 * 100),
+        |len| vec![0; len],
+        |mut current, index, value| { current[index] = value; current }
+    ).deserialize(&mut serde_json::Deserializer::from_str("[3,[[0,1],[2,1]]]")).unwrap();
+    assert_eq!(vec![1, 0, 201], result);
+}
+
+#[test]
+fn test_sparse_matrix_serde_postcard() {
+    let data = vec![vec![0, 5, 0], vec![7, 0, 0]];
+    let serialized = postcard::to_allocvec(&SerializableSparseMatrix::new(2, 3,
+        data.iter().enumerate().flat_map(|(i, row)| row.iter().enumerate().map(move |(j, x)| ((i, j), x))).filter(|(_, x)| **x != 0)
+    )).unwrap();
+    let result = DeserializeSeedSparseMatrix::new(
+        |_, _| PhantomData::<i64>,
+        |rows, cols| vec![vec![0; cols]; rows],
+        |mut current, (i, j), value| { current[i][j] = value; current }
+    ).deserialize(
+        &mut postcard::Deserializer::from_flavor(postcard::de_flavors::Slice::new(&serialized))
+    ).unwrap();
+    assert_eq!(data, result);
+}
+
+#[test]
+fn test_sparse_matrix_serde_json() {
+    let data = vec![vec![0, 5, 0], vec![7, 0, 0]];
+    let serialized = serde_json::to_string(&SerializableSparseMatrix::new(2, 3,
+        data.iter().enumerate().flat_map(|(i, row)| row.iter().enumerate().map(move |(j, x)| ((i, j), x))).filter(|(_, x)| **x != 0)
+    )).unwrap();
+    assert_eq!("[2,3,[[1,5],[3,7]]]", serialized);
+    let result = DeserializeSeedSparseMatrix::new(
+        |_, _| PhantomData::<i64>,
+        |rows, cols| vec![vec![0; cols]; rows],
+        |mut current, (i, j), value| { current[i][j] = value; current }
+    ).deserialize(
+        &mut serde_json::Deserializer::from_str(&serialized)
+    ).unwrap();
+    assert_eq!(data, result);
+
+    let result = DeserializeSeedSparseMatrix::new(
+        |_, _| PhantomData::<i64>,
+        |rows, cols| vec![vec![0; cols]; rows],
+        |mut current, (i, j), value| { current[i][j] = value; current }
+    ).deserialize(&mut serde_json::Deserializer::from_str("[2,3,[[6,1]]]"));
+    assert!(result.is_err());
+}
@@ -0,0 +1,235 @@
+use serde::de::DeserializeSeed;
+
+///
+/// A source of multiple consecutive top-level values, as used by [`SeededStreamDeserializer`].
+///
+/// This crate implements it for postcard (with feature `postcard`) via [`PostcardStream`],
+/// and for JSON (with feature `json`) via [`JsonStream`].
+///
+pub trait StreamSource<'de> {
+
+    type Error;
+
+    ///
+    /// Deserializes the next value using the given seed, or returns `None` if there are
+    /// no more values.
+    ///
+    fn next_seeded<S>(&mut self, seed: S) -> Option<Result<S::Value, Self::Error>>
+        where S: DeserializeSeed<'de>;
+}
+
+///
+/// A [`StreamSource`] reading consecutive postcard-encoded values from a byte slice.
+///
+#[cfg(feature = "postcard")]
+pub struct PostcardStream<'de> {
+    data: &'de [u8]
+}
+
+#[cfg(feature = "postcard")]
+impl<'de> PostcardStream<'de> {
+
+    pub fn new(data: &'de [u8]) -> Self {
+        Self { data }
+    }
+}
+
+#[cfg(feature = "postcard")]
+impl<'de> StreamSource<'de> for PostcardStream<'de> {
+
+    type Error = ::postcard::Error;
+
+    fn next_seeded<S>(&mut self, seed: S) -> Option<Result<S::Value, Self::Error>>
+        where S: DeserializeSeed<'de>
+    {
+        if self.data.is_empty() {
+            return None;
+        }
+        return Some(crate::postcard::take_from_slice_seeded(seed, self.data).map(|(result, rest)| {
+            self.data = rest;
+            result
+        }));
+    }
+}
+
+///
+/// A [`StreamSource`] reading consecutive JSON values from a string, which may be separated
+/// by whitespace, like for [`serde_json::StreamDeserializer`].
+///
+#[cfg(feature = "json")]
+pub struct JsonStream<'de> {
+    data: &'de str
+}
+
+#[cfg(feature = "json")]
+impl<'de> JsonStream<'de> {
+
+    pub fn new(data: &'de str) -> Self {
+        Self { data }
+    }
+}
+
+#[cfg(feature = "json")]
+impl<'de> StreamSource<'de> for JsonStream<'de> {
+
+    type Error = serde_json::Error;
+
+    fn next_seeded<S>(&mut self, seed: S) -> Option<Result<S::Value, Self::Error>>
+        where S: DeserializeSeed<'de>
+    {
+        let data = self.data.trim_start();
+        // serde_json does not expose the position of a Deserializer, so we first find the
+        // end of the next value using a StreamDeserializer
+        let mut values = serde_json::Deserializer::from_str(data).into_iter::<serde::de::IgnoredAny>();
+        if let Err(e) = values.next()? {
+            return Some(Err(e));
+        }
+        let (current, rest) = data.split_at(values.byte_offset());
+        self.data = rest;
+        return Some(seed.deserialize(&mut serde_json::Deserializer::from_str(current)));
+    }
+}
+
+///
+/// An [`Iterator`] over the consecutive top-level values of a [`StreamSource`], each of
+/// which is deserialized with its own [`DeserializeSeed`]. The seeds are produced by a
+/// factory, which gets the index of the current value.
+///
+/// Since the factory is only called when the previous value has been deserialized, it can
+/// share state with the seeds, so that later values can depend on earlier ones. After the
+/// first error, the iterator returns `None`.
+///
+/// This is the analogue of [`serde_json::StreamDeserializer`] for [`DeserializeSeed`]s.
+///
+/// # Example
+/// ```
+/// # use feanor_serde::stream::*;
+/// # use feanor_serde::seq::*;
+/// # use feanor_serde::default_seed::seed_of;
+/// # use std::iter::repeat;
+/// # #[cfg(feature = "postcard")] {
+/// let data = feanor_serde::postcard::to_allocvec(&(vec![1, 2], vec![1, 2, 3])).unwrap();
+/// let scaled_seq = |i: usize| DeserializeSeedSeq::new(
+///     repeat(seed_of::<i64>()),
+///     Vec::new(),
+///     move |mut current, next| { current.push(next * (i as i64 + 1)); current }
+/// );
+/// let values = SeededStreamDeserializer::new(PostcardStream::new(&data), scaled_seq).collect::<Result<Vec<_>, _>>().unwrap();
+/// assert_eq!(vec![vec![1, 2], vec![2, 4, 6]], values);
+/// # }
+/// ```
+///
+pub struct SeededStreamDeserializer<'de, R, F, S>
+    where R: StreamSource<'de>,
+        F: FnMut(usize) -> S,
+        S: DeserializeSeed<'de>
+{
+    source: R,
+    seeds: F,
+    index: usize,
+    failed: bool,
+    deserializer: std::marker::PhantomData<&'de ()>
+}
+
+impl<'de, R, F, S> SeededStreamDeserializer<'de, R, F, S>
+    where R: StreamSource<'de>,
+        F: FnMut(usize) -> S,
+        S: DeserializeSeed<'de>
+{
+    pub fn new(source: R, seeds: F) -> Self {
+        Self { source, seeds, index: 0, failed: false, deserializer: std::marker::PhantomData }
+    }
+
+    ///
+    /// Returns the number of values that have been deserialized successfully so far.
+    ///
+    pub fn index(&self) -> usize {
+        self.index
+    }
+
+    pub fn into_source(self) -> R {
+        self.source
+    }
+}
+
+impl<'de, R, F, S> Iterator for SeededStreamDeserializer<'de, R, F, S>
+    where R: StreamSource<'de>,
+        F: FnMut(usize) -> S,
+        S: DeserializeSeed<'de>
+{
+    type Item = Result<S::Value, R::Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.failed {
+            return None;
+        }
+        let result = self.source.next_seeded((self.seeds)(self.index))?;
+        match &result {
+            Ok(_) => self.index += 1,
+            Err(_) => self.failed = true
+        }
+        return Some(result);
+    }
+}
+
+#[cfg(all(test, any(feature = "postcard", feature = "json")))]
+use std::marker::PhantomData;
+#[cfg(all(test, feature = "postcard"))]
+use std::cell::Cell;
+
+///
+/// The first value is the modulus, and all following values are reduced by it.
+///
+#[cfg(all(test, feature = "postcard"))]
+struct DeserializeSeedReduced<'a> {
+    modulus: &'a Cell<Option<u64>>
+}
+
+#[cfg(all(test, feature = "postcard"))]
+impl<'a, 'de> DeserializeSeed<'de> for DeserializeSeedReduced<'a> {
+
+    type Value = u64;
+
+    fn deserialize<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+        where D: serde::Deserializer<'de>
+    {
+        let value = <u64 as serde::Deserialize>::deserialize(deserializer)?;
+        match self.modulus.get() {
+            Some(modulus) => Ok(value % modulus),
+            None => { self.modulus.set(Some(value)); Ok(value) }
+        }
+    }
+}
+
+#[cfg(feature = "postcard")]
+#[test]
+fn test_postcard_stream() {
+    let data = postcard::to_allocvec(&(7u64, 5u64, 10u64, 100u64)).unwrap();
+    let modulus = Cell::new(None);
+    let mut stream = SeededStreamDeserializer::new(PostcardStream::new(&data), |_| DeserializeSeedReduced { modulus: &modulus });
+    assert_eq!(vec![7, 5, 3, 2], (&mut stream).collect::<Result<Vec<_>, _>>().unwrap());
+    assert!(stream.next().is_none());
+    assert_eq!(4, stream.index());
+
+    let mut stream = SeededStreamDeserializer::new(PostcardStream::new(&[1, 2]), |_| PhantomData::<bool>);
+    assert!(stream.next().unwrap().unwrap());
+    assert!(stream.next().unwrap().is_err());
+    assert!(stream.next().is_none());
+    assert_eq!(1, stream.index());
+}
+
+#[cfg(feature = "json")]
+#[test]
+fn test_json_stream() {
+    let values = SeededStreamDeserializer::new(JsonStream::new(" 1 [2, 3]\n\"a\" "), |_| PhantomData::<serde_json::Value>).collect::<Result<Vec<_>, _>>().unwrap();
+    assert_eq!(vec![serde_json::json!(1), serde_json::json!([2, 3]), serde_json::json!("a")], values);
+
+    let mut stream = SeededStreamDeserializer::new(JsonStream::new("{\"a\":1}{\"a\":\"b\"}"), |i| crate::ext::DeserializeSeedExt::context(PhantomData::<std::collections::HashMap<String, i32>>, format!("document {}", i)));
+    assert_eq!(1, stream.next().unwrap().unwrap()["a"]);
+    assert!(stream.next().unwrap().unwrap_err().to_string().starts_with("document 1: invalid type"));
+
+    let mut stream = SeededStreamDeserializer::new(JsonStream::new("1 [2"), |_| PhantomData::<serde_json::Value>);
+    assert!(stream.next().unwrap().is_ok());
+    assert!(stream.next().unwrap().is_err());
+    assert!(stream.next().is_none());
+}
@@ -0,0 +1,388 @@
+use std::marker::PhantomData;
+
+use serde::de::{DeserializeSeed, Error, MapAccess, SeqAccess, Visitor};
+use serde::Deserializer;
+
+use crate::into_seed::IntoSeed;
+use crate::rust_struct::MissingFieldDeserializer;
+
+///
+/// A field of a [`StructSeed`], consisting of its name and the [`DeserializeSeed`] for its value.
+///
+#[derive(Clone, Copy, Debug)]
+pub struct Field<S> {
+    name: &'static str,
+    seed: S
+}
+
+///
+/// Implemented by the typestates of a [`StructSeed`] with at least one field, i.e. by
+/// [`Field`] and by pairs `(L, Field<S>)` with `L: FieldList`.
+///
+pub trait FieldList {}
+
+impl<S> FieldList for Field<S> {}
+
+impl<L, S> FieldList for (L, Field<S>)
+    where L: FieldList
+{}
+
+///
+/// The state of a single field during deserialization of a [`DeserializeSeedStruct`].
+///
+pub struct FieldState<S, V> {
+    name: &'static str,
+    seed: Option<S>,
+    value: Option<V>
+}
+
+///
+/// The fields of a [`DeserializeSeedStruct`]. This is implemented for [`Field`], whose value
+/// is the value of the field, and for pairs `(L, Field<S>)`, whose value is the pair of values,
+/// i.e. the values of `a`, `b` and `c` are given as `((a, b), c)`.
+///
+pub trait StructFields<'de>: FieldList {
+
+    type Value;
+    type State;
+
+    const LEN: usize;
+
+    fn into_state(self) -> Self::State;
+
+    fn index_of(state: &Self::State, name: &str) -> Option<usize>;
+
+    fn deserialize_field<A>(state: &mut Self::State, index: usize, map: &mut A) -> Result<(), A::Error>
+        where A: MapAccess<'de>;
+
+    fn finish_state<E>(state: Self::State) -> Result<Self::Value, E>
+        where E: Error;
+
+    fn visit_seq<A>(self, seq: &mut A, encountered: &mut usize, struct_name: &'static str) -> Result<Self::Value, A::Error>
+        where A: SeqAccess<'de>;
+}
+
+impl<'de, S> StructFields<'de> for Field<S>
+    where S: DeserializeSeed<'de>
+{
+    type Value = S::Value;
+    type State = FieldState<S, S::Value>;
+
+    const LEN: usize = 1;
+
+    fn into_state(self) -> Self::State {
+        FieldState { name: self.name, seed: Some(self.seed), value: None }
+    }
+
+    fn index_of(state: &Self::State, name: &str) -> Option<usize> {
+        if state.name == name { Some(0) } else { None }
+    }
+
+    fn deserialize_field<A>(state: &mut Self::State, _index: usize, map: &mut A) -> Result<(), A::Error>
+        where A: MapAccess<'de>
+    {
+        let Some(seed) = state.seed.take() else {
+            return Err(A::Error::duplicate_field(state.name));
+        };
+        state.value = Some(map.next_value_seed(seed)?);
+        return Ok(());
+    }
+
+    fn finish_state<E>(state: Self::State) -> Result<Self::Value, E>
+        where E: Error
+    {
+        match (state.value, state.seed) {
+            (Some(value), _) => Ok(value),
+            (None, Some(seed)) => seed.deserialize(MissingFieldDeserializer::<E>::new(state.name)),
+            (None, None) => unreachable!()
+        }
+    }
+
+    fn visit_seq<A>(self, seq: &mut A, encountered: &mut usize, struct_name: &'static str) -> Result<Self::Value, A::Error>
+        where A: SeqAccess<'de>
+    {
+        match seq.next_element_seed(self.seed)? {
+            Some(value) => {
+                *encountered += 1;
+                return Ok(value);
+            },
+            None => Err(crate::errors::invalid_length(*encountered, "a struct with more fields", format_args!("struct {} with more than {} elements", struct_name, *encountered)))
+        }
+    }
+}
+
+impl<'de, L, S> StructFields<'de> for (L, Field<S>)
+    where L: StructFields<'de>,
+        S: DeserializeSeed<'de>
+{
+    type Value = (L::Value, S::Value);
+    type State = (L::State, FieldState<S, S::Value>);
+
+    const LEN: usize = L::LEN + 1;
+
+    fn into_state(self) -> Self::State {
+        (self.0.into_state(), self.1.into_state())
+    }
+
+    fn index_of(state: &Self::State, name: &str) -> Option<usize> {
+        L::index_of(&state.0, name).or_else(|| Field::<S>::index_of(&state.1, name).map(|_| L::LEN))
+    }
+
+    fn deserialize_field<A>(state: &mut Self::State, index: usize, map: &mut A) -> Result<(), A::Error>
+        where A: MapAccess<'de>
+    {
+        if index < L::LEN {
+            L::deserialize_field(&mut state.0, index, map)
+        } else {
+            Field::<S>::deserialize_field(&mut state.1, 0, map)
+        }
+    }
+
+    fn finish_state<E>(state: Self::State) -> Result<Self::Value, E>
+        where E: Error
+    {
+        Ok((L::finish_state(state.0)?, Field::<S>::finish_state(state.1)?))
+    }
+
+    fn visit_seq<A>(self, seq: &mut A, encountered: &mut usize, struct_name: &'static str) -> Result<Self::Value, A::Error>
+        where A: SeqAccess<'de>
+    {
+        let first = self.0.visit_seq(seq, encountered, struct_name)?;
+        let last = self.1.visit_seq(seq, encountered, struct_name)?;
+        return Ok((first, last));
+    }
+}
+
+///
+/// Builder for a [`DeserializeSeedStruct`], which is a non-macro alternative to
+/// [`crate::impl_deserialize_seed_for_dependent_struct!`].
+///
+/// The fields added via [`StructSeed::field()`] are stored in the type of the builder, so
+/// the result is fully typed, and mistakes lead to ordinary type errors. The values of the
+/// fields are returned as nested pairs, e.g. `((a, b), c)`, or passed to the closure given
+/// to [`StructSeed::finish_with()`].
+///
+/// As for the macro, fields may be identified by their name or by their index, missing fields
+/// are deserialized from a [`MissingFieldDeserializer`], and structs may be given as sequences.
+/// However, since the field names are only known at runtime, the struct is requested from the
+/// deserializer as a map if it is human-readable, and as a tuple otherwise. This matches the
+/// representation of structs in most formats, e.g. JSON and postcard, but means that JSON input
+/// must be a map.
+///
+/// # Example
+/// ```
+/// # use feanor_serde::struct_seed::*;
+/// # use feanor_serde::default_seed::seed_of;
+/// # use serde::de::DeserializeSeed;
+/// struct Foo {
+///     a: i64,
+///     b: String,
+///     c: Option<bool>
+/// }
+/// let seed = StructSeed::new("Foo")
+///     .field("a", seed_of::<i64>())
+///     .field("b", seed_of::<String>())
+///     .field("c", seed_of::<Option<bool>>())
+///     .finish_with(|((a, b), c)| Foo { a, b, c });
+/// let foo = seed.deserialize(&mut serde_json::Deserializer::from_str(r#"{ "b": "x", "a": 1 }"#)).unwrap();
+/// assert_eq!((1, "x", None), (foo.a, foo.b.as_str(), foo.c));
+/// ```
+///
+#[derive(Clone, Copy, Debug)]
+pub struct StructSeed<F> {
+    name: &'static str,
+    fields: F
+}
+
+impl StructSeed<()> {
+
+    pub const fn new(name: &'static str) -> Self {
+        Self { name: name, fields: () }
+    }
+
+    pub fn field<'de, I, M, S>(self, name: &'static str, seed: I) -> StructSeed<Field<S>>
+        where I: IntoSeed<'de, M, Seed = S>
+    {
+        StructSeed { name: self.name, fields: Field { name: name, seed: seed.into_seed() } }
+    }
+}
+
+impl<F> StructSeed<F>
+    where F: FieldList
+{
+    pub fn field<'de, I, M, S>(self, name: &'static str, seed: I) -> StructSeed<(F, Field<S>)>
+        where I: IntoSeed<'de, M, Seed = S>
+    {
+        StructSeed { name: self.name, fields: (self.fields, Field { name: name, seed: seed.into_seed() }) }
+    }
+
+    ///
+    /// Builds a [`DeserializeSeedStruct`] that returns the values of the fields as nested pairs.
+    ///
+    #[allow(clippy::type_complexity)]
+    pub fn finish<'de>(self) -> DeserializeSeedStruct<F, fn(F::Value) -> F::Value>
+        where F: StructFields<'de>
+    {
+        self.finish_with(|value| value)
+    }
+
+    ///
+    /// Builds a [`DeserializeSeedStruct`] that passes the values of the fields, as nested pairs,
+    /// to the given function, e.g. to construct a user-defined struct.
+    ///
+    pub fn finish_with<M>(self, map: M) -> DeserializeSeedStruct<F, M> {
+        DeserializeSeedStruct { name: self.name, fields: self.fields, map: map }
+    }
+}
+
+///
+/// A [`DeserializeSeed`] for a struct, built using [`StructSeed`].
+///
+#[derive(Clone, Copy, Debug)]
+pub struct DeserializeSeedStruct<F, M> {
+    name: &'static str,
+    fields: F,
+    map: M
+}
+
+impl<'de, F, M, T> DeserializeSeed<'de> for DeserializeSeedStruct<F, M>
+    where F: StructFields<'de>,
+        M: FnOnce(F::Value) -> T
+{
+    type Value = T;
+
+    fn deserialize<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+        where D: Deserializer<'de>
+    {
+        enum Key {
+            Index(u64),
+            Name(String)
+        }
+
+        struct KeyVisitor;
+
+        impl<'de> Visitor<'de> for KeyVisitor {
+            type Value = Key;
+
+            fn expecting(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+                write!(f, "field identifier")
+            }
+
+            fn visit_u64<E>(self, value: u64) -> Result<Self::Value, E> {
+                Ok(Key::Index(value))
+            }
+
+            fn visit_str<E>(self, value: &str) -> Result<Self::Value, E> {
+                Ok(Key::Name(value.to_owned()))
+            }
+
+            fn visit_bytes<E>(self, value: &[u8]) -> Result<Self::Value, E> {
+                Ok(Key::Name(String::from_utf8_lossy(value).into_owned()))
+            }
+        }
+
+        struct KeySeed;
+
+        impl<'de> DeserializeSeed<'de> for KeySeed {
+            type Value = Key;
+
+            fn deserialize<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+                where D: Deserializer<'de>
+            {
+                deserializer.deserialize_identifier(KeyVisitor)
+            }
+        }
+
+        struct ResultVisitor<'de, F> {
+            name: &'static str,
+            fields: F,
+            deserializer: PhantomData<&'de ()>
+        }
+
+        impl<'de, F> Visitor<'de> for ResultVisitor<'de, F>
+            where F: StructFields<'de>
+        {
+            type Value = F::Value;
+
+            fn expecting(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+                write!(f, "struct {}", self.name)
+            }
+
+            fn visit_seq<A>(self, mut seq: A) -> Result<Self::Value, A::Error>
+                where A: SeqAccess<'de>
+            {
+                self.fields.visit_seq(&mut seq, &mut 0, self.name)
+            }
+
+            fn visit_map<A>(self, mut map: A) -> Result<Self::Value, A::Error>
+                where A: MapAccess<'de>
+            {
+                let mut state = self.fields.into_state();
+                while let Some(key) = map.next_key_seed(KeySeed)? {
+                    let index = match key {
+                        Key::Index(index) if index < F::LEN as u64 => index as usize,
+                        Key::Index(index) => return Err(crate::errors::custom("unknown field", format_args!("unknown field with index {}", index))),
+                        Key::Name(name) => match F::index_of(&state, &name) {
+                            Some(index) => index,
+                            None => return Err(crate::errors::custom("unknown field", format_args!("unknown field `{}`", name)))
+                        }
+                    };
+                    F::deserialize_field(&mut state, index, &mut map)?;
+                }
+                return F::finish_state(state);
+            }
+        }
+
+        let visitor = ResultVisitor { name: self.name, fields: self.fields, deserializer: PhantomData };
+        let value = if deserializer.is_human_readable() {
+            deserializer.deserialize_map(visitor)?
+        } else {
+            deserializer.deserialize_tuple(F::LEN, visitor)?
+        };
+        return Ok((self.map)(value));
+    }
+}
+
+#[cfg(test)]
+use crate::default_seed::seed_of;
+#[cfg(test)]
+use crate::mock::{MockDeserializer, MockValue};
+
+#[cfg(test)]
+#[derive(serde::Serialize)]
+struct Foo {
+    a: i64,
+    b: Option<String>,
+    c: Vec<u8>
+}
+
+#[test]
+fn test_serde_json() {
+    let seed = || StructSeed::new("Foo").field("a", seed_of::<i64>()).field("b", seed_of::<Option<String>>()).field("c", seed_of::<Vec<u8>>()).finish();
+    let serialized = serde_json::to_string(&Foo { a: 1, b: Some("x".to_owned()), c: vec![2] }).unwrap();
+    assert_eq!(((1, Some("x".to_owned())), vec![2]), seed().deserialize(&mut serde_json::Deserializer::from_str(&serialized)).unwrap());
+    assert_eq!(((1, None), vec![]), seed().deserialize(&mut serde_json::Deserializer::from_str(r#"{"c": [], "a": 1}"#)).unwrap());
+
+    assert!(seed().deserialize(&mut serde_json::Deserializer::from_str(r#"{"a": 1, "c": []}"#)).is_ok());
+    assert!(seed().deserialize(&mut serde_json::Deserializer::from_str(r#"{"b": "x", "c": []}"#)).unwrap_err().to_string().starts_with("missing field `a`"));
+    assert!(seed().deserialize(&mut serde_json::Deserializer::from_str(r#"{"a": 1, "a": 2, "c": []}"#)).unwrap_err().to_string().starts_with("duplicate field `a`"));
+    assert!(seed().deserialize(&mut serde_json::Deserializer::from_str(r#"{"a": 1, "d": 2, "c": []}"#)).is_err());
+
+    let single = StructSeed::new("Bar").field("x", seed_of::<bool>()).finish_with(|x: bool| !x);
+    assert!(!single.deserialize(&mut serde_json::Deserializer::from_str(r#"{"x": true}"#)).unwrap());
+}
+
+#[test]
+fn test_serde_postcard() {
+    let seed = StructSeed::new("Foo").field("a", seed_of::<i64>()).field("b", seed_of::<Option<String>>()).field("c", seed_of::<Vec<u8>>()).finish();
+    let serialized = postcard::to_allocvec(&Foo { a: -1, b: None, c: vec![1, 2] }).unwrap();
+    assert_eq!(((-1, None), vec![1, 2]), seed.deserialize(&mut postcard::Deserializer::from_bytes(&serialized)).unwrap());
+}
+
+#[test]
+fn test_field_indices() {
+    let seed = StructSeed::new("Foo").field("a", seed_of::<i64>()).field("b", seed_of::<Option<String>>()).finish();
+    let value = MockValue::map([(MockValue::from(1u64), MockValue::some("y")), (MockValue::from(0u64), MockValue::from(3i64))]);
+    assert_eq!((3, Some("y".to_owned())), seed.deserialize(MockDeserializer::new(value)).unwrap());
+    assert!(seed.deserialize(MockDeserializer::new(MockValue::map([(2u64, 1i64)]))).is_err());
+}
@@ -0,0 +1,500 @@
+use std::cell::RefCell;
+
+use serde::ser::*;
+
+///
+/// Serializes `value` to both serializers at once, and returns the output of `primary`.
+/// See [`TeeSerializer`] for details.
+///
+/// # Example
+/// ```
+/// # use feanor_serde::tee::*;
+/// # use postcard::ser_flavors::Flavor;
+/// let value = vec![(1, "a"), (2, "b")];
+/// let mut json = Vec::new();
+/// let mut postcard = postcard::Serializer { output: postcard::ser_flavors::AllocVec::new() };
+/// serialize_tee(&value, &mut postcard, &mut serde_json::Serializer::new(&mut json)).unwrap();
+/// assert_eq!(postcard::to_allocvec(&value).unwrap(), postcard.output.finalize().unwrap());
+/// assert_eq!(r#"[[1,"a"],[2,"b"]]"#, String::from_utf8(json).unwrap());
+/// ```
+///
+pub fn serialize_tee<T, S1, S2>(value: &T, primary: S1, secondary: S2) -> Result<S1::Ok, S1::Error>
+    where T: ?Sized + Serialize,
+        S1: Serializer,
+        S2: Serializer
+{
+    value.serialize(TeeSerializer::new(primary, secondary)).map(|(result, _)| result)
+}
+
+///
+/// A [`Serializer`] that forwards every call to two serializers, thus produces two
+/// serialized copies while serializing the value only once. This is useful when e.g.
+/// the value is expensive to compute during serialization.
+///
+/// The first serializer is the primary one, i.e. it is used for [`Serializer::is_human_readable()`],
+/// and errors of the second one are converted into errors of the first one.
+///
+pub struct TeeSerializer<S1, S2> {
+    first: S1,
+    second: S2
+}
+
+impl<S1, S2> TeeSerializer<S1, S2>
+    where S1: Serializer,
+        S2: Serializer
+{
+    pub fn new(first: S1, second: S2) -> Self {
+        Self { first: first, second: second }
+    }
+}
+
+fn convert_error<E1, E2>(error: E2) -> E1
+    where E1: Error,
+        E2: Error
+{
+    E1::custom(error)
+}
+
+///
+/// A single call that passes a nested value to a serializer, e.g. [`Serializer::serialize_some()`]
+/// or [`SerializeSeq::serialize_element()`].
+///
+trait Forward {
+    type Ok;
+    type Error: Error;
+
+    fn forward<T>(self, value: &T) -> Result<Self::Ok, Self::Error>
+        where T: ?Sized + Serialize;
+}
+
+///
+/// Passes `value` to `first`, and within that, to `second`, such that the nested serializers
+/// of both can be combined to a [`TeeSerializer`]. Thus `value` is serialized only once.
+///
+fn forward_both<T, F1, F2>(value: &T, first: F1, second: F2) -> Result<(F1::Ok, F2::Ok), F1::Error>
+    where T: ?Sized + Serialize,
+        F1: Forward,
+        F2: Forward
+{
+    let outer = Outer { value: value, second: RefCell::new(Some(second)), second_result: RefCell::new(None) };
+    let first_result = first.forward(&outer)?;
+    let second_result = outer.second_result.take().ok_or_else(|| Error::custom("value was not serialized by the primary serializer"))?;
+    return Ok((first_result, second_result));
+}
+
+struct Outer<'a, T, F2>
+    where T: ?Sized,
+        F2: Forward
+{
+    value: &'a T,
+    second: RefCell<Option<F2>>,
+    second_result: RefCell<Option<F2::Ok>>
+}
+
+impl<'a, T, F2> Serialize for Outer<'a, T, F2>
+    where T: ?Sized + Serialize,
+        F2: Forward
+{
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+        where S: Serializer
+    {
+        let second = self.second.take().ok_or_else(|| Error::custom("value was serialized twice by the primary serializer"))?;
+        let inner = Inner::<T, S> { value: self.value, first: RefCell::new(Some(serializer)), first_result: RefCell::new(None) };
+        let second_result = second.forward(&inner);
+        return match (inner.first_result.take(), second_result) {
+            (Some(Err(e)), _) => Err(e),
+            (Some(Ok(first_result)), Ok(second_result)) => {
+                *self.second_result.borrow_mut() = Some(second_result);
+                Ok(first_result)
+            },
+            (_, Err(e)) => Err(convert_error(e)),
+            (None, Ok(_)) => Err(Error::custom("value was not serialized by the secondary serializer"))
+        };
+    }
+}
+
+struct Inner<'a, T, S1>
+    where T: ?Sized,
+        S1: Serializer
+{
+    value: &'a T,
+    first: RefCell<Option<S1>>,
+    first_result: RefCell<Option<Result<S1::Ok, S1::Error>>>
+}
+
+impl<'a, T, S1> Serialize for Inner<'a, T, S1>
+    where T: ?Sized + Serialize,
+        S1: Serializer
+{
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+        where S: Serializer
+    {
+        let first = self.first.take().ok_or_else(|| Error::custom("value was serialized twice by the secondary serializer"))?;
+        return match self.value.serialize(TeeSerializer::new(first, serializer)) {
+            Ok((first_result, second_result)) => {
+                *self.first_result.borrow_mut() = Some(Ok(first_result));
+                Ok(second_result)
+            },
+            Err(e) => {
+                let error = Error::custom(&e);
+                *self.first_result.borrow_mut() = Some(Err(e));
+                Err(error)
+            }
+        };
+    }
+}
+
+struct ForwardSome<S>(S);
+
+impl<S> Forward for ForwardSome<S>
+    where S: Serializer
+{
+    type Ok = S::Ok;
+    type Error = S::Error;
+
+    fn forward<T>(self, value: &T) -> Result<S::Ok, S::Error>
+        where T: ?Sized + Serialize
+    {
+        self.0.serialize_some(value)
+    }
+}
+
+struct ForwardNewtypeStruct<S>(S, &'static str);
+
+impl<S> Forward for ForwardNewtypeStruct<S>
+    where S: Serializer
+{
+    type Ok = S::Ok;
+    type Error = S::Error;
+
+    fn forward<T>(self, value: &T) -> Result<S::Ok, S::Error>
+        where T: ?Sized + Serialize
+    {
+        self.0.serialize_newtype_struct(self.1, value)
+    }
+}
+
+struct ForwardNewtypeVariant<S>(S, &'static str, u32, &'static str);
+
+impl<S> Forward for ForwardNewtypeVariant<S>
+    where S: Serializer
+{
+    type Ok = S::Ok;
+    type Error = S::Error;
+
+    fn forward<T>(self, value: &T) -> Result<S::Ok, S::Error>
+        where T: ?Sized + Serialize
+    {
+        self.0.serialize_newtype_variant(self.1, self.2, self.3, value)
+    }
+}
+
+macro_rules! forward_compound {
+    ($name:ident, $trait:ident, $method:ident) => {
+        struct $name<'b, C>(&'b mut C);
+
+        impl<'b, C> Forward for $name<'b, C>
+            where C: $trait
+        {
+            type Ok = ();
+            type Error = C::Error;
+
+            fn forward<T>(self, value: &T) -> Result<(), C::Error>
+                where T: ?Sized + Serialize
+            {
+                self.0.$method(value)
+            }
+        }
+    };
+    ($name:ident, $trait:ident, $method:ident, key) => {
+        struct $name<'b, C>(&'b mut C, &'static str);
+
+        impl<'b, C> Forward for $name<'b, C>
+            where C: $trait
+        {
+            type Ok = ();
+            type Error = C::Error;
+
+            fn forward<T>(self, value: &T) -> Result<(), C::Error>
+                where T: ?Sized + Serialize
+            {
+                self.0.$method(self.1, value)
+            }
+        }
+    };
+}
+
+forward_compound!(ForwardSeqElement, SerializeSeq, serialize_element);
+forward_compound!(ForwardTupleElement, SerializeTuple, serialize_element);
+forward_compound!(ForwardTupleStructField, SerializeTupleStruct, serialize_field);
+forward_compound!(ForwardTupleVariantField, SerializeTupleVariant, serialize_field);
+forward_compound!(ForwardMapKey, SerializeMap, serialize_key);
+forward_compound!(ForwardMapValue, SerializeMap, serialize_value);
+forward_compound!(ForwardStructField, SerializeStruct, serialize_field, key);
+forward_compound!(ForwardStructVariantField, SerializeStructVariant, serialize_field, key);
+
+macro_rules! forward_primitive {
+    ($($method:ident($type:ty)),*) => {
+        $(
+            fn $method(self, v: $type) -> Result<Self::Ok, S1::Error> {
+                let first = self.first.$method(v)?;
+                let second = self.second.$method(v).map_err(convert_error)?;
+                return Ok((first, second));
+            }
+        )*
+    };
+}
+
+impl<S1, S2> Serializer for TeeSerializer<S1, S2>
+    where S1: Serializer,
+        S2: Serializer
+{
+    type Ok = (S1::Ok, S2::Ok);
+    type Error = S1::Error;
+    type SerializeSeq = TeeCompound<S1::SerializeSeq, S2::SerializeSeq>;
+    type SerializeTuple = TeeCompound<S1::SerializeTuple, S2::SerializeTuple>;
+    type SerializeTupleStruct = TeeCompound<S1::SerializeTupleStruct, S2::SerializeTupleStruct>;
+    type SerializeTupleVariant = TeeCompound<S1::SerializeTupleVariant, S2::SerializeTupleVariant>;
+    type SerializeMap = TeeCompound<S1::SerializeMap, S2::SerializeMap>;
+    type SerializeStruct = TeeCompound<S1::SerializeStruct, S2::SerializeStruct>;
+    type SerializeStructVariant = TeeCompound<S1::SerializeStructVariant, S2::SerializeStructVariant>;
+
+    forward_primitive!{
+        serialize_bool(bool), serialize_i8(i8), serialize_i16(i16), serialize_i32(i32), serialize_i64(i64), serialize_i128(i128),
+        serialize_u8(u8), serialize_u16(u16), serialize_u32(u32), serialize_u64(u64), serialize_u128(u128), serialize_f32(f32), serialize_f64(f64),
+        serialize_char(char), serialize_str(&str), serialize_bytes(&[u8]), serialize_unit_struct(&'static str)
+    }
+
+    fn serialize_none(self) -> Result<Self::Ok, S1::Error> {
+        let first = self.first.serialize_none()?;
+        let second = self.second.serialize_none().map_err(convert_error)?;
+        return Ok((first, second));
+    }
+
+    fn serialize_unit(self) -> Result<Self::Ok, S1::Error> {
+        let first = self.first.serialize_unit()?;
+        let second = self.second.serialize_unit().map_err(convert_error)?;
+        return Ok((first, second));
+    }
+
+    fn serialize_unit_variant(self, name: &'static str, variant_index: u32, variant: &'static str) -> Result<Self::Ok, S1::Error> {
+        let first = self.first.serialize_unit_variant(name, variant_index, variant)?;
+        let second = self.second.serialize_unit_variant(name, variant_index, variant).map_err(convert_error)?;
+        return Ok((first, second));
+    }
+
+    fn serialize_some<T>(self, value: &T) -> Result<Self::Ok, S1::Error>
+        where T: ?Sized + Serialize
+    {
+        forward_both(value, ForwardSome(self.first), ForwardSome(self.second))
+    }
+
+    fn serialize_newtype_struct<T>(self, name: &'static str, value: &T) -> Result<Self::Ok, S1::Error>
+        where T: ?Sized + Serialize
+    {
+        forward_both(value, ForwardNewtypeStruct(self.first, name), ForwardNewtypeStruct(self.second, name))
+    }
+
+    fn serialize_newtype_variant<T>(self, name: &'static str, variant_index: u32, variant: &'static str, value: &T) -> Result<Self::Ok, S1::Error>
+        where T: ?Sized + Serialize
+    {
+        forward_both(value, ForwardNewtypeVariant(self.first, name, variant_index, variant), ForwardNewtypeVariant(self.second, name, variant_index, variant))
+    }
+
+    fn serialize_seq(self, len: Option<usize>) -> Result<Self::SerializeSeq, S1::Error> {
+        let first = self.first.serialize_seq(len)?;
+        let second = self.second.serialize_seq(len).map_err(convert_error)?;
+        return Ok(TeeCompound { first: first, second: second });
+    }
+
+    fn serialize_tuple(self, len: usize) -> Result<Self::SerializeTuple, S1::Error> {
+        let first = self.first.serialize_tuple(len)?;
+        let second = self.second.serialize_tuple(len).map_err(convert_error)?;
+        return Ok(TeeCompound { first: first, second: second });
+    }
+
+    fn serialize_tuple_struct(self, name: &'static str, len: usize) -> Result<Self::SerializeTupleStruct, S1::Error> {
+        let first = self.first.serialize_tuple_struct(name, len)?;
+        let second = self.second.serialize_tuple_struct(name, len).map_err(convert_error)?;
+        return Ok(TeeCompound { first: first, second: second });
+    }
+
+    fn serialize_tuple_variant(self, name: &'static str, variant_index: u32, variant: &'static str, len: usize) -> Result<Self::SerializeTupleVariant, S1::Error> {
+        let first = self.first.serialize_tuple_variant(name, variant_index, variant, len)?;
+        let second = self.second.serialize_tuple_variant(name, variant_index, variant, len).map_err(convert_error)?;
+        return Ok(TeeCompound { first: first, second: second });
+    }
+
+    fn serialize_map(self, len: Option<usize>) -> Result<Self::SerializeMap, S1::Error> {
+        let first = self.first.serialize_map(len)?;
+        let second = self.second.serialize_map(len).map_err(convert_error)?;
+        return Ok(TeeCompound { first: first, second: second });
+    }
+
+    fn serialize_struct(self, name: &'static str, len: usize) -> Result<Self::SerializeStruct, S1::Error> {
+        let first = self.first.serialize_struct(name, len)?;
+        let second = self.second.serialize_struct(name, len).map_err(convert_error)?;
+        return Ok(TeeCompound { first: first, second: second });
+    }
+
+    fn serialize_struct_variant(self, name: &'static str, variant_index: u32, variant: &'static str, len: usize) -> Result<Self::SerializeStructVariant, S1::Error> {
+        let first = self.first.serialize_struct_variant(name, variant_index, variant, len)?;
+        let second = self.second.serialize_struct_variant(name, variant_index, variant, len).map_err(convert_error)?;
+        return Ok(TeeCompound { first: first, second: second });
+    }
+
+    fn is_human_readable(&self) -> bool {
+        self.first.is_human_readable()
+    }
+}
+
+///
+/// The compound serializers of [`TeeSerializer`].
+///
+pub struct TeeCompound<C1, C2> {
+    first: C1,
+    second: C2
+}
+
+macro_rules! impl_tee_compound {
+    ($trait:ident, $forward:ident, key) => {
+        impl<C1, C2> $trait for TeeCompound<C1, C2>
+            where C1: $trait,
+                C2: $trait
+        {
+            type Ok = (C1::Ok, C2::Ok);
+            type Error = C1::Error;
+
+            fn serialize_field<T>(&mut self, key: &'static str, value: &T) -> Result<(), C1::Error>
+                where T: ?Sized + Serialize
+            {
+                forward_both(value, $forward(&mut self.first, key), $forward(&mut self.second, key)).map(|_| ())
+            }
+
+            fn skip_field(&mut self, key: &'static str) -> Result<(), C1::Error> {
+                self.first.skip_field(key)?;
+                return self.second.skip_field(key).map_err(convert_error);
+            }
+
+            fn end(self) -> Result<Self::Ok, C1::Error> {
+                let first = self.first.end()?;
+                let second = self.second.end().map_err(convert_error)?;
+                return Ok((first, second));
+            }
+        }
+    };
+    ($trait:ident, $method:ident, $forward:ident) => {
+        impl<C1, C2> $trait for TeeCompound<C1, C2>
+            where C1: $trait,
+                C2: $trait
+        {
+            type Ok = (C1::Ok, C2::Ok);
+            type Error = C1::Error;
+
+            fn $method<T>(&mut self, value: &T) -> Result<(), C1::Error>
+                where T: ?Sized + Serialize
+            {
+                forward_both(value, $forward(&mut self.first), $forward(&mut self.second)).map(|_| ())
+            }
+
+            fn end(self) -> Result<Self::Ok, C1::Error> {
+                let first = self.first.end()?;
+                let second = self.second.end().map_err(convert_error)?;
+                return Ok((first, second));
+            }
+        }
+    };
+}
+
+impl_tee_compound!(SerializeSeq, serialize_element, ForwardSeqElement);
+impl_tee_compound!(SerializeTuple, serialize_element, ForwardTupleElement);
+impl_tee_compound!(SerializeTupleStruct, serialize_field, ForwardTupleStructField);
+impl_tee_compound!(SerializeTupleVariant, serialize_field, ForwardTupleVariantField);
+impl_tee_compound!(SerializeStruct, ForwardStructField, key);
+impl_tee_compound!(SerializeStructVariant, ForwardStructVariantField, key);
+
+impl<C1, C2> SerializeMap for TeeCompound<C1, C2>
+    where C1: SerializeMap,
+        C2: SerializeMap
+{
+    type Ok = (C1::Ok, C2::Ok);
+    type Error = C1::Error;
+
+    fn serialize_key<T>(&mut self, key: &T) -> Result<(), C1::Error>
+        where T: ?Sized + Serialize
+    {
+        forward_both(key, ForwardMapKey(&mut self.first), ForwardMapKey(&mut self.second)).map(|_| ())
+    }
+
+    fn serialize_value<T>(&mut self, value: &T) -> Result<(), C1::Error>
+        where T: ?Sized + Serialize
+    {
+        forward_both(value, ForwardMapValue(&mut self.first), ForwardMapValue(&mut self.second)).map(|_| ())
+    }
+
+    fn end(self) -> Result<Self::Ok, C1::Error> {
+        let first = self.first.end()?;
+        let second = self.second.end().map_err(convert_error)?;
+        return Ok((first, second));
+    }
+}
+
+#[cfg(test)]
+use std::cell::Cell;
+#[cfg(test)]
+use postcard::ser_flavors::Flavor;
+#[cfg(test)]
+use crate::mock::{MockSerializer, MockValue};
+#[cfg(test)]
+use crate::trace::{trace_serialize, TraceSerializer};
+
+#[cfg(test)]
+#[derive(serde::Serialize)]
+enum Record {
+    A(Option<i64>),
+    B { values: Vec<(u8, String)>, unit: () },
+    C(u8, u16)
+}
+
+#[test]
+fn test_tee() {
+    let value = vec![Record::A(Some(1)), Record::B { values: vec![(1, "a".to_owned())], unit: () }, Record::C(3, 4), Record::A(None)];
+    let trace = RefCell::new(Vec::new());
+    let (mock, ()) = value.serialize(TeeSerializer::new(MockSerializer::new(), TraceSerializer::new(&trace))).unwrap();
+    assert_eq!(MockValue::from_serialize(&value).unwrap(), mock);
+    assert_eq!(trace_serialize(&value).unwrap(), trace.into_inner());
+
+    let mut json = Vec::new();
+    let mut postcard = postcard::Serializer { output: postcard::ser_flavors::AllocVec::new() };
+    serialize_tee(&value, &mut postcard, &mut serde_json::Serializer::new(&mut json)).unwrap();
+    assert_eq!(postcard::to_allocvec(&value).unwrap(), postcard.output.finalize().unwrap());
+    assert_eq!(serde_json::to_vec(&value).unwrap(), json);
+}
+
+#[test]
+fn test_serialized_once() {
+    struct Counting<'a>(&'a Cell<usize>);
+
+    impl<'a> Serialize for Counting<'a> {
+
+        fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+            where S: Serializer
+        {
+            self.0.set(self.0.get() + 1);
+            serializer.serialize_u32(self.0.get() as u32)
+        }
+    }
+    let counter = Cell::new(0);
+    let value = (Some(Counting(&counter)), vec![Counting(&counter), Counting(&counter)]);
+    let (first, second) = value.serialize(TeeSerializer::new(MockSerializer::new(), MockSerializer::new())).unwrap();
+    assert_eq!(3, counter.get());
+    assert_eq!(first, second);
+}
+
+#[test]
+fn test_secondary_error() {
+    let value = std::collections::BTreeMap::from([((1, 2), 3)]);
+    let mut json = Vec::new();
+    let mut postcard = postcard::Serializer { output: postcard::ser_flavors::AllocVec::new() };
+    assert!(serialize_tee(&value, &mut postcard, &mut serde_json::Serializer::new(&mut json)).is_err());
+}
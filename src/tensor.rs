@@ -0,0 +1,298 @@
+use std::marker::PhantomData;
+
+use serde::de::{DeserializeSeed, Error, IgnoredAny, SeqAccess, Visitor};
+use serde::ser::{Serialize, SerializeTuple, Serializer};
+use serde::Deserializer;
+
+use crate::seq::SerializableSeq;
+
+///
+/// Wraps an [`Iterator`] over the entries of an N-dimensional tensor in row-major
+/// order, and implements [`Serialize`] by mapping it to the tuple `(ndim, shape..., data)`
+/// in the serde data model, where `data` is a sequence of `shape[0] * ... * shape[ndim - 1]`
+/// entries.
+///
+/// # Example
+/// ```
+/// # use feanor_serde::tensor::*;
+/// let data = [1, 2, 3, 4, 5, 6];
+/// assert_eq!("[3,1,2,3,[1,2,3,4,5,6]]", serde_json::to_string(&SerializableTensor::new(&[1, 2, 3], data.iter())).unwrap());
+/// ```
+///
+pub struct SerializableTensor<'a, I>
+    where I: Iterator + Clone
+{
+    shape: &'a [usize],
+    data: I
+}
+
+impl<'a, I> SerializableTensor<'a, I>
+    where I: Iterator + Clone
+{
+    ///
+    /// Creates a new [`SerializableTensor`] for a tensor of the given shape, whose
+    /// entries are given by `data` in row-major order.
+    ///
+    pub const fn new(shape: &'a [usize], data: I) -> Self {
+        Self { shape, data }
+    }
+}
+
+fn shape_len(shape: &[usize]) -> Option<usize> {
+    shape.iter().try_fold(1usize, |current, next| current.checked_mul(*next))
+}
+
+impl<'a, I> Serialize for SerializableTensor<'a, I>
+    where I: Iterator + Clone,
+        I::Item: Serialize
+{
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+        where S: Serializer
+    {
+        let len = shape_len(self.shape).ok_or_else(|| <S::Error as serde::ser::Error>::custom(format!("tensor shape {:?} overflows usize", self.shape)))?;
+        let mut tuple = serializer.serialize_tuple(self.shape.len() + 2)?;
+        tuple.serialize_element(&self.shape.len())?;
+        for dim in self.shape {
+            tuple.serialize_element(dim)?;
+        }
+        tuple.serialize_element(&SerializableSeq::new_with_len(self.data.clone(), len))?;
+        return tuple.end();
+    }
+}
+
+///
+/// A [`DeserializeSeed`] that deserializes a tensor that was serialized using
+/// [`SerializableTensor`]. Once the shape is known, the storage is created by calling
+/// `initial(shape)`, and the entry at row-major position `k` is then deserialized using
+/// the [`DeserializeSeed`] returned by `element_seed(k)` and added to the storage
+/// using `collector`.
+///
+/// Deserialization fails if the number of entries does not match the product of
+/// the shape.
+///
+/// # Example
+/// ```
+/// # use feanor_serde::tensor::*;
+/// # use feanor_serde::default_seed::seed_of;
+/// # use serde::de::DeserializeSeed;
+/// let mut deserializer = serde_json::Deserializer::new(serde_json::de::StrRead::new("[2, 1, 3, [1, 2, 3]]"));
+/// let deserialize_seed = DeserializeSeedTensor::new(
+///     |_k| seed_of::<i64>(),
+///     |shape| (shape.to_vec(), Vec::new()),
+///     |(shape, mut data), x| { data.push(x); (shape, data) }
+/// );
+/// assert_eq!((vec![1, 3], vec![1, 2, 3]), deserialize_seed.deserialize(&mut deserializer).unwrap());
+/// ```
+///
+pub struct DeserializeSeedTensor<F, S, I, C, T>
+    where F: FnMut(usize) -> S,
+        I: FnOnce(&[usize]) -> T
+{
+    element_seed: F,
+    initial: I,
+    collector: C
+}
+
+impl<'de, F, S, I, C, T> DeserializeSeedTensor<F, S, I, C, T>
+    where F: FnMut(usize) -> S,
+        S: DeserializeSeed<'de>,
+        I: FnOnce(&[usize]) -> T,
+        C: FnMut(T, S::Value) -> T
+{
+    pub const fn new(element_seed: F, initial: I, collector: C) -> Self {
+        Self { element_seed, initial, collector }
+    }
+}
+
+struct DeserializeSeedEntries<'a, 'de, F, S, C, T>
+    where F: FnMut(usize) -> S,
+        S: DeserializeSeed<'de>,
+        C: FnMut(T, S::Value) -> T
+{
+    deserializer: PhantomData<&'de ()>,
+    len: usize,
+    initial: T,
+    element_seed: &'a mut F,
+    collector: &'a mut C
+}
+
+impl<'a, 'de, F, S, C, T> Visitor<'de> for DeserializeSeedEntries<'a, 'de, F, S, C, T>
+    where F: FnMut(usize) -> S,
+        S: DeserializeSeed<'de>,
+        C: FnMut(T, S::Value) -> T
+{
+    type Value = T;
+
+    fn expecting(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "a sequence of {} tensor entries", self.len)
+    }
+
+    fn visit_seq<A>(self, mut seq: A) -> Result<Self::Value, A::Error>
+        where A: SeqAccess<'de>
+    {
+        let mut result = self.initial;
+        for k in 0..self.len {
+            match seq.next_element_seed((self.element_seed)(k))? {
+                Some(entry) => result = (self.collector)(result, entry),
+                None => return Err(<A::Error as Error>::invalid_length(k, &format!("a sequence of {} tensor entries", self.len).as_str()))
+            }
+        }
+        if seq.next_element::<IgnoredAny>()?.is_some() {
+            return Err(<A::Error as Error>::invalid_length(self.len + 1, &format!("a sequence of {} tensor entries", self.len).as_str()));
+        }
+        return Ok(result);
+    }
+}
+
+impl<'a, 'de, F, S, C, T> DeserializeSeed<'de> for DeserializeSeedEntries<'a, 'de, F, S, C, T>
+    where F: FnMut(usize) -> S,
+        S: DeserializeSeed<'de>,
+        C: FnMut(T, S::Value) -> T
+{
+    type Value = T;
+
+    fn deserialize<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+        where D: Deserializer<'de>
+    {
+        return deserializer.deserialize_seq(self);
+    }
+}
+
+impl<'de, F, S, I, C, T> DeserializeSeed<'de> for DeserializeSeedTensor<F, S, I, C, T>
+    where F: FnMut(usize) -> S,
+        S: DeserializeSeed<'de>,
+        I: FnOnce(&[usize]) -> T,
+        C: FnMut(T, S::Value) -> T
+{
+    type Value = T;
+
+    fn deserialize<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+        where D: Deserializer<'de>
+    {
+        struct ResultVisitor<'de, F, S, I, C, T>
+            where F: FnMut(usize) -> S,
+                S: DeserializeSeed<'de>,
+                I: FnOnce(&[usize]) -> T,
+                C: FnMut(T, S::Value) -> T
+        {
+            deserializer: PhantomData<&'de ()>,
+            element_seed: F,
+            initial: I,
+            collector: C
+        }
+
+        impl<'de, F, S, I, C, T> Visitor<'de> for ResultVisitor<'de, F, S, I, C, T>
+            where F: FnMut(usize) -> S,
+                S: DeserializeSeed<'de>,
+                I: FnOnce(&[usize]) -> T,
+                C: FnMut(T, S::Value) -> T
+        {
+            type Value = T;
+
+            fn expecting(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+                write!(f, "a tuple (ndim, shape..., data)")
+            }
+
+            fn visit_seq<A>(mut self, mut seq: A) -> Result<Self::Value, A::Error>
+                where A: SeqAccess<'de>
+            {
+                let ndim: usize = seq.next_element()?.ok_or_else(|| <A::Error as Error>::invalid_length(0, &self))?;
+                let mut shape = Vec::new();
+                for i in 0..ndim {
+                    shape.push(seq.next_element()?.ok_or_else(|| <A::Error as Error>::invalid_length(i + 1, &self))?);
+                }
+                let len = shape_len(&shape).ok_or_else(|| <A::Error as Error>::custom(format!("tensor shape {:?} overflows usize", shape)))?;
+                let initial = (self.initial)(&shape);
+                return seq.next_element_seed(DeserializeSeedEntries {
+                    deserializer: PhantomData,
+                    len: len,
+                    initial: initial,
+                    element_seed: &mut self.element_seed,
+                    collector: &mut self.collector
+                })?.ok_or_else(|| <A::Error as Error>::invalid_length(ndim + 1, &"a tuple (ndim, shape..., data)"));
+            }
+        }
+
+        // the length of the tuple is only known after reading `ndim`
+        return deserializer.deserialize_tuple(usize::MAX, ResultVisitor {
+            deserializer: PhantomData,
+            element_seed: self.element_seed,
+            initial: self.initial,
+            collector: self.collector
+        });
+    }
+}
+
+#[cfg(test)]
+fn testdata() -> Vec<(Vec<usize>, Vec<i64>)> {
+    vec![
+        (vec![], vec![1]),
+        (vec![0], vec![]),
+        (vec![3], vec![1, 2, 3]),
+        (vec![2, 3], vec![1, 2, 3, 4, 5, 6]),
+        (vec![2, 0, 4], vec![]),
+        (vec![2, 1, 2, 2], vec![1, 2, 3, 4, 5, 6, 7, 8])
+    ]
+}
+
+#[cfg(test)]
+fn deserialize_seed_testdata<'de>() -> impl DeserializeSeed<'de, Value = (Vec<usize>, Vec<i64>)> {
+    DeserializeSeedTensor::new(
+        |_| PhantomData::<i64>,
+        |shape| (shape.to_vec(), Vec::new()),
+        |(shape, mut data), x| { data.push(x); (shape, data) }
+    )
+}
+
+#[test]
+fn test_serde_postcard() {
+    for (shape, data) in testdata() {
+        let serialized = postcard::to_allocvec(&SerializableTensor::new(&shape, data.iter())).unwrap();
+        let result = deserialize_seed_testdata().deserialize(
+            &mut postcard::Deserializer::from_flavor(postcard::de_flavors::Slice::new(&serialized))
+        ).unwrap();
+        assert_eq!((shape, data), result);
+    }
+}
+
+#[test]
+fn test_serde_json() {
+    for (shape, data) in testdata() {
+        let serialized = serde_json::to_string(&SerializableTensor::new(&shape, data.iter())).unwrap();
+        let result = deserialize_seed_testdata().deserialize(
+            &mut serde_json::Deserializer::from_str(&serialized)
+        ).unwrap();
+        assert_eq!((shape, data), result);
+    }
+}
+
+#[test]
+fn test_element_seed_position() {
+    struct DeserializeSeedOffset(i64);
+
+    impl<'de> DeserializeSeed<'de> for DeserializeSeedOffset {
+        type Value = i64;
+
+        fn deserialize<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+            where D: Deserializer<'de>
+        {
+            Ok(<i64 as serde::Deserialize>::deserialize(deserializer)? + self.0)
+        }
+    }
+
+    let result = DeserializeSeedTensor::new(|k| DeserializeSeedOffset(k as i64), |_| Vec::new(), |mut data, x| { data.push(x); data }).deserialize(
+        &mut serde_json::Deserializer::from_str("[2, 2, 2, [0, 0, 0, 0]]")
+    ).unwrap();
+    assert_eq!(vec![0, 1, 2, 3], result);
+}
+
+#[test]
+fn test_invalid_shape() {
+    let deserialize = |json: &str| deserialize_seed_testdata().deserialize(&mut serde_json::Deserializer::from_str(json));
+    assert!(deserialize("[2, 2, 2, [1, 2, 3]]").is_err());
+    assert!(deserialize("[2, 2, 2, [1, 2, 3, 4, 5]]").is_err());
+    assert!(deserialize("[2, 2, [1, 2]]").is_err());
+    assert!(deserialize(&format!("[2, {}, 3, []]", usize::MAX)).is_err());
+
+    assert!(serde_json::to_string(&SerializableTensor::new(&[usize::MAX, 2], [0].iter())).is_err());
+}
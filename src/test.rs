@@ -0,0 +1,989 @@
+use std::cell::RefCell;
+use std::fmt::{Debug, Display};
+use std::marker::PhantomData;
+use std::panic::AssertUnwindSafe;
+
+use serde::de::{DeserializeOwned, DeserializeSeed};
+use serde::Serialize;
+
+use crate::mock::{MockDeserializer, MockValue, MockVariant};
+use crate::path::{Path, Segment};
+use crate::trace::TraceSerializer;
+
+pub mod token;
+
+pub use token::{Token, Tokens};
+use token::{TokenDeserializer, TokenError, TokenSerializer};
+
+///
+/// Configuration of the token-based [`serde::Serializer`] and [`serde::Deserializer`]
+/// used by the assertions in this module, analogous to `serde_test::Configure`.
+///
+/// The free functions of this module use [`TokenConfig::default()`], which is
+/// human-readable and not self-describing.
+///
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct TokenConfig {
+    is_human_readable: bool,
+    self_describing: bool
+}
+
+impl TokenConfig {
+
+    pub const fn new() -> Self {
+        Self { is_human_readable: true, self_describing: false }
+    }
+
+    pub const fn with_human_readable(self, is_human_readable: bool) -> Self {
+        Self { is_human_readable, ..self }
+    }
+
+    ///
+    /// Sets whether the deserializer supports [`serde::Deserializer::deserialize_any()`].
+    ///
+    pub const fn with_self_describing(self, self_describing: bool) -> Self {
+        Self { self_describing, ..self }
+    }
+
+    ///
+    /// Serializes the given value, and returns the produced tokens.
+    ///
+    pub fn to_tokens<T>(&self, value: &T) -> Result<Tokens, TokenError>
+        where T: ?Sized + Serialize
+    {
+        value.serialize(TokenSerializer::new().with_human_readable(self.is_human_readable))
+    }
+
+    ///
+    /// Deserializes a value from the given tokens using the given [`DeserializeSeed`].
+    /// Fails if not all tokens are consumed.
+    ///
+    pub fn from_tokens_seed<'de, S, I>(&self, seed: S, tokens: I) -> Result<S::Value, TokenError>
+        where S: DeserializeSeed<'de>,
+            I: IntoIterator<Item = Token>
+    {
+        let mut deserializer = TokenDeserializer::new(tokens)
+            .with_human_readable(self.is_human_readable)
+            .with_self_describing(self.self_describing);
+        let result = seed.deserialize(&mut deserializer)?;
+        if !deserializer.is_empty() {
+            return Err(<TokenError as serde::de::Error>::custom("remaining tokens after deserialization"));
+        }
+        return Ok(result);
+    }
+
+    ///
+    /// Asserts that serializing `value` produces the `expected` tokens. The value can also be
+    /// one of the wrappers of this crate that serialize using some context, e.g. a
+    /// [`crate::seq::SerializableSeq`].
+    ///
+    pub fn assert_ser_tokens_ctx<T>(&self, value: &T, expected: &[Token])
+        where T: ?Sized + Serialize
+    {
+        let tokens = self.to_tokens(value).unwrap_or_else(|e| panic!("serialization failed: {}", e));
+        assert!(tokens == expected.to_vec(), "serialization produced unexpected tokens\n  produced: {:?}\n  expected: {:?}", tokens, expected);
+    }
+
+    ///
+    /// Asserts that deserializing the given tokens using `seed` succeeds, consumes all tokens,
+    /// and produces the `expected` value.
+    ///
+    pub fn assert_de_tokens_seed<'de, S>(&self, seed: S, tokens: &[Token], expected: &S::Value)
+        where S: DeserializeSeed<'de>,
+            S::Value: PartialEq + Debug
+    {
+        let value = self.from_tokens_seed(seed, tokens.iter().cloned()).unwrap_or_else(|e| panic!("deserialization failed: {}", e));
+        assert_eq!(*expected, value);
+    }
+
+    ///
+    /// Asserts that deserializing the given tokens using `seed` fails with the given error message.
+    ///
+    pub fn assert_de_tokens_seed_error<'de, S>(&self, seed: S, tokens: &[Token], error: &str)
+        where S: DeserializeSeed<'de>,
+            S::Value: Debug
+    {
+        match self.from_tokens_seed(seed, tokens.iter().cloned()) {
+            Ok(value) => panic!("deserialization succeeded with {:?}, but expected error {}", value, error),
+            Err(e) => assert_eq!(error, e.to_string())
+        }
+    }
+
+    ///
+    /// Asserts that `value` serializes to `tokens`, and that deserializing `tokens` using `seed`
+    /// produces `expected`.
+    ///
+    pub fn assert_tokens_seed<'de, T, S>(&self, value: &T, seed: S, tokens: &[Token], expected: &S::Value)
+        where T: ?Sized + Serialize,
+            S: DeserializeSeed<'de>,
+            S::Value: PartialEq + Debug
+    {
+        self.assert_ser_tokens_ctx(value, tokens);
+        self.assert_de_tokens_seed(seed, tokens, expected);
+    }
+}
+
+impl Default for TokenConfig {
+
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+///
+/// Asserts that serializing `value` produces the `expected` tokens, see
+/// [`TokenConfig::assert_ser_tokens_ctx()`].
+///
+/// # Example
+/// ```
+/// # use feanor_serde::test::*;
+/// # use feanor_serde::seq::*;
+/// assert_ser_tokens_ctx(&SerializableSeq::new_with_len([1i64, 2].into_iter(), 2), &[
+///     Token::Seq { len: Some(2) },
+///     Token::I64(1),
+///     Token::I64(2),
+///     Token::SeqEnd
+/// ]);
+/// ```
+///
+pub fn assert_ser_tokens_ctx<T>(value: &T, expected: &[Token])
+    where T: ?Sized + Serialize
+{
+    TokenConfig::default().assert_ser_tokens_ctx(value, expected)
+}
+
+///
+/// Asserts that deserializing the given tokens using `seed` produces the `expected` value,
+/// see [`TokenConfig::assert_de_tokens_seed()`].
+///
+/// # Example
+/// ```
+/// # use feanor_serde::test::*;
+/// # use feanor_serde::seq::*;
+/// # use feanor_serde::default_seed::seed_of;
+/// # use std::iter::repeat;
+/// let seed = DeserializeSeedSeq::new(repeat(seed_of::<i64>()), Vec::new(), |mut current, next| { current.push(next); current });
+/// assert_de_tokens_seed(seed, &[
+///     Token::Seq { len: Some(2) },
+///     Token::I64(1),
+///     Token::I64(2),
+///     Token::SeqEnd
+/// ], &vec![1, 2]);
+/// ```
+///
+pub fn assert_de_tokens_seed<'de, S>(seed: S, tokens: &[Token], expected: &S::Value)
+    where S: DeserializeSeed<'de>,
+        S::Value: PartialEq + Debug
+{
+    TokenConfig::default().assert_de_tokens_seed(seed, tokens, expected)
+}
+
+///
+/// Asserts that deserializing the given tokens using `seed` fails with the given error message,
+/// see [`TokenConfig::assert_de_tokens_seed_error()`].
+///
+pub fn assert_de_tokens_seed_error<'de, S>(seed: S, tokens: &[Token], error: &str)
+    where S: DeserializeSeed<'de>,
+        S::Value: Debug
+{
+    TokenConfig::default().assert_de_tokens_seed_error(seed, tokens, error)
+}
+
+///
+/// Asserts that `value` serializes to `tokens`, and that deserializing `tokens` using `seed`
+/// produces `expected`, see [`TokenConfig::assert_tokens_seed()`].
+///
+pub fn assert_tokens_seed<'de, T, S>(value: &T, seed: S, tokens: &[Token], expected: &S::Value)
+    where T: ?Sized + Serialize,
+        S: DeserializeSeed<'de>,
+        S::Value: PartialEq + Debug
+{
+    TokenConfig::default().assert_tokens_seed(value, seed, tokens, expected)
+}
+
+///
+/// Serializes `value` and deserializes the result using a seed produced by `seed`, for every
+/// enabled format, and asserts that `is_expected` holds for each deserialized value. If the
+/// roundtrip fails for some format, the panic message contains the name of the format.
+///
+/// Currently, the following formats are used:
+///  - the tokens of this module, both human-readable and not human-readable,
+///  - JSON, using `serde_json`,
+///  - postcard, if the feature `postcard` is enabled,
+///  - CBOR, using `ciborium`, if the feature `ciborium` is enabled,
+///  - MessagePack with named struct fields, using `rmp-serde`, if the feature `rmp-serde` is enabled,
+///  - bincode, with the configuration of [`crate::bincode::legacy_options()`], if the feature
+///    `bincode` is enabled.
+///
+/// The seeds may borrow from the serialized data, which only lives during the call to
+/// `is_expected`. Hence, the seed must be a [`DeserializeSeed`] for every lifetime, as
+/// usual for seeds.
+///
+/// # Example
+/// ```
+/// # use feanor_serde::test::*;
+/// # use feanor_serde::seq::*;
+/// # use feanor_serde::default_seed::seed_of;
+/// # use std::iter::repeat;
+/// let data = [1i64, 2, 3];
+/// assert_roundtrip_seeded(
+///     &SerializableSeq::new_with_len(data.iter(), data.len()),
+///     || DeserializeSeedSeq::new(repeat(seed_of::<i64>()), Vec::new(), |mut current, next| { current.push(next); current }),
+///     |result| result[..] == data[..]
+/// );
+/// ```
+///
+pub fn assert_roundtrip_seeded<T, F, S, E>(value: &T, mut seed: F, mut is_expected: E)
+    where T: ?Sized + Serialize,
+        F: FnMut() -> S,
+        S: for<'de> DeserializeSeed<'de>,
+        for<'de> <S as DeserializeSeed<'de>>::Value: Debug,
+        E: for<'de> FnMut(&<S as DeserializeSeed<'de>>::Value) -> bool
+{
+    roundtrip_all_formats(value, &mut seed, |format, result| match result {
+        Ok(result) => assert!(is_expected(&result), "roundtrip through {} produced unexpected value {:?}", format, result),
+        Err(e) => panic!("roundtrip through {} failed: {}", format, e)
+    });
+}
+
+///
+/// Performs the roundtrips of [`assert_roundtrip_seeded()`], and passes the result of each one
+/// to `check`, together with the name of the format. The result may borrow from the serialized
+/// data, which is dropped afterwards.
+///
+fn roundtrip_all_formats<T, F, S, C>(value: &T, mut seed: F, mut check: C)
+    where T: ?Sized + Serialize,
+        F: FnMut() -> S,
+        S: for<'de> DeserializeSeed<'de>,
+        C: for<'de> FnMut(&'static str, Result<<S as DeserializeSeed<'de>>::Value, String>)
+{
+    for is_human_readable in [true, false] {
+        let config = TokenConfig::new().with_human_readable(is_human_readable);
+        let format = if is_human_readable { "human-readable tokens" } else { "tokens" };
+        let result = config.to_tokens(value).map_err(|e| e.to_string()).and_then(|tokens| config.from_tokens_seed(seed(), tokens).map_err(|e| e.to_string()));
+        check(format, result);
+    }
+
+    let serialized = serde_json::to_string(value).map_err(|e| e.to_string());
+    let result = serialized.as_deref().map_err(String::clone).and_then(|serialized| {
+        let mut deserializer = serde_json::Deserializer::from_str(serialized);
+        let result = seed().deserialize(&mut deserializer).map_err(|e| e.to_string())?;
+        deserializer.end().map_err(|e| e.to_string())?;
+        return Ok(result);
+    });
+    check("JSON", result);
+
+    #[cfg(feature = "postcard")]
+    {
+        let serialized = ::postcard::to_allocvec(value).map_err(|e| e.to_string());
+        let result = serialized.as_deref().map_err(String::clone).and_then(|serialized| {
+            let (result, rest) = crate::postcard::take_from_slice_seeded(seed(), serialized).map_err(|e| e.to_string())?;
+            if !rest.is_empty() {
+                return Err(format!("{} remaining bytes after deserialization", rest.len()));
+            }
+            return Ok(result);
+        });
+        check("postcard", result);
+    }
+
+    #[cfg(feature = "ciborium")]
+    {
+        let serialized = crate::ciborium::to_vec(value).map_err(|e| e.to_string());
+        let parsed = serialized.and_then(|serialized| {
+            let mut reader = &serialized[..];
+            let parsed: ::ciborium::value::Value = ::ciborium::from_reader(&mut reader).map_err(|e| e.to_string())?;
+            if !reader.is_empty() {
+                return Err(format!("{} remaining bytes after deserialization", reader.len()));
+            }
+            return Ok(parsed);
+        });
+        let result = parsed.as_ref().map_err(String::clone).and_then(|parsed| crate::ciborium::from_value_seeded(seed(), parsed).map_err(|e| e.to_string()));
+        check("CBOR", result);
+    }
+
+    #[cfg(feature = "rmp-serde")]
+    {
+        let serialized = crate::rmp_serde::to_vec_named(value).map_err(|e| e.to_string());
+        // rmp-serde checks that arrays and maps are consumed completely, so there cannot be remaining bytes
+        let result = serialized.as_deref().map_err(String::clone).and_then(|serialized| crate::rmp_serde::from_slice_seeded(seed(), serialized).map_err(|e| e.to_string()));
+        check("MessagePack", result);
+    }
+
+    #[cfg(feature = "bincode")]
+    {
+        use ::bincode::Options;
+        let serialized = crate::bincode::to_vec(value).map_err(|e| e.to_string());
+        let result = serialized.as_deref().map_err(String::clone).and_then(|serialized| {
+            let options = crate::bincode::legacy_options().reject_trailing_bytes();
+            return crate::bincode::from_slice_seeded_with_options(options, seed(), serialized).map_err(|e| e.to_string());
+        });
+        check("bincode", result);
+    }
+}
+
+///
+/// Asserts that deserializing with a seed produced by `seed` behaves exactly like deserializing
+/// with the [`Deserialize`](serde::Deserialize) implementation of `T`, usually obtained via `#[derive(Deserialize)]`.
+/// This is meant to check that the seeds generated by the macros of this crate, e.g.
+/// [`crate::impl_deserialize_seed_for_dependent_struct!`], follow the semantics of serde derive.
+///
+/// Both are used to deserialize `value` after serializing it to every format used by
+/// [`assert_roundtrip_seeded()`], and to deserialize its [`MockValue`] together with all
+/// [`MockValue::mutations()`] of it, which e.g. reorder, duplicate or remove fields, or use
+/// numeric identifiers. For each input, either both must succeed and the result of the seed,
+/// converted by `convert`, must equal the derived result, or both must fail with the same error
+/// message.
+///
+/// # Example
+/// ```
+/// # use feanor_serde::impl_deserialize_seed_for_dependent_struct;
+/// # use feanor_serde::test::*;
+/// # use serde::{Deserialize, Serialize};
+/// # use feanor_serde::default_seed::seed_of;
+/// #[derive(Serialize, Deserialize, PartialEq, Debug)]
+/// struct Point { x: i64, y: i64 }
+///
+/// struct PointDeserializeSeed;
+/// impl_deserialize_seed_for_dependent_struct!{
+///     pub struct DeserializedPoint<'de> using PointDeserializeSeed {
+///         x: i64: |_| seed_of::<i64>(),
+///         y: i64: |_| seed_of::<i64>()
+///     }
+/// }
+/// assert_derive_equivalent(&Point { x: 1, y: 2 }, || PointDeserializeSeed, |point| Point { x: point.x, y: point.y });
+/// ```
+///
+pub fn assert_derive_equivalent<T, F, S, C>(value: &T, mut seed: F, mut convert: C)
+    where T: Serialize + DeserializeOwned + PartialEq + Debug,
+        F: FnMut() -> S,
+        S: for<'de> DeserializeSeed<'de>,
+        C: for<'de> FnMut(<S as DeserializeSeed<'de>>::Value) -> T
+{
+    let mut mismatches = Vec::new();
+    let mut compare = |input: &dyn Display, derived: Result<T, String>, seeded: Result<T, String>| match (derived, seeded) {
+        (Ok(derived), Ok(seeded)) if derived == seeded => {},
+        (Err(derived), Err(seeded)) if derived == seeded => {},
+        (derived, seeded) => mismatches.push(format!("{}\n  derive produced {:?}\n  seed produced   {:?}", input, derived, seeded))
+    };
+
+    let mut derived_results = Vec::new();
+    roundtrip_all_formats(value, PhantomData::<T>::default, |format, result| derived_results.push((format, result)));
+    let mut seeded_results = Vec::new();
+    roundtrip_all_formats(value, &mut seed, |_, result| seeded_results.push(result.map(&mut convert)));
+    for ((format, derived), seeded) in derived_results.into_iter().zip(seeded_results) {
+        compare(&format!("roundtrip through {}", format), derived, seeded);
+    }
+
+    let mock = MockValue::from_serialize(value).unwrap_or_else(|e| panic!("serialization failed: {}", e));
+    let mutations = mock.mutations();
+    for input in [mock].into_iter().chain(mutations) {
+        let derived = T::deserialize(MockDeserializer::new(input.clone())).map_err(|e| e.to_string());
+        let seeded = seed().deserialize(MockDeserializer::new(input.clone())).map(&mut convert).map_err(|e| e.to_string());
+        compare(&format!("input {}", input), derived, seeded);
+    }
+
+    assert!(mismatches.is_empty(), "seed differs from derived implementation on {} inputs\n{}", mismatches.len(), mismatches.join("\n"));
+}
+
+///
+/// A single check performed by a [`ConformanceHarness`].
+///
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ConformanceCheck {
+    ///
+    /// Serializing the value to the given format and deserializing it again produces the
+    /// expected value, see [`assert_roundtrip_seeded()`].
+    ///
+    Roundtrip { format: &'static str },
+    ///
+    /// Deserializing the [`MockValue`] of the serialized value produces the expected value.
+    ///
+    Mock,
+    ///
+    /// Deserializing the [`MockValue`] of the serialized value, with the entries of all maps
+    /// and structs in reverse order, produces the expected value.
+    ///
+    ReorderedFields,
+    ///
+    /// Deserializing the [`MockValue`] of the serialized value, with all string keys of maps
+    /// and structs replaced by their index, produces the expected value. This is how some
+    /// formats identify struct fields.
+    ///
+    NumericIdentifiers,
+    ///
+    /// Deserializing the [`MockValue`] of the serialized value, with the map or struct entry at
+    /// the given path removed, does not panic. It may still fail or succeed.
+    ///
+    MissingField { path: Path }
+}
+
+impl Display for ConformanceCheck {
+
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ConformanceCheck::Roundtrip { format } => write!(f, "roundtrip through {}", format),
+            ConformanceCheck::Mock => write!(f, "mock deserialization"),
+            ConformanceCheck::ReorderedFields => write!(f, "reordered fields"),
+            ConformanceCheck::NumericIdentifiers => write!(f, "numeric identifiers"),
+            ConformanceCheck::MissingField { path } => write!(f, "missing field {}", path)
+        }
+    }
+}
+
+///
+/// The result of running a [`ConformanceHarness`], containing the outcome of every
+/// performed check.
+///
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ConformanceReport {
+    pub results: Vec<(ConformanceCheck, Result<(), String>)>
+}
+
+impl ConformanceReport {
+
+    pub fn is_ok(&self) -> bool {
+        self.results.iter().all(|(_, result)| result.is_ok())
+    }
+
+    ///
+    /// Returns the failed checks, together with a description of the failure.
+    ///
+    pub fn failures(&self) -> impl Iterator<Item = (&ConformanceCheck, &str)> {
+        self.results.iter().filter_map(|(check, result)| result.as_ref().err().map(|e| (check, &e[..])))
+    }
+}
+
+impl Display for ConformanceReport {
+
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for (check, result) in &self.results {
+            match result {
+                Ok(()) => writeln!(f, "ok      {}", check)?,
+                Err(e) => writeln!(f, "FAILED  {}: {}", check, e)?
+            }
+        }
+        return Ok(());
+    }
+}
+
+///
+/// Checks whether a pair of a serializable wrapper and a [`DeserializeSeed`] follows the serde
+/// data model, by exercising it against all formats used by [`assert_roundtrip_seeded()`], and
+/// against [`MockValue`]s that well-behaved serializers do not produce, but which are valid
+/// inputs of the serde data model. In particular, these are
+///  - maps and structs with entries in a different order,
+///  - struct fields identified by their index instead of their name,
+///  - maps and structs with a missing entry, which must not cause a panic.
+///
+/// Note that the [`MockValue`] of the serialized value does not distinguish between maps and
+/// structs, hence the mock checks are only meaningful for seeds that deserialize structs, or
+/// maps whose order is irrelevant. Checks can be disabled if this is not the case.
+///
+/// # Example
+/// ```
+/// # use feanor_serde::impl_deserialize_seed_for_dependent_struct;
+/// # use feanor_serde::test::*;
+/// # use serde::Serialize;
+/// # use feanor_serde::default_seed::seed_of;
+/// #[derive(Serialize)]
+/// struct Point { x: i64, y: i64 }
+///
+/// struct PointDeserializeSeed;
+/// impl_deserialize_seed_for_dependent_struct!{
+///     pub struct DeserializedPoint<'de> using PointDeserializeSeed {
+///         x: i64: |_| seed_of::<i64>(),
+///         y: i64: |_| seed_of::<i64>()
+///     }
+/// }
+/// let report = ConformanceHarness::new(&Point { x: 1, y: 2 }, || PointDeserializeSeed, |point| point.x == 1 && point.y == 2).run();
+/// assert!(report.is_ok(), "{}", report);
+/// ```
+///
+pub struct ConformanceHarness<'a, T: ?Sized, F, E> {
+    value: &'a T,
+    seed: F,
+    is_expected: E,
+    reordered_fields: bool,
+    numeric_identifiers: bool,
+    missing_fields: bool
+}
+
+impl<'a, T, F, S, E> ConformanceHarness<'a, T, F, E>
+    where T: ?Sized + Serialize,
+        F: FnMut() -> S,
+        S: for<'de> DeserializeSeed<'de>,
+        E: for<'de> FnMut(&<S as DeserializeSeed<'de>>::Value) -> bool
+{
+    pub fn new(value: &'a T, seed: F, is_expected: E) -> Self {
+        Self { value: value, seed: seed, is_expected: is_expected, reordered_fields: true, numeric_identifiers: true, missing_fields: true }
+    }
+
+    pub fn with_reordered_fields(self, reordered_fields: bool) -> Self {
+        Self { reordered_fields, ..self }
+    }
+
+    pub fn with_numeric_identifiers(self, numeric_identifiers: bool) -> Self {
+        Self { numeric_identifiers, ..self }
+    }
+
+    pub fn with_missing_fields(self, missing_fields: bool) -> Self {
+        Self { missing_fields, ..self }
+    }
+
+    ///
+    /// Performs all enabled checks. Panics of the seed are caught and reported as failures.
+    ///
+    pub fn run(mut self) -> ConformanceReport {
+        let mut results = Vec::new();
+        let is_expected = &mut self.is_expected;
+
+        let value = self.value;
+        let seed = &mut self.seed;
+        let roundtrips = catch_panic(|| {
+            let mut roundtrips = Vec::new();
+            roundtrip_all_formats(value, &mut *seed, |format, result| roundtrips.push((format, expect_value(&mut *is_expected, result))));
+            return Ok(roundtrips);
+        });
+        match roundtrips {
+            Ok(roundtrips) => results.extend(roundtrips.into_iter().map(|(format, result)| (ConformanceCheck::Roundtrip { format }, result))),
+            Err(e) => results.push((ConformanceCheck::Roundtrip { format: "all formats" }, Err(e)))
+        }
+
+        let mock = match MockValue::from_serialize(self.value) {
+            Ok(mock) => mock,
+            Err(e) => {
+                results.push((ConformanceCheck::Mock, Err(format!("serialization failed: {}", e))));
+                return ConformanceReport { results };
+            }
+        };
+        // the outer result is `Err` if the seed panicked
+        let mut deserialize_mock = |input: MockValue| catch_panic(|| Ok(seed().deserialize(MockDeserializer::new(input)).map_err(|e| e.to_string())));
+        let mut expect_mock = |result: Result<Result<<S as DeserializeSeed<'static>>::Value, String>, String>| expect_value(&mut *is_expected, result.and_then(|result| result));
+        results.push((ConformanceCheck::Mock, expect_mock(deserialize_mock(mock.clone()))));
+        if self.reordered_fields {
+            results.push((ConformanceCheck::ReorderedFields, expect_mock(deserialize_mock(map_entries(&mock, &|entries| entries.into_iter().rev().collect())))));
+        }
+        if self.numeric_identifiers {
+            let numeric_identifiers = |entries: Vec<(MockValue, MockValue)>| entries.into_iter().enumerate().map(|(i, (key, value))| match key {
+                MockValue::Str(_) => (MockValue::U64(i as u64), value),
+                key => (key, value)
+            }).collect();
+            results.push((ConformanceCheck::NumericIdentifiers, expect_mock(deserialize_mock(map_entries(&mock, &numeric_identifiers)))));
+        }
+        if self.missing_fields {
+            for (path, input) in without_each_entry(&mock) {
+                results.push((ConformanceCheck::MissingField { path: Path::from_segments(path) }, deserialize_mock(input).map(|_| ())));
+            }
+        }
+        return ConformanceReport { results };
+    }
+
+    ///
+    /// Performs all enabled checks, and panics with the report if any of them fails.
+    ///
+    pub fn assert_conforms(self) {
+        let report = self.run();
+        assert!(report.is_ok(), "seed does not conform to the serde data model\n{}", report);
+    }
+}
+
+fn expect_value<V, E>(is_expected: &mut E, result: Result<V, String>) -> Result<(), String>
+    where E: FnMut(&V) -> bool
+{
+    match result {
+        Ok(result) if is_expected(&result) => Ok(()),
+        Ok(_) => Err("unexpected value".to_owned()),
+        Err(e) => Err(e)
+    }
+}
+
+fn catch_panic<F, V>(f: F) -> Result<V, String>
+    where F: FnOnce() -> Result<V, String>
+{
+    std::panic::catch_unwind(AssertUnwindSafe(f)).unwrap_or_else(|payload| {
+        let message = payload.downcast_ref::<String>().map(|s| &s[..]).or_else(|| payload.downcast_ref::<&str>().copied()).unwrap_or("<unknown>");
+        Err(format!("panicked: {}", message))
+    })
+}
+
+///
+/// Applies `f` to the entries of all maps and struct variants in `value`.
+///
+fn map_entries<F>(value: &MockValue, f: &F) -> MockValue
+    where F: Fn(Vec<(MockValue, MockValue)>) -> Vec<(MockValue, MockValue)>
+{
+    let map_all = |entries: &[(MockValue, MockValue)]| f(entries.iter().map(|(key, value)| (key.clone(), map_entries(value, f))).collect());
+    match value {
+        MockValue::Some(value) => MockValue::Some(Box::new(map_entries(value, f))),
+        MockValue::NewtypeStruct(value) => MockValue::NewtypeStruct(Box::new(map_entries(value, f))),
+        MockValue::Seq(elements) => MockValue::Seq(elements.iter().map(|element| map_entries(element, f)).collect()),
+        MockValue::Map(entries) => MockValue::Map(map_all(entries)),
+        MockValue::Enum(variant, MockVariant::Newtype(value)) => MockValue::Enum(variant.clone(), MockVariant::Newtype(Box::new(map_entries(value, f)))),
+        MockValue::Enum(variant, MockVariant::Tuple(elements)) => MockValue::Enum(variant.clone(), MockVariant::Tuple(elements.iter().map(|element| map_entries(element, f)).collect())),
+        MockValue::Enum(variant, MockVariant::Struct(entries)) => MockValue::Enum(variant.clone(), MockVariant::Struct(map_all(entries))),
+        value => value.clone()
+    }
+}
+
+///
+/// Returns all values obtained from `value` by removing a single entry of a map or struct variant,
+/// together with the path of the removed entry.
+///
+fn without_each_entry(value: &MockValue) -> Vec<(Vec<Segment>, MockValue)> {
+    fn nested<F>(segment: Segment, value: &MockValue, create: F, result: &mut Vec<(Vec<Segment>, MockValue)>)
+        where F: Fn(MockValue) -> MockValue
+    {
+        result.extend(without_each_entry(value).into_iter().map(|(mut path, value)| {
+            path.insert(0, segment.clone());
+            (path, create(value))
+        }));
+    }
+    fn seq<F>(elements: &[MockValue], create: F, result: &mut Vec<(Vec<Segment>, MockValue)>)
+        where F: Fn(Vec<MockValue>) -> MockValue
+    {
+        for i in 0..elements.len() {
+            nested(Segment::Seq { index: i }, &elements[i], |element| {
+                let mut elements = elements.to_vec();
+                elements[i] = element;
+                create(elements)
+            }, result);
+        }
+    }
+    fn map<F>(entries: &[(MockValue, MockValue)], create: F, result: &mut Vec<(Vec<Segment>, MockValue)>)
+        where F: Fn(Vec<(MockValue, MockValue)>) -> MockValue
+    {
+        for i in 0..entries.len() {
+            let segment = Segment::Map { key: match &entries[i].0 { MockValue::Str(key) => key.clone(), key => key.to_string() } };
+            let mut removed = entries.to_vec();
+            removed.remove(i);
+            result.push((vec![segment.clone()], create(removed)));
+            nested(segment, &entries[i].1, |value| {
+                let mut entries = entries.to_vec();
+                entries[i].1 = value;
+                create(entries)
+            }, result);
+        }
+    }
+
+    let mut result = Vec::new();
+    match value {
+        MockValue::Some(value) => result.extend(without_each_entry(value).into_iter().map(|(path, value)| (path, MockValue::some(value)))),
+        MockValue::NewtypeStruct(value) => result.extend(without_each_entry(value).into_iter().map(|(path, value)| (path, MockValue::newtype_struct(value)))),
+        MockValue::Seq(elements) => seq(elements, MockValue::Seq, &mut result),
+        MockValue::Map(entries) => map(entries, MockValue::Map, &mut result),
+        MockValue::Enum(variant, content) => {
+            let segment = Segment::Enum { variant: match &**variant { MockValue::Str(variant) => variant.clone(), variant => variant.to_string() } };
+            match content {
+                MockVariant::Unit => {},
+                MockVariant::Newtype(value) => result.extend(without_each_entry(value).into_iter().map(|(path, value)| (path, MockValue::Enum(variant.clone(), MockVariant::Newtype(Box::new(value)))))),
+                MockVariant::Tuple(elements) => seq(elements, |elements| MockValue::Enum(variant.clone(), MockVariant::Tuple(elements)), &mut result),
+                MockVariant::Struct(entries) => map(entries, |entries| MockValue::Enum(variant.clone(), MockVariant::Struct(entries)), &mut result)
+            }
+            for (path, _) in &mut result {
+                path.insert(0, segment.clone());
+            }
+        },
+        _ => {}
+    }
+    return result;
+}
+
+///
+/// The environment variable that causes [`assert_snapshot()`] to overwrite the snapshot
+/// files instead of comparing against them.
+///
+pub const UPDATE_SNAPSHOTS_ENV_VAR: &str = "FEANOR_SERDE_UPDATE_SNAPSHOTS";
+
+///
+/// Serializes `value` to every enabled format, and compares the result against snapshot files
+/// `<dir>/<name>.<format>`. This is meant to detect accidental changes of the wire format, e.g.
+/// when refactoring seeds or serializable wrappers.
+///
+/// Currently, the following snapshots are used:
+///  - `trace` and `compact.trace`, the calls to the serde data model as recorded by
+///    [`crate::trace::TraceSerializer`], human-readable and not human-readable respectively,
+///  - `json`, the pretty-printed JSON as produced by `serde_json`,
+///  - `postcard`, the postcard bytes in hex, if the feature `postcard` is enabled,
+///  - `cbor`, the CBOR bytes as written by `ciborium` in hex, if the feature `ciborium` is enabled,
+///  - `msgpack`, the MessagePack bytes with named struct fields in hex, if the feature `rmp-serde`
+///    is enabled,
+///  - `bincode`, the bincode bytes in hex, if the feature `bincode` is enabled.
+///
+/// If a snapshot file does not exist, or the environment variable [`UPDATE_SNAPSHOTS_ENV_VAR`]
+/// is set, the snapshot file is written instead. On mismatch, this panics with a line-based diff
+/// for each mismatching snapshot, where the data model trace is usually the most readable one.
+///
+/// # Example
+/// ```
+/// # use feanor_serde::test::*;
+/// # use feanor_serde::seq::*;
+/// let dir = std::env::temp_dir().join("feanor_serde_snapshot_doctest");
+/// let data = [1i64, 2, 3];
+/// // the first call creates the snapshots
+/// assert_snapshot(&SerializableSeq::new_with_len(data.iter(), data.len()), &dir, "seq");
+/// assert_snapshot(&SerializableSeq::new_with_len(data.iter(), data.len()), &dir, "seq");
+/// # std::fs::remove_dir_all(&dir).unwrap();
+/// ```
+///
+pub fn assert_snapshot<T, P>(value: &T, dir: P, name: &str)
+    where T: ?Sized + Serialize,
+        P: AsRef<std::path::Path>
+{
+    let dir = dir.as_ref();
+    let update = std::env::var_os(UPDATE_SNAPSHOTS_ENV_VAR).is_some();
+    let mut mismatches = Vec::new();
+    let mut check = |format: &str, result: Result<String, String>| {
+        let actual = result.unwrap_or_else(|e| panic!("serialization to {} failed: {}", format, e));
+        let path = dir.join(format!("{}.{}", name, format));
+        match std::fs::read_to_string(&path) {
+            Ok(expected) if !update => if expected != actual {
+                mismatches.push(format!("snapshot {} does not match\n{}", path.display(), line_diff(&expected, &actual)));
+            },
+            _ => {
+                std::fs::create_dir_all(dir).and_then(|_| std::fs::write(&path, &actual)).unwrap_or_else(|e| panic!("failed to write snapshot {}: {}", path.display(), e));
+            }
+        }
+    };
+
+    for (format, is_human_readable) in [("trace", true), ("compact.trace", false)] {
+        let calls = RefCell::new(Vec::new());
+        let result = value.serialize(TraceSerializer::new(&calls).with_human_readable(is_human_readable))
+            .map(|_| calls.into_inner().into_iter().map(|call| call + "\n").collect())
+            .map_err(|e| e.to_string());
+        check(format, result);
+    }
+
+    check("json", serde_json::to_string_pretty(value).map(|json| json + "\n").map_err(|e| e.to_string()));
+
+    #[cfg(feature = "postcard")]
+    check("postcard", ::postcard::to_allocvec(value).map(|serialized| hex_lines(&serialized)).map_err(|e| e.to_string()));
+    #[cfg(feature = "ciborium")]
+    check("cbor", crate::ciborium::to_vec(value).map(|serialized| hex_lines(&serialized)).map_err(|e| e.to_string()));
+    #[cfg(feature = "rmp-serde")]
+    check("msgpack", crate::rmp_serde::to_vec_named(value).map(|serialized| hex_lines(&serialized)).map_err(|e| e.to_string()));
+    #[cfg(feature = "bincode")]
+    check("bincode", crate::bincode::to_vec(value).map(|serialized| hex_lines(&serialized)).map_err(|e| e.to_string()));
+
+    assert!(mismatches.is_empty(), "{}\nrerun with {} set to update the snapshots", mismatches.join("\n"), UPDATE_SNAPSHOTS_ENV_VAR);
+}
+
+///
+/// Formats binary data as hex, with 16 bytes per line.
+///
+#[cfg(any(feature = "postcard", feature = "ciborium", feature = "rmp-serde", feature = "bincode"))]
+fn hex_lines(data: &[u8]) -> String {
+    data.chunks(16).map(|line| line.iter().map(|byte| format!("{:02x}", byte)).collect::<Vec<_>>().join(" ") + "\n").collect()
+}
+
+///
+/// Computes a line-based diff, based on the longest common subsequence, where removed lines
+/// are prefixed with `-` and added lines with `+`.
+///
+fn line_diff(expected: &str, actual: &str) -> String {
+    let expected = expected.lines().collect::<Vec<_>>();
+    let actual = actual.lines().collect::<Vec<_>>();
+    // lcs[i][j] is the length of the longest common subsequence of expected[i..] and actual[j..]
+    let mut lcs = vec![vec![0; actual.len() + 1]; expected.len() + 1];
+    for i in (0..expected.len()).rev() {
+        for j in (0..actual.len()).rev() {
+            lcs[i][j] = if expected[i] == actual[j] { lcs[i + 1][j + 1] + 1 } else { lcs[i + 1][j].max(lcs[i][j + 1]) };
+        }
+    }
+    let mut result = String::new();
+    let (mut i, mut j) = (0, 0);
+    while i < expected.len() || j < actual.len() {
+        if i < expected.len() && j < actual.len() && expected[i] == actual[j] {
+            result.push_str(&format!("  {}\n", expected[i]));
+            i += 1;
+            j += 1;
+        } else if i < expected.len() && (j == actual.len() || lcs[i + 1][j] >= lcs[i][j + 1]) {
+            result.push_str(&format!("- {}\n", expected[i]));
+            i += 1;
+        } else {
+            result.push_str(&format!("+ {}\n", actual[j]));
+            j += 1;
+        }
+    }
+    return result;
+}
+
+#[cfg(test)]
+use serde::Deserialize;
+#[cfg(test)]
+use std::iter::repeat;
+#[cfg(test)]
+use crate::seq::{DeserializeSeedSeq, SerializableSeq};
+#[cfg(test)]
+use crate::map::{DeserializeSeedMap, SerializableMap};
+#[cfg(test)]
+use crate::impl_deserialize_seed_for_dependent_struct;
+#[cfg(test)]
+use crate::impl_deserialize_seed_for_dependent_enum;
+
+#[test]
+fn test_seq() {
+    let tokens = [Token::Seq { len: Some(2) }, Token::I64(1), Token::I64(2), Token::SeqEnd];
+    assert_tokens_seed(
+        &SerializableSeq::new_with_len([1i64, 2].into_iter(), 2),
+        DeserializeSeedSeq::new(repeat(PhantomData::<i64>), Vec::new(), |mut current, next| { current.push(next); current }),
+        &tokens,
+        &vec![1, 2]
+    );
+    assert_de_tokens_seed_error(
+        DeserializeSeedSeq::new(repeat(PhantomData::<i64>), Vec::new(), |mut current, next| { current.push(next); current }),
+        &tokens[..3],
+        "end of tokens"
+    );
+    assert_de_tokens_seed_error(PhantomData::<i64>, &tokens[1..], "remaining tokens after deserialization");
+}
+
+#[test]
+fn test_map() {
+    let data = [(1i64, "a")];
+    let tokens = [Token::Map { len: Some(1) }, Token::I64(1), Token::Str("a".to_owned()), Token::MapEnd];
+    assert_ser_tokens_ctx(&SerializableMap::new_with_len(data.iter().map(|(k, v)| (k, v)), 1), &tokens);
+    assert_de_tokens_seed(
+        DeserializeSeedMap::new(repeat((PhantomData::<i64>, PhantomData::<String>)), Vec::new(), |mut current, k, v| { current.push((k, v)); current }),
+        &tokens,
+        &vec![(1, "a".to_owned())]
+    );
+}
+
+#[test]
+fn test_config() {
+    let config = TokenConfig::new().with_human_readable(false).with_self_describing(true);
+    assert_eq!(TokenConfig::new(), TokenConfig::default());
+    assert!(config.to_tokens(&5i64).unwrap() == [Token::I64(5)]);
+    assert_eq!(5, config.from_tokens_seed(PhantomData::<i64>, [Token::I64(5)]).unwrap());
+}
+
+#[test]
+fn test_roundtrip() {
+    let data = [(1i64, 2i64), (3, 4)];
+    assert_roundtrip_seeded(
+        &SerializableMap::new_with_len(data.iter().map(|(k, v)| (k, v)), data.len()),
+        || DeserializeSeedMap::new(repeat((PhantomData::<i64>, PhantomData::<i64>)), Vec::new(), |mut current, k, v| { current.push((k, v)); current }),
+        |result| result[..] == data[..]
+    );
+}
+
+#[test]
+#[should_panic(expected = "roundtrip through human-readable tokens produced unexpected value")]
+fn test_roundtrip_failed() {
+    assert_roundtrip_seeded(&5i64, || PhantomData::<i64>, |result| *result == 6);
+}
+
+#[test]
+fn test_line_diff() {
+    assert_eq!("  a\n- b\n+ c\n  d\n+ e\n", line_diff("a\nb\nd\n", "a\nc\nd\ne\n"));
+    assert_eq!("", line_diff("", ""));
+}
+
+#[test]
+fn test_snapshot() {
+    let dir = std::env::temp_dir().join(format!("feanor_serde_test_snapshot_{}", std::process::id()));
+    let data = [1i64, 2];
+    assert_snapshot(&SerializableSeq::new_with_len(data.iter(), data.len()), &dir, "seq");
+    assert_eq!("serialize_seq(Some(2))\nserialize_element\nserialize_i64(1)\nserialize_element\nserialize_i64(2)\nend\n", std::fs::read_to_string(dir.join("seq.trace")).unwrap());
+    assert_eq!("[\n  1,\n  2\n]\n", std::fs::read_to_string(dir.join("seq.json")).unwrap());
+    #[cfg(feature = "ciborium")]
+    assert_eq!("82 01 02\n", std::fs::read_to_string(dir.join("seq.cbor")).unwrap());
+    #[cfg(feature = "rmp-serde")]
+    assert_eq!("92 01 02\n", std::fs::read_to_string(dir.join("seq.msgpack")).unwrap());
+    #[cfg(feature = "bincode")]
+    assert_eq!("02 00 00 00 00 00 00 00 01 00 00 00 00 00 00 00\n02 00 00 00 00 00 00 00\n", std::fs::read_to_string(dir.join("seq.bincode")).unwrap());
+    assert_snapshot(&SerializableSeq::new_with_len(data.iter(), data.len()), &dir, "seq");
+
+    let changed = [1i64, 3];
+    let result = std::panic::catch_unwind(|| assert_snapshot(&SerializableSeq::new_with_len(changed.iter(), changed.len()), &dir, "seq"));
+    std::fs::remove_dir_all(&dir).unwrap();
+    let message = *result.err().unwrap().downcast::<String>().unwrap();
+    assert!(message.contains("- serialize_i64(2)\n+ serialize_i64(3)"));
+    assert!(message.contains("seq.json does not match"));
+}
+
+#[cfg(test)]
+struct DeserializeSeedRecord;
+
+#[cfg(test)]
+impl_deserialize_seed_for_dependent_struct!{
+    pub struct Record<'de> using DeserializeSeedRecord {
+        a: i64: |_| PhantomData::<i64>,
+        b: Option<Vec<i64>>: |_| PhantomData::<Option<Vec<i64>>>
+    }
+}
+
+#[test]
+fn test_conformance_harness() {
+    #[derive(Serialize)]
+    struct Record {
+        a: i64,
+        b: Option<Vec<i64>>
+    }
+    let report = ConformanceHarness::new(&Record { a: 1, b: Some(vec![2, 3]) }, || DeserializeSeedRecord, |result| result.a == 1 && result.b == Some(vec![2, 3])).run();
+    assert!(report.is_ok(), "{}", report);
+    assert!(report.results.iter().any(|(check, _)| *check == ConformanceCheck::ReorderedFields));
+    assert!(report.results.iter().any(|(check, _)| check.to_string() == "missing field .b"));
+
+    let data = [(1i64, 2i64), (3, 4)];
+    let seed = || DeserializeSeedMap::new(repeat((PhantomData::<i64>, PhantomData::<i64>)), Vec::new(), |mut current, k, v| { current.push((k, v)); current });
+    let report = ConformanceHarness::new(&SerializableMap::new_with_len(data.iter().map(|(k, v)| (k, v)), data.len()), seed, |result| result[..] == data[..]).run();
+    assert_eq!(vec![&ConformanceCheck::ReorderedFields], report.failures().map(|(check, _)| check).collect::<Vec<_>>());
+    assert!(report.to_string().contains("FAILED  reordered fields: unexpected value"));
+
+    let report = ConformanceHarness::new(&SerializableMap::new_with_len(data.iter().map(|(k, v)| (k, v)), data.len()), seed, |result| result[..] == data[..])
+        .with_reordered_fields(false)
+        .run();
+    assert!(report.is_ok(), "{}", report);
+}
+
+#[test]
+fn test_without_each_entry() {
+    let value = MockValue::seq([MockValue::struct_variant("A", [("x", MockValue::map([("y", 1i64)]))])]);
+    let result = without_each_entry(&value);
+    assert_eq!(vec![
+        (Path::from_segments(vec![Segment::Seq { index: 0 }, Segment::Enum { variant: "A".to_owned() }, Segment::Map { key: "x".to_owned() }]), MockValue::seq([MockValue::struct_variant("A", Vec::<(MockValue, MockValue)>::new())])),
+        (Path::from_segments(vec![Segment::Seq { index: 0 }, Segment::Enum { variant: "A".to_owned() }, Segment::Map { key: "x".to_owned() }, Segment::Map { key: "y".to_owned() }]), MockValue::seq([MockValue::struct_variant("A", [("x", MockValue::Map(Vec::new()))])]))
+    ], result.into_iter().map(|(path, value)| (Path::from_segments(path), value)).collect::<Vec<_>>());
+}
+
+#[test]
+fn test_derive_equivalent() {
+    #[derive(Serialize, Deserialize, PartialEq, Debug)]
+    struct Record {
+        a: i64,
+        b: Option<Vec<i64>>
+    }
+    assert_derive_equivalent(&Record { a: 1, b: Some(vec![2, 3]) }, || DeserializeSeedRecord, |result| Record { a: result.a, b: result.b });
+    assert_derive_equivalent(&Record { a: -1, b: None }, || DeserializeSeedRecord, |result| Record { a: result.a, b: result.b });
+
+    #[derive(Serialize, Deserialize, PartialEq, Debug)]
+    enum Foo {
+        A(i64),
+        B(String)
+    }
+    struct DeserializeSeedFoo;
+    impl_deserialize_seed_for_dependent_enum!{
+        pub enum DeserializedFoo<'de> using DeserializeSeedFoo {
+            A(i64): |_| PhantomData::<i64>,
+            B(String): |_| PhantomData::<String>
+        }
+    }
+    let convert = |result: DeserializedFoo<'_>| match result {
+        DeserializedFoo::A((a, _)) => Foo::A(a),
+        DeserializedFoo::B((b, _)) => Foo::B(b)
+    };
+    assert_derive_equivalent(&Foo::A(1), || DeserializeSeedFoo, convert);
+    assert_derive_equivalent(&Foo::B("x".to_owned()), || DeserializeSeedFoo, convert);
+}
+
+#[test]
+#[should_panic(expected = "seed differs from derived implementation")]
+fn test_derive_equivalent_failed() {
+    #[derive(Serialize, Deserialize, PartialEq, Debug)]
+    struct Record {
+        a: i64,
+        b: Option<Vec<i64>>
+    }
+    assert_derive_equivalent(&Record { a: 1, b: None }, || DeserializeSeedRecord, |result| Record { a: result.a + 1, b: result.b });
+}
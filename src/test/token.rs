@@ -0,0 +1,765 @@
+use std::fmt::{Display, Formatter};
+use std::collections::VecDeque;
+
+use serde::de::value::BorrowedStrDeserializer;
+use serde::de::{DeserializeSeed, Deserializer, EnumAccess, MapAccess, SeqAccess, VariantAccess, Visitor};
+use serde::ser::{SerializeMap, SerializeSeq, SerializeStruct, SerializeStructVariant, SerializeTuple, SerializeTupleStruct, SerializeTupleVariant};
+use serde::{Serialize, Serializer};
+
+///
+/// A single token of the serde data model, as produced by [`TokenSerializer`] and consumed
+/// by [`TokenDeserializer`].
+///
+/// In contrast to [`crate::mock::MockValue`], tokens are a flat representation that keeps
+/// the names and lengths passed to the [`Serializer`], and distinguishes e.g. tuples, sequences
+/// and tuple structs.
+///
+#[derive(Clone, Debug, PartialEq)]
+pub enum Token {
+    Bool(bool),
+    I8(i8),
+    I16(i16),
+    I32(i32),
+    I64(i64),
+    I128(i128),
+    U8(u8),
+    U16(u16),
+    U32(u32),
+    U64(u64),
+    U128(u128),
+    F32(f32),
+    F64(f64),
+    Char(char),
+    Str(String),
+    Bytes(Vec<u8>),
+    None,
+    Some,
+    Unit,
+    UnitStruct { name: &'static str },
+    UnitVariant { name: &'static str, variant_index: u32, variant: &'static str },
+    NewtypeStruct { name: &'static str },
+    NewtypeVariant { name: &'static str, variant_index: u32, variant: &'static str },
+    Seq { len: Option<usize> },
+    SeqEnd,
+    Tuple { len: usize },
+    TupleEnd,
+    TupleStruct { name: &'static str, len: usize },
+    TupleStructEnd,
+    TupleVariant { name: &'static str, variant_index: u32, variant: &'static str, len: usize },
+    TupleVariantEnd,
+    Map { len: Option<usize> },
+    MapEnd,
+    Field(&'static str),
+    SkippedField(&'static str),
+    Struct { name: &'static str, len: usize },
+    StructEnd,
+    StructVariant { name: &'static str, variant_index: u32, variant: &'static str, len: usize },
+    StructVariantEnd
+}
+
+///
+/// The sequence of tokens produced by serializing a single value.
+///
+pub type Tokens = Vec<Token>;
+
+///
+/// The error of [`TokenSerializer`] and [`TokenDeserializer`].
+///
+#[derive(Clone, Debug, PartialEq)]
+pub enum TokenError {
+    EndOfTokens,
+    UnexpectedToken(Token),
+    UnsupportedDeserializeAny,
+    Custom(String)
+}
+
+impl Display for TokenError {
+
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            TokenError::EndOfTokens => write!(f, "end of tokens"),
+            TokenError::UnexpectedToken(token) => write!(f, "unexpected token {:?}", token),
+            TokenError::UnsupportedDeserializeAny => write!(f, "deserialize_any() is not supported by a non-self-describing deserializer"),
+            TokenError::Custom(message) => write!(f, "{}", message)
+        }
+    }
+}
+
+impl std::error::Error for TokenError {}
+
+impl serde::ser::Error for TokenError {
+
+    fn custom<T: Display>(msg: T) -> Self {
+        TokenError::Custom(msg.to_string())
+    }
+}
+
+impl serde::de::Error for TokenError {
+
+    fn custom<T: Display>(msg: T) -> Self {
+        TokenError::Custom(msg.to_string())
+    }
+}
+
+///
+/// A [`Serializer`] that produces the [`Tokens`] corresponding to the serialized data.
+///
+/// # Example
+/// ```
+/// # use feanor_serde::test::token::*;
+/// # use serde::Serialize;
+/// assert_eq!(vec![Token::Tuple { len: 2 }, Token::I32(1), Token::Str("a".to_owned()), Token::TupleEnd], (1i32, "a").serialize(TokenSerializer::new()).unwrap());
+/// ```
+///
+#[derive(Clone, Copy, Debug)]
+pub struct TokenSerializer {
+    is_human_readable: bool
+}
+
+impl TokenSerializer {
+
+    pub fn new() -> Self {
+        Self { is_human_readable: true }
+    }
+
+    pub fn with_human_readable(self, is_human_readable: bool) -> Self {
+        Self { is_human_readable: is_human_readable }
+    }
+}
+
+impl Default for TokenSerializer {
+
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Serializer for TokenSerializer {
+    type Ok = Tokens;
+    type Error = TokenError;
+    type SerializeSeq = SerializeTokens;
+    type SerializeTuple = SerializeTokens;
+    type SerializeTupleStruct = SerializeTokens;
+    type SerializeTupleVariant = SerializeTokens;
+    type SerializeMap = SerializeTokens;
+    type SerializeStruct = SerializeTokens;
+    type SerializeStructVariant = SerializeTokens;
+
+    fn serialize_bool(self, v: bool) -> Result<Tokens, TokenError> { Ok(vec![Token::Bool(v)]) }
+    fn serialize_i8(self, v: i8) -> Result<Tokens, TokenError> { Ok(vec![Token::I8(v)]) }
+    fn serialize_i16(self, v: i16) -> Result<Tokens, TokenError> { Ok(vec![Token::I16(v)]) }
+    fn serialize_i32(self, v: i32) -> Result<Tokens, TokenError> { Ok(vec![Token::I32(v)]) }
+    fn serialize_i64(self, v: i64) -> Result<Tokens, TokenError> { Ok(vec![Token::I64(v)]) }
+    fn serialize_i128(self, v: i128) -> Result<Tokens, TokenError> { Ok(vec![Token::I128(v)]) }
+    fn serialize_u8(self, v: u8) -> Result<Tokens, TokenError> { Ok(vec![Token::U8(v)]) }
+    fn serialize_u16(self, v: u16) -> Result<Tokens, TokenError> { Ok(vec![Token::U16(v)]) }
+    fn serialize_u32(self, v: u32) -> Result<Tokens, TokenError> { Ok(vec![Token::U32(v)]) }
+    fn serialize_u64(self, v: u64) -> Result<Tokens, TokenError> { Ok(vec![Token::U64(v)]) }
+    fn serialize_u128(self, v: u128) -> Result<Tokens, TokenError> { Ok(vec![Token::U128(v)]) }
+    fn serialize_f32(self, v: f32) -> Result<Tokens, TokenError> { Ok(vec![Token::F32(v)]) }
+    fn serialize_f64(self, v: f64) -> Result<Tokens, TokenError> { Ok(vec![Token::F64(v)]) }
+    fn serialize_char(self, v: char) -> Result<Tokens, TokenError> { Ok(vec![Token::Char(v)]) }
+    fn serialize_str(self, v: &str) -> Result<Tokens, TokenError> { Ok(vec![Token::Str(v.to_owned())]) }
+    fn serialize_bytes(self, v: &[u8]) -> Result<Tokens, TokenError> { Ok(vec![Token::Bytes(v.to_vec())]) }
+    fn serialize_none(self) -> Result<Tokens, TokenError> { Ok(vec![Token::None]) }
+    fn serialize_unit(self) -> Result<Tokens, TokenError> { Ok(vec![Token::Unit]) }
+    fn serialize_unit_struct(self, name: &'static str) -> Result<Tokens, TokenError> { Ok(vec![Token::UnitStruct { name }]) }
+
+    fn serialize_some<T>(self, value: &T) -> Result<Tokens, TokenError>
+        where T: ?Sized + Serialize
+    {
+        let mut result = vec![Token::Some];
+        result.extend(value.serialize(self)?);
+        return Ok(result);
+    }
+
+    fn serialize_unit_variant(self, name: &'static str, variant_index: u32, variant: &'static str) -> Result<Tokens, TokenError> {
+        Ok(vec![Token::UnitVariant { name, variant_index, variant }])
+    }
+
+    fn serialize_newtype_struct<T>(self, name: &'static str, value: &T) -> Result<Tokens, TokenError>
+        where T: ?Sized + Serialize
+    {
+        let mut result = vec![Token::NewtypeStruct { name }];
+        result.extend(value.serialize(self)?);
+        return Ok(result);
+    }
+
+    fn serialize_newtype_variant<T>(self, name: &'static str, variant_index: u32, variant: &'static str, value: &T) -> Result<Tokens, TokenError>
+        where T: ?Sized + Serialize
+    {
+        let mut result = vec![Token::NewtypeVariant { name, variant_index, variant }];
+        result.extend(value.serialize(self)?);
+        return Ok(result);
+    }
+
+    fn serialize_seq(self, len: Option<usize>) -> Result<SerializeTokens, TokenError> {
+        Ok(SerializeTokens::new(self, Token::Seq { len }, Token::SeqEnd))
+    }
+
+    fn serialize_tuple(self, len: usize) -> Result<SerializeTokens, TokenError> {
+        Ok(SerializeTokens::new(self, Token::Tuple { len }, Token::TupleEnd))
+    }
+
+    fn serialize_tuple_struct(self, name: &'static str, len: usize) -> Result<SerializeTokens, TokenError> {
+        Ok(SerializeTokens::new(self, Token::TupleStruct { name, len }, Token::TupleStructEnd))
+    }
+
+    fn serialize_tuple_variant(self, name: &'static str, variant_index: u32, variant: &'static str, len: usize) -> Result<SerializeTokens, TokenError> {
+        Ok(SerializeTokens::new(self, Token::TupleVariant { name, variant_index, variant, len }, Token::TupleVariantEnd))
+    }
+
+    fn serialize_map(self, len: Option<usize>) -> Result<SerializeTokens, TokenError> {
+        Ok(SerializeTokens::new(self, Token::Map { len }, Token::MapEnd))
+    }
+
+    fn serialize_struct(self, name: &'static str, len: usize) -> Result<SerializeTokens, TokenError> {
+        Ok(SerializeTokens::new(self, Token::Struct { name, len }, Token::StructEnd))
+    }
+
+    fn serialize_struct_variant(self, name: &'static str, variant_index: u32, variant: &'static str, len: usize) -> Result<SerializeTokens, TokenError> {
+        Ok(SerializeTokens::new(self, Token::StructVariant { name, variant_index, variant, len }, Token::StructVariantEnd))
+    }
+
+    fn is_human_readable(&self) -> bool {
+        self.is_human_readable
+    }
+}
+
+///
+/// The compound serializer of [`TokenSerializer`], used for sequences, tuples, maps and structs.
+///
+pub struct SerializeTokens {
+    serializer: TokenSerializer,
+    tokens: Tokens,
+    end: Token
+}
+
+impl SerializeTokens {
+
+    fn new(serializer: TokenSerializer, start: Token, end: Token) -> Self {
+        Self { serializer: serializer, tokens: vec![start], end: end }
+    }
+
+    fn push<T>(&mut self, value: &T) -> Result<(), TokenError>
+        where T: ?Sized + Serialize
+    {
+        self.tokens.extend(value.serialize(self.serializer)?);
+        return Ok(());
+    }
+
+    fn finish(mut self) -> Result<Tokens, TokenError> {
+        self.tokens.push(self.end);
+        return Ok(self.tokens);
+    }
+}
+
+impl SerializeSeq for SerializeTokens {
+    type Ok = Tokens;
+    type Error = TokenError;
+
+    fn serialize_element<T>(&mut self, value: &T) -> Result<(), TokenError>
+        where T: ?Sized + Serialize
+    {
+        self.push(value)
+    }
+
+    fn end(self) -> Result<Tokens, TokenError> {
+        self.finish()
+    }
+}
+
+impl SerializeTuple for SerializeTokens {
+    type Ok = Tokens;
+    type Error = TokenError;
+
+    fn serialize_element<T>(&mut self, value: &T) -> Result<(), TokenError>
+        where T: ?Sized + Serialize
+    {
+        self.push(value)
+    }
+
+    fn end(self) -> Result<Tokens, TokenError> {
+        self.finish()
+    }
+}
+
+impl SerializeTupleStruct for SerializeTokens {
+    type Ok = Tokens;
+    type Error = TokenError;
+
+    fn serialize_field<T>(&mut self, value: &T) -> Result<(), TokenError>
+        where T: ?Sized + Serialize
+    {
+        self.push(value)
+    }
+
+    fn end(self) -> Result<Tokens, TokenError> {
+        self.finish()
+    }
+}
+
+impl SerializeTupleVariant for SerializeTokens {
+    type Ok = Tokens;
+    type Error = TokenError;
+
+    fn serialize_field<T>(&mut self, value: &T) -> Result<(), TokenError>
+        where T: ?Sized + Serialize
+    {
+        self.push(value)
+    }
+
+    fn end(self) -> Result<Tokens, TokenError> {
+        self.finish()
+    }
+}
+
+impl SerializeMap for SerializeTokens {
+    type Ok = Tokens;
+    type Error = TokenError;
+
+    fn serialize_key<T>(&mut self, key: &T) -> Result<(), TokenError>
+        where T: ?Sized + Serialize
+    {
+        self.push(key)
+    }
+
+    fn serialize_value<T>(&mut self, value: &T) -> Result<(), TokenError>
+        where T: ?Sized + Serialize
+    {
+        self.push(value)
+    }
+
+    fn end(self) -> Result<Tokens, TokenError> {
+        self.finish()
+    }
+}
+
+impl SerializeStruct for SerializeTokens {
+    type Ok = Tokens;
+    type Error = TokenError;
+
+    fn serialize_field<T>(&mut self, key: &'static str, value: &T) -> Result<(), TokenError>
+        where T: ?Sized + Serialize
+    {
+        self.tokens.push(Token::Field(key));
+        self.push(value)
+    }
+
+    fn skip_field(&mut self, key: &'static str) -> Result<(), TokenError> {
+        self.tokens.push(Token::SkippedField(key));
+        return Ok(());
+    }
+
+    fn end(self) -> Result<Tokens, TokenError> {
+        self.finish()
+    }
+}
+
+impl SerializeStructVariant for SerializeTokens {
+    type Ok = Tokens;
+    type Error = TokenError;
+
+    fn serialize_field<T>(&mut self, key: &'static str, value: &T) -> Result<(), TokenError>
+        where T: ?Sized + Serialize
+    {
+        self.tokens.push(Token::Field(key));
+        self.push(value)
+    }
+
+    fn skip_field(&mut self, key: &'static str) -> Result<(), TokenError> {
+        self.tokens.push(Token::SkippedField(key));
+        return Ok(());
+    }
+
+    fn end(self) -> Result<Tokens, TokenError> {
+        self.finish()
+    }
+}
+
+///
+/// A [`Deserializer`] that reads values from a sequence of [`Token`]s.
+///
+/// Every method of [`Deserializer`] passes the next value to the visitor as described by the
+/// tokens, independent of the type hint. However, unless the deserializer is configured to be
+/// self-describing, [`Deserializer::deserialize_any()`] fails, so that seeds relying on it are
+/// caught by tests.
+///
+/// # Example
+/// ```
+/// # use feanor_serde::test::token::*;
+/// # use serde::Deserialize;
+/// let mut deserializer = TokenDeserializer::new([Token::Seq { len: Some(1) }, Token::I64(1), Token::SeqEnd]);
+/// assert_eq!(vec![1], Vec::<i64>::deserialize(&mut deserializer).unwrap());
+/// assert!(deserializer.is_empty());
+/// ```
+///
+#[derive(Clone, Debug)]
+pub struct TokenDeserializer {
+    tokens: VecDeque<Token>,
+    is_human_readable: bool,
+    self_describing: bool
+}
+
+impl TokenDeserializer {
+
+    pub fn new<I>(tokens: I) -> Self
+        where I: IntoIterator<Item = Token>
+    {
+        Self { tokens: tokens.into_iter().collect(), is_human_readable: true, self_describing: false }
+    }
+
+    pub fn with_human_readable(self, is_human_readable: bool) -> Self {
+        Self { is_human_readable, ..self }
+    }
+
+    pub fn with_self_describing(self, self_describing: bool) -> Self {
+        Self { self_describing, ..self }
+    }
+
+    ///
+    /// Returns whether all tokens have been consumed.
+    ///
+    pub fn is_empty(&self) -> bool {
+        self.tokens.is_empty()
+    }
+
+    fn next_token(&mut self) -> Result<Token, TokenError> {
+        self.tokens.pop_front().ok_or(TokenError::EndOfTokens)
+    }
+
+    fn next_is(&self, token: &Token) -> bool {
+        self.tokens.front() == Some(token)
+    }
+
+    fn expect(&mut self, end: Token) -> Result<(), TokenError> {
+        match self.next_token()? {
+            token if token == end => Ok(()),
+            token => Err(TokenError::UnexpectedToken(token))
+        }
+    }
+
+    fn skip_value(&mut self) -> Result<(), TokenError> {
+        let end = match self.next_token()? {
+            Token::Some | Token::NewtypeStruct { .. } | Token::NewtypeVariant { .. } => return self.skip_value(),
+            Token::Seq { .. } => Token::SeqEnd,
+            Token::Tuple { .. } => Token::TupleEnd,
+            Token::TupleStruct { .. } => Token::TupleStructEnd,
+            Token::TupleVariant { .. } => Token::TupleVariantEnd,
+            Token::Map { .. } => Token::MapEnd,
+            Token::Struct { .. } => Token::StructEnd,
+            Token::StructVariant { .. } => Token::StructVariantEnd,
+            _ => return Ok(())
+        };
+        while !self.next_is(&end) {
+            self.skip_value()?;
+        }
+        return self.expect(end);
+    }
+
+    fn deserialize_next<'de, V>(&mut self, visitor: V) -> Result<V::Value, TokenError>
+        where V: Visitor<'de>
+    {
+        match self.next_token()? {
+            Token::Bool(v) => visitor.visit_bool(v),
+            Token::I8(v) => visitor.visit_i8(v),
+            Token::I16(v) => visitor.visit_i16(v),
+            Token::I32(v) => visitor.visit_i32(v),
+            Token::I64(v) => visitor.visit_i64(v),
+            Token::I128(v) => visitor.visit_i128(v),
+            Token::U8(v) => visitor.visit_u8(v),
+            Token::U16(v) => visitor.visit_u16(v),
+            Token::U32(v) => visitor.visit_u32(v),
+            Token::U64(v) => visitor.visit_u64(v),
+            Token::U128(v) => visitor.visit_u128(v),
+            Token::F32(v) => visitor.visit_f32(v),
+            Token::F64(v) => visitor.visit_f64(v),
+            Token::Char(v) => visitor.visit_char(v),
+            Token::Str(v) => visitor.visit_string(v),
+            Token::Bytes(v) => visitor.visit_byte_buf(v),
+            Token::Field(name) => visitor.visit_borrowed_str(name),
+            Token::None => visitor.visit_none(),
+            Token::Some => visitor.visit_some(self),
+            Token::Unit | Token::UnitStruct { .. } => visitor.visit_unit(),
+            Token::NewtypeStruct { .. } => visitor.visit_newtype_struct(self),
+            Token::Seq { .. } => self.visit_seq(visitor, Token::SeqEnd),
+            Token::Tuple { .. } => self.visit_seq(visitor, Token::TupleEnd),
+            Token::TupleStruct { .. } => self.visit_seq(visitor, Token::TupleStructEnd),
+            Token::Map { .. } => self.visit_map(visitor, Token::MapEnd),
+            Token::Struct { .. } => self.visit_map(visitor, Token::StructEnd),
+            token @ (Token::UnitVariant { .. } | Token::NewtypeVariant { .. } | Token::TupleVariant { .. } | Token::StructVariant { .. }) => {
+                visitor.visit_enum(TokenEnumAccess { deserializer: self, token: token })
+            },
+            token => Err(TokenError::UnexpectedToken(token))
+        }
+    }
+
+    fn visit_seq<'de, V>(&mut self, visitor: V, end: Token) -> Result<V::Value, TokenError>
+        where V: Visitor<'de>
+    {
+        let result = visitor.visit_seq(TokenSeqAccess { deserializer: self, end: &end })?;
+        self.expect(end)?;
+        return Ok(result);
+    }
+
+    fn visit_map<'de, V>(&mut self, visitor: V, end: Token) -> Result<V::Value, TokenError>
+        where V: Visitor<'de>
+    {
+        let result = visitor.visit_map(TokenSeqAccess { deserializer: self, end: &end })?;
+        self.skip_skipped_fields();
+        self.expect(end)?;
+        return Ok(result);
+    }
+
+    fn skip_skipped_fields(&mut self) {
+        while let Some(Token::SkippedField(_)) = self.tokens.front() {
+            self.tokens.pop_front();
+        }
+    }
+}
+
+macro_rules! forward_to_deserialize_next {
+    ($($method:ident),*) => {
+        $(
+            fn $method<V>(self, visitor: V) -> Result<V::Value, TokenError>
+                where V: Visitor<'de>
+            {
+                self.deserialize_next(visitor)
+            }
+        )*
+    };
+}
+
+impl<'de> Deserializer<'de> for &mut TokenDeserializer {
+    type Error = TokenError;
+
+    fn deserialize_any<V>(self, visitor: V) -> Result<V::Value, TokenError>
+        where V: Visitor<'de>
+    {
+        if !self.self_describing {
+            return Err(TokenError::UnsupportedDeserializeAny);
+        }
+        self.deserialize_next(visitor)
+    }
+
+    fn deserialize_ignored_any<V>(self, visitor: V) -> Result<V::Value, TokenError>
+        where V: Visitor<'de>
+    {
+        self.skip_value()?;
+        visitor.visit_unit()
+    }
+
+    fn deserialize_unit_struct<V>(self, _name: &'static str, visitor: V) -> Result<V::Value, TokenError>
+        where V: Visitor<'de>
+    {
+        self.deserialize_next(visitor)
+    }
+
+    fn deserialize_newtype_struct<V>(self, _name: &'static str, visitor: V) -> Result<V::Value, TokenError>
+        where V: Visitor<'de>
+    {
+        self.deserialize_next(visitor)
+    }
+
+    fn deserialize_tuple<V>(self, _len: usize, visitor: V) -> Result<V::Value, TokenError>
+        where V: Visitor<'de>
+    {
+        self.deserialize_next(visitor)
+    }
+
+    fn deserialize_tuple_struct<V>(self, _name: &'static str, _len: usize, visitor: V) -> Result<V::Value, TokenError>
+        where V: Visitor<'de>
+    {
+        self.deserialize_next(visitor)
+    }
+
+    fn deserialize_struct<V>(self, _name: &'static str, _fields: &'static [&'static str], visitor: V) -> Result<V::Value, TokenError>
+        where V: Visitor<'de>
+    {
+        self.deserialize_next(visitor)
+    }
+
+    fn deserialize_enum<V>(self, _name: &'static str, _variants: &'static [&'static str], visitor: V) -> Result<V::Value, TokenError>
+        where V: Visitor<'de>
+    {
+        self.deserialize_next(visitor)
+    }
+
+    forward_to_deserialize_next!{
+        deserialize_bool, deserialize_i8, deserialize_i16, deserialize_i32, deserialize_i64, deserialize_i128,
+        deserialize_u8, deserialize_u16, deserialize_u32, deserialize_u64, deserialize_u128, deserialize_f32, deserialize_f64,
+        deserialize_char, deserialize_str, deserialize_string, deserialize_bytes, deserialize_byte_buf, deserialize_option,
+        deserialize_unit, deserialize_seq, deserialize_map, deserialize_identifier
+    }
+
+    fn is_human_readable(&self) -> bool {
+        self.is_human_readable
+    }
+}
+
+struct TokenSeqAccess<'a, 'b> {
+    deserializer: &'a mut TokenDeserializer,
+    end: &'b Token
+}
+
+impl<'de, 'a, 'b> SeqAccess<'de> for TokenSeqAccess<'a, 'b> {
+    type Error = TokenError;
+
+    fn next_element_seed<T>(&mut self, seed: T) -> Result<Option<T::Value>, TokenError>
+        where T: DeserializeSeed<'de>
+    {
+        if self.deserializer.next_is(self.end) {
+            return Ok(None);
+        }
+        return seed.deserialize(&mut *self.deserializer).map(Some);
+    }
+}
+
+impl<'de, 'a, 'b> MapAccess<'de> for TokenSeqAccess<'a, 'b> {
+    type Error = TokenError;
+
+    fn next_key_seed<K>(&mut self, seed: K) -> Result<Option<K::Value>, TokenError>
+        where K: DeserializeSeed<'de>
+    {
+        self.deserializer.skip_skipped_fields();
+        if self.deserializer.next_is(self.end) {
+            return Ok(None);
+        }
+        return seed.deserialize(&mut *self.deserializer).map(Some);
+    }
+
+    fn next_value_seed<V>(&mut self, seed: V) -> Result<V::Value, TokenError>
+        where V: DeserializeSeed<'de>
+    {
+        seed.deserialize(&mut *self.deserializer)
+    }
+}
+
+struct TokenEnumAccess<'a> {
+    deserializer: &'a mut TokenDeserializer,
+    token: Token
+}
+
+impl<'de, 'a> EnumAccess<'de> for TokenEnumAccess<'a> {
+    type Error = TokenError;
+    type Variant = Self;
+
+    fn variant_seed<V>(self, seed: V) -> Result<(V::Value, Self), TokenError>
+        where V: DeserializeSeed<'de>
+    {
+        let variant = match &self.token {
+            Token::UnitVariant { variant, .. } |
+                Token::NewtypeVariant { variant, .. } |
+                Token::TupleVariant { variant, .. } |
+                Token::StructVariant { variant, .. } => *variant,
+            _ => unreachable!()
+        };
+        let result = seed.deserialize(BorrowedStrDeserializer::<TokenError>::new(variant))?;
+        return Ok((result, self));
+    }
+}
+
+impl<'de, 'a> VariantAccess<'de> for TokenEnumAccess<'a> {
+    type Error = TokenError;
+
+    fn unit_variant(self) -> Result<(), TokenError> {
+        match self.token {
+            Token::UnitVariant { .. } => Ok(()),
+            token => Err(TokenError::UnexpectedToken(token))
+        }
+    }
+
+    fn newtype_variant_seed<T>(self, seed: T) -> Result<T::Value, TokenError>
+        where T: DeserializeSeed<'de>
+    {
+        match self.token {
+            Token::NewtypeVariant { .. } => seed.deserialize(self.deserializer),
+            token => Err(TokenError::UnexpectedToken(token))
+        }
+    }
+
+    fn tuple_variant<V>(self, _len: usize, visitor: V) -> Result<V::Value, TokenError>
+        where V: Visitor<'de>
+    {
+        match self.token {
+            Token::TupleVariant { .. } => self.deserializer.visit_seq(visitor, Token::TupleVariantEnd),
+            token => Err(TokenError::UnexpectedToken(token))
+        }
+    }
+
+    fn struct_variant<V>(self, _fields: &'static [&'static str], visitor: V) -> Result<V::Value, TokenError>
+        where V: Visitor<'de>
+    {
+        match self.token {
+            Token::StructVariant { .. } => self.deserializer.visit_map(visitor, Token::StructVariantEnd),
+            token => Err(TokenError::UnexpectedToken(token))
+        }
+    }
+}
+
+#[cfg(test)]
+use serde::Deserialize;
+
+#[test]
+fn test_roundtrip_enum() {
+    #[derive(Serialize, Deserialize, PartialEq, Debug)]
+    enum Foo {
+        A,
+        B(i64),
+        C(i64, String),
+        D { x: Option<i64>, #[serde(skip_serializing_if = "Option::is_none")] y: Option<u8> }
+    }
+    let data = vec![Foo::A, Foo::B(1), Foo::C(2, "a".to_owned()), Foo::D { x: Some(3), y: None }];
+    let tokens = data.serialize(TokenSerializer::new()).unwrap();
+    assert_eq!(vec![
+        Token::Seq { len: Some(4) },
+        Token::UnitVariant { name: "Foo", variant_index: 0, variant: "A" },
+        Token::NewtypeVariant { name: "Foo", variant_index: 1, variant: "B" },
+        Token::I64(1),
+        Token::TupleVariant { name: "Foo", variant_index: 2, variant: "C", len: 2 },
+        Token::I64(2),
+        Token::Str("a".to_owned()),
+        Token::TupleVariantEnd,
+        Token::StructVariant { name: "Foo", variant_index: 3, variant: "D", len: 1 },
+        Token::Field("x"),
+        Token::Some,
+        Token::I64(3),
+        Token::SkippedField("y"),
+        Token::StructVariantEnd,
+        Token::SeqEnd
+    ], tokens);
+    let mut deserializer = TokenDeserializer::new(tokens);
+    assert_eq!(data, Vec::<Foo>::deserialize(&mut deserializer).unwrap());
+    assert!(deserializer.is_empty());
+}
+
+#[test]
+fn test_ignored_any() {
+    #[derive(Deserialize, PartialEq, Debug)]
+    struct Foo {
+        a: i64
+    }
+    let tokens = [
+        Token::Struct { name: "Foo", len: 2 },
+        Token::Field("b"),
+        Token::Seq { len: None },
+        Token::Map { len: Some(1) },
+        Token::I64(0),
+        Token::Some,
+        Token::Unit,
+        Token::MapEnd,
+        Token::SeqEnd,
+        Token::Field("a"),
+        Token::I64(1),
+        Token::StructEnd
+    ];
+    assert_eq!(Foo { a: 1 }, Foo::deserialize(&mut TokenDeserializer::new(tokens)).unwrap());
+}
+
+#[test]
+fn test_errors() {
+    assert_eq!(TokenError::UnsupportedDeserializeAny, serde_json::Value::deserialize(&mut TokenDeserializer::new([Token::I64(0)])).unwrap_err());
+    assert_eq!(serde_json::Value::from(0), serde_json::Value::deserialize(&mut TokenDeserializer::new([Token::I64(0)]).with_self_describing(true)).unwrap());
+    assert_eq!(TokenError::EndOfTokens, i64::deserialize(&mut TokenDeserializer::new([])).unwrap_err());
+    assert_eq!(TokenError::UnexpectedToken(Token::I64(2)), <(i64,)>::deserialize(&mut TokenDeserializer::new([Token::Tuple { len: 2 }, Token::I64(1), Token::I64(2), Token::TupleEnd])).unwrap_err());
+}
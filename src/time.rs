@@ -0,0 +1,73 @@
+use ::time::format_description::well_known::Rfc3339;
+use ::time::{Date, OffsetDateTime, PrimitiveDateTime};
+
+use crate::timestamp::Timestamp;
+
+///
+/// Custom formats are format descriptions as described in [`time::format_description::parse()`].
+/// When parsing, the offset may be omitted, in which case the timestamp is taken to be in UTC,
+/// and the time may be omitted, in which case it is taken to be midnight.
+///
+impl Timestamp for OffsetDateTime {
+
+    fn from_epoch_seconds(seconds: i64) -> Option<Self> {
+        OffsetDateTime::from_unix_timestamp(seconds).ok()
+    }
+
+    fn epoch_seconds(&self) -> Option<i64> {
+        Some(self.unix_timestamp())
+    }
+
+    fn from_epoch_millis(millis: i64) -> Option<Self> {
+        OffsetDateTime::from_unix_timestamp_nanos(millis as i128 * 1_000_000).ok()
+    }
+
+    fn epoch_millis(&self) -> Option<i64> {
+        i64::try_from(self.unix_timestamp_nanos().div_euclid(1_000_000)).ok()
+    }
+
+    fn parse_rfc3339(value: &str) -> Option<Self> {
+        OffsetDateTime::parse(value, &Rfc3339).ok()
+    }
+
+    fn to_rfc3339(&self) -> Option<String> {
+        self.format(&Rfc3339).ok()
+    }
+
+    fn parse_custom(value: &str, format: &str) -> Option<Self> {
+        let format = ::time::format_description::parse_borrowed::<2>(format).ok()?;
+        return OffsetDateTime::parse(value, &format).ok()
+            .or_else(|| PrimitiveDateTime::parse(value, &format).ok().map(PrimitiveDateTime::assume_utc))
+            .or_else(|| Date::parse(value, &format).ok().map(|date| date.midnight().assume_utc()));
+    }
+
+    fn format_custom(&self, format: &str) -> Option<String> {
+        let format = ::time::format_description::parse_borrowed::<2>(format).ok()?;
+        return self.format(&format).ok();
+    }
+}
+
+#[cfg(test)]
+use crate::timestamp::{SerializableTimestamp, DeserializeSeedTimestamp, TimestampFormat};
+#[cfg(test)]
+use serde::de::DeserializeSeed;
+
+#[test]
+fn test_serde_json() {
+    let timestamp = OffsetDateTime::from_unix_timestamp_nanos(-1_500_000_000).unwrap();
+    let formats = [TimestampFormat::EpochSeconds, TimestampFormat::EpochMillis, TimestampFormat::Rfc3339, TimestampFormat::Custom("[year]-[month]-[day] [hour]:[minute]:[second].[subsecond digits:3]")];
+    let expected = ["-2", "-1500", "\"1969-12-31T23:59:58.5Z\"", "\"1969-12-31 23:59:58.500\""];
+    for (format, expected) in formats.into_iter().zip(expected) {
+        assert_eq!(expected, serde_json::to_string(&SerializableTimestamp::new(&timestamp, format)).unwrap());
+        let result: OffsetDateTime = DeserializeSeedTimestamp::new(format).deserialize(&mut serde_json::Deserializer::from_str(expected)).unwrap();
+        if format == TimestampFormat::EpochSeconds {
+            assert_eq!(OffsetDateTime::from_unix_timestamp(-2).unwrap(), result);
+        } else {
+            assert_eq!(timestamp, result);
+        }
+    }
+    let seed = DeserializeSeedTimestamp::<OffsetDateTime>::new(TimestampFormat::Custom("[day].[month].[year]"));
+    assert_eq!(OffsetDateTime::UNIX_EPOCH, seed.deserialize(&mut serde_json::Deserializer::from_str("\"01.01.1970\"")).unwrap());
+    assert!(seed.deserialize(&mut serde_json::Deserializer::from_str("\"1970-01-01\"")).is_err());
+    assert!(serde_json::to_string(&SerializableTimestamp::new(&timestamp, TimestampFormat::Custom("[unknown]"))).is_err());
+}
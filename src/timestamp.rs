@@ -0,0 +1,298 @@
+use std::marker::PhantomData;
+
+use serde::de::{DeserializeSeed, Error, Unexpected, Visitor};
+use serde::{Deserializer, Serialize, Serializer};
+
+///
+/// The representation of a timestamp in the serde data model, used by [`SerializableTimestamp`]
+/// and [`DeserializeSeedTimestamp`].
+///
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TimestampFormat<'a> {
+    ///
+    /// An `i64` counting the (whole) seconds since the unix epoch.
+    ///
+    EpochSeconds,
+    ///
+    /// An `i64` counting the (whole) milliseconds since the unix epoch.
+    ///
+    EpochMillis,
+    ///
+    /// A string as specified by RFC 3339, e.g. `1970-01-01T00:00:00Z`.
+    ///
+    Rfc3339,
+    ///
+    /// A string in the given format, whose syntax is determined by the [`Timestamp`]
+    /// implementation, i.e. it is a `strftime`-like string for `chrono` and a format
+    /// description (version 2) like `[year]-[month]-[day]` for `time`.
+    ///
+    Custom(&'a str)
+}
+
+///
+/// A type of timestamps that can be serialized using [`SerializableTimestamp`] and
+/// deserialized using [`DeserializeSeedTimestamp`].
+///
+/// If the features `chrono` resp. `time` are enabled, this is implemented for
+/// `chrono::DateTime<Utc>` resp. `time::OffsetDateTime`. All functions return `None`
+/// if the timestamp cannot be represented in the requested way.
+///
+pub trait Timestamp: Sized {
+
+    fn from_epoch_seconds(seconds: i64) -> Option<Self>;
+
+    fn epoch_seconds(&self) -> Option<i64>;
+
+    fn from_epoch_millis(millis: i64) -> Option<Self>;
+
+    fn epoch_millis(&self) -> Option<i64>;
+
+    fn parse_rfc3339(value: &str) -> Option<Self>;
+
+    fn to_rfc3339(&self) -> Option<String>;
+
+    fn parse_custom(value: &str, format: &str) -> Option<Self>;
+
+    fn format_custom(&self, format: &str) -> Option<String>;
+}
+
+///
+/// Wraps a reference to a [`Timestamp`], and implements [`Serialize`] by mapping it to
+/// either an `i64` or a string in the serde data model, as determined by the given
+/// [`TimestampFormat`]. Since the format is given at runtime, it can e.g. be read from
+/// a file header.
+///
+/// Use [`DeserializeSeedTimestamp`] to deserialize it.
+///
+/// # Example
+/// ```
+/// # #[cfg(feature = "chrono")] {
+/// # use feanor_serde::timestamp::*;
+/// # use serde::de::DeserializeSeed;
+/// let timestamp = chrono::DateTime::from_timestamp(86400, 0).unwrap();
+/// assert_eq!("86400", serde_json::to_string(&SerializableTimestamp::new(&timestamp, TimestampFormat::EpochSeconds)).unwrap());
+/// assert_eq!("\"1970-01-02T00:00:00+00:00\"", serde_json::to_string(&SerializableTimestamp::new(&timestamp, TimestampFormat::Rfc3339)).unwrap());
+/// assert_eq!("\"02.01.1970\"", serde_json::to_string(&SerializableTimestamp::new(&timestamp, TimestampFormat::Custom("%d.%m.%Y"))).unwrap());
+///
+/// let seed = DeserializeSeedTimestamp::new(TimestampFormat::EpochMillis);
+/// assert_eq!(timestamp, seed.deserialize(&mut serde_json::Deserializer::from_str("86400000")).unwrap());
+/// # }
+/// ```
+///
+pub struct SerializableTimestamp<'a, T>
+    where T: Timestamp
+{
+    data: &'a T,
+    format: TimestampFormat<'a>
+}
+
+impl<'a, T> SerializableTimestamp<'a, T>
+    where T: Timestamp
+{
+    pub const fn new(data: &'a T, format: TimestampFormat<'a>) -> Self {
+        Self { data, format }
+    }
+}
+
+impl<'a, T> Clone for SerializableTimestamp<'a, T>
+    where T: Timestamp
+{
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<'a, T> Copy for SerializableTimestamp<'a, T>
+    where T: Timestamp
+{}
+
+impl<'a, T> Serialize for SerializableTimestamp<'a, T>
+    where T: Timestamp
+{
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+        where S: Serializer
+    {
+        let unrepresentable = || <S::Error as serde::ser::Error>::custom(format_args!("timestamp cannot be represented in format {:?}", self.format));
+        match self.format {
+            TimestampFormat::EpochSeconds => serializer.serialize_i64(self.data.epoch_seconds().ok_or_else(unrepresentable)?),
+            TimestampFormat::EpochMillis => serializer.serialize_i64(self.data.epoch_millis().ok_or_else(unrepresentable)?),
+            TimestampFormat::Rfc3339 => serializer.serialize_str(&self.data.to_rfc3339().ok_or_else(unrepresentable)?),
+            TimestampFormat::Custom(format) => serializer.serialize_str(&self.data.format_custom(format).ok_or_else(unrepresentable)?)
+        }
+    }
+}
+
+///
+/// A [`DeserializeSeed`] that deserializes a [`Timestamp`] that was serialized using
+/// [`SerializableTimestamp`] with the given [`TimestampFormat`].
+///
+pub struct DeserializeSeedTimestamp<'a, T>
+    where T: Timestamp
+{
+    format: TimestampFormat<'a>,
+    timestamp: PhantomData<fn() -> T>
+}
+
+impl<'a, T> DeserializeSeedTimestamp<'a, T>
+    where T: Timestamp
+{
+    pub const fn new(format: TimestampFormat<'a>) -> Self {
+        Self { format, timestamp: PhantomData }
+    }
+}
+
+impl<'a, T> Clone for DeserializeSeedTimestamp<'a, T>
+    where T: Timestamp
+{
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<'a, T> Copy for DeserializeSeedTimestamp<'a, T>
+    where T: Timestamp
+{}
+
+impl<'a, T> std::fmt::Debug for DeserializeSeedTimestamp<'a, T>
+    where T: Timestamp
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("DeserializeSeedTimestamp").field("format", &self.format).finish()
+    }
+}
+
+impl<'a, T> DeserializeSeedTimestamp<'a, T>
+    where T: Timestamp
+{
+    fn decode_epoch<E>(self, value: i64, unexpected: Unexpected) -> Result<T, E>
+        where E: Error
+    {
+        let result = match self.format {
+            TimestampFormat::EpochSeconds => T::from_epoch_seconds(value),
+            TimestampFormat::EpochMillis => T::from_epoch_millis(value),
+            TimestampFormat::Rfc3339 | TimestampFormat::Custom(_) => return Err(E::invalid_type(unexpected, &self))
+        };
+        return result.ok_or_else(|| crate::errors::invalid_value(unexpected, "a timestamp in the supported range", format_args!("a timestamp in format {:?} in the supported range", self.format)));
+    }
+}
+
+impl<'a, 'de, T> Visitor<'de> for DeserializeSeedTimestamp<'a, T>
+    where T: Timestamp
+{
+    type Value = T;
+
+    fn expecting(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self.format {
+            TimestampFormat::EpochSeconds => write!(f, "an integer counting seconds since the unix epoch"),
+            TimestampFormat::EpochMillis => write!(f, "an integer counting milliseconds since the unix epoch"),
+            TimestampFormat::Rfc3339 => write!(f, "an RFC 3339 timestamp"),
+            TimestampFormat::Custom(format) => write!(f, "a timestamp in format {:?}", format)
+        }
+    }
+
+    fn visit_i64<E>(self, value: i64) -> Result<Self::Value, E>
+        where E: Error
+    {
+        self.decode_epoch(value, Unexpected::Signed(value))
+    }
+
+    fn visit_u64<E>(self, value: u64) -> Result<Self::Value, E>
+        where E: Error
+    {
+        let signed = i64::try_from(value).map_err(|_| E::invalid_value(Unexpected::Unsigned(value), &self))?;
+        return self.decode_epoch(signed, Unexpected::Unsigned(value));
+    }
+
+    fn visit_str<E>(self, value: &str) -> Result<Self::Value, E>
+        where E: Error
+    {
+        let result = match self.format {
+            TimestampFormat::Rfc3339 => T::parse_rfc3339(value),
+            TimestampFormat::Custom(format) => T::parse_custom(value, format),
+            TimestampFormat::EpochSeconds | TimestampFormat::EpochMillis => return Err(E::invalid_type(Unexpected::Str(value), &self))
+        };
+        return result.ok_or_else(|| E::invalid_value(Unexpected::Str(value), &self));
+    }
+}
+
+impl<'a, 'de, T> DeserializeSeed<'de> for DeserializeSeedTimestamp<'a, T>
+    where T: Timestamp
+{
+    type Value = T;
+
+    fn deserialize<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+        where D: Deserializer<'de>
+    {
+        match self.format {
+            TimestampFormat::EpochSeconds | TimestampFormat::EpochMillis => deserializer.deserialize_i64(self),
+            TimestampFormat::Rfc3339 | TimestampFormat::Custom(_) => deserializer.deserialize_str(self)
+        }
+    }
+}
+
+///
+/// A timestamp counting seconds since the epoch, to test the wrappers without
+/// enabling `chrono` or `time`.
+///
+#[cfg(test)]
+#[derive(Debug, PartialEq)]
+struct Seconds(i64);
+
+#[cfg(test)]
+impl Timestamp for Seconds {
+
+    fn from_epoch_seconds(seconds: i64) -> Option<Self> {
+        Some(Seconds(seconds))
+    }
+
+    fn epoch_seconds(&self) -> Option<i64> {
+        Some(self.0)
+    }
+
+    fn from_epoch_millis(millis: i64) -> Option<Self> {
+        if millis % 1000 == 0 { Some(Seconds(millis / 1000)) } else { None }
+    }
+
+    fn epoch_millis(&self) -> Option<i64> {
+        self.0.checked_mul(1000)
+    }
+
+    fn parse_rfc3339(_value: &str) -> Option<Self> {
+        None
+    }
+
+    fn to_rfc3339(&self) -> Option<String> {
+        None
+    }
+
+    fn parse_custom(value: &str, format: &str) -> Option<Self> {
+        value.strip_suffix(format)?.parse().ok().map(Seconds)
+    }
+
+    fn format_custom(&self, format: &str) -> Option<String> {
+        Some(format!("{}{}", self.0, format))
+    }
+}
+
+#[test]
+fn test_serde_json() {
+    let formats = [TimestampFormat::EpochSeconds, TimestampFormat::EpochMillis, TimestampFormat::Custom("s")];
+    let expected = ["5", "5000", "\"5s\""];
+    for (format, expected) in formats.into_iter().zip(expected) {
+        assert_eq!(expected, serde_json::to_string(&SerializableTimestamp::new(&Seconds(5), format)).unwrap());
+        assert_eq!(Seconds(5), DeserializeSeedTimestamp::new(format).deserialize(&mut serde_json::Deserializer::from_str(expected)).unwrap());
+    }
+    assert!(serde_json::to_string(&SerializableTimestamp::new(&Seconds(5), TimestampFormat::Rfc3339)).is_err());
+    assert!(serde_json::to_string(&SerializableTimestamp::new(&Seconds(i64::MAX), TimestampFormat::EpochMillis)).is_err());
+    assert!(DeserializeSeedTimestamp::<Seconds>::new(TimestampFormat::EpochMillis).deserialize(&mut serde_json::Deserializer::from_str("5001")).is_err());
+    assert!(DeserializeSeedTimestamp::<Seconds>::new(TimestampFormat::EpochSeconds).deserialize(&mut serde_json::Deserializer::from_str("\"5s\"")).is_err());
+    assert!(DeserializeSeedTimestamp::<Seconds>::new(TimestampFormat::Custom("s")).deserialize(&mut serde_json::Deserializer::from_str("5")).is_err());
+}
+
+#[test]
+fn test_serde_postcard() {
+    for format in [TimestampFormat::EpochSeconds, TimestampFormat::EpochMillis, TimestampFormat::Custom("s")] {
+        let serialized = postcard::to_allocvec(&SerializableTimestamp::new(&Seconds(-3), format)).unwrap();
+        assert_eq!(Seconds(-3), DeserializeSeedTimestamp::new(format).deserialize(&mut postcard::Deserializer::from_bytes(&serialized)).unwrap());
+    }
+}
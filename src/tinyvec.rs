@@ -0,0 +1,63 @@
+use serde::de::DeserializeSeed;
+
+use ::tinyvec::{Array, TinyVec};
+
+use crate::seq::{SeqSeedBuilder, DeserializeSeedSeq};
+
+///
+/// The collector used by [`SeqSeedBuilder::collect_tinyvec()`].
+///
+pub type PushToTinyVec<A> = fn(TinyVec<A>, <A as Array>::Item) -> TinyVec<A>;
+
+impl<'de, V, S> SeqSeedBuilder<V>
+    where V: Iterator<Item = S>,
+        S: DeserializeSeed<'de>,
+        S::Value: Default
+{
+    ///
+    /// Builds a [`DeserializeSeedSeq`] that collects the elements into a [`TinyVec`].
+    /// Sequences that fit into the inline storage of the [`TinyVec`] are deserialized
+    /// without allocating.
+    ///
+    /// The initial capacity is chosen as for [`SeqSeedBuilder::collect_vec()`], thus if
+    /// it exceeds the inline capacity, the storage is allocated upfront. The storage is also
+    /// reserved as in [`SeqSeedBuilder::collect_vec()`] once the length of the sequence is known.
+    ///
+    /// # Example
+    /// ```
+    /// # use feanor_serde::seq::*;
+    /// # use feanor_serde::default_seed::seed_of;
+    /// # use serde::de::DeserializeSeed;
+    /// let seed = SeqSeedBuilder::new(seed_of::<i64>).collect_tinyvec::<[i64; 4]>();
+    /// let result = seed.deserialize(&mut serde_json::Deserializer::from_str("[1, 3, 5]")).unwrap();
+    /// assert_eq!(&[1, 3, 5], &result[..]);
+    /// assert!(result.is_inline());
+    /// ```
+    ///
+    pub fn collect_tinyvec<A>(self) -> DeserializeSeedSeq<V, S, TinyVec<A>, PushToTinyVec<A>>
+        where A: Array<Item = S::Value>
+    {
+        let capacity = self.initial_capacity();
+        return self.fold::<_, PushToTinyVec<_>>(TinyVec::with_capacity(capacity), |mut current, next| { current.push(next); current }).with_reserve(TinyVec::reserve);
+    }
+}
+
+#[cfg(test)]
+use crate::default_seed::seed_of;
+
+#[test]
+fn test_collect_tinyvec() {
+    let deserialize = |seed: DeserializeSeedSeq<_, _, TinyVec<[i64; 2]>, _>, json: &str| seed.deserialize(&mut serde_json::Deserializer::from_str(json));
+
+    let result = deserialize(SeqSeedBuilder::new(seed_of::<i64>).collect_tinyvec(), "[1, 2]").unwrap();
+    assert_eq!(&[1, 2], &result[..]);
+    assert!(result.is_inline());
+
+    let result = deserialize(SeqSeedBuilder::new(seed_of::<i64>).collect_tinyvec(), "[1, 2, 3]").unwrap();
+    assert_eq!(&[1, 2, 3], &result[..]);
+    assert!(result.is_heap());
+
+    let result = deserialize(SeqSeedBuilder::new(seed_of::<i64>).exact_len(3).collect_tinyvec(), "[1, 2, 3]").unwrap();
+    assert_eq!(&[1, 2, 3], &result[..]);
+    assert!(deserialize(SeqSeedBuilder::new(seed_of::<i64>).max_len(2).collect_tinyvec(), "[1, 2, 3]").is_err());
+}
@@ -0,0 +1,458 @@
+use std::cell::RefCell;
+use std::fmt::{Debug, Display};
+
+use serde::ser::*;
+
+///
+/// Serializes the given value using a [`TraceSerializer`], and returns the sequence of
+/// calls to the serde data model, see [`TraceSerializer`].
+///
+/// # Example
+/// ```
+/// # use feanor_serde::trace::*;
+/// # use feanor_serde::seq::*;
+/// let trace = trace_serialize(&SerializableSeq::new_with_len([1i64, 2].into_iter(), 2)).unwrap();
+/// assert_eq!(vec![
+///     "serialize_seq(Some(2))",
+///     "serialize_element",
+///     "serialize_i64(1)",
+///     "serialize_element",
+///     "serialize_i64(2)",
+///     "end"
+/// ], trace);
+/// ```
+///
+pub fn trace_serialize<T>(value: &T) -> Result<Vec<String>, TraceError>
+    where T: ?Sized + Serialize
+{
+    let calls = RefCell::new(Vec::new());
+    value.serialize(TraceSerializer::new(&calls))?;
+    return Ok(calls.into_inner());
+}
+
+///
+/// Formats a sequence of calls recorded by [`TraceSerializer`] for use in test failure messages
+/// and debug logs, with one call per line, indented by nesting depth. Calls with very long
+/// arguments, e.g. large byte strings, are truncated to `max_line_len` characters.
+///
+/// # Example
+/// ```
+/// # use feanor_serde::trace::*;
+/// let trace = trace_serialize(&(1i64, vec![0u8; 3])).unwrap();
+/// assert_eq!("serialize_tuple(2)
+///   serialize_element
+///   serialize_i64(1)
+///   serialize_element
+///   serialize_seq(Some(3))
+///     serialize_element
+///     serialize_u8(0)
+///     serialize_element
+///     serialize_u8(0)
+///     serialize_element
+///     serialize_u8(0)
+///   end
+/// end
+/// ", format_trace(&trace, 100));
+/// ```
+///
+pub fn format_trace<S>(calls: &[S], max_line_len: usize) -> String
+    where S: AsRef<str>
+{
+    const OPENING_CALLS: [&str; 7] = ["serialize_seq(", "serialize_tuple(", "serialize_tuple_struct(", "serialize_tuple_variant(", "serialize_map(", "serialize_struct(", "serialize_struct_variant("];
+    let mut result = String::new();
+    let mut depth: usize = 0;
+    for call in calls {
+        let call = call.as_ref();
+        if call == "end" {
+            depth = depth.saturating_sub(1);
+        }
+        result.extend(std::iter::repeat_n("  ", depth));
+        match call.char_indices().nth(max_line_len) {
+            Some((end, _)) => {
+                result.push_str(&call[..end]);
+                result.push_str("...");
+            },
+            None => result.push_str(call)
+        }
+        result.push('\n');
+        if OPENING_CALLS.iter().any(|opening| call.starts_with(opening)) {
+            depth += 1;
+        }
+    }
+    return result;
+}
+
+///
+/// The error type of [`TraceSerializer`], which only occurs if the serialized value
+/// itself produces an error via [`serde::ser::Error::custom()`].
+///
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TraceError {
+    message: String
+}
+
+impl Display for TraceError {
+
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.message)
+    }
+}
+
+impl std::error::Error for TraceError {}
+
+impl Error for TraceError {
+
+    fn custom<T>(msg: T) -> Self
+        where T: Display
+    {
+        Self { message: msg.to_string() }
+    }
+}
+
+///
+/// A [`Serializer`] that does not produce any output, but records the sequence of calls
+/// to the serde data model, e.g. `serialize_struct("Foo", 2)` or `serialize_field("a")`.
+/// This allows tests to check the shape of the data produced by a [`Serialize`] implementation,
+/// independent of a concrete format.
+///
+/// Each call is recorded as a string containing the name of the function and its
+/// arguments, formatted using [`Debug`]. Values passed to functions like
+/// [`SerializeStruct::serialize_field()`] are not included in the string, but recorded
+/// by the following calls.
+///
+#[derive(Clone, Copy)]
+pub struct TraceSerializer<'a> {
+    calls: &'a RefCell<Vec<String>>,
+    is_human_readable: bool
+}
+
+impl<'a> TraceSerializer<'a> {
+
+    pub fn new(calls: &'a RefCell<Vec<String>>) -> Self {
+        Self { calls: calls, is_human_readable: true }
+    }
+
+    pub fn with_human_readable(self, is_human_readable: bool) -> Self {
+        Self { is_human_readable, ..self }
+    }
+
+    fn record(&self, call: String) {
+        self.calls.borrow_mut().push(call);
+    }
+
+    fn record_value<T>(&self, name: &str, value: T) -> Result<(), TraceError>
+        where T: Debug
+    {
+        self.record(format!("{}({:?})", name, value));
+        return Ok(());
+    }
+}
+
+impl<'a> Serializer for TraceSerializer<'a> {
+    type Ok = ();
+    type Error = TraceError;
+    type SerializeSeq = Self;
+    type SerializeTuple = Self;
+    type SerializeTupleStruct = Self;
+    type SerializeTupleVariant = Self;
+    type SerializeMap = Self;
+    type SerializeStruct = Self;
+    type SerializeStructVariant = Self;
+
+    fn serialize_bool(self, v: bool) -> Result<(), TraceError> { self.record_value("serialize_bool", v) }
+    fn serialize_i8(self, v: i8) -> Result<(), TraceError> { self.record_value("serialize_i8", v) }
+    fn serialize_i16(self, v: i16) -> Result<(), TraceError> { self.record_value("serialize_i16", v) }
+    fn serialize_i32(self, v: i32) -> Result<(), TraceError> { self.record_value("serialize_i32", v) }
+    fn serialize_i64(self, v: i64) -> Result<(), TraceError> { self.record_value("serialize_i64", v) }
+    fn serialize_i128(self, v: i128) -> Result<(), TraceError> { self.record_value("serialize_i128", v) }
+    fn serialize_u8(self, v: u8) -> Result<(), TraceError> { self.record_value("serialize_u8", v) }
+    fn serialize_u16(self, v: u16) -> Result<(), TraceError> { self.record_value("serialize_u16", v) }
+    fn serialize_u32(self, v: u32) -> Result<(), TraceError> { self.record_value("serialize_u32", v) }
+    fn serialize_u64(self, v: u64) -> Result<(), TraceError> { self.record_value("serialize_u64", v) }
+    fn serialize_u128(self, v: u128) -> Result<(), TraceError> { self.record_value("serialize_u128", v) }
+    fn serialize_f32(self, v: f32) -> Result<(), TraceError> { self.record_value("serialize_f32", v) }
+    fn serialize_f64(self, v: f64) -> Result<(), TraceError> { self.record_value("serialize_f64", v) }
+    fn serialize_char(self, v: char) -> Result<(), TraceError> { self.record_value("serialize_char", v) }
+    fn serialize_str(self, v: &str) -> Result<(), TraceError> { self.record_value("serialize_str", v) }
+    fn serialize_bytes(self, v: &[u8]) -> Result<(), TraceError> { self.record_value("serialize_bytes", v) }
+
+    fn serialize_none(self) -> Result<(), TraceError> {
+        self.record("serialize_none".to_owned());
+        return Ok(());
+    }
+
+    fn serialize_some<T>(self, value: &T) -> Result<(), TraceError>
+        where T: ?Sized + Serialize
+    {
+        self.record("serialize_some".to_owned());
+        return value.serialize(self);
+    }
+
+    fn serialize_unit(self) -> Result<(), TraceError> {
+        self.record("serialize_unit".to_owned());
+        return Ok(());
+    }
+
+    fn serialize_unit_struct(self, name: &'static str) -> Result<(), TraceError> {
+        self.record_value("serialize_unit_struct", name)
+    }
+
+    fn serialize_unit_variant(self, name: &'static str, variant_index: u32, variant: &'static str) -> Result<(), TraceError> {
+        self.record(format!("serialize_unit_variant({:?}, {}, {:?})", name, variant_index, variant));
+        return Ok(());
+    }
+
+    fn serialize_newtype_struct<T>(self, name: &'static str, value: &T) -> Result<(), TraceError>
+        where T: ?Sized + Serialize
+    {
+        self.record_value("serialize_newtype_struct", name)?;
+        return value.serialize(self);
+    }
+
+    fn serialize_newtype_variant<T>(self, name: &'static str, variant_index: u32, variant: &'static str, value: &T) -> Result<(), TraceError>
+        where T: ?Sized + Serialize
+    {
+        self.record(format!("serialize_newtype_variant({:?}, {}, {:?})", name, variant_index, variant));
+        return value.serialize(self);
+    }
+
+    fn serialize_seq(self, len: Option<usize>) -> Result<Self, TraceError> {
+        self.record_value("serialize_seq", len)?;
+        return Ok(self);
+    }
+
+    fn serialize_tuple(self, len: usize) -> Result<Self, TraceError> {
+        self.record_value("serialize_tuple", len)?;
+        return Ok(self);
+    }
+
+    fn serialize_tuple_struct(self, name: &'static str, len: usize) -> Result<Self, TraceError> {
+        self.record(format!("serialize_tuple_struct({:?}, {})", name, len));
+        return Ok(self);
+    }
+
+    fn serialize_tuple_variant(self, name: &'static str, variant_index: u32, variant: &'static str, len: usize) -> Result<Self, TraceError> {
+        self.record(format!("serialize_tuple_variant({:?}, {}, {:?}, {})", name, variant_index, variant, len));
+        return Ok(self);
+    }
+
+    fn serialize_map(self, len: Option<usize>) -> Result<Self, TraceError> {
+        self.record_value("serialize_map", len)?;
+        return Ok(self);
+    }
+
+    fn serialize_struct(self, name: &'static str, len: usize) -> Result<Self, TraceError> {
+        self.record(format!("serialize_struct({:?}, {})", name, len));
+        return Ok(self);
+    }
+
+    fn serialize_struct_variant(self, name: &'static str, variant_index: u32, variant: &'static str, len: usize) -> Result<Self, TraceError> {
+        self.record(format!("serialize_struct_variant({:?}, {}, {:?}, {})", name, variant_index, variant, len));
+        return Ok(self);
+    }
+
+    fn is_human_readable(&self) -> bool {
+        self.is_human_readable
+    }
+}
+
+impl<'a> SerializeSeq for TraceSerializer<'a> {
+    type Ok = ();
+    type Error = TraceError;
+
+    fn serialize_element<T>(&mut self, value: &T) -> Result<(), TraceError>
+        where T: ?Sized + Serialize
+    {
+        self.record("serialize_element".to_owned());
+        return value.serialize(*self);
+    }
+
+    fn end(self) -> Result<(), TraceError> {
+        self.record("end".to_owned());
+        return Ok(());
+    }
+}
+
+impl<'a> SerializeTuple for TraceSerializer<'a> {
+    type Ok = ();
+    type Error = TraceError;
+
+    fn serialize_element<T>(&mut self, value: &T) -> Result<(), TraceError>
+        where T: ?Sized + Serialize
+    {
+        SerializeSeq::serialize_element(self, value)
+    }
+
+    fn end(self) -> Result<(), TraceError> {
+        SerializeSeq::end(self)
+    }
+}
+
+impl<'a> SerializeTupleStruct for TraceSerializer<'a> {
+    type Ok = ();
+    type Error = TraceError;
+
+    fn serialize_field<T>(&mut self, value: &T) -> Result<(), TraceError>
+        where T: ?Sized + Serialize
+    {
+        self.record("serialize_field".to_owned());
+        return value.serialize(*self);
+    }
+
+    fn end(self) -> Result<(), TraceError> {
+        SerializeSeq::end(self)
+    }
+}
+
+impl<'a> SerializeTupleVariant for TraceSerializer<'a> {
+    type Ok = ();
+    type Error = TraceError;
+
+    fn serialize_field<T>(&mut self, value: &T) -> Result<(), TraceError>
+        where T: ?Sized + Serialize
+    {
+        SerializeTupleStruct::serialize_field(self, value)
+    }
+
+    fn end(self) -> Result<(), TraceError> {
+        SerializeSeq::end(self)
+    }
+}
+
+impl<'a> SerializeMap for TraceSerializer<'a> {
+    type Ok = ();
+    type Error = TraceError;
+
+    fn serialize_key<T>(&mut self, key: &T) -> Result<(), TraceError>
+        where T: ?Sized + Serialize
+    {
+        self.record("serialize_key".to_owned());
+        return key.serialize(*self);
+    }
+
+    fn serialize_value<T>(&mut self, value: &T) -> Result<(), TraceError>
+        where T: ?Sized + Serialize
+    {
+        self.record("serialize_value".to_owned());
+        return value.serialize(*self);
+    }
+
+    fn end(self) -> Result<(), TraceError> {
+        SerializeSeq::end(self)
+    }
+}
+
+impl<'a> SerializeStruct for TraceSerializer<'a> {
+    type Ok = ();
+    type Error = TraceError;
+
+    fn serialize_field<T>(&mut self, key: &'static str, value: &T) -> Result<(), TraceError>
+        where T: ?Sized + Serialize
+    {
+        self.record_value("serialize_field", key)?;
+        return value.serialize(*self);
+    }
+
+    fn skip_field(&mut self, key: &'static str) -> Result<(), TraceError> {
+        self.record_value("skip_field", key)
+    }
+
+    fn end(self) -> Result<(), TraceError> {
+        SerializeSeq::end(self)
+    }
+}
+
+impl<'a> SerializeStructVariant for TraceSerializer<'a> {
+    type Ok = ();
+    type Error = TraceError;
+
+    fn serialize_field<T>(&mut self, key: &'static str, value: &T) -> Result<(), TraceError>
+        where T: ?Sized + Serialize
+    {
+        SerializeStruct::serialize_field(self, key, value)
+    }
+
+    fn skip_field(&mut self, key: &'static str) -> Result<(), TraceError> {
+        SerializeStruct::skip_field(self, key)
+    }
+
+    fn end(self) -> Result<(), TraceError> {
+        SerializeSeq::end(self)
+    }
+}
+
+#[test]
+fn test_trace_struct() {
+    #[derive(serde::Serialize)]
+    struct Foo {
+        a: i64,
+        b: Option<String>
+    }
+
+    #[derive(serde::Serialize)]
+    enum Bar {
+        A(Foo),
+        B { c: (u8, bool) }
+    }
+
+    assert_eq!(vec![
+        "serialize_newtype_variant(\"Bar\", 0, \"A\")",
+        "serialize_struct(\"Foo\", 2)",
+        "serialize_field(\"a\")",
+        "serialize_i64(1)",
+        "serialize_field(\"b\")",
+        "serialize_some",
+        "serialize_str(\"x\")",
+        "end"
+    ], trace_serialize(&Bar::A(Foo { a: 1, b: Some("x".to_owned()) })).unwrap());
+
+    assert_eq!(vec![
+        "serialize_struct_variant(\"Bar\", 1, \"B\", 1)",
+        "serialize_field(\"c\")",
+        "serialize_tuple(2)",
+        "serialize_element",
+        "serialize_u8(2)",
+        "serialize_element",
+        "serialize_bool(false)",
+        "end",
+        "end"
+    ], trace_serialize(&Bar::B { c: (2, false) }).unwrap());
+}
+
+#[test]
+fn test_trace_human_readable() {
+    struct HumanReadable;
+
+    impl Serialize for HumanReadable {
+        fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+            where S: Serializer
+        {
+            let is_human_readable = serializer.is_human_readable();
+            serializer.serialize_bool(is_human_readable)
+        }
+    }
+
+    let calls = RefCell::new(Vec::new());
+    HumanReadable.serialize(TraceSerializer::new(&calls).with_human_readable(false)).unwrap();
+    HumanReadable.serialize(TraceSerializer::new(&calls)).unwrap();
+    assert_eq!(vec!["serialize_bool(false)", "serialize_bool(true)"], calls.into_inner());
+}
+
+#[test]
+fn test_format_trace() {
+    let trace = trace_serialize(&(serde_bytes_like(&[1, 2, 3, 4]), ())).unwrap();
+    assert_eq!("serialize_tuple(2)\n  serialize_element\n  serialize_bytes([1...\n  serialize_element\n  serialize_unit\nend\n", format_trace(&trace, 18));
+}
+
+#[cfg(test)]
+fn serde_bytes_like(data: &[u8]) -> impl Serialize + '_ {
+    struct Bytes<'a>(&'a [u8]);
+    impl<'a> Serialize for Bytes<'a> {
+        fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+            where S: Serializer
+        {
+            serializer.serialize_bytes(self.0)
+        }
+    }
+    return Bytes(data);
+}
@@ -0,0 +1,141 @@
+use std::fmt::Display;
+
+use serde::de::{DeserializeSeed, Error};
+use serde::ser::{Serialize, Serializer};
+use serde::Deserializer;
+
+use crate::raw_bytes::ByteBufSeed;
+use crate::into_seed::IntoSeed;
+
+///
+/// Wraps a serializable value, and implements [`Serialize`] by encoding it with postcard,
+/// applying the given function to the result, and mapping the returned bytes to a byte
+/// buffer in the serde data model.
+///
+/// This generalizes e.g. [`crate::encrypted::SerializableEncrypted`] and
+/// [`crate::compressed::SerializableCompressed`], and can be used to implement custom framing
+/// or encoding schemes. Use
+/// [`DeserializeSeedTransformed`] with the inverse function to deserialize it.
+///
+/// # Example
+/// ```
+/// # use feanor_serde::transformed::*;
+/// # use feanor_serde::default_seed::seed_of;
+/// let reverse = |mut data: Vec<u8>| { data.reverse(); Ok::<_, String>(data) };
+///
+/// let serialized = postcard::to_allocvec(&SerializableTransformed::new((1u8, 2u8, 3u8), reverse)).unwrap();
+/// assert_eq!(vec![3, 3, 2, 1], serialized);
+/// let seed = DeserializeSeedTransformed::new(seed_of::<(u8, u8, u8)>(), reverse);
+/// assert_eq!((1, 2, 3), feanor_serde::postcard::from_slice_seeded(seed, &serialized).unwrap());
+/// ```
+///
+pub struct SerializableTransformed<T, F, E>
+    where T: Serialize,
+        F: Fn(Vec<u8>) -> Result<Vec<u8>, E>,
+        E: Display
+{
+    data: T,
+    transform: F
+}
+
+impl<T, F, E> SerializableTransformed<T, F, E>
+    where T: Serialize,
+        F: Fn(Vec<u8>) -> Result<Vec<u8>, E>,
+        E: Display
+{
+    pub const fn new(data: T, transform: F) -> Self {
+        Self { data, transform }
+    }
+}
+
+impl<T, F, E> Serialize for SerializableTransformed<T, F, E>
+    where T: Serialize,
+        F: Fn(Vec<u8>) -> Result<Vec<u8>, E>,
+        E: Display
+{
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+        where S: Serializer
+    {
+        let encoded = ::postcard::to_allocvec(&self.data).map_err(serde::ser::Error::custom)?;
+        let transformed = (self.transform)(encoded).map_err(serde::ser::Error::custom)?;
+        return serializer.serialize_bytes(&transformed);
+    }
+}
+
+///
+/// A [`DeserializeSeed`] that deserializes a value serialized using [`SerializableTransformed`],
+/// by applying the given function to the byte buffer, and passing the result to the wrapped
+/// [`DeserializeSeed`]. Errors returned by the function are reported as custom errors.
+///
+/// Since the transformed data is not borrowed from the input, the wrapped seed must be able
+/// to deserialize from data of any lifetime.
+///
+pub struct DeserializeSeedTransformed<S, F, E>
+    where F: FnOnce(Vec<u8>) -> Result<Vec<u8>, E>,
+        E: Display
+{
+    seed: S,
+    inverse: F
+}
+
+impl<S, F, E> DeserializeSeedTransformed<S, F, E>
+    where F: FnOnce(Vec<u8>) -> Result<Vec<u8>, E>,
+        E: Display
+{
+    pub fn new<'de, I, M>(seed: I, inverse: F) -> Self
+        where I: IntoSeed<'de, M, Seed = S>
+    {
+        Self { seed: seed.into_seed(), inverse }
+    }
+}
+
+impl<'de, S, F, E, T> DeserializeSeed<'de> for DeserializeSeedTransformed<S, F, E>
+    where S: for<'a> DeserializeSeed<'a, Value = T>,
+        F: FnOnce(Vec<u8>) -> Result<Vec<u8>, E>,
+        E: Display
+{
+    type Value = T;
+
+    fn deserialize<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+        where D: Deserializer<'de>
+    {
+        let transformed = ByteBufSeed.deserialize(deserializer)?;
+        let encoded = (self.inverse)(transformed).map_err(D::Error::custom)?;
+        let (result, rest) = crate::postcard::take_from_slice_seeded(self.seed, &encoded).map_err(D::Error::custom)?;
+        if !rest.is_empty() {
+            return Err(D::Error::custom(format!("transformed data has {} trailing bytes", rest.len())));
+        }
+        return Ok(result);
+    }
+}
+
+#[cfg(test)]
+use std::marker::PhantomData;
+
+#[test]
+fn test_serde_postcard() {
+    let frame = |data: Vec<u8>| Ok::<_, String>([&[0xAB][..], &data].concat());
+    let unframe = |data: Vec<u8>| match data.split_first() {
+        Some((0xAB, rest)) => Ok(rest.to_owned()),
+        _ => Err("missing frame marker")
+    };
+    let data = vec![(1i64, "a".to_owned()), (-2, "b".to_owned())];
+    let serialized = postcard::to_allocvec(&SerializableTransformed::new(&data, frame)).unwrap();
+    assert_eq!(data, crate::postcard::from_slice_seeded(DeserializeSeedTransformed::new(PhantomData::<Vec<(i64, String)>>, unframe), &serialized).unwrap());
+
+    let serialized = postcard::to_allocvec(&SerializableTransformed::new(&data, Ok::<_, String>)).unwrap();
+    assert_eq!(postcard::Error::SerdeDeCustom, crate::postcard::from_slice_seeded(DeserializeSeedTransformed::new(PhantomData::<Vec<(i64, String)>>, unframe), &serialized).unwrap_err());
+}
+
+#[test]
+fn test_serde_json() {
+    let serialized = serde_json::to_string(&SerializableTransformed::new(5u8, |mut data: Vec<u8>| { data.push(0); Ok::<_, String>(data) })).unwrap();
+    assert_eq!("[5,0]", serialized);
+    let error = DeserializeSeedTransformed::new(PhantomData::<u8>, Ok::<_, String>).deserialize(&mut serde_json::Deserializer::from_str(&serialized)).unwrap_err();
+    assert!(error.to_string().contains("1 trailing bytes"));
+    let error = DeserializeSeedTransformed::new(PhantomData::<u8>, |_| Err("failed")).deserialize(&mut serde_json::Deserializer::from_str(&serialized)).unwrap_err();
+    assert!(error.to_string().contains("failed"));
+
+    let error = serde_json::to_string(&SerializableTransformed::new(5u8, |_| Err("failed"))).unwrap_err();
+    assert!(error.to_string().contains("failed"));
+}
@@ -0,0 +1,217 @@
+use std::fmt::Display;
+use std::marker::PhantomData;
+use std::str::FromStr;
+
+use serde::de::{DeserializeSeed, Error, Visitor};
+use serde::ser::{Serialize, Serializer};
+use serde::{Deserialize, Deserializer};
+use wasm_bindgen::JsValue;
+
+///
+/// Converts the given value into a [`JsValue`], using [`serde_wasm_bindgen::Serializer::json_compatible()`].
+///
+/// In particular, maps become plain JavaScript objects instead of ES2015 `Map`s, so the result
+/// has the same shape as the JSON produced by `serde_json`. Note however that this means that
+/// map keys must be strings (use e.g. [`crate::map::SerializableMapAsSeq`] otherwise), and that
+/// 64-bit integers outside the safe integer range of JavaScript cannot be represented (use
+/// [`SerializableIntAsString`] for those).
+///
+/// Like [`crate::postcard::to_allocvec()`], this takes the value by value, so that the
+/// serializable wrappers of this crate can be created inline.
+///
+pub fn to_js_value<T>(value: T) -> Result<JsValue, serde_wasm_bindgen::Error>
+    where T: Serialize
+{
+    value.serialize(&serde_wasm_bindgen::Serializer::json_compatible())
+}
+
+///
+/// Deserializes a value from the given [`JsValue`], using the given [`DeserializeSeed`].
+///
+/// This is the analogue of [`serde_wasm_bindgen::from_value()`] for [`DeserializeSeed`]s,
+/// and accepts both the output of [`to_js_value()`] and of [`serde_wasm_bindgen::to_value()`].
+///
+pub fn from_js_value_seeded<'de, S>(seed: S, value: JsValue) -> Result<S::Value, serde_wasm_bindgen::Error>
+    where S: DeserializeSeed<'de>
+{
+    seed.deserialize(serde_wasm_bindgen::Deserializer::from(value))
+}
+
+///
+/// Wraps an integer, and implements [`Serialize`] by mapping it to its decimal representation
+/// as string in human-readable formats, and to the integer itself otherwise.
+///
+/// JavaScript numbers can only represent integers up to `2^53` exactly, thus 64-bit (and
+/// 128-bit) integers cannot be represented in JSON or by [`to_js_value()`] without risking a
+/// loss of precision. Use [`DeserializeSeedIntAsString`] to deserialize it.
+///
+/// # Example
+/// ```
+/// # use feanor_serde::wasm::*;
+/// # use std::marker::PhantomData;
+/// # use serde::de::DeserializeSeed;
+/// let json = serde_json::to_string(&SerializableIntAsString::new(u64::MAX)).unwrap();
+/// assert_eq!("\"18446744073709551615\"", json);
+/// assert_eq!(u64::MAX, DeserializeSeedIntAsString::<u64>::new().deserialize(&mut serde_json::Deserializer::from_str(&json)).unwrap());
+/// ```
+///
+#[derive(Clone, Copy)]
+pub struct SerializableIntAsString<T>
+    where T: Display + Serialize
+{
+    value: T
+}
+
+impl<T> SerializableIntAsString<T>
+    where T: Display + Serialize
+{
+    pub const fn new(value: T) -> Self {
+        Self { value }
+    }
+}
+
+impl<T> Serialize for SerializableIntAsString<T>
+    where T: Display + Serialize
+{
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+        where S: Serializer
+    {
+        if serializer.is_human_readable() {
+            return serializer.collect_str(&self.value);
+        } else {
+            return self.value.serialize(serializer);
+        }
+    }
+}
+
+///
+/// A [`DeserializeSeed`] for integers serialized using [`SerializableIntAsString`]. In
+/// human-readable formats, this also accepts integers that are not encoded as string,
+/// which requires the format to be self-describing.
+///
+pub struct DeserializeSeedIntAsString<T> {
+    value: PhantomData<T>
+}
+
+impl<T> DeserializeSeedIntAsString<T> {
+
+    pub const fn new() -> Self {
+        Self { value: PhantomData }
+    }
+}
+
+impl<T> Default for DeserializeSeedIntAsString<T> {
+
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> Clone for DeserializeSeedIntAsString<T> {
+
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T> Copy for DeserializeSeedIntAsString<T> {}
+
+impl<'de, T> DeserializeSeed<'de> for DeserializeSeedIntAsString<T>
+    where T: FromStr + TryFrom<u64> + TryFrom<i64> + Deserialize<'de>,
+        <T as FromStr>::Err: Display
+{
+    type Value = T;
+
+    fn deserialize<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+        where D: Deserializer<'de>
+    {
+        if deserializer.is_human_readable() {
+            return deserializer.deserialize_any(self);
+        } else {
+            return T::deserialize(deserializer);
+        }
+    }
+}
+
+impl<'de, T> Visitor<'de> for DeserializeSeedIntAsString<T>
+    where T: FromStr + TryFrom<u64> + TryFrom<i64>,
+        <T as FromStr>::Err: Display
+{
+    type Value = T;
+
+    fn expecting(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "an integer, possibly encoded as decimal string")
+    }
+
+    fn visit_str<E>(self, v: &str) -> Result<Self::Value, E>
+        where E: Error
+    {
+        v.parse().map_err(|e| E::custom(format!("invalid integer {:?}: {}", v, e)))
+    }
+
+    fn visit_u64<E>(self, v: u64) -> Result<Self::Value, E>
+        where E: Error
+    {
+        T::try_from(v).map_err(|_| E::custom(format!("integer {} is out of range", v)))
+    }
+
+    fn visit_i64<E>(self, v: i64) -> Result<Self::Value, E>
+        where E: Error
+    {
+        T::try_from(v).map_err(|_| E::custom(format!("integer {} is out of range", v)))
+    }
+}
+
+#[test]
+fn test_int_as_string_json() {
+    let serialized = serde_json::to_string(&[SerializableIntAsString::new(i64::MIN), SerializableIntAsString::new(5)]).unwrap();
+    assert_eq!("[\"-9223372036854775808\",\"5\"]", serialized);
+    assert_eq!(i64::MIN, DeserializeSeedIntAsString::<i64>::new().deserialize(&mut serde_json::Deserializer::from_str("\"-9223372036854775808\"")).unwrap());
+    assert_eq!(5, DeserializeSeedIntAsString::<u128>::new().deserialize(&mut serde_json::Deserializer::from_str("5")).unwrap());
+    assert!(DeserializeSeedIntAsString::<u64>::new().deserialize(&mut serde_json::Deserializer::from_str("-1")).is_err());
+    assert!(DeserializeSeedIntAsString::<u64>::new().deserialize(&mut serde_json::Deserializer::from_str("\"1.5\"")).is_err());
+}
+
+#[test]
+fn test_int_as_string_postcard() {
+    let serialized = postcard::to_allocvec(&SerializableIntAsString::new(u64::MAX)).unwrap();
+    assert_eq!(postcard::to_allocvec(&u64::MAX).unwrap(), serialized);
+    let mut deserializer = postcard::Deserializer::from_bytes(&serialized);
+    assert_eq!(u64::MAX, DeserializeSeedIntAsString::<u64>::new().deserialize(&mut deserializer).unwrap());
+}
+
+#[cfg(all(test, target_arch = "wasm32"))]
+use wasm_bindgen_test::wasm_bindgen_test;
+
+#[cfg(all(test, target_arch = "wasm32"))]
+#[wasm_bindgen_test]
+fn test_seq_js_value() {
+    let value = to_js_value(crate::seq::SerializableSeq::new_with_len([1, 2, 3].into_iter(), 3)).unwrap();
+    assert!(value.is_object());
+    let seed = crate::seq::DeserializeSeedSeq::new(std::iter::repeat(PhantomData::<i64>), 0, |current, next| current + next);
+    assert_eq!(6, from_js_value_seeded(seed, value).unwrap());
+}
+
+#[cfg(all(test, target_arch = "wasm32"))]
+#[wasm_bindgen_test]
+fn test_map_js_value() {
+    let data = [("a".to_owned(), 1), ("b".to_owned(), 2)];
+    let seed = || crate::map::DeserializeSeedMap::new(std::iter::repeat((PhantomData::<String>, PhantomData::<i64>)), Vec::new(), |mut current, k, v| { current.push((k, v)); current });
+
+    let value = to_js_value(crate::map::SerializableMap::new(data.iter().map(|(k, v)| (k, v)))).unwrap();
+    assert_eq!(Some(1.), js_sys::Reflect::get(&value, &JsValue::from_str("a")).unwrap().as_f64());
+    assert_eq!(data.to_vec(), from_js_value_seeded(seed(), value).unwrap());
+
+    let value = serde_wasm_bindgen::to_value(&std::collections::BTreeMap::from(data.clone())).unwrap();
+    assert_eq!(data.to_vec(), from_js_value_seeded(seed(), value).unwrap());
+}
+
+#[cfg(all(test, target_arch = "wasm32"))]
+#[wasm_bindgen_test]
+fn test_large_int_js_value() {
+    assert!(to_js_value(u64::MAX).is_err());
+    let value = to_js_value(SerializableIntAsString::new(u64::MAX)).unwrap();
+    assert_eq!(Some("18446744073709551615".to_owned()), value.as_string());
+    assert_eq!(u64::MAX, from_js_value_seeded(DeserializeSeedIntAsString::<u64>::new(), value).unwrap());
+    assert_eq!(5, from_js_value_seeded(DeserializeSeedIntAsString::<u64>::new(), JsValue::from_f64(5.)).unwrap());
+}